@@ -12,6 +12,7 @@ use log::{trace, warn};
 
 use common::database::Database;
 use common::display::Display;
+use common::display::image::draw_background;
 use common::platform::{DefaultPlatform, Platform};
 use common::stylesheet::Stylesheet;
 use type_map::TypeMap;
@@ -34,7 +35,9 @@ impl ActivityTracker<DefaultPlatform> {
         let mut res = TypeMap::new();
         res.insert(Database::new()?);
         res.insert(Stylesheet::load()?);
-        res.insert(Locale::new(&LocaleSettings::load()?.lang));
+        let locale = Locale::new(&LocaleSettings::load()?);
+        geom::set_right_to_left(locale.is_right_to_left(), display.size().width);
+        res.insert(locale);
         res.insert(Into::<geom::Size>::into(display.size()));
         let res = Resources::new(res);
 
@@ -49,8 +52,14 @@ impl ActivityTracker<DefaultPlatform> {
     }
 
     pub async fn run_event_loop(&mut self) -> Result<()> {
-        self.display
-            .clear(self.res.get::<Stylesheet>().background_color)?;
+        {
+            let styles = self.res.get::<Stylesheet>();
+            draw_background(
+                &mut self.display,
+                &styles,
+                styles.background_image.as_deref(),
+            )?;
+        }
         self.display.save()?;
 
         #[cfg(unix)]
@@ -74,6 +83,7 @@ impl ActivityTracker<DefaultPlatform> {
                     self.handle_command(Command::Exit).await?;
                 }
                 event = self.platform.poll() => {
+                    let event = event.ui();
                     let mut bubble = VecDeque::new();
                     self.view.handle_key_event(event, tx.clone(), &mut bubble).await?;
                 }
@@ -83,6 +93,7 @@ impl ActivityTracker<DefaultPlatform> {
             #[cfg(not(unix))]
             tokio::select! {
                 event = self.platform.poll() => {
+                    let event = event.ui();
                     let mut bubble = VecDeque::new();
                     self.view.handle_key_event(event, tx.clone(), &mut bubble).await?;
                 }
@@ -4,13 +4,12 @@ mod view;
 use anyhow::Result;
 
 use common::platform::{DefaultPlatform, Platform};
-use simple_logger::SimpleLogger;
 
 use crate::activity_tracker::ActivityTracker;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    SimpleLogger::new().env().init().unwrap();
+    common::logging::init("activity-tracker")?;
 
     let platform = DefaultPlatform::new()?;
     let mut app = ActivityTracker::new(platform)?;
@@ -3,7 +3,7 @@ use std::collections::{HashMap, VecDeque};
 use anyhow::Result;
 use async_trait::async_trait;
 use common::command::Command;
-use common::constants::{RECENT_GAMES_LIMIT, SELECTION_MARGIN};
+use common::constants::RECENT_GAMES_LIMIT;
 use common::database::{Database, Game};
 use common::display::Display;
 use common::geom::{Alignment, Point, Rect};
@@ -36,7 +36,7 @@ impl ActivityTracker {
             Rect::new(x + 12, y, w - 24, h - 8 - ButtonIcon::diameter(&styles)),
             Vec::new(),
             Vec::new(),
-            res.get::<Stylesheet>().ui_font.size + SELECTION_MARGIN,
+            res.get::<Stylesheet>().row_height(),
         );
 
         let button_hints = Row::new(
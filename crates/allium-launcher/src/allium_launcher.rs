@@ -1,31 +1,46 @@
 use std::collections::VecDeque;
-use std::path::Path;
-use std::process;
-use std::time::Instant;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use std::{fs, process};
 
 use anyhow::Result;
+use common::combo::{ComboAction, ComboDetector, Detection};
 use common::command::Command;
-use common::constants::{ALLIUM_GAMES_DIR, ALLIUM_SD_ROOT};
+use common::constants::{
+    ALLIUM_DATS_DIR, ALLIUM_LAUNCH_REQUEST, ALLIUM_ROM_VERIFICATION_REPORT, ANIMATION_FRAME_TIME,
+};
 use common::display::color::Color;
+use common::game_info::GameInfo;
 use common::geom;
+use common::led;
 use common::locale::{Locale, LocaleSettings};
+use common::notifications::NotificationHistory;
+use common::onion;
+use common::performance::PerformanceSettings;
 use common::resources::Resources;
-use common::view::View;
-use embedded_graphics::image::ImageRaw;
+use common::rumble::{self, RumbleSettings};
+use common::sound;
+use common::splash;
+use common::storage;
+use common::ui_keymap::UiKeymap;
+use common::view::{Toast, ToastQueue, View, animation};
 use embedded_graphics::prelude::*;
 use enum_map::EnumMap;
-use log::{error, info, trace, warn};
+use log::{debug, info, trace, warn};
 
-use common::database::Database;
+use common::database::{Database, RomStatus};
 use common::display::Display;
 use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
 use common::stylesheet::Stylesheet;
 use type_map::TypeMap;
 
+use crate::boot_timer::BootTimer;
 use crate::consoles::ConsoleMapper;
+use crate::dat::{self, Dat};
+use crate::entry::archive;
 use crate::entry::directory::Directory;
 use crate::entry::game::Game;
-use crate::view::{App, Toast};
+use crate::view::App;
 
 #[derive(Debug)]
 pub struct AlliumLauncher<P: Platform> {
@@ -33,52 +48,83 @@ pub struct AlliumLauncher<P: Platform> {
     display: P::Display,
     res: Resources,
     view: App<P::Battery>,
-    toast: Option<Toast>,
+    toasts: ToastQueue,
+    combos: ComboDetector,
+    rumble: RumbleSettings,
+    /// Startup timing, until the first frame is shown.
+    boot_timer: Option<BootTimer>,
 }
 
 impl AlliumLauncher<DefaultPlatform> {
-    pub fn new(mut platform: DefaultPlatform) -> Result<Self> {
-        let display = platform.display()?;
+    pub async fn new(mut platform: DefaultPlatform) -> Result<Self> {
+        let mut boot_timer = BootTimer::new();
+
+        // Configs are loaded in the background while the splash is drawn and the database,
+        // which can't be moved between threads, is opened
+        let console_mapper = tokio::task::spawn_blocking(|| -> Result<ConsoleMapper> {
+            let mut console_mapper = ConsoleMapper::new();
+            console_mapper.load_config()?;
+            Ok(console_mapper)
+        });
+        let styles = tokio::task::spawn_blocking(Stylesheet::load);
+        let locale = tokio::task::spawn_blocking(|| -> Result<Locale> {
+            Ok(Locale::new(&LocaleSettings::load()?))
+        });
+
+        let mut display = platform.display()?;
+        match splash::draw(&mut display) {
+            Ok(true) => display.flush()?,
+            Ok(false) => {}
+            Err(e) => warn!("failed to draw boot splash: {}", e),
+        }
         let battery = platform.battery()?;
+        boot_timer.phase("splash");
+
+        let database = Database::new()?;
+        boot_timer.phase("database");
 
-        let mut console_mapper = ConsoleMapper::new();
-        console_mapper.load_config()?;
+        let (console_mapper, styles, locale) = tokio::try_join!(console_mapper, styles, locale)?;
+        boot_timer.phase("configs");
 
         let mut res = TypeMap::new();
-        res.insert(Database::new()?);
-        res.insert(console_mapper);
-        res.insert(Stylesheet::load()?);
-        res.insert(Locale::new(&LocaleSettings::load()?.lang));
+        res.insert(database);
+        res.insert(console_mapper?);
+        let styles = styles?;
+        animation::set_reduce_motion(styles.reduce_motion);
+        res.insert(styles);
+        let locale = locale?;
+        geom::set_right_to_left(locale.is_right_to_left(), display.size().width);
+        res.insert(locale);
         res.insert(Into::<geom::Size>::into(display.size()));
         let res = Resources::new(res);
 
         let view = App::load_or_new(display.bounding_box().into(), res.clone(), battery)?;
+        boot_timer.phase("views");
 
         Ok(AlliumLauncher {
             platform,
             display,
             res,
             view,
-            toast: None,
+            toasts: ToastQueue::new(),
+            // No game is running while the launcher is open
+            combos: ComboDetector::default().without(ComboAction::ForceQuit),
+            rumble: RumbleSettings::load().unwrap_or_else(|e| {
+                warn!("failed to load rumble settings: {}", e);
+                RumbleSettings::default()
+            }),
+            boot_timer: Some(boot_timer),
         })
     }
 
     pub async fn run_event_loop(&mut self) -> Result<()> {
-        {
-            let styles = self.res.get::<Stylesheet>();
-
-            if let Some(wallpaper) = styles.wallpaper.as_deref() {
-                let path = ALLIUM_SD_ROOT.join(wallpaper);
-                if let Err(e) = set_wallpaper(&mut self.display, &path) {
-                    error!("Failed to set wallpaper: {}", e);
-                }
-            }
+        // The background is drawn by the app view, depending on the selected tab
+        self.display.save()?;
 
-            self.display.clear(styles.background_color)?;
+        if let Err(e) = self.launch_requested_game().await {
+            warn!("failed to launch requested game: {}", e);
         }
 
-        self.display.save()?;
-
         #[cfg(unix)]
         let mut sigterm =
             tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
@@ -88,6 +134,8 @@ impl AlliumLauncher<DefaultPlatform> {
         let mut keys: EnumMap<Key, bool> = EnumMap::default();
 
         let mut frame_interval = tokio::time::interval(tokio::time::Duration::from_micros(166_667));
+        let mut animation_interval = tokio::time::interval(ANIMATION_FRAME_TIME);
+        animation_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
 
         let mut last_frame = Instant::now();
         loop {
@@ -100,10 +148,10 @@ impl AlliumLauncher<DefaultPlatform> {
                     .view
                     .draw(&mut self.display, &self.res.get::<Stylesheet>())?;
 
-            if let Some(toast) = self.toast.as_mut() {
+            if let Some(toast) = self.toasts.current_mut() {
                 if toast.has_expired() {
                     self.handle_command(Command::Redraw).await?;
-                    self.toast = None;
+                    self.toasts.dismiss();
                 } else {
                     drawn |= toast.draw(&mut self.display, &self.res.get::<Stylesheet>())?;
                 }
@@ -111,11 +159,17 @@ impl AlliumLauncher<DefaultPlatform> {
 
             if drawn {
                 self.display.flush()?;
+                if let Some(boot_timer) = self.boot_timer.take() {
+                    boot_timer.finish();
+                }
             }
 
+            let animating = self.view.is_animating();
+
             #[cfg(unix)]
             tokio::select! {
                 _ = frame_interval.tick() => {}
+                _ = animation_interval.tick(), if animating => {}
                 _ = sigterm.recv() => {
                     self.handle_command(Command::Exit).await?;
                 }
@@ -125,6 +179,10 @@ impl AlliumLauncher<DefaultPlatform> {
                     }
                 }
                 event = self.platform.poll() => {
+                    let Some(event) = self.detect_combo(event).await? else {
+                        continue;
+                    };
+                    let event = event.ui();
                     let mut bubble = VecDeque::new();
                     match event {
                         KeyEvent::Pressed(key) => {
@@ -133,12 +191,16 @@ impl AlliumLauncher<DefaultPlatform> {
                         KeyEvent::Released(key) => {
                             keys[key] = false;
                         }
-                        KeyEvent::Autorepeat(_) => {}
+                        KeyEvent::Autorepeat(_) | KeyEvent::LongPressed(_) => {}
                     }
 
                     // Ignore menu key presses
-                    if !keys[Key::Menu] && !matches!(event, KeyEvent::Released(Key::Menu)) {
-                        self.view.handle_key_event(event, tx.clone(), &mut bubble).await?;
+                    if !keys[Key::Menu]
+                        && !matches!(event, KeyEvent::Released(Key::Menu))
+                        && self.view.handle_key_event(event, tx.clone(), &mut bubble).await?
+                    {
+                        sound::play_for_key_event(&self.res.get::<Stylesheet>(), event);
+                        rumble::rumble_for_key_event(&self.platform, &self.rumble, event);
                     }
                 }
                 else => {}
@@ -146,15 +208,93 @@ impl AlliumLauncher<DefaultPlatform> {
 
             #[cfg(not(unix))]
             tokio::select! {
+                _ = animation_interval.tick(), if animating => {}
                 event = self.platform.poll() => {
+                    let Some(event) = self.detect_combo(event).await? else {
+                        continue;
+                    };
+                    let event = event.ui();
                     let mut bubble = VecDeque::new();
-                    self.view.handle_key_event(event, tx.clone(), &mut bubble).await?;
+                    if self.view.handle_key_event(event, tx.clone(), &mut bubble).await? {
+                        sound::play_for_key_event(&self.res.get::<Stylesheet>(), event);
+                        rumble::rumble_for_key_event(&self.platform, &self.rumble, event);
+                    }
                 }
                 else => {}
             }
         }
     }
 
+    /// Runs the action of a button combo, if the event completes one. Returns the event if it
+    /// should be handled as usual.
+    async fn detect_combo(&mut self, event: KeyEvent) -> Result<Option<KeyEvent>> {
+        let action = match self.combos.detect(event) {
+            Detection::Key(event) => return Ok(Some(event)),
+            Detection::Consumed => return Ok(None),
+            Detection::Combo(action) => action,
+        };
+        debug!("combo: {:?}", action);
+        match action {
+            // alliumd takes screenshots, as it does for its own hotkey
+            ComboAction::Screenshot => {
+                #[cfg(unix)]
+                tokio::process::Command::new("pkill")
+                    .arg("-USR1")
+                    .arg("alliumd")
+                    .spawn()?
+                    .wait()
+                    .await?;
+            }
+            ComboAction::QuickSettings => self.view.open_settings()?,
+            // Left out of the combos the launcher detects
+            ComboAction::ForceQuit => {}
+        }
+        Ok(None)
+    }
+
+    /// Queues a toast, and keeps it in the notification history.
+    fn show_toast(&mut self, toast: Toast) {
+        let text = toast.text().to_string();
+        let replaced = self.toasts.push(toast);
+        // The history is also changed by the settings page, so it is loaded each time
+        let result = NotificationHistory::load().and_then(|mut history| {
+            if replaced {
+                history.replace_last(text);
+            } else {
+                history.push(text);
+            }
+            history.save()
+        });
+        if let Err(e) = result {
+            warn!("failed to save notification history: {}", e);
+        }
+    }
+
+    /// Launches the game requested through the remote control API, if any.
+    async fn launch_requested_game(&mut self) -> Result<()> {
+        let Ok(path) = fs::read_to_string(ALLIUM_LAUNCH_REQUEST.as_path()) else {
+            return Ok(());
+        };
+        fs::remove_file(ALLIUM_LAUNCH_REQUEST.as_path())?;
+        let path = PathBuf::from(path);
+        info!("launching requested game: {}", path.display());
+
+        let command = {
+            let database = self.res.get::<Database>();
+            let mut game = match database.select_game(&path)? {
+                Some(game) => Game::from_db(game),
+                None => Game::new(path),
+            };
+            self.res
+                .get::<ConsoleMapper>()
+                .launch_game(&database, &mut game, false)?
+        };
+        if let Some(command) = command {
+            self.handle_command(command).await?;
+        }
+        Ok(())
+    }
+
     async fn handle_command(&mut self, command: Command) -> Result<()> {
         match command {
             Command::Exit => {
@@ -168,6 +308,36 @@ impl AlliumLauncher<DefaultPlatform> {
             Command::Exec(mut cmd) => {
                 info!("executing command: {:?}", cmd);
                 self.view.save()?;
+                let game_info = GameInfo::load()?;
+                if let Some(game_info) = &game_info
+                    && let Err(e) = self.platform.set_cpu_profile(
+                        PerformanceSettings::load()
+                            .unwrap_or_else(|e| {
+                                warn!("failed to load performance settings: {}", e);
+                                PerformanceSettings::default()
+                            })
+                            .cpu_profile(game_info.cpu_profile),
+                    )
+                {
+                    warn!("failed to set cpu profile: {}", e);
+                }
+                if let Some(game_info) = &game_info
+                    && storage::is_network_share(&game_info.path)
+                {
+                    let text = self.res.get::<Locale>().t("network-share-copying");
+                    Toast::new(text, None)
+                        .draw(&mut self.display, &self.res.get::<Stylesheet>())?;
+                    self.display.flush()?;
+                    if let Err(e) = storage::cache_network_game(&game_info.path).await {
+                        warn!("failed to copy game from the network share: {}", e);
+                        GameInfo::delete()?;
+                        let text = self.res.get::<Locale>().t("network-share-copy-failed");
+                        self.show_toast(Toast::new(text, Some(Duration::from_secs(3))));
+                        self.display.load(self.display.bounding_box().into())?;
+                        self.view.set_should_draw();
+                        return Ok(());
+                    }
+                }
                 self.display.clear(Color::new(0, 0, 0))?;
                 self.display.flush()?;
                 #[cfg(feature = "miyoo")]
@@ -195,22 +365,7 @@ impl AlliumLauncher<DefaultPlatform> {
                 styles.load_fonts()?;
                 styles.save()?;
 
-                {
-                    let old_styles = self.res.get::<Stylesheet>();
-                    if old_styles.wallpaper != styles.wallpaper
-                        || old_styles.background_color != styles.background_color
-                    {
-                        if let Some(wallpaper) = styles.wallpaper.as_deref() {
-                            let path = ALLIUM_SD_ROOT.join(wallpaper);
-                            if let Err(e) = set_wallpaper(&mut self.display, &path) {
-                                error!("Failed to set wallpaper: {}", e);
-                            }
-                        }
-                        self.display.clear(styles.background_color)?;
-                        self.display.save()?;
-                    }
-                }
-
+                animation::set_reduce_motion(styles.reduce_motion);
                 self.res.insert(*styles);
                 self.view.save()?;
                 self.view = App::load_or_new(
@@ -222,12 +377,60 @@ impl AlliumLauncher<DefaultPlatform> {
             Command::SaveDisplaySettings(mut settings) => {
                 trace!("saving display settings");
                 self.platform.set_display_settings(&mut settings)?;
+                self.display.set_vsync(settings.vsync);
                 settings.save()?;
             }
+            Command::SaveAudioSettings(settings) => {
+                trace!("saving audio settings");
+                self.platform.set_audio_settings(&settings)?;
+                settings.save()?;
+                // Let alliumd apply the volume curve
+                #[cfg(unix)]
+                std::process::Command::new("pkill")
+                    .arg("-HUP")
+                    .arg("alliumd")
+                    .spawn()?;
+            }
             Command::SaveLocaleSettings(settings) => {
                 trace!("saving locale settings");
                 settings.save()?;
-                self.res.insert(Locale::new(&settings.lang));
+                let locale = Locale::new(&settings);
+                geom::set_right_to_left(locale.is_right_to_left(), self.display.size().width);
+                self.res.insert(locale);
+                // Let alliumd reload its strings
+                #[cfg(unix)]
+                std::process::Command::new("pkill")
+                    .arg("-HUP")
+                    .arg("alliumd")
+                    .spawn()?;
+            }
+            Command::LocaleChanged => {
+                trace!("locale changed, reloading strings");
+                self.view.locale_changed()?;
+                self.toasts.clear();
+                self.display.load(self.display.bounding_box().into())?;
+            }
+            Command::SaveConsoleSettings(settings) => {
+                trace!("saving console settings");
+                settings.save()?;
+                self.view.save()?;
+                self.view = App::load_or_new(
+                    self.display.bounding_box().into(),
+                    self.res.clone(),
+                    self.platform.battery()?,
+                )?;
+            }
+            Command::SaveUiKeymap(keymap) => {
+                trace!("saving UI keymap");
+                keymap.save()?;
+                UiKeymap::set_current(keymap);
+                // Let alliumd pick up the long press duration
+                #[cfg(unix)]
+                std::process::Command::new("pkill")
+                    .arg("-HUP")
+                    .arg("alliumd")
+                    .spawn()?;
+                // Button hints show the physical keys when built, so rebuild the views
                 self.view.save()?;
                 self.view = App::load_or_new(
                     self.display.bounding_box().into(),
@@ -235,6 +438,16 @@ impl AlliumLauncher<DefaultPlatform> {
                     self.platform.battery()?,
                 )?;
             }
+            Command::SaveComboSettings(settings) => {
+                trace!("saving combo settings");
+                settings.save()?;
+                self.combos.set_settings(settings);
+            }
+            Command::SaveRumbleSettings(settings) => {
+                trace!("saving rumble settings");
+                settings.save()?;
+                self.rumble = settings;
+            }
             Command::Redraw => {
                 trace!("redrawing");
                 self.display.load(self.display.bounding_box().into())?;
@@ -242,7 +455,7 @@ impl AlliumLauncher<DefaultPlatform> {
             }
             Command::StartSearch => {
                 trace!("starting search");
-                self.view.start_search();
+                self.view.start_search()?;
             }
             Command::Search(query) => {
                 trace!("searching");
@@ -250,15 +463,15 @@ impl AlliumLauncher<DefaultPlatform> {
             }
             Command::Toast(text, duration) => {
                 trace!("showing toast: {:?}", text);
-                self.toast = Some(Toast::new(text, duration));
+                self.show_toast(Toast::new(text, duration));
             }
             Command::ImageToast(image, text, duration) => {
                 trace!("showing image toast: {:?}", text);
-                self.toast = Some(Toast::with_image(image, text, duration));
+                self.show_toast(Toast::with_image(image, text, duration));
             }
             Command::DismissToast => {
                 trace!("dismissing toast");
-                self.toast = None;
+                self.toasts.dismiss();
                 self.display.load(self.display.bounding_box().into())?;
                 self.view.set_should_draw();
             }
@@ -276,7 +489,7 @@ impl AlliumLauncher<DefaultPlatform> {
                 }
 
                 let mut queue = VecDeque::with_capacity(10);
-                queue.push_back(Directory::new(ALLIUM_GAMES_DIR.clone()));
+                queue.extend(storage::games_dirs().into_iter().map(Directory::new));
 
                 let database = self.res.get::<Database>();
                 let console_mapper = self.res.get::<ConsoleMapper>();
@@ -290,7 +503,7 @@ impl AlliumLauncher<DefaultPlatform> {
                         if let Err(e) = database.update_game_path(&old, &game.path) {
                             warn!("failed to update game path: {}", e);
                         }
-                    } else if !game.path.exists() {
+                    } else if !archive::exists(&game.path) && !storage::is_unmounted(&game.path) {
                         database.delete_game(&game.path)?;
                     }
                 }
@@ -319,7 +532,11 @@ impl AlliumLauncher<DefaultPlatform> {
                     dir.populate_db(&mut queue, &database, &console_mapper, &self.res.get())?;
                 }
 
+                if let Err(e) = onion::import(&database) {
+                    warn!("failed to import from Onion OS: {}", e);
+                }
                 database.set_has_indexed(true)?;
+                led::notify_job_finished();
 
                 self.view.save()?;
                 self.view = App::load_or_new(
@@ -328,6 +545,95 @@ impl AlliumLauncher<DefaultPlatform> {
                     self.platform.battery()?,
                 )?;
             }
+            Command::VerifyRoms => {
+                #[cfg(feature = "miyoo")]
+                {
+                    std::process::Command::new("show")
+                        .arg("--clear")
+                        .spawn()?
+                        .wait()?;
+                    std::process::Command::new("say")
+                        .arg(self.res.get::<Locale>().t("verifying-roms"))
+                        .spawn()?
+                        .wait()?;
+                }
+
+                let dat = Dat::load(&ALLIUM_DATS_DIR).unwrap_or_else(|e| {
+                    warn!("failed to load DAT files: {}", e);
+                    Dat::default()
+                });
+                let message = if dat.is_empty() {
+                    self.res.get::<Locale>().t("verify-roms-no-dats")
+                } else {
+                    let database = self.res.get::<Database>();
+                    let games = database.select_all_games()?;
+                    let statuses: Vec<_> = games
+                        .iter()
+                        .filter(|game| game.path.is_file())
+                        .map(|game| {
+                            let status = dat.verify(&game.path).unwrap_or_else(|e| {
+                                warn!("failed to verify {}: {}", game.path.display(), e);
+                                RomStatus::Unknown
+                            });
+                            (game.path.as_path(), status)
+                        })
+                        .collect();
+                    database.set_rom_statuses(&statuses)?;
+                    if let Err(e) = dat::write_report(&ALLIUM_ROM_VERIFICATION_REPORT, &statuses) {
+                        warn!("failed to write verification report: {}", e);
+                    }
+
+                    let count = |status| statuses.iter().filter(|(_, s)| *s == status).count();
+                    let mut map = std::collections::HashMap::new();
+                    map.insert("verified".into(), count(RomStatus::Verified).into());
+                    map.insert("bad".into(), count(RomStatus::BadDump).into());
+                    map.insert("overdump".into(), count(RomStatus::Overdump).into());
+                    map.insert("unknown".into(), count(RomStatus::Unknown).into());
+                    self.res.get::<Locale>().ta("verify-roms-summary", &map)
+                };
+
+                self.view.save()?;
+                self.view = App::load_or_new(
+                    self.display.bounding_box().into(),
+                    self.res.clone(),
+                    self.platform.battery()?,
+                )?;
+                self.show_toast(Toast::new(message, None));
+                led::notify_job_finished();
+            }
+            Command::LaunchRandomGame { favorites_only } => {
+                trace!("launching random game, favorites only: {}", favorites_only);
+                let game = {
+                    let database = self.res.get::<Database>();
+                    if favorites_only {
+                        database.select_random_favorites(1)?
+                    } else {
+                        database.select_random(1)?
+                    }
+                }
+                .into_iter()
+                .next();
+
+                let Some(game) = game else {
+                    let message = self.res.get::<Locale>().t("surprise-me-no-games");
+                    self.show_toast(Toast::new(message, Some(Duration::from_secs(2))));
+                    return Ok(());
+                };
+
+                let command = self.res.get::<ConsoleMapper>().launch_game(
+                    &self.res.get(),
+                    &mut Game::from_db(game),
+                    false,
+                )?;
+                if let Some(command) = command {
+                    Box::pin(self.handle_command(command)).await?;
+                }
+            }
+            Command::Batch(commands) => {
+                for command in commands {
+                    Box::pin(self.handle_command(command)).await?;
+                }
+            }
             command => {
                 warn!("unhandled command: {:?}", command);
             }
@@ -335,23 +641,3 @@ impl AlliumLauncher<DefaultPlatform> {
         Ok(())
     }
 }
-
-fn set_wallpaper(display: &mut impl Display, path: &Path) -> Result<()> {
-    if !path.exists() {
-        return Ok(());
-    }
-
-    let rect = display.bounding_box().size;
-
-    let image = ::image::open(path)?;
-    let image = image.resize_to_fill(
-        rect.width,
-        rect.height,
-        image::imageops::FilterType::Lanczos3,
-    );
-    let image = image.into_rgba8();
-    let image: ImageRaw<'_, Color> = ImageRaw::new(&image, rect.width);
-    let image = embedded_graphics::image::Image::new(&image, display.bounding_box().top_left);
-    image.draw(display)?;
-    Ok(())
-}
@@ -0,0 +1,43 @@
+use std::time::{Duration, Instant};
+
+use itertools::Itertools;
+use log::info;
+
+/// Times the phases of startup, and logs them together once the first frame is shown, so that
+/// slow startups can be tracked down from the log.
+#[derive(Debug)]
+pub struct BootTimer {
+    start: Instant,
+    last: Instant,
+    phases: Vec<(&'static str, Duration)>,
+}
+
+impl BootTimer {
+    pub fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            start: now,
+            last: now,
+            phases: Vec::new(),
+        }
+    }
+
+    /// Marks the end of a phase that started when the previous one ended.
+    pub fn phase(&mut self, name: &'static str) {
+        let now = Instant::now();
+        self.phases.push((name, now - self.last));
+        self.last = now;
+    }
+
+    pub fn finish(mut self) {
+        self.phase("first frame");
+        info!(
+            "startup took {}ms: {}",
+            self.start.elapsed().as_millis(),
+            self.phases
+                .iter()
+                .map(|(name, duration)| format!("{} {}ms", name, duration.as_millis()))
+                .join(", ")
+        );
+    }
+}
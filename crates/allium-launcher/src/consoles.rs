@@ -6,11 +6,14 @@ use anyhow::{Context, Result, anyhow, bail};
 use common::command::Command;
 use common::database::Database;
 use common::game_info::GameInfo;
+use common::session_journal::SessionJournal;
+use common::storage;
 use serde::Deserialize;
 
 use common::constants::{ALLIUM_CONFIG_CONSOLES, ALLIUM_CONFIG_CORES, ALLIUM_RETROARCH};
 use log::{debug, error, trace};
 
+use crate::entry::archive;
 use crate::entry::game::Game;
 
 pub type CoreName = String;
@@ -34,6 +37,10 @@ pub struct Console {
     /// e.g. "Doukutsu.exe" for NXEngine
     #[serde(default)]
     pub file_name: Vec<String>,
+    /// libretro thumbnail system name, used to download box art.
+    /// e.g. "Nintendo - Game Boy Advance"
+    #[serde(default)]
+    pub thumbnails: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -133,6 +140,14 @@ impl ConsoleMapper {
         None
     }
 
+    /// Whether a file extension is a game of any console.
+    pub fn is_rom_extension(&self, extension: &str) -> bool {
+        let extension = extension.to_ascii_lowercase();
+        self.consoles
+            .iter()
+            .any(|console| console.extensions.contains(&extension))
+    }
+
     /// Returns a console that this path maps to, or none.
     pub fn get_console(&self, path: &Path) -> Option<&Console> {
         let path_lowercase = path.as_os_str().to_ascii_lowercase();
@@ -184,9 +199,7 @@ impl ConsoleMapper {
         game: &mut Game,
         disable_savestate_auto_load: bool,
     ) -> Result<Option<Command>> {
-        if !game.path.exists()
-            && let Some(old) = Game::resync(&mut game.path)?
-        {
+        if let Some(old) = Game::resync(&mut game.path)? {
             database.update_game_path(&old, &game.path)?;
         }
 
@@ -207,7 +220,17 @@ impl ConsoleMapper {
             error!("Core \"{}\" does not exist.", core_name);
             return Ok(None);
         };
-        let game_info = match &core.core {
+        // Games in a ROM pack are launched from a temporary copy, but keep their path in the
+        // archive so that saves and play time stay with it. Likewise for games on the network share,
+        // which are copied when the game is executed.
+        let rom = if archive::split(&game.path).is_some() {
+            archive::extract(&game.path)?
+        } else if storage::is_network_share(&game.path) {
+            storage::network_cache_path(&game.path)?
+        } else {
+            game.path.clone()
+        };
+        let mut game_info = match &core.core {
             CoreType::RetroArch(libretro_core) => GameInfo::new(
                 game.name.clone(),
                 game.path.clone(),
@@ -223,7 +246,7 @@ impl ConsoleMapper {
                 } else {
                     ALLIUM_RETROARCH.display().to_string()
                 },
-                vec![libretro_core.to_string(), game.path.display().to_string()],
+                vec![libretro_core.to_string(), rom.display().to_string()],
                 true,
                 core.swap,
             ),
@@ -233,13 +256,15 @@ impl ConsoleMapper {
                 core_name.clone(),
                 image,
                 path.to_string_lossy().to_string(),
-                vec![game.path.display().to_string()],
+                vec![rom.display().to_string()],
                 false,
                 core.swap,
             ),
         };
+        game_info.cpu_profile = database.get_cpu_profile(&game.path)?;
         debug!("Saving game info: {:?}", game_info);
         game_info.save()?;
+        SessionJournal::open(&game_info)?;
         Ok(Some(Command::Exec(game_info.command())))
     }
 
@@ -267,6 +292,7 @@ mod tests {
             extensions: vec!["gb".into(), "gbc".into()],
             cores: vec![],
             file_name: vec![],
+            thumbnails: None,
         }];
 
         assert!(mapper.get_console(Path::new("Roms/POKE/rom.zip")).is_some());
@@ -0,0 +1,211 @@
+//! Verifies games against No-Intro style DAT files, which list the size and CRC32 of every known
+//! good dump.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use anyhow::Result;
+use common::database::RomStatus;
+use crc32fast::Hasher;
+use log::{debug, warn};
+use serde::Deserialize;
+use zip::ZipArchive;
+
+#[derive(Debug, Deserialize)]
+struct DataFile {
+    #[serde(default, rename = "game")]
+    games: Vec<DatGame>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DatGame {
+    #[serde(default, rename = "rom")]
+    roms: Vec<DatRom>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DatRom {
+    #[serde(rename = "@name")]
+    name: String,
+    #[serde(rename = "@size")]
+    size: u64,
+    #[serde(rename = "@crc")]
+    crc: String,
+}
+
+/// The known dumps of every DAT file in a directory.
+#[derive(Debug, Default)]
+pub struct Dat {
+    /// Size and CRC32 of every known dump.
+    hashes: HashSet<(u64, u32)>,
+    /// Size and CRC32 of known dumps, by lowercase file stem.
+    names: HashMap<String, (u64, u32)>,
+}
+
+impl Dat {
+    /// Loads every `.dat` and `.xml` file in a directory.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let mut dat = Self::default();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let is_dat = path.extension().is_some_and(|ext| {
+                ext.eq_ignore_ascii_case("dat") || ext.eq_ignore_ascii_case("xml")
+            });
+            if !is_dat {
+                continue;
+            }
+            debug!("loading DAT file {}", path.display());
+            if let Err(e) = dat.parse(&fs::read_to_string(&path)?) {
+                warn!("failed to parse DAT file {}: {}", path.display(), e);
+            }
+        }
+        Ok(dat)
+    }
+
+    fn parse(&mut self, xml: &str) -> Result<()> {
+        let data_file: DataFile = quick_xml::de::from_str(xml)?;
+        for rom in data_file.games.into_iter().flat_map(|game| game.roms) {
+            let Ok(crc) = u32::from_str_radix(&rom.crc, 16) else {
+                continue;
+            };
+            self.hashes.insert((rom.size, crc));
+            self.names.insert(stem(&rom.name), (rom.size, crc));
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Checks a game file. Zip files holding a single file are checked by their contents.
+    pub fn verify(&self, path: &Path) -> Result<RomStatus> {
+        if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+        {
+            let mut archive = ZipArchive::new(File::open(path)?)?;
+            if archive.len() == 1 {
+                let (name, size, crc) = {
+                    let file = archive.by_index(0)?;
+                    (file.name().to_owned(), file.size(), file.crc32())
+                };
+                return self.check(&name, size, crc, |len| {
+                    crc32(archive.by_index(0)?.take(len))
+                });
+            }
+        }
+
+        let name = path.to_string_lossy();
+        let size = fs::metadata(path)?.len();
+        let crc = crc32(File::open(path)?)?;
+        self.check(&name, size, crc, |len| crc32(File::open(path)?.take(len)))
+    }
+
+    /// Checks a dump by its hash, then by its name. `prefix_crc` hashes the first bytes of the
+    /// dump, to find overdumps.
+    fn check(
+        &self,
+        name: &str,
+        size: u64,
+        crc: u32,
+        prefix_crc: impl FnOnce(u64) -> Result<u32>,
+    ) -> Result<RomStatus> {
+        if self.hashes.contains(&(size, crc)) {
+            return Ok(RomStatus::Verified);
+        }
+        let Some(&(expected_size, expected_crc)) = self.names.get(&stem(name)) else {
+            return Ok(RomStatus::Unknown);
+        };
+        if size > expected_size && prefix_crc(expected_size)? == expected_crc {
+            Ok(RomStatus::Overdump)
+        } else {
+            Ok(RomStatus::BadDump)
+        }
+    }
+}
+
+/// Writes the games that failed verification to a report file.
+pub fn write_report(path: &Path, statuses: &[(&Path, RomStatus)]) -> Result<()> {
+    let mut file = File::create(path)?;
+    for (game, status) in statuses {
+        let label = match status {
+            RomStatus::BadDump => "bad dump",
+            RomStatus::Overdump => "overdump",
+            RomStatus::Unknown | RomStatus::Verified => continue,
+        };
+        writeln!(file, "{}: {}", label, game.display())?;
+    }
+    Ok(())
+}
+
+fn stem(name: &str) -> String {
+    Path::new(name)
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_lowercase()
+}
+
+fn crc32(mut reader: impl Read) -> Result<u32> {
+    let mut hasher = Hasher::new();
+    let mut buf = [0; 64 * 1024];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => hasher.update(&buf[..n]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn test_verify() {
+        let dir = env::temp_dir().join("allium-test-dat");
+        fs::create_dir_all(&dir).unwrap();
+
+        let good = b"good game";
+        let crc = crc32fast::hash(good);
+        let mut dat = Dat::default();
+        dat.parse(&format!(
+            r#"<?xml version="1.0"?>
+<datafile>
+    <header><name>Test</name></header>
+    <game name="Game (World)">
+        <description>Game (World)</description>
+        <rom name="Game (World).gb" size="{}" crc="{:08x}"/>
+    </game>
+</datafile>"#,
+            good.len(),
+            crc
+        ))
+        .unwrap();
+
+        let renamed = dir.join("Renamed.gb");
+        fs::write(&renamed, good).unwrap();
+        assert_eq!(dat.verify(&renamed).unwrap(), RomStatus::Verified);
+
+        let overdump = dir.join("Game (World).gb");
+        fs::write(&overdump, b"good game\0\0\0\0").unwrap();
+        assert_eq!(dat.verify(&overdump).unwrap(), RomStatus::Overdump);
+
+        fs::write(&overdump, b"bad game").unwrap();
+        assert_eq!(dat.verify(&overdump).unwrap(), RomStatus::BadDump);
+
+        let unknown = dir.join("Other.gb");
+        fs::write(&unknown, b"bad game").unwrap();
+        assert_eq!(dat.verify(&unknown).unwrap(), RomStatus::Unknown);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
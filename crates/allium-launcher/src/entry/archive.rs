@@ -0,0 +1,149 @@
+//! ROM packs are zip archives holding several games, which are browsed like folders. They are
+//! opt-in by naming them `*.pack.zip`, so that zipped games, such as DOS games made of several
+//! files, are left alone and listing a folder doesn't have to open every zip in it. The games
+//! inside have virtual paths under the archive, e.g. `Roms/GBA/Games.pack.zip/Game.gba`, and are
+//! extracted when launched.
+
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+use common::constants::ALLIUM_ARCHIVE_EXTRACT_DIR;
+use log::debug;
+use zip::ZipArchive;
+
+use crate::consoles::ConsoleMapper;
+
+const PACK_SUFFIX: &str = ".pack.zip";
+
+/// Name of a ROM pack without the `.pack.zip` suffix, or None if the path isn't named as one.
+pub fn pack_name(path: &Path) -> Option<&str> {
+    let name = path.file_name()?.to_str()?;
+    let split = name.len().checked_sub(PACK_SUFFIX.len())?;
+    (name.is_char_boundary(split) && name[split..].eq_ignore_ascii_case(PACK_SUFFIX))
+        .then(|| &name[..split])
+}
+
+/// Whether a file is named as a zip archive of several games, rather than a single zipped game.
+pub fn is_rom_pack(path: &Path) -> bool {
+    pack_name(path).is_some_and(|name| !name.is_empty())
+}
+
+/// Splits a virtual path into the archive and the name of the file inside it.
+pub fn split(path: &Path) -> Option<(&Path, String)> {
+    let archive = path
+        .ancestors()
+        .skip(1)
+        .find(|a| is_rom_pack(a) && a.is_file())?;
+    let name = path.strip_prefix(archive).ok()?;
+    Some((archive, name.to_string_lossy().replace('\\', "/")))
+}
+
+/// Names of the files in an archive that are games of a known console.
+pub fn roms(archive: &Path, console_mapper: &ConsoleMapper) -> Result<Vec<String>> {
+    let archive = ZipArchive::new(File::open(archive)?)?;
+    let mut roms: Vec<String> = archive
+        .file_names()
+        .filter(|name| !name.ends_with('/'))
+        .filter(|name| {
+            Path::new(name)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| console_mapper.is_rom_extension(ext))
+        })
+        .map(str::to_owned)
+        .collect();
+    roms.sort_unstable_by_key(|name| name.to_lowercase());
+    Ok(roms)
+}
+
+/// Whether a game exists, either as a file or inside an archive.
+pub fn exists(path: &Path) -> bool {
+    path.exists()
+        || split(path).is_some_and(|(archive, name)| {
+            File::open(archive)
+                .ok()
+                .and_then(|file| ZipArchive::new(file).ok())
+                .is_some_and(|archive| archive.index_for_name(&name).is_some())
+        })
+}
+
+/// Extracts a game from its archive, replacing the previously extracted game. Returns the path
+/// to the extracted file.
+pub fn extract(path: &Path) -> Result<PathBuf> {
+    let (archive, name) =
+        split(path).ok_or_else(|| anyhow!("{} is not in an archive", path.display()))?;
+    let file_name = Path::new(&name)
+        .file_name()
+        .ok_or_else(|| anyhow!("invalid archive entry: {}", name))?;
+
+    if ALLIUM_ARCHIVE_EXTRACT_DIR.exists() {
+        fs::remove_dir_all(ALLIUM_ARCHIVE_EXTRACT_DIR.as_path())?;
+    }
+    fs::create_dir_all(ALLIUM_ARCHIVE_EXTRACT_DIR.as_path())?;
+
+    let out = ALLIUM_ARCHIVE_EXTRACT_DIR.join(file_name);
+    debug!(
+        "extracting {} from {} to {:?}",
+        name,
+        archive.display(),
+        out
+    );
+    let mut archive = ZipArchive::new(File::open(archive)?)?;
+    io::copy(&mut archive.by_name(&name)?, &mut File::create(&out)?)?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::io::Write;
+
+    use serial_test::serial;
+    use zip::ZipWriter;
+    use zip::write::SimpleFileOptions;
+
+    use super::*;
+
+    #[test]
+    #[serial(env_ALLIUM_BASE_DIR)]
+    fn test_rom_pack() {
+        // SAFETY: tests that depend on this env var are run serially
+        unsafe {
+            env::set_var("ALLIUM_BASE_DIR", "../../static/.allium");
+        }
+        let mut console_mapper = ConsoleMapper::new();
+        console_mapper.load_config().unwrap();
+
+        let dir = env::temp_dir().join("allium-test-rom-pack");
+        fs::create_dir_all(&dir).unwrap();
+        let pack = dir.join("Games.Pack.zip");
+        let mut zip = ZipWriter::new(File::create(&pack).unwrap());
+        for name in ["readme.txt", "Tetris.gb", "Games/Pokemon.GBA"] {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(name.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        assert_eq!(
+            roms(&pack, &console_mapper).unwrap(),
+            ["Games/Pokemon.GBA", "Tetris.gb"]
+        );
+        assert!(is_rom_pack(&pack));
+        assert_eq!(pack_name(&pack), Some("Games"));
+        assert!(!is_rom_pack(&dir.join("Doom.zip")));
+        assert!(!is_rom_pack(&dir.join(".pack.zip")));
+
+        let game = pack.join("Games/Pokemon.GBA");
+        assert_eq!(
+            split(&game),
+            Some((pack.as_path(), "Games/Pokemon.GBA".to_owned()))
+        );
+        assert!(exists(&game));
+        assert!(!exists(&pack.join("Zelda.gb")));
+        assert_eq!(split(&pack), None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -7,18 +7,20 @@ use std::{
 };
 
 use anyhow::{Result, anyhow};
+use chrono::Utc;
 use common::{
     constants::ALLIUM_GAMES_DIR,
     database::{Database, NewGame},
     locale::Locale,
+    storage,
 };
 use itertools::Itertools;
-use log::{debug, error, trace};
+use log::{debug, error, trace, warn};
 use serde::{Deserialize, Serialize};
 
 use crate::{
     consoles::ConsoleMapper,
-    entry::{Entry, game::Game, gamelist::GameList, lazy_image::LazyImage, short_name},
+    entry::{Entry, archive, game::Game, gamelist::GameList, lazy_image::LazyImage, short_name},
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -29,6 +31,31 @@ pub struct Directory {
     /// image is loaded lazily.
     /// None means image hasn't been looked for, Some(None) means no image was found, Some(Some(path)) means an image was found.
     pub image: LazyImage,
+    #[serde(default)]
+    pub kind: DirectoryKind,
+}
+
+/// What a directory lists.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DirectoryKind {
+    /// The entries of a folder on the SD card.
+    #[default]
+    Folder,
+    /// Every game under the path, across all consoles.
+    AllGames,
+    /// A directory for each genre of the games under the path.
+    Genres,
+    /// Every game of a genre under the path.
+    Genre(String),
+    /// The games in a zip archive.
+    Archive,
+}
+
+impl DirectoryKind {
+    /// Whether the directory lists games from many consoles.
+    pub fn is_flattened(&self) -> bool {
+        matches!(self, DirectoryKind::AllGames | DirectoryKind::Genre(_))
+    }
 }
 
 impl Ord for Directory {
@@ -50,6 +77,7 @@ impl Default for Directory {
             full_name: "Games".into(),
             path: ALLIUM_GAMES_DIR.to_owned(),
             image: LazyImage::Unknown(ALLIUM_GAMES_DIR.to_owned()),
+            kind: DirectoryKind::Folder,
         }
     }
 }
@@ -68,6 +96,7 @@ impl Directory {
             full_name,
             path,
             image,
+            kind: DirectoryKind::Folder,
         }
     }
 
@@ -83,6 +112,29 @@ impl Directory {
             full_name,
             path,
             image,
+            kind: DirectoryKind::Folder,
+        }
+    }
+
+    /// A directory that isn't backed by a folder, listing games under `path` by `kind`.
+    pub fn virtual_dir(path: PathBuf, name: String, kind: DirectoryKind) -> Directory {
+        Directory {
+            full_name: name.clone(),
+            name,
+            path,
+            image: LazyImage::NotFound,
+            kind,
+        }
+    }
+
+    /// A ROM pack, listing the games in the archive at `path`.
+    pub fn archive(path: PathBuf) -> Directory {
+        let full_name = archive::pack_name(&path).unwrap_or_default().to_string();
+        Directory {
+            name: short_name(&full_name),
+            full_name,
+            kind: DirectoryKind::Archive,
+            ..Directory::new(path)
         }
     }
 
@@ -90,6 +142,17 @@ impl Directory {
         self.image.image()
     }
 
+    /// Whether the directory or any of its subdirectories contains a game, according to the
+    /// database. Assumes it does if the database can't be read, so that nothing is hidden wrongly.
+    pub fn has_games(&self, database: &Database) -> bool {
+        database
+            .has_games_under_directory(&self.path)
+            .unwrap_or_else(|e| {
+                warn!("failed to check for games in {:?}: {}", self.path, e);
+                true
+            })
+    }
+
     fn parse_game_list(&self, game_list: &Path) -> Result<Vec<Entry>> {
         let mut file = File::open(game_list)?;
         let mut s = String::with_capacity(1024);
@@ -170,6 +233,71 @@ impl Directory {
         console_mapper: &ConsoleMapper,
         #[allow(unused)] locale: &Locale,
     ) -> Result<Vec<Entry>> {
+        match &self.kind {
+            DirectoryKind::Folder => {}
+            DirectoryKind::AllGames => {
+                debug!("Populating all games under directory: {:?}", self.path);
+                let mut entries = Vec::new();
+                for dir in self.search_dirs() {
+                    entries.extend(
+                        database
+                            .select_games_under_directory(&dir)?
+                            .into_iter()
+                            .map(Game::from_db)
+                            .map(Entry::Game),
+                    );
+                }
+                return Ok(entries);
+            }
+            DirectoryKind::Genres => {
+                debug!("Populating genres under directory: {:?}", self.path);
+                let mut genres = Vec::new();
+                for dir in self.search_dirs() {
+                    genres.extend(database.select_genres_under_directory(&dir)?);
+                }
+                genres.sort_unstable();
+                genres.dedup();
+                return Ok(genres
+                    .into_iter()
+                    .map(|genre| {
+                        Entry::Directory(Directory::virtual_dir(
+                            self.path.clone(),
+                            genre.clone(),
+                            DirectoryKind::Genre(genre),
+                        ))
+                    })
+                    .collect());
+            }
+            DirectoryKind::Genre(genre) => {
+                debug!(
+                    "Populating {} games under directory: {:?}",
+                    genre, self.path
+                );
+                let mut entries = Vec::new();
+                for dir in self.search_dirs() {
+                    entries.extend(
+                        database
+                            .select_games_with_genre(&dir, genre)?
+                            .into_iter()
+                            .map(Game::from_db)
+                            .map(Entry::Game),
+                    );
+                }
+                return Ok(entries);
+            }
+            DirectoryKind::Archive => {
+                debug!("Populating games in archive: {:?}", self.path);
+                return archive::roms(&self.path, console_mapper)?
+                    .into_iter()
+                    .map(|name| {
+                        let mut game = Game::new(self.path.join(name));
+                        game.core = database.get_core(&game.path)?;
+                        Ok(Entry::Game(game))
+                    })
+                    .collect();
+            }
+        }
+
         let mut entries: Vec<Entry> = Vec::with_capacity(64);
         debug!("Populating entries for directory: {:?}", &self.path);
 
@@ -343,6 +471,33 @@ impl Directory {
                 .dedup_by(|a, b| a.name() == b.name()),
         );
 
+        // The console folders on secondary storage are listed with the SD card's, labelled
+        // with the storage they're on.
+        if self.path == *ALLIUM_GAMES_DIR {
+            for dir in storage::games_dirs().into_iter().skip(1) {
+                let label = dir
+                    .parent()
+                    .and_then(Path::file_name)
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into_owned();
+                entries.extend(
+                    fs::read_dir(&dir)
+                        .into_iter()
+                        .flatten()
+                        .flatten()
+                        .filter_map(|entry| match Entry::new(entry.path(), console_mapper) {
+                            Ok(Some(Entry::Directory(mut dir))) => {
+                                dir.name = format!("{} ({})", dir.name, label);
+                                Some(Entry::Directory(dir))
+                            }
+                            _ => None,
+                        })
+                        .sorted(),
+                );
+            }
+        }
+
         trace!(
             "Entries after filesystem: {:?}",
             entries.iter().map(|e| e.path()).collect::<Vec<_>>()
@@ -368,6 +523,16 @@ impl Directory {
         Ok(entries)
     }
 
+    /// Directories to search for games: every Roms directory for the top-level games directory,
+    /// otherwise just this one.
+    fn search_dirs(&self) -> Vec<PathBuf> {
+        if self.path == *ALLIUM_GAMES_DIR {
+            storage::games_dirs()
+        } else {
+            vec![self.path.clone()]
+        }
+    }
+
     /// Populate the database with the games in this directory, pushing any subdirectories onto the
     /// queue.
     pub fn populate_db(
@@ -405,6 +570,10 @@ impl Directory {
             })
             .collect();
         database.update_games(&games)?;
+        database.add_discovered_games(
+            &games.iter().map(|g| g.path.as_path()).collect::<Vec<_>>(),
+            Utc::now(),
+        )?;
 
         Ok(())
     }
@@ -2,19 +2,19 @@ use std::{
     cmp::Ordering,
     ffi::OsStr,
     fs, mem,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
 };
 
-use anyhow::Result;
+use anyhow::{Result, anyhow, bail};
 use chrono::NaiveDate;
-use common::constants::ALLIUM_GAMES_DIR;
-use common::database::{Game as DbGame, NewGame};
+use common::database::{Database, Game as DbGame, NewGame};
+use common::storage;
 use log::info;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     consoles::CoreName,
-    entry::{lazy_image::LazyImage, short_name},
+    entry::{archive, lazy_image::LazyImage, short_name},
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -117,17 +117,73 @@ impl Game {
         self.image.image()
     }
 
+    /// Renames the game file, keeping its extension. The name must be a plain file name, so the
+    /// game can't be moved out of its folder.
+    pub fn rename(&mut self, database: &Database, name: &str) -> Result<()> {
+        let mut components = Path::new(name).components();
+        let valid = !name.starts_with('.')
+            && matches!(
+                (components.next(), components.next()),
+                (Some(Component::Normal(n)), None) if n == name
+            );
+        if !valid {
+            bail!("invalid game name: {:?}", name);
+        }
+
+        let mut file_name = name.to_owned();
+        if !self.extension.is_empty() {
+            file_name.push('.');
+            file_name.push_str(&self.extension);
+        }
+        let path = self.path.with_file_name(file_name);
+        self.move_file(database, path)
+    }
+
+    /// Moves the game file into another folder.
+    pub fn move_to(&mut self, database: &Database, dir: &Path) -> Result<()> {
+        let file_name = self
+            .path
+            .file_name()
+            .ok_or_else(|| anyhow!("game has no file name"))?;
+        let path = dir.join(file_name);
+        self.move_file(database, path)
+    }
+
+    fn move_file(&mut self, database: &Database, path: PathBuf) -> Result<()> {
+        if path.exists() {
+            bail!("{} already exists", path.display());
+        }
+        info!("moving game {:?} to {:?}", self.path, path);
+        fs::rename(&self.path, &path)?;
+        database.update_game_path(&self.path, &path)?;
+        self.path = path;
+        Ok(())
+    }
+
+    /// Deletes the game file, and forgets the game.
+    pub fn delete(&self, database: &Database) -> Result<()> {
+        info!("deleting game {:?}", self.path);
+        fs::remove_file(&self.path)?;
+        database.delete_game(&self.path)?;
+        Ok(())
+    }
+
     /// Attempts to resync the game path with the games directory. Returns the old path if it changed.
     pub fn resync(path: &mut PathBuf) -> Result<Option<PathBuf>> {
-        Ok(if path.exists() {
+        Ok(if archive::exists(path) {
             None
         } else if let Some(name) = path.file_name() {
-            if let Some(game) = find(&ALLIUM_GAMES_DIR, name)? {
-                info!("Resynced game path: {:?}", game);
-                Some(mem::replace(path, game))
-            } else {
-                None
+            let mut found = None;
+            for dir in storage::games_dirs() {
+                if let Some(game) = find(&dir, name)? {
+                    found = Some(game);
+                    break;
+                }
             }
+            found.map(|game| {
+                info!("Resynced game path: {:?}", game);
+                mem::replace(path, game)
+            })
         } else {
             None
         })
@@ -191,3 +247,73 @@ fn find(path: &Path, name: &OsStr) -> Result<Option<PathBuf>> {
     }
     Ok(None)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    fn setup(name: &str) -> (PathBuf, Database, Game) {
+        let dir = env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("Tetris.gb");
+        fs::write(&path, "rom").unwrap();
+
+        let database = Database::in_memory().unwrap();
+        let game = Game::new(path);
+        database.update_games(&[game.clone().into()]).unwrap();
+        (dir, database, game)
+    }
+
+    #[test]
+    fn test_rename() {
+        let (dir, database, mut game) = setup("allium-test-game-rename");
+
+        for name in [
+            "",
+            ".",
+            "..",
+            ".hidden",
+            "../Tetris",
+            "Sub/Tetris",
+            "/Tetris",
+            "Tetris/",
+        ] {
+            assert!(game.rename(&database, name).is_err(), "{name:?}");
+        }
+        assert!(dir.join("Tetris.gb").exists());
+
+        game.rename(&database, "Tetris DX").unwrap();
+        assert_eq!(game.path, dir.join("Tetris DX.gb"));
+        assert!(game.path.exists());
+        assert!(!dir.join("Tetris.gb").exists());
+        assert!(database.select_game(&game.path).unwrap().is_some());
+
+        fs::write(dir.join("Zelda.gb"), "rom").unwrap();
+        assert!(game.rename(&database, "Zelda").is_err());
+        assert!(game.path.exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_move_to_and_delete() {
+        let (dir, database, mut game) = setup("allium-test-game-move");
+        let sub = dir.join("Puzzle");
+        fs::create_dir_all(&sub).unwrap();
+
+        game.move_to(&database, &sub).unwrap();
+        assert_eq!(game.path, sub.join("Tetris.gb"));
+        assert!(game.path.exists());
+        assert!(!dir.join("Tetris.gb").exists());
+        assert!(database.select_game(&game.path).unwrap().is_some());
+
+        game.delete(&database).unwrap();
+        assert!(!game.path.exists());
+        assert!(database.select_game(&game.path).unwrap().is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,6 +1,6 @@
 use std::path::{Path, PathBuf};
 
-use common::constants::ALLIUM_SD_ROOT;
+use common::storage;
 use log::debug;
 use serde::{Deserialize, Serialize};
 
@@ -43,6 +43,7 @@ impl LazyImage {
         // - /Roms/Imgs/file.png
         // - /Roms/Imgs/path/to/game/file.png
         // - /Imgs/path-to/game/file.png
+        // Secondary storage is searched the same way, up to its root.
         let mut parent = path.clone();
         let mut image = None;
         let file_name = path.file_name().unwrap();
@@ -71,7 +72,7 @@ impl LazyImage {
                     }
                 }
             }
-            if parent.as_path() == ALLIUM_SD_ROOT.as_path() {
+            if storage::is_root(&parent) {
                 let mut image_path = parent.join("Imgs");
                 parent.push("Roms");
                 image_path.extend(path.strip_prefix(&parent).unwrap());
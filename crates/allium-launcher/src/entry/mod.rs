@@ -1,4 +1,5 @@
 pub mod app;
+pub mod archive;
 pub mod directory;
 pub mod game;
 mod gamelist;
@@ -76,6 +77,8 @@ impl Entry {
             if extension == "pak" && path.join("config.json").exists() {
                 return Ok(Some(Entry::App(App::new(path)?)));
             }
+        } else if archive::is_rom_pack(&path) {
+            return Ok(Some(Entry::Directory(Directory::archive(path))));
         }
 
         Ok(Some(Entry::Game(Game::new(path))))
@@ -142,4 +145,8 @@ pub trait Sort: Debug + Clone {
         locale: &Locale,
     ) -> Result<Vec<Entry>>;
     fn preserve_selection(&self) -> bool;
+    /// Secondary text to show alongside each entry, if any.
+    fn details(&self, _entries: &[Entry], _console_mapper: &ConsoleMapper) -> Option<Vec<String>> {
+        None
+    }
 }
@@ -2,7 +2,9 @@
 #![warn(rust_2018_idioms)]
 
 mod allium_launcher;
+mod boot_timer;
 mod consoles;
+mod dat;
 mod entry;
 mod view;
 
@@ -10,14 +12,13 @@ use anyhow::Result;
 
 use allium_launcher::AlliumLauncher;
 use common::platform::{DefaultPlatform, Platform};
-use simple_logger::SimpleLogger;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    SimpleLogger::new().env().init().unwrap();
+    common::logging::init("allium-launcher")?;
 
     let platform = DefaultPlatform::new()?;
-    let mut app = AlliumLauncher::new(platform)?;
+    let mut app = AlliumLauncher::new(platform).await?;
     app.run_event_loop().await?;
     Ok(())
 }
@@ -1,6 +1,8 @@
 use std::collections::VecDeque;
 use std::fs::{self, File};
 use std::marker::PhantomData;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use async_trait::async_trait;
@@ -8,43 +10,198 @@ use common::battery::Battery;
 use common::command::Command;
 use common::constants::ALLIUM_LAUNCHER_STATE;
 use common::display::Display;
+use common::display::highlight::draw_rounded_rect;
+use common::display::image::draw_background;
 use common::geom::{Alignment, Point, Rect};
 use common::locale::Locale;
 use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
 use common::resources::Resources;
 use common::stylesheet::{Stylesheet, StylesheetColor};
-use common::view::{BatteryIndicator, Clock, Label, Row, View};
-use log::{trace, warn};
+use common::view::{
+    Animation, BatteryIndicator, Clock, Easing, Label, NotificationIndicator,
+    PowerProfileIndicator, Row, View,
+};
+use log::{debug, trace, warn};
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::Sender;
 
 use crate::view::Recents;
 use crate::view::apps::AppsState;
 use crate::view::games::GamesState;
+use crate::view::onboarding::{Onboarding, OnboardingState};
 use crate::view::recents::RecentsState;
 use crate::view::settings::SettingsState;
 use crate::view::{Apps, Games, Settings};
 
+/// How long the selected tab indicator takes to slide to a new tab.
+const TAB_ANIMATION_DURATION: Duration = Duration::from_millis(200);
+
+/// Locale keys of the tab names, in tab order.
+const TAB_NAMES: [&str; 4] = ["tab-recents", "tab-games", "tab-apps", "tab-settings"];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct AppState {
     selected: usize,
-    recents: RecentsState,
-    games: GamesState,
-    apps: AppsState,
-    settings: SettingsState,
+    /// Tab states are `None` if the tab hasn't been shown yet.
+    #[serde(default)]
+    recents: Option<RecentsState>,
+    #[serde(default)]
+    games: Option<GamesState>,
+    #[serde(default)]
+    apps: Option<AppsState>,
+    #[serde(default)]
+    settings: Option<SettingsState>,
+    #[serde(default)]
+    onboarding: Option<OnboardingState>,
+}
+
+/// A view shown as a tab of the app.
+trait TabView: View + Sized {
+    type State: std::fmt::Debug + Clone;
+
+    fn load_tab(rect: Rect, res: Resources, state: Option<Self::State>) -> Result<Self>;
+
+    fn save_tab(&self) -> Self::State;
+}
+
+impl TabView for Recents {
+    type State = RecentsState;
+
+    fn load_tab(rect: Rect, res: Resources, state: Option<Self::State>) -> Result<Self> {
+        Recents::load_or_new(rect, res, state)
+    }
+
+    fn save_tab(&self) -> Self::State {
+        self.save()
+    }
 }
 
+impl TabView for Games {
+    type State = GamesState;
+
+    fn load_tab(rect: Rect, res: Resources, state: Option<Self::State>) -> Result<Self> {
+        Games::load_or_new(rect, res.clone(), state)
+            .or_else(|_| Games::load_or_new(rect, res, None))
+    }
+
+    fn save_tab(&self) -> Self::State {
+        self.save()
+    }
+}
+
+impl TabView for Apps {
+    type State = AppsState;
+
+    fn load_tab(rect: Rect, res: Resources, state: Option<Self::State>) -> Result<Self> {
+        Apps::load_or_new(rect, res, state)
+    }
+
+    fn save_tab(&self) -> Self::State {
+        self.save()
+    }
+}
+
+impl TabView for Settings {
+    type State = SettingsState;
+
+    fn load_tab(rect: Rect, res: Resources, state: Option<Self::State>) -> Result<Self> {
+        Settings::new(rect, res, state.unwrap_or_default())
+    }
+
+    fn save_tab(&self) -> Self::State {
+        self.save()
+    }
+}
+
+/// A tab that is only built when it is first shown, so that startup doesn't wait on tabs that
+/// aren't visible.
+#[derive(Debug)]
+struct LazyTab<V: TabView> {
+    rect: Rect,
+    res: Resources,
+    /// State to build the view from, until it is built.
+    state: Option<V::State>,
+    view: Option<V>,
+}
+
+impl<V: TabView> LazyTab<V> {
+    fn new(rect: Rect, res: Resources, state: Option<V::State>) -> Self {
+        Self {
+            rect,
+            res,
+            state,
+            view: None,
+        }
+    }
+
+    fn load(&mut self) -> Result<&mut V> {
+        if self.view.is_none() {
+            let start = Instant::now();
+            self.view = Some(V::load_tab(self.rect, self.res.clone(), self.state.take())?);
+            debug!(
+                "loaded {} in {}ms",
+                std::any::type_name::<V>(),
+                start.elapsed().as_millis()
+            );
+        }
+        Ok(self.view.as_mut().unwrap())
+    }
+
+    /// The view, which must have been loaded.
+    fn get(&self) -> &V {
+        self.view
+            .as_ref()
+            .expect("tab should be loaded before it is shown")
+    }
+
+    fn get_mut(&mut self) -> &mut V {
+        self.view
+            .as_mut()
+            .expect("tab should be loaded before it is shown")
+    }
+
+    fn save(&self) -> Option<V::State> {
+        match &self.view {
+            Some(view) => Some(view.save_tab()),
+            None => self.state.clone(),
+        }
+    }
+
+    /// Drops the view, keeping its state, so that it is built again the next time it is loaded.
+    fn unload(&mut self) {
+        if let Some(view) = self.view.take() {
+            self.state = Some(view.save_tab());
+        }
+    }
+}
+
+type Tabs = (
+    LazyTab<Recents>,
+    LazyTab<Games>,
+    LazyTab<Apps>,
+    LazyTab<Settings>,
+);
+
 #[derive(Debug)]
 pub struct App<B>
 where
     B: Battery + 'static,
 {
     rect: Rect,
+    res: Resources,
     status_bar: Row<Box<dyn View>>,
-    views: (Recents, Games, Apps, Settings),
+    views: Tabs,
     selected: usize,
     tabs: Row<Label<String>>,
+    /// First boot setup, shown instead of the tabs until finished.
+    onboarding: Option<Onboarding>,
+    /// Tab the indicator is sliding from.
+    tab_from: usize,
+    /// Progress of the indicator sliding from `tab_from` to `selected`.
+    tab_animation: Animation,
     // title: Label<String>,
+    /// Background image currently drawn, or `None` if no background has been drawn yet.
+    background: Option<Option<PathBuf>>,
     dirty: bool,
     _phantom_battery: PhantomData<B>,
 }
@@ -53,11 +210,12 @@ impl<B> App<B>
 where
     B: Battery + 'static,
 {
-    pub fn new(
+    fn new(
         rect: Rect,
         res: Resources,
-        views: (Recents, Games, Apps, Settings),
+        views: Tabs,
         selected: usize,
+        onboarding: Option<Onboarding>,
         battery: B,
     ) -> Result<Self> {
         let Rect { x, y, w, h: _h } = rect;
@@ -78,6 +236,18 @@ where
             children.push(Box::new(clock));
         }
 
+        children.push(Box::new(PowerProfileIndicator::new(
+            res.clone(),
+            Point::new(0, 0),
+            Alignment::Right,
+        )));
+
+        children.push(Box::new(NotificationIndicator::new(
+            res.clone(),
+            Point::new(0, 0),
+            Alignment::Right,
+        )));
+
         let status_bar: Row<Box<dyn View>> = Row::new(
             Point::new(w as i32 - 12, y + 8),
             children,
@@ -87,29 +257,15 @@ where
 
         let mut tabs = Row::new(
             Point::new(x + 12, y + 8),
-            {
-                let mut tabs = vec![
-                    Label::new(
-                        Point::zero(),
-                        locale.t("tab-recents"),
-                        Alignment::Left,
-                        None,
-                    ),
-                    Label::new(Point::zero(), locale.t("tab-games"), Alignment::Left, None),
-                    Label::new(Point::zero(), locale.t("tab-apps"), Alignment::Left, None),
-                    Label::new(
-                        Point::zero(),
-                        locale.t("tab-settings"),
-                        Alignment::Left,
-                        None,
-                    ),
-                ];
-                for tab in tabs.iter_mut() {
+            TAB_NAMES
+                .iter()
+                .map(|name| {
+                    let mut tab = Label::new(Point::zero(), locale.t(name), Alignment::Left, None);
                     tab.color(StylesheetColor::Tab);
                     tab.font_size(styles.tab_font_size);
-                }
-                tabs
-            },
+                    tab
+                })
+                .collect(),
             Alignment::Left,
             12,
         );
@@ -128,16 +284,23 @@ where
         drop(styles);
         drop(locale);
 
-        Ok(Self {
+        let mut this = Self {
             rect,
+            res,
             views,
             selected,
             status_bar,
             tabs,
+            onboarding,
+            tab_from: selected,
+            tab_animation: Animation::finished(1.0),
             // title,
+            background: None,
             dirty: true,
             _phantom_battery: PhantomData,
-        })
+        };
+        this.load_tab(selected)?;
+        Ok(this)
     }
 
     pub fn load_or_new(rect: Rect, res: Resources, battery: B) -> Result<Self> {
@@ -156,36 +319,39 @@ where
             let file = File::open(ALLIUM_LAUNCHER_STATE.as_path())?;
             if let Ok(state) = serde_json::from_reader::<_, AppState>(file) {
                 let views = (
-                    Recents::load_or_new(tab_rect, res.clone(), Some(state.recents))?,
-                    Games::load_or_new(tab_rect, res.clone(), Some(state.games)).unwrap_or_else(
-                        |_| Games::load_or_new(tab_rect, res.clone(), None).unwrap(),
-                    ),
-                    Apps::load_or_new(tab_rect, res.clone(), Some(state.apps))?,
-                    Settings::new(
+                    LazyTab::new(tab_rect, res.clone(), state.recents),
+                    LazyTab::new(tab_rect, res.clone(), state.games),
+                    LazyTab::new(tab_rect, res.clone(), state.apps),
+                    LazyTab::new(
                         tab_rect,
                         res.clone(),
                         if state.selected == 3 {
                             // Only load settings if it was the last selected tab
                             state.settings
                         } else {
-                            Default::default()
+                            None
                         },
-                    )?,
+                    ),
                 );
-                return Self::new(rect, res, views, state.selected, battery);
+                let onboarding = state
+                    .onboarding
+                    .map(|state| Onboarding::new(rect, res.clone(), Some(state)))
+                    .transpose()?;
+                return Self::new(rect, res, views, state.selected, onboarding, battery);
             }
             warn!("failed to deserialize state file, deleting");
             fs::remove_file(ALLIUM_LAUNCHER_STATE.as_path())?;
         }
 
         let views = (
-            Recents::load_or_new(tab_rect, res.clone(), None)?,
-            Games::load_or_new(tab_rect, res.clone(), None)?,
-            Apps::load_or_new(tab_rect, res.clone(), None)?,
-            Settings::new(tab_rect, res.clone(), Default::default())?,
+            LazyTab::new(tab_rect, res.clone(), None),
+            LazyTab::new(tab_rect, res.clone(), None),
+            LazyTab::new(tab_rect, res.clone(), None),
+            LazyTab::new(tab_rect, res.clone(), None),
         );
         let selected = 1;
-        Self::new(rect, res, views, selected, battery)
+        let onboarding = Onboarding::new(rect, res.clone(), None)?;
+        Self::new(rect, res, views, selected, Some(onboarding), battery)
     }
 
     pub fn save(&self) -> Result<()> {
@@ -196,6 +362,7 @@ where
             games: self.views.1.save(),
             apps: self.views.2.save(),
             settings: self.views.3.save(),
+            onboarding: self.onboarding.as_ref().map(Onboarding::save),
         };
         serde_json::to_writer(file, &state)?;
         Ok(())
@@ -203,29 +370,44 @@ where
 
     fn view(&self) -> &dyn View {
         match self.selected {
-            0 => &self.views.0,
-            1 => &self.views.1,
-            2 => &self.views.2,
-            3 => &self.views.3,
+            0 => self.views.0.get(),
+            1 => self.views.1.get(),
+            2 => self.views.2.get(),
+            3 => self.views.3.get(),
             _ => unreachable!(),
         }
     }
 
     fn view_mut(&mut self) -> &mut dyn View {
         match self.selected {
-            0 => &mut self.views.0,
-            1 => &mut self.views.1,
-            2 => &mut self.views.2,
-            3 => &mut self.views.3,
+            0 => self.views.0.get_mut(),
+            1 => self.views.1.get_mut(),
+            2 => self.views.2.get_mut(),
+            3 => self.views.3.get_mut(),
             _ => unreachable!(),
         }
     }
 
-    fn tab_change(&mut self, selected: usize) {
+    fn load_tab(&mut self, selected: usize) -> Result<()> {
+        match selected {
+            0 => self.views.0.load().map(|_| ()),
+            1 => self.views.1.load().map(|_| ()),
+            2 => self.views.2.load().map(|_| ()),
+            3 => self.views.3.load().map(|_| ()),
+            _ => unreachable!(),
+        }
+    }
+
+    fn tab_change(&mut self, selected: usize) -> Result<()> {
+        self.load_tab(selected)?;
         self.tabs
             .get_mut(self.selected)
             .unwrap()
             .color(StylesheetColor::Tab);
+        if selected != self.selected {
+            self.tab_from = self.selected;
+            self.tab_animation = Animation::new(0.0, 1.0, TAB_ANIMATION_DURATION, Easing::EaseOut);
+        }
         self.selected = selected;
         self.view_mut().set_should_draw();
         self.set_should_draw();
@@ -234,26 +416,32 @@ where
             .unwrap()
             .color(StylesheetColor::TabSelected);
         // self.title.set_text(self.title());
+        Ok(())
     }
 
-    fn next(&mut self) {
+    fn next(&mut self) -> Result<()> {
         let selected = (self.selected + 1).rem_euclid(4);
         self.tab_change(selected)
     }
 
-    fn prev(&mut self) {
+    fn prev(&mut self) -> Result<()> {
         let selected = (self.selected as isize - 1).rem_euclid(4);
         self.tab_change(selected as usize)
     }
 
-    pub fn start_search(&mut self) {
-        self.tab_change(0);
-        self.views.0.start_search();
+    pub fn open_settings(&mut self) -> Result<()> {
+        self.tab_change(3)
+    }
+
+    pub fn start_search(&mut self) -> Result<()> {
+        self.tab_change(0)?;
+        self.views.0.get_mut().start_search();
+        Ok(())
     }
 
     pub fn search(&mut self, query: String) -> Result<()> {
-        self.tab_change(0);
-        self.views.0.search(query)?;
+        self.tab_change(0)?;
+        self.views.0.get_mut().search(query)?;
         Ok(())
     }
 
@@ -267,11 +455,29 @@ impl<B> View for App<B>
 where
     B: Battery,
 {
+    fn update(&mut self, dt: Duration) {
+        if self.tab_animation.update(dt) {
+            self.tabs.set_should_draw();
+        }
+        self.children_mut().iter_mut().for_each(|c| c.update(dt));
+    }
+
     fn draw(
         &mut self,
         display: &mut <DefaultPlatform as Platform>::Display,
         styles: &Stylesheet,
     ) -> Result<bool> {
+        let background = styles
+            .tab_background_image(self.selected)
+            .map(|path| path.to_path_buf());
+        if self.background.as_ref() != Some(&background) {
+            display.pop();
+            draw_background(display, styles, background.as_deref())?;
+            display.save()?;
+            self.background = Some(background);
+            self.set_should_draw();
+        }
+
         if self.dirty {
             display.load(self.bounding_box(styles))?;
             self.dirty = false;
@@ -279,6 +485,19 @@ where
 
         let mut drawn = false;
 
+        if let Some(onboarding) = self.onboarding.as_mut() {
+            if onboarding.should_draw() {
+                drawn |= onboarding.draw(display, styles)?;
+                // Onboarding may clear the whole screen
+                self.status_bar.set_should_draw();
+            }
+            if self.status_bar.should_draw() {
+                display.load(self.status_bar.bounding_box(styles))?;
+                drawn |= self.status_bar.draw(display, styles)?;
+            }
+            return Ok(drawn);
+        }
+
         if self.tabs.should_draw() || self.status_bar.should_draw() {
             display.load(
                 self.tabs
@@ -288,6 +507,31 @@ where
             // drawn |= self.title.should_draw() && self.title.draw(display, styles)?;
             drawn |= self.tabs.should_draw() && self.tabs.draw(display, styles)?;
             drawn |= self.status_bar.should_draw() && self.status_bar.draw(display, styles)?;
+
+            // Selected tab indicator, sliding between tabs
+            let from = self
+                .tabs
+                .get_mut(self.tab_from)
+                .unwrap()
+                .bounding_box(styles);
+            let to = self
+                .tabs
+                .get_mut(self.selected)
+                .unwrap()
+                .bounding_box(styles);
+            let t = self.tab_animation.value();
+            let lerp = |a: i32, b: i32| a + ((b - a) as f32 * t).round() as i32;
+            draw_rounded_rect(
+                display,
+                styles,
+                Rect::new(
+                    lerp(from.x, to.x),
+                    to.y + to.h as i32 - 4,
+                    lerp(from.w as i32, to.w as i32) as u32,
+                    3,
+                ),
+                styles.tab_selected_color,
+            )?;
         }
 
         drawn |= self.view().should_draw() && self.view_mut().draw(display, styles)?;
@@ -296,6 +540,9 @@ where
     }
 
     fn should_draw(&self) -> bool {
+        if let Some(onboarding) = self.onboarding.as_ref() {
+            return self.status_bar.should_draw() || onboarding.should_draw();
+        }
         self.status_bar.should_draw() || self.view().should_draw() || self.tabs.should_draw()
     }
 
@@ -304,6 +551,36 @@ where
         self.status_bar.set_should_draw();
         self.view_mut().set_should_draw();
         self.tabs.set_should_draw();
+        if let Some(onboarding) = self.onboarding.as_mut() {
+            onboarding.set_should_draw();
+        }
+    }
+
+    fn is_animating(&self) -> bool {
+        !self.tab_animation.is_finished() || self.view().is_animating()
+    }
+
+    fn locale_changed(&mut self) -> Result<()> {
+        // Tabs pull their strings when they are built, so build them again from their state
+        self.views.0.unload();
+        self.views.1.unload();
+        self.views.2.unload();
+        self.views.3.unload();
+        self.load_tab(self.selected)?;
+        if let Some(onboarding) = self.onboarding.as_ref() {
+            let state = onboarding.save();
+            self.onboarding = Some(Onboarding::new(self.rect, self.res.clone(), Some(state))?);
+        }
+
+        {
+            let locale = self.res.get::<Locale>();
+            for (i, name) in TAB_NAMES.iter().enumerate() {
+                self.tabs.get_mut(i).unwrap().set_text(locale.t(name));
+            }
+        }
+        self.status_bar.locale_changed()?;
+        self.set_should_draw();
+        Ok(())
     }
 
     async fn handle_key_event(
@@ -312,9 +589,20 @@ where
         commands: Sender<Command>,
         bubble: &mut VecDeque<Command>,
     ) -> Result<bool> {
+        if let Some(onboarding) = self.onboarding.as_mut() {
+            onboarding.handle_key_event(event, commands, bubble).await?;
+            if bubble.iter().any(|c| matches!(c, Command::CloseView)) {
+                bubble.retain(|c| !matches!(c, Command::CloseView));
+                self.onboarding = None;
+                self.save()?;
+                self.set_should_draw();
+            }
+            return Ok(true);
+        }
+
         if self
             .view_mut()
-            .handle_key_event(event, commands, bubble)
+            .handle_key_event(event, commands.clone(), bubble)
             .await?
         {
             return Ok(true);
@@ -323,12 +611,12 @@ where
         match event {
             KeyEvent::Pressed(Key::Left) => {
                 trace!("switch state prev");
-                self.prev();
+                self.prev()?;
                 Ok(true)
             }
             KeyEvent::Pressed(Key::Right) => {
                 trace!("switch state next");
-                self.next();
+                self.next()?;
                 Ok(true)
             }
             _ => Ok(false),
@@ -336,15 +624,21 @@ where
     }
 
     fn children(&self) -> Vec<&dyn View> {
+        if let Some(onboarding) = self.onboarding.as_ref() {
+            return vec![&self.status_bar, onboarding];
+        }
         vec![&self.status_bar, self.view(), &self.tabs]
     }
 
     fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        if let Some(onboarding) = self.onboarding.as_mut() {
+            return vec![&mut self.status_bar, onboarding];
+        }
         let view: &mut dyn View = match self.selected {
-            0 => &mut self.views.0,
-            1 => &mut self.views.1,
-            2 => &mut self.views.2,
-            3 => &mut self.views.3,
+            0 => self.views.0.get_mut(),
+            1 => self.views.1.get_mut(),
+            2 => self.views.2.get_mut(),
+            3 => self.views.3.get_mut(),
             _ => unreachable!(),
         };
         vec![&mut self.status_bar, view, &mut self.tabs]
@@ -1,26 +1,32 @@
 use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use anyhow::Result;
 use async_trait::async_trait;
-use common::command::Command;
-use common::constants::SELECTION_MARGIN;
-use common::database::Database;
+use common::command::{Command, Value};
+use common::database::{Database, RomStatus};
 use common::display::Display;
+use common::display::highlight::draw_rounded_rect;
 use common::geom::{Alignment, Point, Rect};
 use common::locale::Locale;
+use common::performance::CpuProfile;
 use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
 use common::resources::Resources;
+use common::storage;
 use common::stylesheet::{Stylesheet, StylesheetColor};
-use common::view::{ButtonHint, ButtonIcon, Image, ImageMode, Row, ScrollList, View};
-use embedded_graphics::Drawable;
-use embedded_graphics::prelude::{Dimensions, OriginDimensions, Size};
-use embedded_graphics::primitives::{CornerRadii, Primitive, PrimitiveStyle, RoundedRectangle};
-use log::{debug, trace};
+use common::view::{
+    Animation, ButtonHint, ButtonIcon, Easing, Image, ImageMode, Keyboard, Row, ScrollList, View,
+};
+use embedded_graphics::prelude::{Dimensions, OriginDimensions};
+use log::{debug, trace, warn};
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::Sender;
 
 use crate::consoles::ConsoleMapper;
-use crate::entry::{Entry, Sort};
+use crate::entry::directory::DirectoryKind;
+use crate::entry::{Entry, Sort, archive};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EntryListState<S> {
@@ -35,6 +41,9 @@ pub struct CoreSelection {
     cores: Vec<String>,
 }
 
+/// How long the menu takes to open or close.
+const MENU_ANIMATION_DURATION: Duration = Duration::from_millis(120);
+
 #[derive(Debug)]
 pub struct EntryList<S>
 where
@@ -43,12 +52,22 @@ where
     rect: Rect,
     res: Resources,
     entries: Vec<Entry>,
+    /// Verification results of the entries, by index.
+    rom_statuses: Vec<RomStatus>,
     sort: S,
     list: ScrollList,
     image: Image,
     menu: Option<ScrollList>,
     menu_entries: Vec<MenuEntry>,
+    /// Scale of the menu as it opens or closes.
+    menu_animation: Animation,
+    menu_closing: bool,
+    /// Whether A was pressed on this list. The entry is selected when A is released, as holding
+    /// it on a game opens the menu instead.
+    is_a_pressed: bool,
     core: Option<CoreSelection>,
+    /// Keyboard for renaming the selected game.
+    keyboard: Option<Keyboard>,
     button_hints: Row<ButtonHint<String>>,
     pub child: Option<Box<EntryList<S>>>,
 }
@@ -75,7 +94,7 @@ where
             ),
             Vec::new(),
             Alignment::Left,
-            res.get::<Stylesheet>().ui_font.size + SELECTION_MARGIN,
+            res.get::<Stylesheet>().row_height(),
         );
 
         let mut image = Image::empty(
@@ -126,12 +145,17 @@ where
             rect,
             res,
             entries: vec![],
+            rom_statuses: vec![],
             sort,
             list,
             image,
             menu: None,
             menu_entries: vec![],
+            menu_animation: Animation::finished(1.0),
+            menu_closing: false,
+            is_a_pressed: false,
             core: None,
+            keyboard: None,
             button_hints,
             child: None,
         };
@@ -208,16 +232,37 @@ where
         self.entries = self
             .sort
             .entries(&self.res.get(), &self.res.get(), &self.res.get())?;
-        self.list.set_items(
-            self.entries
+        let statuses = {
+            let paths: Vec<&Path> = self
+                .entries
                 .iter()
-                .map(|e| match e {
-                    Entry::Game(game) => {
-                        format!("{}{}", if game.favorite { "♥ " } else { "" }, e.name())
-                    }
-                    _ => e.name().to_string(),
+                .filter_map(|e| match e {
+                    Entry::Game(game) => Some(game.path.as_path()),
+                    _ => None,
                 })
+                .collect();
+            self.res.get::<Database>().select_rom_statuses(&paths)?
+        };
+        let mut statuses = statuses.into_iter();
+        self.rom_statuses = self
+            .entries
+            .iter()
+            .map(|e| match e {
+                Entry::Game(_) => statuses.next().unwrap_or_default(),
+                _ => RomStatus::Unknown,
+            })
+            .collect();
+        let details = self
+            .sort
+            .details(&self.entries, &self.res.get())
+            .unwrap_or_default();
+        self.list.set_items_with_details(
+            self.entries
+                .iter()
+                .zip(&self.rom_statuses)
+                .map(|(e, status)| entry_text(e, *status))
                 .collect(),
+            details,
             self.sort.preserve_selection(),
         );
 
@@ -225,19 +270,18 @@ where
     }
 
     fn open_menu(&mut self) -> Result<()> {
-        let Rect { x, y, w, h } = self.rect;
-        let styles = self.res.get::<Stylesheet>();
-        let locale = self.res.get::<Locale>();
-
         let entry = self.entries.get(self.list.selected()).unwrap();
         let entries = match entry {
             Entry::Game(game) => {
                 let mut entries = vec![
                     MenuEntry::Favorite(game.favorite),
                     MenuEntry::Launch(None),
+                    MenuEntry::CpuProfile(self.res.get::<Database>().get_cpu_profile(&game.path)?),
                     MenuEntry::Reset,
                     MenuEntry::RemoveFromRecents,
                     MenuEntry::RepopulateDatabase,
+                    MenuEntry::VerifyRoms,
+                    MenuEntry::SurpriseMe(false),
                 ];
 
                 let cores = self
@@ -269,11 +313,104 @@ where
                     MenuEntry::Reset,
                     MenuEntry::RemoveFromRecents,
                     MenuEntry::RepopulateDatabase,
+                    MenuEntry::VerifyRoms,
+                    MenuEntry::SurpriseMe(false),
                 ]
             }
         };
 
-        let height = entries.len() as u32 * (styles.ui_font.size + SELECTION_MARGIN);
+        self.show_menu(entries);
+
+        Ok(())
+    }
+
+    /// Whether a menu or the rename keyboard is open, which takes every key.
+    pub fn is_menu_open(&self) -> bool {
+        match &self.child {
+            Some(child) => child.is_menu_open(),
+            None => self.menu.is_some() || self.keyboard.is_some(),
+        }
+    }
+
+    /// Opens the file actions for the selected game, if it's a file that can be managed.
+    fn open_file_menu(&mut self) {
+        if let Some(Entry::Game(game)) = self.entries.get(self.list.selected())
+            && game.path.is_file()
+        {
+            self.show_menu(vec![
+                MenuEntry::Rename,
+                MenuEntry::MoveToFolder,
+                MenuEntry::Delete,
+            ]);
+        }
+    }
+
+    /// Opens the folders the selected game can be moved to: the parent folder, and the
+    /// subfolders of the current one.
+    fn open_folder_menu(&mut self) {
+        let Some(Entry::Game(game)) = self.entries.get(self.list.selected()) else {
+            return;
+        };
+        let Some(dir) = game.path.parent() else {
+            return;
+        };
+
+        let mut folders: Vec<_> = {
+            let console_mapper = self.res.get::<ConsoleMapper>();
+            fs::read_dir(dir)
+                .into_iter()
+                .flatten()
+                .flatten()
+                .filter_map(|entry| match Entry::new(entry.path(), &console_mapper) {
+                    Ok(Some(Entry::Directory(dir))) if dir.kind == DirectoryKind::Folder => {
+                        Some(MenuEntry::Folder(dir.path, Some(dir.name)))
+                    }
+                    _ => None,
+                })
+                .collect()
+        };
+        folders.sort_unstable_by_key(|entry| match entry {
+            MenuEntry::Folder(_, Some(name)) => name.to_lowercase(),
+            _ => String::new(),
+        });
+
+        let mut entries = Vec::with_capacity(folders.len() + 2);
+        if !storage::is_games_dir(dir)
+            && let Some(parent) = dir.parent()
+        {
+            entries.push(MenuEntry::Folder(parent.to_path_buf(), None));
+        }
+        entries.extend(folders);
+        entries.push(MenuEntry::Cancel);
+        self.show_menu(entries);
+    }
+
+    /// Asks for confirmation before a file action.
+    fn confirm(&mut self, action: FileAction) {
+        self.show_menu(vec![MenuEntry::Confirm(action), MenuEntry::Cancel]);
+    }
+
+    /// Applies a confirmed file action to the selected game.
+    fn apply(&mut self, action: &FileAction) -> Result<()> {
+        let Some(Entry::Game(game)) = self.entries.get_mut(self.list.selected()) else {
+            return Ok(());
+        };
+        let database = self.res.get::<Database>();
+        match action {
+            FileAction::Rename(name) => game.rename(&database, name)?,
+            FileAction::Move(dir) => game.move_to(&database, dir)?,
+            FileAction::Delete => game.delete(&database)?,
+        }
+        drop(database);
+        self.load_entries()
+    }
+
+    fn show_menu(&mut self, entries: Vec<MenuEntry>) {
+        let Rect { x, y, w, h } = self.rect;
+        let styles = self.res.get::<Stylesheet>();
+        let locale = self.res.get::<Locale>();
+
+        let height = entries.len() as u32 * styles.row_height();
 
         let mut menu = ScrollList::new(
             Rect::new(
@@ -284,13 +421,13 @@ where
             ),
             entries.iter().map(|e| e.text(&locale)).collect(),
             Alignment::Left,
-            styles.ui_font.size + SELECTION_MARGIN,
+            styles.row_height(),
         );
         menu.set_background_color(Some(StylesheetColor::BackgroundHighlightBlend));
         self.menu = Some(menu);
         self.menu_entries = entries;
-
-        Ok(())
+        self.menu_animation = Animation::new(0.0, 1.0, MENU_ANIMATION_DURATION, Easing::EaseOut);
+        self.menu_closing = false;
     }
 }
 
@@ -299,6 +436,15 @@ impl<S> View for EntryList<S>
 where
     S: Sort,
 {
+    fn update(&mut self, dt: Duration) {
+        if let Some(menu) = self.menu.as_mut()
+            && self.menu_animation.update(dt)
+        {
+            menu.set_should_draw();
+        }
+        self.children_mut().iter_mut().for_each(|c| c.update(dt));
+    }
+
     fn draw(
         &mut self,
         display: &mut <DefaultPlatform as Platform>::Display,
@@ -308,29 +454,44 @@ where
             return child.draw(display, styles);
         }
 
+        if let Some(keyboard) = self.keyboard.as_mut() {
+            return Ok(keyboard.should_draw() && keyboard.draw(display, styles)?);
+        }
+
         let mut drawn = false;
 
+        let mut closing_menu = None;
         if let Some(menu) = &mut self.menu {
-            if menu.should_draw() {
-                let mut rect = menu.bounding_box(styles);
-                rect.y -= 12;
-                rect.h += 24;
-                rect.x -= 24;
-                rect.w += 48;
-                rect = rect.intersection(&display.bounding_box().into());
-                RoundedRectangle::new(
-                    rect.into(),
-                    CornerRadii::new(Size::new_equal((styles.ui_font.size + 8) / 2)),
-                )
-                .into_styled(PrimitiveStyle::with_fill(
-                    StylesheetColor::BackgroundHighlightBlend.to_color(styles),
-                ))
-                .draw(display)?;
-                menu.set_should_draw();
-                menu.draw(display, styles)?;
-                drawn = true;
+            let mut rect = menu.bounding_box(styles);
+            rect.y -= 12;
+            rect.h += 24;
+            rect.x -= 24;
+            rect.w += 48;
+            rect = rect.intersection(&display.bounding_box().into());
+
+            if !self.menu_closing {
+                if menu.should_draw() {
+                    draw_rounded_rect(
+                        display,
+                        styles,
+                        scale_rect(rect, self.menu_animation.value()),
+                        StylesheetColor::BackgroundHighlightBlend.to_color(styles),
+                    )?;
+                    if self.menu_animation.is_finished() {
+                        menu.set_should_draw();
+                        menu.draw(display, styles)?;
+                    }
+                    drawn = true;
+                }
+                return Ok(drawn);
             }
-            return Ok(drawn);
+
+            // Restore what was under the menu, and draw the shrinking menu over it
+            display.load(rect)?;
+            self.list.set_should_draw();
+            self.image.set_should_draw();
+            self.button_hints.set_should_draw();
+            closing_menu = Some(rect);
         }
 
         drawn |= self.list.should_draw() && self.list.draw(display, styles)?;
@@ -340,6 +501,13 @@ where
                 if let Some(path) = entry.image() {
                     trace!("Loading image from {:?}", path);
                     self.image.set_path(Some(path.to_path_buf()));
+                } else if let Entry::Directory(dir) = entry
+                    && let Some(icon) = dir
+                        .path
+                        .file_name()
+                        .and_then(|name| styles.folder_icon(&name.to_string_lossy()))
+                {
+                    self.image.set_path(Some(icon));
                 } else {
                     trace!("No image for entry {:?}", entry);
                     self.image.set_path(None);
@@ -365,6 +533,21 @@ where
             }
         }
 
+        if let Some(rect) = closing_menu {
+            if self.menu_animation.is_finished() {
+                self.menu = None;
+                self.menu_closing = false;
+            } else {
+                draw_rounded_rect(
+                    display,
+                    styles,
+                    scale_rect(rect, self.menu_animation.value()),
+                    StylesheetColor::BackgroundHighlightBlend.to_color(styles),
+                )?;
+            }
+            drawn = true;
+        }
+
         Ok(drawn)
     }
 
@@ -372,9 +555,11 @@ where
         if let Some(child) = self.child.as_ref() {
             child.should_draw()
         } else {
-            self.menu
-                .as_ref()
-                .is_some_and(common::view::View::should_draw)
+            self.keyboard.as_ref().is_some_and(|k| k.should_draw())
+                || self
+                    .menu
+                    .as_ref()
+                    .is_some_and(common::view::View::should_draw)
                 || self.list.should_draw()
                 || self.image.should_draw()
                 || self.button_hints.should_draw()
@@ -388,12 +573,23 @@ where
             if let Some(menu) = self.menu.as_mut() {
                 menu.set_should_draw();
             }
+            if let Some(keyboard) = self.keyboard.as_mut() {
+                keyboard.set_should_draw();
+            }
             self.list.set_should_draw();
             self.image.set_should_draw();
             self.button_hints.set_should_draw();
         }
     }
 
+    fn is_animating(&self) -> bool {
+        if let Some(child) = self.child.as_ref() {
+            child.is_animating()
+        } else {
+            self.menu.is_some() && !self.menu_animation.is_finished()
+        }
+    }
+
     async fn handle_key_event(
         &mut self,
         event: KeyEvent,
@@ -415,41 +611,100 @@ where
                 }
                 false => Ok(false),
             }
+        } else if let Some(keyboard) = self.keyboard.as_mut() {
+            if keyboard
+                .handle_key_event(event, commands.clone(), bubble)
+                .await?
+            {
+                let mut name = None;
+                bubble.retain_mut(|c| match c {
+                    Command::ValueChanged(_, Value::String(val)) => {
+                        name = Some(val.clone());
+                        false
+                    }
+                    Command::CloseView => {
+                        self.keyboard = None;
+                        false
+                    }
+                    _ => true,
+                });
+                if let Some(name) = name.filter(|name| !name.trim().is_empty()) {
+                    self.keyboard = None;
+                    self.confirm(FileAction::Rename(name.trim().to_owned()));
+                }
+                commands.send(Command::Redraw).await?;
+            }
+            Ok(true)
         } else if let Some(menu) = self.menu.as_mut() {
+            if self.menu_closing {
+                return Ok(true);
+            }
             match event {
                 KeyEvent::Pressed(Key::Left) => {
-                    if let Some(core) = self.core.as_mut() {
-                        let selected = &mut self.menu_entries[menu.selected()];
-                        if let MenuEntry::Launch(launch_core) = selected {
-                            core.core = core.core.saturating_sub(1);
-                            let console_mapper = self.res.get::<ConsoleMapper>();
-                            *launch_core =
-                                Some(console_mapper.get_core_name(&core.cores[core.core]));
-                            menu.set_item(menu.selected(), selected.text(&self.res.get()));
+                    let selected = &mut self.menu_entries[menu.selected()];
+                    match selected {
+                        MenuEntry::Launch(launch_core) => {
+                            if let Some(core) = self.core.as_mut() {
+                                core.core = core.core.saturating_sub(1);
+                                let console_mapper = self.res.get::<ConsoleMapper>();
+                                *launch_core =
+                                    Some(console_mapper.get_core_name(&core.cores[core.core]));
+                            }
                         }
+                        MenuEntry::CpuProfile(profile) => {
+                            *profile = profile.prev();
+                            if let Some(Entry::Game(game)) = self.entries.get(self.list.selected())
+                            {
+                                self.res
+                                    .get::<Database>()
+                                    .set_cpu_profile(&game.name, &game.path, *profile)?;
+                            }
+                        }
+                        MenuEntry::SurpriseMe(favorites_only) => {
+                            *favorites_only = !*favorites_only;
+                        }
+                        _ => {}
                     }
+                    menu.set_item(menu.selected(), selected.text(&self.res.get()));
                     Ok(true) // trap tab focus
                 }
                 KeyEvent::Pressed(Key::Right) => {
-                    if let Some(core) = self.core.as_mut() {
-                        let selected = &mut self.menu_entries[menu.selected()];
-                        if let MenuEntry::Launch(launch_core) = selected {
-                            core.core = (core.core + 1).min(core.cores.len() - 1);
-                            let console_mapper = self.res.get::<ConsoleMapper>();
-                            *launch_core =
-                                Some(console_mapper.get_core_name(&core.cores[core.core]));
-                            menu.set_item(menu.selected(), selected.text(&self.res.get()));
+                    let selected = &mut self.menu_entries[menu.selected()];
+                    match selected {
+                        MenuEntry::Launch(launch_core) => {
+                            if let Some(core) = self.core.as_mut() {
+                                core.core = (core.core + 1).min(core.cores.len() - 1);
+                                let console_mapper = self.res.get::<ConsoleMapper>();
+                                *launch_core =
+                                    Some(console_mapper.get_core_name(&core.cores[core.core]));
+                            }
                         }
+                        MenuEntry::CpuProfile(profile) => {
+                            *profile = profile.next();
+                            if let Some(Entry::Game(game)) = self.entries.get(self.list.selected())
+                            {
+                                self.res
+                                    .get::<Database>()
+                                    .set_cpu_profile(&game.name, &game.path, *profile)?;
+                            }
+                        }
+                        MenuEntry::SurpriseMe(favorites_only) => {
+                            *favorites_only = !*favorites_only;
+                        }
+                        _ => {}
                     }
+                    menu.set_item(menu.selected(), selected.text(&self.res.get()));
                     Ok(true) // trap tab focus
                 }
                 KeyEvent::Pressed(Key::Select | Key::B) => {
-                    self.menu = None;
-                    commands.send(Command::Redraw).await?;
+                    self.menu_animation =
+                        Animation::new(1.0, 0.0, MENU_ANIMATION_DURATION, Easing::EaseOut);
+                    self.menu_closing = true;
+                    menu.set_should_draw();
                     Ok(true)
                 }
                 KeyEvent::Pressed(Key::A) => {
-                    let selected = &self.menu_entries[menu.selected()];
+                    let selected = self.menu_entries[menu.selected()].clone();
                     match selected {
                         MenuEntry::Favorite(_) => {
                             let entry = self.entries.get_mut(self.list.selected()).unwrap();
@@ -458,13 +713,10 @@ where
                                 self.res
                                     .get::<Database>()
                                     .set_favorite(&game.path, game.favorite)?;
+                                let selected = self.list.selected();
                                 self.list.set_item(
-                                    self.list.selected(),
-                                    format!(
-                                        "{}{}",
-                                        if game.favorite { "♥ " } else { "" },
-                                        entry.name()
-                                    ),
+                                    selected,
+                                    entry_text(entry, self.rom_statuses[selected]),
                                 );
                             }
                             commands.send(Command::Redraw).await?;
@@ -480,6 +732,9 @@ where
                             self.core = None;
                             self.select_entry(commands).await?;
                         }
+                        MenuEntry::CpuProfile(_) => {
+                            commands.send(Command::Redraw).await?;
+                        }
                         MenuEntry::Reset => {
                             let entry = self.entries.get_mut(self.list.selected()).unwrap();
                             match entry {
@@ -500,7 +755,7 @@ where
                         MenuEntry::RemoveFromRecents => {
                             if let Some(Entry::Game(game)) = self.entries.get(self.list.selected())
                             {
-                                if game.path.exists() {
+                                if archive::exists(&game.path) {
                                     self.res.get::<Database>().reset_game(&game.path)?;
                                 } else {
                                     self.res.get::<Database>().delete_game(&game.path)?;
@@ -528,6 +783,55 @@ where
                             }
                             commands.send(Command::Redraw).await?;
                         }
+                        MenuEntry::VerifyRoms => {
+                            commands.send(Command::Redraw).await?;
+                            #[cfg(not(feature = "miyoo"))]
+                            {
+                                let message = self.res.get::<Locale>().t("verifying-roms");
+                                commands.send(Command::Toast(message, None)).await?;
+                            }
+                            commands.send(Command::VerifyRoms).await?;
+                            commands.send(Command::Redraw).await?;
+                        }
+                        MenuEntry::SurpriseMe(favorites_only) => {
+                            commands
+                                .send(Command::LaunchRandomGame { favorites_only })
+                                .await?;
+                        }
+                        MenuEntry::Rename => {
+                            if let Some(Entry::Game(game)) = self.entries.get(self.list.selected())
+                            {
+                                let name = game
+                                    .path
+                                    .file_stem()
+                                    .unwrap_or_default()
+                                    .to_string_lossy()
+                                    .into_owned();
+                                self.keyboard = Some(Keyboard::new(self.res.clone(), name, false));
+                            }
+                        }
+                        MenuEntry::MoveToFolder => {
+                            self.open_folder_menu();
+                            return Ok(true);
+                        }
+                        MenuEntry::Delete => {
+                            self.confirm(FileAction::Delete);
+                            return Ok(true);
+                        }
+                        MenuEntry::Folder(dir, _) => {
+                            self.confirm(FileAction::Move(dir));
+                            return Ok(true);
+                        }
+                        MenuEntry::Confirm(action) => {
+                            if let Err(e) = self.apply(&action) {
+                                warn!("failed to {:?}: {}", action, e);
+                                commands.send(Command::Toast(e.to_string(), None)).await?;
+                            }
+                            commands.send(Command::Redraw).await?;
+                        }
+                        MenuEntry::Cancel => {
+                            commands.send(Command::Redraw).await?;
+                        }
                     }
                     self.menu = None;
                     Ok(true)
@@ -582,6 +886,22 @@ where
                     Ok(true)
                 }
                 KeyEvent::Pressed(Key::A) => {
+                    self.is_a_pressed = true;
+                    Ok(true)
+                }
+                KeyEvent::LongPressed(Key::A)
+                    if self.is_a_pressed
+                        && matches!(
+                            self.entries.get(self.list.selected()),
+                            Some(Entry::Game(_))
+                        ) =>
+                {
+                    self.is_a_pressed = false;
+                    self.open_menu()?;
+                    Ok(true)
+                }
+                KeyEvent::Released(Key::A) if self.is_a_pressed => {
+                    self.is_a_pressed = false;
                     self.select_entry(commands).await?;
                     Ok(true)
                 }
@@ -593,6 +913,10 @@ where
                     self.open_menu()?;
                     Ok(true)
                 }
+                KeyEvent::LongPressed(Key::X) => {
+                    self.open_file_menu();
+                    Ok(true)
+                }
                 _ => {
                     let res = self.list.handle_key_event(event, commands, bubble).await?;
                     debug!(
@@ -634,9 +958,28 @@ where
 enum MenuEntry {
     Favorite(bool),
     Launch(Option<String>),
+    CpuProfile(CpuProfile),
     Reset,
     RemoveFromRecents,
     RepopulateDatabase,
+    VerifyRoms,
+    /// Launches a random game, from favorites only if set.
+    SurpriseMe(bool),
+    Rename,
+    MoveToFolder,
+    Delete,
+    /// A folder to move the game into, with its name, or the parent folder if there's no name.
+    Folder(PathBuf, Option<String>),
+    Confirm(FileAction),
+    Cancel,
+}
+
+/// A change to a game file, applied once confirmed.
+#[derive(Debug, Clone)]
+enum FileAction {
+    Rename(String),
+    Move(PathBuf),
+    Delete,
 }
 
 impl MenuEntry {
@@ -659,9 +1002,75 @@ impl MenuEntry {
                     locale.t("menu-launch")
                 }
             }
+            MenuEntry::CpuProfile(profile) => {
+                let profile = profile.text(locale);
+                locale.ta(
+                    "menu-cpu-profile",
+                    &[("profile".into(), profile.into())].into_iter().collect(),
+                )
+            }
             MenuEntry::Reset => locale.t("menu-reset"),
             MenuEntry::RemoveFromRecents => locale.t("menu-remove-from-recents"),
             MenuEntry::RepopulateDatabase => locale.t("menu-repopulate-database"),
+            MenuEntry::VerifyRoms => locale.t("menu-verify-roms"),
+            MenuEntry::SurpriseMe(favorites_only) => {
+                if *favorites_only {
+                    locale.t("menu-surprise-me-favorites")
+                } else {
+                    locale.t("menu-surprise-me")
+                }
+            }
+            MenuEntry::Rename => locale.t("menu-rename"),
+            MenuEntry::MoveToFolder => locale.t("menu-move-to-folder"),
+            MenuEntry::Delete => locale.t("menu-delete"),
+            MenuEntry::Folder(_, Some(name)) => name.clone(),
+            MenuEntry::Folder(_, None) => locale.t("menu-parent-folder"),
+            MenuEntry::Confirm(FileAction::Rename(name)) => locale.ta(
+                "menu-confirm-rename",
+                &[("name".into(), name.clone().into())].into_iter().collect(),
+            ),
+            MenuEntry::Confirm(FileAction::Move(dir)) => locale.ta(
+                "menu-confirm-move",
+                &[(
+                    "folder".into(),
+                    dir.file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .into_owned()
+                        .into(),
+                )]
+                .into_iter()
+                .collect(),
+            ),
+            MenuEntry::Confirm(FileAction::Delete) => locale.t("menu-confirm-delete"),
+            MenuEntry::Cancel => locale.t("menu-cancel"),
         }
     }
 }
+
+/// Text of an entry in the list, with its favorite and verification badges.
+fn entry_text(entry: &Entry, status: RomStatus) -> String {
+    match entry {
+        Entry::Game(game) => {
+            let mut text = format!("{}{}", if game.favorite { "♥ " } else { "" }, entry.name());
+            if let Some(badge) = status.badge() {
+                text.push(' ');
+                text.push_str(badge);
+            }
+            text
+        }
+        _ => entry.name().to_string(),
+    }
+}
+
+/// Scales a rect around its center.
+fn scale_rect(rect: Rect, scale: f32) -> Rect {
+    let w = (rect.w as f32 * scale).round() as u32;
+    let h = (rect.h as f32 * scale).round() as u32;
+    Rect::new(
+        rect.x + (rect.w - w) as i32 / 2,
+        rect.y + (rect.h - h) as i32 / 2,
+        w,
+        h,
+    )
+}
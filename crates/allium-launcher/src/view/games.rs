@@ -1,15 +1,19 @@
 use std::cmp::Reverse;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use std::ffi::OsStr;
+use std::path::Path;
 
 use anyhow::Result;
 use async_trait::async_trait;
 use common::command::Command;
+use common::console_settings::ConsoleSettings;
 use common::constants::ALLIUM_GAMES_DIR;
-use common::database::Database;
+use common::database::{Database, Game as DbGame};
 use common::geom::{Alignment, Point, Rect};
 use common::locale::Locale;
 use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
 use common::resources::Resources;
+use common::storage;
 use common::stylesheet::Stylesheet;
 use common::view::{ButtonHint, ButtonIcon, Row, View};
 use itertools::Itertools;
@@ -18,7 +22,8 @@ use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::Sender;
 
 use crate::consoles::ConsoleMapper;
-use crate::entry::directory::Directory;
+use crate::entry::directory::{Directory, DirectoryKind};
+use crate::entry::game::Game;
 use crate::entry::{Entry, Sort};
 use crate::view::entry_list::{EntryList, EntryListState};
 
@@ -29,6 +34,9 @@ pub struct Games {
     rect: Rect,
     list: EntryList<GamesSort>,
     button_hints: Row<ButtonHint<String>>,
+    /// Whether X was pressed on this view. Search starts when X is released, as holding it
+    /// opens the file actions instead.
+    is_x_pressed: bool,
 }
 
 impl Games {
@@ -60,6 +68,7 @@ impl Games {
             rect,
             list,
             button_hints,
+            is_x_pressed: false,
         })
     }
 
@@ -119,9 +128,18 @@ impl View for Games {
         bubble: &mut VecDeque<Command>,
     ) -> Result<bool> {
         match event {
-            KeyEvent::Pressed(Key::X) => {
+            KeyEvent::Pressed(Key::X) if !self.list.is_menu_open() => {
+                self.is_x_pressed = true;
+                Ok(true)
+            }
+            KeyEvent::LongPressed(Key::X) if self.is_x_pressed => {
+                self.is_x_pressed = false;
+                self.list.handle_key_event(event, commands, bubble).await
+            }
+            KeyEvent::Released(Key::X) if self.is_x_pressed => {
+                self.is_x_pressed = false;
                 commands.send(Command::StartSearch).await?;
-                return Ok(true);
+                Ok(true)
             }
             _ => self.list.handle_key_event(event, commands, bubble).await,
         }
@@ -151,6 +169,8 @@ pub enum GamesSort {
     MostPlayed(Directory),
     Rating(Directory),
     ReleaseDate(Directory),
+    Genre(Directory),
+    RecentlyAdded(Directory),
     Random(Directory),
 }
 
@@ -162,6 +182,8 @@ impl GamesSort {
             GamesSort::MostPlayed(d) => d,
             GamesSort::Rating(d) => d,
             GamesSort::ReleaseDate(d) => d,
+            GamesSort::Genre(d) => d,
+            GamesSort::RecentlyAdded(d) => d,
             GamesSort::Random(d) => d,
         }
     }
@@ -175,6 +197,8 @@ impl Sort for GamesSort {
             GamesSort::MostPlayed(_) => locale.t("sort-most-played"),
             GamesSort::Rating(_) => locale.t("sort-rating"),
             GamesSort::ReleaseDate(_) => locale.t("sort-release-date"),
+            GamesSort::Genre(_) => locale.t("sort-genre"),
+            GamesSort::RecentlyAdded(_) => locale.t("sort-recently-added"),
             GamesSort::Random(_) => locale.t("sort-random"),
         }
     }
@@ -185,7 +209,9 @@ impl Sort for GamesSort {
             GamesSort::LastPlayed(d) => GamesSort::MostPlayed(d.clone()),
             GamesSort::MostPlayed(d) => GamesSort::Rating(d.clone()),
             GamesSort::Rating(d) => GamesSort::ReleaseDate(d.clone()),
-            GamesSort::ReleaseDate(d) => GamesSort::Random(d.clone()),
+            GamesSort::ReleaseDate(d) => GamesSort::Genre(d.clone()),
+            GamesSort::Genre(d) => GamesSort::RecentlyAdded(d.clone()),
+            GamesSort::RecentlyAdded(d) => GamesSort::Random(d.clone()),
             GamesSort::Random(d) => GamesSort::Alphabetical(d.clone()),
         }
     }
@@ -197,6 +223,8 @@ impl Sort for GamesSort {
             GamesSort::MostPlayed(_) => GamesSort::MostPlayed(directory),
             GamesSort::Rating(_) => GamesSort::Rating(directory),
             GamesSort::ReleaseDate(_) => GamesSort::ReleaseDate(directory),
+            GamesSort::Genre(_) => GamesSort::Genre(directory),
+            GamesSort::RecentlyAdded(_) => GamesSort::RecentlyAdded(directory),
             GamesSort::Random(_) => GamesSort::Random(directory),
         }
     }
@@ -207,34 +235,27 @@ impl Sort for GamesSort {
         console_mapper: &ConsoleMapper,
         locale: &Locale,
     ) -> Result<Vec<Entry>> {
-        let mut entries = self.directory().entries(database, console_mapper, locale)?;
+        let directory = self.directory();
+        let mut entries = directory.entries(database, console_mapper, locale)?;
+
+        let console_settings = if directory.path == *ALLIUM_GAMES_DIR {
+            let settings = ConsoleSettings::load()?;
+            hide_consoles(&mut entries, &settings, database);
+            Some(settings)
+        } else {
+            None
+        };
 
         match self {
             GamesSort::Alphabetical(_) => {
-                let mut games = Vec::with_capacity(entries.len());
-                let mut i = 0;
-                while i < entries.len() {
-                    if matches!(entries[i], Entry::Game(_)) {
-                        match entries.remove(i) {
-                            Entry::Game(game) => games.push(game),
-                            _ => unreachable!(),
-                        }
-                    } else {
-                        i += 1;
-                    }
-                }
-
-                let db_games = database
-                    .select_games(&games.iter().map(|g| g.path.as_path()).collect::<Vec<_>>())?;
-
-                let games = games.into_iter().zip(db_games).collect::<Vec<_>>();
+                let games = take_games(&mut entries);
+                let games = with_db_games(database, games)?;
                 let (favorites, non_favorites): (Vec<_>, Vec<_>) =
                     games.into_iter().partition(|(_, db_game)| {
                         db_game.as_ref().map(|g| g.favorite).unwrap_or_default()
                     });
                 let favorites = favorites.into_iter().map(|(g, _)| g).sorted_unstable();
                 let non_favorites = non_favorites.into_iter().map(|(g, _)| g).sorted_unstable();
-                entries.retain(|e| matches!(e, Entry::Directory(_) | Entry::App(_)));
                 entries.sort_unstable();
                 entries.extend(favorites.map(Entry::Game));
                 entries.extend(non_favorites.map(Entry::Game));
@@ -242,102 +263,74 @@ impl Sort for GamesSort {
             GamesSort::LastPlayed(_) => {
                 // With this current implementation, apps will appear before games.
                 // TOOD: think about whether this is OK?
-                let mut games = Vec::with_capacity(entries.len());
-                let mut i = 0;
-                while i < entries.len() {
-                    if matches!(entries[i], Entry::Game(_)) {
-                        match entries.remove(i) {
-                            Entry::Game(game) => games.push(game),
-                            _ => unreachable!(),
-                        }
-                    } else {
-                        i += 1;
-                    }
-                }
-
-                let db_games = database
-                    .select_games(&games.iter().map(|g| g.path.as_path()).collect::<Vec<_>>())?;
-
-                let mut games = games.into_iter().zip(db_games).collect::<Vec<_>>();
+                let games = take_games(&mut entries);
+                let mut games = with_db_games(database, games)?;
                 games.sort_unstable_by_key(|(_, db_game)| {
                     db_game.as_ref().map(|g| Reverse(g.last_played))
                 });
-                entries.retain(|e| matches!(e, Entry::Directory(_) | Entry::App(_)));
                 entries.sort_unstable();
                 entries.extend(games.into_iter().map(|(game, _)| Entry::Game(game)));
             }
             GamesSort::MostPlayed(_) => {
-                let mut games = Vec::with_capacity(entries.len());
-                let mut i = 0;
-                while i < entries.len() {
-                    if matches!(entries[i], Entry::Game(_)) {
-                        match entries.remove(i) {
-                            Entry::Game(game) => games.push(game),
-                            _ => unreachable!(),
-                        }
-                    } else {
-                        i += 1;
-                    }
-                }
-
-                let db_games = database
-                    .select_games(&games.iter().map(|g| g.path.as_path()).collect::<Vec<_>>())?;
-
-                let mut games = games.into_iter().zip(db_games).collect::<Vec<_>>();
+                let games = take_games(&mut entries);
+                let mut games = with_db_games(database, games)?;
                 games.sort_unstable_by_key(|(_, db_game)| {
                     db_game.as_ref().map(|g| Reverse(g.play_time))
                 });
-                entries.retain(|e| matches!(e, Entry::Directory(_) | Entry::App(_)));
                 entries.sort_unstable();
                 entries.extend(games.into_iter().map(|(game, _)| Entry::Game(game)));
             }
             GamesSort::Rating(_) => {
-                let mut games = Vec::with_capacity(entries.len());
-                let mut i = 0;
-                while i < entries.len() {
-                    if matches!(entries[i], Entry::Game(_)) {
-                        match entries.remove(i) {
-                            Entry::Game(game) => games.push(game),
-                            _ => unreachable!(),
-                        }
-                    } else {
-                        i += 1;
-                    }
-                }
-
-                let db_games = database
-                    .select_games(&games.iter().map(|g| g.path.as_path()).collect::<Vec<_>>())?;
-
-                let mut games = games.into_iter().zip(db_games).collect::<Vec<_>>();
+                let games = take_games(&mut entries);
+                let mut games = with_db_games(database, games)?;
                 games.sort_unstable_by_key(|(_, db_game)| {
                     db_game.as_ref().map(|g| Reverse(g.rating))
                 });
-                entries.retain(|e| matches!(e, Entry::Directory(_) | Entry::App(_)));
                 entries.sort_unstable();
-                entries.extend(games.into_iter().map(|(game, _)| Entry::Game(game)));
+                entries.extend(
+                    games
+                        .into_iter()
+                        .map(|(game, db_game)| Entry::Game(with_metadata(game, db_game))),
+                );
             }
             GamesSort::ReleaseDate(_) => {
-                let mut games = Vec::with_capacity(entries.len());
-                let mut i = 0;
-                while i < entries.len() {
-                    if matches!(entries[i], Entry::Game(_)) {
-                        match entries.remove(i) {
-                            Entry::Game(game) => games.push(game),
-                            _ => unreachable!(),
-                        }
-                    } else {
-                        i += 1;
-                    }
-                }
-
-                let db_games = database
-                    .select_games(&games.iter().map(|g| g.path.as_path()).collect::<Vec<_>>())?;
-
-                let mut games = games.into_iter().zip(db_games).collect::<Vec<_>>();
+                let games = take_games(&mut entries);
+                let mut games = with_db_games(database, games)?;
                 games.sort_unstable_by_key(|(_, db_game)| {
                     db_game.as_ref().map(|g| Reverse(g.release_date))
                 });
-                entries.retain(|e| matches!(e, Entry::Directory(_) | Entry::App(_)));
+                entries.sort_unstable();
+                entries.extend(
+                    games
+                        .into_iter()
+                        .map(|(game, db_game)| Entry::Game(with_metadata(game, db_game))),
+                );
+            }
+            GamesSort::Genre(_) => {
+                let games = take_games(&mut entries);
+                let mut games = with_db_games(database, games)?
+                    .into_iter()
+                    .map(|(game, db_game)| with_metadata(game, db_game))
+                    .collect::<Vec<_>>();
+                // Games without a genre go last
+                games.sort_unstable_by(|a, b| {
+                    (a.genres.is_empty(), a.genres.first())
+                        .cmp(&(b.genres.is_empty(), b.genres.first()))
+                        .then_with(|| a.cmp(b))
+                });
+                entries.sort_unstable();
+                entries.extend(games.into_iter().map(Entry::Game));
+            }
+            GamesSort::RecentlyAdded(_) => {
+                let games = take_games(&mut entries);
+                let times = database.select_discovered_times(
+                    &games.iter().map(|g| g.path.as_path()).collect::<Vec<_>>(),
+                )?;
+
+                let mut games = games.into_iter().zip(times).collect::<Vec<_>>();
+                games.sort_unstable_by(|(a, a_time), (b, b_time)| {
+                    b_time.cmp(a_time).then_with(|| a.cmp(b))
+                });
                 entries.sort_unstable();
                 entries.extend(games.into_iter().map(|(game, _)| Entry::Game(game)));
             }
@@ -346,10 +339,264 @@ impl Sort for GamesSort {
             }
         }
 
+        if let Some(settings) = console_settings
+            && directory.kind == DirectoryKind::Folder
+        {
+            if !matches!(self, GamesSort::Random(_)) {
+                arrange_consoles(&mut entries, &settings);
+            }
+            entries.splice(
+                0..0,
+                [
+                    (locale.t("games-all-games"), DirectoryKind::AllGames),
+                    (locale.t("games-by-genre"), DirectoryKind::Genres),
+                ]
+                .map(|(name, kind)| {
+                    Entry::Directory(Directory::virtual_dir(directory.path.clone(), name, kind))
+                }),
+            );
+        }
+
         Ok(entries)
     }
 
     fn preserve_selection(&self) -> bool {
         false
     }
+
+    fn details(&self, entries: &[Entry], console_mapper: &ConsoleMapper) -> Option<Vec<String>> {
+        let sort_key = |game: &Game| match self {
+            GamesSort::Rating(_) => game.rating.map(|rating| format!("{}/10", rating)),
+            GamesSort::ReleaseDate(_) => game.release_date.map(|date| date.to_string()),
+            GamesSort::Genre(_) => game.genres.first().cloned(),
+            _ => None,
+        };
+        if matches!(
+            self,
+            GamesSort::Rating(_) | GamesSort::ReleaseDate(_) | GamesSort::Genre(_)
+        ) {
+            return Some(
+                entries
+                    .iter()
+                    .map(|entry| match entry {
+                        Entry::Game(game) => sort_key(game).unwrap_or_default(),
+                        Entry::Directory(_) | Entry::App(_) => String::new(),
+                    })
+                    .collect(),
+            );
+        }
+
+        if !self.directory().kind.is_flattened() {
+            return None;
+        }
+
+        let settings = ConsoleSettings::load().unwrap_or_default();
+        let mut names = HashMap::new();
+        Some(
+            entries
+                .iter()
+                .map(|entry| {
+                    let Some(dir) =
+                        storage::strip_games_dir(entry.path()).and_then(|p| p.iter().next())
+                    else {
+                        return String::new();
+                    };
+                    names
+                        .entry(dir.to_owned())
+                        .or_insert_with(|| {
+                            let name = dir.to_str().unwrap_or_default();
+                            settings
+                                .name(name)
+                                .map(str::to_owned)
+                                .or_else(|| {
+                                    console_mapper
+                                        .get_console_by_dir(Path::new(dir))
+                                        .map(|c| c.name.clone())
+                                })
+                                .unwrap_or_else(|| name.to_owned())
+                        })
+                        .clone()
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Fills in the metadata used for sorting from the database, if the game doesn't have it.
+/// Removes the games from `entries`, leaving the directories and apps.
+fn take_games(entries: &mut Vec<Entry>) -> Vec<Game> {
+    let mut games = Vec::with_capacity(entries.len());
+    let mut i = 0;
+    while i < entries.len() {
+        if matches!(entries[i], Entry::Game(_)) {
+            match entries.remove(i) {
+                Entry::Game(game) => games.push(game),
+                _ => unreachable!(),
+            }
+        } else {
+            i += 1;
+        }
+    }
+    games
+}
+
+/// Pairs each game with its row in the database, if it has one.
+fn with_db_games(database: &Database, games: Vec<Game>) -> Result<Vec<(Game, Option<DbGame>)>> {
+    let db_games =
+        database.select_games(&games.iter().map(|g| g.path.as_path()).collect::<Vec<_>>())?;
+    Ok(games.into_iter().zip(db_games).collect())
+}
+
+fn with_metadata(mut game: Game, db_game: Option<DbGame>) -> Game {
+    if let Some(db_game) = db_game {
+        game.rating = game.rating.or(db_game.rating);
+        game.release_date = game.release_date.or(db_game.release_date);
+        if game.genres.is_empty() {
+            game.genres = db_game.genres;
+        }
+    }
+    game
+}
+
+/// Removes console directories that are hidden, or empty if empty consoles are hidden, and games
+/// inside hidden console directories.
+fn hide_consoles(entries: &mut Vec<Entry>, settings: &ConsoleSettings, database: &Database) {
+    entries.retain(|entry| {
+        let console = storage::strip_games_dir(entry.path())
+            .and_then(|p| p.iter().next())
+            .and_then(OsStr::to_str)
+            .unwrap_or_default();
+        if settings.is_hidden(console) {
+            return false;
+        }
+        match entry {
+            Entry::Directory(dir) => {
+                dir.kind != DirectoryKind::Folder || !settings.hide_empty || dir.has_games(database)
+            }
+            Entry::App(_) | Entry::Game(_) => true,
+        }
+    });
+}
+
+/// Applies custom names and ordering to the console directories at the start of `entries`.
+pub fn arrange_consoles(entries: &mut [Entry], settings: &ConsoleSettings) {
+    let dir_name = |entry: &Entry| {
+        entry
+            .path()
+            .file_name()
+            .and_then(OsStr::to_str)
+            .unwrap_or_default()
+            .to_owned()
+    };
+
+    let len = entries
+        .iter()
+        .take_while(|e| matches!(e, Entry::Directory(_)))
+        .count();
+    let consoles = &mut entries[..len];
+
+    for entry in consoles.iter_mut() {
+        if let Some(name) = settings.name(&dir_name(entry))
+            && let Entry::Directory(dir) = entry
+        {
+            dir.name = name.to_owned();
+        }
+    }
+
+    consoles.sort_by(|a, b| {
+        settings
+            .position(&dir_name(a))
+            .cmp(&settings.position(&dir_name(b)))
+            .then_with(|| a.cmp(b))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use common::database::NewGame;
+    use common::geom;
+    use common::locale::LocaleSettings;
+    use serial_test::serial;
+    use type_map::TypeMap;
+
+    use super::*;
+
+    #[tokio::test]
+    #[serial(env_ALLIUM_BASE_DIR)]
+    async fn test_x_long_press() {
+        // SAFETY: tests that depend on this env var are run serially
+        unsafe {
+            env::set_var("ALLIUM_BASE_DIR", "../../static/.allium");
+        }
+
+        let dir = env::temp_dir().join("allium-test-games-x");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("Tetris.gb");
+        fs::write(&path, "rom").unwrap();
+
+        let database = Database::in_memory().unwrap();
+        database
+            .update_games(&[NewGame {
+                name: "Tetris".to_owned(),
+                path,
+                image: None,
+                core: None,
+                rating: None,
+                release_date: None,
+                developer: None,
+                publisher: None,
+                genres: Vec::new(),
+                favorite: false,
+            }])
+            .unwrap();
+
+        let mut map = TypeMap::new();
+        map.insert(database);
+        map.insert(ConsoleMapper::new());
+        map.insert(Stylesheet::default());
+        map.insert(Locale::new(&LocaleSettings::new()));
+        map.insert(geom::Size::new(640, 480));
+        let res = Resources::new(map);
+
+        let rect = Rect::new(0, 0, 640, 480);
+        let list = EntryList::new(
+            rect,
+            res.clone(),
+            GamesSort::Alphabetical(Directory::new(dir.clone())),
+        )
+        .unwrap();
+        let mut games = Games::new(rect, res, list).unwrap();
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        let mut bubble = VecDeque::new();
+
+        // Tapping X starts search when it's released
+        for event in [KeyEvent::Pressed(Key::X), KeyEvent::Released(Key::X)] {
+            games
+                .handle_key_event(event, tx.clone(), &mut bubble)
+                .await
+                .unwrap();
+        }
+        assert!(matches!(rx.try_recv(), Ok(Command::StartSearch)));
+        assert!(!games.list.is_menu_open());
+
+        // Holding X opens the file actions instead
+        for event in [
+            KeyEvent::Pressed(Key::X),
+            KeyEvent::LongPressed(Key::X),
+            KeyEvent::Released(Key::X),
+        ] {
+            games
+                .handle_key_event(event, tx.clone(), &mut bubble)
+                .await
+                .unwrap();
+        }
+        assert!(games.list.is_menu_open());
+        assert!(rx.try_recv().is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -2,13 +2,12 @@ mod app;
 mod apps;
 mod entry_list;
 mod games;
+mod onboarding;
 mod recents;
 mod settings;
-mod toast;
 
 pub use app::App;
 pub use apps::Apps;
 pub use games::Games;
 pub use recents::Recents;
 pub use settings::Settings;
-pub use toast::Toast;
@@ -0,0 +1,409 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use common::command::Command;
+use common::display::Display;
+use common::geom::{Alignment, Point, Rect};
+use common::locale::{Locale, LocaleSettings};
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::stylesheet::{Stylesheet, StylesheetColor};
+use common::view::{
+    ButtonHint, ButtonIcon, Label, Row, Select, SettingsList, TextBox, Toggle, View,
+};
+use common::wifi::WiFiSettings;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Sender;
+
+use crate::view::settings::{TIMEZONE_NAMES, current_timezone, set_timezone};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum Step {
+    Language,
+    Timezone,
+    Theme,
+    Wifi,
+    Scan,
+}
+
+impl Step {
+    fn title(self, locale: &Locale) -> String {
+        match self {
+            Step::Language => locale.t("onboarding-language"),
+            Step::Timezone => locale.t("onboarding-timezone"),
+            Step::Theme => locale.t("onboarding-theme"),
+            Step::Wifi => locale.t("onboarding-wifi"),
+            Step::Scan => locale.t("onboarding-scan"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnboardingState {
+    step: Step,
+    scan: bool,
+}
+
+/// Guided setup shown on first boot. Each step is a settings list, and settings that reload the
+/// launcher resume at the same step.
+#[derive(Debug)]
+pub struct Onboarding {
+    rect: Rect,
+    res: Resources,
+    steps: Vec<Step>,
+    step: usize,
+    langs: Vec<String>,
+    wifi: WiFiSettings,
+    scan: bool,
+    title: Label<String>,
+    list: SettingsList,
+    button_hints: Row<ButtonHint<String>>,
+    dirty: bool,
+}
+
+impl Onboarding {
+    pub fn new(rect: Rect, res: Resources, state: Option<OnboardingState>) -> Result<Self> {
+        let Rect { x, y, w, h } = rect;
+
+        let mut steps = vec![Step::Language, Step::Timezone, Step::Theme];
+        if DefaultPlatform::has_wifi() {
+            steps.push(Step::Wifi);
+        }
+        steps.push(Step::Scan);
+
+        let state = state.unwrap_or(OnboardingState {
+            step: Step::Language,
+            scan: true,
+        });
+        let step = steps.iter().position(|s| *s == state.step).unwrap_or(0);
+
+        let styles = res.get::<Stylesheet>();
+        let locale = res.get::<Locale>();
+
+        let mut title = Label::new(
+            Point::new(x + 12, y + 8),
+            String::new(),
+            Alignment::Left,
+            None,
+        );
+        title.font_size(styles.tab_font_size);
+        title.color(StylesheetColor::TabSelected);
+
+        let title_height = (styles.ui_font.size as f32 * styles.tab_font_size) as u32 + 8;
+        let list = SettingsList::new(
+            Rect::new(
+                x + 12,
+                y + title_height as i32 + 8,
+                w - 24,
+                h - title_height - 8 - ButtonIcon::diameter(&styles) - 8,
+            ),
+            Vec::new(),
+            Vec::new(),
+            styles.row_height(),
+        );
+
+        let button_hints = Row::new(
+            Point::new(
+                x + w as i32 - 12,
+                y + h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            vec![
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::A,
+                    locale.t("button-edit"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::B,
+                    locale.t("button-back"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::Start,
+                    locale.t("button-next"),
+                    Alignment::Right,
+                ),
+            ],
+            Alignment::Right,
+            12,
+        );
+
+        let langs = locale.languages();
+
+        drop(styles);
+        drop(locale);
+
+        let mut this = Self {
+            rect,
+            res,
+            steps,
+            step,
+            langs,
+            wifi: WiFiSettings::load()?,
+            scan: state.scan,
+            title,
+            list,
+            button_hints,
+            dirty: true,
+        };
+        this.set_step(step)?;
+        Ok(this)
+    }
+
+    pub fn save(&self) -> OnboardingState {
+        OnboardingState {
+            step: self.steps[self.step],
+            scan: self.scan,
+        }
+    }
+
+    fn set_step(&mut self, step: usize) -> Result<()> {
+        self.step = step;
+
+        let locale = self.res.get::<Locale>();
+
+        let mut map = HashMap::new();
+        map.insert("step".into(), (step + 1).into());
+        map.insert("total".into(), self.steps.len().into());
+        map.insert("name".into(), self.steps[step].title(&locale).into());
+        self.title.set_text(locale.ta("onboarding-title", &map));
+
+        let (left, right): (Vec<String>, Vec<Box<dyn View>>) = match self.steps[step] {
+            Step::Language => {
+                let lang = LocaleSettings::load()?.lang;
+                let lang = self.langs.iter().position(|l| *l == lang).unwrap_or(0);
+                (
+                    vec![locale.t("settings-language-language")],
+                    vec![Box::new(Select::new(
+                        Point::zero(),
+                        lang,
+                        self.langs
+                            .iter()
+                            .map(|l| {
+                                let name = locale.t(&format!("lang-{}", l));
+                                if name.is_empty() { l.clone() } else { name }
+                            })
+                            .collect(),
+                        Alignment::Right,
+                    ))],
+                )
+            }
+            Step::Timezone => (
+                vec![locale.t("settings-clock-timezone")],
+                vec![Box::new(Select::new(
+                    Point::zero(),
+                    current_timezone(),
+                    TIMEZONE_NAMES.iter().map(|s| s.to_string()).collect(),
+                    Alignment::Right,
+                ))],
+            ),
+            Step::Theme => (
+                vec![locale.t("settings-theme-dark-mode")],
+                vec![Box::new(Toggle::new(
+                    Point::zero(),
+                    self.res.get::<Stylesheet>().background_color.is_dark(),
+                    Alignment::Right,
+                ))],
+            ),
+            Step::Wifi => (
+                vec![
+                    locale.t("settings-wifi-wifi-enabled"),
+                    locale.t("settings-wifi-wifi-network"),
+                    locale.t("settings-wifi-wifi-password"),
+                ],
+                vec![
+                    Box::new(Toggle::new(Point::zero(), self.wifi.wifi, Alignment::Right)),
+                    Box::new(TextBox::new(
+                        Point::zero(),
+                        self.res.clone(),
+                        self.wifi.ssid.clone(),
+                        Alignment::Right,
+                        false,
+                    )),
+                    Box::new(TextBox::new(
+                        Point::zero(),
+                        self.res.clone(),
+                        self.wifi.password.clone(),
+                        Alignment::Right,
+                        true,
+                    )),
+                ],
+            ),
+            Step::Scan => (
+                vec![locale.t("onboarding-scan-games")],
+                vec![Box::new(Toggle::new(
+                    Point::zero(),
+                    self.scan,
+                    Alignment::Right,
+                ))],
+            ),
+        };
+        self.list.set_items(left, right);
+        self.list.select(0);
+
+        let next = if step + 1 == self.steps.len() {
+            locale.t("button-finish")
+        } else {
+            locale.t("button-next")
+        };
+        self.button_hints.get_mut(2).unwrap().set_text(next);
+
+        self.dirty = true;
+        Ok(())
+    }
+
+    async fn finish(
+        &mut self,
+        commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<()> {
+        bubble.push_back(Command::CloseView);
+        if self.scan {
+            let toast = self.res.get::<Locale>().t("populating-database");
+            commands.send(Command::Toast(toast, None)).await?;
+            commands.send(Command::PopulateDb).await?;
+            commands
+                .send(Command::Toast(String::new(), Some(Duration::ZERO)))
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl View for Onboarding {
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        if self.dirty {
+            display.load(self.rect)?;
+            self.title.set_should_draw();
+            self.list.set_should_draw();
+            self.button_hints.set_should_draw();
+            self.dirty = false;
+        }
+
+        let mut drawn = false;
+
+        if self.title.should_draw() {
+            display.load(self.title.bounding_box(styles))?;
+            drawn |= self.title.draw(display, styles)?;
+        }
+
+        drawn |= self.list.should_draw() && self.list.draw(display, styles)?;
+
+        if self.button_hints.should_draw() {
+            display.load(Rect::new(
+                self.rect.x,
+                self.rect.y + self.rect.h as i32 - ButtonIcon::diameter(styles) as i32 - 8,
+                self.rect.w,
+                ButtonIcon::diameter(styles),
+            ))?;
+            drawn |= self.button_hints.draw(display, styles)?;
+        }
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.dirty
+            || self.title.should_draw()
+            || self.list.should_draw()
+            || self.button_hints.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.dirty = true;
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        if self
+            .list
+            .handle_key_event(event, commands.clone(), bubble)
+            .await?
+        {
+            while let Some(command) = bubble.pop_front() {
+                if let Command::ValueChanged(i, val) = command {
+                    match (self.steps[self.step], i) {
+                        (Step::Language, _) => {
+                            let mut settings = LocaleSettings::load()?;
+                            settings
+                                .lang
+                                .clone_from(&self.langs[val.as_int().unwrap() as usize]);
+                            commands.send(Command::SaveLocaleSettings(settings)).await?;
+                            commands.send(Command::LocaleChanged).await?;
+                        }
+                        (Step::Timezone, _) => {
+                            set_timezone(val.as_int().unwrap() as usize).await?;
+                        }
+                        (Step::Theme, _) => {
+                            let mut styles = self.res.get::<Stylesheet>().clone();
+                            if styles.background_color.is_dark() != val.as_bool().unwrap() {
+                                styles.toggle_dark_mode();
+                                commands
+                                    .send(Command::SaveStylesheet(Box::new(styles)))
+                                    .await?;
+                            }
+                        }
+                        (Step::Wifi, 0) => self.wifi.set_wifi(val.as_bool().unwrap())?,
+                        (Step::Wifi, 1) => {
+                            self.wifi.set_ssid(val.as_string().unwrap().to_string())?
+                        }
+                        (Step::Wifi, _) => self
+                            .wifi
+                            .set_password(val.as_string().unwrap().to_string())?,
+                        (Step::Scan, _) => self.scan = val.as_bool().unwrap(),
+                    }
+                }
+            }
+            return Ok(true);
+        }
+
+        match event {
+            KeyEvent::Pressed(Key::Start) => {
+                if self.step + 1 < self.steps.len() {
+                    self.set_step(self.step + 1)?;
+                } else {
+                    self.finish(commands, bubble).await?;
+                }
+            }
+            KeyEvent::Pressed(Key::B) if self.step > 0 => {
+                self.set_step(self.step - 1)?;
+            }
+            _ => {}
+        }
+        // Trap focus until setup is finished
+        Ok(true)
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.title, &self.list, &self.button_hints]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![&mut self.title, &mut self.list, &mut self.button_hints]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::VecDeque;
+use std::time::Duration;
 
 use anyhow::Result;
 use async_trait::async_trait;
@@ -36,8 +37,8 @@ impl Default for RecentsState {
 
 #[derive(Debug)]
 pub enum Recents {
-    Carousel(RecentsCarousel),
-    List(RecentsList),
+    Carousel(Box<RecentsCarousel>),
+    List(Box<RecentsList>),
 }
 
 impl Recents {
@@ -49,17 +50,19 @@ impl Recents {
                 Some(RecentsState::Carousel(s)) => Some(s),
                 _ => None,
             };
-            Ok(Self::Carousel(RecentsCarousel::load_or_new(
+            Ok(Self::Carousel(Box::new(RecentsCarousel::load_or_new(
                 rect,
                 res,
                 carousel_state,
-            )?))
+            )?)))
         } else {
             let list_state = match state {
                 Some(RecentsState::List(s)) => Some(s),
                 _ => None,
             };
-            Ok(Self::List(RecentsList::load_or_new(rect, res, list_state)?))
+            Ok(Self::List(Box::new(RecentsList::load_or_new(
+                rect, res, list_state,
+            )?)))
         }
     }
 
@@ -87,6 +90,13 @@ impl Recents {
 
 #[async_trait(?Send)]
 impl View for Recents {
+    fn update(&mut self, dt: Duration) {
+        match self {
+            Self::Carousel(c) => c.update(dt),
+            Self::List(l) => l.update(dt),
+        }
+    }
+
     fn draw(
         &mut self,
         display: &mut <DefaultPlatform as Platform>::Display,
@@ -112,6 +122,13 @@ impl View for Recents {
         }
     }
 
+    fn is_animating(&self) -> bool {
+        match self {
+            Self::Carousel(c) => c.is_animating(),
+            Self::List(l) => l.is_animating(),
+        }
+    }
+
     async fn handle_key_event(
         &mut self,
         event: KeyEvent,
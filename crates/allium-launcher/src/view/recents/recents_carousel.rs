@@ -12,13 +12,18 @@ use common::locale::Locale;
 use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
 use common::resources::Resources;
 use common::stylesheet::Stylesheet;
-use common::view::{ButtonHint, ButtonIcon, Image, ImageMode, Keyboard, Label, Row, View};
+use common::view::{
+    Animation, ButtonHint, ButtonIcon, Easing, Image, ImageMode, Keyboard, Label, Row, View,
+};
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::Sender;
 
 use crate::consoles::ConsoleMapper;
 use crate::entry::game::Game;
 
+/// How long the screenshot takes to slide in when changing games.
+const SLIDE_DURATION: Duration = Duration::from_millis(150);
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecentsCarouselState {
     pub selected: usize,
@@ -37,6 +42,8 @@ pub struct RecentsCarousel {
     games: Vec<Game>,
     selected: usize,
     screenshot: Image,
+    /// Horizontal offset of the screenshot as it slides in.
+    slide: Animation,
     game_name: Label<String>,
     button_hints: Row<ButtonHint<String>>,
     keyboard: Option<Keyboard>,
@@ -101,6 +108,7 @@ impl RecentsCarousel {
             games,
             selected,
             screenshot,
+            slide: Animation::finished(0.0),
             game_name,
             button_hints,
             keyboard: None,
@@ -178,7 +186,7 @@ impl RecentsCarousel {
     }
 
     pub fn start_search(&mut self) {
-        self.keyboard = Some(Keyboard::new(self.res.clone(), String::new(), false));
+        self.keyboard = Some(Keyboard::search(self.res.clone(), String::new()));
     }
 
     pub fn search(&mut self, _query: String) -> Result<()> {
@@ -208,6 +216,7 @@ impl RecentsCarousel {
         if self.selected > 0 {
             self.selected -= 1;
             self.update_current_game()?;
+            self.slide_in(-1);
         }
         Ok(())
     }
@@ -216,10 +225,19 @@ impl RecentsCarousel {
         if self.selected < self.games.len().saturating_sub(1) {
             self.selected += 1;
             self.update_current_game()?;
+            self.slide_in(1);
         }
         Ok(())
     }
 
+    /// Slides the screenshot in from the left (`direction` < 0) or right (`direction` > 0).
+    fn slide_in(&mut self, direction: i32) {
+        let distance = (direction * self.rect.w as i32 / 4) as f32;
+        self.slide = Animation::new(distance, 0.0, SLIDE_DURATION, Easing::EaseOut);
+        self.screenshot
+            .set_offset_x(self.slide.value().round() as i32);
+    }
+
     async fn launch_game(&mut self, commands: Sender<Command>) -> Result<()> {
         if let Some(game) = self.games.get_mut(self.selected) {
             let command =
@@ -236,6 +254,14 @@ impl RecentsCarousel {
 
 #[async_trait(?Send)]
 impl View for RecentsCarousel {
+    fn update(&mut self, dt: Duration) {
+        if self.slide.update(dt) {
+            self.screenshot
+                .set_offset_x(self.slide.value().round() as i32);
+        }
+        self.game_name.update(dt);
+    }
+
     fn draw(
         &mut self,
         display: &mut <DefaultPlatform as Platform>::Display,
@@ -293,6 +319,10 @@ impl View for RecentsCarousel {
             || self.keyboard.as_ref().is_some_and(|k| k.should_draw())
     }
 
+    fn is_animating(&self) -> bool {
+        !self.slide.is_finished()
+    }
+
     fn set_should_draw(&mut self) {
         self.dirty = true;
         self.screenshot.set_should_draw();
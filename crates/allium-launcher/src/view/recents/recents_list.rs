@@ -31,6 +31,9 @@ pub struct RecentsList {
     list: EntryList<RecentsSort>,
     button_hints: Row<ButtonHint<String>>,
     keyboard: Option<Keyboard>,
+    /// Whether X was pressed on this view. Search starts when X is released, as holding it
+    /// opens the file actions instead.
+    is_x_pressed: bool,
 }
 
 impl RecentsList {
@@ -66,6 +69,7 @@ impl RecentsList {
             list,
             button_hints,
             keyboard: None,
+            is_x_pressed: false,
         })
     }
 
@@ -88,7 +92,7 @@ impl RecentsList {
     }
 
     pub fn start_search(&mut self) {
-        self.keyboard = Some(Keyboard::new(self.res.clone(), String::new(), false));
+        self.keyboard = Some(Keyboard::search(self.res.clone(), String::new()));
     }
 
     pub async fn try_search(&mut self, commands: Sender<Command>, query: String) -> Result<()> {
@@ -183,15 +187,24 @@ impl View for RecentsList {
         }
 
         match event {
-            KeyEvent::Pressed(Key::X) => {
-                if self.keyboard.is_none() {
-                    self.start_search();
-                } else {
-                    self.keyboard = None;
-                    self.list.sort(RecentsSort::LastPlayed)?;
-                    commands.send(Command::Redraw).await?;
-                }
-                return Ok(true);
+            KeyEvent::Pressed(Key::X) if self.keyboard.is_some() => {
+                self.keyboard = None;
+                self.list.sort(RecentsSort::LastPlayed)?;
+                commands.send(Command::Redraw).await?;
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::X) if !self.list.is_menu_open() => {
+                self.is_x_pressed = true;
+                Ok(true)
+            }
+            KeyEvent::LongPressed(Key::X) if self.is_x_pressed => {
+                self.is_x_pressed = false;
+                self.list.handle_key_event(event, commands, bubble).await
+            }
+            KeyEvent::Released(Key::X) if self.is_x_pressed => {
+                self.is_x_pressed = false;
+                self.start_search();
+                Ok(true)
             }
             _ => self.list.handle_key_event(event, commands, bubble).await,
         }
@@ -3,19 +3,21 @@ use std::collections::VecDeque;
 use anyhow::Result;
 use async_trait::async_trait;
 use common::command::Command;
-use common::constants::{ALLIUM_VERSION, SELECTION_MARGIN};
+use common::constants::ALLIUM_VERSION;
 use common::geom::{Alignment, Point, Rect};
 use common::locale::Locale;
+use common::logging::{LogLevel, LogSettings};
 use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
 use common::resources::Resources;
 use common::stylesheet::Stylesheet;
-use common::view::{ButtonHint, ButtonIcon, Label, Row, SettingsList, View};
+use common::view::{ButtonHint, ButtonIcon, Label, Row, Select, SettingsList, View};
 use tokio::sync::mpsc::Sender;
 
 use crate::view::settings::{ChildState, SettingsChild};
 
 pub struct About {
     rect: Rect,
+    log_settings: LogSettings,
     list: SettingsList,
     button_hints: Row<ButtonHint<String>>,
 }
@@ -25,6 +27,7 @@ impl About {
         let Rect { x, y, w, h } = rect;
 
         let firmware = DefaultPlatform::firmware();
+        let log_settings = LogSettings::load().unwrap_or_default();
 
         let mut sys = sysinfo::System::new();
         sys.refresh_memory();
@@ -46,6 +49,7 @@ impl About {
                 locale.t("settings-about-operating-system-version"),
                 locale.t("settings-about-kernel-version"),
                 locale.t("settings-about-memory-used"),
+                locale.t("settings-about-log-level"),
             ],
             vec![
                 Box::new(Label::new(
@@ -87,8 +91,19 @@ impl About {
                     Alignment::Right,
                     None,
                 )),
+                Box::new(Select::new(
+                    Point::zero(),
+                    log_settings.level as usize,
+                    vec![
+                        locale.t("log-level-off"),
+                        locale.t("log-level-error"),
+                        locale.t("log-level-warn"),
+                        locale.t("log-level-info"),
+                    ],
+                    Alignment::Right,
+                )),
             ],
-            styles.ui_font.size + SELECTION_MARGIN,
+            styles.row_height(),
         );
         if let Some(state) = state {
             list.select(state.selected);
@@ -112,6 +127,7 @@ impl About {
 
         Self {
             rect,
+            log_settings,
             list,
             button_hints,
         }
@@ -153,12 +169,34 @@ impl View for About {
         commands: Sender<Command>,
         bubble: &mut VecDeque<Command>,
     ) -> Result<bool> {
+        if self
+            .list
+            .handle_key_event(event, commands.clone(), bubble)
+            .await?
+        {
+            while let Some(command) = bubble.pop_front() {
+                if let Command::ValueChanged(6, val) = command {
+                    self.log_settings.level =
+                        LogLevel::from_repr(val.as_int().unwrap() as usize).unwrap_or_default();
+                    self.log_settings.save()?;
+                    self.log_settings.apply();
+                    // Let alliumd change its log level too
+                    #[cfg(unix)]
+                    std::process::Command::new("pkill")
+                        .arg("-HUP")
+                        .arg("alliumd")
+                        .spawn()?;
+                }
+            }
+            return Ok(true);
+        }
+
         match event {
             KeyEvent::Pressed(Key::B) => {
                 bubble.push_back(Command::CloseView);
                 Ok(true)
             }
-            _ => self.list.handle_key_event(event, commands, bubble).await,
+            _ => Ok(false),
         }
     }
 
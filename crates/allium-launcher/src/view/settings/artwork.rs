@@ -0,0 +1,337 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use common::boxart::download_boxart;
+use common::command::Command;
+use common::database::Database;
+use common::display::Display as DisplayTrait;
+use common::geom::{Alignment, Point, Rect};
+use common::led;
+use common::locale::Locale;
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::stylesheet::Stylesheet;
+use common::view::{ButtonHint, ButtonIcon, Row, ScrollList, View};
+use log::warn;
+use tokio::sync::mpsc::{self, Sender, UnboundedReceiver, error::TryRecvError};
+
+use crate::consoles::ConsoleMapper;
+use crate::entry::game::Game;
+use crate::view::settings::{ChildState, SettingsChild};
+
+/// A game without box art.
+#[derive(Debug, Clone)]
+struct MissingGame {
+    /// Name of the game's console, if known.
+    console: Option<String>,
+    /// libretro thumbnail system name of the game's console, if it has one.
+    system: Option<String>,
+    /// No-Intro name of the game, taken from its file name.
+    name: String,
+    /// Where the box art goes, so that it is found next to the game.
+    image: PathBuf,
+}
+
+/// Lists how many games are missing box art per console, and downloads the missing box art.
+pub struct MissingArtwork {
+    rect: Rect,
+    res: Resources,
+    games: Vec<MissingGame>,
+    list: ScrollList,
+    button_hints: Row<ButtonHint<String>>,
+    /// Receives how many games were tried and downloaded so far, while downloading.
+    download_rx: Option<UnboundedReceiver<(usize, usize)>>,
+    /// Games tried, games downloaded, and games to download.
+    progress: (usize, usize, usize),
+    dirty: bool,
+}
+
+impl MissingArtwork {
+    pub fn new(rect: Rect, res: Resources, state: Option<ChildState>) -> Self {
+        let Rect { x, y, w, h } = rect;
+
+        let locale = res.get::<Locale>();
+        let styles = res.get::<Stylesheet>();
+
+        let list = ScrollList::new(
+            Rect::new(
+                x + 12,
+                y + 8,
+                w - 24,
+                h - 8 - ButtonIcon::diameter(&styles) - 8,
+            ),
+            Vec::new(),
+            Alignment::Left,
+            styles.row_height(),
+        );
+
+        let button_hints = Row::new(
+            Point::new(
+                rect.x + rect.w as i32 - 12,
+                rect.y + rect.h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            vec![
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::A,
+                    locale.t("button-select"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::B,
+                    locale.t("button-back"),
+                    Alignment::Right,
+                ),
+            ],
+            Alignment::Right,
+            12,
+        );
+
+        drop(locale);
+        drop(styles);
+
+        let mut this = Self {
+            rect,
+            res,
+            games: Vec::new(),
+            list,
+            button_hints,
+            download_rx: None,
+            progress: (0, 0, 0),
+            dirty: true,
+        };
+        this.scan();
+        if let Some(state) = state {
+            this.list.select(state.selected);
+        }
+        this
+    }
+
+    /// Finds the games whose box art can't be found, and lists them by console.
+    fn scan(&mut self) {
+        self.games = find_missing(&self.res).unwrap_or_else(|e| {
+            warn!("failed to find games missing box art: {}", e);
+            Vec::new()
+        });
+
+        let locale = self.res.get::<Locale>();
+        let mut counts: BTreeMap<Option<&str>, usize> = BTreeMap::new();
+        for game in &self.games {
+            *counts.entry(game.console.as_deref()).or_default() += 1;
+        }
+
+        let mut items = Vec::with_capacity(counts.len() + 1);
+        if self.games.is_empty() {
+            items.push(locale.t("settings-artwork-none"));
+        } else {
+            let mut map = HashMap::new();
+            map.insert("count".into(), self.games.len().into());
+            items.push(locale.ta("settings-artwork-download", &map));
+        }
+        for (console, count) in counts {
+            let mut map = HashMap::new();
+            map.insert(
+                "console".into(),
+                console
+                    .map(str::to_owned)
+                    .unwrap_or_else(|| locale.t("settings-artwork-other"))
+                    .into(),
+            );
+            map.insert("count".into(), count.into());
+            items.push(locale.ta("settings-artwork-console", &map));
+        }
+        self.list.set_items(items, true);
+        self.dirty = true;
+    }
+
+    /// Downloads the missing box art in the background, showing progress in the first row.
+    async fn download(&mut self, commands: Sender<Command>) -> Result<()> {
+        if self.download_rx.is_some() {
+            return Ok(());
+        }
+        let games: Vec<_> = self
+            .games
+            .iter()
+            .filter(|game| game.system.is_some())
+            .cloned()
+            .collect();
+
+        let total = games.len();
+        if total == 0 {
+            let text = self.res.get::<Locale>().t("settings-artwork-unsupported");
+            commands
+                .send(Command::Toast(text, Some(Duration::from_secs(5))))
+                .await?;
+            return Ok(());
+        }
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut downloaded = 0;
+            for (i, game) in games.iter().enumerate() {
+                let system = game.system.as_deref().unwrap_or_default();
+                match download_boxart(system, &game.name, &game.image).await {
+                    Ok(()) => downloaded += 1,
+                    Err(e) => warn!("failed to download box art for {}: {}", game.name, e),
+                }
+                if tx.send((i + 1, downloaded)).is_err() {
+                    break;
+                }
+            }
+        });
+        self.download_rx = Some(rx);
+        self.progress = (0, 0, total);
+        self.set_progress("settings-artwork-downloading");
+        Ok(())
+    }
+
+    fn set_progress(&mut self, key: &str) {
+        let (done, downloaded, total) = self.progress;
+        let mut map = HashMap::new();
+        map.insert("done".into(), done.into());
+        map.insert("count".into(), downloaded.into());
+        map.insert("total".into(), total.into());
+        let text = self.res.get::<Locale>().ta(key, &map);
+        self.list.set_item(0, text);
+    }
+}
+
+fn find_missing(res: &Resources) -> Result<Vec<MissingGame>> {
+    let games = res.get::<Database>().select_all_games()?;
+    let console_mapper = res.get::<ConsoleMapper>();
+
+    let mut missing = Vec::new();
+    for game in games {
+        let mut game = Game::from_db(game);
+        if game.image.image().is_some() {
+            continue;
+        }
+        // Games inside archives have virtual paths, so use the first real directory
+        let Some(dir) = game.path.ancestors().skip(1).find(|p| p.is_dir()) else {
+            continue;
+        };
+        let Some(name) = game.path.file_stem() else {
+            continue;
+        };
+        let console = console_mapper.get_console(&game.path);
+        missing.push(MissingGame {
+            console: console.map(|c| c.name.clone()),
+            system: console.and_then(|c| c.thumbnails.clone()),
+            name: name.to_string_lossy().into_owned(),
+            image: dir.join("Imgs").join(name).with_extension("png"),
+        });
+    }
+    Ok(missing)
+}
+
+#[async_trait(?Send)]
+impl View for MissingArtwork {
+    fn update(&mut self, _dt: Duration) {
+        let Some(rx) = self.download_rx.as_mut() else {
+            return;
+        };
+        let mut changed = false;
+        loop {
+            match rx.try_recv() {
+                Ok((done, downloaded)) => {
+                    self.progress.0 = done;
+                    self.progress.1 = downloaded;
+                    changed = true;
+                }
+                Err(TryRecvError::Empty) => {
+                    if changed {
+                        self.set_progress("settings-artwork-downloading");
+                    }
+                    break;
+                }
+                Err(TryRecvError::Disconnected) => {
+                    self.download_rx = None;
+                    self.scan();
+                    self.set_progress("settings-artwork-downloaded");
+                    led::notify_job_finished();
+                    break;
+                }
+            }
+        }
+    }
+
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        let mut drawn = false;
+
+        if self.dirty {
+            display.load(self.rect)?;
+            self.list.set_should_draw();
+            self.button_hints.set_should_draw();
+            self.dirty = false;
+            drawn = true;
+        }
+
+        drawn |= self.list.should_draw() && self.list.draw(display, styles)?;
+        drawn |= self.button_hints.should_draw() && self.button_hints.draw(display, styles)?;
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.dirty || self.list.should_draw() || self.button_hints.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.dirty = true;
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        match event {
+            KeyEvent::Pressed(Key::A) => {
+                if self.list.selected() == 0 && !self.games.is_empty() {
+                    self.download(commands).await?;
+                }
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::B) => {
+                bubble.push_back(Command::CloseView);
+                Ok(true)
+            }
+            _ => self.list.handle_key_event(event, commands, bubble).await,
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.list, &self.button_hints]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![&mut self.list, &mut self.button_hints]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
+
+impl SettingsChild for MissingArtwork {
+    fn save(&self) -> ChildState {
+        ChildState {
+            selected: self.list.selected(),
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use common::command::Command;
+use common::constants::ALLIUM_SETTINGS_BACKUP;
+use common::display::Display as DisplayTrait;
+use common::geom::{Alignment, Point, Rect};
+use common::locale::Locale;
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::settings_backup;
+use common::stylesheet::Stylesheet;
+use common::view::{ButtonHint, ButtonIcon, Row, ScrollList, View};
+use log::{info, warn};
+use tokio::sync::mpsc::Sender;
+use tokio::sync::oneshot;
+
+use crate::view::settings::{ChildState, SettingsChild};
+
+const EXPORT_ROW: usize = 0;
+const RESTORE_ROW: usize = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Task {
+    Export,
+    Restore,
+}
+
+/// Exports all settings and the database to an archive on the SD card, and restores them.
+pub struct Backup {
+    rect: Rect,
+    res: Resources,
+    list: ScrollList,
+    button_hints: Row<ButtonHint<String>>,
+    /// Running export or restore, and the number of files it handled.
+    task: Option<(Task, oneshot::Receiver<Result<usize>>)>,
+    failed: Option<Task>,
+    dirty: bool,
+}
+
+impl Backup {
+    pub fn new(rect: Rect, res: Resources, state: Option<ChildState>) -> Self {
+        let Rect { x, y, w, h } = rect;
+
+        let locale = res.get::<Locale>();
+        let styles = res.get::<Stylesheet>();
+
+        let mut list = ScrollList::new(
+            Rect::new(
+                x + 12,
+                y + 8,
+                w - 24,
+                h - 8 - ButtonIcon::diameter(&styles) - 8,
+            ),
+            Vec::new(),
+            Alignment::Left,
+            styles.row_height(),
+        );
+        if let Some(state) = state {
+            list.select(state.selected);
+        }
+
+        let button_hints = Row::new(
+            Point::new(
+                rect.x + rect.w as i32 - 12,
+                rect.y + rect.h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            vec![
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::A,
+                    locale.t("button-select"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::B,
+                    locale.t("button-back"),
+                    Alignment::Right,
+                ),
+            ],
+            Alignment::Right,
+            12,
+        );
+
+        drop(locale);
+        drop(styles);
+
+        let mut this = Self {
+            rect,
+            res,
+            list,
+            button_hints,
+            task: None,
+            failed: None,
+            dirty: true,
+        };
+        this.update_list();
+        this
+    }
+
+    fn update_list(&mut self) {
+        let locale = self.res.get::<Locale>();
+
+        let status = |task: Task, last: String| {
+            if self.task.as_ref().is_some_and(|(t, _)| *t == task) {
+                match task {
+                    Task::Export => locale.t("settings-backup-exporting"),
+                    Task::Restore => locale.t("settings-backup-restoring"),
+                }
+            } else if self.failed == Some(task) {
+                locale.t("settings-backup-failed")
+            } else {
+                last
+            }
+        };
+
+        let modified = fs::metadata(ALLIUM_SETTINGS_BACKUP.as_path())
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .map(DateTime::<Local>::from);
+        let items = vec![
+            locale.t("settings-backup-export"),
+            locale.t("settings-backup-restore"),
+        ];
+        let details = vec![
+            status(
+                Task::Export,
+                modified.map_or_else(String::new, |time| {
+                    time.format("%Y-%m-%d %H:%M").to_string()
+                }),
+            ),
+            status(
+                Task::Restore,
+                if modified.is_some() {
+                    String::new()
+                } else {
+                    locale.t("settings-backup-none")
+                },
+            ),
+        ];
+
+        drop(locale);
+        self.list.set_items_with_details(items, details, true);
+        self.dirty = true;
+    }
+
+    fn start(&mut self, task: Task) {
+        if self.task.is_some() {
+            return;
+        }
+        if task == Task::Restore && !ALLIUM_SETTINGS_BACKUP.exists() {
+            return;
+        }
+        let (tx, rx) = oneshot::channel();
+        tokio::task::spawn_blocking(move || {
+            let result = match task {
+                Task::Export => settings_backup::export(&ALLIUM_SETTINGS_BACKUP),
+                Task::Restore => settings_backup::restore(&ALLIUM_SETTINGS_BACKUP),
+            };
+            tx.send(result).ok();
+        });
+        self.task = Some((task, rx));
+        self.failed = None;
+        self.update_list();
+    }
+}
+
+#[async_trait(?Send)]
+impl View for Backup {
+    fn update(&mut self, _dt: Duration) {
+        let Some((task, rx)) = self.task.as_mut() else {
+            return;
+        };
+        let task = *task;
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                Err(anyhow!("settings backup task stopped"))
+            }
+        };
+        self.task = None;
+        match result {
+            Ok(count) => {
+                info!("{:?} of {} settings files finished", task, count);
+                if task == Task::Restore {
+                    // Exits without saving the launcher state, which would overwrite the restored
+                    // state. alliumd restarts the launcher, which loads the restored settings.
+                    std::process::exit(0);
+                }
+            }
+            Err(e) => {
+                warn!("failed to {:?} settings: {}", task, e);
+                self.failed = Some(task);
+            }
+        }
+        self.update_list();
+    }
+
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        let mut drawn = false;
+
+        if self.dirty {
+            display.load(self.rect)?;
+            self.list.set_should_draw();
+            self.button_hints.set_should_draw();
+            self.dirty = false;
+            drawn = true;
+        }
+
+        drawn |= self.list.should_draw() && self.list.draw(display, styles)?;
+        drawn |= self.button_hints.should_draw() && self.button_hints.draw(display, styles)?;
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.dirty || self.list.should_draw() || self.button_hints.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.dirty = true;
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        match event {
+            KeyEvent::Pressed(Key::A) => {
+                match self.list.selected() {
+                    EXPORT_ROW => self.start(Task::Export),
+                    RESTORE_ROW => self.start(Task::Restore),
+                    _ => {}
+                }
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::B) => {
+                if self.task.is_none() {
+                    bubble.push_back(Command::CloseView);
+                }
+                Ok(true)
+            }
+            _ => self.list.handle_key_event(event, commands, bubble).await,
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.list, &self.button_hints]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![&mut self.list, &mut self.button_hints]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
+
+impl SettingsChild for Backup {
+    fn save(&self) -> ChildState {
+        ChildState {
+            selected: self.list.selected(),
+        }
+    }
+}
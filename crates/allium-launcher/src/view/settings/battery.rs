@@ -0,0 +1,376 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration as StdDuration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use common::battery::{BatteryCalibration, BatterySample, estimate_time_remaining};
+use common::command::Command;
+use common::constants::BATTERY_LOG_INTERVAL;
+use common::database::Database;
+use common::display::Display as DisplayTrait;
+use common::geom::{Alignment, Point, Rect};
+use common::locale::Locale;
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::stylesheet::Stylesheet;
+use common::view::{ButtonHint, ButtonIcon, Label, Row, SettingsList, View};
+use embedded_graphics::Drawable;
+use embedded_graphics::prelude::Primitive;
+use embedded_graphics::primitives::{Line, PrimitiveStyle, Rectangle};
+use log::error;
+use tokio::sync::mpsc::Sender;
+
+use crate::view::settings::{ChildState, SettingsChild};
+
+/// How much history the graph shows.
+const GRAPH_DURATION: Duration = Duration::hours(24);
+
+pub struct Battery {
+    rect: Rect,
+    res: Resources,
+    calibration: BatteryCalibration,
+    list: SettingsList,
+    graph: BatteryGraph,
+    button_hints: Row<ButtonHint<String>>,
+}
+
+impl Battery {
+    pub fn new(rect: Rect, res: Resources, state: Option<ChildState>) -> Self {
+        let Rect { x, y, w, h } = rect;
+
+        let locale = res.get::<Locale>();
+        let styles = res.get::<Stylesheet>();
+
+        let samples = res
+            .get::<Database>()
+            .select_battery_history(Utc::now() - GRAPH_DURATION)
+            .unwrap_or_else(|e| {
+                error!("failed to load battery history: {}", e);
+                Vec::new()
+            });
+
+        let time_remaining = match samples.last() {
+            Some(sample) if sample.charging => locale.t("settings-battery-charging"),
+            _ => match estimate_time_remaining(&samples) {
+                Some(remaining) => {
+                    let mut map = HashMap::new();
+                    map.insert("hours".into(), remaining.num_hours().into());
+                    map.insert("minutes".into(), (remaining.num_minutes() % 60).into());
+                    locale.ta("settings-battery-duration", &map)
+                }
+                None => locale.t("settings-battery-unknown"),
+            },
+        };
+        let level = samples.last().map_or_else(
+            || locale.t("settings-battery-unknown"),
+            |sample| format!("{}%", sample.percentage),
+        );
+
+        let calibration = BatteryCalibration::load().unwrap_or_default();
+
+        let entry_height = styles.row_height();
+        let mut list = SettingsList::new(
+            Rect::new(x + 12, y + 8, w - 24, entry_height * 3),
+            vec![
+                locale.t("settings-battery-level"),
+                locale.t("settings-battery-time-remaining"),
+                locale.t("settings-battery-calibration"),
+            ],
+            vec![
+                Box::new(Label::new(Point::zero(), level, Alignment::Right, None)),
+                Box::new(Label::new(
+                    Point::zero(),
+                    time_remaining,
+                    Alignment::Right,
+                    None,
+                )),
+                Box::new(Label::new(
+                    Point::zero(),
+                    calibration_status(&locale, &calibration),
+                    Alignment::Right,
+                    None,
+                )),
+            ],
+            entry_height,
+        );
+        if let Some(state) = state {
+            list.select(state.selected);
+        }
+
+        let graph_y = y + 8 + entry_height as i32 * 3 + 8;
+        let graph = BatteryGraph::new(
+            Rect::new(
+                x + 24,
+                graph_y,
+                w - 48,
+                (y + h as i32 - ButtonIcon::diameter(&styles) as i32 - 16 - graph_y) as u32,
+            ),
+            samples,
+        );
+
+        let button_hints = Row::new(
+            Point::new(
+                rect.x + rect.w as i32 - 12,
+                rect.y + rect.h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            vec![
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::A,
+                    locale.t("settings-battery-calibrate"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::X,
+                    locale.t("settings-battery-reset-calibration"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::B,
+                    locale.t("button-back"),
+                    Alignment::Right,
+                ),
+            ],
+            Alignment::Right,
+            12,
+        );
+
+        drop(locale);
+        drop(styles);
+
+        Self {
+            rect,
+            res,
+            calibration,
+            list,
+            graph,
+            button_hints,
+        }
+    }
+
+    fn update_calibration(&mut self) -> Result<()> {
+        self.calibration.save()?;
+        let status = calibration_status(&self.res.get::<Locale>(), &self.calibration);
+        self.list.set_right(
+            CALIBRATION_INDEX,
+            Box::new(Label::new(Point::zero(), status, Alignment::Right, None)),
+        );
+        Ok(())
+    }
+}
+
+const CALIBRATION_INDEX: usize = 2;
+
+fn calibration_status(locale: &Locale, calibration: &BatteryCalibration) -> String {
+    if calibration.recording.is_some() {
+        locale.t("settings-battery-calibration-in-progress")
+    } else if calibration.is_calibrated() {
+        locale.t("settings-battery-calibration-done")
+    } else {
+        locale.t("settings-battery-calibration-none")
+    }
+}
+
+#[async_trait(?Send)]
+impl View for Battery {
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        let mut drawn = false;
+
+        drawn |= self.list.should_draw() && self.list.draw(display, styles)?;
+        drawn |= self.graph.should_draw() && self.graph.draw(display, styles)?;
+        drawn |= self.button_hints.should_draw() && self.button_hints.draw(display, styles)?;
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.list.should_draw() || self.graph.should_draw() || self.button_hints.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.list.set_should_draw();
+        self.graph.set_should_draw();
+        self.button_hints.set_should_draw();
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        match event {
+            KeyEvent::Pressed(Key::A) if self.list.selected() == CALIBRATION_INDEX => {
+                if self.calibration.recording.is_some() {
+                    self.calibration.cancel();
+                } else {
+                    self.calibration.start();
+                    let text = self
+                        .res
+                        .get::<Locale>()
+                        .t("settings-battery-calibration-instructions");
+                    commands
+                        .send(Command::Toast(text, Some(StdDuration::from_secs(5))))
+                        .await?;
+                }
+                self.update_calibration()?;
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::X) if self.list.selected() == CALIBRATION_INDEX => {
+                self.calibration = BatteryCalibration::new();
+                self.update_calibration()?;
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::B) => {
+                bubble.push_back(Command::CloseView);
+                Ok(true)
+            }
+            _ => self.list.handle_key_event(event, commands, bubble).await,
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.list, &self.graph, &self.button_hints]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![&mut self.list, &mut self.graph, &mut self.button_hints]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
+
+impl SettingsChild for Battery {
+    fn save(&self) -> ChildState {
+        ChildState {
+            selected: self.list.selected(),
+        }
+    }
+}
+
+/// Plots the battery level over the last `GRAPH_DURATION`.
+#[derive(Debug)]
+struct BatteryGraph {
+    rect: Rect,
+    samples: Vec<BatterySample>,
+    dirty: bool,
+}
+
+impl BatteryGraph {
+    fn new(rect: Rect, samples: Vec<BatterySample>) -> Self {
+        Self {
+            rect,
+            samples,
+            dirty: true,
+        }
+    }
+
+    fn point(&self, sample: &BatterySample, now: chrono::DateTime<Utc>) -> Point {
+        let Rect { x, y, w, h } = self.rect;
+        let age = (now - sample.time)
+            .num_seconds()
+            .clamp(0, GRAPH_DURATION.num_seconds());
+        Point::new(
+            x + w as i32 - (age * w as i64 / GRAPH_DURATION.num_seconds()) as i32,
+            y + h as i32 - sample.percentage.clamp(0, 100) * h as i32 / 100,
+        )
+    }
+}
+
+#[async_trait(?Send)]
+impl View for BatteryGraph {
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        let Rect { x, y, w, h } = self.rect;
+
+        display.load(self.rect)?;
+
+        // Grid lines at every 25%
+        for i in 0..=4 {
+            let line_y = y + h as i32 * i / 4;
+            Line::new(
+                Point::new(x, line_y).into(),
+                Point::new(x + w as i32, line_y).into(),
+            )
+            .into_styled(PrimitiveStyle::with_stroke(styles.disabled_color, 1))
+            .draw(display)?;
+        }
+        Rectangle::from(self.rect)
+            .into_styled(PrimitiveStyle::with_stroke(styles.disabled_color, 1))
+            .draw(display)?;
+
+        // Samples further apart than this were not recorded continuously, e.g. the device was off
+        let max_gap = Duration::from_std(BATTERY_LOG_INTERVAL * 2).unwrap_or(Duration::MAX);
+        let now = Utc::now();
+        for pair in self.samples.windows(2) {
+            let (a, b) = (&pair[0], &pair[1]);
+            if b.time - a.time > max_gap {
+                continue;
+            }
+            let color = if b.charging {
+                styles.foreground_color
+            } else {
+                styles.highlight_color
+            };
+            Line::new(self.point(a, now).into(), self.point(b, now).into())
+                .into_styled(PrimitiveStyle::with_stroke(color, 3))
+                .draw(display)?;
+        }
+
+        self.dirty = false;
+        Ok(true)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.dirty
+    }
+
+    fn set_should_draw(&mut self) {
+        self.dirty = true;
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        _event: KeyEvent,
+        _commands: Sender<Command>,
+        _bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        Ok(false)
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        Vec::new()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        Vec::new()
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, point: Point) {
+        self.rect.x = point.x;
+        self.rect.y = point.y;
+    }
+}
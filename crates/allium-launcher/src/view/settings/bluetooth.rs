@@ -0,0 +1,349 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use common::bluetooth::{self, BluetoothDevice};
+use common::command::Command;
+use common::display::Display as DisplayTrait;
+use common::geom::{Alignment, Point, Rect};
+use common::locale::Locale;
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::stylesheet::Stylesheet;
+use common::view::{ButtonHint, ButtonIcon, Label, Row, ScrollList, View};
+use log::warn;
+use tokio::sync::mpsc::Sender;
+use tokio::sync::oneshot;
+
+use crate::view::settings::{ChildState, SettingsChild};
+
+/// Scans for Bluetooth controllers, and pairs or forgets them.
+pub struct Bluetooth {
+    rect: Rect,
+    res: Resources,
+    devices: Vec<BluetoothDevice>,
+    list: ScrollList,
+    status: Label<String>,
+    button_hints: Row<ButtonHint<String>>,
+    devices_rx: Option<oneshot::Receiver<Vec<BluetoothDevice>>>,
+    selected: usize,
+    dirty: bool,
+}
+
+impl Bluetooth {
+    pub fn new(rect: Rect, res: Resources, state: Option<ChildState>) -> Self {
+        let Rect { x, y, w, h } = rect;
+
+        let locale = res.get::<Locale>();
+        let styles = res.get::<Stylesheet>();
+
+        let list = ScrollList::new(
+            Rect::new(
+                x + 12,
+                y + 8,
+                w - 24,
+                h - 8 - ButtonIcon::diameter(&styles) - 16,
+            ),
+            Vec::new(),
+            Alignment::Left,
+            styles.row_height(),
+        );
+
+        let status = Label::new(
+            Point::new(x + 12, y + 8),
+            String::new(),
+            Alignment::Left,
+            Some(w - 24),
+        );
+
+        let button_hints = Row::new(
+            Point::new(
+                rect.x + rect.w as i32 - 12,
+                rect.y + rect.h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            vec![
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::A,
+                    locale.t("button-pair"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::X,
+                    locale.t("button-forget"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::Y,
+                    locale.t("button-scan"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::B,
+                    locale.t("button-back"),
+                    Alignment::Right,
+                ),
+            ],
+            Alignment::Right,
+            12,
+        );
+
+        drop(locale);
+        drop(styles);
+
+        // Start with the devices that are already known, without scanning
+        let (tx, rx) = oneshot::channel();
+        tokio::spawn(async move {
+            let devices = bluetooth::devices().await.unwrap_or_else(|e| {
+                warn!("failed to list bluetooth devices: {}", e);
+                Vec::new()
+            });
+            tx.send(devices).ok();
+        });
+
+        Self {
+            rect,
+            res,
+            devices: Vec::new(),
+            list,
+            status,
+            button_hints,
+            devices_rx: Some(rx),
+            selected: state.map_or(0, |s| s.selected),
+            dirty: true,
+        }
+    }
+
+    fn set_devices(&mut self, devices: Vec<BluetoothDevice>) {
+        let locale = self.res.get::<Locale>();
+        if devices.is_empty() {
+            self.status.set_text(locale.t("settings-bluetooth-empty"));
+        } else {
+            self.status.set_text(String::new());
+        }
+        let paired = locale.t("settings-bluetooth-paired");
+        self.list.set_items(
+            devices
+                .iter()
+                .map(|device| {
+                    if device.paired {
+                        format!("{} ({})", device.name, paired)
+                    } else {
+                        device.name.clone()
+                    }
+                })
+                .collect(),
+            false,
+        );
+        drop(locale);
+        self.devices = devices;
+        self.list.select(self.selected);
+        self.dirty = true;
+    }
+
+    async fn scan(&mut self, commands: Sender<Command>) -> Result<()> {
+        if self.devices_rx.is_some() {
+            return Ok(());
+        }
+
+        let (scanning, failed) = {
+            let locale = self.res.get::<Locale>();
+            (
+                locale.t("settings-bluetooth-scanning"),
+                locale.t("settings-bluetooth-scan-failed"),
+            )
+        };
+        commands.send(Command::Toast(scanning, None)).await?;
+
+        let (tx, rx) = oneshot::channel();
+        self.devices_rx = Some(rx);
+        tokio::spawn(async move {
+            let toast = match bluetooth::scan().await {
+                Ok(devices) => {
+                    tx.send(devices).ok();
+                    Command::Toast(String::new(), Some(Duration::ZERO))
+                }
+                Err(e) => {
+                    warn!("failed to scan for bluetooth devices: {}", e);
+                    Command::Toast(failed, Some(Duration::from_secs(5)))
+                }
+            };
+            commands.send(toast).await.ok();
+        });
+        Ok(())
+    }
+
+    /// Pairs with the selected device, or forgets it, then lists the devices again.
+    async fn pair_or_forget(&mut self, pair: bool, commands: Sender<Command>) -> Result<()> {
+        if self.devices_rx.is_some() {
+            return Ok(());
+        }
+        let Some(device) = self.devices.get(self.list.selected()).cloned() else {
+            return Ok(());
+        };
+        // Paired devices can only be forgotten, and other devices only paired
+        if device.paired == pair {
+            return Ok(());
+        }
+
+        let (progress, done, failed) = {
+            let locale = self.res.get::<Locale>();
+            let mut map = HashMap::new();
+            map.insert("name".into(), device.name.clone().into());
+            if pair {
+                (
+                    Some(locale.ta("settings-bluetooth-pairing", &map)),
+                    locale.ta("settings-bluetooth-paired-with", &map),
+                    locale.ta("settings-bluetooth-pair-failed", &map),
+                )
+            } else {
+                (
+                    None,
+                    locale.ta("settings-bluetooth-forgotten", &map),
+                    locale.ta("settings-bluetooth-forget-failed", &map),
+                )
+            }
+        };
+        if let Some(progress) = progress {
+            commands.send(Command::Toast(progress, None)).await?;
+        }
+
+        let (tx, rx) = oneshot::channel();
+        self.devices_rx = Some(rx);
+        tokio::spawn(async move {
+            let result = if pair {
+                bluetooth::pair(&device.address).await
+            } else {
+                bluetooth::forget(&device.address).await
+            };
+            let text = match result {
+                Ok(()) => done,
+                Err(e) => {
+                    warn!("failed to pair or forget {}: {}", device.address, e);
+                    failed
+                }
+            };
+            commands
+                .send(Command::Toast(text, Some(Duration::from_secs(5))))
+                .await
+                .ok();
+
+            let devices = bluetooth::devices().await.unwrap_or_else(|e| {
+                warn!("failed to list bluetooth devices: {}", e);
+                Vec::new()
+            });
+            tx.send(devices).ok();
+        });
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl View for Bluetooth {
+    fn update(&mut self, _dt: Duration) {
+        if let Some(rx) = self.devices_rx.as_mut() {
+            match rx.try_recv() {
+                Ok(devices) => {
+                    self.devices_rx = None;
+                    self.selected = self.list.selected();
+                    self.set_devices(devices);
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => self.devices_rx = None,
+            }
+        }
+    }
+
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        let mut drawn = false;
+
+        if self.dirty {
+            display.load(self.rect)?;
+            self.list.set_should_draw();
+            self.status.set_should_draw();
+            self.button_hints.set_should_draw();
+            self.dirty = false;
+            drawn = true;
+        }
+
+        drawn |= self.list.should_draw() && self.list.draw(display, styles)?;
+        drawn |= self.status.should_draw() && self.status.draw(display, styles)?;
+        drawn |= self.button_hints.should_draw() && self.button_hints.draw(display, styles)?;
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.dirty
+            || self.list.should_draw()
+            || self.status.should_draw()
+            || self.button_hints.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.dirty = true;
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        match event {
+            KeyEvent::Pressed(Key::A) => {
+                self.pair_or_forget(true, commands).await?;
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::X) => {
+                self.pair_or_forget(false, commands).await?;
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::Y) => {
+                self.scan(commands).await?;
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::B) => {
+                bubble.push_back(Command::CloseView);
+                Ok(true)
+            }
+            _ => self.list.handle_key_event(event, commands, bubble).await,
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.list, &self.status, &self.button_hints]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![&mut self.list, &mut self.status, &mut self.button_hints]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
+
+impl SettingsChild for Bluetooth {
+    fn save(&self) -> ChildState {
+        ChildState {
+            selected: self.list.selected(),
+        }
+    }
+}
@@ -5,7 +5,7 @@ use anyhow::Result;
 use async_trait::async_trait;
 use chrono::Local;
 use common::command::Command;
-use common::constants::{ALLIUM_TIMEZONE, SELECTION_MARGIN};
+use common::constants::ALLIUM_TIMEZONE;
 
 use common::display::Display as DisplayTrait;
 use common::geom::{Alignment, Point, Rect};
@@ -72,7 +72,7 @@ const TIMEZONE_VALUES: [&str; 39] = [
     "UTC+1",
 ];
 
-const TIMEZONE_NAMES: [&str; 39] = [
+pub const TIMEZONE_NAMES: [&str; 39] = [
     "UTC+0",
     "UTC+1",
     "UTC+2",
@@ -114,13 +114,29 @@ const TIMEZONE_NAMES: [&str; 39] = [
     "UTC-1",
 ];
 
+/// Index of the current timezone in `TIMEZONE_NAMES`.
+pub fn current_timezone() -> usize {
+    env::var("TZ")
+        .map(|tz| TIMEZONE_VALUES.iter().position(|&s| s == tz).unwrap_or(0))
+        .unwrap_or(0)
+}
+
+/// Sets and persists the timezone at `index` in `TIMEZONE_NAMES`.
+pub async fn set_timezone(index: usize) -> Result<()> {
+    let timezone = TIMEZONE_VALUES[index];
+    File::create(ALLIUM_TIMEZONE.as_path())
+        .await?
+        .write_all(timezone.as_bytes())
+        .await?;
+    unsafe { env::set_var("TZ", timezone) };
+    Ok(())
+}
+
 impl Clock {
     pub fn new(rect: Rect, res: Resources, state: Option<ChildState>) -> Self {
         let Rect { x, y, w, h } = rect;
 
-        let timezone = env::var("TZ")
-            .map(|tz| TIMEZONE_VALUES.iter().position(|&s| s == tz).unwrap_or(0))
-            .unwrap_or(0);
+        let timezone = current_timezone();
         let locale = res.get::<Locale>();
         let styles = res.get::<Stylesheet>();
 
@@ -148,7 +164,7 @@ impl Clock {
                     Alignment::Right,
                 )),
             ],
-            styles.ui_font.size + SELECTION_MARGIN,
+            styles.row_height(),
         );
         if let Some(state) = state {
             list.select(state.selected);
@@ -237,12 +253,7 @@ impl View for Clock {
                         }
                         1 => {
                             self.timezone = val.as_int().unwrap() as usize;
-                            let timezone = TIMEZONE_VALUES[self.timezone];
-                            File::create(ALLIUM_TIMEZONE.as_path())
-                                .await?
-                                .write_all(timezone.as_bytes())
-                                .await?;
-                            unsafe { env::set_var("TZ", timezone) };
+                            set_timezone(self.timezone).await?;
                             self.list.set_right(
                                 0,
                                 Box::new(DateTime::new(
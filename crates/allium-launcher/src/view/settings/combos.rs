@@ -0,0 +1,246 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use common::combo::{Combo, ComboAction, ComboSettings, Conflict};
+use common::command::Command;
+use common::display::Display as DisplayTrait;
+use common::geom::{Alignment, Point, Rect};
+use common::locale::Locale;
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::stylesheet::Stylesheet;
+use common::view::{ButtonHint, ButtonIcon, Row, Select, SettingsList, View};
+use log::warn;
+use strum::EnumCount;
+use tokio::sync::mpsc::Sender;
+
+use crate::view::settings::{ChildState, SettingsChild};
+
+/// Binds button combos that work anywhere in Allium, warning about combos that conflict with
+/// each other or with alliumd's global hotkeys.
+pub struct Combos {
+    rect: Rect,
+    res: Resources,
+    settings: ComboSettings,
+    choices: Vec<Combo>,
+    labels: Vec<String>,
+    list: SettingsList,
+    button_hints: Row<ButtonHint<String>>,
+}
+
+impl Combos {
+    pub fn new(rect: Rect, res: Resources, state: Option<ChildState>) -> Self {
+        let Rect { x, y, w, h } = rect;
+
+        let locale = res.get::<Locale>();
+        let styles = res.get::<Stylesheet>();
+        let settings = ComboSettings::load().unwrap_or_else(|e| {
+            warn!("failed to load combo settings: {}", e);
+            ComboSettings::default()
+        });
+
+        let labels: Vec<String> = (0..ComboAction::COUNT)
+            .map(|i| {
+                locale.t(match ComboAction::from_repr(i).unwrap() {
+                    ComboAction::Screenshot => "settings-combos-screenshot",
+                    ComboAction::QuickSettings => "settings-combos-quick-settings",
+                    ComboAction::ForceQuit => "settings-combos-force-quit",
+                })
+            })
+            .collect();
+
+        let choices = Combo::choices();
+        let mut options = vec![locale.t("settings-combos-none")];
+        options.extend(
+            choices
+                .iter()
+                .map(|combo| format!("{:?} + {:?}", combo.modifier, combo.key)),
+        );
+        let widgets: Vec<Box<dyn View>> = (0..ComboAction::COUNT)
+            .map(|i| {
+                let value = settings
+                    .get(ComboAction::from_repr(i).unwrap())
+                    .and_then(|combo| choices.iter().position(|c| *c == combo))
+                    .map_or(0, |i| i + 1);
+                Box::new(Select::new(
+                    Point::zero(),
+                    value,
+                    options.clone(),
+                    Alignment::Right,
+                )) as Box<dyn View>
+            })
+            .collect();
+
+        let mut list = SettingsList::new(
+            Rect::new(
+                x + 12,
+                y + 8,
+                w - 24,
+                h - 8 - ButtonIcon::diameter(&styles) - 8,
+            ),
+            labels.clone(),
+            widgets,
+            styles.row_height(),
+        );
+        if let Some(state) = state {
+            list.select(state.selected);
+        }
+
+        let button_hints = Row::new(
+            Point::new(
+                rect.x + rect.w as i32 - 12,
+                rect.y + rect.h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            vec![ButtonHint::new(
+                res.clone(),
+                Point::zero(),
+                Key::B,
+                locale.t("button-back"),
+                Alignment::Right,
+            )],
+            Alignment::Right,
+            12,
+        );
+
+        drop(locale);
+        drop(styles);
+
+        let mut this = Self {
+            rect,
+            res,
+            settings,
+            choices,
+            labels,
+            list,
+            button_hints,
+        };
+        this.update_labels();
+        this
+    }
+
+    /// Marks the combos that conflict.
+    fn update_labels(&mut self) {
+        for (i, label) in self.labels.iter().enumerate() {
+            let action = ComboAction::from_repr(i).unwrap();
+            let label = if self.settings.conflict(action).is_some() {
+                format!("{label} (!)")
+            } else {
+                label.clone()
+            };
+            self.list.set_left(i, label);
+        }
+    }
+
+    fn conflict_message(&self, action: ComboAction) -> Option<String> {
+        let locale = self.res.get::<Locale>();
+        match self.settings.conflict(action)? {
+            Conflict::Allium => Some(locale.t("settings-hotkeys-conflict-allium")),
+            Conflict::Combo(other) => {
+                let mut map = HashMap::new();
+                map.insert("hotkey".into(), self.labels[other as usize].clone().into());
+                Some(locale.ta("settings-hotkeys-conflict-hotkey", &map))
+            }
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl View for Combos {
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        let mut drawn = false;
+
+        drawn |= self.list.should_draw() && self.list.draw(display, styles)?;
+
+        if self.button_hints.should_draw() {
+            display.load(Rect::new(
+                self.rect.x,
+                self.rect.y + self.rect.h as i32 - ButtonIcon::diameter(styles) as i32 - 8,
+                self.rect.w,
+                ButtonIcon::diameter(styles),
+            ))?;
+            drawn |= self.button_hints.draw(display, styles)?;
+        }
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.list.should_draw() || self.button_hints.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.list.set_should_draw();
+        self.button_hints.set_should_draw();
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        if self
+            .list
+            .handle_key_event(event, commands.clone(), bubble)
+            .await?
+        {
+            while let Some(command) = bubble.pop_front() {
+                if let Command::ValueChanged(i, val) = command {
+                    let action = ComboAction::from_repr(i).unwrap();
+                    let combo = (val.as_int().unwrap() as usize)
+                        .checked_sub(1)
+                        .map(|i| self.choices[i]);
+                    self.settings.set(action, combo);
+                    commands
+                        .send(Command::SaveComboSettings(self.settings.clone()))
+                        .await?;
+                    self.update_labels();
+                    if let Some(text) = self.conflict_message(action) {
+                        commands
+                            .send(Command::Toast(text, Some(Duration::from_secs(5))))
+                            .await?;
+                    }
+                }
+            }
+            return Ok(true);
+        }
+
+        match event {
+            KeyEvent::Pressed(Key::B) => {
+                bubble.push_back(Command::CloseView);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.list, &self.button_hints]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![&mut self.list, &mut self.button_hints]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
+
+impl SettingsChild for Combos {
+    fn save(&self) -> ChildState {
+        ChildState {
+            selected: self.list.selected(),
+        }
+    }
+}
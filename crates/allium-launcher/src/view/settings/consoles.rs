@@ -0,0 +1,360 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ffi::OsStr;
+use std::fs;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use common::command::Command;
+use common::console_settings::ConsoleSettings;
+use common::geom::{Alignment, Point, Rect};
+use common::locale::Locale;
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::storage;
+use common::stylesheet::Stylesheet;
+use common::view::{ButtonHint, ButtonIcon, Row, SettingsList, TextBox, Toggle, View};
+use log::warn;
+use tokio::sync::mpsc::Sender;
+
+use crate::consoles::ConsoleMapper;
+use crate::entry::Entry;
+use crate::view::games::arrange_consoles;
+use crate::view::settings::{ChildState, SettingsChild};
+
+pub struct Consoles {
+    rect: Rect,
+    res: Resources,
+    settings: ConsoleSettings,
+    /// Console directories, in display order.
+    dirs: Vec<String>,
+    /// Names shown when no custom name is set, keyed by directory.
+    default_names: HashMap<String, String>,
+    list: SettingsList,
+    button_hints: Row<ButtonHint<String>>,
+    has_changed: bool,
+}
+
+impl Consoles {
+    pub fn new(rect: Rect, res: Resources, state: Option<ChildState>) -> Self {
+        let Rect { x, y, w, h } = rect;
+
+        let settings = ConsoleSettings::load().unwrap_or_default();
+
+        let mut entries = Vec::new();
+        for games_dir in storage::games_dirs() {
+            match fs::read_dir(&games_dir) {
+                Ok(dir) => {
+                    let console_mapper = res.get::<ConsoleMapper>();
+                    entries.extend(
+                        dir.filter_map(|e| e.ok())
+                            .filter_map(|e| Entry::new(e.path(), &console_mapper).ok().flatten())
+                            .filter(|e| matches!(e, Entry::Directory(_))),
+                    );
+                }
+                Err(e) => warn!("failed to read games directory: {}", e),
+            }
+        }
+        entries.sort_unstable();
+        // Consoles are configured by folder name, so folders on secondary storage share the
+        // settings of the SD card's folders of the same name
+        let mut seen = HashSet::new();
+        entries.retain(|e| seen.insert(e.path().file_name().map(OsStr::to_owned)));
+
+        let dir_name = |entry: &Entry| {
+            entry
+                .path()
+                .file_name()
+                .and_then(OsStr::to_str)
+                .unwrap_or_default()
+                .to_owned()
+        };
+        let default_names = entries
+            .iter()
+            .map(|e| (dir_name(e), e.name().to_owned()))
+            .collect();
+        arrange_consoles(&mut entries, &settings);
+        let dirs = entries.iter().map(dir_name).collect();
+
+        let locale = res.get::<Locale>();
+        let styles = res.get::<Stylesheet>();
+
+        let list = SettingsList::new(
+            Rect::new(
+                x + 12,
+                y + 8,
+                w - 24,
+                h - 8 - ButtonIcon::diameter(&styles) - 8,
+            ),
+            Vec::new(),
+            Vec::new(),
+            styles.row_height(),
+        );
+
+        let button_hints = Row::new(
+            Point::new(
+                rect.x + rect.w as i32 - 12,
+                rect.y + rect.h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            vec![
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::A,
+                    locale.t("settings-consoles-rename"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::X,
+                    locale.t("settings-consoles-move-up"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::Y,
+                    locale.t("settings-consoles-move-down"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::Select,
+                    locale.t("settings-consoles-show-hide"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::B,
+                    locale.t("button-back"),
+                    Alignment::Right,
+                ),
+            ],
+            Alignment::Right,
+            12,
+        );
+
+        drop(locale);
+        drop(styles);
+
+        let mut this = Self {
+            rect,
+            res,
+            settings,
+            dirs,
+            default_names,
+            list,
+            button_hints,
+            has_changed: false,
+        };
+        this.set_items();
+        if let Some(state) = state {
+            this.list.select(state.selected.min(this.dirs.len()));
+        }
+        this
+    }
+
+    fn label(&self, dir: &str) -> String {
+        if self.settings.is_hidden(dir) {
+            let mut map = HashMap::new();
+            map.insert("name".into(), dir.into());
+            self.res
+                .get::<Locale>()
+                .ta("settings-consoles-hidden", &map)
+        } else {
+            dir.to_owned()
+        }
+    }
+
+    fn display_name(&self, dir: &str) -> String {
+        self.settings
+            .name(dir)
+            .or_else(|| self.default_names.get(dir).map(String::as_str))
+            .unwrap_or(dir)
+            .to_owned()
+    }
+
+    fn name_box(&self, dir: &str) -> Box<dyn View> {
+        Box::new(TextBox::new(
+            Point::zero(),
+            self.res.clone(),
+            self.display_name(dir),
+            Alignment::Right,
+            false,
+        ))
+    }
+
+    /// Rows are the hide empty toggle, followed by the consoles.
+    fn set_items(&mut self) {
+        let mut left = vec![self.res.get::<Locale>().t("settings-consoles-hide-empty")];
+        left.extend(self.dirs.iter().map(|d| self.label(d)));
+
+        let mut right: Vec<Box<dyn View>> = vec![Box::new(Toggle::new(
+            Point::zero(),
+            self.settings.hide_empty,
+            Alignment::Right,
+        ))];
+        right.extend(self.dirs.iter().map(|d| self.name_box(d)));
+
+        self.list.set_items(left, right);
+    }
+
+    /// Updates the row of the console at `index`.
+    fn set_row(&mut self, index: usize) {
+        let dir = &self.dirs[index];
+        let (left, right) = (self.label(dir), self.name_box(dir));
+        self.list.set_left(index + 1, left);
+        self.list.set_right(index + 1, right);
+    }
+
+    /// Index of the selected console, if a console is selected.
+    fn selected_console(&self) -> Option<usize> {
+        self.list.selected().checked_sub(1)
+    }
+
+    /// Moves the selected console by `offset` places.
+    fn move_selected(&mut self, offset: isize) {
+        let Some(selected) = self.selected_console() else {
+            return;
+        };
+        let Some(target) = selected
+            .checked_add_signed(offset)
+            .filter(|i| *i < self.dirs.len())
+        else {
+            return;
+        };
+
+        self.dirs.swap(selected, target);
+        self.settings.order.clone_from(&self.dirs);
+        self.has_changed = true;
+
+        self.set_row(selected);
+        self.set_row(target);
+        self.list.select(target + 1);
+    }
+
+    fn toggle_hidden(&mut self) {
+        let Some(selected) = self.selected_console() else {
+            return;
+        };
+        let dir = &self.dirs[selected];
+        self.settings.set_hidden(dir, !self.settings.is_hidden(dir));
+        self.has_changed = true;
+
+        self.set_row(selected);
+    }
+}
+
+#[async_trait(?Send)]
+impl View for Consoles {
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        let mut drawn = false;
+
+        if self.list.should_draw() && self.list.draw(display, styles)? {
+            drawn = true;
+        }
+
+        if self.button_hints.should_draw() && self.button_hints.draw(display, styles)? {
+            drawn = true;
+        }
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.list.should_draw() || self.button_hints.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.list.set_should_draw();
+        self.button_hints.set_should_draw();
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        if self
+            .list
+            .handle_key_event(event, commands.clone(), bubble)
+            .await?
+        {
+            while let Some(command) = bubble.pop_front() {
+                if let Command::ValueChanged(i, val) = command {
+                    match i {
+                        0 => self.settings.hide_empty = val.as_bool().unwrap(),
+                        i => {
+                            let dir = self.dirs[i - 1].clone();
+                            let name = val.as_string().unwrap().trim().to_owned();
+                            if self.default_names.get(&dir) == Some(&name) {
+                                self.settings.set_name(&dir, String::new());
+                            } else {
+                                self.settings.set_name(&dir, name);
+                            }
+                            self.set_row(i - 1);
+                        }
+                    }
+                    self.has_changed = true;
+                }
+            }
+            return Ok(true);
+        }
+
+        match event {
+            KeyEvent::Pressed(Key::X) | KeyEvent::Autorepeat(Key::X) => {
+                self.move_selected(-1);
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::Y) | KeyEvent::Autorepeat(Key::Y) => {
+                self.move_selected(1);
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::Select) => {
+                self.toggle_hidden();
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::B) => {
+                if self.has_changed {
+                    commands
+                        .send(Command::SaveConsoleSettings(self.settings.clone()))
+                        .await?;
+                }
+                bubble.push_back(Command::CloseView);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.list, &self.button_hints]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![&mut self.list, &mut self.button_hints]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
+
+impl SettingsChild for Consoles {
+    fn save(&self) -> ChildState {
+        ChildState {
+            selected: self.list.selected(),
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::VecDeque;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use common::command::Command;
+use common::display::Display as DisplayTrait;
+use common::geom::{Alignment, Point, Rect};
+use common::locale::Locale;
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::rumble::RumbleSettings;
+use common::stylesheet::Stylesheet;
+use common::ui_keymap::{UI_KEY_CHOICES, UiKeymap};
+use common::view::{ButtonHint, ButtonIcon, Number, Row, Select, SettingsList, Toggle, View};
+use log::warn;
+use tokio::sync::mpsc::Sender;
+
+use crate::view::settings::{ChildState, SettingsChild};
+
+/// Remaps the keys of Allium's own UI: confirm and back, and the keys that open search and the
+/// menu. Also sets how long a key is held to long press it, and when the device vibrates, if it
+/// has a vibration motor.
+pub struct Controls {
+    rect: Rect,
+    keymap: UiKeymap,
+    rumble: RumbleSettings,
+    list: SettingsList,
+    button_hints: Row<ButtonHint<String>>,
+}
+
+impl Controls {
+    pub fn new(rect: Rect, res: Resources, state: Option<ChildState>) -> Self {
+        let Rect { x, y, w, h } = rect;
+
+        let locale = res.get::<Locale>();
+        let styles = res.get::<Stylesheet>();
+        let keymap = UiKeymap::current();
+        let rumble = RumbleSettings::load().unwrap_or_else(|e| {
+            warn!("failed to load rumble settings: {}", e);
+            RumbleSettings::default()
+        });
+
+        let keys: Vec<String> = UI_KEY_CHOICES
+            .iter()
+            .map(|key| format!("{key:?}"))
+            .collect();
+        let position = |key: Key| UI_KEY_CHOICES.iter().position(|k| *k == key).unwrap_or(0);
+
+        let mut labels = vec![
+            locale.t("settings-controls-confirm"),
+            locale.t("settings-controls-search"),
+            locale.t("settings-controls-menu"),
+            locale.t("settings-controls-long-press"),
+        ];
+        let mut widgets: Vec<Box<dyn View>> = vec![
+            Box::new(Select::new(
+                Point::zero(),
+                keymap.swap_confirm_back as usize,
+                vec![
+                    locale.t("settings-controls-confirm-nintendo"),
+                    locale.t("settings-controls-confirm-xbox"),
+                ],
+                Alignment::Right,
+            )),
+            Box::new(Select::new(
+                Point::zero(),
+                position(keymap.search()),
+                keys.clone(),
+                Alignment::Right,
+            )),
+            Box::new(Select::new(
+                Point::zero(),
+                position(keymap.menu()),
+                keys,
+                Alignment::Right,
+            )),
+            Box::new(Number::new(
+                Point::zero(),
+                keymap.long_press_ms,
+                300,
+                2000,
+                100,
+                |x: &i32| format!("{x} ms"),
+                Alignment::Right,
+            )),
+        ];
+        if DefaultPlatform::has_rumble() {
+            labels.extend([
+                locale.t("settings-controls-rumble-navigation"),
+                locale.t("settings-controls-rumble-confirm"),
+                locale.t("settings-controls-rumble-low-battery"),
+            ]);
+            widgets.extend([
+                Box::new(Toggle::new(
+                    Point::zero(),
+                    rumble.navigation,
+                    Alignment::Right,
+                )) as Box<dyn View>,
+                Box::new(Toggle::new(Point::zero(), rumble.confirm, Alignment::Right)),
+                Box::new(Toggle::new(
+                    Point::zero(),
+                    rumble.low_battery,
+                    Alignment::Right,
+                )),
+            ]);
+        }
+
+        let mut list = SettingsList::new(
+            Rect::new(
+                x + 12,
+                y + 8,
+                w - 24,
+                h - 8 - ButtonIcon::diameter(&styles) - 8,
+            ),
+            labels,
+            widgets,
+            styles.row_height(),
+        );
+        if let Some(state) = state {
+            list.select(state.selected);
+        }
+
+        let button_hints = Row::new(
+            Point::new(
+                rect.x + rect.w as i32 - 12,
+                rect.y + rect.h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            vec![ButtonHint::new(
+                res.clone(),
+                Point::zero(),
+                Key::B,
+                locale.t("button-back"),
+                Alignment::Right,
+            )],
+            Alignment::Right,
+            12,
+        );
+
+        drop(locale);
+        drop(styles);
+
+        Self {
+            rect,
+            keymap,
+            rumble,
+            list,
+            button_hints,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl View for Controls {
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        let mut drawn = false;
+
+        drawn |= self.list.should_draw() && self.list.draw(display, styles)?;
+
+        if self.button_hints.should_draw() {
+            display.load(Rect::new(
+                self.rect.x,
+                self.rect.y + self.rect.h as i32 - ButtonIcon::diameter(styles) as i32 - 8,
+                self.rect.w,
+                ButtonIcon::diameter(styles),
+            ))?;
+            drawn |= self.button_hints.draw(display, styles)?;
+        }
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.list.should_draw() || self.button_hints.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.list.set_should_draw();
+        self.button_hints.set_should_draw();
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        if self
+            .list
+            .handle_key_event(event, commands.clone(), bubble)
+            .await?
+        {
+            while let Some(command) = bubble.pop_front() {
+                if let Command::ValueChanged(i, val) = command {
+                    match i {
+                        0 => self.keymap.swap_confirm_back = val.as_int().unwrap() == 1,
+                        1 => self
+                            .keymap
+                            .set_search(UI_KEY_CHOICES[val.as_int().unwrap() as usize]),
+                        2 => self
+                            .keymap
+                            .set_menu(UI_KEY_CHOICES[val.as_int().unwrap() as usize]),
+                        3 => self.keymap.long_press_ms = val.as_int().unwrap(),
+                        4 => self.rumble.navigation = val.as_bool().unwrap(),
+                        5 => self.rumble.confirm = val.as_bool().unwrap(),
+                        6 => self.rumble.low_battery = val.as_bool().unwrap(),
+                        _ => unreachable!("Invalid index"),
+                    }
+                    if i < 4 {
+                        // Views are rebuilt with the new keys, including this one
+                        commands.send(Command::SaveUiKeymap(self.keymap)).await?;
+                    } else {
+                        commands
+                            .send(Command::SaveRumbleSettings(self.rumble))
+                            .await?;
+                    }
+                }
+            }
+            return Ok(true);
+        }
+
+        match event {
+            KeyEvent::Pressed(Key::B) => {
+                bubble.push_back(Command::CloseView);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.list, &self.button_hints]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![&mut self.list, &mut self.button_hints]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
+
+impl SettingsChild for Controls {
+    fn save(&self) -> ChildState {
+        ChildState {
+            selected: self.list.selected(),
+        }
+    }
+}
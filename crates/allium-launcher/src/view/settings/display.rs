@@ -1,25 +1,32 @@
 use std::collections::VecDeque;
+use std::time::Duration;
 
 use anyhow::Result;
 use async_trait::async_trait;
 use common::command::Command;
-use common::constants::SELECTION_MARGIN;
 
 use common::display::Display as DisplayTrait;
-use common::display::settings::DisplaySettings;
+use common::display::rotation::Rotation;
+use common::display::settings::{DisplaySettings, NightMode};
 use common::geom::{Alignment, Point, Rect, Size};
 use common::locale::Locale;
 use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
 use common::resources::Resources;
 use common::stylesheet::Stylesheet;
-use common::view::{ButtonHint, ButtonIcon, Label, Percentage, Row, SettingsList, View};
+use common::view::{
+    ButtonHint, ButtonIcon, Label, Number, Percentage, Row, Select, SettingsList, Toggle, View,
+};
 
 use tokio::sync::mpsc::Sender;
 
 use crate::view::settings::{ChildState, SettingsChild};
 
+/// Minutes between the times the night mode schedule can be set to.
+const SCHEDULE_STEP: i32 = 15;
+
 pub struct Display {
     rect: Rect,
+    res: Resources,
     settings: DisplaySettings,
     list: SettingsList,
     button_hints: Row<ButtonHint<String>>,
@@ -51,6 +58,12 @@ impl Display {
                 locale.t("settings-display-red"),
                 locale.t("settings-display-green"),
                 locale.t("settings-display-blue"),
+                locale.t("settings-display-night-mode"),
+                locale.t("settings-display-night-mode-strength"),
+                locale.t("settings-display-night-mode-start"),
+                locale.t("settings-display-night-mode-end"),
+                locale.t("settings-display-vsync"),
+                locale.t("settings-display-rotation"),
             ],
             vec![
                 Box::new(Label::new(
@@ -111,8 +124,55 @@ impl Display {
                     100,
                     Alignment::Right,
                 )),
+                Box::new(Select::new(
+                    Point::zero(),
+                    settings.night_mode as usize,
+                    vec![
+                        locale.t("settings-display-night-mode-off"),
+                        locale.t("settings-display-night-mode-on"),
+                        locale.t("settings-display-night-mode-scheduled"),
+                    ],
+                    Alignment::Right,
+                )),
+                Box::new(Percentage::new(
+                    Point::zero(),
+                    i32::from(settings.night_mode_strength),
+                    0,
+                    100,
+                    Alignment::Right,
+                )),
+                Box::new(Number::new(
+                    Point::zero(),
+                    i32::from(settings.night_mode_start_minute),
+                    0,
+                    24 * 60 - SCHEDULE_STEP,
+                    SCHEDULE_STEP,
+                    format_minute_of_day,
+                    Alignment::Right,
+                )),
+                Box::new(Number::new(
+                    Point::zero(),
+                    i32::from(settings.night_mode_end_minute),
+                    0,
+                    24 * 60 - SCHEDULE_STEP,
+                    SCHEDULE_STEP,
+                    format_minute_of_day,
+                    Alignment::Right,
+                )),
+                Box::new(Toggle::new(Point::zero(), settings.vsync, Alignment::Right)),
+                Box::new(Select::new(
+                    Point::zero(),
+                    settings.rotation as usize,
+                    vec![
+                        locale.t("settings-display-rotation-0"),
+                        locale.t("settings-display-rotation-90"),
+                        locale.t("settings-display-rotation-180"),
+                        locale.t("settings-display-rotation-270"),
+                    ],
+                    Alignment::Right,
+                )),
             ],
-            styles.ui_font.size + SELECTION_MARGIN,
+            styles.row_height(),
         );
         if let Some(state) = state {
             list.select(state.selected);
@@ -141,8 +201,12 @@ impl Display {
             Alignment::Right,
         ));
 
+        drop(locale);
+        drop(styles);
+
         Self {
             rect,
+            res,
             settings,
             list,
             button_hints,
@@ -151,6 +215,10 @@ impl Display {
     }
 }
 
+fn format_minute_of_day(minute: &i32) -> String {
+    format!("{:02}:{:02}", minute / 60, minute % 60)
+}
+
 #[async_trait(?Send)]
 impl View for Display {
     fn draw(
@@ -213,6 +281,27 @@ impl View for Display {
                         5 => self.settings.r = val.as_int().unwrap() as u8,
                         6 => self.settings.g = val.as_int().unwrap() as u8,
                         7 => self.settings.b = val.as_int().unwrap() as u8,
+                        8 => {
+                            self.settings.night_mode =
+                                NightMode::from_repr(val.as_int().unwrap() as usize)
+                                    .unwrap_or_default()
+                        }
+                        9 => self.settings.night_mode_strength = val.as_int().unwrap() as u8,
+                        10 => self.settings.night_mode_start_minute = val.as_int().unwrap() as u16,
+                        11 => self.settings.night_mode_end_minute = val.as_int().unwrap() as u16,
+                        12 => self.settings.vsync = val.as_bool().unwrap(),
+                        13 => {
+                            self.settings.rotation =
+                                Rotation::from_repr(val.as_int().unwrap() as usize)
+                                    .unwrap_or_default();
+                            let text = self
+                                .res
+                                .get::<Locale>()
+                                .t("settings-needs-restart-for-effect");
+                            commands
+                                .send(Command::Toast(text, Some(Duration::from_secs(5))))
+                                .await?;
+                        }
                         _ => unreachable!("Invalid index"),
                     }
 
@@ -0,0 +1,239 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use common::command::Command;
+use common::display::Display as DisplayTrait;
+use common::geom::{Alignment, Point, Rect};
+use common::locale::Locale;
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::retroarch_hotkeys::{Conflict, HOTKEY_BUTTONS, Hotkey, RetroArchHotkeys};
+use common::stylesheet::Stylesheet;
+use common::view::{ButtonHint, ButtonIcon, Row, Select, SettingsList, View};
+use log::warn;
+use strum::EnumCount;
+use tokio::sync::mpsc::Sender;
+
+use crate::view::settings::{ChildState, SettingsChild};
+
+/// Edits RetroArch's hotkeys, warning about bindings that conflict with each other or with
+/// Allium's global hotkeys.
+pub struct Hotkeys {
+    rect: Rect,
+    res: Resources,
+    hotkeys: RetroArchHotkeys,
+    labels: Vec<String>,
+    list: SettingsList,
+    button_hints: Row<ButtonHint<String>>,
+}
+
+impl Hotkeys {
+    pub fn new(rect: Rect, res: Resources, state: Option<ChildState>) -> Self {
+        let Rect { x, y, w, h } = rect;
+
+        let locale = res.get::<Locale>();
+        let styles = res.get::<Stylesheet>();
+        let hotkeys = RetroArchHotkeys::load().unwrap_or_else(|e| {
+            warn!("failed to load RetroArch hotkeys: {}", e);
+            RetroArchHotkeys::default()
+        });
+
+        let labels: Vec<String> = (0..Hotkey::COUNT)
+            .map(|i| {
+                locale.t(match Hotkey::from_repr(i).unwrap() {
+                    Hotkey::Enable => "settings-hotkeys-enable",
+                    Hotkey::MenuToggle => "settings-hotkeys-menu-toggle",
+                    Hotkey::SaveState => "settings-hotkeys-save-state",
+                    Hotkey::LoadState => "settings-hotkeys-load-state",
+                    Hotkey::FastForward => "settings-hotkeys-fast-forward",
+                })
+            })
+            .collect();
+
+        let mut buttons = vec![locale.t("settings-hotkeys-none")];
+        buttons.extend(HOTKEY_BUTTONS.iter().map(|key| format!("{key:?}")));
+        let widgets: Vec<Box<dyn View>> = (0..Hotkey::COUNT)
+            .map(|i| {
+                let value = hotkeys
+                    .get(Hotkey::from_repr(i).unwrap())
+                    .and_then(|key| HOTKEY_BUTTONS.iter().position(|k| *k == key))
+                    .map_or(0, |i| i + 1);
+                Box::new(Select::new(
+                    Point::zero(),
+                    value,
+                    buttons.clone(),
+                    Alignment::Right,
+                )) as Box<dyn View>
+            })
+            .collect();
+
+        let mut list = SettingsList::new(
+            Rect::new(
+                x + 12,
+                y + 8,
+                w - 24,
+                h - 8 - ButtonIcon::diameter(&styles) - 8,
+            ),
+            labels.clone(),
+            widgets,
+            styles.row_height(),
+        );
+        if let Some(state) = state {
+            list.select(state.selected);
+        }
+
+        let button_hints = Row::new(
+            Point::new(
+                rect.x + rect.w as i32 - 12,
+                rect.y + rect.h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            vec![ButtonHint::new(
+                res.clone(),
+                Point::zero(),
+                Key::B,
+                locale.t("button-back"),
+                Alignment::Right,
+            )],
+            Alignment::Right,
+            12,
+        );
+
+        drop(locale);
+        drop(styles);
+
+        let mut this = Self {
+            rect,
+            res,
+            hotkeys,
+            labels,
+            list,
+            button_hints,
+        };
+        this.update_labels();
+        this
+    }
+
+    /// Marks the hotkeys that conflict.
+    fn update_labels(&mut self) {
+        for (i, label) in self.labels.iter().enumerate() {
+            let hotkey = Hotkey::from_repr(i).unwrap();
+            let label = if self.hotkeys.conflict(hotkey).is_some() {
+                format!("{label} (!)")
+            } else {
+                label.clone()
+            };
+            self.list.set_left(i, label);
+        }
+    }
+
+    fn conflict_message(&self, hotkey: Hotkey) -> Option<String> {
+        let locale = self.res.get::<Locale>();
+        match self.hotkeys.conflict(hotkey)? {
+            Conflict::Allium => Some(locale.t("settings-hotkeys-conflict-allium")),
+            Conflict::Hotkey(other) => {
+                let mut map = HashMap::new();
+                map.insert("hotkey".into(), self.labels[other as usize].clone().into());
+                Some(locale.ta("settings-hotkeys-conflict-hotkey", &map))
+            }
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl View for Hotkeys {
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        let mut drawn = false;
+
+        drawn |= self.list.should_draw() && self.list.draw(display, styles)?;
+
+        if self.button_hints.should_draw() {
+            display.load(Rect::new(
+                self.rect.x,
+                self.rect.y + self.rect.h as i32 - ButtonIcon::diameter(styles) as i32 - 8,
+                self.rect.w,
+                ButtonIcon::diameter(styles),
+            ))?;
+            drawn |= self.button_hints.draw(display, styles)?;
+        }
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.list.should_draw() || self.button_hints.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.list.set_should_draw();
+        self.button_hints.set_should_draw();
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        if self
+            .list
+            .handle_key_event(event, commands.clone(), bubble)
+            .await?
+        {
+            while let Some(command) = bubble.pop_front() {
+                if let Command::ValueChanged(i, val) = command {
+                    let hotkey = Hotkey::from_repr(i).unwrap();
+                    let key = (val.as_int().unwrap() as usize)
+                        .checked_sub(1)
+                        .map(|i| HOTKEY_BUTTONS[i]);
+                    self.hotkeys.set(hotkey, key);
+                    self.hotkeys.save()?;
+                    self.update_labels();
+                    if let Some(text) = self.conflict_message(hotkey) {
+                        commands
+                            .send(Command::Toast(text, Some(Duration::from_secs(5))))
+                            .await?;
+                    }
+                }
+            }
+            return Ok(true);
+        }
+
+        match event {
+            KeyEvent::Pressed(Key::B) => {
+                bubble.push_back(Command::CloseView);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.list, &self.button_hints]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![&mut self.list, &mut self.button_hints]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
+
+impl SettingsChild for Hotkeys {
+    fn save(&self) -> ChildState {
+        ChildState {
+            selected: self.list.selected(),
+        }
+    }
+}
@@ -3,15 +3,15 @@ use std::collections::VecDeque;
 use anyhow::Result;
 use async_trait::async_trait;
 use common::command::Command;
-use common::constants::SELECTION_MARGIN;
 
 use common::geom::{Alignment, Point, Rect};
-use common::locale::{Locale, LocaleSettings};
+use common::locale::{KeyboardLayout, Locale, LocaleSettings};
 use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
 use common::resources::Resources;
 use common::stylesheet::Stylesheet;
-use common::view::{ButtonHint, ButtonIcon, Row, Select, SettingsList, View};
+use common::view::{ButtonHint, ButtonIcon, Row, Select, SettingsList, Toggle, View};
 
+use strum::EnumCount;
 use tokio::sync::mpsc::Sender;
 
 use crate::view::settings::{ChildState, SettingsChild};
@@ -44,20 +44,39 @@ impl Language {
                 w - 24,
                 h - 8 - ButtonIcon::diameter(&styles) - 8,
             ),
-            vec![locale.t("settings-language-language")],
-            vec![Box::new(Select::new(
-                Point::zero(),
-                lang,
-                langs
-                    .iter()
-                    .map(|l| {
-                        let name = locale.t(&format!("lang-{}", l));
-                        if name.is_empty() { l.clone() } else { name }
-                    })
-                    .collect(),
-                Alignment::Right,
-            ))],
-            styles.ui_font.size + SELECTION_MARGIN,
+            vec![
+                locale.t("settings-language-language"),
+                locale.t("settings-language-keyboard-layout"),
+                locale.t("settings-language-t9-search"),
+            ],
+            vec![
+                Box::new(Select::new(
+                    Point::zero(),
+                    lang,
+                    langs
+                        .iter()
+                        .map(|l| {
+                            let name = locale.t(&format!("lang-{}", l));
+                            if name.is_empty() { l.clone() } else { name }
+                        })
+                        .collect(),
+                    Alignment::Right,
+                )),
+                Box::new(Select::new(
+                    Point::zero(),
+                    settings.keyboard_layout as usize,
+                    (0..KeyboardLayout::COUNT)
+                        .map(|i| KeyboardLayout::from_repr(i).unwrap().name().to_owned())
+                        .collect(),
+                    Alignment::Right,
+                )),
+                Box::new(Toggle::new(
+                    Point::zero(),
+                    settings.t9_search,
+                    Alignment::Right,
+                )),
+            ],
+            styles.row_height(),
         );
         if let Some(state) = state {
             list.select(state.selected);
@@ -146,6 +165,11 @@ impl View for Language {
                             .settings
                             .lang
                             .clone_from(&self.langs[val.as_int().unwrap() as usize]),
+                        1 => {
+                            self.settings.keyboard_layout =
+                                KeyboardLayout::from_repr(val.as_int().unwrap() as usize).unwrap()
+                        }
+                        2 => self.settings.t9_search = val.as_bool().unwrap(),
                         _ => unreachable!("Invalid index"),
                     }
 
@@ -154,6 +178,9 @@ impl View for Language {
                     commands
                         .send(Command::SaveLocaleSettings(self.settings.clone()))
                         .await?;
+                    if i == 0 {
+                        commands.send(Command::LocaleChanged).await?;
+                    }
                 }
             }
             return Ok(true);
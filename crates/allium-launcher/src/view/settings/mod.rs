@@ -1,18 +1,50 @@
 mod about;
+mod artwork;
+mod backup;
+mod battery;
+mod bluetooth;
 mod clock;
+mod combos;
+mod consoles;
+mod controls;
 mod display;
+mod hotkeys;
 mod language;
+mod notifications;
 mod power;
+mod retroarch_import;
+mod saves;
+mod sound;
+mod splash;
+mod storage;
 mod theme;
+mod theme_store;
 mod wifi;
 
 use crate::view::settings::clock::Clock;
 
+pub use self::clock::{TIMEZONE_NAMES, current_timezone, set_timezone};
+
 use self::about::About;
+use self::artwork::MissingArtwork;
+use self::backup::Backup;
+use self::battery::Battery;
+use self::bluetooth::Bluetooth;
+use self::combos::Combos;
+use self::consoles::Consoles;
+use self::controls::Controls;
 use self::display::Display;
+use self::hotkeys::Hotkeys;
 use self::language::Language;
+use self::notifications::Notifications;
 use self::power::Power;
+use self::retroarch_import::RetroArchImport;
+use self::saves::Saves;
+use self::sound::Sound;
+use self::splash::BootSplash;
+use self::storage::Storage;
 use self::theme::Theme;
+use self::theme_store::ThemeStore;
 use self::wifi::Wifi;
 
 use std::collections::VecDeque;
@@ -21,7 +53,6 @@ use std::fmt::Debug;
 use anyhow::Result;
 use async_trait::async_trait;
 use common::command::Command;
-use common::constants::SELECTION_MARGIN;
 use common::display::Display as DisplayTrait;
 use common::geom::{Alignment, Point, Rect};
 use common::locale::Locale;
@@ -61,6 +92,7 @@ pub struct Settings {
     child: Option<Box<dyn SettingsChild>>,
     button_hints: Row<ButtonHint<String>>,
     has_wifi: bool,
+    has_bluetooth: bool,
     dirty: bool,
 }
 
@@ -72,43 +104,47 @@ impl Settings {
         let styles = res.get::<Stylesheet>();
 
         let has_wifi = DefaultPlatform::has_wifi();
-        let mut labels = Vec::with_capacity(7);
+        let has_bluetooth = DefaultPlatform::has_bluetooth();
+        let mut labels = Vec::with_capacity(22);
         if has_wifi {
             labels.push(locale.t("settings-wifi"));
         }
+        if has_bluetooth {
+            labels.push(locale.t("settings-bluetooth"));
+        }
         labels.push(locale.t("settings-clock"));
         labels.push(locale.t("settings-power"));
+        labels.push(locale.t("settings-battery"));
         labels.push(locale.t("settings-display"));
+        labels.push(locale.t("settings-sound"));
         labels.push(locale.t("settings-theme"));
+        labels.push(locale.t("settings-theme-store"));
+        labels.push(locale.t("settings-boot-splash"));
         labels.push(locale.t("settings-language"));
+        labels.push(locale.t("settings-saves"));
+        labels.push(locale.t("settings-consoles"));
+        labels.push(locale.t("settings-artwork"));
+        labels.push(locale.t("settings-storage"));
+        labels.push(locale.t("settings-backup"));
+        labels.push(locale.t("settings-retroarch-import"));
+        labels.push(locale.t("settings-controls"));
+        labels.push(locale.t("settings-hotkeys"));
+        labels.push(locale.t("settings-combos"));
+        labels.push(locale.t("settings-notifications"));
         labels.push(locale.t("settings-about"));
 
         let mut list = ScrollList::new(
             Rect::new(x + 12, y + 8, w - 24, h - 8 - styles.ui_font.size - 8),
             labels,
             Alignment::Left,
-            styles.ui_font.size + SELECTION_MARGIN,
+            styles.row_height(),
         );
         list.select(state.selected);
 
-        let child: Option<Box<dyn SettingsChild>> = if let Some(child) = state.child {
-            let mut selected = state.selected;
-            if !has_wifi {
-                selected += 1;
-            };
-            match selected {
-                0 => Some(Box::new(Wifi::new(rect, res.clone(), Some(child)))),
-                1 => Some(Box::new(Clock::new(rect, res.clone(), Some(child)))),
-                2 => Some(Box::new(Power::new(rect, res.clone(), Some(child)))),
-                3 => Some(Box::new(Display::new(rect, res.clone(), Some(child)))),
-                4 => Some(Box::new(Theme::new(rect, res.clone(), Some(child)))),
-                5 => Some(Box::new(Language::new(rect, res.clone(), Some(child)))),
-                6 => Some(Box::new(About::new(rect, res.clone(), Some(child)))),
-                _ => None,
-            }
-        } else {
-            None
-        };
+        let child = state.child.and_then(|child| {
+            let page = page(state.selected, has_wifi, has_bluetooth);
+            open_page(page, rect, res.clone(), Some(child))
+        });
 
         let button_hints = Row::new(
             Point::new(
@@ -144,6 +180,7 @@ impl Settings {
             child,
             button_hints,
             has_wifi,
+            has_bluetooth,
             dirty: true,
         })
     }
@@ -156,25 +193,57 @@ impl Settings {
     }
 
     async fn select_entry(&mut self, _commands: Sender<Command>) -> Result<()> {
-        let mut selected = self.list.selected();
-        if !self.has_wifi {
-            selected += 1
-        };
-        match selected {
-            0 => self.child = Some(Box::new(Wifi::new(self.rect, self.res.clone(), None))),
-            1 => self.child = Some(Box::new(Clock::new(self.rect, self.res.clone(), None))),
-            2 => self.child = Some(Box::new(Power::new(self.rect, self.res.clone(), None))),
-            3 => self.child = Some(Box::new(Display::new(self.rect, self.res.clone(), None))),
-            4 => self.child = Some(Box::new(Theme::new(self.rect, self.res.clone(), None))),
-            5 => self.child = Some(Box::new(Language::new(self.rect, self.res.clone(), None))),
-            6 => self.child = Some(Box::new(About::new(self.rect, self.res.clone(), None))),
-            _ => unreachable!("Invalid index"),
-        }
+        let page = page(self.list.selected(), self.has_wifi, self.has_bluetooth);
+        self.child = open_page(page, self.rect, self.res.clone(), None);
         self.dirty = true;
         Ok(())
     }
 }
 
+/// Index of the page for an entry of the list, which leaves out pages the device doesn't support.
+fn page(mut selected: usize, has_wifi: bool, has_bluetooth: bool) -> usize {
+    if !has_wifi {
+        selected += 1;
+    }
+    if !has_bluetooth && selected >= 1 {
+        selected += 1;
+    }
+    selected
+}
+
+fn open_page(
+    page: usize,
+    rect: Rect,
+    res: Resources,
+    state: Option<ChildState>,
+) -> Option<Box<dyn SettingsChild>> {
+    Some(match page {
+        0 => Box::new(Wifi::new(rect, res, state)),
+        1 => Box::new(Bluetooth::new(rect, res, state)),
+        2 => Box::new(Clock::new(rect, res, state)),
+        3 => Box::new(Power::new(rect, res, state)),
+        4 => Box::new(Battery::new(rect, res, state)),
+        5 => Box::new(Display::new(rect, res, state)),
+        6 => Box::new(Sound::new(rect, res, state)),
+        7 => Box::new(Theme::new(rect, res, state)),
+        8 => Box::new(ThemeStore::new(rect, res, state)),
+        9 => Box::new(BootSplash::new(rect, res, state)),
+        10 => Box::new(Language::new(rect, res, state)),
+        11 => Box::new(Saves::new(rect, res, state)),
+        12 => Box::new(Consoles::new(rect, res, state)),
+        13 => Box::new(MissingArtwork::new(rect, res, state)),
+        14 => Box::new(Storage::new(rect, res, state)),
+        15 => Box::new(Backup::new(rect, res, state)),
+        16 => Box::new(RetroArchImport::new(rect, res, state)),
+        17 => Box::new(Controls::new(rect, res, state)),
+        18 => Box::new(Hotkeys::new(rect, res, state)),
+        19 => Box::new(Combos::new(rect, res, state)),
+        20 => Box::new(Notifications::new(rect, res, state)),
+        21 => Box::new(About::new(rect, res, state)),
+        _ => return None,
+    })
+}
+
 #[async_trait(?Send)]
 impl View for Settings {
     fn draw(
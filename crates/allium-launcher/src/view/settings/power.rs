@@ -4,13 +4,14 @@ use std::time::Duration;
 use anyhow::Result;
 use async_trait::async_trait;
 use common::command::Command;
-use common::constants::SELECTION_MARGIN;
 
 use common::display::Display as DisplayTrait;
 use common::geom::{Alignment, Point, Rect};
+use common::led::LedSettings;
 use common::locale::Locale;
+use common::performance::{PerformanceSettings, PowerProfile};
 use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
-use common::power::{PowerButtonAction, PowerSettings};
+use common::power::{LidCloseAction, PowerButtonAction, PowerSettings};
 use common::resources::Resources;
 use common::stylesheet::Stylesheet;
 use common::view::{ButtonHint, ButtonIcon, Number, Row, Select, SettingsList, Toggle, View};
@@ -19,10 +20,32 @@ use tokio::sync::mpsc::Sender;
 
 use crate::view::settings::{ChildState, SettingsChild};
 
+/// Settings on the page. Some are only shown on devices that have the hardware.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PowerRow {
+    PowerProfile,
+    AutoSleepWhenCharging,
+    AutoSleepDuration,
+    IdleDim,
+    AutoPowerOff,
+    PowerButtonAction,
+    PowerButtonLongPressAction,
+    BatteryShutdownThreshold,
+    LowBatteryWarning,
+    LidCloseAction,
+    LedCharging,
+    LedLowBattery,
+    LedJobFinished,
+}
+
 pub struct Power {
     res: Resources,
     rect: Rect,
     power_settings: PowerSettings,
+    performance_settings: PerformanceSettings,
+    led_settings: LedSettings,
+    /// What each row of the list sets.
+    rows: Vec<PowerRow>,
     list: SettingsList,
     button_hints: Row<ButtonHint<String>>,
 }
@@ -34,12 +57,32 @@ impl Power {
         let locale = res.get::<Locale>();
         let styles = res.get::<Stylesheet>();
         let power_settings = PowerSettings::load().unwrap_or_default();
+        let performance_settings = PerformanceSettings::load().unwrap_or_default();
+        let led_settings = LedSettings::load().unwrap_or_default();
 
         let auto_sleep_duration_disabled_label =
             locale.t("settings-power-auto-sleep-duration-disabled");
+        let idle_dim_disabled_label = auto_sleep_duration_disabled_label.clone();
+        let auto_power_off_disabled_label = auto_sleep_duration_disabled_label.clone();
+        let low_battery_warning_disabled_label = auto_sleep_duration_disabled_label.clone();
 
-        let mut buttons: Vec<(String, Box<dyn View>)> = vec![
+        let mut buttons: Vec<(PowerRow, String, Box<dyn View>)> = vec![
+            (
+                PowerRow::PowerProfile,
+                locale.t("settings-power-power-profile"),
+                Box::new(Select::new(
+                    Point::zero(),
+                    performance_settings.profile as usize,
+                    vec![
+                        locale.t("power-profile-battery-saver"),
+                        locale.t("power-profile-balanced"),
+                        locale.t("power-profile-performance"),
+                    ],
+                    Alignment::Right,
+                )),
+            ),
             (
+                PowerRow::AutoSleepWhenCharging,
                 locale.t("settings-power-auto-sleep-when-charging"),
                 Box::new(Toggle::new(
                     Point::zero(),
@@ -48,6 +91,7 @@ impl Power {
                 )),
             ),
             (
+                PowerRow::AutoSleepDuration,
                 locale.t("settings-power-auto-sleep-duration-minutes"),
                 Box::new(Number::new(
                     Point::zero(),
@@ -66,27 +110,117 @@ impl Power {
                 )),
             ),
             (
+                PowerRow::IdleDim,
+                locale.t("settings-power-idle-dim-seconds"),
+                Box::new(Number::new(
+                    Point::zero(),
+                    power_settings.idle_dim_seconds,
+                    0,
+                    120,
+                    10,
+                    move |x: &i32| {
+                        if *x == 0 {
+                            idle_dim_disabled_label.clone()
+                        } else {
+                            x.to_string()
+                        }
+                    },
+                    Alignment::Right,
+                )),
+            ),
+            (
+                PowerRow::AutoPowerOff,
+                locale.t("settings-power-auto-power-off-minutes"),
+                Box::new(Number::new(
+                    Point::zero(),
+                    power_settings.auto_power_off_minutes,
+                    0,
+                    120,
+                    10,
+                    move |x: &i32| {
+                        if *x == 0 {
+                            auto_power_off_disabled_label.clone()
+                        } else {
+                            x.to_string()
+                        }
+                    },
+                    Alignment::Right,
+                )),
+            ),
+            (
+                PowerRow::PowerButtonAction,
                 locale.t("settings-power-power-button-action"),
                 Box::new(Select::new(
                     Point::zero(),
                     power_settings.power_button_action as usize,
                     vec![
                         locale.t("settings-power-power-button-action-suspend"),
+                        locale.t("settings-power-power-button-action-screenshot"),
                         locale.t("settings-power-power-button-action-shutdown"),
                         locale.t("settings-power-power-button-action-nothing"),
                     ],
                     Alignment::Right,
                 )),
             ),
+            (
+                PowerRow::PowerButtonLongPressAction,
+                locale.t("settings-power-power-button-long-press-action"),
+                Box::new(Select::new(
+                    Point::zero(),
+                    power_settings.power_button_long_press_action as usize,
+                    vec![
+                        locale.t("settings-power-power-button-action-suspend"),
+                        locale.t("settings-power-power-button-action-screenshot"),
+                        locale.t("settings-power-power-button-action-shutdown"),
+                        locale.t("settings-power-power-button-action-nothing"),
+                    ],
+                    Alignment::Right,
+                )),
+            ),
+            (
+                PowerRow::BatteryShutdownThreshold,
+                locale.t("settings-power-battery-shutdown-threshold"),
+                Box::new(Number::new(
+                    Point::zero(),
+                    power_settings.battery_shutdown_threshold,
+                    1,
+                    20,
+                    1,
+                    |x: &i32| format!("{x}%"),
+                    Alignment::Right,
+                )),
+            ),
+            (
+                PowerRow::LowBatteryWarning,
+                locale.t("settings-power-low-battery-warning"),
+                Box::new(Number::new(
+                    Point::zero(),
+                    power_settings.low_battery_warning,
+                    0,
+                    30,
+                    5,
+                    move |x: &i32| {
+                        if *x == 0 {
+                            low_battery_warning_disabled_label.clone()
+                        } else {
+                            format!("{x}%")
+                        }
+                    },
+                    Alignment::Right,
+                )),
+            ),
         ];
         if DefaultPlatform::has_lid() {
             buttons.push((
+                PowerRow::LidCloseAction,
                 locale.t("settings-power-lid-close-action"),
                 Box::new(Select::new(
                     Point::zero(),
                     power_settings.lid_close_action as usize,
                     vec![
                         locale.t("settings-power-power-button-action-suspend"),
+                        locale.t("settings-power-lid-close-action-pause"),
+                        locale.t("settings-power-lid-close-action-save-and-quit"),
                         locale.t("settings-power-power-button-action-shutdown"),
                         locale.t("settings-power-power-button-action-nothing"),
                     ],
@@ -94,7 +228,45 @@ impl Power {
                 )),
             ));
         }
-        let (left, right) = buttons.into_iter().unzip();
+        if DefaultPlatform::has_led() {
+            buttons.extend([
+                (
+                    PowerRow::LedCharging,
+                    locale.t("settings-power-led-charging"),
+                    Box::new(Toggle::new(
+                        Point::zero(),
+                        led_settings.charging,
+                        Alignment::Right,
+                    )) as Box<dyn View>,
+                ),
+                (
+                    PowerRow::LedLowBattery,
+                    locale.t("settings-power-led-low-battery"),
+                    Box::new(Toggle::new(
+                        Point::zero(),
+                        led_settings.low_battery,
+                        Alignment::Right,
+                    )),
+                ),
+                (
+                    PowerRow::LedJobFinished,
+                    locale.t("settings-power-led-job-finished"),
+                    Box::new(Toggle::new(
+                        Point::zero(),
+                        led_settings.job_finished,
+                        Alignment::Right,
+                    )),
+                ),
+            ]);
+        }
+        let mut rows = Vec::with_capacity(buttons.len());
+        let (left, right) = buttons
+            .into_iter()
+            .map(|(row, label, view)| {
+                rows.push(row);
+                (label, view)
+            })
+            .unzip();
 
         let mut list = SettingsList::new(
             Rect::new(
@@ -105,7 +277,7 @@ impl Power {
             ),
             left,
             right,
-            styles.ui_font.size + SELECTION_MARGIN,
+            styles.row_height(),
         );
         if let Some(state) = state {
             list.select(state.selected);
@@ -134,6 +306,9 @@ impl Power {
             res,
             rect,
             power_settings,
+            performance_settings,
+            led_settings,
+            rows,
             list,
             button_hints,
         }
@@ -186,12 +361,54 @@ impl View for Power {
         {
             while let Some(command) = bubble.pop_front() {
                 if let Command::ValueChanged(i, val) = command {
-                    match i {
-                        0 => self.power_settings.auto_sleep_when_charging = val.as_bool().unwrap(),
-                        1 => {
-                            self.power_settings.auto_sleep_duration_minutes = val.as_int().unwrap()
+                    let row = self.rows[i];
+                    match row {
+                        PowerRow::PowerProfile => {
+                            self.performance_settings.profile =
+                                PowerProfile::from_repr(val.as_int().unwrap() as usize)
+                                    .unwrap_or_default();
+                            self.performance_settings.save()?;
+                            // Let alliumd apply the profile
+                            #[cfg(unix)]
+                            std::process::Command::new("pkill")
+                                .arg("-HUP")
+                                .arg("alliumd")
+                                .spawn()?;
+                        }
+                        PowerRow::AutoSleepWhenCharging => {
+                            self.power_settings.auto_sleep_when_charging = val.as_bool().unwrap()
                         }
-                        2 => {
+                        PowerRow::AutoSleepDuration => {
+                            self.power_settings.auto_sleep_duration_minutes = val.as_int().unwrap();
+                            let text = self
+                                .res
+                                .get::<Locale>()
+                                .t("settings-needs-restart-for-effect");
+                            commands
+                                .send(Command::Toast(text, Some(Duration::from_secs(5))))
+                                .await?;
+                        }
+                        PowerRow::IdleDim => {
+                            self.power_settings.idle_dim_seconds = val.as_int().unwrap();
+                            let text = self
+                                .res
+                                .get::<Locale>()
+                                .t("settings-needs-restart-for-effect");
+                            commands
+                                .send(Command::Toast(text, Some(Duration::from_secs(5))))
+                                .await?;
+                        }
+                        PowerRow::AutoPowerOff => {
+                            self.power_settings.auto_power_off_minutes = val.as_int().unwrap();
+                            let text = self
+                                .res
+                                .get::<Locale>()
+                                .t("settings-needs-restart-for-effect");
+                            commands
+                                .send(Command::Toast(text, Some(Duration::from_secs(5))))
+                                .await?;
+                        }
+                        PowerRow::PowerButtonAction => {
                             self.power_settings.power_button_action =
                                 PowerButtonAction::from_repr(val.as_int().unwrap() as usize)
                                     .unwrap_or_default();
@@ -203,10 +420,42 @@ impl View for Power {
                                 ))
                                 .await?;
                         }
-                        3 => {
-                            self.power_settings.lid_close_action =
+                        PowerRow::PowerButtonLongPressAction => {
+                            self.power_settings.power_button_long_press_action =
                                 PowerButtonAction::from_repr(val.as_int().unwrap() as usize)
                                     .unwrap_or_default();
+                            let text = self
+                                .res
+                                .get::<Locale>()
+                                .t("settings-needs-restart-for-effect");
+                            commands
+                                .send(Command::Toast(text, Some(Duration::from_secs(5))))
+                                .await?;
+                        }
+                        PowerRow::BatteryShutdownThreshold => {
+                            self.power_settings.battery_shutdown_threshold = val.as_int().unwrap();
+                            let text = self
+                                .res
+                                .get::<Locale>()
+                                .t("settings-needs-restart-for-effect");
+                            commands
+                                .send(Command::Toast(text, Some(Duration::from_secs(5))))
+                                .await?;
+                        }
+                        PowerRow::LowBatteryWarning => {
+                            self.power_settings.low_battery_warning = val.as_int().unwrap();
+                            let text = self
+                                .res
+                                .get::<Locale>()
+                                .t("settings-needs-restart-for-effect");
+                            commands
+                                .send(Command::Toast(text, Some(Duration::from_secs(5))))
+                                .await?;
+                        }
+                        PowerRow::LidCloseAction => {
+                            self.power_settings.lid_close_action =
+                                LidCloseAction::from_repr(val.as_int().unwrap() as usize)
+                                    .unwrap_or_default();
                             let locale = self.res.get::<Locale>();
                             commands
                                 .send(Command::Toast(
@@ -215,7 +464,18 @@ impl View for Power {
                                 ))
                                 .await?;
                         }
-                        _ => unreachable!("Invalid index"),
+                        PowerRow::LedCharging
+                        | PowerRow::LedLowBattery
+                        | PowerRow::LedJobFinished => {
+                            let val = val.as_bool().unwrap();
+                            match row {
+                                PowerRow::LedCharging => self.led_settings.charging = val,
+                                PowerRow::LedLowBattery => self.led_settings.low_battery = val,
+                                _ => self.led_settings.job_finished = val,
+                            }
+                            self.led_settings.save()?;
+                            continue;
+                        }
                     }
                     self.power_settings.save()?;
                 }
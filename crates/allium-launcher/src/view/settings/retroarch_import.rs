@@ -0,0 +1,314 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use common::command::Command;
+use common::display::Display as DisplayTrait;
+use common::geom::{Alignment, Point, Rect};
+use common::locale::Locale;
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::retroarch_import::{self, FileChange};
+use common::stylesheet::Stylesheet;
+use common::view::{ButtonHint, ButtonIcon, Row, ScrollList, View};
+use log::warn;
+use tokio::sync::mpsc::Sender;
+use tokio::sync::oneshot;
+
+use crate::view::settings::{ChildState, SettingsChild};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Scanning,
+    Ready,
+    Importing,
+    /// Imported this many files.
+    Imported(usize),
+    Failed,
+}
+
+/// Previews and imports core options, overrides, and remaps from another RetroArch install.
+pub struct RetroArchImport {
+    rect: Rect,
+    res: Resources,
+    list: ScrollList,
+    button_hints: Row<ButtonHint<String>>,
+    changes: Vec<FileChange>,
+    status: Status,
+    preview_rx: Option<oneshot::Receiver<Result<Vec<FileChange>>>>,
+    import_rx: Option<oneshot::Receiver<Result<()>>>,
+    dirty: bool,
+}
+
+impl RetroArchImport {
+    pub fn new(rect: Rect, res: Resources, state: Option<ChildState>) -> Self {
+        let Rect { x, y, w, h } = rect;
+
+        let locale = res.get::<Locale>();
+        let styles = res.get::<Stylesheet>();
+
+        let mut list = ScrollList::new(
+            Rect::new(
+                x + 12,
+                y + 8,
+                w - 24,
+                h - 8 - ButtonIcon::diameter(&styles) - 8,
+            ),
+            Vec::new(),
+            Alignment::Left,
+            styles.row_height(),
+        );
+        if let Some(state) = state {
+            list.select(state.selected);
+        }
+
+        let button_hints = Row::new(
+            Point::new(
+                rect.x + rect.w as i32 - 12,
+                rect.y + rect.h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            vec![
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::A,
+                    locale.t("button-select"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::B,
+                    locale.t("button-back"),
+                    Alignment::Right,
+                ),
+            ],
+            Alignment::Right,
+            12,
+        );
+
+        drop(locale);
+        drop(styles);
+
+        let mut this = Self {
+            rect,
+            res,
+            list,
+            button_hints,
+            changes: Vec::new(),
+            status: Status::Scanning,
+            preview_rx: None,
+            import_rx: None,
+            dirty: true,
+        };
+        this.scan();
+        this
+    }
+
+    fn scan(&mut self) {
+        let (tx, rx) = oneshot::channel();
+        tokio::task::spawn_blocking(move || {
+            tx.send(retroarch_import::preview()).ok();
+        });
+        self.preview_rx = Some(rx);
+        self.update_list();
+    }
+
+    fn import(&mut self) {
+        if self.status != Status::Ready || self.changes.is_empty() {
+            return;
+        }
+        let changes = self.changes.clone();
+        let (tx, rx) = oneshot::channel();
+        tokio::task::spawn_blocking(move || {
+            tx.send(retroarch_import::apply(&changes)).ok();
+        });
+        self.import_rx = Some(rx);
+        self.status = Status::Importing;
+        self.update_list();
+    }
+
+    fn update_list(&mut self) {
+        let locale = self.res.get::<Locale>();
+
+        let mut items = vec![locale.t("settings-retroarch-import-apply")];
+        let mut details = vec![match self.status {
+            Status::Scanning => locale.t("settings-retroarch-import-scanning"),
+            Status::Importing => locale.t("settings-retroarch-import-importing"),
+            Status::Failed => locale.t("settings-retroarch-import-failed"),
+            Status::Imported(count) => {
+                let mut map = HashMap::new();
+                map.insert("count".into(), count.into());
+                locale.ta("settings-retroarch-import-imported", &map)
+            }
+            Status::Ready if self.changes.is_empty() => {
+                locale.t("settings-retroarch-import-nothing")
+            }
+            Status::Ready => {
+                let mut map = HashMap::new();
+                map.insert("count".into(), self.changes.len().into());
+                locale.ta("settings-retroarch-import-files", &map)
+            }
+        }];
+
+        for change in &self.changes {
+            items.push(change.name.display().to_string());
+            details.push(if change.is_new {
+                locale.t("settings-retroarch-import-new")
+            } else {
+                let mut map = HashMap::new();
+                map.insert("count".into(), change.keys.len().into());
+                locale.ta("settings-retroarch-import-changes", &map)
+            });
+            if change.is_new {
+                continue;
+            }
+            for key in &change.keys {
+                items.push(format!("  {}", key.key));
+                details.push(match &key.old {
+                    Some(old) => format!("{} -> {}", old, key.new),
+                    None => key.new.clone(),
+                });
+            }
+        }
+
+        drop(locale);
+        self.list.set_items_with_details(items, details, true);
+        self.dirty = true;
+    }
+}
+
+#[async_trait(?Send)]
+impl View for RetroArchImport {
+    fn update(&mut self, _dt: Duration) {
+        if let Some(rx) = self.preview_rx.as_mut() {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.preview_rx = None;
+                    match result {
+                        Ok(changes) => {
+                            self.changes = changes;
+                            if self.status == Status::Scanning {
+                                self.status = Status::Ready;
+                            }
+                        }
+                        Err(e) => {
+                            warn!("failed to preview RetroArch import: {}", e);
+                            self.status = Status::Failed;
+                        }
+                    }
+                    self.update_list();
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => {
+                    self.preview_rx = None;
+                    self.status = Status::Failed;
+                    self.update_list();
+                }
+            }
+        }
+
+        if let Some(rx) = self.import_rx.as_mut() {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.import_rx = None;
+                    match result {
+                        Ok(()) => {
+                            self.status = Status::Imported(self.changes.len());
+                            // Rescan so that only what's left to import is shown
+                            self.scan();
+                        }
+                        Err(e) => {
+                            warn!("failed to import RetroArch configs: {}", e);
+                            self.status = Status::Failed;
+                            self.update_list();
+                        }
+                    }
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => {
+                    self.import_rx = None;
+                    self.status = Status::Failed;
+                    self.update_list();
+                }
+            }
+        }
+    }
+
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        let mut drawn = false;
+
+        if self.dirty {
+            display.load(self.rect)?;
+            self.list.set_should_draw();
+            self.button_hints.set_should_draw();
+            self.dirty = false;
+            drawn = true;
+        }
+
+        drawn |= self.list.should_draw() && self.list.draw(display, styles)?;
+        drawn |= self.button_hints.should_draw() && self.button_hints.draw(display, styles)?;
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.dirty || self.list.should_draw() || self.button_hints.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.dirty = true;
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        match event {
+            KeyEvent::Pressed(Key::A) => {
+                if self.list.selected() == 0 {
+                    self.import();
+                }
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::B) => {
+                if self.import_rx.is_none() {
+                    bubble.push_back(Command::CloseView);
+                }
+                Ok(true)
+            }
+            _ => self.list.handle_key_event(event, commands, bubble).await,
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.list, &self.button_hints]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![&mut self.list, &mut self.button_hints]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
+
+impl SettingsChild for RetroArchImport {
+    fn save(&self) -> ChildState {
+        ChildState {
+            selected: self.list.selected(),
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use std::collections::VecDeque;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use common::audio::{AudioSettings, VolumeCurve};
+use common::command::Command;
+use common::display::Display as DisplayTrait;
+use common::geom::{Alignment, Point, Rect};
+use common::locale::Locale;
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::stylesheet::Stylesheet;
+use common::view::{
+    ButtonHint, ButtonIcon, Number, Percentage, Row, Select, SettingsList, Toggle, View,
+};
+use tokio::sync::mpsc::Sender;
+
+use crate::view::settings::{ChildState, SettingsChild};
+
+pub struct Sound {
+    rect: Rect,
+    settings: AudioSettings,
+    list: SettingsList,
+    button_hints: Row<ButtonHint<String>>,
+}
+
+impl Sound {
+    pub fn new(rect: Rect, res: Resources, state: Option<ChildState>) -> Self {
+        let Rect { x, y, w, h } = rect;
+
+        let settings = AudioSettings::load().unwrap_or_default();
+
+        let locale = res.get::<Locale>();
+        let styles = res.get::<Stylesheet>();
+
+        let balance_center_label = locale.t("settings-sound-balance-center");
+        let balance_left_label = locale.t("settings-sound-balance-left");
+        let balance_right_label = locale.t("settings-sound-balance-right");
+
+        let mut list = SettingsList::new(
+            Rect::new(
+                x + 12,
+                y + 8,
+                w - 24,
+                h - 8 - ButtonIcon::diameter(&styles) - 8,
+            ),
+            vec![
+                locale.t("settings-sound-mono"),
+                locale.t("settings-sound-balance"),
+                locale.t("settings-sound-volume-curve"),
+                locale.t("settings-sound-max-volume"),
+            ],
+            vec![
+                Box::new(Toggle::new(Point::zero(), settings.mono, Alignment::Right)),
+                Box::new(Number::new(
+                    Point::zero(),
+                    i32::from(settings.balance),
+                    -10,
+                    10,
+                    1,
+                    move |x: &i32| match *x {
+                        0 => balance_center_label.clone(),
+                        x if x < 0 => format!("{balance_left_label}{}", -x),
+                        x => format!("{balance_right_label}{x}"),
+                    },
+                    Alignment::Right,
+                )),
+                Box::new(Select::new(
+                    Point::zero(),
+                    settings.volume_curve as usize,
+                    vec![
+                        locale.t("settings-sound-volume-curve-linear"),
+                        locale.t("settings-sound-volume-curve-smooth"),
+                        locale.t("settings-sound-volume-curve-logarithmic"),
+                    ],
+                    Alignment::Right,
+                )),
+                Box::new(Percentage::new(
+                    Point::zero(),
+                    settings.max_volume,
+                    10,
+                    100,
+                    Alignment::Right,
+                )),
+            ],
+            styles.row_height(),
+        );
+        if let Some(state) = state {
+            list.select(state.selected);
+        }
+
+        let button_hints = Row::new(
+            Point::new(
+                rect.x + rect.w as i32 - 12,
+                rect.y + rect.h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            vec![ButtonHint::new(
+                res.clone(),
+                Point::zero(),
+                Key::B,
+                locale.t("button-back"),
+                Alignment::Right,
+            )],
+            Alignment::Right,
+            12,
+        );
+
+        Self {
+            rect,
+            settings,
+            list,
+            button_hints,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl View for Sound {
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        let mut drawn = false;
+
+        drawn |= self.list.should_draw() && self.list.draw(display, styles)?;
+
+        if self.button_hints.should_draw() {
+            display.load(Rect::new(
+                self.rect.x,
+                self.rect.y + self.rect.h as i32 - ButtonIcon::diameter(styles) as i32 - 8,
+                self.rect.w,
+                ButtonIcon::diameter(styles),
+            ))?;
+            drawn |= self.button_hints.draw(display, styles)?;
+        }
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.list.should_draw() || self.button_hints.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.list.set_should_draw();
+        self.button_hints.set_should_draw();
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        if self
+            .list
+            .handle_key_event(event, commands.clone(), bubble)
+            .await?
+        {
+            while let Some(command) = bubble.pop_front() {
+                if let Command::ValueChanged(i, val) = command {
+                    match i {
+                        0 => self.settings.mono = val.as_bool().unwrap(),
+                        1 => self.settings.balance = val.as_int().unwrap() as i8,
+                        2 => {
+                            self.settings.volume_curve =
+                                VolumeCurve::from_repr(val.as_int().unwrap() as usize)
+                                    .unwrap_or_default()
+                        }
+                        3 => self.settings.max_volume = val.as_int().unwrap(),
+                        _ => unreachable!("Invalid index"),
+                    }
+
+                    commands
+                        .send(Command::SaveAudioSettings(Box::new(self.settings.clone())))
+                        .await?;
+                }
+            }
+            return Ok(true);
+        }
+
+        match event {
+            KeyEvent::Pressed(Key::B) => {
+                bubble.push_back(Command::CloseView);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.list, &self.button_hints]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![&mut self.list, &mut self.button_hints]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
+
+impl SettingsChild for Sound {
+    fn save(&self) -> ChildState {
+        ChildState {
+            selected: self.list.selected(),
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use common::command::Command;
+use common::display::Display as DisplayTrait;
+use common::geom::{Alignment, Point, Rect, Size};
+use common::locale::Locale;
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::splash::SplashSettings;
+use common::stylesheet::Stylesheet;
+use common::view::{ButtonHint, ButtonIcon, Image, ImageMode, Row, Select, SettingsList, View};
+use log::warn;
+use tokio::sync::mpsc::Sender;
+
+use crate::view::settings::{ChildState, SettingsChild};
+
+pub struct BootSplash {
+    rect: Rect,
+    res: Resources,
+    settings: SplashSettings,
+    images: Vec<PathBuf>,
+    list: SettingsList,
+    preview: Image,
+    button_hints: Row<ButtonHint<String>>,
+}
+
+impl BootSplash {
+    pub fn new(rect: Rect, res: Resources, state: Option<ChildState>) -> Self {
+        let Rect { x, y, w, h } = rect;
+
+        let settings = SplashSettings::load().unwrap_or_default();
+        let images = SplashSettings::available_images().unwrap_or_else(|e| {
+            warn!("failed to list splash images: {}", e);
+            Vec::new()
+        });
+
+        let locale = res.get::<Locale>();
+        let styles = res.get::<Stylesheet>();
+
+        let row_height = styles.row_height();
+        let mut list = SettingsList::new(
+            Rect::new(x + 12, y + 8, w - 24, row_height),
+            vec![locale.t("settings-boot-splash-image")],
+            vec![Self::select(&locale, &images, &settings)],
+            row_height,
+        );
+        if let Some(state) = state {
+            list.select(state.selected);
+        }
+
+        let preview_y = y + 8 + row_height as i32 + 8;
+        let mut preview = Image::empty(
+            Rect::new(
+                x + 12,
+                preview_y,
+                w - 24,
+                (y + h as i32 - ButtonIcon::diameter(&styles) as i32 - 16 - preview_y) as u32,
+            ),
+            ImageMode::Contain,
+        );
+        preview.set_alignment(Alignment::Center);
+        preview.set_path(settings.image.clone());
+
+        let button_hints = Row::new(
+            Point::new(
+                rect.x + rect.w as i32 - 12,
+                rect.y + rect.h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            vec![ButtonHint::new(
+                res.clone(),
+                Point::zero(),
+                Key::B,
+                locale.t("button-back"),
+                Alignment::Right,
+            )],
+            Alignment::Right,
+            12,
+        );
+
+        drop(locale);
+        drop(styles);
+
+        Self {
+            rect,
+            res,
+            settings,
+            images,
+            list,
+            preview,
+            button_hints,
+        }
+    }
+
+    fn select(locale: &Locale, images: &[PathBuf], settings: &SplashSettings) -> Box<dyn View> {
+        let selected = settings
+            .image
+            .as_ref()
+            .and_then(|image| images.iter().position(|i| i == image))
+            .map_or(0, |i| i + 1);
+        let mut options = vec![locale.t("settings-boot-splash-none")];
+        options.extend(images.iter().map(|image| {
+            image
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default()
+        }));
+        Box::new(Select::new(
+            Point::zero(),
+            selected,
+            options,
+            Alignment::Right,
+        ))
+    }
+
+    async fn set_image(&mut self, index: usize, commands: &Sender<Command>) -> Result<()> {
+        let image = index.checked_sub(1).map(|i| self.images[i].clone());
+        let size = *self.res.get::<Size>();
+        if let Err(e) = self.settings.set_image(image, size) {
+            warn!("failed to set boot splash: {}", e);
+            let text = {
+                let locale = self.res.get::<Locale>();
+                let mut map = HashMap::new();
+                map.insert("width".into(), size.w.into());
+                map.insert("height".into(), size.h.into());
+                // Revert to the previous image
+                self.list
+                    .set_right(0, Self::select(&locale, &self.images, &self.settings));
+                locale.ta("settings-boot-splash-invalid", &map)
+            };
+            commands
+                .send(Command::Toast(text, Some(Duration::from_secs(5))))
+                .await?;
+        }
+        self.preview.set_path(self.settings.image.clone());
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl View for BootSplash {
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        let mut drawn = false;
+
+        drawn |= self.list.should_draw() && self.list.draw(display, styles)?;
+
+        drawn |= self.preview.should_draw() && self.preview.draw(display, styles)?;
+
+        if self.button_hints.should_draw() {
+            display.load(Rect::new(
+                self.rect.x,
+                self.rect.y + self.rect.h as i32 - ButtonIcon::diameter(styles) as i32 - 8,
+                self.rect.w,
+                ButtonIcon::diameter(styles),
+            ))?;
+            drawn |= self.button_hints.draw(display, styles)?;
+        }
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.list.should_draw() || self.preview.should_draw() || self.button_hints.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.list.set_should_draw();
+        self.preview.set_should_draw();
+        self.button_hints.set_should_draw();
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        if self
+            .list
+            .handle_key_event(event, commands.clone(), bubble)
+            .await?
+        {
+            while let Some(command) = bubble.pop_front() {
+                if let Command::ValueChanged(i, val) = command {
+                    match i {
+                        0 => {
+                            self.set_image(val.as_int().unwrap() as usize, &commands)
+                                .await?
+                        }
+                        _ => unreachable!("Invalid index"),
+                    }
+                }
+            }
+            return Ok(true);
+        }
+
+        match event {
+            KeyEvent::Pressed(Key::B) => {
+                bubble.push_back(Command::CloseView);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.list, &self.preview, &self.button_hints]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![&mut self.list, &mut self.preview, &mut self.button_hints]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
+
+impl SettingsChild for BootSplash {
+    fn save(&self) -> ChildState {
+        ChildState {
+            selected: self.list.selected(),
+        }
+    }
+}
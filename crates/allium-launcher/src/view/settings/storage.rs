@@ -0,0 +1,479 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use common::command::Command;
+use common::constants::{ALLIUM_GAMES_DIR, ALLIUM_READ_SPEED_TEST_FILE, ALLIUM_SD_ROOT};
+use common::display::Display as DisplayTrait;
+use common::geom::{Alignment, Point, Rect};
+use common::locale::Locale;
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::stylesheet::Stylesheet;
+use common::view::{ButtonHint, ButtonIcon, Row, ScrollList, View};
+use log::warn;
+use rand::Rng;
+use sysinfo::Disks;
+use tokio::sync::mpsc::{self, Sender, UnboundedReceiver, error::TryRecvError};
+use tokio::sync::oneshot;
+
+use crate::consoles::ConsoleMapper;
+use crate::view::settings::{ChildState, SettingsChild};
+
+/// Size of the file written to test read speeds.
+const READ_SPEED_TEST_SIZE: usize = 32 * 1024 * 1024;
+/// Size of each read in the random read test, like the small reads of loading a game.
+const RANDOM_READ_SIZE: usize = 4 * 1024;
+const RANDOM_READ_COUNT: usize = 1024;
+/// Below these speeds, in bytes per second, games take noticeably longer to load.
+const SLOW_SEQUENTIAL_READ: f64 = 8.0 * 1024.0 * 1024.0;
+const SLOW_RANDOM_READ: f64 = 512.0 * 1024.0;
+
+/// Read speeds of the SD card, in bytes per second.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ReadSpeed {
+    sequential: f64,
+    random: f64,
+}
+
+impl ReadSpeed {
+    fn is_slow(&self) -> bool {
+        self.sequential < SLOW_SEQUENTIAL_READ || self.random < SLOW_RANDOM_READ
+    }
+}
+
+/// Shows the free space on the SD card, and how much space each console and folder uses.
+pub struct Storage {
+    rect: Rect,
+    res: Resources,
+    /// Free space and SD card size, in bytes.
+    space: Option<(u64, u64)>,
+    /// Names of the measured folders.
+    labels: Vec<String>,
+    /// Sizes of the measured folders, sorted largest first once measured.
+    sizes: Vec<(String, u64)>,
+    list: ScrollList,
+    button_hints: Row<ButtonHint<String>>,
+    /// Receives each folder's size as it is measured.
+    size_rx: Option<UnboundedReceiver<u64>>,
+    /// Result of the last read speed test, or None if it failed.
+    speed: Option<Option<ReadSpeed>>,
+    speed_rx: Option<oneshot::Receiver<Result<ReadSpeed>>>,
+    /// Index of the read speed test row.
+    speed_row: usize,
+    dirty: bool,
+}
+
+impl Storage {
+    pub fn new(rect: Rect, res: Resources, state: Option<ChildState>) -> Self {
+        let Rect { x, y, w, h } = rect;
+
+        let locale = res.get::<Locale>();
+        let styles = res.get::<Stylesheet>();
+
+        let mut list = ScrollList::new(
+            Rect::new(
+                x + 12,
+                y + 8,
+                w - 24,
+                h - 8 - ButtonIcon::diameter(&styles) - 8,
+            ),
+            Vec::new(),
+            Alignment::Left,
+            styles.row_height(),
+        );
+        if let Some(state) = state {
+            list.select(state.selected);
+        }
+
+        let button_hints = Row::new(
+            Point::new(
+                rect.x + rect.w as i32 - 12,
+                rect.y + rect.h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            vec![
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::A,
+                    locale.t("button-select"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::B,
+                    locale.t("button-back"),
+                    Alignment::Right,
+                ),
+            ],
+            Alignment::Right,
+            12,
+        );
+
+        drop(locale);
+        drop(styles);
+
+        let (labels, dirs) = folders(&res.get::<ConsoleMapper>());
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::task::spawn_blocking(move || {
+            for dir in dirs {
+                if tx.send(dir_size(&dir)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut this = Self {
+            rect,
+            res,
+            space: sd_space(),
+            labels,
+            sizes: Vec::new(),
+            list,
+            button_hints,
+            size_rx: Some(rx),
+            speed: None,
+            speed_rx: None,
+            speed_row: 0,
+            dirty: true,
+        };
+        this.update_list();
+        this
+    }
+
+    fn update_list(&mut self) {
+        let locale = self.res.get::<Locale>();
+
+        let mut items = Vec::with_capacity(self.sizes.len() + 3);
+        let mut details = Vec::with_capacity(self.sizes.len() + 3);
+        if let Some((free, total)) = self.space {
+            items.push(locale.t("settings-storage-used"));
+            details.push(format!(
+                "{} / {}",
+                format_size(total - free),
+                format_size(total)
+            ));
+            items.push(locale.t("settings-storage-free"));
+            details.push(format_size(free));
+        }
+        self.speed_row = items.len();
+        items.push(locale.t("settings-storage-test-speed"));
+        details.push(if self.speed_rx.is_some() {
+            locale.t("settings-storage-testing")
+        } else if self.speed == Some(None) {
+            locale.t("settings-storage-test-failed")
+        } else {
+            String::new()
+        });
+        if let Some(Some(speed)) = self.speed {
+            items.push(locale.t("settings-storage-sequential-read"));
+            details.push(format_speed(speed.sequential));
+            items.push(locale.t("settings-storage-random-read"));
+            details.push(format_speed(speed.random));
+            if speed.is_slow() {
+                items.push(locale.t("settings-storage-slow"));
+                details.push(String::new());
+            }
+        }
+        if self.size_rx.is_some() {
+            let mut map = HashMap::new();
+            map.insert("done".into(), self.sizes.len().into());
+            map.insert("total".into(), self.labels.len().into());
+            items.push(locale.ta("settings-storage-calculating", &map));
+            details.push(String::new());
+        }
+        for (label, size) in &self.sizes {
+            items.push(label.clone());
+            details.push(format_size(*size));
+        }
+
+        self.list.set_items_with_details(items, details, true);
+        self.dirty = true;
+    }
+
+    fn test_read_speed(&mut self) {
+        if self.speed_rx.is_some() {
+            return;
+        }
+        let (tx, rx) = oneshot::channel();
+        tokio::task::spawn_blocking(move || {
+            let result = measure_read_speed(&ALLIUM_READ_SPEED_TEST_FILE);
+            if let Err(e) = fs::remove_file(ALLIUM_READ_SPEED_TEST_FILE.as_path()) {
+                warn!("failed to remove read speed test file: {}", e);
+            }
+            tx.send(result).ok();
+        });
+        self.speed_rx = Some(rx);
+        self.update_list();
+    }
+}
+
+/// Writes a test file, then times reading it in order and at random offsets.
+fn measure_read_speed(path: &Path) -> Result<ReadSpeed> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut rng = rand::rng();
+    let mut buf = vec![0u8; 1024 * 1024];
+    {
+        let mut file = File::create(path)?;
+        for _ in 0..READ_SPEED_TEST_SIZE / buf.len() {
+            rng.fill(&mut buf[..]);
+            file.write_all(&buf)?;
+        }
+        file.sync_all()?;
+    }
+
+    drop_caches();
+    let mut file = File::open(path)?;
+    let start = Instant::now();
+    while file.read(&mut buf)? > 0 {}
+    let sequential = READ_SPEED_TEST_SIZE as f64 / start.elapsed().as_secs_f64();
+
+    drop_caches();
+    let mut file = File::open(path)?;
+    let blocks = (READ_SPEED_TEST_SIZE / RANDOM_READ_SIZE) as u64;
+    let buf = &mut buf[..RANDOM_READ_SIZE];
+    let start = Instant::now();
+    for _ in 0..RANDOM_READ_COUNT {
+        let offset = rng.random_range(0..blocks) * RANDOM_READ_SIZE as u64;
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(buf)?;
+    }
+    let random = (RANDOM_READ_COUNT * RANDOM_READ_SIZE) as f64 / start.elapsed().as_secs_f64();
+
+    if !sequential.is_finite() || !random.is_finite() {
+        return Err(anyhow!("read speed test finished too quickly to measure"));
+    }
+    Ok(ReadSpeed { sequential, random })
+}
+
+/// Drops the page cache, so that the test file is read from the SD card instead of memory.
+fn drop_caches() {
+    #[cfg(feature = "miyoo")]
+    if let Err(e) = fs::write("/proc/sys/vm/drop_caches", "3") {
+        warn!("failed to drop caches: {}", e);
+    }
+}
+
+/// Lists the folders to measure: each console folder, and the other folders on the SD card.
+fn folders(console_mapper: &ConsoleMapper) -> (Vec<String>, Vec<PathBuf>) {
+    let mut labels = Vec::new();
+    let mut dirs = Vec::new();
+
+    for dir in subdirs(&ALLIUM_GAMES_DIR) {
+        let name = dir.file_name().unwrap_or_default().to_string_lossy();
+        labels.push(
+            console_mapper
+                .get_console(&dir)
+                .map_or_else(|| name.to_string(), |c| c.name.clone()),
+        );
+        dirs.push(dir);
+    }
+
+    for dir in subdirs(&ALLIUM_SD_ROOT) {
+        if dir == *ALLIUM_GAMES_DIR {
+            continue;
+        }
+        labels.push(format!(
+            "{}/",
+            dir.file_name().unwrap_or_default().to_string_lossy()
+        ));
+        dirs.push(dir);
+    }
+
+    (labels, dirs)
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<_> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|entry| entry.path())
+        .collect();
+    dirs.sort_unstable();
+    dirs
+}
+
+/// Total size of the files in a directory, recursively. Symlinks are not followed.
+fn dir_size(dir: &Path) -> u64 {
+    let mut size = 0;
+    let mut queue = vec![dir.to_path_buf()];
+    while let Some(dir) = queue.pop() {
+        for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                queue.push(entry.path());
+            } else if metadata.is_file() {
+                size += metadata.len();
+            }
+        }
+    }
+    size
+}
+
+/// Free space and size of the disk holding the SD card root.
+fn sd_space() -> Option<(u64, u64)> {
+    let disks = Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .filter(|disk| ALLIUM_SD_ROOT.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| (disk.available_space(), disk.total_space()))
+}
+
+fn format_speed(bytes_per_sec: f64) -> String {
+    format!("{:.1} MB/s", bytes_per_sec / (1024.0 * 1024.0))
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if size < 10.0 {
+        format!("{:.1} {}", size, UNITS[unit])
+    } else {
+        format!("{:.0} {}", size, UNITS[unit])
+    }
+}
+
+#[async_trait(?Send)]
+impl View for Storage {
+    fn update(&mut self, _dt: Duration) {
+        if let Some(rx) = self.speed_rx.as_mut() {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.speed_rx = None;
+                    self.speed = Some(
+                        result
+                            .map_err(|e| warn!("failed to test read speed: {}", e))
+                            .ok(),
+                    );
+                    self.update_list();
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => {
+                    self.speed_rx = None;
+                    self.update_list();
+                }
+            }
+        }
+
+        let Some(rx) = self.size_rx.as_mut() else {
+            return;
+        };
+        let mut changed = false;
+        loop {
+            match rx.try_recv() {
+                Ok(size) => {
+                    let label = self.labels[self.sizes.len()].clone();
+                    self.sizes.push((label, size));
+                    changed = true;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.size_rx = None;
+                    self.sizes.sort_by_key(|(_, size)| Reverse(*size));
+                    changed = true;
+                    break;
+                }
+            }
+        }
+        if changed {
+            self.update_list();
+        }
+    }
+
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        let mut drawn = false;
+
+        if self.dirty {
+            display.load(self.rect)?;
+            self.list.set_should_draw();
+            self.button_hints.set_should_draw();
+            self.dirty = false;
+            drawn = true;
+        }
+
+        drawn |= self.list.should_draw() && self.list.draw(display, styles)?;
+        drawn |= self.button_hints.should_draw() && self.button_hints.draw(display, styles)?;
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.dirty || self.list.should_draw() || self.button_hints.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.dirty = true;
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        match event {
+            KeyEvent::Pressed(Key::A) => {
+                if self.list.selected() == self.speed_row {
+                    self.test_read_speed();
+                }
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::B) => {
+                bubble.push_back(Command::CloseView);
+                Ok(true)
+            }
+            _ => self.list.handle_key_event(event, commands, bubble).await,
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.list, &self.button_hints]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![&mut self.list, &mut self.button_hints]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
+
+impl SettingsChild for Storage {
+    fn save(&self) -> ChildState {
+        ChildState {
+            selected: self.list.selected(),
+        }
+    }
+}
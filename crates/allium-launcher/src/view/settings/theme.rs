@@ -1,28 +1,38 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::path::PathBuf;
+use std::time::Duration;
 
 use anyhow::Result;
 use async_trait::async_trait;
 use common::command::Command;
-use common::constants::SELECTION_MARGIN;
+use common::constants::ALLIUM_THEMES_DIR;
+use common::display::Display;
 use common::geom::{Alignment, Point, Rect};
 use common::locale::Locale;
 use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
 use common::resources::Resources;
-use common::stylesheet::{Stylesheet, StylesheetFont};
+use common::stylesheet::{ButtonHintStyle, SelectionStyle, Stylesheet, StylesheetFont};
 use common::view::{
-    ButtonHint, ButtonIcon, ColorPicker, Number, Percentage, Row, Select, SettingsList, Toggle,
-    View,
+    Button, ButtonHint, ButtonIcon, ColorPicker, Label, Number, Percentage, Row, Select,
+    SettingsList, Toggle, View,
 };
+use log::warn;
 use tokio::sync::mpsc::Sender;
 
 use crate::view::settings::{ChildState, SettingsChild};
 
 pub struct Theme {
     rect: Rect,
+    res: Resources,
     stylesheet: Stylesheet,
     fonts: Vec<PathBuf>,
+    backgrounds: Vec<PathBuf>,
+    packs: Vec<PathBuf>,
     list: SettingsList,
+    preview: Label<String>,
+    /// Font being previewed while its Select is being edited.
+    preview_font: Option<StylesheetFont>,
+    preview_dirty: bool,
     button_hints: Row<ButtonHint<String>>,
 }
 
@@ -46,6 +56,37 @@ impl Theme {
             })
             .collect();
 
+        let mut backgrounds = Stylesheet::available_background_images().unwrap_or_default();
+        if let Some(image) = &stylesheet.background_image
+            && !backgrounds.contains(image)
+        {
+            backgrounds.push(image.clone());
+        }
+        let mut background_names = vec![locale.t("settings-theme-background-image-none")];
+        background_names.extend(backgrounds.iter().map(|p| {
+            p.file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default()
+        }));
+        let background = stylesheet
+            .background_image
+            .as_ref()
+            .and_then(|image| backgrounds.iter().position(|p| p == image))
+            .map_or(0, |i| i + 1);
+
+        let packs = Stylesheet::available_packs().unwrap_or_default();
+        let mut pack_names = vec![locale.t("settings-theme-theme-pack-custom")];
+        pack_names.extend(packs.iter().map(|p| {
+            p.file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default()
+        }));
+        let pack = stylesheet
+            .theme_pack
+            .as_ref()
+            .and_then(|name| pack_names.iter().skip(1).position(|n| n == name))
+            .map_or(0, |i| i + 1);
+
         let mut list = SettingsList::new(
             Rect::new(
                 x + 12,
@@ -76,6 +117,19 @@ impl Theme {
                 locale.t("settings-theme-button-b-color"),
                 locale.t("settings-theme-button-x-color"),
                 locale.t("settings-theme-button-y-color"),
+                locale.t("settings-theme-ui-sounds"),
+                locale.t("settings-theme-background-image"),
+                locale.t("settings-theme-background-dim"),
+                locale.t("settings-theme-border-radius"),
+                locale.t("settings-theme-selection-style"),
+                locale.t("settings-theme-row-spacing"),
+                locale.t("settings-theme-button-hint-style"),
+                locale.t("settings-theme-reduce-motion"),
+                locale.t("settings-theme-marquee-speed"),
+                locale.t("settings-theme-marquee-delay"),
+                locale.t("settings-theme-marquee-selected-only"),
+                locale.t("settings-theme-theme-pack"),
+                locale.t("settings-theme-export-theme-pack"),
             ],
             vec![
                 Box::new(Toggle::new(
@@ -220,13 +274,115 @@ impl Theme {
                     stylesheet.button_y_color,
                     Alignment::Right,
                 )),
+                Box::new(Toggle::new(
+                    Point::zero(),
+                    stylesheet.ui_sounds,
+                    Alignment::Right,
+                )),
+                Box::new(Select::new(
+                    Point::zero(),
+                    background,
+                    background_names,
+                    Alignment::Right,
+                )),
+                Box::new(Percentage::new(
+                    Point::zero(),
+                    stylesheet.background_dim as i32,
+                    0,
+                    100,
+                    Alignment::Right,
+                )),
+                Box::new(Percentage::new(
+                    Point::zero(),
+                    stylesheet.border_radius as i32,
+                    0,
+                    100,
+                    Alignment::Right,
+                )),
+                Box::new(Select::new(
+                    Point::zero(),
+                    stylesheet.selection_style as usize,
+                    vec![
+                        locale.t("settings-theme-selection-style-pill"),
+                        locale.t("settings-theme-selection-style-underline"),
+                    ],
+                    Alignment::Right,
+                )),
+                Box::new(Number::new(
+                    Point::zero(),
+                    stylesheet.row_spacing as i32,
+                    0,
+                    32,
+                    2,
+                    |px| format!("{}px", px),
+                    Alignment::Right,
+                )),
+                Box::new(Select::new(
+                    Point::zero(),
+                    stylesheet.button_hint_style as usize,
+                    vec![
+                        locale.t("settings-theme-button-hint-style-filled"),
+                        locale.t("settings-theme-button-hint-style-outline"),
+                    ],
+                    Alignment::Right,
+                )),
+                Box::new(Toggle::new(
+                    Point::zero(),
+                    stylesheet.reduce_motion,
+                    Alignment::Right,
+                )),
+                Box::new(Number::new(
+                    Point::zero(),
+                    stylesheet.marquee_speed as i32,
+                    1,
+                    30,
+                    1,
+                    i32::to_string,
+                    Alignment::Right,
+                )),
+                Box::new(Number::new(
+                    Point::zero(),
+                    stylesheet.marquee_delay as i32,
+                    0,
+                    5000,
+                    250,
+                    |ms| format!("{}ms", ms),
+                    Alignment::Right,
+                )),
+                Box::new(Toggle::new(
+                    Point::zero(),
+                    stylesheet.marquee_selected_only,
+                    Alignment::Right,
+                )),
+                Box::new(Select::new(
+                    Point::zero(),
+                    pack,
+                    pack_names,
+                    Alignment::Right,
+                )),
+                Box::new(Button::new(Label::new(
+                    Point::zero(),
+                    locale.t("settings-theme-export-theme-pack-button"),
+                    Alignment::Right,
+                    None,
+                ))),
             ],
-            res.get::<Stylesheet>().ui_font.size + SELECTION_MARGIN,
+            res.get::<Stylesheet>().row_height(),
         );
         if let Some(state) = state {
             list.select(state.selected);
         }
 
+        let preview = Label::new(
+            Point::new(
+                x + 12,
+                y + h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            locale.t("settings-theme-font-preview"),
+            Alignment::Left,
+            Some(w / 2 - 12),
+        );
+
         let button_hints = Row::new(
             Point::new(
                 rect.x + rect.w as i32 - 12,
@@ -252,14 +408,68 @@ impl Theme {
             12,
         );
 
+        drop(locale);
+        drop(styles);
+
         Self {
             rect,
+            res,
             stylesheet,
             fonts,
+            backgrounds,
+            packs,
             list,
+            preview,
+            preview_font: None,
+            preview_dirty: false,
             button_hints,
         }
     }
+
+    /// Previews the font at `index` for the UI font (row 5) or guide font (row 7). The preview is
+    /// hidden when the font is the one already in use.
+    fn preview_font(&mut self, row: usize, index: usize) {
+        let current = match row {
+            5 => &self.stylesheet.ui_font,
+            7 => &self.stylesheet.guide_font,
+            _ => return,
+        };
+
+        let path = &self.fonts[index];
+        self.preview_font = if *path == current.path {
+            None
+        } else {
+            let mut font = StylesheetFont::new(path.clone(), current.size);
+            if let Err(e) = font.load() {
+                warn!("failed to load font for preview: {}", e);
+            }
+            font.font.is_some().then_some(font)
+        };
+        self.preview_dirty = true;
+    }
+
+    async fn export_pack(&self, commands: &Sender<Command>) -> Result<()> {
+        let name = format!("Allium {}", chrono::Local::now().format("%Y-%m-%d %H%M%S"));
+        let path = ALLIUM_THEMES_DIR.join(format!("{name}.zip"));
+        let text = {
+            let locale = self.res.get::<Locale>();
+            match self.stylesheet.save_as_pack(&path) {
+                Ok(()) => {
+                    let mut map = HashMap::new();
+                    map.insert("name".into(), name.into());
+                    locale.ta("settings-theme-export-theme-pack-success", &map)
+                }
+                Err(e) => {
+                    warn!("failed to export theme pack: {}", e);
+                    locale.t("settings-theme-export-theme-pack-failed")
+                }
+            }
+        };
+        commands
+            .send(Command::Toast(text, Some(Duration::from_secs(5))))
+            .await?;
+        Ok(())
+    }
 }
 
 #[async_trait(?Send)]
@@ -275,6 +485,20 @@ impl View for Theme {
             drawn = true;
         }
 
+        if self.preview_dirty {
+            let Rect { x, y, w, h } = self.rect;
+            let top = self.preview.bounding_box(styles).y;
+            display.load(Rect::new(x, top, w / 2, (y + h as i32 - top) as u32))?;
+            if let Some(font) = &self.preview_font {
+                let mut styles = styles.clone();
+                styles.ui_font = font.clone();
+                self.preview.draw(display, &styles)?;
+            }
+            self.preview_dirty = false;
+            self.button_hints.set_should_draw();
+            drawn = true;
+        }
+
         if self.button_hints.should_draw() && self.button_hints.draw(display, styles)? {
             drawn = true;
         }
@@ -283,11 +507,12 @@ impl View for Theme {
     }
 
     fn should_draw(&self) -> bool {
-        self.list.should_draw() || self.button_hints.should_draw()
+        self.list.should_draw() || self.preview_dirty || self.button_hints.should_draw()
     }
 
     fn set_should_draw(&mut self) {
         self.list.set_should_draw();
+        self.preview_dirty = true;
         self.button_hints.set_should_draw();
     }
 
@@ -303,6 +528,10 @@ impl View for Theme {
             .await?
         {
             while let Some(command) = bubble.pop_front() {
+                if let Command::ValuePreview(i, val) = &command {
+                    self.preview_font(*i, val.clone().as_int().unwrap() as usize);
+                    continue;
+                }
                 if let Command::ValueChanged(i, val) = command {
                     match i {
                         0 => {
@@ -418,6 +647,55 @@ impl View for Theme {
                         19 => self.stylesheet.button_b_color = val.as_color().unwrap(),
                         20 => self.stylesheet.button_x_color = val.as_color().unwrap(),
                         21 => self.stylesheet.button_y_color = val.as_color().unwrap(),
+                        22 => self.stylesheet.ui_sounds = val.as_bool().unwrap(),
+                        23 => {
+                            self.stylesheet.background_image = match val.as_int().unwrap() as usize
+                            {
+                                0 => None,
+                                i => Some(self.backgrounds[i - 1].clone()),
+                            }
+                        }
+                        24 => self.stylesheet.background_dim = val.as_int().unwrap() as u8,
+                        25 => self.stylesheet.border_radius = val.as_int().unwrap() as u32,
+                        26 => {
+                            self.stylesheet.selection_style =
+                                SelectionStyle::from_repr(val.as_int().unwrap() as usize)
+                                    .unwrap_or_default()
+                        }
+                        27 => self.stylesheet.row_spacing = val.as_int().unwrap() as u32,
+                        28 => {
+                            self.stylesheet.button_hint_style =
+                                ButtonHintStyle::from_repr(val.as_int().unwrap() as usize)
+                                    .unwrap_or_default()
+                        }
+                        29 => self.stylesheet.reduce_motion = val.as_bool().unwrap(),
+                        30 => self.stylesheet.marquee_speed = val.as_int().unwrap() as u32,
+                        31 => self.stylesheet.marquee_delay = val.as_int().unwrap() as u32,
+                        32 => self.stylesheet.marquee_selected_only = val.as_bool().unwrap(),
+                        33 => {
+                            let styles = match val.as_int().unwrap() as usize {
+                                0 => continue,
+                                i => Stylesheet::load_from_pack(&self.packs[i - 1]),
+                            };
+                            match styles {
+                                Ok(styles) => self.stylesheet = styles,
+                                Err(e) => {
+                                    warn!("failed to load theme pack: {}", e);
+                                    let text = self
+                                        .res
+                                        .get::<Locale>()
+                                        .t("settings-theme-theme-pack-failed");
+                                    commands
+                                        .send(Command::Toast(text, Some(Duration::from_secs(5))))
+                                        .await?;
+                                    continue;
+                                }
+                            }
+                        }
+                        34 => {
+                            self.export_pack(&commands).await?;
+                            continue;
+                        }
                         _ => unreachable!("Invalid index"),
                     }
 
@@ -439,11 +717,11 @@ impl View for Theme {
     }
 
     fn children(&self) -> Vec<&dyn View> {
-        vec![&self.list, &self.button_hints]
+        vec![&self.list, &self.preview, &self.button_hints]
     }
 
     fn children_mut(&mut self) -> Vec<&mut dyn View> {
-        vec![&mut self.list, &mut self.button_hints]
+        vec![&mut self.list, &mut self.preview, &mut self.button_hints]
     }
 
     fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
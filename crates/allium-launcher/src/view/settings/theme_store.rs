@@ -0,0 +1,312 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use common::command::Command;
+use common::display::Display as DisplayTrait;
+use common::geom::{Alignment, Point, Rect};
+use common::locale::Locale;
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::stylesheet::Stylesheet;
+use common::theme_store::{self, ThemeIndexEntry};
+use common::view::{ButtonHint, ButtonIcon, Image, ImageMode, Label, Row, ScrollList, View};
+use log::warn;
+use tokio::sync::mpsc::Sender;
+use tokio::sync::oneshot;
+
+use crate::view::settings::{ChildState, SettingsChild};
+
+/// Browses the community theme index and installs theme packs from it.
+pub struct ThemeStore {
+    rect: Rect,
+    res: Resources,
+    entries: Vec<ThemeIndexEntry>,
+    list: ScrollList,
+    preview: Image,
+    status: Label<String>,
+    button_hints: Row<ButtonHint<String>>,
+    index_rx: Option<oneshot::Receiver<Result<Vec<ThemeIndexEntry>>>>,
+    preview_rx: Option<(usize, oneshot::Receiver<Option<PathBuf>>)>,
+    selected: usize,
+    dirty: bool,
+}
+
+impl ThemeStore {
+    pub fn new(rect: Rect, res: Resources, state: Option<ChildState>) -> Self {
+        let Rect { x, y, w, h } = rect;
+
+        let locale = res.get::<Locale>();
+        let styles = res.get::<Stylesheet>();
+
+        let content_h = h - 8 - ButtonIcon::diameter(&styles) - 16;
+        let list = ScrollList::new(
+            Rect::new(x + 12, y + 8, w / 2 - 18, content_h),
+            Vec::new(),
+            Alignment::Left,
+            styles.row_height(),
+        );
+
+        let mut preview = Image::empty(
+            Rect::new(x + w as i32 / 2 + 6, y + 8, w / 2 - 18, content_h),
+            ImageMode::Contain,
+        );
+        preview.set_alignment(Alignment::Center);
+
+        let status = Label::new(
+            Point::new(x + 12, y + 8),
+            locale.t("settings-theme-store-loading"),
+            Alignment::Left,
+            Some(w - 24),
+        );
+
+        let button_hints = Row::new(
+            Point::new(
+                rect.x + rect.w as i32 - 12,
+                rect.y + rect.h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            vec![
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::A,
+                    locale.t("button-install"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::B,
+                    locale.t("button-back"),
+                    Alignment::Right,
+                ),
+            ],
+            Alignment::Right,
+            12,
+        );
+
+        drop(locale);
+        drop(styles);
+
+        let (tx, rx) = oneshot::channel();
+        tokio::spawn(async move {
+            tx.send(theme_store::fetch_index().await).ok();
+        });
+
+        Self {
+            rect,
+            res,
+            entries: Vec::new(),
+            list,
+            preview,
+            status,
+            button_hints,
+            index_rx: Some(rx),
+            preview_rx: None,
+            selected: state.map_or(0, |s| s.selected),
+            dirty: true,
+        }
+    }
+
+    fn load_preview(&mut self) {
+        let selected = self.list.selected();
+        self.preview.set_path(None);
+        let Some(entry) = self.entries.get(selected).cloned() else {
+            return;
+        };
+
+        let (tx, rx) = oneshot::channel();
+        tokio::spawn(async move {
+            let path = entry.download_preview().await.unwrap_or_else(|e| {
+                warn!("failed to download theme preview: {}", e);
+                None
+            });
+            tx.send(path).ok();
+        });
+        self.preview_rx = Some((selected, rx));
+    }
+
+    async fn install(&self, commands: Sender<Command>) -> Result<()> {
+        let Some(entry) = self.entries.get(self.list.selected()).cloned() else {
+            return Ok(());
+        };
+
+        let (downloading, installed, failed) = {
+            let locale = self.res.get::<Locale>();
+            let mut map = HashMap::new();
+            map.insert("name".into(), entry.name.clone().into());
+            (
+                locale.ta("settings-theme-store-downloading", &map),
+                locale.ta("settings-theme-store-installed", &map),
+                locale.ta("settings-theme-store-install-failed", &map),
+            )
+        };
+        commands.send(Command::Toast(downloading, None)).await?;
+
+        tokio::spawn(async move {
+            let text = match entry.install().await {
+                Ok(_) => installed,
+                Err(e) => {
+                    warn!("failed to install theme pack: {}", e);
+                    failed
+                }
+            };
+            commands
+                .send(Command::Toast(text, Some(Duration::from_secs(5))))
+                .await
+                .ok();
+        });
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl View for ThemeStore {
+    fn update(&mut self, _dt: Duration) {
+        if let Some(rx) = self.index_rx.as_mut() {
+            match rx.try_recv() {
+                Ok(Ok(entries)) => {
+                    self.index_rx = None;
+                    if entries.is_empty() {
+                        let text = self.res.get::<Locale>().t("settings-theme-store-empty");
+                        self.status.set_text(text);
+                    } else {
+                        self.status.set_text(String::new());
+                    }
+                    self.list.set_items(
+                        entries
+                            .iter()
+                            .map(|entry| match &entry.author {
+                                Some(author) => format!("{} ({})", entry.name, author),
+                                None => entry.name.clone(),
+                            })
+                            .collect(),
+                        false,
+                    );
+                    self.entries = entries;
+                    self.list.select(self.selected);
+                    self.load_preview();
+                    self.dirty = true;
+                }
+                Ok(Err(e)) => {
+                    warn!("failed to fetch theme index: {}", e);
+                    self.index_rx = None;
+                    let text = self.res.get::<Locale>().t("settings-theme-store-failed");
+                    self.status.set_text(text);
+                    self.dirty = true;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => self.index_rx = None,
+            }
+        }
+
+        if let Some((selected, rx)) = self.preview_rx.as_mut() {
+            match rx.try_recv() {
+                Ok(path) => {
+                    if *selected == self.list.selected() {
+                        self.preview.set_path(path);
+                    }
+                    self.preview_rx = None;
+                }
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => self.preview_rx = None,
+            }
+        }
+    }
+
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        let mut drawn = false;
+
+        if self.dirty {
+            display.load(self.rect)?;
+            self.list.set_should_draw();
+            self.preview.set_should_draw();
+            self.status.set_should_draw();
+            self.button_hints.set_should_draw();
+            self.dirty = false;
+            drawn = true;
+        }
+
+        drawn |= self.list.should_draw() && self.list.draw(display, styles)?;
+        drawn |= self.status.should_draw() && self.status.draw(display, styles)?;
+        drawn |= self.preview.should_draw() && self.preview.draw(display, styles)?;
+        drawn |= self.button_hints.should_draw() && self.button_hints.draw(display, styles)?;
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.dirty
+            || self.list.should_draw()
+            || self.status.should_draw()
+            || self.preview.should_draw()
+            || self.button_hints.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.dirty = true;
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        match event {
+            KeyEvent::Pressed(Key::A) => {
+                self.install(commands).await?;
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::B) => {
+                bubble.push_back(Command::CloseView);
+                Ok(true)
+            }
+            _ => {
+                let selected = self.list.selected();
+                if self.list.handle_key_event(event, commands, bubble).await? {
+                    if self.list.selected() != selected {
+                        self.load_preview();
+                    }
+                    return Ok(true);
+                }
+                Ok(false)
+            }
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.list, &self.status, &self.preview, &self.button_hints]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![
+            &mut self.list,
+            &mut self.status,
+            &mut self.preview,
+            &mut self.button_hints,
+        ]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
+
+impl SettingsChild for ThemeStore {
+    fn save(&self) -> ChildState {
+        ChildState {
+            selected: self.list.selected(),
+        }
+    }
+}
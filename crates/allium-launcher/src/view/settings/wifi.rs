@@ -1,10 +1,10 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::time::Duration;
 
 use anyhow::Result;
 use async_trait::async_trait;
 use common::command::Command;
-use common::constants::SELECTION_MARGIN;
+use common::constants::ALLIUM_REMOTE_CONTROL_PORT;
 use common::geom::{Alignment, Point, Rect};
 use common::locale::Locale;
 use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
@@ -18,6 +18,23 @@ use tokio::sync::mpsc::Sender;
 
 use crate::view::settings::{ChildState, SettingsChild};
 
+const WIFI_ROW: usize = 0;
+const IP_ADDRESS_ROW: usize = 1;
+const SSID_ROW: usize = 2;
+const PASSWORD_ROW: usize = 3;
+const NTP_ROW: usize = 4;
+const WEB_FILE_BROWSER_ROW: usize = 5;
+const TELNET_ROW: usize = 6;
+const SSH_ROW: usize = 7;
+const FTP_ROW: usize = 8;
+const SYNCTHING_ROW: usize = 9;
+const WEB_UI_ROW: usize = 10;
+const REMOTE_CONTROL_ROW: usize = 11;
+const NETWORK_SHARE_ROW: usize = 12;
+const NETWORK_SHARE_URL_ROW: usize = 13;
+const NETWORK_SHARE_USERNAME_ROW: usize = 14;
+const NETWORK_SHARE_PASSWORD_ROW: usize = 15;
+
 pub struct Wifi {
     rect: Rect,
     res: Resources,
@@ -52,8 +69,15 @@ impl Wifi {
                 locale.t("settings-wifi-ntp-enabled"),
                 locale.t("settings-wifi-web-file-explorer"),
                 locale.t("settings-wifi-telnet-enabled"),
+                locale.t("settings-wifi-ssh-enabled"),
                 locale.t("settings-wifi-ftp-enabled"),
                 locale.t("settings-wifi-syncthing"),
+                locale.t("settings-wifi-web-ui"),
+                locale.t("settings-wifi-remote-control"),
+                locale.t("settings-wifi-network-share"),
+                locale.t("settings-wifi-network-share-address"),
+                locale.t("settings-wifi-network-share-username"),
+                locale.t("settings-wifi-network-share-password"),
             ],
             vec![
                 Box::new(Toggle::new(Point::zero(), settings.wifi, Alignment::Right)),
@@ -88,14 +112,51 @@ impl Wifi {
                     settings.telnet,
                     Alignment::Right,
                 )),
+                Box::new(Toggle::new(Point::zero(), settings.ssh, Alignment::Right)),
                 Box::new(Toggle::new(Point::zero(), settings.ftp, Alignment::Right)),
                 Box::new(Toggle::new(
                     Point::zero(),
                     settings.syncthing,
                     Alignment::Right,
                 )),
+                Box::new(Toggle::new(
+                    Point::zero(),
+                    settings.web_ui,
+                    Alignment::Right,
+                )),
+                Box::new(Toggle::new(
+                    Point::zero(),
+                    settings.remote_control,
+                    Alignment::Right,
+                )),
+                Box::new(Toggle::new(
+                    Point::zero(),
+                    settings.network_share,
+                    Alignment::Right,
+                )),
+                Box::new(TextBox::new(
+                    Point::zero(),
+                    res.clone(),
+                    settings.network_share_url.clone(),
+                    Alignment::Right,
+                    false,
+                )),
+                Box::new(TextBox::new(
+                    Point::zero(),
+                    res.clone(),
+                    settings.network_share_username.clone(),
+                    Alignment::Right,
+                    false,
+                )),
+                Box::new(TextBox::new(
+                    Point::zero(),
+                    res.clone(),
+                    settings.network_share_password.clone(),
+                    Alignment::Right,
+                    true,
+                )),
             ],
-            res.get::<Stylesheet>().ui_font.size + SELECTION_MARGIN,
+            res.get::<Stylesheet>().row_height(),
         );
         if let Some(state) = state {
             list.select(state.selected);
@@ -139,6 +200,49 @@ impl Wifi {
             button_hints,
         }
     }
+
+    /// Shows a QR code of a service's URL once Wi-Fi is connected, with an optional note such as
+    /// login details.
+    fn show_url(
+        &self,
+        commands: Sender<Command>,
+        scheme: &'static str,
+        port: u16,
+        note: Option<String>,
+    ) {
+        let (fg_color, bg_color) = {
+            let styles = self.res.get::<Stylesheet>();
+            (styles.foreground_color, styles.highlight_color)
+        };
+        tokio::spawn(async move {
+            if wifi::wait_for_wifi().await.is_ok()
+                && let Some(ip_address) = wifi::ip_address()
+            {
+                let url = match (scheme, port) {
+                    ("http", 80) | ("ftp", 21) | ("ssh", 22) => format!("{scheme}://{ip_address}/"),
+                    _ => format!("{scheme}://{ip_address}:{port}/"),
+                };
+                let Ok(code) = QrCode::new(url.as_bytes()) else {
+                    warn!("Failed to generate QR code for {}", url);
+                    return;
+                };
+                let image = code
+                    .render::<image::Rgba<u8>>()
+                    .dark_color(fg_color.into())
+                    .light_color(bg_color.into())
+                    .min_dimensions(300, 300)
+                    .build();
+                let text = match note {
+                    Some(note) => format!("{url} ({note})"),
+                    None => url,
+                };
+                commands
+                    .send(Command::ImageToast(image, text, None))
+                    .await
+                    .ok();
+            }
+        });
+    }
 }
 
 #[async_trait(?Send)]
@@ -155,7 +259,7 @@ impl View for Wifi {
                 if let Some(ip_address) = wifi::ip_address() {
                     self.has_ip_address = true;
                     self.list.set_right(
-                        1,
+                        IP_ADDRESS_ROW,
                         Box::new(Label::new(
                             Point::zero(),
                             ip_address,
@@ -166,7 +270,7 @@ impl View for Wifi {
                 } else {
                     let locale = self.res.get::<Locale>();
                     self.list.set_right(
-                        1,
+                        IP_ADDRESS_ROW,
                         Box::new(Label::new(
                             Point::zero(),
                             locale.t("settings-wifi-connecting"),
@@ -179,7 +283,7 @@ impl View for Wifi {
         } else if self.has_ip_address {
             self.has_ip_address = false;
             self.list.set_right(
-                1,
+                IP_ADDRESS_ROW,
                 Box::new(Label::new(
                     Point::zero(),
                     String::new(),
@@ -226,7 +330,7 @@ impl View for Wifi {
             while let Some(command) = bubble.pop_front() {
                 if let Command::ValueChanged(i, val) = command {
                     match i {
-                        0 => {
+                        WIFI_ROW => {
                             self.settings.set_wifi(val.as_bool().unwrap())?;
                             let commands = commands.clone();
                             tokio::spawn(async move {
@@ -235,88 +339,116 @@ impl View for Wifi {
                                 }
                             });
                         }
-                        1 => {} // ip address
-                        2 => self
+                        IP_ADDRESS_ROW => {}
+                        SSID_ROW => self
                             .settings
                             .set_ssid(val.as_string().unwrap().to_string())?,
-                        3 => self
+                        PASSWORD_ROW => self
                             .settings
                             .set_password(val.as_string().unwrap().to_string())?,
-                        4 => self.settings.toggle_ntp(val.as_bool().unwrap())?,
-                        5 => {
+                        NTP_ROW => self.settings.toggle_ntp(val.as_bool().unwrap())?,
+                        WEB_FILE_BROWSER_ROW => {
                             let enabled = val.as_bool().unwrap();
                             self.settings.toggle_web_file_browser(enabled)?;
                             if enabled {
-                                let (fg_color, bg_color) = {
-                                    let styles = self.res.get::<Stylesheet>();
-                                    (styles.foreground_color, styles.highlight_color)
-                                };
-                                let commands = commands.clone();
-                                tokio::spawn(async move {
-                                    if wifi::wait_for_wifi().await.is_ok()
-                                        && let Some(ip_address) = wifi::ip_address()
-                                    {
-                                        let url = format!("http://{ip_address}/");
-                                        let Ok(code) = QrCode::new(url.as_bytes()) else {
-                                            warn!(
-                                                "Failed to generate QR code for web file explorer"
-                                            );
-                                            return;
-                                        };
-                                        let image = code
-                                            .render::<image::Rgba<u8>>()
-                                            .dark_color(fg_color.into())
-                                            .light_color(bg_color.into())
-                                            .min_dimensions(300, 300)
-                                            .build();
-                                        commands
-                                            .send(Command::ImageToast(image, url, None))
-                                            .await
-                                            .ok();
-                                    }
-                                });
+                                self.show_url(commands.clone(), "http", 80, None);
+                            } else {
+                                commands.send(Command::DismissToast).await.ok();
+                            }
+                        }
+                        TELNET_ROW => self.settings.toggle_telnet(val.as_bool().unwrap())?,
+                        SSH_ROW => {
+                            let enabled = val.as_bool().unwrap();
+                            self.settings.toggle_ssh(enabled)?;
+                            if enabled {
+                                let login = self.res.get::<Locale>().t("settings-wifi-ssh-login");
+                                self.show_url(commands.clone(), "ssh", 22, Some(login));
+                            } else {
+                                commands.send(Command::DismissToast).await.ok();
+                            }
+                        }
+                        FTP_ROW => {
+                            let enabled = val.as_bool().unwrap();
+                            self.settings.toggle_ftp(enabled)?;
+                            if enabled {
+                                let login = self.res.get::<Locale>().t("settings-wifi-ftp-login");
+                                self.show_url(commands.clone(), "ftp", 21, Some(login));
                             } else {
                                 commands.send(Command::DismissToast).await.ok();
                             }
                         }
-                        6 => self.settings.toggle_telnet(val.as_bool().unwrap())?,
-                        7 => self.settings.toggle_ftp(val.as_bool().unwrap())?,
-                        8 => {
+                        SYNCTHING_ROW => {
                             let enabled = val.as_bool().unwrap();
                             self.settings.toggle_syncthing(enabled)?;
                             if enabled {
-                                let (fg_color, bg_color) = {
-                                    let styles = self.res.get::<Stylesheet>();
-                                    (styles.foreground_color, styles.highlight_color)
-                                };
-                                let commands = commands.clone();
-                                tokio::spawn(async move {
-                                    if wifi::wait_for_wifi().await.is_ok()
-                                        && let Some(ip_address) = wifi::ip_address()
-                                    {
-                                        let url = format!("http://{ip_address}:8384/");
-                                        let Ok(code) = QrCode::new(url.as_bytes()) else {
-                                            warn!(
-                                                "Failed to generate QR code for web file explorer"
-                                            );
-                                            return;
-                                        };
-                                        let image = code
-                                            .render::<image::Rgba<u8>>()
-                                            .dark_color(fg_color.into())
-                                            .light_color(bg_color.into())
-                                            .min_dimensions(300, 300)
-                                            .build();
-                                        commands
-                                            .send(Command::ImageToast(image, url, None))
-                                            .await
-                                            .ok();
-                                    }
-                                });
+                                self.show_url(commands.clone(), "http", 8384, None);
                             } else {
                                 commands.send(Command::DismissToast).await.ok();
                             }
                         }
+                        WEB_UI_ROW => {
+                            let enabled = val.as_bool().unwrap();
+                            self.settings.toggle_web_ui(enabled)?;
+                            if enabled {
+                                let pin = wifi::access_pin()?;
+                                let mut map = HashMap::new();
+                                map.insert("pin".into(), pin.into());
+                                let login = self
+                                    .res
+                                    .get::<Locale>()
+                                    .ta("settings-wifi-web-ui-login", &map);
+                                self.show_url(commands.clone(), "http", 8080, Some(login));
+                            } else {
+                                commands.send(Command::DismissToast).await.ok();
+                            }
+                        }
+                        REMOTE_CONTROL_ROW => {
+                            let enabled = val.as_bool().unwrap();
+                            self.settings.remote_control = enabled;
+                            self.settings.save()?;
+                            // Let alliumd start or stop the remote control
+                            #[cfg(unix)]
+                            std::process::Command::new("pkill")
+                                .arg("-HUP")
+                                .arg("alliumd")
+                                .spawn()?;
+                            if enabled {
+                                let mut map = HashMap::new();
+                                map.insert("port".into(), ALLIUM_REMOTE_CONTROL_PORT.into());
+                                map.insert("pin".into(), wifi::access_pin()?.into());
+                                let text = self
+                                    .res
+                                    .get::<Locale>()
+                                    .ta("settings-wifi-remote-control-enabled", &map);
+                                commands
+                                    .send(Command::Toast(text, Some(Duration::from_secs(10))))
+                                    .await
+                                    .ok();
+                            }
+                        }
+                        NETWORK_SHARE_ROW => {
+                            let enabled = val.as_bool().unwrap();
+                            self.settings.toggle_network_share(enabled)?;
+                            if enabled && self.settings.network_share_url.is_empty() {
+                                let text = self
+                                    .res
+                                    .get::<Locale>()
+                                    .t("settings-wifi-network-share-no-address");
+                                commands
+                                    .send(Command::Toast(text, Some(Duration::from_secs(3))))
+                                    .await
+                                    .ok();
+                            }
+                        }
+                        NETWORK_SHARE_URL_ROW => self
+                            .settings
+                            .set_network_share_url(val.as_string().unwrap().trim().to_string())?,
+                        NETWORK_SHARE_USERNAME_ROW => self
+                            .settings
+                            .set_network_share_username(val.as_string().unwrap().to_string())?,
+                        NETWORK_SHARE_PASSWORD_ROW => self
+                            .settings
+                            .set_network_share_password(val.as_string().unwrap().to_string())?,
                         _ => unreachable!("Invalid index"),
                     }
                 }
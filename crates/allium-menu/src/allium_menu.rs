@@ -1,21 +1,27 @@
 use std::collections::VecDeque;
+use std::time::Duration;
 
 use anyhow::Result;
-use base32::encode;
+use common::combo::{ComboAction, ComboDetector, Detection};
 use common::command::Command;
-use common::constants::ALLIUM_SCREENSHOTS_DIR;
+use common::constants::{ALLIUM_SCREENSHOTS_DIR, SAVE_STATE_IMAGE_WIDTH};
 use common::database::Database;
 use common::display::Display;
 use common::game_info::GameInfo;
 use common::geom;
 use common::locale::{Locale, LocaleSettings};
-use common::platform::{DefaultPlatform, Platform};
+use common::performance::PerformanceSettings;
+use common::platform::{DefaultPlatform, KeyEvent, Platform};
 use common::resources::Resources;
+use common::retroarch::{RetroArchClient, RetroArchCommand};
+use common::rumble::{self, RumbleSettings};
+use common::save_state;
+use common::sound;
 use common::stylesheet::Stylesheet;
-use common::view::View;
+use common::view::{Toast, View};
 use embedded_graphics::prelude::*;
 use log::{info, warn};
-use sha2::{Digest, Sha256};
+use tokio::sync::watch;
 use type_map::TypeMap;
 
 use crate::retroarch_info::RetroArchInfo;
@@ -32,6 +38,11 @@ where
     display: P::Display,
     res: Resources,
     view: IngameMenu<P::Battery>,
+    toast: Option<Toast>,
+    combos: ComboDetector,
+    rumble: RumbleSettings,
+    /// RetroArch's state, kept up to date while the menu is open.
+    info_rx: Option<watch::Receiver<RetroArchInfo>>,
 }
 
 impl AlliumMenu<DefaultPlatform> {
@@ -44,60 +55,112 @@ impl AlliumMenu<DefaultPlatform> {
         res.insert(Database::new()?);
         res.insert(GameInfo::load()?.unwrap_or_default());
         res.insert(Stylesheet::load()?);
-        res.insert(Locale::new(&LocaleSettings::load()?.lang));
+        let locale = Locale::new(&LocaleSettings::load()?);
+        geom::set_right_to_left(locale.is_right_to_left(), display.size().width);
+        res.insert(locale);
         res.insert(Into::<geom::Size>::into(display.size()));
         let res = Resources::new(res);
 
+        // The simulator has no RetroArch to poll
+        let info_rx = info
+            .filter(|_| !cfg!(feature = "simulator"))
+            .map(|info| info.subscribe(RetroArchClient::new()));
+
         Ok(AlliumMenu {
             platform,
             display,
             res: res.clone(),
             view: IngameMenu::load_or_new(rect, res, battery, info).await?,
+            toast: None,
+            combos: ComboDetector::default(),
+            rumble: RumbleSettings::load().unwrap_or_else(|e| {
+                warn!("failed to load rumble settings: {}", e);
+                RumbleSettings::default()
+            }),
+            info_rx,
         })
     }
 
     pub async fn run_event_loop(&mut self) -> Result<()> {
         self.display.save()?;
-        {
-            let styles = self.res.get::<Stylesheet>();
-            self.display
-                .map_pixels(|pixel| pixel.blend(styles.background_color.overlay(pixel), 192))?;
-        }
-        self.display.save()?;
+        self.darken_background()?;
 
         #[cfg(unix)]
         let mut sigterm = tokio::signal::unix::signal(SignalKind::terminate())?;
+        #[cfg(unix)]
+        let mut sighup = tokio::signal::unix::signal(SignalKind::hangup())?;
 
         let (tx, mut rx) = tokio::sync::mpsc::channel(100);
 
+        let mut frame_interval = tokio::time::interval(Duration::from_micros(166_667));
+
         loop {
-            if self.view.should_draw() && self.view.draw(&mut self.display, &self.res.get())? {
+            let mut drawn =
+                self.view.should_draw() && self.view.draw(&mut self.display, &self.res.get())?;
+
+            if let Some(toast) = self.toast.as_mut() {
+                if toast.has_expired() {
+                    self.toast = None;
+                    self.handle_command(Command::Redraw)?;
+                    drawn |= self.view.draw(&mut self.display, &self.res.get())?;
+                } else {
+                    drawn |= toast.draw(&mut self.display, &self.res.get())?;
+                }
+            }
+
+            if drawn {
                 self.display.flush()?;
             }
 
             #[cfg(unix)]
             tokio::select! {
+                _ = frame_interval.tick(), if self.toast.is_some() => {}
                 _ = sigterm.recv() => {
                     self.handle_command(Command::Exit)?;
                 }
+                _ = sighup.recv() => {
+                    // alliumd passes on that the settings changed
+                    self.handle_command(Command::LocaleChanged)?;
+                }
                 Some(command) = rx.recv() => {
                     self.handle_command(command)?;
                 }
+                Some(info) = info_changed(&mut self.info_rx) => {
+                    self.view.set_retroarch_info(info);
+                }
                 event = self.platform.poll() => {
+                    let Some(event) = self.detect_combo(event).await? else {
+                        continue;
+                    };
+                    let event = event.ui();
                     let mut bubble = VecDeque::new();
-                    self.view.handle_key_event(event, tx.clone(), &mut bubble).await?;
+                    if self.view.handle_key_event(event, tx.clone(), &mut bubble).await? {
+                        sound::play_for_key_event(&self.res.get::<Stylesheet>(), event);
+                        rumble::rumble_for_key_event(&self.platform, &self.rumble, event);
+                    }
                 }
                 else => {}
             }
 
             #[cfg(not(unix))]
             tokio::select! {
+                _ = frame_interval.tick(), if self.toast.is_some() => {}
                 Some(command) = rx.recv() => {
                     self.handle_command(command)?;
                 }
+                Some(info) = info_changed(&mut self.info_rx) => {
+                    self.view.set_retroarch_info(info);
+                }
                 event = self.platform.poll() => {
+                    let Some(event) = self.detect_combo(event).await? else {
+                        continue;
+                    };
+                    let event = event.ui();
                     let mut bubble = VecDeque::new();
-                    self.view.handle_key_event(event, tx.clone(), &mut bubble).await?;
+                    if self.view.handle_key_event(event, tx.clone(), &mut bubble).await? {
+                        sound::play_for_key_event(&self.res.get::<Stylesheet>(), event);
+                        rumble::rumble_for_key_event(&self.platform, &self.rumble, event);
+                    }
                 }
                 else => {}
             }
@@ -118,22 +181,28 @@ impl AlliumMenu<DefaultPlatform> {
                 self.display.load(self.display.bounding_box().into())?;
                 self.view.set_should_draw();
             }
+            Command::LocaleChanged => {
+                let settings = match LocaleSettings::load() {
+                    Ok(settings) => settings,
+                    Err(e) => {
+                        warn!("failed to load locale settings: {}", e);
+                        return Ok(());
+                    }
+                };
+                let locale = Locale::new(&settings);
+                geom::set_right_to_left(locale.is_right_to_left(), self.display.size().width);
+                self.res.insert(locale);
+                self.view.locale_changed()?;
+                self.display.load(self.display.bounding_box().into())?;
+            }
             Command::SaveStateScreenshot { path, core, slot } => {
                 if self.display.pop() {
                     self.display.load(self.display.bounding_box().into())?;
                     self.display.flush()?;
 
-                    let mut hasher = Sha256::new();
-                    hasher.update(&path);
-                    hasher.update(&core);
-                    hasher.update(slot.to_le_bytes());
-                    let hash = hasher.finalize();
-                    let base32 = encode(base32::Alphabet::Crockford, &hash);
-                    let file_name = format!("{}.png", base32);
-
                     std::fs::create_dir_all(&*ALLIUM_SCREENSHOTS_DIR).ok();
 
-                    let screenshot_path = ALLIUM_SCREENSHOTS_DIR.join(&file_name);
+                    let screenshot_path = save_state::screenshot_path(&path, &core, slot);
                     info!("saving screenshot to {:?}", screenshot_path);
 
                     let database = self.res.get::<Database>();
@@ -145,10 +214,7 @@ impl AlliumMenu<DefaultPlatform> {
                     #[cfg(feature = "miyoo")]
                     std::process::Command::new("screenshot")
                         .arg(screenshot_path)
-                        .arg(format!(
-                            "--width={}",
-                            common::constants::SAVE_STATE_IMAGE_WIDTH
-                        ))
+                        .arg(format!("--width={}", SAVE_STATE_IMAGE_WIDTH))
                         .arg("--crop")
                         .spawn()?;
 
@@ -159,10 +225,143 @@ impl AlliumMenu<DefaultPlatform> {
                     )?;
                 }
             }
+            Command::TakeScreenshot => {
+                if let Err(e) = self.take_screenshot() {
+                    warn!("failed to take screenshot: {}", e);
+                }
+            }
+            Command::SetCpuProfile(profile) => {
+                if let Err(e) = self
+                    .platform
+                    .set_cpu_profile(PerformanceSettings::load()?.cpu_profile(profile))
+                {
+                    warn!("failed to set cpu profile: {}", e);
+                }
+                let mut game_info = self.res.get::<GameInfo>().clone();
+                game_info.cpu_profile = profile;
+                game_info.save()?;
+                self.res.get::<Database>().set_cpu_profile(
+                    &game_info.name,
+                    &game_info.path,
+                    profile,
+                )?;
+                self.res.insert(game_info);
+            }
+            Command::Toast(text, duration) => {
+                self.toast = Some(Toast::new(text, duration));
+            }
+            Command::ImageToast(image, text, duration) => {
+                self.toast = Some(Toast::with_image(image, text, duration));
+            }
+            Command::Batch(commands) => {
+                for command in commands {
+                    self.handle_command(command)?;
+                }
+            }
             command => {
                 warn!("unhandled command: {:?}", command);
             }
         }
         Ok(())
     }
+
+    /// Runs the action of a button combo, if the event completes one. Returns the event if it
+    /// should be handled as usual.
+    async fn detect_combo(&mut self, event: KeyEvent) -> Result<Option<KeyEvent>> {
+        let action = match self.combos.detect(event) {
+            Detection::Key(event) => return Ok(Some(event)),
+            Detection::Consumed => return Ok(None),
+            Detection::Combo(action) => action,
+        };
+        info!("combo: {:?}", action);
+        match action {
+            ComboAction::Screenshot => self.handle_command(Command::TakeScreenshot)?,
+            ComboAction::QuickSettings => {
+                RetroArchCommand::Unpause.send().await?;
+                RetroArchCommand::MenuToggle.send().await?;
+                self.handle_command(Command::Exit)?;
+            }
+            ComboAction::ForceQuit => {
+                tokio::process::Command::new("pkill")
+                    .arg("retroarch")
+                    .spawn()?
+                    .wait()
+                    .await?;
+                self.handle_command(Command::Exit)?;
+            }
+        }
+        Ok(None)
+    }
+
+    /// Dims the game frame and saves it as the menu background.
+    fn darken_background(&mut self) -> Result<()> {
+        {
+            let styles = self.res.get::<Stylesheet>();
+            self.display
+                .map_pixels(|pixel| pixel.blend(styles.background_color.overlay(pixel), 192))?;
+        }
+        self.display.save()?;
+        Ok(())
+    }
+
+    /// Captures the game frame without the menu, saves it as the game's screenshot, and shows a
+    /// preview of it.
+    fn take_screenshot(&mut self) -> Result<()> {
+        if !self.display.pop() {
+            return Ok(());
+        }
+        self.display.load(self.display.bounding_box().into())?;
+        self.display.flush()?;
+
+        let game_info = self.res.get::<GameInfo>();
+        let file_name = format!(
+            "{}-{}.png",
+            chrono::Local::now().format("%Y-%m-%d_%H-%M-%S"),
+            game_info.name,
+        );
+        std::fs::create_dir_all(&*ALLIUM_SCREENSHOTS_DIR)?;
+        let screenshot_path = ALLIUM_SCREENSHOTS_DIR.join(file_name);
+        info!("saving screenshot to {:?}", screenshot_path);
+
+        #[cfg(feature = "miyoo")]
+        std::process::Command::new("screenshot")
+            .arg(&screenshot_path)
+            .arg("--crop")
+            .spawn()?
+            .wait()?;
+
+        #[cfg(feature = "simulator")]
+        std::fs::copy(
+            common::constants::ALLIUM_SD_ROOT.join("bg-640x480.png"),
+            &screenshot_path,
+        )?;
+
+        self.res
+            .get::<Database>()
+            .update_screenshot_path(&game_info.path, Some(&screenshot_path))?;
+        drop(game_info);
+
+        self.darken_background()?;
+        self.view.set_should_draw();
+
+        let image = ::image::open(&screenshot_path)?
+            .thumbnail(SAVE_STATE_IMAGE_WIDTH, SAVE_STATE_IMAGE_WIDTH)
+            .into_rgba8();
+        self.toast = Some(Toast::with_image(
+            image,
+            self.res.get::<Locale>().t("ingame-menu-screenshot-saved"),
+            Some(Duration::from_secs(2)),
+        ));
+
+        Ok(())
+    }
+}
+
+/// Waits for RetroArch's state to change. Never resolves to Some if there's nothing to watch.
+async fn info_changed(
+    info_rx: &mut Option<watch::Receiver<RetroArchInfo>>,
+) -> Option<RetroArchInfo> {
+    let info_rx = info_rx.as_mut()?;
+    info_rx.changed().await.ok()?;
+    Some(*info_rx.borrow_and_update())
 }
@@ -2,6 +2,7 @@
 #![warn(rust_2018_idioms)]
 
 mod allium_menu;
+mod overrides;
 mod retroarch_info;
 pub mod view;
 
@@ -12,42 +13,37 @@ use anyhow::Result;
 use allium_menu::AlliumMenu;
 use common::{
     platform::{DefaultPlatform, Platform},
-    retroarch::RetroArchCommand,
+    retroarch::{RetroArchClient, RetroArchCommand},
 };
-use simple_logger::SimpleLogger;
 
 use crate::retroarch_info::RetroArchInfo;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    SimpleLogger::new().env().init().unwrap();
+    common::logging::init("allium-menu")?;
+
+    let retroarch = RetroArchClient::new();
 
     #[cfg(not(feature = "simulator"))]
-    let info = RetroArchCommand::GetInfo.send_recv().await?.map(|ret| {
-        let mut rets = ret.split_ascii_whitespace().skip(1);
-
-        let max_disk_slots = rets.next().map_or(0, |s| s.parse().unwrap_or(0));
-        let disk_slot = rets.next().map_or(0, |s| s.parse().unwrap_or(0));
-        let state_slot = rets.next().map(|s| s.parse().unwrap_or(0));
-
-        RetroArchInfo {
-            max_disk_slots,
-            disk_slot,
-            state_slot,
-        }
-    });
+    let info = RetroArchInfo::fetch(&retroarch).await?;
 
     #[cfg(feature = "simulator")]
     let info = Some(RetroArchInfo {
         max_disk_slots: 3,
         disk_slot: 0,
         state_slot: Some(0),
+        paused: false,
     });
 
     if info.is_some() {
-        RetroArchCommand::Pause.send().await?;
-        tokio::time::sleep(Duration::from_millis(50)).await;
+        retroarch
+            .send_and_wait(&RetroArchCommand::Pause, Duration::from_millis(50))
+            .await?;
     }
+    let info = info.map(|info| RetroArchInfo {
+        paused: true,
+        ..info
+    });
 
     let platform = DefaultPlatform::new()?;
     let mut app = AlliumMenu::new(platform, info).await?;
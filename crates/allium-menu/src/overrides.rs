@@ -0,0 +1,176 @@
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use common::constants::{RETROARCH_CONFIG, RETROARCH_CONFIG_DIR};
+use common::game_info::GameInfo;
+use common::retroarch::{Config, core_name};
+use log::warn;
+
+/// Which games a RetroArch override applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverrideScope {
+    /// `config/<core>/<game>.cfg`
+    Game,
+    /// `config/<core>/<content directory>.cfg`
+    ContentDir,
+    /// `config/<core>/<core>.cfg`
+    Core,
+}
+
+impl OverrideScope {
+    pub fn from_repr(i: usize) -> Self {
+        match i {
+            1 => OverrideScope::ContentDir,
+            _ => OverrideScope::Game,
+        }
+    }
+}
+
+/// A RetroArch override config file. Unknown keys are preserved when saving.
+#[derive(Debug, Clone)]
+pub struct RetroArchOverride {
+    path: PathBuf,
+    config: Config,
+}
+
+impl RetroArchOverride {
+    /// Loads the override for the current game, or an empty one if it doesn't exist yet.
+    /// Returns None if the game is not run with RetroArch.
+    pub fn load(game_info: &GameInfo, scope: OverrideScope) -> Result<Option<Self>> {
+        let Some(path) = override_path(game_info, scope) else {
+            return Ok(None);
+        };
+
+        let config = Config::load(&path)?;
+        Ok(Some(Self { path, config }))
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.config.get(key)
+    }
+
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        self.config.get_bool(key)
+    }
+
+    pub fn get_int(&self, key: &str) -> Option<i32> {
+        self.config.get_int(key)
+    }
+
+    pub fn set(&mut self, key: &str, value: impl ToString) {
+        self.config.set(key, value);
+    }
+
+    /// Writes the override to disk. Empty overrides are removed instead.
+    pub fn save(&self) -> Result<()> {
+        if self.config.is_empty() {
+            if self.path.exists() {
+                fs::remove_file(&self.path)?;
+            }
+            return Ok(());
+        }
+
+        self.config.save(&self.path)
+    }
+}
+
+/// Looks up a RetroArch option for the current game in the same order RetroArch applies them:
+/// game override, content directory override, core override, then the main config.
+pub fn resolve_option(game_info: &GameInfo, key: &str) -> Option<String> {
+    for scope in [
+        OverrideScope::Game,
+        OverrideScope::ContentDir,
+        OverrideScope::Core,
+    ] {
+        if let Ok(Some(config)) = RetroArchOverride::load(game_info, scope)
+            && let Some(value) = config.get(key)
+        {
+            return Some(value.to_string());
+        }
+    }
+
+    match Config::load(&RETROARCH_CONFIG) {
+        Ok(config) => config.get(key).map(str::to_string),
+        Err(e) => {
+            warn!("failed to read {}: {}", RETROARCH_CONFIG.display(), e);
+            None
+        }
+    }
+}
+
+/// Returns the path of the override file, following RetroArch's naming scheme.
+fn override_path(game_info: &GameInfo, scope: OverrideScope) -> Option<PathBuf> {
+    let core_name = core_name(game_info.retroarch_core()?)?;
+    override_path_in(&RETROARCH_CONFIG_DIR, &core_name, &game_info.path, scope)
+}
+
+fn override_path_in(
+    config_dir: &Path,
+    core_name: &str,
+    game: &Path,
+    scope: OverrideScope,
+) -> Option<PathBuf> {
+    let mut name = match scope {
+        OverrideScope::Game => game.file_stem()?,
+        OverrideScope::ContentDir => game.parent()?.file_name()?,
+        OverrideScope::Core => OsStr::new(core_name),
+    }
+    .to_os_string();
+    // Appended rather than set as the extension, as game names often contain dots
+    name.push(".cfg");
+    Some(config_dir.join(core_name).join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn test_override_path() {
+        let dir = Path::new("/config");
+        let game = Path::new("/Roms/NES/Super Mario Bros. 3.nes");
+        assert_eq!(
+            override_path_in(dir, "FCEUmm", game, OverrideScope::Game),
+            Some(PathBuf::from("/config/FCEUmm/Super Mario Bros. 3.cfg"))
+        );
+        assert_eq!(
+            override_path_in(dir, "FCEUmm", game, OverrideScope::ContentDir),
+            Some(PathBuf::from("/config/FCEUmm/NES.cfg"))
+        );
+        assert_eq!(
+            override_path_in(dir, "FCEUmm", game, OverrideScope::Core),
+            Some(PathBuf::from("/config/FCEUmm/FCEUmm.cfg"))
+        );
+    }
+
+    #[test]
+    fn test_override_save() {
+        let dir = env::temp_dir().join("allium-test-override");
+        fs::remove_dir_all(&dir).ok();
+        let path = dir.join("FCEUmm/NES.cfg");
+
+        let mut config = RetroArchOverride {
+            path: path.clone(),
+            config: Config::load(&path).unwrap(),
+        };
+        assert_eq!(config.get("video_scale"), None);
+        config.set("video_scale", 2);
+        config.save().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "video_scale = \"2\"\n");
+
+        let mut config = RetroArchOverride {
+            path: path.clone(),
+            config: Config::load(&path).unwrap(),
+        };
+        assert_eq!(config.get_int("video_scale"), Some(2));
+        config.config.remove("video_scale");
+        config.save().unwrap();
+        assert!(!path.exists());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
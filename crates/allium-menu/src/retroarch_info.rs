@@ -1,5 +1,61 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use common::retroarch::RetroArchClient;
+use log::{debug, warn};
+use tokio::sync::watch;
+use tokio::time::MissedTickBehavior;
+
+/// How often RetroArch is polled while the menu is open.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct RetroArchInfo {
     pub max_disk_slots: u8,
     pub disk_slot: u8,
     pub state_slot: Option<i8>,
+    pub paused: bool,
+}
+
+impl RetroArchInfo {
+    /// Asks RetroArch for its current state. Returns None if RetroArch isn't running.
+    pub async fn fetch(client: &RetroArchClient) -> Result<Option<Self>> {
+        let Some(info) = client.info().await? else {
+            return Ok(None);
+        };
+        let status = client.status().await?;
+        Ok(Some(Self {
+            max_disk_slots: info.max_disk_slots,
+            disk_slot: info.disk_slot,
+            state_slot: info.state_slot,
+            paused: status.is_some_and(|status| status.is_paused()),
+        }))
+    }
+
+    /// Keeps polling RetroArch in the background, so that changes made outside of the menu, e.g.
+    /// with hotkeys, show up. Polling stops when the receiver is dropped.
+    pub fn subscribe(self, client: RetroArchClient) -> watch::Receiver<Self> {
+        let (tx, rx) = watch::channel(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(POLL_INTERVAL);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            // The first tick completes immediately, and we start with fresh info
+            interval.tick().await;
+            while !tx.is_closed() {
+                interval.tick().await;
+                match Self::fetch(&client).await {
+                    Ok(Some(info)) => {
+                        tx.send_if_modified(|current| {
+                            let changed = *current != info;
+                            *current = info;
+                            changed
+                        });
+                    }
+                    Ok(None) => debug!("RetroArch did not reply to status poll"),
+                    Err(e) => warn!("failed to poll RetroArch: {}", e),
+                }
+            }
+        });
+        rx
+    }
 }
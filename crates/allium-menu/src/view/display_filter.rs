@@ -0,0 +1,207 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use common::command::Command;
+use common::constants::RETROARCH_SHADERS_DIR;
+use common::display::Display;
+use common::geom::{Alignment, Point, Rect};
+use common::locale::Locale;
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::retroarch::RetroArchCommand;
+use common::stylesheet::Stylesheet;
+use common::view::{ButtonHint, ButtonIcon, Label, Row, ScrollList, View};
+use log::warn;
+use tokio::sync::mpsc::Sender;
+
+use crate::view::IngameMenuChild;
+
+/// Lists the RetroArch shader presets and applies the selected one.
+pub struct DisplayFilter {
+    rect: Rect,
+    title: Label<String>,
+    list: ScrollList,
+    button_hints: Row<ButtonHint<String>>,
+    presets: Vec<PathBuf>,
+    dirty: bool,
+}
+
+impl DisplayFilter {
+    pub fn new(rect: Rect, res: Resources) -> Self {
+        let Rect { x, y, w, h } = rect;
+
+        let locale = res.get::<Locale>();
+        let styles = res.get::<Stylesheet>();
+
+        let title = Label::new(
+            Point::new(x + 12, y + 8),
+            locale.t("ingame-menu-display-filter"),
+            Alignment::Left,
+            None,
+        );
+
+        let mut presets = Vec::new();
+        find_presets(&RETROARCH_SHADERS_DIR, &mut presets);
+        presets.sort_unstable();
+
+        let mut items = vec![locale.t("display-filter-none")];
+        items.extend(presets.iter().map(|p| {
+            p.strip_prefix(RETROARCH_SHADERS_DIR.as_path())
+                .unwrap_or(p)
+                .with_extension("")
+                .to_string_lossy()
+                .to_string()
+        }));
+
+        let list = ScrollList::new(
+            Rect::new(
+                x + 12,
+                y + 8 + ButtonIcon::diameter(&styles) as i32 + 8,
+                w - 24,
+                h - 8 - ButtonIcon::diameter(&styles) - 8 - ButtonIcon::diameter(&styles) - 8,
+            ),
+            items,
+            Alignment::Left,
+            styles.row_height(),
+        );
+
+        let button_hints = Row::new(
+            Point::new(
+                x + w as i32 - 12,
+                y + h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            vec![
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::A,
+                    locale.t("button-select"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::B,
+                    locale.t("button-back"),
+                    Alignment::Right,
+                ),
+            ],
+            Alignment::Right,
+            12,
+        );
+
+        drop(locale);
+        drop(styles);
+
+        Self {
+            rect,
+            title,
+            list,
+            button_hints,
+            presets,
+            dirty: true,
+        }
+    }
+}
+
+/// Recursively collects `.glslp` presets.
+fn find_presets(dir: &Path, presets: &mut Vec<PathBuf>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!("failed to read shaders directory {}: {}", dir.display(), e);
+            return;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            find_presets(&path, presets);
+        } else if path.extension().is_some_and(|ext| ext == "glslp") {
+            presets.push(path);
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl View for DisplayFilter {
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        let mut drawn = false;
+
+        if self.dirty {
+            display.load(self.rect)?;
+            self.dirty = false;
+            drawn = true;
+        }
+
+        drawn |= self.title.should_draw() && self.title.draw(display, styles)?;
+        drawn |= self.list.should_draw() && self.list.draw(display, styles)?;
+        drawn |= self.button_hints.should_draw() && self.button_hints.draw(display, styles)?;
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.dirty
+            || self.title.should_draw()
+            || self.list.should_draw()
+            || self.button_hints.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.dirty = true;
+        self.title.set_should_draw();
+        self.list.set_should_draw();
+        self.button_hints.set_should_draw();
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        match event {
+            KeyEvent::Pressed(Key::A) => {
+                let path = match self.list.selected() {
+                    0 => String::new(),
+                    i => self.presets[i - 1].to_string_lossy().to_string(),
+                };
+                RetroArchCommand::SetShader(path).send().await?;
+                commands.send(Command::Exit).await?;
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::B) => {
+                bubble.push_back(Command::CloseView);
+                Ok(true)
+            }
+            event => self.list.handle_key_event(event, commands, bubble).await,
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.title, &self.list, &self.button_hints]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![&mut self.title, &mut self.list, &mut self.button_hints]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
+
+impl IngameMenuChild for DisplayFilter {}
@@ -0,0 +1,248 @@
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use common::command::Command;
+use common::display::Display;
+use common::geom::{Alignment, Point, Rect};
+use common::locale::Locale;
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::stylesheet::Stylesheet;
+use common::view::{ButtonHint, ButtonIcon, CbzReader, Label, Row, ScrollList, View};
+use tokio::sync::mpsc::Sender;
+
+use crate::view::IngameMenuChild;
+use crate::view::pdf_reader::PdfReader;
+use crate::view::text_reader::TextReader;
+
+/// Opens a guide with the reader for its file type.
+pub fn guide_reader(rect: Rect, res: Resources, path: PathBuf) -> Box<dyn IngameMenuChild> {
+    let ext = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase());
+    match ext.as_deref() {
+        Some("pdf") => Box::new(PdfReader::new(rect, res, path)),
+        Some("cbz") => Box::new(CbzReader::new(rect, res, path)),
+        _ => Box::new(TextReader::new(rect, res, path)),
+    }
+}
+
+/// Lists the guides of a game that has more than one, and opens the selected one.
+pub struct GuideList {
+    rect: Rect,
+    res: Resources,
+    title: Label<String>,
+    list: ScrollList,
+    button_hints: Row<ButtonHint<String>>,
+    guides: Vec<PathBuf>,
+    reader: Option<Box<dyn IngameMenuChild>>,
+    dirty: bool,
+}
+
+impl GuideList {
+    /// Creates the list, with `open` already open in a reader if given.
+    pub fn new(rect: Rect, res: Resources, guides: Vec<PathBuf>, open: Option<&Path>) -> Self {
+        let Rect { x, y, w, h } = rect;
+
+        let locale = res.get::<Locale>();
+        let styles = res.get::<Stylesheet>();
+
+        let title = Label::new(
+            Point::new(x + 12, y + 8),
+            locale.t("ingame-menu-guide"),
+            Alignment::Left,
+            None,
+        );
+
+        let mut list = ScrollList::new(
+            Rect::new(
+                x + 12,
+                y + 8 + ButtonIcon::diameter(&styles) as i32 + 8,
+                w - 24,
+                h - 8 - ButtonIcon::diameter(&styles) - 8 - ButtonIcon::diameter(&styles) - 8,
+            ),
+            guides
+                .iter()
+                .map(|p| {
+                    p.file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .to_string()
+                })
+                .collect(),
+            Alignment::Left,
+            styles.row_height(),
+        );
+
+        let button_hints = Row::new(
+            Point::new(
+                x + w as i32 - 12,
+                y + h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            vec![
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::A,
+                    locale.t("button-select"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::B,
+                    locale.t("button-back"),
+                    Alignment::Right,
+                ),
+            ],
+            Alignment::Right,
+            12,
+        );
+
+        drop(locale);
+        drop(styles);
+
+        let reader = open
+            .and_then(|open| guides.iter().position(|g| g == open))
+            .map(|i| {
+                list.select(i);
+                guide_reader(rect, res.clone(), guides[i].clone())
+            });
+
+        Self {
+            rect,
+            res,
+            title,
+            list,
+            button_hints,
+            guides,
+            reader,
+            dirty: true,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl View for GuideList {
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        if let Some(reader) = self.reader.as_mut() {
+            return Ok(reader.should_draw() && reader.draw(display, styles)?);
+        }
+
+        let mut drawn = false;
+
+        if self.dirty {
+            display.load(self.rect)?;
+            self.dirty = false;
+            drawn = true;
+        }
+
+        drawn |= self.title.should_draw() && self.title.draw(display, styles)?;
+        drawn |= self.list.should_draw() && self.list.draw(display, styles)?;
+        drawn |= self.button_hints.should_draw() && self.button_hints.draw(display, styles)?;
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        if let Some(reader) = self.reader.as_ref() {
+            reader.should_draw()
+        } else {
+            self.dirty
+                || self.title.should_draw()
+                || self.list.should_draw()
+                || self.button_hints.should_draw()
+        }
+    }
+
+    fn set_should_draw(&mut self) {
+        if let Some(reader) = self.reader.as_mut() {
+            reader.set_should_draw();
+        } else {
+            self.dirty = true;
+            self.title.set_should_draw();
+            self.list.set_should_draw();
+            self.button_hints.set_should_draw();
+        }
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        if let Some(reader) = self.reader.as_mut() {
+            if reader
+                .handle_key_event(event, commands.clone(), bubble)
+                .await?
+            {
+                let mut close = false;
+                bubble.retain(|cmd| match cmd {
+                    Command::CloseView => {
+                        close = true;
+                        false
+                    }
+                    _ => true,
+                });
+                if close {
+                    self.reader = None;
+                    self.set_should_draw();
+                }
+            }
+            return Ok(true);
+        }
+
+        match event {
+            KeyEvent::Pressed(Key::A) => {
+                if let Some(guide) = self.guides.get(self.list.selected()) {
+                    self.reader = Some(guide_reader(self.rect, self.res.clone(), guide.clone()));
+                }
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::B) => {
+                bubble.push_back(Command::CloseView);
+                Ok(true)
+            }
+            event => self.list.handle_key_event(event, commands, bubble).await,
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.title, &self.list, &self.button_hints]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![&mut self.title, &mut self.list, &mut self.button_hints]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
+
+impl IngameMenuChild for GuideList {
+    fn save(&self) {
+        if let Some(reader) = self.reader.as_ref() {
+            reader.save();
+        }
+    }
+
+    fn restore_on_open(&self) -> bool {
+        self.reader.as_ref().is_some_and(|r| r.restore_on_open())
+    }
+
+    fn guide(&self) -> Option<&Path> {
+        self.reader.as_ref().and_then(|r| r.guide())
+    }
+}
@@ -2,39 +2,107 @@ use std::collections::{HashMap, VecDeque};
 use std::fs;
 use std::fs::File;
 use std::marker::PhantomData;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-use anyhow::Result;
+use anyhow::{Result, bail};
 use async_trait::async_trait;
-use base32::encode;
+use chrono::{DateTime, Utc};
 use common::battery::Battery;
 use common::command::Command;
-use common::constants::{
-    ALLIUM_MENU_STATE, ALLIUM_SCREENSHOTS_DIR, SAVE_STATE_IMAGE_WIDTH, SELECTION_MARGIN,
-};
+use common::constants::{ALLIUM_MENU_STATE, SAVE_STATE_IMAGE_WIDTH};
 use common::display::Display;
 use common::game_info::GameInfo;
 use common::geom::{Alignment, Point, Rect};
 use common::locale::Locale;
+use common::performance::{CpuProfile, PerformanceSettings, PowerProfile};
 use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
 use common::resources::Resources;
-use common::retroarch::RetroArchCommand;
+use common::retroarch::{RetroArchCommand, core_name};
+use common::save_backup::SaveBackupSettings;
+use common::save_state::{self, SaveState};
 use common::stylesheet::Stylesheet;
 use common::view::{
-    BatteryIndicator, ButtonHint, ButtonIcon, Clock, Image, ImageMode, Label, NullView, Row,
-    SettingsList, View,
+    BatteryIndicator, ButtonHint, ButtonIcon, Clock, Image, ImageMode, Label, NullView,
+    PowerProfileIndicator, Row, SettingsList, Toggle, View,
 };
 use log::warn;
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
 use tokio::sync::mpsc::Sender;
 
+use crate::overrides;
 use crate::retroarch_info::RetroArchInfo;
-use crate::view::text_reader::TextReader;
+use crate::view::IngameMenuChild;
+use crate::view::display_filter::DisplayFilter;
+use crate::view::guide_list::{GuideList, guide_reader};
+use crate::view::overrides::Overrides;
+use crate::view::rewind::Rewind;
+
+/// How long polled RetroArch info is ignored after a slot is changed in the menu, as the poll may
+/// have started before the change.
+const LOCAL_CHANGE_GRACE: Duration = Duration::from_secs(2);
 
 #[derive(Serialize, Deserialize, Default)]
 pub struct IngameMenuState {
     is_text_reader_open: bool,
+    /// Guide that was open, for games with more than one.
+    #[serde(default)]
+    guide: Option<PathBuf>,
+    #[serde(default)]
+    speed: GameSpeed,
+    #[serde(default)]
+    performance_overlay: Option<PerformanceOverlay>,
+}
+
+/// The performance overlay process, and the game session it was turned on in. The overlay exits
+/// with the game.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct PerformanceOverlay {
+    session: DateTime<Utc>,
+    pid: u32,
+}
+
+impl PerformanceOverlay {
+    /// Whether the process is still the overlay, as the PID could have been reused.
+    fn is_running(&self) -> bool {
+        fs::read(format!("/proc/{}/cmdline", self.pid)).is_ok_and(|cmdline| {
+            let mut args = cmdline.split(|&b| b == 0);
+            args.next()
+                .is_some_and(|bin| bin.ends_with(PERFORMANCE_OVERLAY.as_bytes()))
+                && args.next() == Some(b"stats")
+        })
+    }
+
+    /// Stops the overlay and waits for it to exit.
+    async fn stop(&self) -> Result<()> {
+        if !self.is_running() {
+            return Ok(());
+        }
+        tokio::process::Command::new("kill")
+            .arg(self.pid.to_string())
+            .status()
+            .await?;
+        let deadline = Instant::now() + PERFORMANCE_OVERLAY_EXIT_TIMEOUT;
+        while self.is_running() {
+            if Instant::now() > deadline {
+                bail!("performance overlay {} didn't exit", self.pid);
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        Ok(())
+    }
+}
+
+const PERFORMANCE_OVERLAY: &str = "allium-overlay";
+const PERFORMANCE_OVERLAY_EXIT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Fast-forward and slow-motion are toggled in RetroArch and can't be queried, so we remember
+/// them here. The state is tied to the game session it was set in.
+#[derive(Serialize, Deserialize, Default, Clone, Copy)]
+struct GameSpeed {
+    session: Option<DateTime<Utc>>,
+    fast_forward: bool,
+    slow_motion: bool,
 }
 
 pub struct IngameMenu<B>
@@ -46,10 +114,16 @@ where
     name: Label<String>,
     row: Row<Box<dyn View>>,
     menu: SettingsList,
-    child: Option<TextReader>,
+    child: Option<Box<dyn IngameMenuChild>>,
     button_hints: Row<ButtonHint<String>>,
     entries: Vec<MenuEntry>,
     retroarch_info: Option<RetroArchInfo>,
+    /// When a slot was last changed in the menu.
+    local_change: Option<Instant>,
+    speed: GameSpeed,
+    performance_overlay: Option<PerformanceOverlay>,
+    cpu_profile: CpuProfile,
+    power_profile: PowerProfile,
     path: PathBuf,
     image: Image,
     dirty: bool,
@@ -94,6 +168,12 @@ where
             children.push(Box::new(clock));
         }
 
+        children.push(Box::new(PowerProfileIndicator::new(
+            res.clone(),
+            Point::new(0, 0),
+            Alignment::Right,
+        )));
+
         let row: Row<Box<dyn View>> = Row::new(
             Point::new(w as i32 - 12, y + 8),
             children,
@@ -101,7 +181,9 @@ where
             8,
         );
 
-        let entries = MenuEntry::entries(&retroarch_info);
+        let rewind_enabled = retroarch_info.is_some()
+            && overrides::resolve_option(&game_info, "rewind_enable").as_deref() == Some("true");
+        let entries = MenuEntry::entries(&retroarch_info, rewind_enabled);
         let mut menu = SettingsList::new(
             Rect::new(
                 x + 12,
@@ -114,7 +196,7 @@ where
                 .iter()
                 .map(|_| Box::new(NullView) as Box<dyn View>)
                 .collect(),
-            styles.ui_font.size + SELECTION_MARGIN,
+            styles.row_height(),
         );
         if let Some(info) = retroarch_info.as_ref()
             && info.max_disk_slots > 1
@@ -133,6 +215,64 @@ where
             );
         }
 
+        let speed = if state.speed.session == Some(game_info.start_time) {
+            state.speed
+        } else {
+            GameSpeed {
+                session: Some(game_info.start_time),
+                ..Default::default()
+            }
+        };
+        set_speed_toggles(&mut menu, &entries, speed);
+
+        let performance_overlay = state
+            .performance_overlay
+            .filter(|overlay| overlay.session == game_info.start_time);
+        if let Some(i) = entries
+            .iter()
+            .position(|e| *e == MenuEntry::PerformanceOverlay)
+        {
+            menu.set_right(
+                i,
+                Box::new(Toggle::new(
+                    Point::zero(),
+                    performance_overlay.is_some(),
+                    Alignment::Right,
+                )),
+            );
+        }
+
+        let cpu_profile = game_info.cpu_profile;
+        if let Some(i) = entries.iter().position(|e| *e == MenuEntry::CpuProfile) {
+            menu.set_right(
+                i,
+                Box::new(Label::new(
+                    Point::zero(),
+                    cpu_profile.text(&locale),
+                    Alignment::Right,
+                    None,
+                )),
+            );
+        }
+
+        let power_profile = PerformanceSettings::load()
+            .map(|s| s.profile)
+            .unwrap_or_else(|e| {
+                warn!("failed to load performance settings: {}", e);
+                PowerProfile::default()
+            });
+        if let Some(i) = entries.iter().position(|e| *e == MenuEntry::PowerProfile) {
+            menu.set_right(
+                i,
+                Box::new(Label::new(
+                    Point::zero(),
+                    power_profile_text(&locale, power_profile),
+                    Alignment::Right,
+                    None,
+                )),
+            );
+        }
+
         let mut image = Image::empty(
             Rect::new(
                 x + w as i32 - SAVE_STATE_IMAGE_WIDTH as i32 - 24,
@@ -170,12 +310,12 @@ where
             12,
         );
 
-        let mut child = None;
+        let mut child: Option<Box<dyn IngameMenuChild>> = None;
         if state.is_text_reader_open
-            && let Some(guide) = game_info.guide.as_ref()
+            && let Some(guide) = open_guide(rect, res.clone(), &game_info, state.guide.as_deref())
         {
             menu.select(MenuEntry::Guide as usize);
-            child = Some(TextReader::new(rect, res.clone(), guide.clone()));
+            child = Some(guide);
         }
 
         let path = game_info.path.clone();
@@ -194,6 +334,11 @@ where
             button_hints,
             entries,
             retroarch_info,
+            local_change: None,
+            speed,
+            performance_overlay,
+            cpu_profile,
+            power_profile,
             path,
             image,
             dirty: false,
@@ -222,10 +367,17 @@ where
     pub fn save(&self) -> Result<()> {
         let file = File::create(ALLIUM_MENU_STATE.as_path())?;
         let state = IngameMenuState {
-            is_text_reader_open: self.child.is_some(),
+            is_text_reader_open: self.child.as_ref().is_some_and(|c| c.restore_on_open()),
+            guide: self
+                .child
+                .as_ref()
+                .and_then(|c| c.guide())
+                .map(Path::to_path_buf),
+            speed: self.speed,
+            performance_overlay: self.performance_overlay,
         };
         if let Some(child) = self.child.as_ref() {
-            child.save_cursor();
+            child.save();
         }
         serde_json::to_writer(file, &state)?;
         Ok(())
@@ -239,16 +391,21 @@ where
             }
             MenuEntry::Save => {
                 let slot = self.retroarch_info.as_ref().unwrap().state_slot.unwrap();
+                if let Err(e) = self.backup_state(slot) {
+                    warn!("failed to back up save state: {}", e);
+                }
                 RetroArchCommand::SaveStateSlot(slot).send().await?;
                 let core = self.res.get::<GameInfo>().core.to_owned();
                 commands
-                    .send(Command::SaveStateScreenshot {
-                        path: self.path.canonicalize()?.to_string_lossy().to_string(),
-                        core,
-                        slot,
-                    })
+                    .send(Command::Batch(vec![
+                        Command::SaveStateScreenshot {
+                            path: self.path.canonicalize()?.to_string_lossy().to_string(),
+                            core,
+                            slot,
+                        },
+                        Command::Exit,
+                    ]))
                     .await?;
-                commands.send(Command::Exit).await?;
             }
             MenuEntry::Load => {
                 RetroArchCommand::LoadStateSlot(
@@ -258,15 +415,86 @@ where
                 .await?;
                 commands.send(Command::Exit).await?;
             }
+            MenuEntry::Screenshot => {
+                commands.send(Command::TakeScreenshot).await?;
+            }
+            MenuEntry::FastForward => {
+                if self.speed.slow_motion {
+                    RetroArchCommand::SlowMotion.send().await?;
+                    self.speed.slow_motion = false;
+                }
+                RetroArchCommand::FastForward.send().await?;
+                self.speed.fast_forward = !self.speed.fast_forward;
+                set_speed_toggles(&mut self.menu, &self.entries, self.speed);
+            }
+            MenuEntry::SlowMotion => {
+                if self.speed.fast_forward {
+                    RetroArchCommand::FastForward.send().await?;
+                    self.speed.fast_forward = false;
+                }
+                RetroArchCommand::SlowMotion.send().await?;
+                self.speed.slow_motion = !self.speed.slow_motion;
+                set_speed_toggles(&mut self.menu, &self.entries, self.speed);
+            }
+            MenuEntry::Rewind => {
+                self.child = Some(Box::new(Rewind::new(self.rect, self.res.clone())));
+                self.set_should_draw();
+            }
             MenuEntry::Reset => {
                 RetroArchCommand::Reset.send().await?;
                 commands.send(Command::Exit).await?;
             }
             MenuEntry::Guide => {
-                if let Some(guide) = self.res.get::<GameInfo>().guide.as_ref() {
-                    self.child = Some(TextReader::new(self.rect, self.res.clone(), guide.clone()));
+                let guide = open_guide(self.rect, self.res.clone(), &self.res.get(), None);
+                if guide.is_some() {
+                    self.child = guide;
+                    self.set_should_draw();
                 }
             }
+            MenuEntry::DisplayFilter => {
+                self.child = Some(Box::new(DisplayFilter::new(self.rect, self.res.clone())));
+                self.set_should_draw();
+            }
+            MenuEntry::Overrides => {
+                self.child = Some(Box::new(Overrides::new(self.rect, self.res.clone())));
+                self.set_should_draw();
+            }
+            MenuEntry::PerformanceOverlay => {
+                if let Some(overlay) = self.performance_overlay.take() {
+                    if let Err(e) = overlay.stop().await {
+                        warn!("failed to stop performance overlay: {}", e);
+                    }
+                } else {
+                    match tokio::process::Command::new(PERFORMANCE_OVERLAY)
+                        .arg("stats")
+                        .spawn()
+                    {
+                        Ok(child) => {
+                            self.performance_overlay = child.id().map(|pid| PerformanceOverlay {
+                                session: self.res.get::<GameInfo>().start_time,
+                                pid,
+                            })
+                        }
+                        Err(e) => warn!("failed to start performance overlay: {}", e),
+                    }
+                }
+                let i = self.menu.selected();
+                self.menu.set_right(
+                    i,
+                    Box::new(Toggle::new(
+                        Point::zero(),
+                        self.performance_overlay.is_some(),
+                        Alignment::Right,
+                    )),
+                );
+            }
+            MenuEntry::CpuProfile => {
+                self.set_cpu_profile(self.cpu_profile.next(), commands)
+                    .await?;
+            }
+            MenuEntry::PowerProfile => {
+                self.set_power_profile(self.power_profile.next()).await?;
+            }
             MenuEntry::Settings => {
                 RetroArchCommand::Unpause.send().await?;
                 RetroArchCommand::MenuToggle.send().await?;
@@ -275,27 +503,160 @@ where
             MenuEntry::Quit => {
                 if self.retroarch_info.is_some() {
                     let core = self.res.get::<GameInfo>().core.to_owned();
+                    let screenshot = Command::SaveStateScreenshot {
+                        path: self.path.canonicalize()?.to_string_lossy().to_string(),
+                        core,
+                        slot: -1,
+                    };
+                    RetroArchCommand::Quit.send().await?;
                     commands
-                        .send(Command::SaveStateScreenshot {
-                            path: self.path.canonicalize()?.to_string_lossy().to_string(),
-                            core,
-                            slot: -1,
-                        })
+                        .send(Command::Batch(vec![screenshot, Command::Exit]))
                         .await?;
-                    RetroArchCommand::Quit.send().await?;
                 } else {
                     tokio::process::Command::new("pkill")
                         .arg("retroarch")
                         .spawn()?
                         .wait()
                         .await?;
+                    commands.send(Command::Exit).await?;
                 }
-                commands.send(Command::Exit).await?;
             }
         }
         Ok(true)
     }
 
+    async fn set_cpu_profile(
+        &mut self,
+        cpu_profile: CpuProfile,
+        commands: Sender<Command>,
+    ) -> Result<()> {
+        self.cpu_profile = cpu_profile;
+        self.update_cpu_profile_label();
+        commands.send(Command::SetCpuProfile(cpu_profile)).await?;
+        Ok(())
+    }
+
+    fn update_cpu_profile_label(&mut self) {
+        if let Some(i) = self
+            .entries
+            .iter()
+            .position(|e| *e == MenuEntry::CpuProfile)
+        {
+            self.menu.set_right(
+                i,
+                Box::new(Label::new(
+                    Point::zero(),
+                    self.cpu_profile.text(&self.res.get()),
+                    Alignment::Right,
+                    None,
+                )),
+            );
+        }
+    }
+
+    /// Switches the global power profile, which alliumd applies once it reloads the settings.
+    async fn set_power_profile(&mut self, power_profile: PowerProfile) -> Result<()> {
+        self.power_profile = power_profile;
+        self.update_power_profile_label();
+        let mut settings = PerformanceSettings::load()?;
+        settings.profile = power_profile;
+        settings.save()?;
+        #[cfg(unix)]
+        tokio::process::Command::new("pkill")
+            .arg("-HUP")
+            .arg("alliumd")
+            .spawn()?
+            .wait()
+            .await?;
+        Ok(())
+    }
+
+    fn update_power_profile_label(&mut self) {
+        if let Some(i) = self
+            .entries
+            .iter()
+            .position(|e| *e == MenuEntry::PowerProfile)
+        {
+            self.menu.set_right(
+                i,
+                Box::new(Label::new(
+                    Point::zero(),
+                    power_profile_text(&self.res.get(), self.power_profile),
+                    Alignment::Right,
+                    None,
+                )),
+            );
+        }
+    }
+
+    fn update_disk_label(&mut self, i: usize, disk_slot: u8) {
+        let mut map = HashMap::new();
+        map.insert("disk".into(), (disk_slot + 1).into());
+        self.menu.set_right(
+            i,
+            Box::new(Label::new(
+                Point::zero(),
+                self.res.get::<Locale>().ta("ingame-menu-disk", &map),
+                Alignment::Right,
+                None,
+            )),
+        );
+    }
+
+    /// Keeps the state that is about to be overwritten.
+    fn backup_state(&self, slot: i8) -> Result<()> {
+        let Some(core_name) = self
+            .res
+            .get::<GameInfo>()
+            .retroarch_core()
+            .and_then(core_name)
+        else {
+            return Ok(());
+        };
+        let Some(stem) = self.path.file_stem() else {
+            return Ok(());
+        };
+        let state = SaveState {
+            slot,
+            path: SaveState::path(&core_name, &stem.to_string_lossy(), slot),
+        };
+        let settings = SaveBackupSettings::load()?;
+        state.backup(settings.state_backups.max(0) as usize)
+    }
+
+    /// Updates the menu with RetroArch's latest state, e.g. after a slot was changed with a
+    /// hotkey.
+    pub fn set_retroarch_info(&mut self, info: RetroArchInfo) {
+        if self
+            .local_change
+            .is_some_and(|changed| changed.elapsed() < LOCAL_CHANGE_GRACE)
+        {
+            return;
+        }
+        let Some(current) = self.retroarch_info.as_mut() else {
+            return;
+        };
+
+        // The menu entries depend on whether the core supports save states, so keep that as is
+        let state_slot = current
+            .state_slot
+            .map(|slot| info.state_slot.unwrap_or(slot));
+        let disk_changed = current.disk_slot != info.disk_slot;
+        let state_changed = current.state_slot != state_slot;
+        *current = RetroArchInfo { state_slot, ..info };
+
+        let selected = self.entries[self.menu.selected()];
+        if disk_changed && selected == MenuEntry::Continue && info.max_disk_slots > 1 {
+            self.update_disk_label(self.menu.selected(), info.disk_slot);
+        }
+        if let Some(state_slot) = state_slot
+            && state_changed
+            && matches!(selected, MenuEntry::Save | MenuEntry::Load)
+        {
+            self.update_state_slot_label(state_slot);
+        }
+    }
+
     fn update_state_slot_label(&mut self, state_slot: i8) {
         if state_slot == -1 {
             self.menu.set_right(
@@ -329,30 +690,40 @@ where
             .to_string();
         let slot = self.retroarch_info.as_ref().unwrap().state_slot.unwrap();
 
-        let mut hasher = Sha256::new();
-        hasher.update(&path);
-        hasher.update(&self.res.get::<GameInfo>().core);
-        hasher.update(slot.to_le_bytes());
-        let hash = hasher.finalize();
-        let base32 = encode(base32::Alphabet::Crockford, &hash);
-        let file_name = format!("{}.png", base32);
-        let mut screenshot_path = ALLIUM_SCREENSHOTS_DIR.join(file_name);
+        let mut screenshot_path =
+            save_state::screenshot_path(&path, &self.res.get::<GameInfo>().core, slot);
 
         // Previously, the hash did not include the core name. We try looking for that path as well.
         if !screenshot_path.exists() {
-            let mut hasher = Sha256::new();
-            hasher.update(&path);
-            hasher.update(slot.to_le_bytes());
-            let hash = hasher.finalize();
-            let base32 = encode(base32::Alphabet::Crockford, &hash);
-            let file_name = format!("{}.png", base32);
-            screenshot_path = ALLIUM_SCREENSHOTS_DIR.join(file_name);
+            screenshot_path = save_state::legacy_screenshot_path(&path, slot);
         }
 
         self.image.set_path(Some(screenshot_path));
     }
 }
 
+fn set_speed_toggles(menu: &mut SettingsList, entries: &[MenuEntry], speed: GameSpeed) {
+    for (i, entry) in entries.iter().enumerate() {
+        let value = match entry {
+            MenuEntry::FastForward => speed.fast_forward,
+            MenuEntry::SlowMotion => speed.slow_motion,
+            _ => continue,
+        };
+        menu.set_right(
+            i,
+            Box::new(Toggle::new(Point::zero(), value, Alignment::Right)),
+        );
+    }
+}
+
+fn power_profile_text(locale: &Locale, profile: PowerProfile) -> String {
+    match profile {
+        PowerProfile::BatterySaver => locale.t("power-profile-battery-saver"),
+        PowerProfile::Balanced => locale.t("power-profile-balanced"),
+        PowerProfile::Performance => locale.t("power-profile-performance"),
+    }
+}
+
 #[async_trait(?Send)]
 impl<B> View for IngameMenu<B>
 where
@@ -395,6 +766,38 @@ where
         }
     }
 
+    fn locale_changed(&mut self) -> Result<()> {
+        {
+            let locale = self.res.get::<Locale>();
+            for (i, entry) in self.entries.iter().enumerate() {
+                self.menu.set_left(i, entry.as_str(&locale));
+            }
+            for (i, text) in ["button-select", "button-back"].iter().enumerate() {
+                if let Some(hint) = self.button_hints.get_mut(i) {
+                    hint.set_text(locale.t(text));
+                }
+            }
+        }
+        self.update_cpu_profile_label();
+        self.update_power_profile_label();
+        let selected = self.entries[self.menu.selected()];
+        if let Some(info) = self.retroarch_info {
+            if selected == MenuEntry::Continue && info.max_disk_slots > 1 {
+                self.update_disk_label(self.menu.selected(), info.disk_slot);
+            }
+            if let Some(state_slot) = info.state_slot
+                && matches!(selected, MenuEntry::Save | MenuEntry::Load)
+            {
+                self.update_state_slot_label(state_slot);
+            }
+        }
+        if let Some(child) = self.child.as_mut() {
+            child.locale_changed()?;
+        }
+        self.set_should_draw();
+        Ok(())
+    }
+
     fn set_should_draw(&mut self) {
         self.dirty = true;
         if let Some(child) = self.child.as_mut() {
@@ -437,36 +840,20 @@ where
                 match event {
                     KeyEvent::Pressed(Key::Left) | KeyEvent::Autorepeat(Key::Left) => {
                         info.disk_slot = info.disk_slot.saturating_sub(1);
+                        self.local_change = Some(Instant::now());
                         RetroArchCommand::SetDiskSlot(info.disk_slot).send().await?;
 
-                        let mut map = HashMap::new();
-                        map.insert("disk".into(), (info.disk_slot + 1).into());
-                        self.menu.set_right(
-                            self.menu.selected(),
-                            Box::new(Label::new(
-                                Point::zero(),
-                                self.res.get::<Locale>().ta("ingame-menu-disk", &map),
-                                Alignment::Right,
-                                None,
-                            )),
-                        );
+                        let disk_slot = info.disk_slot;
+                        self.update_disk_label(self.menu.selected(), disk_slot);
                         return Ok(true);
                     }
                     KeyEvent::Pressed(Key::Right) | KeyEvent::Autorepeat(Key::Right) => {
                         info.disk_slot = (info.disk_slot + 1).min(info.max_disk_slots - 1);
+                        self.local_change = Some(Instant::now());
                         RetroArchCommand::SetDiskSlot(info.disk_slot).send().await?;
 
-                        let mut map = HashMap::new();
-                        map.insert("disk".into(), (info.disk_slot + 1).into());
-                        self.menu.set_right(
-                            self.menu.selected(),
-                            Box::new(Label::new(
-                                Point::zero(),
-                                self.res.get::<Locale>().ta("ingame-menu-disk", &map),
-                                Alignment::Right,
-                                None,
-                            )),
-                        );
+                        let disk_slot = info.disk_slot;
+                        self.update_disk_label(self.menu.selected(), disk_slot);
                         return Ok(true);
                     }
                     _ => {}
@@ -481,6 +868,7 @@ where
                     KeyEvent::Pressed(Key::Left) | KeyEvent::Autorepeat(Key::Left) => {
                         *state_slot = (*state_slot - 1).max(-1);
                         let state_slot = *state_slot;
+                        self.local_change = Some(Instant::now());
                         RetroArchCommand::SetStateSlot(state_slot).send().await?;
                         self.update_state_slot_label(state_slot);
                         return Ok(true);
@@ -488,6 +876,7 @@ where
                     KeyEvent::Pressed(Key::Right) | KeyEvent::Autorepeat(Key::Right) => {
                         *state_slot = state_slot.saturating_add(1);
                         let state_slot = *state_slot;
+                        self.local_change = Some(Instant::now());
                         RetroArchCommand::SetStateSlot(state_slot).send().await?;
                         self.update_state_slot_label(state_slot);
                         return Ok(true);
@@ -497,6 +886,36 @@ where
             }
         }
 
+        if self.entries[selected] == MenuEntry::CpuProfile {
+            match event {
+                KeyEvent::Pressed(Key::Left) => {
+                    self.set_cpu_profile(self.cpu_profile.prev(), commands)
+                        .await?;
+                    return Ok(true);
+                }
+                KeyEvent::Pressed(Key::Right) => {
+                    self.set_cpu_profile(self.cpu_profile.next(), commands)
+                        .await?;
+                    return Ok(true);
+                }
+                _ => {}
+            }
+        }
+
+        if self.entries[selected] == MenuEntry::PowerProfile {
+            match event {
+                KeyEvent::Pressed(Key::Left) => {
+                    self.set_power_profile(self.power_profile.prev()).await?;
+                    return Ok(true);
+                }
+                KeyEvent::Pressed(Key::Right) => {
+                    self.set_power_profile(self.power_profile.next()).await?;
+                    return Ok(true);
+                }
+                _ => {}
+            }
+        }
+
         match event {
             KeyEvent::Pressed(Key::A) => self.select_entry(commands).await,
             KeyEvent::Pressed(Key::Left | Key::Right)
@@ -513,24 +932,14 @@ where
                 let curr = self.menu.selected();
                 if consumed
                     && prev != curr
-                    && let Some(info) = self.retroarch_info.as_ref()
+                    && let Some(info) = self.retroarch_info
                 {
                     if info.max_disk_slots > 1 {
                         if prev == MenuEntry::Continue as usize {
                             self.menu.set_right(prev, Box::new(NullView));
                         }
                         if curr == MenuEntry::Continue as usize {
-                            let mut map = HashMap::new();
-                            map.insert("disk".into(), (info.disk_slot + 1).into());
-                            self.menu.set_right(
-                                curr,
-                                Box::new(Label::new(
-                                    Point::zero(),
-                                    self.res.get::<Locale>().ta("ingame-menu-disk", &map),
-                                    Alignment::Right,
-                                    None,
-                                )),
-                            );
+                            self.update_disk_label(curr, info.disk_slot);
                         }
                     }
 
@@ -581,9 +990,18 @@ pub enum MenuEntry {
     Continue,
     Save,
     Load,
+    Screenshot,
+    FastForward,
+    SlowMotion,
+    Rewind,
     Reset,
     Guide,
     Settings,
+    DisplayFilter,
+    Overrides,
+    CpuProfile,
+    PowerProfile,
+    PerformanceOverlay,
     Quit,
 }
 
@@ -593,15 +1011,24 @@ impl MenuEntry {
             MenuEntry::Continue => locale.t("ingame-menu-continue"),
             MenuEntry::Save => locale.t("ingame-menu-save"),
             MenuEntry::Load => locale.t("ingame-menu-load"),
+            MenuEntry::Screenshot => locale.t("ingame-menu-screenshot"),
+            MenuEntry::FastForward => locale.t("ingame-menu-fast-forward"),
+            MenuEntry::SlowMotion => locale.t("ingame-menu-slow-motion"),
+            MenuEntry::Rewind => locale.t("ingame-menu-rewind"),
             MenuEntry::Reset => locale.t("ingame-menu-reset"),
             MenuEntry::Guide => locale.t("ingame-menu-guide"),
             MenuEntry::Settings => locale.t("ingame-menu-settings"),
+            MenuEntry::DisplayFilter => locale.t("ingame-menu-display-filter"),
+            MenuEntry::Overrides => locale.t("ingame-menu-overrides"),
+            MenuEntry::CpuProfile => locale.t("ingame-menu-cpu-profile"),
+            MenuEntry::PowerProfile => locale.t("ingame-menu-power-profile"),
+            MenuEntry::PerformanceOverlay => locale.t("ingame-menu-performance-overlay"),
             MenuEntry::Quit => locale.t("ingame-menu-quit"),
         }
     }
 
-    fn entries(info: &Option<RetroArchInfo>) -> Vec<Self> {
-        match info {
+    fn entries(info: &Option<RetroArchInfo>, rewind_enabled: bool) -> Vec<Self> {
+        let mut entries = match info {
             Some(RetroArchInfo {
                 state_slot: Some(_),
                 ..
@@ -609,19 +1036,72 @@ impl MenuEntry {
                 MenuEntry::Continue,
                 MenuEntry::Save,
                 MenuEntry::Load,
+                MenuEntry::Screenshot,
+                MenuEntry::FastForward,
+                MenuEntry::SlowMotion,
+                MenuEntry::Rewind,
                 MenuEntry::Guide,
                 MenuEntry::Settings,
+                MenuEntry::DisplayFilter,
+                MenuEntry::Overrides,
+                MenuEntry::CpuProfile,
+                MenuEntry::PowerProfile,
+                MenuEntry::PerformanceOverlay,
                 MenuEntry::Reset,
                 MenuEntry::Quit,
             ],
             Some(_) => vec![
                 MenuEntry::Continue,
+                MenuEntry::Screenshot,
+                MenuEntry::FastForward,
+                MenuEntry::SlowMotion,
+                MenuEntry::Rewind,
                 MenuEntry::Reset,
                 MenuEntry::Guide,
                 MenuEntry::Settings,
+                MenuEntry::DisplayFilter,
+                MenuEntry::Overrides,
+                MenuEntry::CpuProfile,
+                MenuEntry::PowerProfile,
+                MenuEntry::PerformanceOverlay,
+                MenuEntry::Quit,
+            ],
+            None => vec![
+                MenuEntry::Continue,
+                MenuEntry::Screenshot,
+                MenuEntry::Guide,
+                MenuEntry::CpuProfile,
+                MenuEntry::PowerProfile,
+                MenuEntry::PerformanceOverlay,
                 MenuEntry::Quit,
             ],
-            None => vec![MenuEntry::Continue, MenuEntry::Guide, MenuEntry::Quit],
+        };
+        if !rewind_enabled {
+            entries.retain(|e| *e != MenuEntry::Rewind);
         }
+        entries
+    }
+}
+
+/// Opens the guide list if the game has several guides, or its only guide otherwise. `open` is a
+/// guide to open from the list straight away.
+fn open_guide(
+    rect: Rect,
+    res: Resources,
+    game_info: &GameInfo,
+    open: Option<&Path>,
+) -> Option<Box<dyn IngameMenuChild>> {
+    if game_info.guides.len() > 1 {
+        Some(Box::new(GuideList::new(
+            rect,
+            res,
+            game_info.guides.clone(),
+            open,
+        )))
+    } else {
+        game_info
+            .guide
+            .as_ref()
+            .map(|guide| guide_reader(rect, res, guide.clone()))
     }
 }
@@ -0,0 +1,161 @@
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanStyle {
+    Normal,
+    Heading,
+    Bold,
+    Code,
+}
+
+/// Styled part of a line of a Markdown guide.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span<'a> {
+    /// Byte range of the span in the line, including any markup it replaces.
+    pub range: Range<usize>,
+    /// Text to draw. This is the line's text in `range`, except for list bullets.
+    pub text: &'a str,
+    pub style: SpanStyle,
+}
+
+/// Returns the level and title of a heading line, e.g. `## Water Temple`.
+pub fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.bytes().take_while(|b| *b == b'#').count();
+    if (1..=6).contains(&level) && line[level..].starts_with(' ') {
+        Some((level, line[level..].trim()))
+    } else {
+        None
+    }
+}
+
+/// Splits a line of a Markdown guide into styled spans, hiding markup. `line_start` is whether the
+/// line starts a line of the file, rather than continuing a wrapped one.
+pub fn spans(line: &str, line_start: bool) -> Vec<Span<'_>> {
+    let mut spans = Vec::new();
+    let mut base = SpanStyle::Normal;
+    let mut i = 0;
+
+    if line_start {
+        if let Some((level, _)) = heading(line) {
+            base = SpanStyle::Heading;
+            i = level + 1;
+        } else {
+            let indent = line.len() - line.trim_start().len();
+            let rest = &line[indent..];
+            if ["- ", "* ", "+ "].iter().any(|b| rest.starts_with(b)) {
+                if indent > 0 {
+                    spans.push(Span {
+                        range: 0..indent,
+                        text: &line[..indent],
+                        style: SpanStyle::Normal,
+                    });
+                }
+                spans.push(Span {
+                    range: indent..indent + 2,
+                    text: "• ",
+                    style: SpanStyle::Normal,
+                });
+                i = indent + 2;
+            }
+        }
+    }
+
+    let mut style = base;
+    let mut start = i;
+    while i < line.len() {
+        let rest = &line[i..];
+        let (marker_len, next) = if rest.starts_with("**") && style != SpanStyle::Code {
+            (
+                2,
+                if style == SpanStyle::Bold {
+                    base
+                } else {
+                    SpanStyle::Bold
+                },
+            )
+        } else if rest.starts_with('`') {
+            (
+                1,
+                if style == SpanStyle::Code {
+                    base
+                } else {
+                    SpanStyle::Code
+                },
+            )
+        } else {
+            i += rest.chars().next().map_or(1, char::len_utf8);
+            continue;
+        };
+
+        if start < i {
+            spans.push(Span {
+                range: start..i,
+                text: &line[start..i],
+                style,
+            });
+        }
+        i += marker_len;
+        start = i;
+        style = next;
+    }
+    if start < line.len() {
+        spans.push(Span {
+            range: start..line.len(),
+            text: &line[start..],
+            style,
+        });
+    }
+
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spans() {
+        assert_eq!(heading("## Water Temple"), Some((2, "Water Temple")));
+        assert_eq!(heading("#hashtag"), None);
+
+        assert_eq!(
+            spans("## Water **Temple**", true),
+            vec![
+                Span {
+                    range: 3..9,
+                    text: "Water ",
+                    style: SpanStyle::Heading,
+                },
+                Span {
+                    range: 11..17,
+                    text: "Temple",
+                    style: SpanStyle::Bold,
+                },
+            ]
+        );
+
+        assert_eq!(
+            spans("- Use the `Hookshot`", true),
+            vec![
+                Span {
+                    range: 0..2,
+                    text: "• ",
+                    style: SpanStyle::Normal,
+                },
+                Span {
+                    range: 2..10,
+                    text: "Use the ",
+                    style: SpanStyle::Normal,
+                },
+                Span {
+                    range: 11..19,
+                    text: "Hookshot",
+                    style: SpanStyle::Code,
+                },
+            ]
+        );
+
+        // Wrapped lines don't start blocks
+        assert_eq!(spans("- not a list", false)[0].text, "- not a list");
+    }
+}
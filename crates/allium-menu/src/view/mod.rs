@@ -1,2 +1,42 @@
+mod display_filter;
+mod guide_list;
 pub mod ingame_menu;
+mod markdown;
+mod overrides;
+mod pdf_reader;
+mod rewind;
 mod text_reader;
+
+use std::path::Path;
+
+use common::view::{CbzReader, View};
+
+/// A view that takes over the in-game menu until it is closed.
+pub trait IngameMenuChild: View {
+    /// Persists any state that should survive the menu being closed.
+    fn save(&self) {}
+
+    /// Whether the child should be reopened the next time the menu is opened.
+    fn restore_on_open(&self) -> bool {
+        false
+    }
+
+    /// Guide open in the child, so that the same one is reopened with the menu.
+    fn guide(&self) -> Option<&Path> {
+        None
+    }
+}
+
+impl IngameMenuChild for CbzReader {
+    fn save(&self) {
+        self.save_page();
+    }
+
+    fn restore_on_open(&self) -> bool {
+        true
+    }
+
+    fn guide(&self) -> Option<&Path> {
+        Some(self.path())
+    }
+}
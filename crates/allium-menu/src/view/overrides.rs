@@ -0,0 +1,306 @@
+use std::collections::VecDeque;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use common::command::Command;
+use common::display::Display;
+use common::game_info::GameInfo;
+use common::geom::{Alignment, Point, Rect};
+use common::locale::Locale;
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::stylesheet::Stylesheet;
+use common::view::{
+    ButtonHint, ButtonIcon, Label, Number, Row, Select, SettingsList, Toggle, View,
+};
+use log::error;
+use tokio::sync::mpsc::Sender;
+
+use crate::overrides::{OverrideScope, RetroArchOverride};
+use crate::view::IngameMenuChild;
+
+/// RetroArch `aspect_ratio_index` values that are offered in the editor, with their locale keys.
+const ASPECT_RATIOS: [(i32, &str); 5] = [
+    (22, "overrides-aspect-ratio-core"),
+    (0, "overrides-aspect-ratio-4-3"),
+    (1, "overrides-aspect-ratio-16-9"),
+    (21, "overrides-aspect-ratio-square"),
+    (24, "overrides-aspect-ratio-full"),
+];
+
+const MAX_RUN_AHEAD_FRAMES: i32 = 4;
+
+/// Editor for RetroArch game and content directory overrides.
+pub struct Overrides {
+    rect: Rect,
+    res: Resources,
+    title: Label<String>,
+    list: SettingsList,
+    button_hints: Row<ButtonHint<String>>,
+    config: Option<RetroArchOverride>,
+    dirty: bool,
+}
+
+impl Overrides {
+    pub fn new(rect: Rect, res: Resources) -> Self {
+        let Rect { x, y, w, h } = rect;
+
+        let locale = res.get::<Locale>();
+        let styles = res.get::<Stylesheet>();
+
+        let config = load(&res, OverrideScope::Game);
+
+        let title = Label::new(
+            Point::new(x + 12, y + 8),
+            locale.t("ingame-menu-overrides"),
+            Alignment::Left,
+            None,
+        );
+
+        let mut right: Vec<Box<dyn View>> = vec![Box::new(Select::new(
+            Point::zero(),
+            0,
+            vec![
+                locale.t("overrides-scope-game"),
+                locale.t("overrides-scope-content-dir"),
+            ],
+            Alignment::Right,
+        ))];
+        right.extend(values(&locale, config.as_ref()));
+
+        let list = SettingsList::new(
+            Rect::new(
+                x + 12,
+                y + 8 + ButtonIcon::diameter(&styles) as i32 + 8,
+                w - 24,
+                h - 8 - ButtonIcon::diameter(&styles) - 8 - ButtonIcon::diameter(&styles) - 8,
+            ),
+            vec![
+                locale.t("overrides-scope"),
+                locale.t("overrides-integer-scaling"),
+                locale.t("overrides-aspect-ratio"),
+                locale.t("overrides-run-ahead"),
+            ],
+            right,
+            styles.row_height(),
+        );
+
+        let button_hints = Row::new(
+            Point::new(
+                x + w as i32 - 12,
+                y + h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            vec![
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::A,
+                    locale.t("button-edit"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::B,
+                    locale.t("button-back"),
+                    Alignment::Right,
+                ),
+            ],
+            Alignment::Right,
+            12,
+        );
+
+        drop(locale);
+        drop(styles);
+
+        Self {
+            rect,
+            res,
+            title,
+            list,
+            button_hints,
+            config,
+            dirty: true,
+        }
+    }
+
+    fn set_scope(&mut self, scope: OverrideScope) {
+        self.config = load(&self.res, scope);
+        let locale = self.res.get::<Locale>();
+        for (i, view) in values(&locale, self.config.as_ref())
+            .into_iter()
+            .enumerate()
+        {
+            self.list.set_right(i + 1, view);
+        }
+    }
+
+    fn save_config(&self) -> Result<()> {
+        if let Some(config) = self.config.as_ref() {
+            config.save()?;
+        }
+        Ok(())
+    }
+}
+
+fn load(res: &Resources, scope: OverrideScope) -> Option<RetroArchOverride> {
+    RetroArchOverride::load(&res.get::<GameInfo>(), scope)
+        .map_err(|e| error!("failed to load override: {}", e))
+        .ok()
+        .flatten()
+}
+
+fn values(locale: &Locale, config: Option<&RetroArchOverride>) -> Vec<Box<dyn View>> {
+    let integer_scaling = config
+        .and_then(|c| c.get_bool("video_scale_integer"))
+        .unwrap_or_default();
+    let aspect_ratio = config
+        .and_then(|c| c.get_int("aspect_ratio_index"))
+        .and_then(|i| ASPECT_RATIOS.iter().position(|(index, _)| *index == i))
+        .unwrap_or_default();
+    let run_ahead = if config
+        .and_then(|c| c.get_bool("run_ahead_enabled"))
+        .unwrap_or_default()
+    {
+        config
+            .and_then(|c| c.get_int("run_ahead_frames"))
+            .unwrap_or(1)
+    } else {
+        0
+    };
+
+    let disabled = locale.t("overrides-run-ahead-disabled");
+    vec![
+        Box::new(Toggle::new(
+            Point::zero(),
+            integer_scaling,
+            Alignment::Right,
+        )),
+        Box::new(Select::new(
+            Point::zero(),
+            aspect_ratio,
+            ASPECT_RATIOS.iter().map(|(_, key)| locale.t(key)).collect(),
+            Alignment::Right,
+        )),
+        Box::new(Number::new(
+            Point::zero(),
+            run_ahead,
+            0,
+            MAX_RUN_AHEAD_FRAMES,
+            1,
+            move |x: &i32| {
+                if *x == 0 {
+                    disabled.clone()
+                } else {
+                    x.to_string()
+                }
+            },
+            Alignment::Right,
+        )),
+    ]
+}
+
+#[async_trait(?Send)]
+impl View for Overrides {
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        let mut drawn = false;
+
+        if self.dirty {
+            display.load(self.rect)?;
+            self.dirty = false;
+            drawn = true;
+        }
+
+        drawn |= self.title.should_draw() && self.title.draw(display, styles)?;
+        drawn |= self.list.should_draw() && self.list.draw(display, styles)?;
+        drawn |= self.button_hints.should_draw() && self.button_hints.draw(display, styles)?;
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.dirty
+            || self.title.should_draw()
+            || self.list.should_draw()
+            || self.button_hints.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.dirty = true;
+        self.title.set_should_draw();
+        self.list.set_should_draw();
+        self.button_hints.set_should_draw();
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        if self
+            .list
+            .handle_key_event(event, commands.clone(), bubble)
+            .await?
+        {
+            while let Some(command) = bubble.pop_front() {
+                if let Command::ValueChanged(i, val) = command {
+                    if i == 0 {
+                        self.set_scope(OverrideScope::from_repr(val.as_int().unwrap() as usize));
+                        continue;
+                    }
+                    let Some(config) = self.config.as_mut() else {
+                        continue;
+                    };
+                    match i {
+                        1 => config.set("video_scale_integer", val.as_bool().unwrap()),
+                        2 => {
+                            let (index, _) = ASPECT_RATIOS[val.as_int().unwrap() as usize];
+                            config.set("aspect_ratio_index", index);
+                        }
+                        3 => {
+                            let frames = val.as_int().unwrap();
+                            config.set("run_ahead_enabled", frames > 0);
+                            if frames > 0 {
+                                config.set("run_ahead_frames", frames);
+                            }
+                        }
+                        _ => unreachable!("Invalid index"),
+                    }
+                    self.save_config()?;
+                }
+            }
+            return Ok(true);
+        }
+
+        match event {
+            KeyEvent::Pressed(Key::B) => {
+                bubble.push_back(Command::CloseView);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.title, &self.list, &self.button_hints]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![&mut self.title, &mut self.list, &mut self.button_hints]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
+
+impl IngameMenuChild for Overrides {}
@@ -0,0 +1,316 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use common::command::Command;
+use common::constants::ALLIUM_GUIDE_PAGE_CACHE;
+use common::database::Database;
+use common::game_info;
+use common::geom::{Alignment, Point, Rect};
+use common::locale::Locale;
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::stylesheet::Stylesheet;
+use common::view::{ButtonHint, ButtonIcon, PageViewport, Row, View};
+use image::RgbaImage;
+use log::{error, trace};
+use tokio::sync::mpsc::Sender;
+
+use crate::view::IngameMenuChild;
+
+/// Reader for PDF guides. Pages are rasterized to fit the screen width with `mutool`, and can be
+/// zoomed in and panned around.
+pub struct PdfReader {
+    rect: Rect,
+    res: Resources,
+    path: PathBuf,
+    /// Path to `mutool`, or None if it isn't installed.
+    mutool: Option<PathBuf>,
+    pages: usize,
+    /// Current page, starting from 0.
+    page: usize,
+    viewport: PageViewport,
+    button_hints: Row<ButtonHint<String>>,
+}
+
+impl PdfReader {
+    #[must_use]
+    pub fn new(rect: Rect, res: Resources, path: PathBuf) -> Self {
+        let mutool = game_info::pdf_renderer();
+        let pages = match mutool.as_deref() {
+            // Views are created synchronously, so let the runtime move other tasks off this
+            // thread while mutool runs
+            Some(mutool) => tokio::task::block_in_place(|| page_count(mutool, &path))
+                .map_err(|e| error!("failed to read pdf guide: {}", e))
+                .unwrap_or_default(),
+            None => {
+                error!("mutool is not installed, can't read pdf guide");
+                0
+            }
+        };
+
+        let page = res
+            .get::<Database>()
+            .get_guide_cursor(&path)
+            .map_err(|e| error!("failed to load guide cursor from database: {}", e))
+            .unwrap_or_default() as usize;
+
+        let Rect { x, y, w, h } = rect;
+
+        let locale = res.get::<Locale>();
+        let styles = res.get::<Stylesheet>();
+
+        let button_hints = Row::new(
+            Point::new(
+                x + w as i32 - 12,
+                y + h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            vec![
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::R,
+                    locale.t("guide-button-next-page"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::X,
+                    locale.t("guide-button-zoom-in"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::Y,
+                    locale.t("guide-button-zoom-out"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::B,
+                    locale.t("button-back"),
+                    Alignment::Right,
+                ),
+            ],
+            Alignment::Right,
+            12,
+        );
+
+        let viewport = PageViewport::new(rect, &styles);
+
+        drop(locale);
+        drop(styles);
+
+        let mut this = Self {
+            rect,
+            res,
+            path,
+            mutool,
+            pages,
+            page: page.min(pages.saturating_sub(1)),
+            viewport,
+            button_hints,
+        };
+        if let Some(job) = this.render_job() {
+            let image = tokio::task::block_in_place(job)
+                .map_err(|e| error!("failed to render pdf page: {}", e))
+                .ok();
+            this.viewport.set_image(image);
+        }
+        this
+    }
+
+    pub fn save_page(&self) {
+        self.res
+            .get::<Database>()
+            .update_guide_cursor(&self.path, self.page as u64)
+            .map_err(|e| error!("failed to update guide cursor to database: {}", e))
+            .ok();
+    }
+
+    /// Returns a job that rasterizes the current page at the current zoom, or None if there are
+    /// no pages.
+    fn render_job(&self) -> Option<impl FnOnce() -> Result<RgbaImage> + Send + 'static> {
+        let mutool = self.mutool.clone()?;
+        if self.pages == 0 {
+            return None;
+        }
+        let path = self.path.clone();
+        let page = self.page;
+        let width = self.viewport.rect().w * self.viewport.zoom();
+        Some(move || {
+            trace!("rendering pdf page {} at width {}", page + 1, width);
+            render_page(&mutool, &path, page, width)
+        })
+    }
+
+    /// Rasterizes the current page at the current zoom on a blocking task.
+    async fn render(&mut self) {
+        self.viewport.set_image(None);
+        let Some(job) = self.render_job() else {
+            return;
+        };
+        let image = match tokio::task::spawn_blocking(job).await {
+            Ok(Ok(image)) => Some(image),
+            Ok(Err(e)) => {
+                error!("failed to render pdf page: {}", e);
+                None
+            }
+            Err(e) => {
+                error!("pdf render task failed: {}", e);
+                None
+            }
+        };
+        self.viewport.set_image(image);
+    }
+
+    async fn set_page(&mut self, page: usize) {
+        if page < self.pages && page != self.page {
+            self.page = page;
+            self.viewport.reset_scroll();
+            self.render().await;
+        }
+    }
+
+    async fn set_zoom(&mut self, zoom: u32) {
+        if self.viewport.set_zoom(zoom) {
+            self.render().await;
+        }
+    }
+}
+
+/// Returns the number of pages in a PDF.
+fn page_count(mutool: &Path, path: &Path) -> Result<usize> {
+    let output = process::Command::new(mutool)
+        .arg("info")
+        .arg(path)
+        .output()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix("Pages:"))
+        .and_then(|pages| pages.trim().parse().ok())
+        .ok_or_else(|| anyhow!("missing page count"))
+}
+
+/// Rasterizes a page of a PDF, scaled to `width` pixels wide.
+fn render_page(mutool: &Path, path: &Path, page: usize, width: u32) -> Result<RgbaImage> {
+    if let Some(dir) = ALLIUM_GUIDE_PAGE_CACHE.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let status = process::Command::new(mutool)
+        .arg("draw")
+        .arg("-q")
+        .arg("-o")
+        .arg(ALLIUM_GUIDE_PAGE_CACHE.as_path())
+        .arg("-w")
+        .arg(width.to_string())
+        .arg(path)
+        .arg((page + 1).to_string())
+        .status()?;
+    if !status.success() {
+        return Err(anyhow!("mutool exited with {}", status));
+    }
+    Ok(::image::open(ALLIUM_GUIDE_PAGE_CACHE.as_path())?.to_rgba8())
+}
+
+#[async_trait(?Send)]
+impl View for PdfReader {
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        let mut drawn = false;
+
+        if self.viewport.should_draw() {
+            let locale = self.res.get::<Locale>();
+            let text = if self.viewport.has_image() {
+                let mut map = HashMap::new();
+                map.insert("page".into(), (self.page + 1).into());
+                map.insert("pages".into(), self.pages.into());
+                locale.ta("guide-page", &map)
+            } else {
+                locale.t("guide-pdf-error")
+            };
+            drawn |= self.viewport.draw(display, styles, &text)?;
+        }
+
+        drawn |= self.button_hints.draw(display, styles)?;
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.viewport.should_draw() || self.button_hints.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.viewport.set_should_draw();
+        self.button_hints.set_should_draw();
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        _commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        if self.viewport.handle_key_event(event) {
+            return Ok(true);
+        }
+        match event {
+            KeyEvent::Pressed(Key::L) | KeyEvent::Autorepeat(Key::L) => {
+                self.set_page(self.page.saturating_sub(1)).await
+            }
+            KeyEvent::Pressed(Key::R) | KeyEvent::Autorepeat(Key::R) => {
+                self.set_page(self.page + 1).await
+            }
+            KeyEvent::Pressed(Key::X) => self.set_zoom(self.viewport.zoom() + 1).await,
+            KeyEvent::Pressed(Key::Y) => {
+                self.set_zoom(self.viewport.zoom().saturating_sub(1)).await
+            }
+            KeyEvent::Pressed(Key::B) => {
+                self.save_page();
+                bubble.push_back(Command::CloseView);
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
+
+impl IngameMenuChild for PdfReader {
+    fn save(&self) {
+        self.save_page();
+    }
+
+    fn restore_on_open(&self) -> bool {
+        true
+    }
+
+    fn guide(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+}
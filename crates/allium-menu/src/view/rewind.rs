@@ -0,0 +1,200 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use common::command::Command;
+use common::display::Display;
+use common::geom::{Alignment, Point, Rect};
+use common::locale::Locale;
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::retroarch::RetroArchCommand;
+use common::stylesheet::Stylesheet;
+use common::view::{ButtonHint, ButtonIcon, Label, Row, View};
+use tokio::sync::mpsc::Sender;
+
+use crate::view::IngameMenuChild;
+
+/// Rewinds the game for as long as A is held.
+pub struct Rewind {
+    rect: Rect,
+    res: Resources,
+    title: Label<String>,
+    status: Label<String>,
+    button_hints: Row<ButtonHint<String>>,
+    held_since: Option<Instant>,
+    elapsed: Duration,
+    dirty: bool,
+}
+
+impl Rewind {
+    pub fn new(rect: Rect, res: Resources) -> Self {
+        let Rect { x, y, w, h } = rect;
+
+        let locale = res.get::<Locale>();
+        let styles = res.get::<Stylesheet>();
+
+        let title = Label::new(
+            Point::new(x + 12, y + 8),
+            locale.t("ingame-menu-rewind"),
+            Alignment::Left,
+            None,
+        );
+
+        let status = Label::new(
+            Point::new(
+                x + w as i32 / 2,
+                y + h as i32 / 2 - styles.ui_font.size as i32 / 2,
+            ),
+            locale.t("rewind-hold"),
+            Alignment::Center,
+            None,
+        );
+
+        let button_hints = Row::new(
+            Point::new(
+                x + w as i32 - 12,
+                y + h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            vec![
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::A,
+                    locale.t("ingame-menu-rewind"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::B,
+                    locale.t("button-back"),
+                    Alignment::Right,
+                ),
+            ],
+            Alignment::Right,
+            12,
+        );
+
+        drop(locale);
+        drop(styles);
+
+        Self {
+            rect,
+            res,
+            title,
+            status,
+            button_hints,
+            held_since: None,
+            elapsed: Duration::ZERO,
+            dirty: true,
+        }
+    }
+
+    fn update_status(&mut self) {
+        let elapsed = self.elapsed + self.held_since.map_or(Duration::ZERO, |t| t.elapsed());
+        let mut map = HashMap::new();
+        map.insert(
+            "seconds".into(),
+            format!("{:.1}", elapsed.as_secs_f32()).into(),
+        );
+        self.status
+            .set_text(self.res.get::<Locale>().ta("rewind-elapsed", &map));
+    }
+}
+
+#[async_trait(?Send)]
+impl View for Rewind {
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        let mut drawn = false;
+
+        if self.dirty {
+            display.load(self.rect)?;
+            self.dirty = false;
+            drawn = true;
+        } else if self.status.should_draw() {
+            display.load(Rect::new(
+                self.rect.x,
+                self.rect.y + self.rect.h as i32 / 2 - styles.ui_font.size as i32,
+                self.rect.w,
+                styles.ui_font.size * 2 + styles.row_spacing,
+            ))?;
+        }
+
+        drawn |= self.title.should_draw() && self.title.draw(display, styles)?;
+        drawn |= self.status.should_draw() && self.status.draw(display, styles)?;
+        drawn |= self.button_hints.should_draw() && self.button_hints.draw(display, styles)?;
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.dirty
+            || self.title.should_draw()
+            || self.status.should_draw()
+            || self.button_hints.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.dirty = true;
+        self.title.set_should_draw();
+        self.status.set_should_draw();
+        self.button_hints.set_should_draw();
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        _commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        match event {
+            KeyEvent::Pressed(Key::A) => {
+                self.held_since = Some(Instant::now());
+                RetroArchCommand::Rewind.send().await?;
+                self.update_status();
+                Ok(true)
+            }
+            KeyEvent::Autorepeat(Key::A) => {
+                RetroArchCommand::Rewind.send().await?;
+                self.update_status();
+                Ok(true)
+            }
+            KeyEvent::Released(Key::A) => {
+                if let Some(held_since) = self.held_since.take() {
+                    self.elapsed += held_since.elapsed();
+                }
+                self.update_status();
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::B) => {
+                bubble.push_back(Command::CloseView);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.title, &self.status, &self.button_hints]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![&mut self.title, &mut self.status, &mut self.button_hints]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
+
+impl IngameMenuChild for Rewind {}
@@ -1,4 +1,4 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::path::{Path, PathBuf};
 use std::{fs, mem};
 
@@ -11,8 +11,8 @@ use common::geom::{Alignment, Point, Rect};
 use common::locale::Locale;
 use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
 use common::resources::Resources;
-use common::stylesheet::Stylesheet;
-use common::view::{ButtonHint, Row, View};
+use common::stylesheet::{Stylesheet, StylesheetColor};
+use common::view::{ButtonHint, Row, ScrollList, View};
 use common::view::{ButtonIcon, Keyboard};
 use embedded_graphics::Drawable;
 use embedded_graphics::prelude::{Dimensions, Size};
@@ -21,16 +21,40 @@ use embedded_graphics::text::Text;
 use log::{error, trace};
 use tokio::sync::mpsc::Sender;
 
+use crate::view::IngameMenuChild;
+use crate::view::markdown::{self, Span, SpanStyle};
+
+/// Overlay listing positions in the guide to jump to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JumpList {
+    Bookmarks,
+    Contents,
+}
+
 pub struct TextReader {
     rect: Rect,
     res: Resources,
     path: PathBuf,
     text: String,
     lowercase_text: String,
+    /// Whether the guide is Markdown, and is drawn with formatting.
+    markdown: bool,
+    /// Offset, level and title of each Markdown heading.
+    headings: Vec<(usize, usize, String)>,
     cursor: usize,
+    /// Bookmarked cursor positions, in order.
+    bookmarks: Vec<usize>,
+    jump_list: Option<(JumpList, ScrollList)>,
     button_hints: Row<ButtonHint<String>>,
     keyboard: Option<Keyboard>,
     last_searched: String,
+    /// Whether a search is active, which shows the next and previous button hints.
+    searching: bool,
+    /// Byte offsets of the matches of the last search.
+    matches: Vec<usize>,
+    /// Length in bytes of each match.
+    match_len: usize,
+    current_match: Option<usize>,
     dirty: bool,
 }
 
@@ -40,7 +64,22 @@ impl TextReader {
         let text = fs::read_to_string(&path)
             .map_err(|e| error!("failed to load guide file: {}", e))
             .unwrap_or_default();
-        let lowercase_text = text.to_lowercase();
+        let lowercase_text = fold_case(&text);
+
+        let markdown = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("md") || e.eq_ignore_ascii_case("markdown"));
+        let mut headings = Vec::new();
+        if markdown {
+            let mut offset = 0;
+            for line in text.split_inclusive('\n') {
+                if let Some((level, title)) = markdown::heading(line) {
+                    headings.push((offset, level, title.to_owned()));
+                }
+                offset += line.len();
+            }
+        }
 
         let mut cursor = if text.is_empty() {
             0
@@ -51,12 +90,22 @@ impl TextReader {
             cursor -= 1;
         }
 
+        let bookmarks = res
+            .get::<Database>()
+            .select_guide_bookmarks(&path)
+            .map_err(|e| error!("failed to load guide bookmarks from database: {}", e))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|b| b as usize)
+            .filter(|b| text.is_char_boundary(*b))
+            .collect();
+
         let Rect { x, y, w, h } = rect;
 
         let locale = res.get::<Locale>();
         let styles = res.get::<Stylesheet>();
 
-        let button_hints = Row::new(
+        let mut button_hints = Row::new(
             Point::new(
                 x + w as i32 - 12,
                 y + h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
@@ -69,6 +118,20 @@ impl TextReader {
                     locale.t("guide-button-search"),
                     Alignment::Right,
                 ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::Y,
+                    locale.t("guide-button-bookmark"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::Select,
+                    locale.t("guide-button-bookmarks"),
+                    Alignment::Right,
+                ),
                 ButtonHint::new(
                     res.clone(),
                     Point::zero(),
@@ -80,6 +143,18 @@ impl TextReader {
             Alignment::Right,
             12,
         );
+        if !headings.is_empty() {
+            button_hints.insert(
+                3,
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::Start,
+                    locale.t("guide-button-contents"),
+                    Alignment::Right,
+                ),
+            );
+        }
 
         drop(locale);
         drop(styles);
@@ -90,11 +165,19 @@ impl TextReader {
             path,
             text,
             lowercase_text,
+            markdown,
+            headings,
             cursor,
+            bookmarks,
+            jump_list: None,
             button_hints,
             keyboard: None,
             dirty: true,
             last_searched: String::new(),
+            searching: false,
+            matches: Vec::new(),
+            match_len: 0,
+            current_match: None,
         }
     }
 
@@ -106,6 +189,85 @@ impl TextReader {
             .ok();
     }
 
+    /// Adds a bookmark at the current position, or removes it if there already is one.
+    fn toggle_bookmark(&mut self) {
+        let database = self.res.get::<Database>();
+        let result = match self.bookmarks.binary_search(&self.cursor) {
+            Ok(i) => {
+                self.bookmarks.remove(i);
+                database.delete_guide_bookmark(&self.path, self.cursor as u64)
+            }
+            Err(i) => {
+                self.bookmarks.insert(i, self.cursor);
+                database.add_guide_bookmark(&self.path, self.cursor as u64)
+            }
+        };
+        if let Err(e) = result {
+            error!("failed to update guide bookmarks in database: {}", e);
+        }
+        self.dirty = true;
+    }
+
+    fn jump_targets(&self, kind: JumpList) -> Vec<usize> {
+        match kind {
+            JumpList::Bookmarks => self.bookmarks.clone(),
+            JumpList::Contents => self.headings.iter().map(|(offset, ..)| *offset).collect(),
+        }
+    }
+
+    fn open_jump_list(&mut self, kind: JumpList) {
+        let targets = self.jump_targets(kind);
+        if targets.is_empty() {
+            return;
+        }
+
+        let labels = match kind {
+            JumpList::Bookmarks => targets
+                .iter()
+                .map(|b| {
+                    let line = self.text[*b..].lines().next().unwrap_or_default().trim();
+                    line.chars().take(40).collect()
+                })
+                .collect(),
+            JumpList::Contents => self
+                .headings
+                .iter()
+                .map(|(_, level, title)| format!("{}{}", "  ".repeat(level - 1), title))
+                .collect(),
+        };
+
+        let styles = self.res.get::<Stylesheet>();
+        let Rect { x, y, w, h } = self.rect;
+        let height = (targets.len() as u32 * styles.row_height())
+            .min(h - 24 - 8 - ButtonIcon::diameter(&styles) - 8);
+
+        let mut list = ScrollList::new(
+            Rect::new(
+                x + 12 + (w as i32 - 24) / 6,
+                y + (h as i32 - height as i32) / 2,
+                (w - 24) * 2 / 3,
+                height,
+            ),
+            Vec::new(),
+            Alignment::Left,
+            styles.row_height(),
+        );
+        list.set_items_with_details(
+            labels,
+            targets
+                .iter()
+                .map(|t| format!("{:.0}%", self.percentage(*t)))
+                .collect(),
+            false,
+        );
+        list.set_background_color(Some(StylesheetColor::BackgroundHighlightBlend));
+        self.jump_list = Some((kind, list));
+    }
+
+    fn percentage(&self, cursor: usize) -> f32 {
+        cursor as f32 / self.text.len().max(1) as f32 * 100.0
+    }
+
     fn visible_text(&self, styles: &Stylesheet) -> Vec<&str> {
         let line_count =
             (self.rect.h - 12 - 8 - ButtonIcon::diameter(styles) - 8) / styles.guide_font.size;
@@ -203,71 +365,87 @@ impl TextReader {
         &self.text[cursor..cursor + offset]
     }
 
-    fn search_forward(&mut self, needle: String) {
-        // Skip the current line
-        self.cursor += self.text[self.cursor..].find('\n').unwrap_or_default();
+    /// Finds all matches of `query`, and jumps to the first one after the current position.
+    fn search(&mut self, query: String) {
+        let needle = fold_case(&query);
+        self.matches = if needle.is_empty() {
+            Vec::new()
+        } else {
+            self.lowercase_text
+                .match_indices(&needle)
+                .map(|(i, _)| i)
+                .collect()
+        };
+        self.match_len = needle.len();
+        self.last_searched = query;
+        self.show_search_hints();
+
+        let next = self
+            .matches
+            .iter()
+            .position(|m| *m >= self.cursor)
+            .unwrap_or_default();
+        self.jump_to_match(next);
+    }
+
+    fn jump_to_match(&mut self, index: usize) {
+        self.dirty = true;
+        let Some(m) = self.matches.get(index) else {
+            self.current_match = None;
+            return;
+        };
+        self.current_match = Some(index);
 
-        if let Some(location) = self.lowercase_text[self.cursor..].find(&needle) {
-            self.cursor += location;
+        // Go back to the start of the line
+        self.cursor = self.text[..*m]
+            .rfind('\n')
+            .map(|i| i + 1)
+            .unwrap_or_default();
+    }
 
-            // Go back to the start of the line
-            self.cursor = self.text[..self.cursor].rfind('\n').unwrap_or_default() + 1;
-            self.cursor = self.cursor.clamp(0, self.text.len() - 1);
-            self.last_searched = needle;
-        } else {
-            self.cursor = 0;
-            self.search_forward(needle);
+    fn next_match(&mut self) {
+        if let Some(current) = self.current_match {
+            self.jump_to_match((current + 1) % self.matches.len());
         }
+    }
 
-        if self.button_hints.children().len() <= 2 {
-            let locale = self.res.get::<Locale>();
-            self.button_hints.push(ButtonHint::new(
-                self.res.clone(),
-                Point::zero(),
-                Key::L2,
-                locale.t("guide-next"),
-                Alignment::Right,
-            ));
-            self.button_hints.push(ButtonHint::new(
-                self.res.clone(),
-                Point::zero(),
-                Key::R2,
-                locale.t("guide-prev"),
-                Alignment::Right,
-            ));
+    fn prev_match(&mut self) {
+        if let Some(current) = self.current_match {
+            self.jump_to_match((current + self.matches.len() - 1) % self.matches.len());
         }
     }
 
-    fn search_backward(&mut self, needle: String) {
-        if let Some(location) = self.lowercase_text[..self.cursor].rfind(&needle) {
-            self.cursor = location;
-
-            // Go back to the start of the line
-            self.cursor = self.text[..self.cursor].rfind('\n').unwrap_or_default() + 1;
-            self.cursor = self.cursor.clamp(0, self.text.len() - 1);
-            self.last_searched = needle;
-        } else {
-            self.cursor = self.text.len();
-            self.search_backward(needle);
+    fn show_search_hints(&mut self) {
+        if self.searching {
+            return;
         }
+        self.searching = true;
+        let locale = self.res.get::<Locale>();
+        self.button_hints.push(ButtonHint::new(
+            self.res.clone(),
+            Point::zero(),
+            Key::L2,
+            locale.t("guide-button-prev"),
+            Alignment::Right,
+        ));
+        self.button_hints.push(ButtonHint::new(
+            self.res.clone(),
+            Point::zero(),
+            Key::R2,
+            locale.t("guide-button-next"),
+            Alignment::Right,
+        ));
+    }
 
-        if self.button_hints.children().len() <= 2 {
-            let locale = self.res.get::<Locale>();
-            self.button_hints.push(ButtonHint::new(
-                self.res.clone(),
-                Point::zero(),
-                Key::L,
-                locale.t("guide-next"),
-                Alignment::Right,
-            ));
-            self.button_hints.push(ButtonHint::new(
-                self.res.clone(),
-                Point::zero(),
-                Key::R,
-                locale.t("guide-prev"),
-                Alignment::Right,
-            ));
+    fn clear_search(&mut self) {
+        self.matches.clear();
+        self.current_match = None;
+        if self.searching {
+            self.button_hints.pop();
+            self.button_hints.pop();
+            self.searching = false;
         }
+        self.dirty = true;
     }
 
     fn move_back_lines(&mut self, lines: usize) {
@@ -341,6 +519,20 @@ impl TextReader {
     }
 }
 
+/// Lowercases `text` for case-insensitive search, keeping each character the same length so that
+/// offsets into the result are also offsets into `text`.
+fn fold_case(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            let mut lower = c.to_lowercase();
+            match (lower.next(), lower.next()) {
+                (Some(l), None) if l.len_utf8() == c.len_utf8() => l,
+                _ => c,
+            }
+        })
+        .collect()
+}
+
 fn load_cursor(database: &Database, path: &Path) -> usize {
     database
         .get_guide_cursor(path)
@@ -377,31 +569,140 @@ impl View for TextReader {
                 .text_color(styles.foreground_color)
                 .build();
 
+            let match_style = FontTextStyleBuilder::new(styles.guide_font.font())
+                .font_fallback(styles.cjk_font.font())
+                .font_size(styles.guide_font.size)
+                .background_color(styles.highlight_color)
+                .text_color(styles.foreground_color)
+                .build();
+
+            let style = |text_color, background_color: Option<_>| {
+                let mut builder = FontTextStyleBuilder::new(styles.guide_font.font())
+                    .font_fallback(styles.cjk_font.font())
+                    .font_size(styles.guide_font.size)
+                    .text_color(text_color);
+                if let Some(background_color) = background_color {
+                    builder = builder.background_color(background_color);
+                }
+                builder.build()
+            };
+            let heading_style = style(styles.tab_selected_color, Some(styles.background_color));
+            let code_style = style(
+                styles.foreground_color,
+                Some(StylesheetColor::BackgroundHighlightBlend.to_color(styles)),
+            );
+            // There's no bold font, so bold text is drawn again a pixel to the right
+            let heading_overlay_style = style(styles.tab_selected_color, None);
+            let bold_overlay_style = style(styles.foreground_color, None);
+
             let mut y = self.rect.y + 12 + 8;
             for line in self.visible_text(styles) {
-                let text = Text::new(
-                    line,
-                    Point::new(self.rect.x + 12 + 12, y).into(),
-                    text_style.clone(),
-                );
-                text.draw(display)?;
+                let start = line.as_ptr() as usize - self.text.as_ptr() as usize;
+                let spans = if self.markdown {
+                    markdown::spans(line, start == 0 || self.text[..start].ends_with('\n'))
+                } else {
+                    vec![Span {
+                        range: 0..line.len(),
+                        text: line,
+                        style: SpanStyle::Normal,
+                    }]
+                };
+
+                let mut x = self.rect.x + 12 + 12;
+                for span in spans {
+                    let text = Text::new(
+                        span.text,
+                        Point::new(x, y).into(),
+                        match span.style {
+                            SpanStyle::Normal | SpanStyle::Bold => text_style.clone(),
+                            SpanStyle::Heading => heading_style.clone(),
+                            SpanStyle::Code => code_style.clone(),
+                        },
+                    );
+                    let width = text.bounding_box().size.width as i32;
+                    text.draw(display)?;
+
+                    let overlay_style = match span.style {
+                        SpanStyle::Heading => Some(&heading_overlay_style),
+                        SpanStyle::Bold => Some(&bold_overlay_style),
+                        _ => None,
+                    };
+                    if let Some(overlay_style) = overlay_style {
+                        Text::new(
+                            span.text,
+                            Point::new(x + 1, y).into(),
+                            overlay_style.clone(),
+                        )
+                        .draw(display)?;
+                    }
+
+                    // Highlight the parts of matches in this span, unless it replaces markup
+                    if span.text.len() == span.range.len() {
+                        let span_start = start + span.range.start;
+                        let span_end = start + span.range.end;
+                        let first = self
+                            .matches
+                            .partition_point(|m| m + self.match_len <= span_start);
+                        for m in self.matches[first..].iter().take_while(|m| **m < span_end) {
+                            let from = (*m).max(span_start) - span_start;
+                            let to = (m + self.match_len).min(span_end) - span_start;
+                            let offset = Text::new(
+                                &span.text[..from],
+                                Point::zero().into(),
+                                text_style.clone(),
+                            )
+                            .bounding_box()
+                            .size
+                            .width as i32;
+                            Text::new(
+                                &span.text[from..to],
+                                Point::new(x + offset, y).into(),
+                                match_style.clone(),
+                            )
+                            .draw(display)?;
+                        }
+                    }
+
+                    x += width;
+                }
+
                 y += styles.guide_font.size as i32;
             }
 
-            Text::with_alignment(
-                &format!(
-                    "{:.0}%",
-                    self.cursor as f32 / self.text.len().max(1) as f32 * 100.0
-                ),
-                Point::new(
-                    self.rect.x + self.rect.w as i32 - 16,
-                    self.rect.y + self.rect.h as i32
-                        - styles.guide_font.size as i32
-                        - 8
-                        - ButtonIcon::diameter(styles) as i32
-                        - 8,
+            let status_y = self.rect.y + self.rect.h as i32
+                - styles.guide_font.size as i32
+                - 8
+                - ButtonIcon::diameter(styles) as i32
+                - 8;
+
+            if self.searching {
+                let locale = self.res.get::<Locale>();
+                let status = match self.current_match {
+                    Some(current) => {
+                        let mut map = HashMap::new();
+                        map.insert("current".into(), (current + 1).into());
+                        map.insert("total".into(), self.matches.len().into());
+                        locale.ta("guide-search-matches", &map)
+                    }
+                    None => locale.t("guide-search-no-matches"),
+                };
+                Text::with_alignment(
+                    &status,
+                    Point::new(self.rect.x + 16, status_y).into(),
+                    text_style.clone(),
+                    Alignment::Left.into(),
                 )
-                .into(),
+                .draw(display)?;
+            }
+
+            let bookmarked = if self.bookmarks.binary_search(&self.cursor).is_ok() {
+                "★ "
+            } else {
+                ""
+            };
+            Text::with_alignment(
+                &format!("{}{:.0}%", bookmarked, self.percentage(self.cursor)),
+                Point::new(self.rect.x + self.rect.w as i32 - 16, status_y).into(),
                 text_style,
                 Alignment::Right.into(),
             )
@@ -416,6 +717,12 @@ impl View for TextReader {
 
         drawn |= self.button_hints.draw(display, styles)?;
 
+        if let Some((_, list)) = self.jump_list.as_mut()
+            && list.should_draw()
+        {
+            drawn |= list.draw(display, styles)?;
+        }
+
         if let Some(keyboard) = self.keyboard.as_mut() {
             drawn |= keyboard.draw(display, styles)?;
         }
@@ -426,6 +733,10 @@ impl View for TextReader {
     fn should_draw(&self) -> bool {
         self.dirty
             || self.button_hints.should_draw()
+            || self
+                .jump_list
+                .as_ref()
+                .is_some_and(|(_, list)| list.should_draw())
             || self
                 .keyboard
                 .as_ref()
@@ -435,6 +746,9 @@ impl View for TextReader {
     fn set_should_draw(&mut self) {
         self.dirty = true;
         self.button_hints.set_should_draw();
+        if let Some((_, list)) = self.jump_list.as_mut() {
+            list.set_should_draw();
+        }
         if let Some(keyboard) = self.keyboard.as_mut() {
             keyboard.set_should_draw();
         }
@@ -454,7 +768,7 @@ impl View for TextReader {
                         false
                     }
                     Command::ValueChanged(_, value) => {
-                        self.search_forward(std::mem::take(value).as_string().unwrap());
+                        self.search(mem::take(value).as_string().unwrap());
                         false
                     }
                     _ => true,
@@ -463,6 +777,27 @@ impl View for TextReader {
             } else {
                 Ok(false)
             }
+        } else if let Some((kind, list)) = self.jump_list.as_mut() {
+            let (kind, selected) = (*kind, list.selected());
+            match event {
+                KeyEvent::Pressed(Key::A) => {
+                    self.cursor = self.jump_targets(kind)[selected];
+                    self.jump_list = None;
+                    self.dirty = true;
+                }
+                KeyEvent::Pressed(Key::X) if kind == JumpList::Bookmarks => {
+                    self.cursor = self.bookmarks[selected];
+                    self.toggle_bookmark();
+                    self.jump_list = None;
+                    self.open_jump_list(kind);
+                }
+                KeyEvent::Pressed(Key::B | Key::Select | Key::Start) => {
+                    self.jump_list = None;
+                    self.dirty = true;
+                }
+                _ => return list.handle_key_event(event, commands, bubble).await,
+            }
+            Ok(true)
         } else {
             match event {
                 KeyEvent::Pressed(Key::Up) | KeyEvent::Autorepeat(Key::Up) => {
@@ -477,25 +812,32 @@ impl View for TextReader {
                 KeyEvent::Pressed(Key::R) | KeyEvent::Autorepeat(Key::R) => {
                     self.move_forward_lines(10);
                 }
-                KeyEvent::Pressed(Key::L2) => {
-                    let last_searched = mem::take(&mut self.last_searched);
-                    self.search_backward(last_searched);
-                    self.dirty = true;
+                KeyEvent::Pressed(Key::L2) | KeyEvent::Autorepeat(Key::L2) => {
+                    self.prev_match();
                 }
-                KeyEvent::Pressed(Key::R2) => {
-                    let last_searched = mem::take(&mut self.last_searched);
-                    self.search_forward(last_searched);
-                    self.dirty = true;
+                KeyEvent::Pressed(Key::R2) | KeyEvent::Autorepeat(Key::R2) => {
+                    self.next_match();
+                }
+                KeyEvent::Pressed(Key::B) if self.searching => {
+                    self.clear_search();
                 }
                 KeyEvent::Pressed(Key::B) => {
                     self.save_cursor();
                     bubble.push_back(Command::CloseView);
                 }
+                KeyEvent::Pressed(Key::Y) => {
+                    self.toggle_bookmark();
+                }
+                KeyEvent::Pressed(Key::Select) => {
+                    self.open_jump_list(JumpList::Bookmarks);
+                }
+                KeyEvent::Pressed(Key::Start) if !self.headings.is_empty() => {
+                    self.open_jump_list(JumpList::Contents);
+                }
                 KeyEvent::Pressed(Key::X) => {
-                    self.keyboard = Some(Keyboard::new(
+                    self.keyboard = Some(Keyboard::search(
                         self.res.clone(),
-                        mem::take(&mut self.last_searched),
-                        false,
+                        self.last_searched.clone(),
                     ));
                 }
                 _ => return Ok(false),
@@ -516,3 +858,17 @@ impl View for TextReader {
         unimplemented!()
     }
 }
+
+impl IngameMenuChild for TextReader {
+    fn save(&self) {
+        self.save_cursor();
+    }
+
+    fn restore_on_open(&self) -> bool {
+        true
+    }
+
+    fn guide(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+}
@@ -0,0 +1,595 @@
+#![warn(clippy::all, rust_2018_idioms)]
+
+use std::convert::Infallible;
+use std::fs;
+use std::io;
+use std::sync::{LazyLock, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use clap::{Parser, Subcommand, ValueEnum};
+use common::battery::Battery;
+use common::constants::ALLIUM_GAME_INFO;
+use common::display::color::Color;
+use common::display::font::FontTextStyleBuilder;
+use common::display::rotation::Rotation;
+use common::platform::{DefaultPlatform, Platform};
+use common::retroarch::RetroArchClient;
+use common::stylesheet::Stylesheet;
+use embedded_graphics::Drawable;
+use embedded_graphics::Pixel;
+use embedded_graphics::prelude::{
+    Dimensions, DrawTarget, OriginDimensions, Point, Primitive, Size, Transform,
+};
+use embedded_graphics::primitives::{
+    Circle, CornerRadii, Line, PrimitiveStyle, PrimitiveStyleBuilder, Rectangle, RoundedRectangle,
+    StrokeAlignment, Triangle,
+};
+use embedded_graphics::text::{Alignment, Baseline, Text, TextStyleBuilder};
+use framebuffer::Framebuffer;
+
+/// How many times the battery warning flashes.
+const FLASHES: usize = 3;
+/// How long the battery warning is shown for each flash.
+const FLASH_ON: Duration = Duration::from_millis(700);
+/// How long the game is left alone between flashes.
+const FLASH_OFF: Duration = Duration::from_millis(500);
+/// How long a level stays on screen after it last changed.
+const LEVEL_DURATION: Duration = Duration::from_millis(1500);
+/// The game keeps drawing over the framebuffer, so the overlay is redrawn at this interval.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(16);
+/// How often the performance stats are measured.
+const STATS_INTERVAL: Duration = Duration::from_secs(1);
+const CPU_TEMPERATURE: &str = "/sys/class/thermal/thermal_zone0/temp";
+/// Rotation of what is on screen, which tiles are drawn to match.
+static ROTATION: LazyLock<Rotation> = LazyLock::new(Rotation::current);
+
+/// Draws over whatever is on screen, including games, which Allium does not otherwise draw over.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: OverlayCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum OverlayCommand {
+    /// Flashes a low battery warning in the top right corner
+    Battery {
+        /// Battery percentage to display
+        percentage: i32,
+    },
+    /// Shows a message in the middle of the screen
+    Toast {
+        /// Text to display
+        text: String,
+        /// How long to show the message for, in milliseconds
+        #[arg(short, long, default_value_t = 1500)]
+        duration: u64,
+    },
+    /// Shows a level bar at the top of the screen. Further levels can be sent on stdin as lines
+    /// of `<kind> <percentage>`, which keeps the bar on screen.
+    Level {
+        kind: LevelKind,
+        /// Percentage to fill the bar to
+        percentage: i32,
+    },
+    /// Shows FPS, CPU load, temperature and battery in the top left corner until the game exits
+    Stats,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum LevelKind {
+    Volume,
+    Brightness,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let styles = Stylesheet::load()?;
+    let mut fb = Framebuffer::new("/dev/fb0")?;
+    let (w, h) = display_size(&fb);
+
+    match cli.command {
+        OverlayCommand::Battery { percentage } => {
+            let tile = draw_battery_warning(&styles, percentage);
+            let origin = Point::new((w - tile.size.width) as i32 - 12, 12);
+            for _ in 0..FLASHES {
+                show(&mut fb, &tile, origin, FLASH_ON);
+                thread::sleep(FLASH_OFF);
+            }
+        }
+        OverlayCommand::Toast { text, duration } => {
+            let tile = draw_toast(&styles, &text);
+            let origin = Point::new(
+                (w - tile.size.width) as i32 / 2,
+                (h - tile.size.height) as i32 / 2,
+            );
+            show(&mut fb, &tile, origin, Duration::from_millis(duration));
+        }
+        OverlayCommand::Level { kind, percentage } => {
+            show_level(&mut fb, &styles, kind, percentage);
+        }
+        OverlayCommand::Stats => {
+            show_stats(&mut fb, &styles)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Keeps the level on screen until no new level has been received on stdin for `LEVEL_DURATION`.
+fn show_level(fb: &mut Framebuffer, styles: &Stylesheet, kind: LevelKind, percentage: i32) {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            let mut parts = line.split_whitespace();
+            if let (Some(kind), Some(percentage)) = (parts.next(), parts.next())
+                && let (Ok(kind), Ok(percentage)) =
+                    (LevelKind::from_str(kind, true), percentage.parse())
+                && tx.send((kind, percentage)).is_err()
+            {
+                break;
+            }
+        }
+    });
+
+    let mut tile = draw_level(styles, kind, percentage);
+    let (w, _) = display_size(fb);
+    let origin = Point::new((w - tile.size.width) as i32 / 2, 12);
+    let saved = fb.frame.to_vec();
+    let mut deadline = Instant::now() + LEVEL_DURATION;
+    while Instant::now() < deadline {
+        while let Ok((kind, percentage)) = rx.try_recv() {
+            tile = draw_level(styles, kind, percentage);
+            deadline = Instant::now() + LEVEL_DURATION;
+        }
+        blit(fb, &tile, origin);
+        thread::sleep(REDRAW_INTERVAL);
+    }
+    restore(fb, &tile, origin, &saved);
+}
+
+/// Keeps the performance stats on screen while a game is running, measuring them every
+/// `STATS_INTERVAL`.
+fn show_stats(fb: &mut Framebuffer, styles: &Stylesheet) -> Result<()> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let mut battery = DefaultPlatform::new()?.battery()?;
+    let mut cpu_times = CpuTimes::read();
+    let mut stats = Stats::default();
+
+    let origin = Point::new(12, 12);
+    let saved = fb.frame.to_vec();
+    let mut tile = draw_stats(styles, &stats);
+    let mut measured = Instant::now();
+    while ALLIUM_GAME_INFO.exists() {
+        if measured.elapsed() >= STATS_INTERVAL {
+            measured = Instant::now();
+
+            // Polled, so a missed reply is retried at the next poll
+            stats.fps = runtime
+                .block_on(RetroArchClient::new().retries(0).info())
+                .ok()
+                .flatten()
+                .and_then(|info| info.fps);
+            let now = CpuTimes::read();
+            stats.cpu_load = cpu_times.zip(now).map(|(prev, now)| now.load_since(&prev));
+            cpu_times = now;
+            stats.temperature = fs::read_to_string(CPU_TEMPERATURE)
+                .ok()
+                .and_then(|s| s.trim().parse::<i32>().ok())
+                .map(|t| t / 1000);
+            stats.battery = battery.update().ok().map(|_| battery.percentage());
+
+            // The snapshot is as old as the overlay, so putting it back here would paint over
+            // what the game has drawn since. The game redraws anything the new tile doesn't cover.
+            tile = draw_stats(styles, &stats);
+        }
+        blit(fb, &tile, origin);
+        thread::sleep(REDRAW_INTERVAL);
+    }
+    restore(fb, &tile, origin, &saved);
+
+    Ok(())
+}
+
+#[derive(Debug, Default)]
+struct Stats {
+    fps: Option<f32>,
+    cpu_load: Option<u32>,
+    temperature: Option<i32>,
+    battery: Option<i32>,
+}
+
+/// Cumulative CPU time from `/proc/stat`, in clock ticks.
+#[derive(Debug, Clone, Copy)]
+struct CpuTimes {
+    idle: u64,
+    total: u64,
+}
+
+impl CpuTimes {
+    fn read() -> Option<Self> {
+        let stat = fs::read_to_string("/proc/stat").ok()?;
+        let times: Vec<u64> = stat
+            .lines()
+            .next()?
+            .split_ascii_whitespace()
+            .skip(1)
+            .filter_map(|t| t.parse().ok())
+            .collect();
+        // idle and iowait
+        let idle = times.get(3)? + times.get(4).unwrap_or(&0);
+        Some(Self {
+            idle,
+            total: times.iter().sum(),
+        })
+    }
+
+    /// Percentage of time the CPU was busy since `prev`.
+    fn load_since(&self, prev: &Self) -> u32 {
+        let total = self.total.saturating_sub(prev.total);
+        let idle = self.idle.saturating_sub(prev.idle);
+        if total == 0 {
+            return 0;
+        }
+        (100 * total.saturating_sub(idle) / total) as u32
+    }
+}
+
+/// Keeps the tile on screen for `duration`, then puts back what was underneath it.
+fn show(fb: &mut Framebuffer, tile: &Tile, origin: Point, duration: Duration) {
+    let saved = fb.frame.to_vec();
+    let start = Instant::now();
+    while start.elapsed() < duration {
+        blit(fb, tile, origin);
+        thread::sleep(REDRAW_INTERVAL);
+    }
+    restore(fb, tile, origin, &saved);
+}
+
+/// Draws a battery icon and the percentage in a rounded box.
+fn draw_battery_warning(styles: &Stylesheet, percentage: i32) -> Tile {
+    let font_size = styles.ui_font.size;
+    let text = format!("{percentage}%");
+
+    let character_style = FontTextStyleBuilder::<Color>::new(styles.ui_font.font())
+        .text_color(styles.foreground_color)
+        .font_size(font_size)
+        .build();
+
+    let icon_w = font_size * 6 / 5;
+    let icon_h = font_size * 3 / 5;
+    let stroke = (font_size / 10).max(2);
+    let text_style = TextStyleBuilder::new()
+        .alignment(Alignment::Left)
+        .baseline(Baseline::Top)
+        .build();
+    let text = Text::with_text_style(&text, Point::zero(), character_style, text_style);
+    let text_w = text.bounding_box().size.width;
+    let size = Size::new(12 + icon_w + stroke + 8 + text_w + 12, font_size + 16);
+
+    let mut tile = Tile::new(size);
+
+    let _ = RoundedRectangle::new(
+        Rectangle::new(Point::zero(), size),
+        CornerRadii::new(Size::new_equal(size.height / 2)),
+    )
+    .into_styled(PrimitiveStyle::with_fill(styles.highlight_color))
+    .draw(&mut tile);
+
+    let icon_y = (size.height - icon_h) as i32 / 2;
+    let _ = RoundedRectangle::new(
+        Rectangle::new(Point::new(12, icon_y), Size::new(icon_w, icon_h)),
+        CornerRadii::new(Size::new_equal(stroke * 2)),
+    )
+    .into_styled(
+        PrimitiveStyleBuilder::new()
+            .stroke_color(styles.foreground_color)
+            .stroke_alignment(StrokeAlignment::Inside)
+            .stroke_width(stroke)
+            .build(),
+    )
+    .draw(&mut tile);
+
+    // Battery cap
+    let _ = Rectangle::new(
+        Point::new(12 + icon_w as i32, icon_y + icon_h as i32 / 4),
+        Size::new(stroke, icon_h / 2),
+    )
+    .into_styled(PrimitiveStyle::with_fill(styles.foreground_color))
+    .draw(&mut tile);
+
+    // Remaining charge
+    let inner_w = icon_w - 4 * stroke;
+    let _ = Rectangle::new(
+        Point::new(12 + 2 * stroke as i32, icon_y + 2 * stroke as i32),
+        Size::new(
+            (inner_w * percentage.clamp(0, 100) as u32 / 100).max(stroke),
+            icon_h - 4 * stroke,
+        ),
+    )
+    .into_styled(PrimitiveStyle::with_fill(styles.foreground_color))
+    .draw(&mut tile);
+
+    let _ = text
+        .translate(Point::new((12 + icon_w + stroke + 8) as i32, 8))
+        .draw(&mut tile);
+
+    tile
+}
+
+/// Draws an icon for the kind of level and a bar filled to the percentage in a rounded box. The
+/// size does not depend on the level, so that it can be redrawn in place.
+fn draw_level(styles: &Stylesheet, kind: LevelKind, percentage: i32) -> Tile {
+    let font_size = styles.ui_font.size;
+    let size = Size::new(font_size * 9, font_size + 16);
+    let mid_y = size.height as i32 / 2;
+
+    let mut tile = Tile::new(size);
+
+    let _ = RoundedRectangle::new(
+        Rectangle::new(Point::zero(), size),
+        CornerRadii::new(Size::new_equal(size.height / 2)),
+    )
+    .into_styled(PrimitiveStyle::with_fill(styles.highlight_color))
+    .draw(&mut tile);
+
+    let icon = font_size as i32;
+    let icon_style = PrimitiveStyle::with_fill(styles.foreground_color);
+    match kind {
+        LevelKind::Volume => {
+            // Speaker
+            let _ = Rectangle::new(
+                Point::new(16, mid_y - icon / 6),
+                Size::new_equal(icon as u32 / 3),
+            )
+            .into_styled(icon_style)
+            .draw(&mut tile);
+            let _ = Triangle::new(
+                Point::new(16, mid_y),
+                Point::new(16 + icon * 2 / 3, mid_y - icon / 2),
+                Point::new(16 + icon * 2 / 3, mid_y + icon / 2),
+            )
+            .into_styled(icon_style)
+            .draw(&mut tile);
+        }
+        LevelKind::Brightness => {
+            // Sun
+            let center = Point::new(16 + icon / 2, mid_y);
+            let _ = Circle::with_center(center, icon as u32 / 2)
+                .into_styled(icon_style)
+                .draw(&mut tile);
+            for i in 0..8 {
+                let angle = i as f32 * std::f32::consts::FRAC_PI_4;
+                let (sin, cos) = angle.sin_cos();
+                let ray = |r: f32| {
+                    center + Point::new((cos * r).round() as i32, (sin * r).round() as i32)
+                };
+                let _ = Line::new(ray(icon as f32 * 0.35), ray(icon as f32 * 0.5))
+                    .into_styled(PrimitiveStyle::with_stroke(
+                        styles.foreground_color,
+                        (font_size / 12).max(2),
+                    ))
+                    .draw(&mut tile);
+            }
+        }
+    }
+
+    let bar_x = 16 + icon + 12;
+    let bar_w = size.width as i32 - bar_x - 20;
+    let bar_h = (font_size / 3).max(6);
+    let bar = Rectangle::new(
+        Point::new(bar_x, mid_y - bar_h as i32 / 2),
+        Size::new(bar_w as u32, bar_h),
+    );
+    let _ = RoundedRectangle::new(bar, CornerRadii::new(Size::new_equal(bar_h / 2)))
+        .into_styled(PrimitiveStyle::with_fill(styles.disabled_color))
+        .draw(&mut tile);
+    let filled = bar_w as u32 * percentage.clamp(0, 100) as u32 / 100;
+    if filled > 0 {
+        let _ = RoundedRectangle::new(
+            Rectangle::new(bar.top_left, Size::new(filled.max(bar_h), bar_h)),
+            CornerRadii::new(Size::new_equal(bar_h / 2)),
+        )
+        .into_styled(icon_style)
+        .draw(&mut tile);
+    }
+
+    tile
+}
+
+/// Draws the available stats on a single line in a rounded box.
+fn draw_stats(styles: &Stylesheet, stats: &Stats) -> Tile {
+    let mut parts = Vec::with_capacity(4);
+    parts.push(match stats.fps {
+        Some(fps) => format!("{fps:.1} FPS"),
+        None => "-- FPS".to_string(),
+    });
+    if let Some(load) = stats.cpu_load {
+        parts.push(format!("CPU {load}%"));
+    }
+    if let Some(temperature) = stats.temperature {
+        parts.push(format!("{temperature}°C"));
+    }
+    if let Some(battery) = stats.battery {
+        parts.push(format!("BAT {battery}%"));
+    }
+    let text = parts.join("  ");
+
+    let font_size = styles.status_bar_font_size() as u32;
+    let character_style = FontTextStyleBuilder::<Color>::new(styles.ui_font.font())
+        .text_color(styles.foreground_color)
+        .font_size(font_size)
+        .build();
+    let text_style = TextStyleBuilder::new()
+        .alignment(Alignment::Left)
+        .baseline(Baseline::Top)
+        .build();
+    let text = Text::with_text_style(&text, Point::zero(), character_style, text_style);
+    let bounds = text.bounding_box();
+    let size = Size::new(bounds.size.width + 16, font_size + 8);
+
+    let mut tile = Tile::new(size);
+
+    let _ = RoundedRectangle::new(
+        Rectangle::new(Point::zero(), size),
+        CornerRadii::new(Size::new_equal(8)),
+    )
+    .into_styled(PrimitiveStyle::with_fill(styles.highlight_color))
+    .draw(&mut tile);
+
+    let _ = text
+        .translate(Point::new(8, 4) - bounds.top_left)
+        .draw(&mut tile);
+
+    tile
+}
+
+/// Draws the text in a rounded box, like the toasts shown by Allium.
+fn draw_toast(styles: &Stylesheet, text: &str) -> Tile {
+    let character_style = FontTextStyleBuilder::<Color>::new(styles.ui_font.font())
+        .font_fallback(styles.cjk_font.font())
+        .text_color(styles.foreground_color)
+        .font_size(styles.ui_font.size)
+        .build();
+    let text_style = TextStyleBuilder::new()
+        .alignment(Alignment::Center)
+        .baseline(Baseline::Top)
+        .build();
+    let text = Text::with_text_style(text, Point::zero(), character_style, text_style);
+    let bounds = text.bounding_box();
+    let size = Size::new(bounds.size.width + 24, bounds.size.height + 16);
+
+    let mut tile = Tile::new(size);
+
+    let _ = RoundedRectangle::new(
+        Rectangle::new(Point::zero(), size),
+        CornerRadii::new(Size::new_equal(12)),
+    )
+    .into_styled(PrimitiveStyle::with_fill(styles.highlight_color))
+    .draw(&mut tile);
+
+    let _ = text
+        .translate(Point::new(12, 8) - bounds.top_left)
+        .draw(&mut tile);
+
+    tile
+}
+
+/// Size of the screen as it is seen, which is the panel's size swapped if it is rotated sideways.
+fn display_size(fb: &Framebuffer) -> (u32, u32) {
+    ROTATION.swap_size(fb.var_screen_info.xres, fb.var_screen_info.yres)
+}
+
+/// Calls `f` with the tile coordinates and framebuffer offset of every pixel covered by the tile,
+/// on every page of the framebuffer, so that it shows no matter which page the game is displaying.
+fn for_each_pixel(fb: &Framebuffer, tile: &Tile, origin: Point, mut f: impl FnMut(usize, usize)) {
+    let panel_w = fb.var_screen_info.xres as usize;
+    let panel_h = fb.var_screen_info.yres as usize;
+    let pages = fb.var_screen_info.yres_virtual as usize / panel_h.max(1);
+    let (w, h) = display_size(fb);
+    let bpp = fb.var_screen_info.bits_per_pixel as usize / 8;
+
+    let x0 = origin.x.max(0) as usize;
+    let y0 = origin.y.max(0) as usize;
+
+    for page in 0..pages {
+        for y in 0..(tile.size.height as usize).min((h as usize).saturating_sub(y0)) {
+            for x in 0..(tile.size.width as usize).min((w as usize).saturating_sub(x0)) {
+                let (fb_x, fb_y) = ROTATION.to_panel((x0 + x) as i32, (y0 + y) as i32, w, h);
+                let fb_y = page * panel_h + fb_y as usize;
+                let i = (fb_y * panel_w + fb_x as usize) * bpp;
+                if i + 2 >= fb.frame.len() {
+                    continue;
+                }
+                f(y * tile.size.width as usize + x, i);
+            }
+        }
+    }
+}
+
+/// Blends the tile over the framebuffer.
+fn blit(fb: &mut Framebuffer, tile: &Tile, origin: Point) {
+    let mut writes = Vec::new();
+    for_each_pixel(fb, tile, origin, |t, i| {
+        let color = tile.pixels[t];
+        if color.a() == 0 {
+            return;
+        }
+        let pixel = Color::new(fb.frame[i + 2], fb.frame[i + 1], fb.frame[i]);
+        writes.push((i, pixel.blend(color, color.a())));
+    });
+    for (i, color) in writes {
+        fb.frame[i] = color.b();
+        fb.frame[i + 1] = color.g();
+        fb.frame[i + 2] = color.r();
+    }
+}
+
+/// Puts back the pixels under the tile from a copy of the framebuffer.
+fn restore(fb: &mut Framebuffer, tile: &Tile, origin: Point, saved: &[u8]) {
+    let mut writes = Vec::new();
+    for_each_pixel(fb, tile, origin, |_, i| writes.push(i));
+    for i in writes {
+        fb.frame[i..i + 3].copy_from_slice(&saved[i..i + 3]);
+    }
+}
+
+/// An offscreen image that starts out transparent.
+struct Tile {
+    size: Size,
+    pixels: Vec<Color>,
+}
+
+impl Tile {
+    fn new(size: Size) -> Self {
+        Self {
+            size,
+            pixels: vec![Color::rgba(0, 0, 0, 0); (size.width * size.height) as usize],
+        }
+    }
+}
+
+impl DrawTarget for Tile {
+    type Color = Color;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if point.x < 0
+                || point.y < 0
+                || point.x as u32 >= self.size.width
+                || point.y as u32 >= self.size.height
+            {
+                continue;
+            }
+            let pixel =
+                &mut self.pixels[(point.y as u32 * self.size.width + point.x as u32) as usize];
+            *pixel = if pixel.a() == 0 {
+                color
+            } else {
+                pixel
+                    .blend(color, color.a())
+                    .with_a(pixel.a().max(color.a()))
+            };
+        }
+        Ok(())
+    }
+}
+
+impl OriginDimensions for Tile {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
@@ -1,29 +1,49 @@
 use std::fs::{self, File};
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use std::time::Instant;
 
-use anyhow::Result;
+use anyhow::{Result, bail};
 use chrono::{DateTime, Duration, Utc};
-use common::battery::Battery;
+use common::audio::AudioSettings;
+use common::battery::{Battery, BatteryCalibration, BatterySample};
 use common::constants::{
-    ALLIUM_GAME_INFO, ALLIUM_MENU, ALLIUM_SD_ROOT, ALLIUM_VERSION, ALLIUMD_STATE,
-    BATTERY_SHUTDOWN_THRESHOLD, BATTERY_UPDATE_INTERVAL, IDLE_TIMEOUT, LONG_PRESS_DURATION,
+    ALLIUM_GAME_INFO, ALLIUM_LAUNCH_REQUEST, ALLIUM_LAUNCHER_STATE, ALLIUM_MENU,
+    ALLIUM_REMOTE_CONTROL_PORT, ALLIUM_SCREENSHOTS_DIR, ALLIUM_VERSION, ALLIUMD_STATE,
+    BATTERY_HISTORY_DURATION, BATTERY_LOG_INTERVAL, BATTERY_UPDATE_INTERVAL, BATTERY_WARNING_STEP,
+    CLOCK_UPDATE_INTERVAL, IDLE_DIM_BRIGHTNESS, IDLE_TIMEOUT, RUMBLE_LOW_BATTERY_DURATION,
+    SESSION_JOURNAL_INTERVAL,
 };
 use common::display::settings::DisplaySettings;
+use common::led::{self, LedPattern, LedSettings};
 use common::locale::{Locale, LocaleSettings};
-use common::power::{PowerButtonAction, PowerSettings};
-use common::retroarch::RetroArchCommand;
-use common::wifi::WiFiSettings;
+use common::logging::LogSettings;
+use common::performance::{PerformanceSettings, PowerProfile};
+use common::power::{LidCloseAction, PowerButtonAction, PowerSettings};
+use common::retroarch::{RetroArchClient, RetroArchCommand, core_name};
+use common::rumble::RumbleSettings;
+use common::save_backup::{self, SaveBackupSettings};
+use common::session_journal::SessionJournal;
+use common::ui_keymap::UiKeymap;
+use common::wifi::{self, WiFiSettings};
 use enum_map::EnumMap;
 use log::{debug, error, info, trace, warn};
 use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tokio::io::AsyncWriteExt;
 use tokio::process::{Child, Command};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 
 use common::database::Database;
 use common::game_info::GameInfo;
+use common::platform::idle::{IdleEvent, IdleTimer};
 use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
 
+use crate::remote::{self, RemoteMethod, RemoteRequest};
+use crate::watchdog::{CrashAction, Watchdog, is_crash};
+
 #[cfg(unix)]
 use {
     nix::sys::signal::Signal, nix::sys::signal::kill, nix::unistd::Pid,
@@ -35,6 +55,9 @@ pub struct AlliumDState {
     #[serde(default = "Utc::now")]
     time: DateTime<Utc>,
     volume: i32,
+    /// Volume used while headphones are plugged in.
+    #[serde(default = "AlliumDState::default_headphone_volume")]
+    headphone_volume: i32,
     brightness: u8,
 }
 
@@ -43,13 +66,33 @@ pub struct AlliumD<P: Platform> {
     platform: P,
     main: Child,
     menu: Option<Child>,
+    /// Level bar shown when changing the volume or brightness, kept to update it in place.
+    osd: Option<Child>,
+    /// Keeps the last few seconds of the screen for GIF capture, once started in a game.
+    recorder: Option<Child>,
     keys: EnumMap<Key, bool>,
     is_menu_pressed_alone: bool,
-    pressed_menu: Instant,
+    /// Whether the long press action has been performed for the current power button press.
+    is_power_long_pressed: bool,
     is_terminating: bool,
     state: AlliumDState,
     locale: Locale,
     power_settings: PowerSettings,
+    performance_settings: PerformanceSettings,
+    /// Battery percentage at the last low battery warning, since the battery was last charging.
+    battery_warned_at: Option<i32>,
+    /// Pattern last shown on the LED.
+    led: Option<LedPattern>,
+    idle: IdleTimer,
+    /// Whether the game was paused by closing the lid, and should be unpaused when it is opened.
+    is_lid_paused: bool,
+    is_headphones_connected: bool,
+    /// Remote control listener, while enabled in the Wi-Fi settings.
+    remote: Option<JoinHandle<()>>,
+    remote_tx: mpsc::Sender<RemoteRequest>,
+    remote_rx: mpsc::Receiver<RemoteRequest>,
+    launcher_watchdog: Watchdog,
+    menu_watchdog: Watchdog,
 }
 
 impl AlliumDState {
@@ -57,10 +100,15 @@ impl AlliumDState {
         Self {
             time: Utc::now(),
             volume: 0,
+            headphone_volume: Self::default_headphone_volume(),
             brightness: 50,
         }
     }
 
+    fn default_headphone_volume() -> i32 {
+        5
+    }
+
     pub fn load() -> Result<AlliumDState> {
         if ALLIUMD_STATE.exists() {
             debug!("found state, loading from file");
@@ -128,64 +176,144 @@ impl AlliumD<DefaultPlatform> {
     pub async fn new() -> Result<AlliumD<DefaultPlatform>> {
         let platform = DefaultPlatform::new()?;
         let state = AlliumDState::load()?;
+        let performance_settings = PerformanceSettings::load()?;
+        // A game that is resumed runs with its own CPU profile
+        let cpu_profile = performance_settings
+            .cpu_profile(GameInfo::load()?.map(|g| g.cpu_profile).unwrap_or_default());
+        if let Err(e) = platform.set_cpu_profile(cpu_profile) {
+            warn!("failed to set cpu profile: {}", e);
+        }
+        if let Err(e) = SessionJournal::recover(&Database::new()?) {
+            error!("failed to recover play time: {}", e);
+        }
         let main = spawn_main().await?;
-        let locale = Locale::new(&LocaleSettings::load()?.lang);
+        let is_headphones_connected = platform.is_headphones_connected();
+        let locale = Locale::new(&LocaleSettings::load()?);
         let power_settings = PowerSettings::load()?;
+        let idle = IdleTimer::new(
+            match power_settings.idle_dim_seconds {
+                0 => None,
+                t => Some(std::time::Duration::from_secs(t as u64)),
+            },
+            match power_settings.auto_sleep_duration_minutes {
+                0 => None,
+                t => Some(std::time::Duration::from_secs(t as u64 * 60)),
+            },
+            match power_settings.auto_power_off_minutes {
+                0 => None,
+                t => Some(std::time::Duration::from_secs(t as u64 * 60)),
+            },
+        );
+        let (remote_tx, remote_rx) = mpsc::channel(8);
 
         Ok(AlliumD {
             platform,
             main,
             menu: None,
+            osd: None,
+            recorder: None,
             keys: EnumMap::default(),
             is_menu_pressed_alone: false,
-            pressed_menu: Instant::now(),
+            is_power_long_pressed: false,
             is_terminating: false,
             state,
             locale,
             power_settings,
+            performance_settings,
+            battery_warned_at: None,
+            led: None,
+            idle,
+            is_lid_paused: false,
+            is_headphones_connected,
+            remote: None,
+            remote_tx,
+            remote_rx,
+            launcher_watchdog: Watchdog::new(),
+            menu_watchdog: Watchdog::new(),
         })
     }
 
     pub async fn run_event_loop(&mut self) -> Result<()> {
         info!("hello from Allium {}", ALLIUM_VERSION);
 
-        info!("setting volume: {}", self.state.volume);
-        self.platform.set_volume(self.state.volume)?;
+        info!("setting volume: {}", self.volume());
+        self.platform.set_volume(self.volume())?;
+        self.platform
+            .set_audio_settings(&AudioSettings::load().unwrap_or_default())?;
 
-        info!("setting brightness: {}", self.state.brightness);
-        self.platform.set_brightness(self.state.brightness)?;
+        info!("setting brightness: {}", self.brightness());
+        self.platform.set_brightness(self.brightness())?;
 
         info!("loading display settings");
         self.platform
             .set_display_settings(&mut DisplaySettings::load()?)?;
 
-        if DefaultPlatform::has_wifi() {
+        if DefaultPlatform::has_wifi() && self.performance_settings.profile.wifi() {
             info!("wifi detected, loading wifi settings");
             WiFiSettings::load()?.init()?;
         }
+        self.update_remote_control();
+        self.start_recording();
 
         info!("starting event loop");
         #[cfg(unix)]
         {
             let mut sigint = tokio::signal::unix::signal(SignalKind::interrupt())?;
             let mut sigterm = tokio::signal::unix::signal(SignalKind::terminate())?;
+            let mut sighup = tokio::signal::unix::signal(SignalKind::hangup())?;
+            let mut sigusr1 = tokio::signal::unix::signal(SignalKind::user_defined1())?;
 
             let mut battery_interval = Instant::now();
+            let mut battery_log_interval = Instant::now();
+            let mut night_mode_interval = Instant::now();
+            let mut session_journal_interval = Instant::now();
+            let mut is_night_mode_active = DisplaySettings::load()
+                .is_ok_and(|s| s.is_night_mode_active(chrono::Local::now().time()));
 
             // If battery is charging, suspend.
             let mut battery = self.platform.battery()?;
             battery.update()?;
+            if let Err(e) = log_battery(&battery) {
+                error!("failed to log battery: {}", e);
+            }
             if battery.charging() {
                 self.handle_charging().await?;
             }
 
             loop {
                 if let Some(menu) = self.menu.as_mut()
-                    && menu.try_wait()?.is_some()
+                    && let Some(status) = menu.try_wait()?
                 {
-                    info!("menu process terminated, resuming game");
                     self.menu = None;
-                    RetroArchCommand::Unpause.send().await?;
+                    if !is_crash(status) || !self.restart_menu().await? {
+                        info!("menu process terminated, resuming game");
+                        RetroArchCommand::Unpause.send().await?;
+                    }
+                }
+
+                if night_mode_interval.elapsed() >= CLOCK_UPDATE_INTERVAL {
+                    night_mode_interval = Instant::now();
+                    match DisplaySettings::load() {
+                        Ok(mut settings) => {
+                            let is_active =
+                                settings.is_night_mode_active(chrono::Local::now().time());
+                            if is_active != is_night_mode_active {
+                                info!("night mode active: {}", is_active);
+                                is_night_mode_active = is_active;
+                                if let Err(e) = self.platform.set_display_settings(&mut settings) {
+                                    warn!("failed to apply night mode: {}", e);
+                                }
+                            }
+                        }
+                        Err(e) => warn!("failed to load display settings: {}", e),
+                    }
+                }
+
+                if session_journal_interval.elapsed() >= SESSION_JOURNAL_INTERVAL {
+                    session_journal_interval = Instant::now();
+                    if let Err(e) = self.update_session_journal() {
+                        error!("failed to update session journal: {}", e);
+                    }
                 }
 
                 if battery_interval.elapsed() >= BATTERY_UPDATE_INTERVAL {
@@ -194,38 +322,150 @@ impl AlliumD<DefaultPlatform> {
                     if let Err(e) = battery.update() {
                         error!("failed to update battery: {}", e);
                     }
-                    if battery.percentage() <= BATTERY_SHUTDOWN_THRESHOLD && !battery.charging() {
+                    if battery_log_interval.elapsed() >= BATTERY_LOG_INTERVAL {
+                        battery_log_interval = Instant::now();
+                        if let Err(e) = log_battery(&battery) {
+                            error!("failed to log battery: {}", e);
+                        }
+                        if let Err(e) = record_calibration(&battery) {
+                            error!("failed to record battery calibration: {}", e);
+                        }
+                    }
+                    if battery.charging() {
+                        self.battery_warned_at = None;
+                    } else if let Err(e) = self.warn_low_battery(battery.percentage()) {
+                        error!("failed to show low battery warning: {}", e);
+                    }
+                    if let Err(e) = self.update_led(battery.charging(), battery.percentage()) {
+                        error!("failed to update LED: {}", e);
+                    }
+                    if battery.percentage() <= self.power_settings.battery_shutdown_threshold
+                        && !battery.charging()
+                    {
                         warn!("battery is low, shutting down");
+                        if let Err(e) =
+                            finish_calibration(self.power_settings.battery_shutdown_threshold)
+                        {
+                            error!("failed to finish battery calibration: {}", e);
+                        }
+                        if let Err(e) = self.save_and_quit_game().await {
+                            error!("failed to save game: {}", e);
+                        }
                         self.handle_quit().await?;
                     }
                 }
 
-                let auto_sleep_duration = match self.power_settings.auto_sleep_duration_minutes {
-                    0 => std::time::Duration::MAX, // disabled
-                    t => std::time::Duration::new(t as u64 * 60, 0),
-                };
                 tokio::select! {
                     key_event = self.platform.poll() => {
                         self.handle_key_event(key_event).await?;
                     }
-                    _ = tokio::time::sleep(auto_sleep_duration) => {
-                        if !self.power_settings.auto_sleep_when_charging && battery.charging() {
-                            info!("battery charging, don't auto sleep");
-                        } else {
-                            info!("idle timeout, shutting down");
-                            self.handle_quit().await?;
+                    idle_event = self.idle.next() => match idle_event {
+                        IdleEvent::Dim => {
+                            if !self.is_ingame() {
+                                info!("idle, dimming screen");
+                                self.platform
+                                    .set_brightness(IDLE_DIM_BRIGHTNESS.min(self.brightness()))?;
+                            }
                         }
-                    }
-                    _ = self.main.wait() => {
+                        IdleEvent::Sleep => {
+                            if !self.power_settings.auto_sleep_when_charging && battery.charging() {
+                                info!("battery charging, don't auto sleep");
+                                self.idle.postpone(IdleEvent::Sleep);
+                            } else {
+                                info!("idle timeout, sleeping");
+                                self.handle_suspend(true).await?;
+                            }
+                        }
+                        IdleEvent::PowerOff => {
+                            if !self.power_settings.auto_sleep_when_charging && battery.charging() {
+                                info!("battery charging, don't auto power off");
+                                self.idle.postpone(IdleEvent::PowerOff);
+                            } else if self.is_ingame() && self.menu.is_none() {
+                                // Only power off when paused, not while a game is being watched
+                                self.idle.postpone(IdleEvent::PowerOff);
+                            } else {
+                                info!("idle while paused, powering off");
+                                if let Err(e) = self.save_and_quit_game().await {
+                                    error!("failed to save game: {}", e);
+                                }
+                                self.handle_quit().await?;
+                            }
+                        }
+                    },
+                    status = self.main.wait() => {
                         if !self.is_terminating {
+                            // Games exiting with an error are RetroArch's to report
+                            let is_launcher_crash =
+                                !self.is_ingame() && status.is_ok_and(is_crash);
                             info!("main process terminated, recording play time");
                             self.update_play_time()?;
+                            self.end_session();
+                            self.stop_recording().await?;
+                            if let Err(e) = backup_save() {
+                                error!("failed to back up save: {}", e);
+                            }
                             GameInfo::delete()?;
+                            if let Err(e) = self
+                                .platform
+                                .set_cpu_profile(self.performance_settings.profile.cpu_profile())
+                            {
+                                warn!("failed to reset cpu profile: {}", e);
+                            }
+                            if is_launcher_crash {
+                                self.handle_launcher_crash().await?;
+                            }
                             self.main = spawn_main().await?;
+                            self.start_recording();
+                        }
+                    }
+                    Some(request) = self.remote_rx.recv() => {
+                        let result = self.handle_remote(request.method).await;
+                        request.reply.send(result.map_err(|e| e.to_string())).ok();
+                    }
+                    _ = sigusr1.recv() => {
+                        // The launcher's screenshot combo
+                        if let Err(e) = self.take_screenshot().await {
+                            warn!("failed to take screenshot: {}", e);
                         }
                     }
                     _ = sigint.recv() => self.handle_quit().await?,
                     _ = sigterm.recv() => self.handle_quit().await?,
+                    _ = sighup.recv() => {
+                        // The launcher changed the settings
+                        match PerformanceSettings::load() {
+                            Ok(settings)
+                                if settings.profile != self.performance_settings.profile =>
+                            {
+                                if let Err(e) = self.set_power_profile(settings.profile) {
+                                    warn!("failed to set power profile: {}", e);
+                                }
+                            }
+                            Ok(_) => {}
+                            Err(e) => warn!("failed to reload performance settings: {}", e),
+                        }
+                        if let Err(e) = self.platform.set_volume(self.volume()) {
+                            warn!("failed to set volume: {}", e);
+                        }
+                        match LocaleSettings::load() {
+                            Ok(settings) => self.locale = Locale::new(&settings),
+                            Err(e) => warn!("failed to reload locale settings: {}", e),
+                        }
+                        // Let the in-game menu reload its strings too
+                        if let Some(menu) = self.menu.as_ref()
+                            && let Err(e) = signal(menu, Signal::SIGHUP)
+                        {
+                            warn!("failed to signal menu: {}", e);
+                        }
+                        self.update_remote_control();
+                        match LogSettings::load() {
+                            Ok(settings) => settings.apply(),
+                            Err(e) => warn!("failed to reload log settings: {}", e),
+                        }
+                        match UiKeymap::load() {
+                            Ok(keymap) => UiKeymap::set_current(keymap),
+                            Err(e) => warn!("failed to reload UI keymap, keeping the old one: {}", e),
+                        }
+                    }
                 }
             }
         }
@@ -249,16 +489,37 @@ impl AlliumD<DefaultPlatform> {
             key_event
         );
 
+        self.wake()?;
+
+        // The LED stops showing the finished job at the next battery update
+        if let KeyEvent::Pressed(_) = key_event
+            && let Err(e) = led::clear_job_finished()
+        {
+            warn!("failed to clear finished job: {}", e);
+        }
+
+        // Switch between the speaker and headphone volume
+        if let KeyEvent::Pressed(Key::Headphones) | KeyEvent::Released(Key::Headphones) = key_event
+        {
+            self.is_headphones_connected = matches!(key_event, KeyEvent::Pressed(_));
+            info!("headphones connected: {}", self.is_headphones_connected);
+            self.platform.set_volume(self.volume())?;
+            return Ok(());
+        }
+
         // Handle menu key
         match key_event {
             KeyEvent::Pressed(Key::Menu) => {
                 self.is_menu_pressed_alone = true;
-                self.pressed_menu = Instant::now();
+            }
+            KeyEvent::Pressed(Key::Power) => {
+                self.is_menu_pressed_alone = false;
+                self.is_power_long_pressed = false;
             }
             KeyEvent::Pressed(_) => {
                 self.is_menu_pressed_alone = false;
             }
-            KeyEvent::Released(_) | KeyEvent::Autorepeat(_) => {}
+            KeyEvent::Released(_) | KeyEvent::Autorepeat(_) | KeyEvent::LongPressed(_) => {}
         }
 
         // Update self.keys
@@ -269,16 +530,14 @@ impl AlliumD<DefaultPlatform> {
             KeyEvent::Released(key) => {
                 self.keys[key] = false;
             }
-            KeyEvent::Autorepeat(_) => {}
+            KeyEvent::Autorepeat(_) | KeyEvent::LongPressed(_) => {}
         }
 
         if self.keys[Key::Menu] {
             // Global hotkeys
             match key_event {
-                KeyEvent::Autorepeat(Key::Menu) => {
-                    if self.is_menu_pressed_alone
-                        && self.pressed_menu.elapsed() >= LONG_PRESS_DURATION
-                    {
+                KeyEvent::LongPressed(Key::Menu) => {
+                    if self.is_menu_pressed_alone {
                         // Don't show menu
                         self.is_menu_pressed_alone = false;
                         #[cfg(unix)]
@@ -300,69 +559,90 @@ impl AlliumD<DefaultPlatform> {
                 }
                 KeyEvent::Pressed(Key::Up | Key::VolUp)
                 | KeyEvent::Autorepeat(Key::Up | Key::VolUp) => {
-                    self.add_brightness(5)?;
+                    self.add_brightness(5).await?;
                 }
                 KeyEvent::Pressed(Key::Down | Key::VolDown)
                 | KeyEvent::Autorepeat(Key::Down | Key::VolDown) => {
-                    self.add_brightness(-5)?;
+                    self.add_brightness(-5).await?;
                 }
                 KeyEvent::Pressed(Key::Left) | KeyEvent::Autorepeat(Key::Left) => {
-                    self.add_volume(-1)?;
+                    self.add_volume(-1).await?;
                 }
                 KeyEvent::Pressed(Key::Right) | KeyEvent::Autorepeat(Key::Right) => {
-                    self.add_volume(1)?;
+                    self.add_volume(1).await?;
                 }
-                KeyEvent::Released(Key::Power) => {
-                    let game_info = GameInfo::load()?;
-                    let name = match game_info.as_ref() {
-                        Some(game_info) => game_info.name.as_str(),
-                        None => "Allium",
-                    };
-                    let file_name = format!(
-                        "{}-{}.png",
-                        chrono::Local::now().format("%Y-%m-%d_%H-%M-%S"),
-                        name,
-                    );
-                    Command::new("screenshot")
-                        .arg(ALLIUM_SD_ROOT.join("Screenshots").join(file_name))
-                        .arg("--rumble")
-                        .spawn()?
-                        .wait()
-                        .await?;
+                KeyEvent::Released(Key::Power) | KeyEvent::Pressed(Key::R2) => {
+                    self.take_screenshot().await?;
+                }
+                KeyEvent::Pressed(Key::L2) => {
+                    #[cfg(unix)]
+                    self.capture_gif()?;
+                }
+                KeyEvent::Pressed(Key::Select) => {
+                    let profile = self.performance_settings.profile.next();
+                    self.set_power_profile(profile)?;
+                    self.performance_settings.save()?;
+                    Command::new("allium-overlay")
+                        .arg("toast")
+                        .arg(self.locale.t(match profile {
+                            PowerProfile::BatterySaver => "power-profile-battery-saver",
+                            PowerProfile::Balanced => "power-profile-balanced",
+                            PowerProfile::Performance => "power-profile-performance",
+                        }))
+                        .spawn()?;
                 }
                 _ => {}
             }
         } else {
             match key_event {
                 KeyEvent::Pressed(Key::VolDown) | KeyEvent::Autorepeat(Key::VolDown) => {
-                    self.add_volume(-1)?
+                    self.add_volume(-1).await?
                 }
                 KeyEvent::Pressed(Key::VolUp) | KeyEvent::Autorepeat(Key::VolUp) => {
-                    self.add_volume(1)?
+                    self.add_volume(1).await?
                 }
-                KeyEvent::Autorepeat(Key::Power) => {
-                    if !self.keys[Key::Menu] {
+                KeyEvent::LongPressed(Key::Power) => {
+                    if !self.is_power_long_pressed {
+                        self.is_power_long_pressed = true;
                         #[cfg(unix)]
-                        self.handle_quit().await?;
+                        self.handle_power_button_action(
+                            self.power_settings.power_button_long_press_action,
+                        )
+                        .await?;
                     }
                 }
                 KeyEvent::Released(Key::Power) => {
-                    if !self.keys[Key::Menu] {
+                    if !self.is_power_long_pressed {
                         #[cfg(unix)]
-                        match self.power_settings.power_button_action {
-                            PowerButtonAction::Suspend => self.handle_suspend().await?,
-                            PowerButtonAction::Shutdown => self.handle_quit().await?,
-                            PowerButtonAction::Nothing => {}
-                        }
+                        self.handle_power_button_action(self.power_settings.power_button_action)
+                            .await?;
                     }
                 }
                 KeyEvent::Pressed(Key::LidClose) =>
                 {
                     #[cfg(unix)]
                     match self.power_settings.lid_close_action {
-                        PowerButtonAction::Suspend => self.handle_suspend().await?,
-                        PowerButtonAction::Shutdown => self.handle_quit().await?,
-                        PowerButtonAction::Nothing => {}
+                        LidCloseAction::Suspend => self.handle_suspend(false).await?,
+                        LidCloseAction::Pause => {
+                            if self.is_ingame() && self.menu.is_none() {
+                                info!("lid closed, pausing game");
+                                RetroArchCommand::Pause.send().await?;
+                                self.is_lid_paused = true;
+                            }
+                        }
+                        LidCloseAction::SaveAndQuit => {
+                            if let Err(e) = self.save_and_quit_game().await {
+                                error!("failed to save game: {}", e);
+                            }
+                        }
+                        LidCloseAction::Shutdown => self.handle_quit().await?,
+                        LidCloseAction::Nothing => {}
+                    }
+                }
+                KeyEvent::Released(Key::LidClose) => {
+                    if std::mem::take(&mut self.is_lid_paused) {
+                        info!("lid opened, unpausing game");
+                        RetroArchCommand::Unpause.send().await?;
                     }
                 }
                 KeyEvent::Released(Key::Menu) => {
@@ -436,15 +716,101 @@ impl AlliumD<DefaultPlatform> {
         }
 
         signal(&self.main, Signal::SIGCONT)?;
-        self.platform.unsuspend(ctx)
+        self.platform.unsuspend(ctx)?;
+        self.wake()
+    }
+
+    /// Waits before the crashed launcher is restarted, or shows the recovery screen if it keeps
+    /// crashing.
+    #[cfg(unix)]
+    async fn handle_launcher_crash(&mut self) -> Result<()> {
+        match self.launcher_watchdog.crashed(Instant::now()) {
+            CrashAction::Restart(delay) => {
+                warn!("launcher crashed, restarting in {}ms", delay.as_millis());
+                tokio::time::sleep(delay).await;
+            }
+            CrashAction::GiveUp => {
+                error!("launcher keeps crashing, showing recovery screen");
+                self.show_recovery().await?;
+                self.launcher_watchdog.reset();
+            }
+        }
+        Ok(())
+    }
+
+    /// Lets the user retry launching the launcher, optionally resetting its state in case that
+    /// is what makes it crash, or power off.
+    #[cfg(unix)]
+    async fn show_recovery(&mut self) -> Result<()> {
+        Command::new("show").arg("-c").spawn()?.wait().await?;
+        Command::new("say")
+            .arg(self.locale.t("launcher-recovery"))
+            .spawn()?
+            .wait()
+            .await?;
+
+        loop {
+            match self.platform.poll().await {
+                KeyEvent::Pressed(Key::A) => {
+                    info!("resetting launcher state");
+                    if ALLIUM_LAUNCHER_STATE.exists() {
+                        fs::remove_file(ALLIUM_LAUNCHER_STATE.as_path())?;
+                    }
+                    break;
+                }
+                KeyEvent::Pressed(Key::B) => break,
+                KeyEvent::Released(Key::Power) => {
+                    self.handle_quit().await?;
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        Command::new("show").arg("-c").spawn()?.wait().await?;
+        Ok(())
+    }
+
+    /// Restarts the crashed in-game menu. Returns false if it keeps crashing, and the game
+    /// should be resumed instead.
+    async fn restart_menu(&mut self) -> Result<bool> {
+        match self.menu_watchdog.crashed(Instant::now()) {
+            CrashAction::Restart(delay) => {
+                warn!("menu crashed, restarting in {}ms", delay.as_millis());
+                tokio::time::sleep(delay).await;
+                self.menu = Some(Command::new(ALLIUM_MENU.as_path()).spawn()?);
+                Ok(true)
+            }
+            CrashAction::GiveUp => {
+                error!("menu keeps crashing, resuming game");
+                Ok(false)
+            }
+        }
     }
 
     #[cfg(unix)]
-    async fn handle_suspend(&mut self) -> Result<()> {
+    async fn handle_power_button_action(&mut self, action: PowerButtonAction) -> Result<()> {
+        match action {
+            PowerButtonAction::Suspend => self.handle_suspend(false).await,
+            PowerButtonAction::Screenshot => self.take_screenshot().await,
+            PowerButtonAction::Shutdown => self.handle_quit().await,
+            PowerButtonAction::Nothing => Ok(()),
+        }
+    }
+
+    /// Suspends until the power button is pressed, or any key if `wake_on_any_key` is set.
+    #[cfg(unix)]
+    async fn handle_suspend(&mut self, wake_on_any_key: bool) -> Result<()> {
         info!("suspending...");
+        // The in-game menu already pauses the game
+        let is_playing = self.is_ingame() && self.menu.is_none();
+        if is_playing && let Err(e) = RetroArchCommand::Pause.send().await {
+            warn!("failed to pause retroarch: {}", e);
+        }
         #[allow(clippy::let_unit_value)]
         let ctx = self.platform.suspend()?;
-        signal(&self.main, Signal::SIGSTOP)?;
+        // Standalone emulators and apps have no pause command, so every process is stopped too
+        self.signal_all(Signal::SIGSTOP)?;
 
         loop {
             tokio::select! {
@@ -454,11 +820,18 @@ impl AlliumD<DefaultPlatform> {
                         self.keys[Key::LidClose] = false;
                         break;
                     }
+                    if let KeyEvent::Released(key) = key_event && wake_on_any_key {
+                        self.keys[key] = false;
+                        break;
+                    }
                 }
                 _ = tokio::time::sleep(IDLE_TIMEOUT) => {
                     info!("idle timeout, shutting down");
-                    signal(&self.main, Signal::SIGCONT)?;
+                    self.signal_all(Signal::SIGCONT)?;
                     self.platform.unsuspend(ctx)?;
+                    if let Err(e) = self.save_and_quit_game().await {
+                        error!("failed to save game: {}", e);
+                    }
                     self.handle_quit().await?;
                     return Ok(());
                 }
@@ -466,8 +839,74 @@ impl AlliumD<DefaultPlatform> {
         }
 
         info!("waking up from suspend...");
-        signal(&self.main, Signal::SIGCONT)?;
-        self.platform.unsuspend(ctx)
+        self.platform.unsuspend(ctx)?;
+        self.signal_all(Signal::SIGCONT)?;
+        if is_playing && let Err(e) = RetroArchCommand::Unpause.send().await {
+            warn!("failed to unpause retroarch: {}", e);
+        }
+        self.wake()
+    }
+
+    /// Signals the main process, and the in-game menu if it is open.
+    #[cfg(unix)]
+    fn signal_all(&self, sig: Signal) -> Result<()> {
+        signal(&self.main, sig)?;
+        if let Some(menu) = self.menu.as_ref() {
+            signal(menu, sig)?;
+        }
+        if let Some(recorder) = self.recorder.as_ref() {
+            signal(recorder, sig)?;
+        }
+        Ok(())
+    }
+
+    /// Starts recording the screen in the background while in a game, so that the last few
+    /// seconds can be saved as a GIF at any time.
+    fn start_recording(&mut self) {
+        if self.recorder.is_some() || !self.is_ingame() {
+            return;
+        }
+        let name = match GameInfo::load() {
+            Ok(game_info) => game_info.map_or_else(|| "Allium".to_string(), |g| g.name),
+            Err(e) => {
+                warn!("failed to read game info: {}", e);
+                return;
+            }
+        };
+        debug!("starting gif recording");
+        match Command::new("screenshot")
+            .arg(&*ALLIUM_SCREENSHOTS_DIR)
+            .arg("--record")
+            .arg("--name")
+            .arg(name)
+            .spawn()
+        {
+            Ok(recorder) => self.recorder = Some(recorder),
+            Err(e) => warn!("failed to start gif recording: {}", e),
+        }
+    }
+
+    /// Saves the last few seconds of the game as a GIF.
+    #[cfg(unix)]
+    fn capture_gif(&mut self) -> Result<()> {
+        let Some(recorder) = self.recorder.as_ref() else {
+            return Ok(());
+        };
+        info!("saving gif");
+        signal(recorder, Signal::SIGUSR1)?;
+        Command::new("allium-overlay")
+            .arg("toast")
+            .arg(self.locale.t("gif-saving"))
+            .spawn()?;
+        Ok(())
+    }
+
+    async fn stop_recording(&mut self) -> Result<()> {
+        if let Some(mut recorder) = self.recorder.take() {
+            info!("stopping gif recording");
+            terminate(&mut recorder).await?;
+        }
+        Ok(())
     }
 
     #[cfg(unix)]
@@ -481,7 +920,8 @@ impl AlliumD<DefaultPlatform> {
         self.state.time = Utc::now();
         self.state.save()?;
 
-        if self.is_ingame() {
+        let is_ingame = self.is_ingame();
+        if is_ingame {
             self.update_play_time()?;
 
             if let Some(menu) = self.menu.as_mut() {
@@ -490,6 +930,11 @@ impl AlliumD<DefaultPlatform> {
         }
 
         terminate(&mut self.main).await?;
+        self.stop_recording().await?;
+
+        if is_ingame && let Err(e) = backup_save() {
+            error!("failed to back up save: {}", e);
+        }
 
         self.is_terminating = true;
 
@@ -506,6 +951,168 @@ impl AlliumD<DefaultPlatform> {
         Ok(())
     }
 
+    /// Starts or stops the remote control listener to match the Wi-Fi settings.
+    fn update_remote_control(&mut self) {
+        let settings = match WiFiSettings::load() {
+            Ok(settings) => settings,
+            Err(e) => {
+                warn!("failed to load Wi-Fi settings: {}", e);
+                return;
+            }
+        };
+        let enabled = DefaultPlatform::has_wifi() && settings.wifi && settings.remote_control;
+        match self.remote.take() {
+            Some(remote) if !enabled => {
+                info!("stopping remote control");
+                remote.abort();
+            }
+            None if enabled => {
+                let requests = self.remote_tx.clone();
+                self.remote = Some(tokio::spawn(async move {
+                    if let Err(e) = remote::listen(ALLIUM_REMOTE_CONTROL_PORT, requests).await {
+                        error!("remote control stopped: {}", e);
+                    }
+                }));
+            }
+            remote => self.remote = remote,
+        }
+    }
+
+    #[cfg(unix)]
+    async fn handle_remote(&mut self, method: RemoteMethod) -> Result<Value> {
+        info!("remote control: {:?}", method);
+        self.wake()?;
+        match method {
+            RemoteMethod::LaunchGame { path } => {
+                self.launch_game(path).await?;
+                Ok(Value::Null)
+            }
+            RemoteMethod::NowPlaying => Ok(match GameInfo::load()? {
+                Some(game_info) => json!({
+                    "name": game_info.name,
+                    "path": game_info.path,
+                    "core": game_info.core,
+                    "play_time": game_info.session_play_time().num_seconds(),
+                    "session_id": game_info.session_id,
+                    "started_at": game_info.started_at,
+                }),
+                None => Value::Null,
+            }),
+            RemoteMethod::Screenshot => {
+                self.take_screenshot().await?;
+                Ok(Value::Null)
+            }
+            RemoteMethod::SetVolume { volume } => {
+                self.add_volume(volume - self.volume()).await?;
+                Ok(json!(self.volume()))
+            }
+            RemoteMethod::SetBrightness { brightness } => {
+                self.set_brightness(brightness.min(100)).await?;
+                Ok(json!(self.brightness()))
+            }
+        }
+    }
+
+    /// Quits the running game, or the launcher, leaving the launcher to launch the game when it
+    /// restarts.
+    #[cfg(unix)]
+    async fn launch_game(&mut self, path: PathBuf) -> Result<()> {
+        if !path.is_file() {
+            bail!("no such game: {}", path.display());
+        }
+        fs::write(
+            ALLIUM_LAUNCH_REQUEST.as_path(),
+            path.as_os_str().as_encoded_bytes(),
+        )?;
+        if self.is_ingame() {
+            self.save_and_quit_game().await?;
+        }
+        if self.main.try_wait()?.is_none() {
+            terminate(&mut self.main).await?;
+        }
+        Ok(())
+    }
+
+    /// Saves the auto save state and asks RetroArch to quit, so that no progress is lost when the
+    /// device shuts down.
+    #[cfg(unix)]
+    async fn save_and_quit_game(&mut self) -> Result<()> {
+        if self.is_terminating
+            || GameInfo::load()?.is_none_or(|game_info| game_info.retroarch_core().is_none())
+        {
+            return Ok(());
+        }
+
+        if let Some(mut menu) = self.menu.take() {
+            terminate(&mut menu).await?;
+            RetroArchCommand::Unpause.send().await?;
+        }
+
+        let retroarch = RetroArchClient::new();
+        if !retroarch.is_alive().await {
+            warn!("retroarch is not responding, not saving state");
+            return Ok(());
+        }
+
+        info!("saving state before shutting down");
+        retroarch
+            .send_and_wait(
+                &RetroArchCommand::SaveStateSlot(-1),
+                std::time::Duration::from_secs(1),
+            )
+            .await?;
+        retroarch.send(&RetroArchCommand::Quit).await?;
+        if tokio::time::timeout(std::time::Duration::from_secs(5), self.main.wait())
+            .await
+            .is_err()
+        {
+            warn!("retroarch did not quit in time");
+        }
+        Ok(())
+    }
+
+    /// Flashes a warning over the game when the battery first drops below the warning level, and
+    /// again every `BATTERY_WARNING_STEP` after that.
+    fn warn_low_battery(&mut self, percentage: i32) -> Result<()> {
+        let threshold = self.power_settings.low_battery_warning;
+        if threshold == 0
+            || percentage > threshold
+            || self
+                .battery_warned_at
+                .is_some_and(|warned_at| percentage > warned_at - BATTERY_WARNING_STEP)
+            || !self.is_ingame()
+            || self.menu.is_some()
+        {
+            return Ok(());
+        }
+
+        info!("battery is at {}%, showing warning", percentage);
+        self.battery_warned_at = Some(percentage);
+        Command::new("allium-overlay")
+            .arg("battery")
+            .arg(percentage.to_string())
+            .spawn()?;
+        if RumbleSettings::load()?.low_battery {
+            self.platform.rumble(RUMBLE_LOW_BATTERY_DURATION)?;
+        }
+        Ok(())
+    }
+
+    /// Shows the battery state and finished background jobs on the LED, if it changed.
+    fn update_led(&mut self, charging: bool, percentage: i32) -> Result<()> {
+        if !DefaultPlatform::has_led() {
+            return Ok(());
+        }
+        let threshold = self.power_settings.low_battery_warning;
+        let low_battery = threshold != 0 && percentage <= threshold;
+        let pattern = LedSettings::load()?.pattern(charging, low_battery, led::is_job_finished());
+        if self.led != Some(pattern) {
+            self.platform.set_led(pattern)?;
+            self.led = Some(pattern);
+        }
+        Ok(())
+    }
+
     #[allow(unused)]
     fn update_play_time(&self) -> Result<()> {
         if !self.is_ingame() {
@@ -515,37 +1122,258 @@ impl AlliumD<DefaultPlatform> {
         let file = File::open(ALLIUM_GAME_INFO.as_path())?;
         let mut game_info: GameInfo = serde_json::from_reader(file)?;
 
+        // Recorded time is marked as such, so that it isn't added again if the game keeps running
+        let play_time = game_info.take_play_time();
+        game_info.save()?;
+        SessionJournal::close()?;
+
         // As a sanity check, don't add play time if the game was played for more than 24 hours
-        if game_info.play_time() > Duration::hours(24) {
+        if play_time > Duration::hours(24) {
             warn!("play time is too long, not adding to database");
             return Ok(());
         }
 
         let database = Database::new()?;
-        database.add_play_time(game_info.path.as_path(), game_info.play_time());
+        database.add_play_time(game_info.path.as_path(), play_time);
 
         Ok(())
     }
 
+    /// Marks the running game's unrecorded play time, so that it can be recovered after a crash.
+    fn update_session_journal(&self) -> Result<()> {
+        if !self.is_ingame() {
+            return Ok(());
+        }
+        let file = File::open(ALLIUM_GAME_INFO.as_path())?;
+        let game_info: GameInfo = serde_json::from_reader(file)?;
+        SessionJournal::open(&game_info)
+    }
+
+    /// Logs the session of the game that just exited.
+    fn end_session(&self) {
+        // Read directly, as loading the game info also enables swap
+        let game_info = File::open(ALLIUM_GAME_INFO.as_path())
+            .map_err(anyhow::Error::from)
+            .and_then(|file| Ok(serde_json::from_reader::<_, GameInfo>(file)?));
+        match game_info {
+            Ok(game_info) => info!(
+                "session {} of {} ended, played {} minutes since {}",
+                game_info.session_id,
+                game_info.name,
+                game_info.session_play_time().num_minutes(),
+                game_info.started_at,
+            ),
+            Err(e) => warn!("failed to read game info: {}", e),
+        }
+    }
+
     fn is_ingame(&self) -> bool {
         Path::new(&*ALLIUM_GAME_INFO).exists()
     }
 
-    fn add_volume(&mut self, add: i32) -> Result<()> {
+    async fn take_screenshot(&self) -> Result<()> {
+        let game_info = GameInfo::load()?;
+        let name = match game_info.as_ref() {
+            Some(game_info) => game_info.name.as_str(),
+            None => "Allium",
+        };
+        let file_name = format!(
+            "{}-{}.png",
+            chrono::Local::now().format("%Y-%m-%d_%H-%M-%S"),
+            name,
+        );
+        fs::create_dir_all(&*ALLIUM_SCREENSHOTS_DIR)?;
+        Command::new("screenshot")
+            .arg(ALLIUM_SCREENSHOTS_DIR.join(file_name))
+            .arg("--rumble")
+            .spawn()?
+            .wait()
+            .await?;
+        Command::new("allium-overlay")
+            .arg("toast")
+            .arg(self.locale.t("screenshot-saved"))
+            .spawn()?;
+        Ok(())
+    }
+
+    /// Restarts the idle timers, restoring the brightness if the screen was dimmed.
+    fn wake(&mut self) -> Result<()> {
+        if self.idle.activity() {
+            info!("activity, restoring brightness");
+            self.platform.set_brightness(self.brightness())?;
+        }
+        Ok(())
+    }
+
+    /// Brightness to set, capped by the power profile.
+    fn brightness(&self) -> u8 {
+        self.state
+            .brightness
+            .min(self.performance_settings.profile.max_brightness())
+    }
+
+    /// Switches the power profile, applying its CPU, backlight and WiFi settings.
+    fn set_power_profile(&mut self, profile: PowerProfile) -> Result<()> {
+        info!("setting power profile: {:?}", profile);
+        let previous = std::mem::replace(&mut self.performance_settings.profile, profile);
+
+        let game_profile = GameInfo::load()?.map(|g| g.cpu_profile).unwrap_or_default();
+        if let Err(e) = self
+            .platform
+            .set_cpu_profile(self.performance_settings.cpu_profile(game_profile))
+        {
+            warn!("failed to set cpu profile: {}", e);
+        }
+
+        self.platform.set_brightness(self.brightness())?;
+
+        if DefaultPlatform::has_wifi() && profile.wifi() != previous.wifi() {
+            if profile.wifi() {
+                WiFiSettings::load()?.init()?;
+            } else {
+                wifi::wifi_off()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Volume for the current output, speaker or headphones.
+    fn volume(&self) -> i32 {
+        if self.is_headphones_connected {
+            self.state.headphone_volume
+        } else {
+            self.state.volume
+        }
+    }
+
+    async fn add_volume(&mut self, add: i32) -> Result<()> {
         info!("adding volume: {}", add);
-        self.state.volume = (self.state.volume + add).clamp(0, 20);
-        self.platform.set_volume(self.state.volume)?;
+        let volume = (self.volume() + add).clamp(0, 20);
+        if self.is_headphones_connected {
+            self.state.headphone_volume = volume;
+        } else {
+            self.state.volume = volume;
+        }
+        self.platform.set_volume(volume)?;
+        if let Err(e) = self.show_level("volume", volume * 100 / 20).await {
+            warn!("failed to show volume: {}", e);
+        }
+        Ok(())
+    }
+
+    /// Shows a level bar over whatever is on screen, updating the bar if it is already shown.
+    async fn show_level(&mut self, kind: &str, percentage: i32) -> Result<()> {
+        if let Some(osd) = self.osd.as_mut()
+            && osd.try_wait()?.is_none()
+            && let Some(stdin) = osd.stdin.as_mut()
+            && stdin
+                .write_all(format!("{kind} {percentage}\n").as_bytes())
+                .await
+                .is_ok()
+        {
+            return Ok(());
+        }
+
+        self.osd = Some(
+            Command::new("allium-overlay")
+                .arg("level")
+                .arg(kind)
+                .arg(percentage.to_string())
+                .stdin(Stdio::piped())
+                .spawn()?,
+        );
         Ok(())
     }
 
-    fn add_brightness(&mut self, add: i8) -> Result<()> {
+    async fn add_brightness(&mut self, add: i8) -> Result<()> {
         info!("adding brightness: {}", add);
-        self.state.brightness = (self.state.brightness as i8 + add).clamp(0, 100) as u8;
-        self.platform.set_brightness(self.state.brightness)?;
+        // The power profile only caps the brightness, so that it comes back when switching to
+        // another profile. Lowering it starts from what is on screen.
+        let current = if add < 0 {
+            self.brightness()
+        } else {
+            self.state.brightness
+        };
+        self.set_brightness((current as i8 + add).clamp(0, 100) as u8)
+            .await
+    }
+
+    /// Sets the brightness the user chose, which the power profile may cap.
+    async fn set_brightness(&mut self, brightness: u8) -> Result<()> {
+        self.state.brightness = brightness;
+        self.platform.set_brightness(self.brightness())?;
+        if let Err(e) = self
+            .show_level("brightness", self.brightness() as i32)
+            .await
+        {
+            warn!("failed to show brightness: {}", e);
+        }
         Ok(())
     }
 }
 
+/// Records the battery level for the battery history in settings.
+fn log_battery(battery: &impl Battery) -> Result<()> {
+    let now = Utc::now();
+    Database::new()?.add_battery_sample(
+        &BatterySample {
+            time: now,
+            percentage: battery.percentage(),
+            charging: battery.charging(),
+        },
+        now - BATTERY_HISTORY_DURATION,
+    )
+}
+
+/// Records a voltage reading if a battery calibration is in progress. Readings start from the
+/// full battery milestone, and charging partway starts the calibration over.
+fn record_calibration(battery: &impl Battery) -> Result<()> {
+    let mut calibration = BatteryCalibration::load()?;
+    if calibration.recording.is_none() {
+        return Ok(());
+    }
+    if battery.charging() {
+        if battery.percentage() >= 100 {
+            calibration.mark_charged();
+        } else {
+            calibration.start();
+        }
+    } else if let Some(voltage) = battery.voltage() {
+        calibration.record(Utc::now(), voltage);
+    }
+    calibration.save()
+}
+
+/// Completes an in-progress battery calibration, as the battery is down to `percentage`.
+fn finish_calibration(percentage: i32) -> Result<()> {
+    let mut calibration = BatteryCalibration::load()?;
+    if calibration.recording.is_none() {
+        return Ok(());
+    }
+    calibration.finish(percentage);
+    calibration.save()
+}
+
+/// Backs up the battery save of the game that just exited.
+fn backup_save() -> Result<()> {
+    let Some(game_info) = GameInfo::load()? else {
+        return Ok(());
+    };
+    let Some(core_name) = game_info.retroarch_core().and_then(core_name) else {
+        return Ok(());
+    };
+    let Some(stem) = game_info.path.file_stem() else {
+        return Ok(());
+    };
+    let settings = SaveBackupSettings::load()?;
+    save_backup::backup(
+        &core_name,
+        &stem.to_string_lossy(),
+        settings.save_backups.max(0) as usize,
+    )
+}
+
 #[allow(clippy::needless_pass_by_ref_mut)]
 async fn terminate(child: &mut Child) -> Result<()> {
     #[cfg(unix)]
@@ -2,15 +2,16 @@
 #![warn(rust_2018_idioms)]
 
 mod alliumd;
+mod remote;
+mod watchdog;
 
 use anyhow::Result;
-use simple_logger::SimpleLogger;
 
 use crate::alliumd::AlliumD;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    SimpleLogger::new().env().init().unwrap();
+    common::logging::init("alliumd")?;
 
     #[cfg(feature = "console")]
     {
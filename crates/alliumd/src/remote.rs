@@ -0,0 +1,294 @@
+//! JSON-RPC 2.0 remote control over TCP, for home automation dashboards and automated testing.
+//! Requests and responses are one JSON object per line. Each request carries the access PIN shown
+//! on the device in a `pin` member.
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow};
+use common::wifi;
+use log::{debug, info, warn};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinSet;
+
+const INVALID_REQUEST: i32 = -32600;
+const PARSE_ERROR: i32 = -32700;
+const SERVER_ERROR: i32 = -32000;
+const UNAUTHORIZED: i32 = -32001;
+
+/// Delay after a wrong PIN, so that it can't be guessed quickly.
+const LOGIN_FAILURE_DELAY: Duration = Duration::from_secs(2);
+/// Wrong PINs, across all connections, after which logins are refused for `LOCKOUT_DURATION`.
+const MAX_LOGIN_FAILURES: u32 = 5;
+const LOCKOUT_DURATION: Duration = Duration::from_secs(60);
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum RemoteMethod {
+    /// Quits the running game, if any, and launches a game.
+    LaunchGame {
+        path: PathBuf,
+    },
+    /// Returns the running game, or null.
+    NowPlaying,
+    Screenshot,
+    /// Sets the volume, from 0 to 20.
+    SetVolume {
+        volume: i32,
+    },
+    /// Sets the brightness, from 0 to 100.
+    SetBrightness {
+        brightness: u8,
+    },
+}
+
+/// A request to be handled by alliumd, which replies with the result or an error message.
+#[derive(Debug)]
+pub struct RemoteRequest {
+    pub method: RemoteMethod,
+    pub reply: oneshot::Sender<Result<Value, String>>,
+}
+
+/// Wrong PINs counted across every connection, as opening connections in parallel would
+/// otherwise get around `LOGIN_FAILURE_DELAY`.
+#[derive(Debug, Default)]
+struct LoginLimiter {
+    failures: u32,
+    locked_until: Option<Instant>,
+}
+
+impl LoginLimiter {
+    /// Whether logins are refused, even with the right PIN.
+    fn is_locked(&mut self, now: Instant) -> bool {
+        match self.locked_until {
+            Some(until) if now < until => true,
+            Some(_) => {
+                self.locked_until = None;
+                self.failures = 0;
+                false
+            }
+            None => false,
+        }
+    }
+
+    fn record_failure(&mut self, now: Instant) {
+        self.failures += 1;
+        if self.failures >= MAX_LOGIN_FAILURES {
+            warn!("too many wrong remote control PINs, refusing logins");
+            self.locked_until = Some(now + LOCKOUT_DURATION);
+        }
+    }
+
+    fn record_success(&mut self) {
+        self.failures = 0;
+    }
+}
+
+/// Accepts connections on the Wi-Fi interface, forwarding their requests to alliumd. Dropping
+/// the future also closes every open connection.
+pub async fn listen(port: u16, requests: mpsc::Sender<RemoteRequest>) -> Result<()> {
+    wifi::wait_for_wifi().await?;
+    let address = wifi::ip_address().ok_or_else(|| anyhow!("no Wi-Fi address"))?;
+    let pin = wifi::access_pin()?;
+    let listener = TcpListener::bind((address.as_str(), port)).await?;
+    info!("remote control listening on {}:{}", address, port);
+
+    let limiter = Arc::new(Mutex::new(LoginLimiter::default()));
+    let mut connections = JoinSet::new();
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (stream, addr) = accepted?;
+                info!("remote control connection from {}", addr);
+                let requests = requests.clone();
+                let pin = pin.clone();
+                let limiter = Arc::clone(&limiter);
+                connections.spawn(async move {
+                    if let Err(e) = serve(stream, &pin, &limiter, requests).await {
+                        warn!("remote control connection failed: {}", e);
+                    }
+                });
+            }
+            Some(_) = connections.join_next() => {}
+        }
+    }
+}
+
+async fn serve(
+    stream: TcpStream,
+    pin: &str,
+    limiter: &Mutex<LoginLimiter>,
+    requests: mpsc::Sender<RemoteRequest>,
+) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        debug!("remote control request: {}", line);
+        if limiter.lock().unwrap().is_locked(Instant::now()) {
+            warn!("remote control request while locked out");
+            let response = error(request_id(&line), UNAUTHORIZED, "too many wrong PINs");
+            writer.write_all(format!("{response}\n").as_bytes()).await?;
+            continue;
+        }
+        let response = match parse_request(&line, pin) {
+            Err(response) if response["error"]["code"] == UNAUTHORIZED => {
+                warn!("remote control request with wrong PIN");
+                limiter.lock().unwrap().record_failure(Instant::now());
+                tokio::time::sleep(LOGIN_FAILURE_DELAY).await;
+                response
+            }
+            Ok((id, method)) => {
+                limiter.lock().unwrap().record_success();
+                let (reply, result) = oneshot::channel();
+                requests.send(RemoteRequest { method, reply }).await?;
+                match result.await? {
+                    Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                    Err(message) => error(id, SERVER_ERROR, &message),
+                }
+            }
+            Err(response) => response,
+        };
+        writer.write_all(format!("{response}\n").as_bytes()).await?;
+    }
+    Ok(())
+}
+
+/// The id of a request, or null if it can't be parsed.
+fn request_id(line: &str) -> Value {
+    serde_json::from_str::<Value>(line)
+        .ok()
+        .and_then(|request| request.get("id").cloned())
+        .unwrap_or(Value::Null)
+}
+
+/// Parses a request into its id and method, or the error response to send back.
+fn parse_request(line: &str, pin: &str) -> Result<(Value, RemoteMethod), Value> {
+    let request: Value =
+        serde_json::from_str(line).map_err(|e| error(Value::Null, PARSE_ERROR, &e.to_string()))?;
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let given = request
+        .get("pin")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    if !wifi::check_access_pin(pin, given) {
+        return Err(error(id, UNAUTHORIZED, "wrong PIN"));
+    }
+    let mut call = json!({ "method": request.get("method") });
+    if let Some(params) = request.get("params").filter(|params| {
+        !params.is_null() && params.as_object().is_none_or(|params| !params.is_empty())
+    }) {
+        call["params"] = params.clone();
+    }
+    match serde_json::from_value(call) {
+        Ok(method) => Ok((id, method)),
+        Err(e) => Err(error(id, INVALID_REQUEST, &e.to_string())),
+    }
+}
+
+fn error(id: Value, code: i32, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PIN: &str = "123456";
+
+    #[test]
+    fn test_parse_request() {
+        assert_eq!(
+            parse_request(
+                r#"{"jsonrpc":"2.0","id":1,"pin":"123456","method":"now_playing"}"#,
+                PIN
+            )
+            .unwrap(),
+            (json!(1), RemoteMethod::NowPlaying)
+        );
+        assert_eq!(
+            parse_request(
+                r#"{"jsonrpc":"2.0","id":"a","pin":"123456","method":"screenshot","params":{}}"#,
+                PIN
+            )
+            .unwrap(),
+            (json!("a"), RemoteMethod::Screenshot)
+        );
+        assert_eq!(
+            parse_request(
+                r#"{"jsonrpc":"2.0","id":2,"pin":"123456","method":"set_volume","params":{"volume":10}}"#, PIN
+            )
+            .unwrap(),
+            (json!(2), RemoteMethod::SetVolume { volume: 10 })
+        );
+        assert_eq!(
+            parse_request(
+                r#"{"jsonrpc":"2.0","id":3,"pin":"123456","method":"launch_game","params":{"path":"/mnt/SDCARD/Roms/GBA/Game.gba"}}"#, PIN
+            )
+            .unwrap(),
+            (
+                json!(3),
+                RemoteMethod::LaunchGame {
+                    path: PathBuf::from("/mnt/SDCARD/Roms/GBA/Game.gba")
+                }
+            )
+        );
+
+        let error = parse_request(
+            r#"{"jsonrpc":"2.0","id":4,"pin":"123456","method":"reboot"}"#,
+            PIN,
+        )
+        .unwrap_err();
+        assert_eq!(error["id"], 4);
+        assert_eq!(error["error"]["code"], INVALID_REQUEST);
+
+        let error = parse_request("not json", PIN).unwrap_err();
+        assert_eq!(error["error"]["code"], PARSE_ERROR);
+
+        let error =
+            parse_request(r#"{"jsonrpc":"2.0","id":5,"method":"now_playing"}"#, PIN).unwrap_err();
+        assert_eq!(error["error"]["code"], UNAUTHORIZED);
+        let error = parse_request(
+            r#"{"jsonrpc":"2.0","id":6,"pin":"654321","method":"now_playing"}"#,
+            PIN,
+        )
+        .unwrap_err();
+        assert_eq!(error["error"]["code"], UNAUTHORIZED);
+    }
+
+    #[test]
+    fn test_login_limiter() {
+        let now = Instant::now();
+        let mut limiter = LoginLimiter::default();
+
+        for _ in 0..MAX_LOGIN_FAILURES - 1 {
+            limiter.record_failure(now);
+        }
+        assert!(!limiter.is_locked(now));
+        limiter.record_success();
+        limiter.record_failure(now);
+        assert!(!limiter.is_locked(now));
+
+        for _ in 0..MAX_LOGIN_FAILURES {
+            limiter.record_failure(now);
+        }
+        assert!(limiter.is_locked(now));
+        assert!(limiter.is_locked(now + LOCKOUT_DURATION / 2));
+
+        // The lockout ends, and the count starts over
+        assert!(!limiter.is_locked(now + LOCKOUT_DURATION));
+        limiter.record_failure(now + LOCKOUT_DURATION);
+        assert!(!limiter.is_locked(now + LOCKOUT_DURATION));
+    }
+}
@@ -0,0 +1,96 @@
+//! Restarts the launcher and in-game menu when they crash, and gives up after repeated failures
+//! so that a process that crashes on startup doesn't loop forever.
+
+use std::process::ExitStatus;
+use std::time::{Duration, Instant};
+
+/// Crashes within this window count towards giving up.
+const CRASH_WINDOW: Duration = Duration::from_secs(60);
+
+/// Number of crashes within the window after which the process is no longer restarted.
+const MAX_CRASHES: usize = 3;
+
+/// Delay before restarting after the first crash, doubled for each crash after that.
+const RESTART_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrashAction {
+    /// Restart the process after the delay.
+    Restart(Duration),
+    /// The process keeps crashing, stop restarting it.
+    GiveUp,
+}
+
+#[derive(Debug, Default)]
+pub struct Watchdog {
+    crashes: Vec<Instant>,
+}
+
+impl Watchdog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a crash, and returns what to do about it.
+    pub fn crashed(&mut self, now: Instant) -> CrashAction {
+        self.crashes
+            .retain(|t| now.saturating_duration_since(*t) < CRASH_WINDOW);
+        self.crashes.push(now);
+
+        if self.crashes.len() >= MAX_CRASHES {
+            CrashAction::GiveUp
+        } else {
+            CrashAction::Restart(RESTART_DELAY * 2u32.pow(self.crashes.len() as u32 - 1))
+        }
+    }
+
+    /// Forgets previous crashes, e.g. after the user chose to retry.
+    pub fn reset(&mut self) {
+        self.crashes.clear();
+    }
+}
+
+/// Whether the process exited unexpectedly. Exiting cleanly, or being terminated by us, is not
+/// a crash.
+pub fn is_crash(status: ExitStatus) -> bool {
+    if status.success() {
+        return false;
+    }
+    #[cfg(unix)]
+    {
+        use nix::sys::signal::Signal;
+        use std::os::unix::process::ExitStatusExt;
+        if status.signal() == Some(Signal::SIGTERM as i32) {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watchdog_gives_up_after_repeated_crashes() {
+        let mut watchdog = Watchdog::new();
+        let now = Instant::now();
+
+        assert_eq!(watchdog.crashed(now), CrashAction::Restart(RESTART_DELAY));
+        assert_eq!(
+            watchdog.crashed(now + Duration::from_secs(1)),
+            CrashAction::Restart(RESTART_DELAY * 2)
+        );
+        assert_eq!(
+            watchdog.crashed(now + Duration::from_secs(2)),
+            CrashAction::GiveUp
+        );
+
+        watchdog.reset();
+        assert_eq!(watchdog.crashed(now), CrashAction::Restart(RESTART_DELAY));
+
+        // Crashes outside the window are forgotten
+        let later = now + CRASH_WINDOW * 2;
+        assert_eq!(watchdog.crashed(later), CrashAction::Restart(RESTART_DELAY));
+    }
+}
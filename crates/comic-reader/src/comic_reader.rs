@@ -0,0 +1,147 @@
+use std::collections::VecDeque;
+use std::process;
+use std::time::Duration;
+
+use anyhow::Result;
+use common::command::Command;
+use common::geom;
+use common::locale::{Locale, LocaleSettings};
+use common::resources::Resources;
+use common::view::{Toast, View};
+use embedded_graphics::prelude::*;
+use log::{trace, warn};
+
+use common::database::Database;
+use common::display::Display;
+use common::display::image::draw_background;
+use common::platform::{DefaultPlatform, Platform};
+use common::stylesheet::Stylesheet;
+use type_map::TypeMap;
+
+use crate::view::App;
+
+pub struct ComicReader<P: Platform> {
+    platform: P,
+    display: P::Display,
+    res: Resources,
+    view: App<P::Battery>,
+    toast: Option<Toast>,
+}
+
+impl ComicReader<DefaultPlatform> {
+    pub fn new(mut platform: DefaultPlatform) -> Result<Self> {
+        let display = platform.display()?;
+        let battery = platform.battery()?;
+
+        let mut res = TypeMap::new();
+        res.insert(Database::new()?);
+        res.insert(Stylesheet::load()?);
+        let locale = Locale::new(&LocaleSettings::load()?);
+        geom::set_right_to_left(locale.is_right_to_left(), display.size().width);
+        res.insert(locale);
+        res.insert(Into::<geom::Size>::into(display.size()));
+        let res = Resources::new(res);
+
+        let view = App::new(display.bounding_box().into(), res.clone(), battery)?;
+
+        Ok(ComicReader {
+            platform,
+            display,
+            res,
+            view,
+            toast: None,
+        })
+    }
+
+    pub async fn run_event_loop(&mut self) -> Result<()> {
+        {
+            let styles = self.res.get::<Stylesheet>();
+            draw_background(
+                &mut self.display,
+                &styles,
+                styles.background_image.as_deref(),
+            )?;
+        }
+        self.display.save()?;
+
+        #[cfg(unix)]
+        let mut sigterm =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+
+        let mut frame_interval = tokio::time::interval(Duration::from_micros(166_667));
+
+        loop {
+            let mut drawn = self.view.should_draw()
+                && self
+                    .view
+                    .draw(&mut self.display, &self.res.get::<Stylesheet>())?;
+
+            if let Some(toast) = self.toast.as_mut() {
+                if toast.has_expired() {
+                    self.toast = None;
+                    self.handle_command(Command::Redraw).await?;
+                    drawn |= self
+                        .view
+                        .draw(&mut self.display, &self.res.get::<Stylesheet>())?;
+                } else {
+                    drawn |= toast.draw(&mut self.display, &self.res.get::<Stylesheet>())?;
+                }
+            }
+
+            if drawn {
+                self.display.flush()?;
+            }
+
+            #[cfg(unix)]
+            tokio::select! {
+                _ = frame_interval.tick(), if self.toast.is_some() => {}
+                _ = sigterm.recv() => {
+                    self.handle_command(Command::Exit).await?;
+                }
+                event = self.platform.poll() => {
+                    let event = event.ui();
+                    let mut bubble = VecDeque::new();
+                    self.view.handle_key_event(event, tx.clone(), &mut bubble).await?;
+                }
+                else => {}
+            }
+
+            #[cfg(not(unix))]
+            tokio::select! {
+                _ = frame_interval.tick(), if self.toast.is_some() => {}
+                event = self.platform.poll() => {
+                    let event = event.ui();
+                    let mut bubble = VecDeque::new();
+                    self.view.handle_key_event(event, tx.clone(), &mut bubble).await?;
+                }
+                else => {}
+            }
+
+            while let Ok(cmd) = rx.try_recv() {
+                self.handle_command(cmd).await?;
+            }
+        }
+    }
+
+    async fn handle_command(&mut self, command: Command) -> Result<()> {
+        match command {
+            Command::Exit => {
+                process::exit(0);
+            }
+            Command::Redraw => {
+                trace!("redrawing");
+                self.display.load(self.display.bounding_box().into())?;
+                self.view.set_should_draw();
+            }
+            Command::Toast(text, duration) => {
+                self.toast = Some(Toast::new(text, duration));
+            }
+            command => {
+                warn!("unhandled command: {:?}", command);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+mod comic_reader;
+mod view;
+
+use anyhow::Result;
+
+use common::platform::{DefaultPlatform, Platform};
+
+use crate::comic_reader::ComicReader;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    common::logging::init("comic-reader")?;
+
+    let platform = DefaultPlatform::new()?;
+    let mut app = ComicReader::new(platform)?;
+    app.run_event_loop().await?;
+    Ok(())
+}
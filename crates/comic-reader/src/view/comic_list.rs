@@ -0,0 +1,249 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use common::command::Command;
+use common::constants::ALLIUM_COMICS_DIR;
+use common::display::Display;
+use common::geom::{Alignment, Point, Rect};
+use common::locale::Locale;
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::stylesheet::Stylesheet;
+use common::view::{ButtonHint, ButtonIcon, CbzReader, Label, Row, ScrollList, View};
+use embedded_graphics::prelude::OriginDimensions;
+use log::warn;
+use tokio::sync::mpsc::Sender;
+
+/// Lists the CBZ files in the comics directory.
+#[derive(Debug)]
+pub struct ComicList {
+    rect: Rect,
+    res: Resources,
+    comics: Vec<PathBuf>,
+    list: ScrollList,
+    empty: Option<Label<String>>,
+    button_hints: Row<ButtonHint<String>>,
+    child: Option<CbzReader>,
+}
+
+impl ComicList {
+    pub fn new(rect: Rect, res: Resources) -> Result<Self> {
+        let Rect { x, y, w, h } = rect;
+
+        let styles = res.get::<Stylesheet>();
+        let locale = res.get::<Locale>();
+
+        let mut comics = Vec::new();
+        find_comics(&ALLIUM_COMICS_DIR, &mut comics);
+        comics.sort_by_key(|path| path.to_string_lossy().to_lowercase());
+
+        let mut list = ScrollList::new(
+            Rect::new(x + 12, y, w - 24, h - 8 - ButtonIcon::diameter(&styles)),
+            Vec::new(),
+            Alignment::Left,
+            styles.row_height(),
+        );
+        list.set_items(
+            comics
+                .iter()
+                .map(|path| {
+                    path.strip_prefix(ALLIUM_COMICS_DIR.as_path())
+                        .unwrap_or(path)
+                        .with_extension("")
+                        .to_string_lossy()
+                        .into_owned()
+                })
+                .collect(),
+            false,
+        );
+
+        let empty = comics.is_empty().then(|| {
+            Label::new(
+                Point::new(x + 12, y + 8),
+                locale.t("comic-reader-no-comics"),
+                Alignment::Left,
+                Some(w - 24),
+            )
+        });
+
+        let button_hints = Row::new(
+            Point::new(
+                x + w as i32 - 12,
+                y + h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            vec![
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::A,
+                    locale.t("button-select"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::B,
+                    locale.t("button-back"),
+                    Alignment::Right,
+                ),
+            ],
+            Alignment::Right,
+            12,
+        );
+
+        drop(styles);
+        drop(locale);
+
+        Ok(Self {
+            rect,
+            res,
+            comics,
+            list,
+            empty,
+            button_hints,
+            child: None,
+        })
+    }
+}
+
+/// Recursively collects the CBZ files in `dir`.
+fn find_comics(dir: &Path, comics: &mut Vec<PathBuf>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!("failed to read comics directory {}: {}", dir.display(), e);
+            return;
+        }
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            find_comics(&path, comics);
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("cbz"))
+        {
+            comics.push(path);
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl View for ComicList {
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        if let Some(child) = self.child.as_mut() {
+            return Ok(child.should_draw() && child.draw(display, styles)?);
+        }
+
+        let mut drawn = false;
+
+        if let Some(empty) = self.empty.as_mut() {
+            drawn |= empty.should_draw() && empty.draw(display, styles)?;
+        } else {
+            drawn |= self.list.should_draw() && self.list.draw(display, styles)?;
+        }
+
+        if self.button_hints.should_draw() {
+            display.load(Rect::new(
+                0,
+                display.size().height as i32 - 48,
+                display.size().width,
+                48,
+            ))?;
+            self.button_hints.set_should_draw();
+            if self.button_hints.draw(display, styles)? {
+                drawn = true;
+            }
+        }
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        if let Some(child) = self.child.as_ref() {
+            child.should_draw()
+        } else {
+            self.list.should_draw()
+                || self.empty.as_ref().is_some_and(|e| e.should_draw())
+                || self.button_hints.should_draw()
+        }
+    }
+
+    fn set_should_draw(&mut self) {
+        if let Some(child) = self.child.as_mut() {
+            child.set_should_draw();
+        } else {
+            self.list.set_should_draw();
+            if let Some(empty) = self.empty.as_mut() {
+                empty.set_should_draw();
+            }
+            self.button_hints.set_should_draw();
+        }
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        if let Some(child) = self.child.as_mut() {
+            if child
+                .handle_key_event(event, commands.clone(), bubble)
+                .await?
+            {
+                let mut close = false;
+                bubble.retain(|cmd| match cmd {
+                    Command::CloseView => {
+                        close = true;
+                        false
+                    }
+                    _ => true,
+                });
+                if close {
+                    self.child = None;
+                    commands.send(Command::Redraw).await?;
+                }
+            }
+            return Ok(true);
+        }
+
+        match event {
+            KeyEvent::Pressed(Key::A) => {
+                if let Some(path) = self.comics.get(self.list.selected()) {
+                    self.child = Some(CbzReader::new(self.rect, self.res.clone(), path.clone()));
+                    commands.send(Command::Redraw).await?;
+                }
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::B) => {
+                commands.send(Command::Exit).await?;
+                Ok(true)
+            }
+            _ => self.list.handle_key_event(event, commands, bubble).await,
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.list, &self.button_hints]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![&mut self.list, &mut self.button_hints]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
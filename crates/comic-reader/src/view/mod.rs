@@ -0,0 +1,5 @@
+mod app;
+mod comic_list;
+
+pub use app::App;
+pub use comic_list::ComicList;
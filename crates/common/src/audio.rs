@@ -0,0 +1,89 @@
+use std::fs::{self, File};
+
+use anyhow::Result;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use strum::FromRepr;
+
+use crate::constants::ALLIUM_AUDIO_SETTINGS;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioSettings {
+    /// Mix both channels down to mono.
+    #[serde(default)]
+    pub mono: bool,
+    /// Left/right balance, from -10 (left only) to 10 (right only).
+    #[serde(default)]
+    pub balance: i8,
+    /// How volume steps map to output levels.
+    #[serde(default)]
+    pub volume_curve: VolumeCurve,
+    /// Output level of the highest volume step, in percent of the full range.
+    #[serde(default = "AudioSettings::default_max_volume")]
+    pub max_volume: i32,
+}
+
+/// Mapping from volume steps to output levels.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize, FromRepr)]
+pub enum VolumeCurve {
+    /// The same change in dB for every step.
+    Linear,
+    /// Between linear and logarithmic.
+    Smooth,
+    /// Large steps at low volume, fine steps at high volume.
+    #[default]
+    Logarithmic,
+}
+
+impl VolumeCurve {
+    /// Maps a volume step in `0..=max` to a fraction of the output range.
+    pub fn apply(self, volume: i32, max: i32) -> f32 {
+        let volume = volume.clamp(0, max) as f32;
+        let max = max as f32;
+        match self {
+            VolumeCurve::Linear => volume / max,
+            VolumeCurve::Smooth => (volume / max).sqrt(),
+            VolumeCurve::Logarithmic => (volume + 1.0).log10() / (max + 1.0).log10(),
+        }
+    }
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            mono: false,
+            balance: 0,
+            volume_curve: VolumeCurve::default(),
+            max_volume: Self::default_max_volume(),
+        }
+    }
+}
+
+impl AudioSettings {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    fn default_max_volume() -> i32 {
+        100
+    }
+
+    pub fn load() -> Result<Self> {
+        if ALLIUM_AUDIO_SETTINGS.exists() {
+            debug!("found state, loading from file");
+            let file = File::open(ALLIUM_AUDIO_SETTINGS.as_path())?;
+            if let Ok(json) = serde_json::from_reader(file) {
+                return Ok(json);
+            }
+            warn!("failed to read audio file, removing");
+            fs::remove_file(ALLIUM_AUDIO_SETTINGS.as_path())?;
+        }
+        Ok(Self::new())
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let file = File::create(ALLIUM_AUDIO_SETTINGS.as_path())?;
+        serde_json::to_writer(file, &self)?;
+        Ok(())
+    }
+}
@@ -1,9 +1,22 @@
+use std::fs::{self, File};
+use std::time::SystemTime;
+
 use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{ALLIUM_BATTERY_CALIBRATION, BATTERY_ESTIMATE_WINDOW, BATTERY_LOG_INTERVAL};
 
 pub trait Battery: Send {
     fn update(&mut self) -> Result<()>;
     fn percentage(&self) -> i32;
     fn charging(&self) -> bool;
+
+    /// Raw voltage reading, in device specific units, if the battery reports one.
+    fn voltage(&self) -> Option<i32> {
+        None
+    }
 }
 
 impl Battery for Box<dyn Battery> {
@@ -18,4 +31,340 @@ impl Battery for Box<dyn Battery> {
     fn charging(&self) -> bool {
         (**self).charging()
     }
+
+    fn voltage(&self) -> Option<i32> {
+        (**self).voltage()
+    }
+}
+
+/// Wraps a battery to report percentages from the user's calibration, if there is one. The
+/// calibration is reloaded when its file changes, e.g. when a calibration finishes.
+pub struct CalibratedBattery<B: Battery> {
+    battery: B,
+    calibration: BatteryCalibration,
+    modified: Option<SystemTime>,
+}
+
+impl<B: Battery> CalibratedBattery<B> {
+    pub fn new(battery: B) -> Self {
+        let mut this = Self {
+            battery,
+            calibration: BatteryCalibration::default(),
+            modified: None,
+        };
+        this.reload_calibration();
+        this
+    }
+
+    fn reload_calibration(&mut self) {
+        let modified = fs::metadata(ALLIUM_BATTERY_CALIBRATION.as_path())
+            .and_then(|m| m.modified())
+            .ok();
+        if modified.is_some() && modified == self.modified {
+            return;
+        }
+        self.modified = modified;
+        self.calibration = BatteryCalibration::load().unwrap_or_else(|e| {
+            warn!("failed to load battery calibration: {}", e);
+            BatteryCalibration::default()
+        });
+    }
+}
+
+impl<B: Battery> Battery for CalibratedBattery<B> {
+    fn update(&mut self) -> Result<()> {
+        self.reload_calibration();
+        self.battery.update()
+    }
+
+    fn percentage(&self) -> i32 {
+        if self.battery.charging() {
+            return self.battery.percentage();
+        }
+        self.battery
+            .voltage()
+            .and_then(|voltage| self.calibration.percentage(voltage))
+            .unwrap_or_else(|| self.battery.percentage())
+    }
+
+    fn charging(&self) -> bool {
+        self.battery.charging()
+    }
+
+    fn voltage(&self) -> Option<i32> {
+        self.battery.voltage()
+    }
+}
+
+/// A voltage reading at a known battery percentage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalibrationPoint {
+    pub voltage: i32,
+    pub percentage: i32,
+}
+
+/// A voltage reading taken during calibration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalibrationReading {
+    pub time: DateTime<Utc>,
+    pub voltage: i32,
+}
+
+/// Maps voltage readings to battery percentages.
+///
+/// The hardware's own percentage is nonlinear, so the user can calibrate it by discharging the
+/// battery from full until the device shuts down. The charge is known at two milestones: 100% when
+/// the charger reports a full battery, and the shutdown threshold. Assuming a steady drain, the
+/// time spent between them gives the voltage at every 5% milestone in between.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatteryCalibration {
+    /// Voltage at each milestone, sorted by voltage. Empty if the battery has not been
+    /// calibrated.
+    pub points: Vec<CalibrationPoint>,
+    /// Readings taken since the battery was last full, if a calibration is in progress.
+    pub recording: Option<Vec<CalibrationReading>>,
+    /// Whether the battery has been fully charged since the calibration started. Readings only
+    /// count from that milestone on.
+    #[serde(default)]
+    pub charged: bool,
+}
+
+impl BatteryCalibration {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn load() -> Result<Self> {
+        if ALLIUM_BATTERY_CALIBRATION.exists() {
+            debug!("found state, loading from file");
+            let file = File::open(ALLIUM_BATTERY_CALIBRATION.as_path())?;
+            if let Ok(json) = serde_json::from_reader(file) {
+                return Ok(json);
+            }
+            warn!("failed to read battery calibration file, removing");
+            fs::remove_file(ALLIUM_BATTERY_CALIBRATION.as_path())?;
+        }
+        Ok(Self::new())
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let file = File::create(ALLIUM_BATTERY_CALIBRATION.as_path())?;
+        serde_json::to_writer(file, &self)?;
+        Ok(())
+    }
+
+    pub fn is_calibrated(&self) -> bool {
+        self.points.len() >= 2
+    }
+
+    /// Starts a calibration. Readings are taken once the battery has been fully charged.
+    pub fn start(&mut self) {
+        self.recording = Some(Vec::new());
+        self.charged = false;
+    }
+
+    pub fn cancel(&mut self) {
+        self.recording = None;
+        self.charged = false;
+    }
+
+    /// Marks the battery as fully charged, discarding readings taken before.
+    pub fn mark_charged(&mut self) {
+        if let Some(recording) = self.recording.as_mut() {
+            recording.clear();
+            self.charged = true;
+        }
+    }
+
+    /// Records a reading while discharging, if the battery has been fully charged.
+    pub fn record(&mut self, time: DateTime<Utc>, voltage: i32) {
+        if let Some(recording) = self.recording.as_mut().filter(|_| self.charged) {
+            recording.push(CalibrationReading { time, voltage });
+        }
+    }
+
+    /// Ends the recording at the `final_percentage` milestone, and replaces the calibration with
+    /// one built from the readings. Time spent off or asleep is left out, as the battery barely
+    /// drains then.
+    pub fn finish(&mut self, final_percentage: i32) {
+        let Some(readings) = self.recording.take() else {
+            return;
+        };
+        if !std::mem::take(&mut self.charged) {
+            info!("battery was never fully charged, not calibrating");
+            return;
+        }
+
+        let max_gap = Duration::from_std(BATTERY_LOG_INTERVAL * 2).unwrap_or(Duration::MAX);
+        let mut elapsed = Duration::zero();
+        let mut times = Vec::with_capacity(readings.len());
+        for (i, reading) in readings.iter().enumerate() {
+            if i > 0 {
+                let gap = reading.time - readings[i - 1].time;
+                if gap <= max_gap {
+                    elapsed += gap;
+                }
+            }
+            times.push(elapsed);
+        }
+
+        let total = elapsed.num_seconds();
+        if total <= 0 {
+            info!("not enough battery readings to calibrate");
+            return;
+        }
+
+        // Average the readings around each 5% milestone, to smooth out noise
+        let mut steps: Vec<(i32, i64, i64)> = Vec::new();
+        for (reading, time) in readings.iter().zip(times) {
+            let percentage =
+                100 - ((100 - final_percentage) as i64 * time.num_seconds() / total) as i32;
+            let step = (percentage + 2) / 5 * 5;
+            match steps.iter_mut().find(|(s, _, _)| *s == step) {
+                Some((_, sum, count)) => {
+                    *sum += reading.voltage as i64;
+                    *count += 1;
+                }
+                None => steps.push((step, reading.voltage as i64, 1)),
+            }
+        }
+
+        // Voltage drops as the battery drains, so sorting both keeps the mapping monotonic
+        let mut voltages: Vec<i32> = steps
+            .iter()
+            .map(|(_, sum, count)| (sum / count) as i32)
+            .collect();
+        let mut percentages: Vec<i32> = steps.iter().map(|(step, _, _)| *step).collect();
+        voltages.sort_unstable();
+        percentages.sort_unstable();
+
+        self.points = voltages
+            .into_iter()
+            .zip(percentages)
+            .map(|(voltage, percentage)| CalibrationPoint {
+                voltage,
+                percentage,
+            })
+            .collect();
+        info!("calibrated battery: {:?}", self.points);
+    }
+
+    /// Interpolates the percentage for a voltage reading, if the battery has been calibrated.
+    pub fn percentage(&self, voltage: i32) -> Option<i32> {
+        if !self.is_calibrated() {
+            return None;
+        }
+
+        let i = self.points.partition_point(|p| p.voltage < voltage);
+        if i == 0 {
+            return Some(self.points[0].percentage);
+        }
+        if i == self.points.len() {
+            return Some(self.points[i - 1].percentage);
+        }
+
+        let (lo, hi) = (self.points[i - 1], self.points[i]);
+        if hi.voltage == lo.voltage {
+            return Some(hi.percentage);
+        }
+        Some(
+            lo.percentage
+                + (hi.percentage - lo.percentage) * (voltage - lo.voltage)
+                    / (hi.voltage - lo.voltage),
+        )
+    }
+}
+
+/// A battery level recorded by alliumd.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatterySample {
+    pub time: DateTime<Utc>,
+    pub percentage: i32,
+    pub charging: bool,
+}
+
+/// Estimates how long the battery will last, from the drain rate over the most recent samples
+/// since the battery was last charging. `samples` must be sorted by time.
+pub fn estimate_time_remaining(samples: &[BatterySample]) -> Option<Duration> {
+    let last = samples.last().filter(|s| !s.charging)?;
+    let first = samples
+        .iter()
+        .rev()
+        .take_while(|s| !s.charging && last.time - s.time <= BATTERY_ESTIMATE_WINDOW)
+        .last()?;
+
+    let drained = first.percentage - last.percentage;
+    let elapsed = (last.time - first.time).num_seconds();
+    if drained <= 0 || elapsed <= 0 {
+        return None;
+    }
+
+    Some(Duration::seconds(
+        last.percentage as i64 * elapsed / drained as i64,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calibration() {
+        let mut calibration = BatteryCalibration::new();
+        assert_eq!(calibration.percentage(500), None);
+
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        calibration.start();
+        // Readings before the battery is full don't count
+        calibration.record(start - Duration::minutes(30), 400);
+        calibration.mark_charged();
+        for (minutes, voltage) in [(0, 600), (5, 560), (10, 540), (15, 530), (20, 500)] {
+            calibration.record(start + Duration::minutes(minutes), voltage);
+        }
+        calibration.finish(0);
+
+        assert!(calibration.recording.is_none());
+        assert_eq!(
+            calibration.points,
+            vec![
+                CalibrationPoint {
+                    voltage: 500,
+                    percentage: 0
+                },
+                CalibrationPoint {
+                    voltage: 530,
+                    percentage: 25
+                },
+                CalibrationPoint {
+                    voltage: 540,
+                    percentage: 50
+                },
+                CalibrationPoint {
+                    voltage: 560,
+                    percentage: 75
+                },
+                CalibrationPoint {
+                    voltage: 600,
+                    percentage: 100
+                },
+            ]
+        );
+        assert_eq!(calibration.percentage(450), Some(0));
+        assert_eq!(calibration.percentage(535), Some(37));
+        assert_eq!(calibration.percentage(580), Some(87));
+        assert_eq!(calibration.percentage(700), Some(100));
+    }
+
+    #[test]
+    fn test_calibration_needs_full_charge() {
+        let mut calibration = BatteryCalibration::new();
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+
+        calibration.start();
+        calibration.record(start, 600);
+        calibration.record(start + Duration::minutes(5), 500);
+        calibration.finish(0);
+        assert!(calibration.recording.is_none());
+        assert!(!calibration.is_calibrated());
+    }
 }
@@ -0,0 +1,157 @@
+#[cfg(feature = "miyoo")]
+use std::time::Duration;
+
+use anyhow::Result;
+#[cfg(feature = "miyoo")]
+use anyhow::bail;
+#[cfg(feature = "miyoo")]
+use log::debug;
+#[cfg(feature = "miyoo")]
+use tokio::process::Command;
+
+/// How long a scan looks for devices that are in pairing mode.
+pub const SCAN_DURATION_SECS: u64 = 10;
+
+/// Adapter that BlueZ pairs through, e.g. a USB dongle.
+#[cfg(feature = "miyoo")]
+const ADAPTER: &str = "/sys/class/bluetooth/hci0";
+
+/// Whether there is a Bluetooth adapter and BlueZ's `bluetoothctl` is installed. No supported
+/// device has both out of the box, so this is only true once they have been added.
+#[cfg(feature = "miyoo")]
+pub fn is_available() -> bool {
+    std::path::Path::new(ADAPTER).exists()
+        && std::env::var_os("PATH").is_some_and(|paths| {
+            std::env::split_paths(&paths).any(|dir| dir.join("bluetoothctl").is_file())
+        })
+}
+
+/// A Bluetooth device that was found by a scan or has been paired before.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BluetoothDevice {
+    pub address: String,
+    pub name: String,
+    pub paired: bool,
+}
+
+/// Devices that are known to BlueZ, paired ones first.
+pub async fn devices() -> Result<Vec<BluetoothDevice>> {
+    #[cfg(feature = "miyoo")]
+    {
+        // BlueZ before 5.65 only has `paired-devices`
+        let paired = match bluetoothctl(&["devices", "Paired"]).await {
+            Ok(output) => output,
+            Err(_) => bluetoothctl(&["paired-devices"]).await?,
+        };
+        let paired = parse_devices(&paired);
+        let mut devices: Vec<_> = parse_devices(&bluetoothctl(&["devices"]).await?)
+            .into_iter()
+            .map(|mut device| {
+                device.paired = paired.iter().any(|p| p.address == device.address);
+                device
+            })
+            .collect();
+        devices.sort_by_key(|device| !device.paired);
+        return Ok(devices);
+    }
+
+    #[cfg(not(feature = "miyoo"))]
+    Ok(Vec::new())
+}
+
+/// Looks for devices that are in pairing mode, then returns all known devices.
+pub async fn scan() -> Result<Vec<BluetoothDevice>> {
+    #[cfg(feature = "miyoo")]
+    {
+        bluetoothctl(&["power", "on"]).await?;
+        let timeout = SCAN_DURATION_SECS.to_string();
+        // `scan on` keeps running until the timeout, so it is given a little longer to exit
+        tokio::time::timeout(
+            Duration::from_secs(SCAN_DURATION_SECS + 5),
+            bluetoothctl(&["--timeout", &timeout, "scan", "on"]),
+        )
+        .await??;
+    }
+    devices().await
+}
+
+/// Pairs with the device, then trusts it so that it reconnects by itself, and connects to it.
+pub async fn pair(address: &str) -> Result<()> {
+    #[cfg(feature = "miyoo")]
+    {
+        bluetoothctl(&["pair", address]).await?;
+        bluetoothctl(&["trust", address]).await?;
+        bluetoothctl(&["connect", address]).await?;
+    }
+    #[cfg(not(feature = "miyoo"))]
+    let _ = address;
+    Ok(())
+}
+
+/// Removes the device, so that it has to be paired again.
+pub async fn forget(address: &str) -> Result<()> {
+    #[cfg(feature = "miyoo")]
+    bluetoothctl(&["remove", address]).await?;
+    #[cfg(not(feature = "miyoo"))]
+    let _ = address;
+    Ok(())
+}
+
+#[cfg(feature = "miyoo")]
+async fn bluetoothctl(args: &[&str]) -> Result<String> {
+    debug!("bluetoothctl {}", args.join(" "));
+    let output = Command::new("bluetoothctl").args(args).output().await?;
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    if !output.status.success() {
+        bail!("bluetoothctl {} failed: {}", args.join(" "), stdout.trim());
+    }
+    Ok(stdout)
+}
+
+/// Parses the `Device <address> <name>` lines that `bluetoothctl devices` prints.
+#[cfg_attr(not(feature = "miyoo"), allow(dead_code))]
+fn parse_devices(output: &str) -> Vec<BluetoothDevice> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let rest = line.trim().strip_prefix("Device ")?;
+            let (address, name) = rest.split_once(' ').unwrap_or((rest, ""));
+            if address.len() != 17 || address.split(':').count() != 6 {
+                return None;
+            }
+            let name = name.trim();
+            Some(BluetoothDevice {
+                address: address.to_string(),
+                name: if name.is_empty() { address } else { name }.to_string(),
+                paired: false,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_devices() {
+        let output = "Device E4:17:D8:01:02:03 8BitDo Lite 2\n\
+                      [CHG] Controller 00:1A:7D:DA:71:13 Discovering: yes\n\
+                      Device 98:B6:E9:AA:BB:CC\n";
+        assert_eq!(
+            parse_devices(output),
+            vec![
+                BluetoothDevice {
+                    address: "E4:17:D8:01:02:03".to_string(),
+                    name: "8BitDo Lite 2".to_string(),
+                    paired: false,
+                },
+                BluetoothDevice {
+                    address: "98:B6:E9:AA:BB:CC".to_string(),
+                    name: "98:B6:E9:AA:BB:CC".to_string(),
+                    paired: false,
+                },
+            ]
+        );
+    }
+}
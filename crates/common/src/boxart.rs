@@ -0,0 +1,53 @@
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::constants::ALLIUM_BOXART_URL;
+use crate::theme_store::download;
+
+/// Downloads the box art of a game from the libretro thumbnail server. `system` is the libretro
+/// system name, e.g. "Nintendo - Game Boy Advance", and `name` is the No-Intro game name.
+pub async fn download_boxart(system: &str, name: &str, path: &Path) -> Result<()> {
+    download(&boxart_url(system, name), path).await
+}
+
+fn boxart_url(system: &str, name: &str) -> String {
+    // libretro replaces these characters in thumbnail file names
+    let name = name.replace(
+        ['&', '*', '/', ':', '`', '<', '>', '?', '\\', '|', '"'],
+        "_",
+    );
+    format!(
+        "{}/{}/Named_Boxarts/{}.png",
+        ALLIUM_BOXART_URL,
+        encode(system),
+        encode(&name)
+    )
+}
+
+/// Percent-encodes a URL path segment.
+fn encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(b as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boxart_url() {
+        assert_eq!(
+            boxart_url("Nintendo - Game Boy", "Tetris & Dr. Mario (USA)"),
+            "https://thumbnails.libretro.com/Nintendo%20-%20Game%20Boy/Named_Boxarts/Tetris%20_%20Dr.%20Mario%20%28USA%29.png"
+        );
+    }
+}
@@ -0,0 +1,314 @@
+//! Button combinations that trigger actions from anywhere in the launcher and in-game menu. They
+//! are detected on the physical keys, before any UI key remapping.
+
+use std::fs::{self, File};
+
+use anyhow::Result;
+use enum_map::EnumMap;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use strum::{EnumCount, FromRepr};
+
+use crate::constants::ALLIUM_COMBO_SETTINGS;
+use crate::platform::{Key, KeyEvent};
+use crate::retroarch_hotkeys::ALLIUM_MENU_COMBOS;
+
+/// Keys that are held to start a combo.
+pub const COMBO_MODIFIERS: [Key; 3] = [Key::Menu, Key::Select, Key::Start];
+
+/// Keys that complete a combo.
+const COMBO_KEYS: [Key; 10] = [
+    Key::A,
+    Key::B,
+    Key::X,
+    Key::Y,
+    Key::L,
+    Key::R,
+    Key::L2,
+    Key::R2,
+    Key::Select,
+    Key::Start,
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, FromRepr, EnumCount)]
+pub enum ComboAction {
+    Screenshot,
+    /// Opens settings: the Settings tab in the launcher, or RetroArch's menu in game.
+    QuickSettings,
+    /// Quits the game without saving.
+    ForceQuit,
+}
+
+/// A modifier held while another key is pressed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Combo {
+    pub modifier: Key,
+    pub key: Key,
+}
+
+impl Combo {
+    pub const fn new(modifier: Key, key: Key) -> Self {
+        Self { modifier, key }
+    }
+
+    /// Every combo that can be bound to an action.
+    pub fn choices() -> Vec<Combo> {
+        COMBO_MODIFIERS
+            .iter()
+            .flat_map(|modifier| {
+                COMBO_KEYS
+                    .iter()
+                    .filter(move |key| *key != modifier)
+                    .map(move |key| Combo::new(*modifier, *key))
+            })
+            .collect()
+    }
+}
+
+/// Why a combo doesn't work as expected.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Conflict {
+    /// alliumd handles the combo as a global hotkey too.
+    Allium,
+    /// Another action is bound to the same combo.
+    Combo(ComboAction),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComboSettings {
+    #[serde(default = "ComboSettings::default_screenshot")]
+    screenshot: Option<Combo>,
+    #[serde(default = "ComboSettings::default_quick_settings")]
+    quick_settings: Option<Combo>,
+    #[serde(default = "ComboSettings::default_force_quit")]
+    force_quit: Option<Combo>,
+}
+
+impl Default for ComboSettings {
+    fn default() -> Self {
+        Self {
+            screenshot: Self::default_screenshot(),
+            quick_settings: Self::default_quick_settings(),
+            force_quit: Self::default_force_quit(),
+        }
+    }
+}
+
+impl ComboSettings {
+    fn default_screenshot() -> Option<Combo> {
+        Some(Combo::new(Key::Menu, Key::X))
+    }
+
+    // Menu + Select is taken by alliumd, which cycles the power profile
+    fn default_quick_settings() -> Option<Combo> {
+        Some(Combo::new(Key::Menu, Key::Y))
+    }
+
+    fn default_force_quit() -> Option<Combo> {
+        Some(Combo::new(Key::Select, Key::Start))
+    }
+
+    pub fn load() -> Result<Self> {
+        if ALLIUM_COMBO_SETTINGS.exists() {
+            debug!("found state, loading from file");
+            let file = File::open(ALLIUM_COMBO_SETTINGS.as_path())?;
+            if let Ok(json) = serde_json::from_reader(file) {
+                return Ok(json);
+            }
+            warn!("failed to read combo settings file, removing");
+            fs::remove_file(ALLIUM_COMBO_SETTINGS.as_path())?;
+        }
+        Ok(Self::default())
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let file = File::create(ALLIUM_COMBO_SETTINGS.as_path())?;
+        serde_json::to_writer(file, &self)?;
+        Ok(())
+    }
+
+    pub fn get(&self, action: ComboAction) -> Option<Combo> {
+        match action {
+            ComboAction::Screenshot => self.screenshot,
+            ComboAction::QuickSettings => self.quick_settings,
+            ComboAction::ForceQuit => self.force_quit,
+        }
+    }
+
+    pub fn set(&mut self, action: ComboAction, combo: Option<Combo>) {
+        match action {
+            ComboAction::Screenshot => self.screenshot = combo,
+            ComboAction::QuickSettings => self.quick_settings = combo,
+            ComboAction::ForceQuit => self.force_quit = combo,
+        }
+    }
+
+    pub fn conflict(&self, action: ComboAction) -> Option<Conflict> {
+        let combo = self.get(action)?;
+        if combo.modifier == Key::Menu && ALLIUM_MENU_COMBOS.contains(&combo.key) {
+            return Some(Conflict::Allium);
+        }
+        (0..ComboAction::COUNT)
+            .map(|i| ComboAction::from_repr(i).unwrap())
+            .find(|other| *other != action && self.get(*other) == Some(combo))
+            .map(Conflict::Combo)
+    }
+}
+
+/// What a key event turned out to be, once combos are taken into account.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Detection {
+    /// Not part of a combo, so it is handled as usual.
+    Key(KeyEvent),
+    /// The key event completed a combo.
+    Combo(ComboAction),
+    /// A later event of the key that completed a combo, e.g. its release.
+    Consumed,
+}
+
+#[derive(Debug)]
+pub struct ComboDetector {
+    settings: ComboSettings,
+    keys: EnumMap<Key, bool>,
+    /// Key that completed the last combo, whose events are consumed until it is released.
+    consumed: Option<Key>,
+    /// Actions that do nothing in the app, whose combos are left as ordinary key events.
+    ignored: Vec<ComboAction>,
+}
+
+impl Default for ComboDetector {
+    fn default() -> Self {
+        Self::new(ComboSettings::load().unwrap_or_else(|e| {
+            warn!("failed to load combo settings: {}", e);
+            ComboSettings::default()
+        }))
+    }
+}
+
+impl ComboDetector {
+    pub fn new(settings: ComboSettings) -> Self {
+        Self {
+            settings,
+            keys: EnumMap::default(),
+            consumed: None,
+            ignored: Vec::new(),
+        }
+    }
+
+    /// Stops detecting the combo of an action that does nothing in the app.
+    pub fn without(mut self, action: ComboAction) -> Self {
+        self.ignored.push(action);
+        self
+    }
+
+    pub fn set_settings(&mut self, settings: ComboSettings) {
+        self.settings = settings;
+    }
+
+    pub fn detect(&mut self, event: KeyEvent) -> Detection {
+        match event {
+            KeyEvent::Pressed(key) => {
+                self.keys[key] = true;
+                let action = (0..ComboAction::COUNT)
+                    .map(|i| ComboAction::from_repr(i).unwrap())
+                    .filter(|action| !self.ignored.contains(action))
+                    .find(|action| {
+                        self.settings
+                            .get(*action)
+                            .is_some_and(|combo| combo.key == key && self.keys[combo.modifier])
+                    });
+                if let Some(action) = action {
+                    self.consumed = Some(key);
+                    return Detection::Combo(action);
+                }
+            }
+            KeyEvent::Released(key) => {
+                self.keys[key] = false;
+                if self.consumed == Some(key) {
+                    self.consumed = None;
+                    return Detection::Consumed;
+                }
+            }
+            KeyEvent::Autorepeat(key) | KeyEvent::LongPressed(key) => {
+                if self.consumed == Some(key) {
+                    return Detection::Consumed;
+                }
+            }
+        }
+        Detection::Key(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combo_detector() {
+        let mut detector = ComboDetector::new(ComboSettings::default());
+        let pressed = |key| KeyEvent::Pressed(key);
+
+        assert_eq!(
+            detector.detect(pressed(Key::Start)),
+            Detection::Key(pressed(Key::Start))
+        );
+        assert_eq!(
+            detector.detect(KeyEvent::Released(Key::Start)),
+            Detection::Key(KeyEvent::Released(Key::Start))
+        );
+
+        detector.detect(pressed(Key::Select));
+        assert_eq!(
+            detector.detect(pressed(Key::Start)),
+            Detection::Combo(ComboAction::ForceQuit)
+        );
+        assert_eq!(
+            detector.detect(KeyEvent::LongPressed(Key::Start)),
+            Detection::Consumed
+        );
+        assert_eq!(
+            detector.detect(KeyEvent::Released(Key::Start)),
+            Detection::Consumed
+        );
+    }
+
+    #[test]
+    fn test_combo_detector_without() {
+        let mut detector =
+            ComboDetector::new(ComboSettings::default()).without(ComboAction::ForceQuit);
+
+        detector.detect(KeyEvent::Pressed(Key::Select));
+        assert_eq!(
+            detector.detect(KeyEvent::Pressed(Key::Start)),
+            Detection::Key(KeyEvent::Pressed(Key::Start))
+        );
+        assert_eq!(
+            detector.detect(KeyEvent::Released(Key::Start)),
+            Detection::Key(KeyEvent::Released(Key::Start))
+        );
+    }
+
+    #[test]
+    fn test_combo_conflict() {
+        let mut settings = ComboSettings::default();
+        assert_eq!(settings.conflict(ComboAction::Screenshot), None);
+
+        settings.set(
+            ComboAction::QuickSettings,
+            Some(Combo::new(Key::Menu, Key::Select)),
+        );
+        assert_eq!(
+            settings.conflict(ComboAction::QuickSettings),
+            Some(Conflict::Allium)
+        );
+
+        settings.set(
+            ComboAction::ForceQuit,
+            settings.get(ComboAction::Screenshot),
+        );
+        assert_eq!(
+            settings.conflict(ComboAction::Screenshot),
+            Some(Conflict::Combo(ComboAction::ForceQuit))
+        );
+    }
+}
@@ -2,8 +2,14 @@ use std::time::Duration;
 
 use image::{ImageBuffer, Rgba};
 
+use crate::audio::AudioSettings;
+use crate::combo::ComboSettings;
+use crate::console_settings::ConsoleSettings;
 use crate::display::color::Color;
 use crate::locale::LocaleSettings;
+use crate::performance::CpuProfile;
+use crate::rumble::RumbleSettings;
+use crate::ui_keymap::UiKeymap;
 use crate::{display::settings::DisplaySettings, stylesheet::Stylesheet};
 
 #[derive(Debug)]
@@ -12,9 +18,18 @@ pub enum Command {
     Exec(std::process::Command),
     SaveStylesheet(Box<Stylesheet>),
     SaveDisplaySettings(Box<DisplaySettings>),
+    SaveAudioSettings(Box<AudioSettings>),
     SaveLocaleSettings(LocaleSettings),
+    SaveConsoleSettings(ConsoleSettings),
+    SaveUiKeymap(UiKeymap),
+    SaveComboSettings(ComboSettings),
+    SaveRumbleSettings(RumbleSettings),
+    /// The `Locale` resource was replaced, and views should reload their strings.
+    LocaleChanged,
     CloseView,
     ValueChanged(usize, Value),
+    /// The value being edited changed, but has not been committed yet.
+    ValuePreview(usize, Value),
     TrapFocus,
     Unfocus,
     Redraw,
@@ -24,11 +39,21 @@ pub enum Command {
     ImageToast(ImageBuffer<Rgba<u8>, Vec<u8>>, String, Option<Duration>),
     DismissToast,
     PopulateDb,
+    /// Checks every game against the DAT files, and reports bad dumps.
+    VerifyRoms,
+    /// Launches a random game, only picking from favorites if set.
+    LaunchRandomGame {
+        favorites_only: bool,
+    },
     SaveStateScreenshot {
         path: String,
         core: String,
         slot: i8,
     },
+    TakeScreenshot,
+    SetCpuProfile(CpuProfile),
+    /// Commands that are handled one after another, with nothing else in between.
+    Batch(Vec<Command>),
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,102 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+
+use anyhow::Result;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::ALLIUM_CONSOLE_SETTINGS;
+
+/// How consoles are presented in the Games tab. Consoles are identified by the name of their
+/// directory in the games directory, independently of the matching rules in consoles.toml.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsoleSettings {
+    /// Console directories in display order. Directories that aren't listed come after, sorted
+    /// by name.
+    #[serde(default)]
+    pub order: Vec<String>,
+    /// Custom display names, keyed by directory.
+    #[serde(default)]
+    pub names: HashMap<String, String>,
+    /// Console directories hidden from the Games tab.
+    #[serde(default)]
+    pub hidden: HashSet<String>,
+    /// Hide console directories that don't contain any games.
+    #[serde(default = "ConsoleSettings::default_hide_empty")]
+    pub hide_empty: bool,
+}
+
+impl Default for ConsoleSettings {
+    fn default() -> Self {
+        Self {
+            order: Vec::new(),
+            names: HashMap::new(),
+            hidden: HashSet::new(),
+            hide_empty: Self::default_hide_empty(),
+        }
+    }
+}
+
+impl ConsoleSettings {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn load() -> Result<Self> {
+        if ALLIUM_CONSOLE_SETTINGS.exists() {
+            debug!("found state, loading from file");
+            let file = File::open(ALLIUM_CONSOLE_SETTINGS.as_path())?;
+            if let Ok(json) = serde_json::from_reader(file) {
+                return Ok(json);
+            }
+            warn!("failed to read console settings file, removing");
+            fs::remove_file(ALLIUM_CONSOLE_SETTINGS.as_path())?;
+        }
+        Ok(Self::new())
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let file = File::create(ALLIUM_CONSOLE_SETTINGS.as_path())?;
+        serde_json::to_writer(file, &self)?;
+        Ok(())
+    }
+
+    /// Custom display name of a console directory.
+    pub fn name(&self, dir: &str) -> Option<&str> {
+        self.names.get(dir).map(String::as_str)
+    }
+
+    /// Sets the display name of a console directory. An empty name restores the default.
+    pub fn set_name(&mut self, dir: &str, name: String) {
+        if name.is_empty() {
+            self.names.remove(dir);
+        } else {
+            self.names.insert(dir.to_owned(), name);
+        }
+    }
+
+    pub fn is_hidden(&self, dir: &str) -> bool {
+        self.hidden.contains(dir)
+    }
+
+    pub fn set_hidden(&mut self, dir: &str, hidden: bool) {
+        if hidden {
+            self.hidden.insert(dir.to_owned());
+        } else {
+            self.hidden.remove(dir);
+        }
+    }
+
+    /// Sort key of a console directory. Unlisted directories sort last.
+    pub fn position(&self, dir: &str) -> usize {
+        self.order
+            .iter()
+            .position(|d| d == dir)
+            .unwrap_or(usize::MAX)
+    }
+
+    #[inline]
+    fn default_hide_empty() -> bool {
+        true
+    }
+}
@@ -9,6 +9,17 @@ use crate::geom::Size;
 
 pub const ALLIUM_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Community theme index, listing theme packs that can be downloaded. Can be changed in
+/// `state/theme_store.json`.
+pub const ALLIUM_THEME_INDEX_URL: &str =
+    "https://raw.githubusercontent.com/goweiwen/Allium/main/themes/index.json";
+
+/// libretro thumbnail server, hosting box art by system and No-Intro game name.
+pub const ALLIUM_BOXART_URL: &str = "https://thumbnails.libretro.com";
+
+/// TCP port of the remote control API, when enabled in the Wi-Fi settings.
+pub const ALLIUM_REMOTE_CONTROL_PORT: u16 = 8765;
+
 lazy_static! {
     pub static ref ALLIUM_SD_ROOT: PathBuf = PathBuf::from(
         &env::var("ALLIUM_SD_ROOT").unwrap_or_else(|_| "/mnt/SDCARD/".to_string())
@@ -19,6 +30,23 @@ lazy_static! {
     pub static ref ALLIUM_GAMES_DIR: PathBuf = PathBuf::from(
         &env::var("ALLIUM_GAMES_DIR").map_or_else(|_| ALLIUM_SD_ROOT.join("Roms"), PathBuf::from)
     );
+    /// Mount point of the network share configured in the Wi-Fi settings.
+    pub static ref ALLIUM_NETWORK_SHARE_ROOT: PathBuf = PathBuf::from(
+        &env::var("ALLIUM_NETWORK_SHARE_ROOT").unwrap_or_else(|_| "/mnt/share".to_string())
+    );
+    /// Secondary storage that can hold games in its own Roms directory, e.g. a USB drive or a
+    /// second partition. Overridden by a colon-separated list of paths.
+    pub static ref ALLIUM_SECONDARY_ROOTS: Vec<PathBuf> = env::var_os("ALLIUM_SECONDARY_ROOTS")
+        .map_or_else(
+            || {
+                vec![
+                    PathBuf::from("/mnt/usb"),
+                    PathBuf::from("/mnt/SDCARD2"),
+                    ALLIUM_NETWORK_SHARE_ROOT.clone(),
+                ]
+            },
+            |roots| env::split_paths(&roots).collect(),
+        );
     pub static ref ALLIUM_APPS_DIR: PathBuf = PathBuf::from(
         &env::var("ALLIUM_APPS_DIR").map_or_else(|_| ALLIUM_SD_ROOT.join("Apps"), PathBuf::from)
     );
@@ -26,15 +54,37 @@ lazy_static! {
     // Folders
     pub static ref ALLIUM_SCRIPTS_DIR: PathBuf = ALLIUM_BASE_DIR.join("scripts");
     pub static ref ALLIUM_TOOLS_DIR: PathBuf = ALLIUM_BASE_DIR.join("tools");
+    pub static ref ALLIUM_LOGS_DIR: PathBuf = ALLIUM_BASE_DIR.join("logs");
     pub static ref ALLIUM_FONTS_DIR: PathBuf = ALLIUM_BASE_DIR.join("fonts");
     pub static ref ALLIUM_LOCALES_DIR: PathBuf = ALLIUM_BASE_DIR.join("locales");
     pub static ref ALLIUM_IMAGES_DIR: PathBuf = ALLIUM_BASE_DIR.join("images");
+    pub static ref ALLIUM_SOUNDS_DIR: PathBuf = ALLIUM_BASE_DIR.join("sounds");
     pub static ref ALLIUM_SCREENSHOTS_DIR: PathBuf = ALLIUM_SD_ROOT.join("Saves/CurrentProfile/screenshots");
+    pub static ref ALLIUM_EXPORTS_DIR: PathBuf = ALLIUM_SD_ROOT.join("Saves/Exports");
+    pub static ref ALLIUM_SPLASH_DIR: PathBuf = ALLIUM_SD_ROOT.join("Splash");
+    pub static ref ALLIUM_THEMES_DIR: PathBuf = ALLIUM_SD_ROOT.join("Themes");
+    pub static ref ALLIUM_BACKGROUNDS_DIR: PathBuf = ALLIUM_SD_ROOT.join("Backgrounds");
+    pub static ref ALLIUM_COMICS_DIR: PathBuf = ALLIUM_SD_ROOT.join("Comics");
+    pub static ref ALLIUM_DATS_DIR: PathBuf = ALLIUM_SD_ROOT.join("Dats");
+    pub static ref ALLIUM_ROM_VERIFICATION_REPORT: PathBuf = ALLIUM_SD_ROOT.join("Dats/verification-report.txt");
+    pub static ref ALLIUM_THEME_PACKS_DIR: PathBuf = ALLIUM_BASE_DIR.join("themes");
+    pub static ref ALLIUM_THEME_PREVIEWS_DIR: PathBuf = ALLIUM_BASE_DIR.join("cache/theme-previews");
+    pub static ref ALLIUM_GUIDE_PAGE_CACHE: PathBuf = ALLIUM_BASE_DIR.join("cache/guide-page.png");
+    pub static ref ALLIUM_ARCHIVE_EXTRACT_DIR: PathBuf = ALLIUM_BASE_DIR.join("cache/extracted");
+    pub static ref ALLIUM_NETWORK_SHARE_CACHE_DIR: PathBuf = ALLIUM_BASE_DIR.join("cache/network-share");
+    /// SMB credentials for mounting the network share, kept off the SD card and out of `ps`.
+    pub static ref ALLIUM_NETWORK_SHARE_CREDENTIALS: PathBuf = PathBuf::from("/tmp/allium-share-credentials");
+    pub static ref ALLIUM_READ_SPEED_TEST_FILE: PathBuf = ALLIUM_BASE_DIR.join("cache/read-speed-test");
+    pub static ref ALLIUM_SAVE_BACKUPS_DIR: PathBuf = ALLIUM_SD_ROOT.join("Saves/CurrentProfile/backups");
+    pub static ref ALLIUM_SETTINGS_BACKUP: PathBuf = ALLIUM_SD_ROOT.join("allium-settings.zip");
 
     // Config
     pub static ref ALLIUM_CONFIG_CONSOLES: PathBuf = ALLIUM_BASE_DIR.join("config/consoles.toml");
     pub static ref ALLIUM_CONFIG_CORES: PathBuf = ALLIUM_BASE_DIR.join("config/cores.toml");
+}
 
+// A second block, to stay under the macro recursion limit
+lazy_static! {
     // State
     pub static ref ALLIUMD_STATE: PathBuf = ALLIUM_BASE_DIR.join("state/alliumd.json");
     pub static ref ALLIUM_LAUNCHER_STATE: PathBuf =
@@ -42,13 +92,43 @@ lazy_static! {
     pub static ref ALLIUM_MENU_STATE: PathBuf =
         ALLIUM_BASE_DIR.join("state/allium-menu.json");
     pub static ref ALLIUM_GAME_INFO: PathBuf = ALLIUM_BASE_DIR.join("state/current_game");
+    /// Toasts shown by the launcher.
+    pub static ref ALLIUM_NOTIFICATIONS: PathBuf = ALLIUM_BASE_DIR.join("state/notifications.json");
+    /// Marker for the play session in progress, used to recover play time after a crash.
+    pub static ref ALLIUM_SESSION_JOURNAL: PathBuf = ALLIUM_BASE_DIR.join("state/session_journal.json");
+    /// Game to launch when the launcher next starts, written by the remote control API.
+    pub static ref ALLIUM_LAUNCH_REQUEST: PathBuf = ALLIUM_BASE_DIR.join("state/launch_request");
+    /// Marker for a background job that finished since the last key press, shown on the LED.
+    pub static ref ALLIUM_JOB_FINISHED: PathBuf = ALLIUM_BASE_DIR.join("state/job_finished");
     pub static ref ALLIUM_STYLESHEET: PathBuf = ALLIUM_BASE_DIR.join("state/stylesheet.json");
     pub static ref ALLIUM_DISPLAY_SETTINGS: PathBuf = ALLIUM_BASE_DIR.join("state/display.json");
+    pub static ref ALLIUM_LOG_SETTINGS: PathBuf = ALLIUM_BASE_DIR.join("state/log.json");
     pub static ref ALLIUM_LOCALE_SETTINGS: PathBuf = ALLIUM_BASE_DIR.join("state/locale.json");
     pub static ref ALLIUM_POWER_SETTINGS: PathBuf = ALLIUM_BASE_DIR.join("state/power.json");
+    pub static ref ALLIUM_PERFORMANCE_SETTINGS: PathBuf = ALLIUM_BASE_DIR.join("state/performance.json");
+    pub static ref ALLIUM_AUDIO_SETTINGS: PathBuf = ALLIUM_BASE_DIR.join("state/audio.json");
+    pub static ref ALLIUM_SPLASH_SETTINGS: PathBuf = ALLIUM_BASE_DIR.join("state/splash.json");
+    pub static ref ALLIUM_SPLASH: PathBuf = ALLIUM_BASE_DIR.join("state/splash.rgba");
+    pub static ref ALLIUM_BATTERY_CALIBRATION: PathBuf = ALLIUM_BASE_DIR.join("state/battery_calibration.json");
+    pub static ref ALLIUM_SAVE_BACKUP_SETTINGS: PathBuf = ALLIUM_BASE_DIR.join("state/save_backup.json");
     pub static ref ALLIUM_WIFI_SETTINGS: PathBuf = ALLIUM_BASE_DIR.join("state/wifi.json");
+    /// PIN asked for by the web UI and the remote control API.
+    pub static ref ALLIUM_ACCESS_PIN: PathBuf = ALLIUM_BASE_DIR.join("state/access_pin");
+    pub static ref ALLIUM_CONSOLE_SETTINGS: PathBuf = ALLIUM_BASE_DIR.join("state/consoles.json");
+    pub static ref ALLIUM_UI_KEYMAP: PathBuf = ALLIUM_BASE_DIR.join("state/ui_keymap.json");
+    pub static ref ALLIUM_COMBO_SETTINGS: PathBuf = ALLIUM_BASE_DIR.join("state/combos.json");
+    pub static ref ALLIUM_RUMBLE_SETTINGS: PathBuf = ALLIUM_BASE_DIR.join("state/rumble.json");
+    pub static ref ALLIUM_THEME_STORE_SETTINGS: PathBuf =
+        ALLIUM_BASE_DIR.join("state/theme_store.json");
+    pub static ref ALLIUM_LED_SETTINGS: PathBuf = ALLIUM_BASE_DIR.join("state/led.json");
     pub static ref ALLIUM_TIMEZONE: PathBuf = ALLIUM_BASE_DIR.join("state/timezone");
 
+    // Onion OS, imported once when switching to Allium
+    pub static ref ONION_RECENTS: PathBuf = ALLIUM_SD_ROOT.join("Roms/recentlist.json");
+    pub static ref ONION_FAVORITES: PathBuf = ALLIUM_SD_ROOT.join("Roms/favourite.json");
+    pub static ref ONION_PLAY_ACTIVITY: PathBuf =
+        ALLIUM_SD_ROOT.join("Saves/CurrentProfile/play_activity/play_activity_db.sqlite");
+
     // Database
     pub static ref ALLIUM_DATABASE: PathBuf = env::var("ALLIUM_DATABASE")
         .map(PathBuf::from)
@@ -58,6 +138,23 @@ lazy_static! {
     pub static ref ALLIUM_LAUNCHER: PathBuf = ALLIUM_BASE_DIR.join("bin/allium-launcher");
     pub static ref ALLIUM_MENU: PathBuf = ALLIUM_BASE_DIR.join("bin/allium-menu");
     pub static ref ALLIUM_RETROARCH: PathBuf = ALLIUM_BASE_DIR.join("cores/retroarch/launch.sh");
+
+    // RetroArch
+    pub static ref RETROARCH_HOME: PathBuf = ALLIUM_SD_ROOT.join("RetroArch/.retroarch");
+    pub static ref RETROARCH_CONFIG: PathBuf = RETROARCH_HOME.join("retroarch.cfg");
+    /// Where RetroArch reads overrides from. Allium's RetroArch sets `rgui_config_directory` to the
+    /// profile's folder, rather than RetroArch's default of `.retroarch/config`.
+    pub static ref RETROARCH_CONFIG_DIR: PathBuf = ALLIUM_SD_ROOT.join("Saves/CurrentProfile/config");
+    pub static ref RETROARCH_CORES_DIR: PathBuf = RETROARCH_HOME.join("cores");
+    pub static ref RETROARCH_SHADERS_DIR: PathBuf = RETROARCH_HOME.join("shaders");
+    pub static ref RETROARCH_STATES_DIR: PathBuf = ALLIUM_SD_ROOT.join("Saves/CurrentProfile/states");
+    pub static ref RETROARCH_SAVES_DIR: PathBuf = ALLIUM_SD_ROOT.join("Saves/CurrentProfile/saves");
+    /// RetroArch config folders to import core options, overrides and remaps from: the stock
+    /// install's, and a folder to copy another install's `.retroarch` or `config` folder into.
+    pub static ref RETROARCH_IMPORT_DIRS: Vec<PathBuf> = vec![
+        RETROARCH_HOME.join("config"),
+        ALLIUM_SD_ROOT.join("Import/RetroArch"),
+    ];
 }
 
 // Styles
@@ -65,29 +162,60 @@ pub const IMAGE_WIDTH: u32 = 250;
 pub const SELECTION_MARGIN: u32 = 8;
 pub const SAVE_STATE_IMAGE_WIDTH: u32 = 333;
 
-/// After the battery level drops below this threshold, the device will shut down.
+/// Default battery level at or below which the device saves the game and shuts down.
 pub const BATTERY_SHUTDOWN_THRESHOLD: i32 = 5;
 
+/// The interval at which the battery level is recorded in the database.
+pub const BATTERY_LOG_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// How long battery levels are kept in the database.
+pub const BATTERY_HISTORY_DURATION: chrono::Duration = chrono::Duration::days(7);
+
+/// How far back to look when estimating the battery drain rate.
+pub const BATTERY_ESTIMATE_WINDOW: chrono::Duration = chrono::Duration::hours(1);
+
+/// How far the battery level drops between low battery warnings.
+pub const BATTERY_WARNING_STEP: i32 = 5;
+
 /// The interval at which the battery level is updated.
 pub const BATTERY_UPDATE_INTERVAL: Duration = Duration::from_secs(10);
 
+/// The interval at which the session journal is updated while a game is running. This much play
+/// time may be lost if the device crashes.
+pub const SESSION_JOURNAL_INTERVAL: Duration = Duration::from_secs(60);
+
 /// The interval at which the clock is updated.
 pub const CLOCK_UPDATE_INTERVAL: Duration = Duration::from_secs(60);
 
 /// How long to wait until the device is considered idle.
 pub const IDLE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
 
+/// Brightness of the screen while dimmed for inactivity.
+pub const IDLE_DIM_BRIGHTNESS: u8 = 5;
+
 /// The number of items to jump when pressing left/right in a listing.
 pub const LISTING_JUMP_SIZE: i32 = 5;
 
 /// If a key autorepeat is received after this duration, it will be ignored.
 pub const MAXIMUM_FRAME_TIME: Duration = Duration::from_millis(100);
 
+/// Time between frames while a view is animating.
+pub const ANIMATION_FRAME_TIME: Duration = Duration::from_micros(33_333);
+
 /// Maximum number of recent games to retrieve from the database.
 pub const RECENT_GAMES_LIMIT: i64 = 100;
 
 /// RetroArch network command interface.
 pub const RETROARCH_UDP_SOCKET: &str = "127.0.0.1:55355";
 
-/// Long press duration for the menu button.
+/// Default duration a key is held before it is long pressed.
 pub const LONG_PRESS_DURATION: Duration = Duration::from_millis(1000);
+
+/// How long the device vibrates when the selection moves.
+pub const RUMBLE_NAVIGATION_DURATION: Duration = Duration::from_millis(15);
+
+/// How long the device vibrates when confirming a selection.
+pub const RUMBLE_CONFIRM_DURATION: Duration = Duration::from_millis(40);
+
+/// How long the device vibrates with a low battery warning.
+pub const RUMBLE_LOW_BATTERY_DURATION: Duration = Duration::from_millis(500);
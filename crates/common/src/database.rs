@@ -4,12 +4,15 @@ use std::{
 };
 
 use anyhow::{Context, Result};
-use chrono::{Duration, NaiveDate};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use log::{info, trace};
 use rusqlite::{Connection, OptionalExtension, Row, params};
 use rusqlite_migration::{M, Migrations};
+use strum::FromRepr;
 
+use crate::battery::BatterySample;
 use crate::constants::{ALLIUM_BASE_DIR, ALLIUM_DATABASE};
+use crate::performance::CpuProfile;
 
 #[derive(Debug, Clone, Default)]
 pub struct Database {
@@ -48,6 +51,31 @@ pub struct NewGame {
     pub favorite: bool,
 }
 
+/// Result of checking a game file against the DAT files.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, FromRepr)]
+pub enum RomStatus {
+    /// Not verified, or not found in any DAT file.
+    #[default]
+    Unknown,
+    /// Matches a known good dump.
+    Verified,
+    /// Named like a known dump, but its contents don't match.
+    BadDump,
+    /// A known good dump followed by extra data.
+    Overdump,
+}
+
+impl RomStatus {
+    /// Badge shown next to the game name, in the style of ROM naming conventions.
+    pub fn badge(self) -> Option<&'static str> {
+        match self {
+            RomStatus::Unknown | RomStatus::Verified => None,
+            RomStatus::BadDump => Some("[b]"),
+            RomStatus::Overdump => Some("[o]"),
+        }
+    }
+}
+
 impl Database {
     pub fn new() -> Result<Self> {
         if !ALLIUM_DATABASE.exists() {
@@ -154,10 +182,49 @@ ALTER TABLE games ADD COLUMN favorite INTEGER NOT NULL DEFAULT 0;
 "),
         M::up("
 ALTER TABLE games ADD COLUMN screenshot_path TEXT;
+"),
+        M::up("
+CREATE TABLE IF NOT EXISTS battery_history (
+    time INTEGER NOT NULL,
+    percentage INTEGER NOT NULL,
+    charging INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS battery_history_time ON battery_history (time);
+"),
+        M::up("
+ALTER TABLE games ADD COLUMN cpu_profile INTEGER NOT NULL DEFAULT 0;
+"),
+        M::up("
+CREATE TABLE IF NOT EXISTS discovered_games (
+    path TEXT PRIMARY KEY,
+    time INTEGER NOT NULL
+);
+"),
+        M::up("
+CREATE TABLE IF NOT EXISTS guide_bookmarks (
+    path TEXT NOT NULL,
+    cursor INTEGER NOT NULL,
+    PRIMARY KEY (path, cursor)
+);
+"),
+        M::up("
+CREATE TABLE IF NOT EXISTS rom_statuses (
+    path TEXT PRIMARY KEY,
+    status INTEGER NOT NULL
+);
 "),
                 ])
     }
 
+    /// Writes a consistent copy of the database to a new file.
+    pub fn copy_to(&self, path: &Path) -> Result<()> {
+        self.conn
+            .as_ref()
+            .unwrap()
+            .execute("VACUUM INTO ?", params![path.display().to_string()])?;
+        Ok(())
+    }
+
     pub fn reset_game(&self, path: &Path) -> Result<()> {
         self.conn.as_ref().unwrap().execute(
             "UPDATE games SET play_count = 0, play_time = 0, last_played = 0 WHERE path = ?",
@@ -191,9 +258,53 @@ ALTER TABLE games ADD COLUMN screenshot_path TEXT;
             new.display().to_string(),
             old.display().to_string()
         ])?;
+        self.conn.as_ref().unwrap().execute(
+            "UPDATE OR IGNORE discovered_games SET path = ? WHERE path = ?",
+            params![new.display().to_string(), old.display().to_string()],
+        )?;
+        self.conn.as_ref().unwrap().execute(
+            "UPDATE OR REPLACE rom_statuses SET path = ? WHERE path = ?",
+            params![new.display().to_string(), old.display().to_string()],
+        )?;
+        Ok(())
+    }
+
+    /// Records when games were first found on the SD card. Games that were already found keep
+    /// their original time.
+    pub fn add_discovered_games(&self, paths: &[&Path], time: DateTime<Utc>) -> Result<()> {
+        let tx = self.conn.as_ref().unwrap().unchecked_transaction()?; // safe because single-threaded
+
+        let mut stmt =
+            tx.prepare("INSERT OR IGNORE INTO discovered_games (path, time) VALUES (?, ?)")?;
+        for path in paths {
+            stmt.execute(params![path.display().to_string(), time.timestamp()])?;
+        }
+
+        drop(stmt);
+
+        tx.commit()?;
+
         Ok(())
     }
 
+    /// Selects when games were first found, as Unix timestamps.
+    pub fn select_discovered_times(&self, paths: &[&Path]) -> Result<Vec<Option<i64>>> {
+        let mut stmt = self
+            .conn
+            .as_ref()
+            .unwrap()
+            .prepare("SELECT time FROM discovered_games WHERE path = ?")?;
+
+        let mut results = vec![None; paths.len()];
+        for (i, path) in paths.iter().enumerate() {
+            results[i] = stmt
+                .query_row(params![path.display().to_string()], |row| row.get(0))
+                .optional()?;
+        }
+
+        Ok(results)
+    }
+
     pub fn update_games(&self, games: &[NewGame]) -> Result<()> {
         let tx = self.conn.as_ref().unwrap().unchecked_transaction()?; // safe because single-threaded
 
@@ -332,6 +443,22 @@ ON CONFLICT(path) DO UPDATE SET name = ?, image = ?, core = ?, rating = ?, relea
         Ok(results)
     }
 
+    /// Selects random favorite games.
+    pub fn select_random_favorites(&self, limit: i64) -> Result<Vec<Game>> {
+        let mut stmt = self
+            .conn
+            .as_ref()
+            .unwrap()
+            .prepare("SELECT name, path, image, play_count, play_time, last_played, core, rating, release_date, developer, publisher, genres, favorite, screenshot_path FROM games WHERE id IN (SELECT id FROM games WHERE favorite = 1 ORDER BY RANDOM() LIMIT ?)")?;
+
+        let results = stmt
+            .query_map([limit], map_game)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(results)
+    }
+
     /// Search for games by name. The query is a prefix search on words, so "Fi" will match both "Fire Emblem" and "Pokemon Fire Red".
     pub fn search(&self, query: &str, limit: i64) -> Result<Vec<Game>> {
         if query.is_empty() {
@@ -356,16 +483,65 @@ ON CONFLICT(path) DO UPDATE SET name = ?, image = ?, core = ?, rating = ?, relea
         trace!("select_games_in_directory({:?})", path);
         let conn = self.conn.as_ref().unwrap();
 
-        let mut stmt = conn.prepare("SELECT games.name, games.path, image, play_count, play_time, last_played, core, rating, release_date, games.developer, games.publisher, genres, favorite, screenshot_path FROM games JOIN games_fts ON games.id = games_fts.rowid WHERE games_fts.path LIKE ? AND games_fts.path NOT LIKE ?")?;
+        let mut stmt = conn.prepare("SELECT games.name, games.path, image, play_count, play_time, last_played, core, rating, release_date, games.developer, games.publisher, genres, favorite, screenshot_path FROM games JOIN games_fts ON games.id = games_fts.rowid WHERE games_fts.path LIKE ? ESCAPE '\\' AND games_fts.path NOT LIKE ? ESCAPE '\\'")?;
+
+        let pattern = like_prefix(path);
+        let results = stmt
+            .query_map(params![pattern, format!("{}/%", pattern)], map_game)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(results)
+    }
+
+    /// Selects every game in a directory and its subdirectories, ordered by name.
+    pub fn select_games_under_directory(&self, path: &Path) -> Result<Vec<Game>> {
+        trace!("select_games_under_directory({:?})", path);
+        let conn = self.conn.as_ref().unwrap();
+
+        let mut stmt = conn.prepare("SELECT name, path, image, play_count, play_time, last_played, core, rating, release_date, developer, publisher, genres, favorite, screenshot_path FROM games WHERE path LIKE ? ESCAPE '\\' ORDER BY name COLLATE NOCASE")?;
 
         let results = stmt
-            .query_map(
-                params![
-                    format!("{}/%", path.display().to_string()),
-                    format!("{}/%/%", path.display().to_string())
-                ],
-                map_game,
-            )?
+            .query_map(params![like_prefix(path)], map_game)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(results)
+    }
+
+    /// Whether any game is in a directory or its subdirectories.
+    pub fn has_games_under_directory(&self, path: &Path) -> Result<bool> {
+        trace!("has_games_under_directory({:?})", path);
+        let conn = self.conn.as_ref().unwrap();
+
+        let mut stmt =
+            conn.prepare("SELECT EXISTS (SELECT 1 FROM games WHERE path LIKE ? ESCAPE '\\')")?;
+
+        Ok(stmt.query_row(params![like_prefix(path)], |row| row.get(0))?)
+    }
+
+    /// Selects the genres of all games in a directory and its subdirectories, ordered by name.
+    pub fn select_genres_under_directory(&self, path: &Path) -> Result<Vec<String>> {
+        let conn = self.conn.as_ref().unwrap();
+
+        let mut stmt = conn.prepare("SELECT DISTINCT genre.value FROM games, json_each(games.genres) AS genre WHERE games.path LIKE ? ESCAPE '\\' ORDER BY genre.value COLLATE NOCASE")?;
+
+        let results = stmt
+            .query_map(params![like_prefix(path)], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(results)
+    }
+
+    /// Selects every game with a genre in a directory and its subdirectories, ordered by name.
+    pub fn select_games_with_genre(&self, path: &Path, genre: &str) -> Result<Vec<Game>> {
+        let conn = self.conn.as_ref().unwrap();
+
+        let mut stmt = conn.prepare("SELECT name, path, image, play_count, play_time, last_played, core, rating, release_date, developer, publisher, genres, favorite, screenshot_path FROM games WHERE path LIKE ? ESCAPE '\\' AND EXISTS (SELECT 1 FROM json_each(games.genres) WHERE value = ?) ORDER BY name COLLATE NOCASE")?;
+
+        let results = stmt
+            .query_map(params![like_prefix(path), genre], map_game)?
             .filter_map(|r| r.ok())
             .collect();
 
@@ -452,6 +628,44 @@ ON CONFLICT(path) DO UPDATE SET play_count = play_count + 1;",
         Ok(())
     }
 
+    /// Merges play history imported from another frontend, inserting the game if it doesn't
+    /// exist. Recently played games are moved to the top of the recents.
+    pub fn import_game(
+        &self,
+        game: &NewGame,
+        play_count: i64,
+        play_time: Duration,
+        recent: bool,
+    ) -> Result<()> {
+        self.conn.as_ref().unwrap().execute(
+            "
+INSERT INTO games (name, path, image, play_count, play_time, last_played, core, favorite)
+VALUES (?, ?, ?, ?, ?, 0, ?, ?)
+ON CONFLICT(path) DO UPDATE SET
+    play_count = MAX(play_count, excluded.play_count),
+    play_time = MAX(play_time, excluded.play_time),
+    favorite = MAX(favorite, excluded.favorite);",
+            params![
+                game.name,
+                game.path.display().to_string(),
+                game.image.as_ref().map(|p| p.display().to_string()),
+                play_count,
+                play_time.num_seconds(),
+                game.core,
+                game.favorite,
+            ],
+        )?;
+
+        if recent {
+            self.conn.as_ref().unwrap().execute(
+                "UPDATE games SET last_played = (SELECT MAX(last_played) FROM games) + 1 WHERE path = ?",
+                [game.path.display().to_string()],
+            )?;
+        }
+
+        Ok(())
+    }
+
     /// Sets whether a game is a favorite.
     pub fn set_favorite(&self, path: &Path, favorite: bool) -> Result<()> {
         self.conn.as_ref().unwrap().execute(
@@ -486,12 +700,87 @@ ON CONFLICT(path) DO UPDATE SET play_count = play_count + 1;",
         Ok(())
     }
 
+    /// Returns the bookmarked positions in a guide, in order.
+    pub fn select_guide_bookmarks(&self, path: &Path) -> Result<Vec<u64>> {
+        let mut stmt = self
+            .conn
+            .as_ref()
+            .unwrap()
+            .prepare("SELECT cursor FROM guide_bookmarks WHERE path = ? ORDER BY cursor")?;
+
+        let results = stmt
+            .query_map([path.display().to_string()], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(results)
+    }
+
+    pub fn add_guide_bookmark(&self, path: &Path, cursor: u64) -> Result<()> {
+        self.conn.as_ref().unwrap().execute(
+            "INSERT OR IGNORE INTO guide_bookmarks (path, cursor) VALUES (?, ?)",
+            params![path.display().to_string(), cursor],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_guide_bookmark(&self, path: &Path, cursor: u64) -> Result<()> {
+        self.conn.as_ref().unwrap().execute(
+            "DELETE FROM guide_bookmarks WHERE path = ? AND cursor = ?",
+            params![path.display().to_string(), cursor],
+        )?;
+        Ok(())
+    }
+
+    /// Replaces the results of the last ROM verification.
+    pub fn set_rom_statuses(&self, statuses: &[(&Path, RomStatus)]) -> Result<()> {
+        let tx = self.conn.as_ref().unwrap().unchecked_transaction()?; // safe because single-threaded
+
+        tx.execute("DELETE FROM rom_statuses", [])?;
+        let mut stmt = tx.prepare("INSERT INTO rom_statuses (path, status) VALUES (?, ?)")?;
+        for (path, status) in statuses {
+            stmt.execute(params![path.display().to_string(), *status as usize])?;
+        }
+
+        drop(stmt);
+
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Selects the results of the last ROM verification.
+    pub fn select_rom_statuses(&self, paths: &[&Path]) -> Result<Vec<RomStatus>> {
+        let mut stmt = self
+            .conn
+            .as_ref()
+            .unwrap()
+            .prepare("SELECT status FROM rom_statuses WHERE path = ?")?;
+
+        let mut results = vec![RomStatus::Unknown; paths.len()];
+        for (i, path) in paths.iter().enumerate() {
+            results[i] = stmt
+                .query_row(params![path.display().to_string()], |row| {
+                    row.get::<_, usize>(0)
+                })
+                .optional()?
+                .and_then(RomStatus::from_repr)
+                .unwrap_or_default();
+        }
+
+        Ok(results)
+    }
+
     /// Deletes a game from the database.
     pub fn delete_game(&self, path: &Path) -> Result<()> {
         self.conn.as_ref().unwrap().execute(
             "DELETE FROM games WHERE path = ?",
             [path.display().to_string()],
         )?;
+        self.conn.as_ref().unwrap().execute(
+            "DELETE FROM rom_statuses WHERE path = ?",
+            [path.display().to_string()],
+        )?;
 
         Ok(())
     }
@@ -541,6 +830,30 @@ ON CONFLICT(path) DO UPDATE SET play_count = play_count + 1;",
         Ok(matches!(value.as_deref(), Some("1")))
     }
 
+    pub fn set_has_imported_onion(&self, has_imported: bool) -> Result<()> {
+        self.conn.as_ref().unwrap().execute(
+            "INSERT INTO key_value (key, value) VALUES ('has_imported_onion', ?1) ON CONFLICT(key) DO UPDATE SET value = ?1",
+            [if has_imported { "1" } else { "0" }],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn has_imported_onion(&self) -> Result<bool> {
+        let value = self
+            .conn
+            .as_ref()
+            .unwrap()
+            .query_row(
+                "SELECT value FROM key_value WHERE key = 'has_imported_onion'",
+                [],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?;
+
+        Ok(matches!(value.as_deref(), Some("1")))
+    }
+
     pub fn set_gamelist_fingerprint(&self, path: &Path, fingerprint: u64) -> Result<()> {
         trace!("set_gamelist_fingerprint({:?}, {})", path, fingerprint);
         self.conn.as_ref().unwrap().execute(
@@ -592,6 +905,74 @@ ON CONFLICT(path) DO UPDATE SET play_count = play_count + 1;",
 
         Ok(())
     }
+
+    pub fn get_cpu_profile(&self, path: &Path) -> Result<CpuProfile> {
+        let profile = self
+            .conn
+            .as_ref()
+            .unwrap()
+            .query_row(
+                "SELECT cpu_profile FROM games WHERE path = ?",
+                [path.display().to_string()],
+                |row| row.get::<_, usize>(0),
+            )
+            .optional()?
+            .and_then(CpuProfile::from_repr)
+            .unwrap_or_default();
+
+        Ok(profile)
+    }
+
+    /// Sets the CPU profile of a game, inserting a new row if it doesn't exist.
+    pub fn set_cpu_profile(&self, name: &str, path: &Path, profile: CpuProfile) -> Result<()> {
+        self.conn.as_ref().unwrap().execute(
+            "
+INSERT INTO games (name, path, play_count, play_time, last_played, cpu_profile)
+VALUES (?, ?, 0, 0, 0, ?)
+ON CONFLICT(path) DO UPDATE SET cpu_profile = excluded.cpu_profile;",
+            params![name, path.display().to_string(), profile as usize],
+        )?;
+
+        Ok(())
+    }
+
+    /// Records a battery level, and deletes levels recorded before `keep_since`.
+    pub fn add_battery_sample(
+        &self,
+        sample: &BatterySample,
+        keep_since: DateTime<Utc>,
+    ) -> Result<()> {
+        let conn = self.conn.as_ref().unwrap();
+        conn.execute(
+            "INSERT INTO battery_history (time, percentage, charging) VALUES (?, ?, ?)",
+            params![sample.time.timestamp(), sample.percentage, sample.charging],
+        )?;
+        conn.execute(
+            "DELETE FROM battery_history WHERE time < ?",
+            [keep_since.timestamp()],
+        )?;
+
+        Ok(())
+    }
+
+    /// Selects the battery levels recorded since `since`, oldest first.
+    pub fn select_battery_history(&self, since: DateTime<Utc>) -> Result<Vec<BatterySample>> {
+        let conn = self.conn.as_ref().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT time, percentage, charging FROM battery_history WHERE time >= ? ORDER BY time ASC",
+        )?;
+        let samples = stmt
+            .query_map([since.timestamp()], |row| {
+                Ok(BatterySample {
+                    time: DateTime::from_timestamp(row.get(0)?, 0).unwrap_or_default(),
+                    percentage: row.get(1)?,
+                    charging: row.get(2)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(samples)
+    }
 }
 
 fn map_game(row: &Row<'_>) -> rusqlite::Result<Game> {
@@ -613,10 +994,43 @@ fn map_game(row: &Row<'_>) -> rusqlite::Result<Game> {
     })
 }
 
+/// A LIKE pattern matching every path under `path`, with `%`, `_` and `\` in `path` escaped.
+/// The query must use `ESCAPE '\'`.
+fn like_prefix(path: &Path) -> String {
+    let mut pattern = String::new();
+    for c in path.display().to_string().chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push_str("/%");
+    pattern
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn new_game(path: &str) -> NewGame {
+        NewGame {
+            name: Path::new(path)
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .to_string(),
+            path: PathBuf::from(path),
+            image: None,
+            core: None,
+            rating: None,
+            release_date: None,
+            developer: None,
+            publisher: None,
+            genres: Vec::new(),
+            favorite: false,
+        }
+    }
+
     #[test]
     fn test_migrations() {
         Database::migrations().validate().unwrap();
@@ -1016,6 +1430,115 @@ mod tests {
 
         let results = database.select_games_in_directory(Path::new("")).unwrap();
         assert_eq!(results.len(), 0);
+
+        let results = database
+            .select_games_in_directory(Path::new("test_director_"))
+            .unwrap();
+        assert_eq!(results.len(), 0);
+
+        let results = database.select_games_in_directory(Path::new("%")).unwrap();
+        assert_eq!(results.len(), 0);
+    }
+
+    #[test]
+    fn test_select_games_under_directory() {
+        let database = Database::in_memory().unwrap();
+
+        let games = [
+            "Games/GBA/Zelda.gba",
+            "Games/SNES/Mario.sfc",
+            "Other/Tetris.gb",
+        ]
+        .map(new_game);
+
+        database.update_games(&games).unwrap();
+
+        let results = database
+            .select_games_under_directory(Path::new("Games"))
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].name, "Mario");
+        assert_eq!(results[1].name, "Zelda");
+
+        let results = database
+            .select_games_under_directory(Path::new("G_mes"))
+            .unwrap();
+        assert_eq!(results.len(), 0);
+
+        let results = database
+            .select_games_under_directory(Path::new("%"))
+            .unwrap();
+        assert_eq!(results.len(), 0);
+
+        assert!(
+            database
+                .has_games_under_directory(Path::new("Games/GBA"))
+                .unwrap()
+        );
+        assert!(
+            !database
+                .has_games_under_directory(Path::new("Games/GB"))
+                .unwrap()
+        );
+        assert!(
+            !database
+                .has_games_under_directory(Path::new("Games/G_A"))
+                .unwrap()
+        );
+        assert!(
+            !database
+                .has_games_under_directory(Path::new("Games/%"))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_select_genres() -> Result<()> {
+        let database = Database::in_memory().unwrap();
+
+        let games = [
+            ("Games/GBA/Zelda.gba", vec!["RPG", "Adventure"]),
+            ("Games/SNES/Mario.sfc", vec!["Platformer"]),
+            ("Games/SNES/Chrono Trigger.sfc", vec!["RPG"]),
+            ("Other/Tetris.gb", vec!["Puzzle"]),
+        ]
+        .into_iter()
+        .map(|(path, genres)| NewGame {
+            genres: genres.into_iter().map(str::to_owned).collect(),
+            ..new_game(path)
+        })
+        .collect::<Vec<_>>();
+
+        database.update_games(&games)?;
+
+        assert_eq!(
+            database.select_genres_under_directory(Path::new("Games"))?,
+            vec!["Adventure", "Platformer", "RPG"]
+        );
+
+        let results = database.select_games_with_genre(Path::new("Games"), "RPG")?;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].name, "Chrono Trigger");
+        assert_eq!(results[1].name, "Zelda");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_select_random_favorites() -> Result<()> {
+        let database = Database::in_memory()?;
+
+        let games = ["Games/GBA/Zelda.gba", "Games/SNES/Mario.sfc"].map(new_game);
+        database.update_games(&games)?;
+
+        assert!(database.select_random_favorites(1)?.is_empty());
+
+        database.set_favorite(&games[1].path, true)?;
+        let results = database.select_random_favorites(1)?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].path, games[1].path);
+
+        Ok(())
     }
 
     #[test]
@@ -1062,6 +1585,25 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_cpu_profile() -> Result<()> {
+        let db = Database::in_memory().unwrap();
+
+        let path = Path::new("test_directory/Game One.rom");
+        assert_eq!(db.get_cpu_profile(path)?, CpuProfile::Default);
+
+        // Games that aren't in the database yet are added
+        db.set_cpu_profile("Game One", path, CpuProfile::Performance)?;
+        assert_eq!(db.get_cpu_profile(path)?, CpuProfile::Performance);
+        assert_eq!(db.select_game(path)?.unwrap().name, "Game One");
+
+        db.set_cpu_profile("Game One", path, CpuProfile::Powersave)?;
+        assert_eq!(db.get_cpu_profile(path)?, CpuProfile::Powersave);
+        assert_eq!(db.select_all_games()?.len(), 1);
+
+        Ok(())
+    }
+
     #[test]
     fn test_set_genres() -> Result<()> {
         let db = Database::in_memory().unwrap();
@@ -1098,4 +1640,80 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_discovered_games() -> Result<()> {
+        let db = Database::in_memory().unwrap();
+
+        let first = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let second = DateTime::from_timestamp(1_700_100_000, 0).unwrap();
+        let (a, b, c) = (Path::new("a.rom"), Path::new("b.rom"), Path::new("c.rom"));
+
+        db.add_discovered_games(&[a], first)?;
+        db.add_discovered_games(&[a, b], second)?;
+        db.update_game_path(b, c)?;
+
+        assert_eq!(
+            db.select_discovered_times(&[a, b, c])?,
+            vec![Some(first.timestamp()), None, Some(second.timestamp())]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_guide_bookmarks() -> Result<()> {
+        let db = Database::in_memory().unwrap();
+        let guide = Path::new("guide.txt");
+
+        db.add_guide_bookmark(guide, 200)?;
+        db.add_guide_bookmark(guide, 100)?;
+        db.add_guide_bookmark(guide, 200)?;
+        db.add_guide_bookmark(Path::new("other.txt"), 300)?;
+        assert_eq!(db.select_guide_bookmarks(guide)?, vec![100, 200]);
+
+        db.delete_guide_bookmark(guide, 100)?;
+        assert_eq!(db.select_guide_bookmarks(guide)?, vec![200]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_battery_history() -> Result<()> {
+        let db = Database::in_memory().unwrap();
+
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let sample = |minutes: i64, percentage: i32, charging: bool| BatterySample {
+            time: start + Duration::minutes(minutes),
+            percentage,
+            charging,
+        };
+
+        db.add_battery_sample(&sample(0, 40, true), start)?;
+        db.add_battery_sample(&sample(30, 80, false), start)?;
+        db.add_battery_sample(&sample(60, 70, false), start)?;
+        db.add_battery_sample(&sample(90, 60, false), start + Duration::minutes(10))?;
+
+        let history = db.select_battery_history(start)?;
+        assert_eq!(
+            history,
+            vec![
+                sample(30, 80, false),
+                sample(60, 70, false),
+                sample(90, 60, false)
+            ]
+        );
+
+        // 20% drained over the last hour, so 60% lasts another 3 hours
+        assert_eq!(
+            crate::battery::estimate_time_remaining(&history),
+            Some(Duration::hours(3))
+        );
+
+        db.add_battery_sample(&sample(95, 61, true), start)?;
+        let history = db.select_battery_history(start)?;
+        assert_eq!(crate::battery::estimate_time_remaining(&history), None);
+
+        Ok(())
+    }
 }
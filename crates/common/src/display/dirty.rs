@@ -0,0 +1,84 @@
+use crate::geom::Rect;
+
+/// Number of separate areas tracked before they are merged into one.
+const MAX_DIRTY_RECTS: usize = 8;
+
+/// Areas of the display that changed since the last flush.
+#[derive(Debug, Clone, Default)]
+pub struct DirtyRegion {
+    rects: Vec<Rect>,
+}
+
+impl DirtyRegion {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks an area as changed. Overlapping areas are merged, so that no pixel is flushed twice.
+    pub fn add(&mut self, mut rect: Rect) {
+        if rect.w == 0 || rect.h == 0 {
+            return;
+        }
+
+        // Merging may make the area overlap others it didn't before, so repeat until it doesn't
+        while let Some(i) = self.rects.iter().position(|r| overlaps(r, &rect)) {
+            rect = rect.union(&self.rects.swap_remove(i));
+        }
+        self.rects.push(rect);
+
+        if self.rects.len() > MAX_DIRTY_RECTS {
+            let union = self
+                .rects
+                .drain(..)
+                .fold(Rect::zero(), |acc, r| acc.union(&r));
+            self.rects.push(union);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    /// Returns the changed areas, and clears them.
+    pub fn take(&mut self) -> Vec<Rect> {
+        std::mem::take(&mut self.rects)
+    }
+}
+
+fn overlaps(a: &Rect, b: &Rect) -> bool {
+    a.x < b.right() && b.x < a.right() && a.y < b.bottom() && b.y < a.bottom()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dirty_region_merges_overlapping_rects() {
+        let mut region = DirtyRegion::new();
+        region.add(Rect::new(0, 0, 10, 10));
+        region.add(Rect::new(20, 0, 10, 10));
+        region.add(Rect::new(0, 0, 0, 10));
+        assert_eq!(region.take().len(), 2);
+        assert!(region.is_empty());
+
+        // Bridges the two rects, which are then merged into one
+        region.add(Rect::new(0, 0, 10, 10));
+        region.add(Rect::new(20, 0, 10, 10));
+        region.add(Rect::new(5, 5, 20, 2));
+        assert_eq!(region.take(), vec![Rect::new(0, 0, 30, 10)]);
+
+        for i in 0..=MAX_DIRTY_RECTS as i32 {
+            region.add(Rect::new(i * 10, i * 10, 5, 5));
+        }
+        assert_eq!(
+            region.take(),
+            vec![Rect::new(
+                0,
+                0,
+                MAX_DIRTY_RECTS as u32 * 10 + 5,
+                MAX_DIRTY_RECTS as u32 * 10 + 5
+            )]
+        );
+    }
+}
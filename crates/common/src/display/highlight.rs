@@ -0,0 +1,50 @@
+use anyhow::Result;
+use embedded_graphics::Drawable;
+use embedded_graphics::prelude::Size;
+use embedded_graphics::primitives::{Primitive, PrimitiveStyle, RoundedRectangle};
+
+use crate::display::Display;
+use crate::display::color::Color;
+use crate::geom::Rect;
+use crate::stylesheet::{SelectionStyle, Stylesheet};
+
+/// Fills a rectangle with corners rounded according to the stylesheet.
+pub fn draw_rounded_rect(
+    display: &mut impl Display,
+    styles: &Stylesheet,
+    rect: Rect,
+    color: Color,
+) -> Result<()> {
+    RoundedRectangle::with_equal_corners(
+        rect.into(),
+        Size::new_equal(styles.corner_radius(rect.h)),
+    )
+    .into_styled(PrimitiveStyle::with_fill(color))
+    .draw(display)?;
+    Ok(())
+}
+
+/// Draws the highlight behind a selected item, in the stylesheet's selection style.
+pub fn draw_highlight(
+    display: &mut impl Display,
+    styles: &Stylesheet,
+    rect: Rect,
+    color: Color,
+) -> Result<()> {
+    match styles.selection_style {
+        SelectionStyle::Pill => draw_rounded_rect(display, styles, rect, color),
+        SelectionStyle::Underline => {
+            let thickness = (rect.h / 10).max(2);
+            let underline = Rect::new(
+                rect.x,
+                rect.y + (rect.h - thickness) as i32,
+                rect.w,
+                thickness,
+            );
+            RoundedRectangle::with_equal_corners(underline.into(), Size::new_equal(thickness))
+                .into_styled(PrimitiveStyle::with_fill(color))
+                .draw(display)?;
+            Ok(())
+        }
+    }
+}
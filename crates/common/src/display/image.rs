@@ -1,5 +1,16 @@
+use std::path::Path;
+
+use anyhow::Result;
+use embedded_graphics::Drawable;
+use embedded_graphics::image::ImageRaw;
+use image::imageops::FilterType;
 use image::{Rgba, RgbaImage};
 
+use crate::constants::ALLIUM_SD_ROOT;
+use crate::display::Display;
+use crate::display::color::Color;
+use crate::stylesheet::Stylesheet;
+
 /// Draw rounded corners on an image.
 pub fn round(image: &mut RgbaImage, radius: u32) {
     let color = Rgba([0, 0, 0, 0]);
@@ -85,3 +96,40 @@ pub fn round(image: &mut RgbaImage, radius: u32) {
         }
     }
 }
+
+/// Draws the background that views are drawn over: the background color, overlaid with the
+/// background image if there is one. The image is dimmed towards the background color by
+/// `styles.background_dim` percent.
+pub fn draw_background(
+    display: &mut impl Display,
+    styles: &Stylesheet,
+    image: Option<&Path>,
+) -> Result<()> {
+    display.clear(styles.background_color)?;
+
+    let Some(path) = image else {
+        return Ok(());
+    };
+    let path = ALLIUM_SD_ROOT.join(path);
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let size = display.bounding_box().size;
+    let mut image = ::image::open(&path)?
+        .resize_to_fill(size.width, size.height, FilterType::Lanczos3)
+        .into_rgba8();
+
+    let dim = (u32::from(styles.background_dim.min(100)) * 255 / 100) as u8;
+    if dim > 0 {
+        let bg = styles.background_color;
+        for pixel in image.pixels_mut() {
+            let color = Color::rgba(pixel[0], pixel[1], pixel[2], pixel[3]).blend(bg, dim);
+            *pixel = Rgba([color.r(), color.g(), color.b(), color.a()]);
+        }
+    }
+
+    let image: ImageRaw<'_, Color> = ImageRaw::new(&image, size.width);
+    embedded_graphics::image::Image::new(&image, display.bounding_box().top_left).draw(display)?;
+    Ok(())
+}
@@ -1,6 +1,9 @@
 pub mod color;
+pub mod dirty;
 pub mod font;
+pub mod highlight;
 pub mod image;
+pub mod rotation;
 pub mod settings;
 
 use anyhow::Result;
@@ -18,10 +21,15 @@ pub trait Display:
     where
         F: FnMut(Color) -> Color;
 
+    /// Shows what was drawn since the last flush. Displays that track the drawn areas only copy
+    /// those to the screen.
     fn flush(&mut self) -> Result<()> {
         Ok(())
     }
 
+    /// Sets whether flushing waits for the screen's vertical blank, on displays that support it.
+    fn set_vsync(&mut self, _vsync: bool) {}
+
     fn save(&mut self) -> Result<()>;
     fn load(&mut self, area: Rect) -> Result<()>;
     fn pop(&mut self) -> bool;
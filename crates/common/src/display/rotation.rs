@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+use strum::{EnumCount, FromRepr};
+
+use crate::constants::ALLIUM_GAME_INFO;
+use crate::display::settings::DisplaySettings;
+use crate::geom::Rect;
+use crate::platform::device::DeviceModel;
+
+/// Clockwise rotation of what is drawn, relative to the panel.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize, FromRepr, EnumCount,
+)]
+pub enum Rotation {
+    #[default]
+    Rotate0,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+}
+
+impl Rotation {
+    /// Rotation of what is on screen relative to the panel, for tools that read or draw over the
+    /// framebuffer directly. Games are drawn by RetroArch, which only knows the panel's rotation.
+    pub fn current() -> Rotation {
+        if ALLIUM_GAME_INFO.exists() {
+            DeviceModel::current().panel_rotation()
+        } else {
+            DisplaySettings::load().unwrap_or_default().panel_rotation()
+        }
+    }
+
+    /// This rotation followed by `other`.
+    pub fn then(self, other: Rotation) -> Rotation {
+        Rotation::from_repr((self as usize + other as usize) % Rotation::COUNT).unwrap()
+    }
+
+    /// Whether width and height are swapped between the display and the panel.
+    pub fn is_sideways(self) -> bool {
+        matches!(self, Rotation::Rotate90 | Rotation::Rotate270)
+    }
+
+    /// Size of the display, given the size of the panel, or the other way around.
+    pub fn swap_size(self, w: u32, h: u32) -> (u32, u32) {
+        if self.is_sideways() { (h, w) } else { (w, h) }
+    }
+
+    /// Maps a point on a `w` by `h` display to the panel.
+    pub fn to_panel(self, x: i32, y: i32, w: u32, h: u32) -> (i32, i32) {
+        let (w, h) = (w as i32, h as i32);
+        match self {
+            Rotation::Rotate0 => (x, y),
+            Rotation::Rotate90 => (h - 1 - y, x),
+            Rotation::Rotate180 => (w - 1 - x, h - 1 - y),
+            Rotation::Rotate270 => (y, w - 1 - x),
+        }
+    }
+
+    /// Maps a point on a `w` by `h` panel to the display.
+    pub fn to_display(self, x: i32, y: i32, w: u32, h: u32) -> (i32, i32) {
+        let (w, h) = (w as i32, h as i32);
+        match self {
+            Rotation::Rotate0 => (x, y),
+            Rotation::Rotate90 => (y, w - 1 - x),
+            Rotation::Rotate180 => (w - 1 - x, h - 1 - y),
+            Rotation::Rotate270 => (h - 1 - y, x),
+        }
+    }
+
+    /// Maps an area of a `w` by `h` display to the panel.
+    pub fn rect_to_panel(self, rect: Rect, w: u32, h: u32) -> Rect {
+        let (w, h) = (w as i32, h as i32);
+        let Rect { x, y, w: rw, h: rh } = rect;
+        match self {
+            Rotation::Rotate0 => rect,
+            Rotation::Rotate90 => Rect::new(h - y - rh as i32, x, rh, rw),
+            Rotation::Rotate180 => Rect::new(w - x - rw as i32, h - y - rh as i32, rw, rh),
+            Rotation::Rotate270 => Rect::new(y, w - x - rw as i32, rh, rw),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_round_trip() {
+        let (w, h) = (640, 480);
+        for i in 0..Rotation::COUNT {
+            let rotation = Rotation::from_repr(i).unwrap();
+            let (pw, ph) = rotation.swap_size(w, h);
+
+            for (x, y) in [(0, 0), (10, 20), (639, 479)] {
+                let (px, py) = rotation.to_panel(x, y, w, h);
+                assert!((0..pw as i32).contains(&px) && (0..ph as i32).contains(&py));
+                assert_eq!(rotation.to_display(px, py, pw, ph), (x, y), "{rotation:?}");
+            }
+
+            // The corners of a rect map to the corners of the rect on the panel
+            let rect = Rect::new(10, 20, 30, 40);
+            let panel = rotation.rect_to_panel(rect, w, h);
+            let (ax, ay) = rotation.to_panel(rect.x, rect.y, w, h);
+            let (bx, by) = rotation.to_panel(rect.right() - 1, rect.bottom() - 1, w, h);
+            assert_eq!(
+                panel,
+                Rect::new(
+                    ax.min(bx),
+                    ay.min(by),
+                    ax.abs_diff(bx) + 1,
+                    ay.abs_diff(by) + 1
+                ),
+                "{rotation:?}"
+            );
+        }
+
+        assert_eq!(
+            Rotation::Rotate180.then(Rotation::Rotate270),
+            Rotation::Rotate90
+        );
+    }
+}
@@ -4,10 +4,14 @@ use std::{
 };
 
 use anyhow::Result;
+use chrono::{NaiveTime, Timelike};
 use log::{debug, warn};
 use serde::{Deserialize, Serialize};
+use strum::FromRepr;
 
 use crate::constants::ALLIUM_DISPLAY_SETTINGS;
+use crate::display::rotation::Rotation;
+use crate::platform::device::DeviceModel;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DisplaySettings {
@@ -18,6 +22,32 @@ pub struct DisplaySettings {
     pub r: u8,
     pub g: u8,
     pub b: u8,
+    #[serde(default)]
+    pub night_mode: NightMode,
+    /// How much blue light is filtered out in night mode, from 0 to 100.
+    #[serde(default = "DisplaySettings::default_night_mode_strength")]
+    pub night_mode_strength: u8,
+    /// Minute of the day at which scheduled night mode turns on.
+    #[serde(default = "DisplaySettings::default_night_mode_start")]
+    pub night_mode_start_minute: u16,
+    /// Minute of the day at which scheduled night mode turns off.
+    #[serde(default = "DisplaySettings::default_night_mode_end")]
+    pub night_mode_end_minute: u16,
+    /// Whether drawing is copied to the screen during its vertical blank, to avoid tearing.
+    #[serde(default = "DisplaySettings::default_vsync")]
+    pub vsync: bool,
+    /// Rotation of the screen, relative to how the device is held.
+    #[serde(default)]
+    pub rotation: Rotation,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, FromRepr, Default)]
+pub enum NightMode {
+    #[default]
+    Off,
+    On,
+    /// On between `night_mode_start_minute` and `night_mode_end_minute`.
+    Scheduled,
 }
 
 impl DisplaySettings {
@@ -25,6 +55,57 @@ impl DisplaySettings {
         Self::default()
     }
 
+    fn default_night_mode_strength() -> u8 {
+        50
+    }
+
+    fn default_night_mode_start() -> u16 {
+        21 * 60
+    }
+
+    fn default_night_mode_end() -> u16 {
+        7 * 60
+    }
+
+    fn default_vsync() -> bool {
+        true
+    }
+
+    /// Rotation of what is drawn relative to the panel, which is mounted rotated on some devices.
+    pub fn panel_rotation(&self) -> Rotation {
+        DeviceModel::current().panel_rotation().then(self.rotation)
+    }
+
+    pub fn is_night_mode_active(&self, time: NaiveTime) -> bool {
+        match self.night_mode {
+            NightMode::Off => false,
+            NightMode::On => true,
+            NightMode::Scheduled => {
+                let minute = (time.hour() * 60 + time.minute()) as u16;
+                let (start, end) = (self.night_mode_start_minute, self.night_mode_end_minute);
+                if start <= end {
+                    start <= minute && minute < end
+                } else {
+                    // The schedule runs past midnight
+                    minute >= start || minute < end
+                }
+            }
+        }
+    }
+
+    /// The color balance to apply to the panel at `time`, with green and blue lowered if night
+    /// mode is active.
+    pub fn color_balance(&self, time: NaiveTime) -> (u8, u8, u8) {
+        if !self.is_night_mode_active(time) {
+            return (self.r, self.g, self.b);
+        }
+        let strength = self.night_mode_strength.min(100) as u32;
+        let scale = |value: u8, max_reduction: u32| {
+            (value as u32 * (100 - strength * max_reduction / 100) / 100) as u8
+        };
+        (self.r, scale(self.g, 30), scale(self.b, 70))
+    }
+
     pub fn load() -> Result<Self> {
         if ALLIUM_DISPLAY_SETTINGS.exists() {
             debug!("found state, loading from file");
@@ -56,6 +137,50 @@ impl Default for DisplaySettings {
             r: 50,
             g: 50,
             b: 50,
+            night_mode: NightMode::Off,
+            night_mode_strength: Self::default_night_mode_strength(),
+            night_mode_start_minute: Self::default_night_mode_start(),
+            night_mode_end_minute: Self::default_night_mode_end(),
+            vsync: Self::default_vsync(),
+            rotation: Rotation::default(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_night_mode_schedule() {
+        let at = |hour| NaiveTime::from_hms_opt(hour, 0, 0).unwrap();
+        let mut settings = DisplaySettings {
+            night_mode: NightMode::Scheduled,
+            night_mode_start_minute: 21 * 60,
+            night_mode_end_minute: 7 * 60,
+            ..Default::default()
+        };
+        assert!(settings.is_night_mode_active(at(22)));
+        assert!(settings.is_night_mode_active(at(3)));
+        assert!(!settings.is_night_mode_active(at(7)));
+        assert!(!settings.is_night_mode_active(at(12)));
+
+        settings.night_mode_start_minute = 60;
+        settings.night_mode_end_minute = 5 * 60;
+        assert!(settings.is_night_mode_active(at(1)));
+        assert!(!settings.is_night_mode_active(at(5)));
+        assert!(!settings.is_night_mode_active(at(23)));
+
+        settings.night_mode_start_minute = 22 * 60 + 30;
+        settings.night_mode_end_minute = 6 * 60 + 45;
+        let at = |hour, minute| NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+        assert!(!settings.is_night_mode_active(at(22, 29)));
+        assert!(settings.is_night_mode_active(at(22, 30)));
+        assert!(settings.is_night_mode_active(at(6, 44)));
+        assert!(!settings.is_night_mode_active(at(6, 45)));
+        let at = |hour| at(hour, 0);
+
+        assert_eq!(settings.color_balance(at(12)), (50, 50, 50));
+        assert_eq!(settings.color_balance(at(2)), (50, 42, 32));
+    }
+}
@@ -9,9 +9,11 @@ use chrono::{DateTime, Duration, Utc};
 use log::debug;
 use serde::{Deserialize, Serialize};
 
-use crate::constants::{ALLIUM_GAME_INFO, ALLIUM_GAMES_DIR, ALLIUM_SCRIPTS_DIR};
+use crate::constants::{ALLIUM_GAME_INFO, ALLIUM_SCRIPTS_DIR};
+use crate::performance::CpuProfile;
+use crate::storage;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// Information about a game. Used to restore a game after a restart, and to calculate playtime.
 pub struct GameInfo {
     /// Display name of the game.
@@ -32,12 +34,30 @@ pub struct GameInfo {
     pub image: Option<PathBuf>,
     /// Path to the guide text file.
     pub guide: Option<PathBuf>,
-    /// Start time. Used to measure playtime.
+    /// Paths to all guides found for the game, starting with `guide`.
+    #[serde(default)]
+    pub guides: Vec<PathBuf>,
+    /// Start of the play time that isn't recorded in the database yet. Reset when the game is
+    /// resumed after a restart, so that time spent powered off doesn't count.
     pub start_time: DateTime<Utc>,
+    /// Identifies the play session, which lasts from launch until returning to the launcher, and
+    /// survives restarts.
+    #[serde(default)]
+    pub session_id: i64,
+    /// When the session started.
+    #[serde(default = "Utc::now")]
+    pub started_at: DateTime<Utc>,
+    /// Play time of this session that is already recorded in the database, in seconds.
+    #[serde(default)]
+    pub recorded_play_time: i64,
+    /// CPU profile to apply while the game is running.
+    #[serde(default)]
+    pub cpu_profile: CpuProfile,
 }
 
 impl Default for GameInfo {
     fn default() -> Self {
+        let now = Utc::now();
         Self {
             name: String::new(),
             path: PathBuf::new(),
@@ -48,7 +68,12 @@ impl Default for GameInfo {
             needs_swap: false,
             image: None,
             guide: None,
-            start_time: Utc::now(),
+            guides: Vec::new(),
+            start_time: now,
+            session_id: now.timestamp_micros(),
+            started_at: now,
+            recorded_play_time: 0,
+            cpu_profile: CpuProfile::Default,
         }
     }
 }
@@ -66,7 +91,9 @@ impl GameInfo {
         has_menu: bool,
         needs_swap: bool,
     ) -> Self {
-        let guide = find_guide(&path);
+        let guides = find_guides(&path);
+        let guide = guides.first().cloned();
+        let now = Utc::now();
 
         Self {
             name,
@@ -78,7 +105,12 @@ impl GameInfo {
             needs_swap,
             image,
             guide,
-            start_time: Utc::now(),
+            guides,
+            start_time: now,
+            session_id: now.timestamp_micros(),
+            started_at: now,
+            recorded_play_time: 0,
+            cpu_profile: CpuProfile::Default,
         }
     }
 
@@ -124,38 +156,95 @@ impl GameInfo {
         command
     }
 
-    /// How long the game has been running.
+    /// Play time that isn't recorded in the database yet.
     pub fn play_time(&self) -> Duration {
         Utc::now().signed_duration_since(self.start_time)
     }
 
+    /// Total play time of this session.
+    pub fn session_play_time(&self) -> Duration {
+        Duration::seconds(self.recorded_play_time) + self.play_time()
+    }
+
+    /// Returns the play time that isn't recorded yet, and marks it as recorded.
+    pub fn take_play_time(&mut self) -> Duration {
+        let now = Utc::now();
+        let play_time = now.signed_duration_since(self.start_time);
+        self.recorded_play_time += play_time.num_seconds();
+        self.start_time = now;
+        play_time
+    }
+
     /// Whether swap should be enabled.
     pub fn needs_swap(&self) -> bool {
         self.needs_swap
     }
+
+    /// Name of the libretro core, if the game is run with RetroArch.
+    pub fn retroarch_core(&self) -> Option<&str> {
+        if self.has_menu {
+            self.args.first().map(String::as_str)
+        } else {
+            None
+        }
+    }
+}
+
+/// Path to `mutool`, which renders PDF guides. It isn't shipped with Allium, so PDF guides are only
+/// available once it has been installed somewhere on `PATH`.
+pub fn pdf_renderer() -> Option<PathBuf> {
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join("mutool"))
+        .find(|path| path.is_file())
 }
 
-/// Searches for the guide path, caches it, and returns it
-pub fn find_guide(path: &Path) -> Option<PathBuf> {
-    // Search for Imgs folder upwards, recursively
+/// Searches for the guides of a game. These are the files in the nearest `Guides` folder that
+/// mirror the game's path, and whose names start with the game's file name, e.g.
+/// `Guides/GBA/Pokemon Emerald (Walkthrough).txt`. A guide named exactly after the game comes first.
+/// PDF guides are skipped when they can't be rendered.
+pub fn find_guides(path: &Path) -> Vec<PathBuf> {
+    const GUIDE_EXTENSIONS: [&str; 5] = ["txt", "md", "markdown", "pdf", "cbz"];
+
+    let Some(stem) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+        return Vec::new();
+    };
+
+    // Search for Guides folder upwards, recursively
     let mut parent = path.to_path_buf();
-    let mut guide = None;
-    'image: while parent.pop() {
-        let mut guide_path = parent.join("Guides");
-        if guide_path.is_dir() {
-            guide_path.extend(path.strip_prefix(&parent).unwrap());
-            const GUIDE_EXTENSIONS: [&str; 1] = ["txt"];
-            for ext in &GUIDE_EXTENSIONS {
-                guide_path.set_extension(ext);
-                if guide_path.is_file() {
-                    guide = Some(guide_path);
-                    break 'image;
-                }
+    while parent.pop() {
+        let mut guide_dir = parent.join("Guides");
+        if guide_dir.is_dir() {
+            guide_dir.extend(path.strip_prefix(&parent).unwrap());
+            guide_dir.pop();
+
+            let mut guides: Vec<(bool, String, usize, PathBuf)> = fs::read_dir(&guide_dir)
+                .into_iter()
+                .flatten()
+                .flatten()
+                .filter_map(|entry| {
+                    let guide = entry.path();
+                    let ext = guide.extension()?.to_string_lossy().to_lowercase();
+                    if ext == "pdf" && pdf_renderer().is_none() {
+                        return None;
+                    }
+                    let ext = GUIDE_EXTENSIONS.iter().position(|e| *e == ext)?;
+                    let guide_stem = guide.file_stem()?.to_string_lossy().to_string();
+                    (guide_stem.starts_with(&stem) && guide.is_file()).then_some((
+                        guide_stem != stem,
+                        guide_stem,
+                        ext,
+                        guide,
+                    ))
+                })
+                .collect();
+            if !guides.is_empty() {
+                guides.sort_unstable();
+                return guides.into_iter().map(|(_, _, _, guide)| guide).collect();
             }
         }
-        if parent.to_str() == ALLIUM_GAMES_DIR.to_str() {
+        if storage::is_games_dir(&parent) {
             break;
         }
     }
-    guide
+    Vec::new()
 }
@@ -1,5 +1,39 @@
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+
 use serde::{Deserialize, Serialize};
 
+static RIGHT_TO_LEFT: AtomicBool = AtomicBool::new(false);
+static SCREEN_WIDTH: AtomicI32 = AtomicI32::new(0);
+
+thread_local! {
+    static MIRRORING: Cell<bool> = const { Cell::new(true) };
+}
+
+/// Sets whether layouts are mirrored for a right-to-left language, on a screen `width` wide.
+pub fn set_right_to_left(right_to_left: bool, width: u32) {
+    RIGHT_TO_LEFT.store(right_to_left, Ordering::Relaxed);
+    SCREEN_WIDTH.store(width as i32, Ordering::Relaxed);
+}
+
+/// Returns true if layouts are mirrored for a right-to-left language.
+pub fn right_to_left() -> bool {
+    RIGHT_TO_LEFT.load(Ordering::Relaxed)
+}
+
+/// Runs `f` without mirroring points and alignments. Views that lay out their children in screen
+/// coordinates, which are already mirrored, position them inside this.
+pub fn without_mirroring<T>(f: impl FnOnce() -> T) -> T {
+    let mirroring = MIRRORING.replace(false);
+    let result = f();
+    MIRRORING.set(mirroring);
+    result
+}
+
+fn mirroring() -> bool {
+    right_to_left() && MIRRORING.get()
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Point {
     pub x: i32,
@@ -14,6 +48,15 @@ impl Point {
     pub const fn zero() -> Self {
         Self::new(0, 0)
     }
+
+    /// The point mirrored across the screen if the layout is right-to-left.
+    pub fn directional(self) -> Self {
+        if mirroring() {
+            Self::new(SCREEN_WIDTH.load(Ordering::Relaxed) - self.x, self.y)
+        } else {
+            self
+        }
+    }
 }
 
 impl Default for Point {
@@ -170,6 +213,15 @@ impl Alignment {
             Self::Right => -1,
         }
     }
+
+    /// The alignment with left and right swapped if the layout is right-to-left.
+    pub fn directional(self) -> Self {
+        match self {
+            Self::Left if mirroring() => Self::Right,
+            Self::Right if mirroring() => Self::Left,
+            alignment => alignment,
+        }
+    }
 }
 
 impl From<embedded_graphics::text::Alignment> for Alignment {
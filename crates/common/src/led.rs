@@ -0,0 +1,136 @@
+//! Patterns shown on the power LED, on devices whose LED can be controlled.
+
+use std::fs::{self, File};
+use std::time::Duration;
+
+use anyhow::Result;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{ALLIUM_JOB_FINISHED, ALLIUM_LED_SETTINGS};
+
+/// Blinking while the battery is low.
+const LOW_BATTERY_BLINK: (Duration, Duration) =
+    (Duration::from_millis(250), Duration::from_millis(250));
+
+/// Blinking after a background job finished, until the next key press.
+const JOB_FINISHED_BLINK: (Duration, Duration) =
+    (Duration::from_millis(100), Duration::from_millis(2900));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedPattern {
+    Off,
+    On,
+    Blink { on: Duration, off: Duration },
+}
+
+impl LedPattern {
+    fn blink((on, off): (Duration, Duration)) -> Self {
+        LedPattern::Blink { on, off }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedSettings {
+    /// Light the LED while charging.
+    #[serde(default = "LedSettings::enabled")]
+    pub charging: bool,
+    /// Blink the LED quickly while the battery is below the low battery warning level.
+    #[serde(default = "LedSettings::enabled")]
+    pub low_battery: bool,
+    /// Blink the LED slowly when a background job, like downloading box art, finishes.
+    #[serde(default = "LedSettings::enabled")]
+    pub job_finished: bool,
+}
+
+impl Default for LedSettings {
+    fn default() -> Self {
+        Self {
+            charging: true,
+            low_battery: true,
+            job_finished: true,
+        }
+    }
+}
+
+impl LedSettings {
+    fn enabled() -> bool {
+        true
+    }
+
+    pub fn load() -> Result<Self> {
+        if ALLIUM_LED_SETTINGS.exists() {
+            debug!("found state, loading from file");
+            let file = File::open(ALLIUM_LED_SETTINGS.as_path())?;
+            if let Ok(json) = serde_json::from_reader(file) {
+                return Ok(json);
+            }
+            warn!("failed to read LED settings file, removing");
+            fs::remove_file(ALLIUM_LED_SETTINGS.as_path())?;
+        }
+        Ok(Self::default())
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let file = File::create(ALLIUM_LED_SETTINGS.as_path())?;
+        serde_json::to_writer(file, &self)?;
+        Ok(())
+    }
+
+    /// The pattern to show. A low battery takes priority over a finished job, which takes
+    /// priority over charging.
+    pub fn pattern(&self, charging: bool, low_battery: bool, job_finished: bool) -> LedPattern {
+        if self.low_battery && low_battery && !charging {
+            LedPattern::blink(LOW_BATTERY_BLINK)
+        } else if self.job_finished && job_finished {
+            LedPattern::blink(JOB_FINISHED_BLINK)
+        } else if self.charging && charging {
+            LedPattern::On
+        } else {
+            LedPattern::Off
+        }
+    }
+}
+
+/// Marks that a background job finished, so that the LED blinks until the next key press.
+pub fn notify_job_finished() {
+    if let Err(e) = File::create(ALLIUM_JOB_FINISHED.as_path()) {
+        warn!("failed to mark job as finished: {}", e);
+    }
+}
+
+pub fn is_job_finished() -> bool {
+    ALLIUM_JOB_FINISHED.exists()
+}
+
+/// Clears the finished job marker. Returns whether there was one.
+pub fn clear_job_finished() -> Result<bool> {
+    if !ALLIUM_JOB_FINISHED.exists() {
+        return Ok(false);
+    }
+    fs::remove_file(ALLIUM_JOB_FINISHED.as_path())?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern() {
+        let mut settings = LedSettings::default();
+        assert_eq!(settings.pattern(false, false, false), LedPattern::Off);
+        assert_eq!(settings.pattern(true, true, false), LedPattern::On);
+        assert_eq!(
+            settings.pattern(false, true, true),
+            LedPattern::blink(LOW_BATTERY_BLINK)
+        );
+        assert_eq!(
+            settings.pattern(true, false, true),
+            LedPattern::blink(JOB_FINISHED_BLINK)
+        );
+
+        settings.job_finished = false;
+        assert_eq!(settings.pattern(true, false, true), LedPattern::On);
+    }
+}
@@ -1,18 +1,40 @@
 #![deny(clippy::all, unsafe_op_in_unsafe_fn)]
 #![warn(rust_2018_idioms)]
 
+pub mod audio;
 pub mod battery;
+pub mod bluetooth;
+pub mod boxart;
+pub mod combo;
 pub mod command;
+pub mod console_settings;
 pub mod constants;
 pub mod database;
 pub mod display;
 pub mod game_info;
 pub mod geom;
+pub mod led;
 pub mod locale;
+pub mod logging;
+pub mod notifications;
+pub mod onion;
+pub mod performance;
 pub mod platform;
 pub mod power;
 pub mod resources;
 pub mod retroarch;
+pub mod retroarch_hotkeys;
+pub mod retroarch_import;
+pub mod rumble;
+pub mod save_backup;
+pub mod save_state;
+pub mod session_journal;
+pub mod settings_backup;
+pub mod sound;
+pub mod splash;
+pub mod storage;
 pub mod stylesheet;
+pub mod theme_store;
+pub mod ui_keymap;
 pub mod view;
 pub mod wifi;
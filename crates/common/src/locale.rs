@@ -11,18 +11,52 @@ use fluent_templates::{
 };
 use log::{debug, warn};
 use serde::{Deserialize, Serialize};
+use strum::{EnumCount, FromRepr};
 
 use crate::constants::{ALLIUM_LOCALE_SETTINGS, ALLIUM_LOCALES_DIR};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LocaleSettings {
     pub lang: String,
+    /// Languages to try, in order, when a string is missing from `lang`. Other regions of the
+    /// same language and then en-US are always tried afterwards.
+    #[serde(default)]
+    pub fallbacks: Vec<String>,
+    #[serde(default)]
+    pub keyboard_layout: KeyboardLayout,
+    /// Whether search starts in T9 entry mode.
+    #[serde(default)]
+    pub t9_search: bool,
+}
+
+/// Arrangement of the letters on the on-screen keyboard.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize, FromRepr, EnumCount,
+)]
+pub enum KeyboardLayout {
+    #[default]
+    Qwerty,
+    Azerty,
+    Qwertz,
+}
+
+impl KeyboardLayout {
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyboardLayout::Qwerty => "QWERTY",
+            KeyboardLayout::Azerty => "AZERTY",
+            KeyboardLayout::Qwertz => "QWERTZ",
+        }
+    }
 }
 
 impl Default for LocaleSettings {
     fn default() -> Self {
         Self {
             lang: "en-US".into(),
+            fallbacks: Vec::new(),
+            keyboard_layout: KeyboardLayout::default(),
+            t9_search: false,
         }
     }
 }
@@ -55,24 +89,63 @@ impl LocaleSettings {
 pub struct Locale {
     pub loader: ArcLoader,
     pub lang: LanguageIdentifier,
+    /// Languages to look up strings in, starting with `lang`.
+    chain: Vec<LanguageIdentifier>,
 }
 
 impl Locale {
-    pub fn new(lang: &str) -> Self {
+    pub fn new(settings: &LocaleSettings) -> Self {
         let loader = ArcLoader::builder(ALLIUM_LOCALES_DIR.as_path(), langid!("en-US"))
             .customize(|b| b.set_use_isolating(false))
             .build()
             .unwrap();
-        let lang = lang.parse().unwrap();
-        Self { loader, lang }
+        let lang: LanguageIdentifier = settings.lang.parse().unwrap();
+        let fallbacks = settings
+            .fallbacks
+            .iter()
+            .filter_map(|l| match l.parse() {
+                Ok(l) => Some(l),
+                Err(e) => {
+                    warn!("invalid fallback language {:?}: {}", l, e);
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        let available = loader.locales().cloned().collect::<Vec<_>>();
+        let chain = fallback_chain(&lang, &fallbacks, &available, loader.fallback());
+        debug!("locale fallback chain: {:?}", chain);
+        Self {
+            loader,
+            lang,
+            chain,
+        }
     }
 
     pub fn t(&self, key: &str) -> String {
-        self.loader.lookup(&self.lang, key)
+        self.lookup(key, None)
     }
 
     pub fn ta(&self, key: &str, args: &HashMap<Cow<'static, str>, FluentValue<'_>>) -> String {
-        self.loader.lookup_with_args(&self.lang, key, args)
+        self.lookup(key, Some(args))
+    }
+
+    fn lookup(
+        &self,
+        key: &str,
+        args: Option<&HashMap<Cow<'static, str>, FluentValue<'_>>>,
+    ) -> String {
+        self.chain
+            .iter()
+            .find_map(|lang| self.loader.lookup_single_language(lang, key, args).ok())
+            .unwrap_or_else(|| self.loader.lookup_complete(&self.lang, key, args))
+    }
+
+    /// Whether the language is written right-to-left, e.g. Arabic or Hebrew.
+    pub fn is_right_to_left(&self) -> bool {
+        matches!(
+            self.lang.language.as_str(),
+            "ar" | "ckb" | "dv" | "fa" | "he" | "ps" | "sd" | "ug" | "ur" | "yi"
+        )
     }
 
     pub fn language(&self) -> String {
@@ -86,8 +159,79 @@ impl Locale {
     }
 }
 
+/// Languages to look up strings in for `lang`: the language itself, the configured fallbacks,
+/// other available regions of the same language (the bare language first), then `default`.
+fn fallback_chain(
+    lang: &LanguageIdentifier,
+    fallbacks: &[LanguageIdentifier],
+    available: &[LanguageIdentifier],
+    default: &LanguageIdentifier,
+) -> Vec<LanguageIdentifier> {
+    let mut same_language = available
+        .iter()
+        .filter(|l| l.language == lang.language)
+        .cloned()
+        .collect::<Vec<_>>();
+    same_language.sort_by_key(|l| (l.region.is_some(), l.to_string()));
+
+    let mut chain: Vec<LanguageIdentifier> = Vec::new();
+    for l in std::iter::once(lang)
+        .chain(fallbacks)
+        .chain(&same_language)
+        .chain(std::iter::once(default))
+    {
+        if !chain.contains(l) {
+            chain.push(l.clone());
+        }
+    }
+    chain
+}
+
 impl fmt::Debug for Locale {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Locale").field("lang", &self.lang).finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fallback_chain() {
+        let available = [
+            langid!("en-US"),
+            langid!("pt"),
+            langid!("pt-PT"),
+            langid!("es-ES"),
+        ];
+
+        assert_eq!(
+            fallback_chain(&langid!("pt-BR"), &[], &available, &langid!("en-US")),
+            [
+                langid!("pt-BR"),
+                langid!("pt"),
+                langid!("pt-PT"),
+                langid!("en-US")
+            ]
+        );
+        assert_eq!(
+            fallback_chain(
+                &langid!("es-CO"),
+                &[langid!("pt")],
+                &available,
+                &langid!("en-US")
+            ),
+            [
+                langid!("es-CO"),
+                langid!("pt"),
+                langid!("es-ES"),
+                langid!("en-US")
+            ]
+        );
+        assert_eq!(
+            fallback_chain(&langid!("en-US"), &[], &available, &langid!("en-US")),
+            [langid!("en-US")]
+        );
+    }
+}
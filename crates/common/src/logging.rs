@@ -0,0 +1,234 @@
+//! Logger shared by the Allium binaries. Each binary logs to its own file in the logs folder,
+//! which is rotated once it grows too large, so that logs are available after the fact without
+//! filling the SD card.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use anyhow::Result;
+use chrono::Local;
+use log::{LevelFilter, Log, Metadata, Record, debug, warn};
+use serde::{Deserialize, Serialize};
+use strum::{EnumCount, FromRepr};
+
+use crate::constants::{ALLIUM_LOG_SETTINGS, ALLIUM_LOGS_DIR};
+
+/// Size after which a log file is rotated.
+const MAX_LOG_SIZE: u64 = 256 * 1024;
+
+/// Number of rotated log files kept per binary, besides the current one.
+const MAX_LOG_BACKUPS: usize = 2;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogSettings {
+    pub level: LogLevel,
+}
+
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize, FromRepr, EnumCount,
+)]
+pub enum LogLevel {
+    Off,
+    Error,
+    #[default]
+    Warn,
+    Info,
+}
+
+impl LogLevel {
+    pub fn level_filter(self) -> LevelFilter {
+        match self {
+            LogLevel::Off => LevelFilter::Off,
+            LogLevel::Error => LevelFilter::Error,
+            LogLevel::Warn => LevelFilter::Warn,
+            LogLevel::Info => LevelFilter::Info,
+        }
+    }
+}
+
+impl LogSettings {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn load() -> Result<Self> {
+        if ALLIUM_LOG_SETTINGS.exists() {
+            debug!("found state, loading from file");
+            let file = File::open(ALLIUM_LOG_SETTINGS.as_path())?;
+            if let Ok(json) = serde_json::from_reader(file) {
+                return Ok(json);
+            }
+            warn!("failed to read log settings file, removing");
+            fs::remove_file(ALLIUM_LOG_SETTINGS.as_path())?;
+        }
+        Ok(Self::new())
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let file = File::create(ALLIUM_LOG_SETTINGS.as_path())?;
+        serde_json::to_writer(file, &self)?;
+        Ok(())
+    }
+
+    /// Changes the log level of the running process. `RUST_LOG` takes precedence, for debugging.
+    pub fn apply(&self) {
+        let level = env_level_filter().unwrap_or_else(|| self.level.level_filter());
+        log::set_max_level(level);
+    }
+}
+
+fn env_level_filter() -> Option<LevelFilter> {
+    std::env::var("RUST_LOG")
+        .ok()
+        .and_then(|level| LevelFilter::from_str(&level).ok())
+}
+
+/// Sets up logging for the binary with the given name, at the level from the log settings.
+pub fn init(name: &str) -> Result<()> {
+    let file = match LogFile::open(&ALLIUM_LOGS_DIR, name) {
+        Ok(file) => Some(file),
+        Err(e) => {
+            eprintln!("failed to open log file: {e}");
+            None
+        }
+    };
+    log::set_boxed_logger(Box::new(Logger {
+        file: Mutex::new(file),
+    }))?;
+    LogSettings::load()?.apply();
+    Ok(())
+}
+
+struct Logger {
+    file: Mutex<Option<LogFile>>,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let line = format!(
+            "{} {:<5} [{}] {}\n",
+            Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+
+        eprint!("{line}");
+        if let Ok(mut file) = self.file.lock()
+            && let Some(file) = file.as_mut()
+            && let Err(e) = file.write(line.as_bytes())
+        {
+            eprintln!("failed to write log file: {e}");
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock()
+            && let Some(file) = file.as_mut()
+        {
+            file.file.flush().ok();
+        }
+    }
+}
+
+struct LogFile {
+    dir: PathBuf,
+    name: String,
+    file: File,
+    size: u64,
+}
+
+impl LogFile {
+    fn open(dir: &Path, name: &str) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+        let path = log_path(dir, name, 0);
+        if path.metadata().is_ok_and(|m| m.len() >= MAX_LOG_SIZE) {
+            rotate(dir, name)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            name: name.to_string(),
+            file,
+            size,
+        })
+    }
+
+    fn write(&mut self, buf: &[u8]) -> io::Result<()> {
+        if self.size >= MAX_LOG_SIZE {
+            rotate(&self.dir, &self.name).map_err(io::Error::other)?;
+            self.file = File::create(log_path(&self.dir, &self.name, 0))?;
+            self.size = 0;
+        }
+        self.file.write_all(buf)?;
+        self.size += buf.len() as u64;
+        Ok(())
+    }
+}
+
+/// Path of a binary's log file. Index 0 is the current file, higher indices are older.
+fn log_path(dir: &Path, name: &str, index: usize) -> PathBuf {
+    if index == 0 {
+        dir.join(format!("{name}.log"))
+    } else {
+        dir.join(format!("{name}.{index}.log"))
+    }
+}
+
+/// Shifts the log files of a binary by one, dropping the oldest.
+fn rotate(dir: &Path, name: &str) -> Result<()> {
+    for index in (0..MAX_LOG_BACKUPS).rev() {
+        let path = log_path(dir, name, index);
+        if path.exists() {
+            fs::rename(&path, log_path(dir, name, index + 1))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn test_rotate() {
+        let dir = env::temp_dir().join("allium-test-logging");
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+        fs::create_dir_all(&dir).unwrap();
+
+        for i in 0..=MAX_LOG_BACKUPS + 1 {
+            fs::write(log_path(&dir, "test", 0), i.to_string()).unwrap();
+            rotate(&dir, "test").unwrap();
+        }
+
+        assert!(!log_path(&dir, "test", 0).exists());
+        assert_eq!(
+            fs::read_to_string(log_path(&dir, "test", 1)).unwrap(),
+            (MAX_LOG_BACKUPS + 1).to_string()
+        );
+        assert_eq!(
+            fs::read_to_string(log_path(&dir, "test", MAX_LOG_BACKUPS)).unwrap(),
+            "2"
+        );
+        assert!(!log_path(&dir, "test", MAX_LOG_BACKUPS + 1).exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
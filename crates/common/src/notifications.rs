@@ -0,0 +1,123 @@
+//! History of the toasts shown by the launcher, so that messages from background jobs can be read
+//! after they are gone.
+
+use std::collections::VecDeque;
+use std::fs::{self, File};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::ALLIUM_NOTIFICATIONS;
+
+/// Most notifications kept. Older ones are dropped first.
+const MAX_NOTIFICATIONS: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Notification {
+    pub text: String,
+    pub time: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationHistory {
+    /// Oldest first.
+    notifications: VecDeque<Notification>,
+    /// How many of the latest notifications haven't been seen in the history yet.
+    #[serde(default)]
+    unread: usize,
+}
+
+impl NotificationHistory {
+    pub fn load() -> Result<Self> {
+        if !ALLIUM_NOTIFICATIONS.exists() {
+            return Ok(Self::default());
+        }
+        let file = File::open(ALLIUM_NOTIFICATIONS.as_path())?;
+        match serde_json::from_reader(file) {
+            Ok(history) => Ok(history),
+            Err(e) => {
+                warn!("failed to read notification history, removing: {}", e);
+                fs::remove_file(ALLIUM_NOTIFICATIONS.as_path())?;
+                Ok(Self::default())
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let file = File::create(ALLIUM_NOTIFICATIONS.as_path())?;
+        serde_json::to_writer(file, self)?;
+        Ok(())
+    }
+
+    pub fn push(&mut self, text: String) {
+        if self.notifications.len() >= MAX_NOTIFICATIONS {
+            self.notifications.pop_front();
+        }
+        self.notifications.push_back(Notification {
+            text,
+            time: Utc::now(),
+        });
+        self.unread = (self.unread + 1).min(self.notifications.len());
+    }
+
+    /// Replaces the latest notification, e.g. when a progress toast is updated.
+    pub fn replace_last(&mut self, text: String) {
+        match self.notifications.back_mut() {
+            Some(last) => {
+                last.text = text;
+                last.time = Utc::now();
+            }
+            None => self.push(text),
+        }
+    }
+
+    /// Newest first.
+    pub fn iter(&self) -> impl Iterator<Item = &Notification> {
+        self.notifications.iter().rev()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.notifications.is_empty()
+    }
+
+    pub fn unread(&self) -> usize {
+        self.unread
+    }
+
+    pub fn mark_read(&mut self) {
+        self.unread = 0;
+    }
+
+    pub fn clear(&mut self) {
+        self.notifications.clear();
+        self.unread = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history() {
+        let mut history = NotificationHistory::default();
+        history.push("Verifying 1/2".into());
+        history.replace_last("Verifying 2/2".into());
+        history.push("Done".into());
+        assert_eq!(history.unread(), 2);
+        assert_eq!(
+            history.iter().map(|n| n.text.as_str()).collect::<Vec<_>>(),
+            ["Done", "Verifying 2/2"]
+        );
+
+        history.mark_read();
+        for i in 0..MAX_NOTIFICATIONS {
+            history.push(i.to_string());
+        }
+        assert_eq!(history.iter().count(), MAX_NOTIFICATIONS);
+        assert_eq!(history.iter().last().unwrap().text, "0");
+        assert_eq!(history.unread(), MAX_NOTIFICATIONS);
+    }
+}
@@ -0,0 +1,264 @@
+//! Imports recents, favorites, and play activity from Onion OS, so that switching to Allium keeps
+//! the play history. Onion uses the same `Roms` and `Imgs` layout, so only its lists need
+//! importing.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::Result;
+use chrono::Duration;
+use log::{debug, info, warn};
+use rusqlite::{Connection, OpenFlags};
+use serde::Deserialize;
+
+use crate::constants::{ALLIUM_SD_ROOT, ONION_FAVORITES, ONION_PLAY_ACTIVITY, ONION_RECENTS};
+use crate::database::{Database, NewGame};
+
+/// Where Onion mounts the SD card, which its lists use in absolute paths.
+const ONION_SD_ROOT: &str = "/mnt/SDCARD";
+
+/// An entry of Onion's recents or favorites, which are JSON objects, one per line.
+#[derive(Debug, Deserialize)]
+struct ListEntry {
+    label: Option<String>,
+    rompath: String,
+    imgpath: Option<String>,
+}
+
+/// A game's play activity, from Onion's play activity database.
+#[derive(Debug)]
+struct PlayActivity {
+    name: Option<String>,
+    path: String,
+    play_count: i64,
+    /// Play time in seconds.
+    play_time: i64,
+}
+
+#[derive(Debug, Default)]
+struct OnionGame {
+    name: Option<String>,
+    image: Option<PathBuf>,
+    play_count: i64,
+    /// Play time in seconds.
+    play_time: i64,
+    favorite: bool,
+}
+
+/// Imports Onion's lists into the database, once. Returns the number of games imported.
+pub fn import(database: &Database) -> Result<usize> {
+    if database.has_imported_onion()? {
+        return Ok(0);
+    }
+    let lists = [&*ONION_RECENTS, &*ONION_FAVORITES, &*ONION_PLAY_ACTIVITY];
+    if !lists.iter().any(|path| path.exists()) {
+        return Ok(0);
+    }
+    info!("importing play history from Onion OS");
+
+    let mut games: HashMap<PathBuf, OnionGame> = HashMap::new();
+
+    // Most recent first
+    let recents = read_list(&ONION_RECENTS)?;
+    let recents: Vec<PathBuf> = recents
+        .into_iter()
+        .map(|entry| {
+            let path = resolve(&entry.rompath, &ALLIUM_SD_ROOT);
+            let game = games.entry(path.clone()).or_default();
+            game.name = entry.label;
+            game.image = entry.imgpath.map(|image| resolve(&image, &ALLIUM_SD_ROOT));
+            path
+        })
+        .collect();
+
+    for entry in read_list(&ONION_FAVORITES)? {
+        let game = games
+            .entry(resolve(&entry.rompath, &ALLIUM_SD_ROOT))
+            .or_default();
+        game.name = game.name.take().or(entry.label);
+        game.image = game
+            .image
+            .take()
+            .or_else(|| entry.imgpath.map(|image| resolve(&image, &ALLIUM_SD_ROOT)));
+        game.favorite = true;
+    }
+
+    if ONION_PLAY_ACTIVITY.exists() {
+        match read_play_activity(&ONION_PLAY_ACTIVITY) {
+            Ok(activity) => {
+                for activity in activity {
+                    let game = games
+                        .entry(resolve(&activity.path, &ALLIUM_SD_ROOT))
+                        .or_default();
+                    game.name = game.name.take().or(activity.name);
+                    game.play_count = activity.play_count;
+                    game.play_time = activity.play_time;
+                }
+            }
+            Err(e) => warn!("failed to read Onion play activity: {}", e),
+        }
+    }
+
+    // Games that aren't recent go first, then recents from oldest to newest, so that the most
+    // recent game ends up at the top
+    let mut paths: Vec<PathBuf> = games
+        .keys()
+        .filter(|path| !recents.contains(path))
+        .cloned()
+        .collect();
+    paths.sort_unstable();
+    let mut count = 0;
+    for (path, recent) in paths
+        .into_iter()
+        .map(|path| (path, false))
+        .chain(recents.into_iter().rev().map(|path| (path, true)))
+    {
+        let Some(game) = games.remove(&path) else {
+            continue;
+        };
+        if !path.exists() {
+            debug!("skipping missing Onion game {}", path.display());
+            continue;
+        }
+        let name = game.name.unwrap_or_else(|| {
+            path.file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned()
+        });
+        database.import_game(
+            &NewGame {
+                name,
+                image: game.image.filter(|image| image.is_file()),
+                path,
+                core: None,
+                rating: None,
+                release_date: None,
+                developer: None,
+                publisher: None,
+                genres: Vec::new(),
+                favorite: game.favorite,
+            },
+            // Recents are at least played once
+            game.play_count.max(recent as i64),
+            Duration::seconds(game.play_time),
+            recent,
+        )?;
+        count += 1;
+    }
+
+    database.set_has_imported_onion(true)?;
+    info!("imported {} games from Onion OS", count);
+    Ok(count)
+}
+
+fn read_list(path: &Path) -> Result<Vec<ListEntry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(parse_list(&fs::read_to_string(path)?))
+}
+
+fn parse_list(list: &str) -> Vec<ListEntry> {
+    list.lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                warn!("skipping invalid Onion list entry {:?}: {}", line, e);
+                None
+            }
+        })
+        .collect()
+}
+
+fn read_play_activity(path: &Path) -> Result<Vec<PlayActivity>> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let mut stmt = conn.prepare(
+        "
+SELECT rom.name, rom.file_path, COUNT(play_activity.rom_id), COALESCE(SUM(play_activity.play_time), 0)
+FROM rom JOIN play_activity ON play_activity.rom_id = rom.id
+WHERE rom.file_path IS NOT NULL
+GROUP BY rom.id",
+    )?;
+    let activity = stmt
+        .query_map([], |row| {
+            Ok(PlayActivity {
+                name: row.get(0)?,
+                path: row.get(1)?,
+                play_count: row.get(2)?,
+                play_time: row.get(3)?,
+            })
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(activity)
+}
+
+/// Resolves a path from Onion's lists to a path on the SD card. Onion paths are absolute, or
+/// relative to the SD card, and often go through its emulator folders, e.g.
+/// `/mnt/SDCARD/Emu/GBA/../../Roms/GBA/Game.gba`.
+fn resolve(path: &str, sd_root: &Path) -> PathBuf {
+    let path = Path::new(path);
+    let path = path.strip_prefix(ONION_SD_ROOT).unwrap_or(path);
+    let mut resolved = sd_root.to_path_buf();
+    let mut depth = 0;
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => {
+                resolved.push(name);
+                depth += 1;
+            }
+            Component::ParentDir if depth > 0 => {
+                resolved.pop();
+                depth -= 1;
+            }
+            _ => {}
+        }
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let sd_root = Path::new("/sd");
+        assert_eq!(
+            resolve("/mnt/SDCARD/Emu/GBA/../../Roms/GBA/Game.gba", sd_root),
+            Path::new("/sd/Roms/GBA/Game.gba")
+        );
+        assert_eq!(
+            resolve("/mnt/SDCARD/Roms/GBA/Imgs/Game.png", sd_root),
+            Path::new("/sd/Roms/GBA/Imgs/Game.png")
+        );
+        assert_eq!(
+            resolve("../../Roms/GB/Game.gb", sd_root),
+            Path::new("/sd/Roms/GB/Game.gb")
+        );
+        assert_eq!(
+            resolve("Roms/GB/./Game.gb", sd_root),
+            Path::new("/sd/Roms/GB/Game.gb")
+        );
+    }
+
+    #[test]
+    fn test_parse_list() {
+        let list = r#"{"label":"Pokemon Emerald","rompath":"/mnt/SDCARD/Emu/GBA/../../Roms/GBA/Pokemon Emerald.gba","imgpath":"/mnt/SDCARD/Roms/GBA/Imgs/Pokemon Emerald.png","launch":"/mnt/SDCARD/Emu/GBA/launch.sh","type":5}
+not json
+
+{"label":"Tetris","rompath":"/mnt/SDCARD/Roms/GB/Tetris.gb","type":5}
+"#;
+        let entries = parse_list(list);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].label.as_deref(), Some("Pokemon Emerald"));
+        assert_eq!(
+            entries[0].imgpath.as_deref(),
+            Some("/mnt/SDCARD/Roms/GBA/Imgs/Pokemon Emerald.png")
+        );
+        assert_eq!(entries[1].rompath, "/mnt/SDCARD/Roms/GB/Tetris.gb");
+        assert_eq!(entries[1].imgpath, None);
+    }
+}
@@ -0,0 +1,145 @@
+use std::fs::{self, File};
+
+use anyhow::Result;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use strum::{EnumCount, FromRepr};
+
+use crate::constants::ALLIUM_PERFORMANCE_SETTINGS;
+use crate::locale::Locale;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PerformanceSettings {
+    pub profile: PowerProfile,
+}
+
+impl PerformanceSettings {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn load() -> Result<Self> {
+        if ALLIUM_PERFORMANCE_SETTINGS.exists() {
+            debug!("found state, loading from file");
+            let file = File::open(ALLIUM_PERFORMANCE_SETTINGS.as_path())?;
+            if let Ok(json) = serde_json::from_reader(file) {
+                return Ok(json);
+            }
+            warn!("failed to read performance file, removing");
+            fs::remove_file(ALLIUM_PERFORMANCE_SETTINGS.as_path())?;
+        }
+        Ok(Self::new())
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let file = File::create(ALLIUM_PERFORMANCE_SETTINGS.as_path())?;
+        serde_json::to_writer(file, &self)?;
+        Ok(())
+    }
+
+    /// CPU profile to use for a game, falling back to the global profile.
+    pub fn cpu_profile(&self, game: CpuProfile) -> CpuProfile {
+        match game {
+            CpuProfile::Default => self.profile.cpu_profile(),
+            game => game,
+        }
+    }
+}
+
+/// Global profile bundling the CPU, backlight and WiFi settings.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize, FromRepr, EnumCount,
+)]
+pub enum PowerProfile {
+    /// Cap the CPU frequency and backlight, and turn off WiFi.
+    BatterySaver,
+    #[default]
+    Balanced,
+    /// Run the CPU at full speed.
+    Performance,
+}
+
+impl PowerProfile {
+    pub fn cpu_profile(self) -> CpuProfile {
+        match self {
+            PowerProfile::BatterySaver => CpuProfile::Powersave,
+            PowerProfile::Balanced => CpuProfile::Default,
+            PowerProfile::Performance => CpuProfile::Performance,
+        }
+    }
+
+    /// Highest backlight level allowed, in percent.
+    pub fn max_brightness(self) -> u8 {
+        match self {
+            PowerProfile::BatterySaver => 30,
+            PowerProfile::Balanced | PowerProfile::Performance => 100,
+        }
+    }
+
+    /// Whether WiFi may be turned on.
+    pub fn wifi(self) -> bool {
+        !matches!(self, PowerProfile::BatterySaver)
+    }
+
+    pub fn next(self) -> Self {
+        Self::from_repr((self as usize + 1) % Self::COUNT).unwrap()
+    }
+
+    pub fn prev(self) -> Self {
+        Self::from_repr((self as usize + Self::COUNT - 1) % Self::COUNT).unwrap()
+    }
+}
+
+/// CPU frequency scaling profile used while a game is running.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize, FromRepr, EnumCount,
+)]
+pub enum CpuProfile {
+    /// Use the global power profile. For the global profile, leave the CPU at the system
+    /// defaults.
+    #[default]
+    Default,
+    /// Cap the CPU frequency to save battery, e.g. for handheld consoles.
+    Powersave,
+    /// Scale the frequency on demand, up to a moderate cap.
+    Balanced,
+    /// Run the CPU at full speed, e.g. for demanding consoles like the PS1.
+    Performance,
+}
+
+impl CpuProfile {
+    /// Governor to set in cpufreq.
+    pub fn governor(self) -> &'static str {
+        match self {
+            CpuProfile::Default | CpuProfile::Powersave | CpuProfile::Balanced => "ondemand",
+            CpuProfile::Performance => "performance",
+        }
+    }
+
+    /// Maximum CPU frequency in kHz, or `None` for the hardware maximum.
+    pub fn max_frequency(self) -> Option<u32> {
+        match self {
+            CpuProfile::Default | CpuProfile::Performance => None,
+            CpuProfile::Powersave => Some(800_000),
+            CpuProfile::Balanced => Some(1_200_000),
+        }
+    }
+
+    /// Name of the profile in the UI.
+    pub fn text(self, locale: &Locale) -> String {
+        match self {
+            CpuProfile::Default => locale.t("cpu-profile-default"),
+            CpuProfile::Powersave => locale.t("cpu-profile-powersave"),
+            CpuProfile::Balanced => locale.t("cpu-profile-balanced"),
+            CpuProfile::Performance => locale.t("cpu-profile-performance"),
+        }
+    }
+
+    pub fn next(self) -> Self {
+        Self::from_repr((self as usize + 1) % Self::COUNT).unwrap()
+    }
+
+    pub fn prev(self) -> Self {
+        Self::from_repr((self as usize + Self::COUNT - 1) % Self::COUNT).unwrap()
+    }
+}
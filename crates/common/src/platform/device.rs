@@ -0,0 +1,64 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use crate::display::rotation::Rotation;
+
+/// The handhelds that Allium runs on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceModel {
+    Miyoo283,
+    Miyoo285,
+    Miyoo354,
+    Rg35xx,
+    TrimuiSmartPro,
+}
+
+static CURRENT: LazyLock<DeviceModel> = LazyLock::new(DeviceModel::detect);
+
+impl DeviceModel {
+    /// Model of the device that Allium is running on.
+    pub fn current() -> DeviceModel {
+        *CURRENT
+    }
+
+    fn detect() -> DeviceModel {
+        // Checked before the Flip, as other devices also have more than one input device
+        if fs::read_to_string("/proc/device-tree/model").is_ok_and(|model| model.contains("RG35XX"))
+        {
+            DeviceModel::Rg35xx
+        } else if Path::new("/usr/trimui").exists() {
+            DeviceModel::TrimuiSmartPro
+        } else if Path::new("/dev/input/event1").exists() {
+            DeviceModel::Miyoo285
+        } else if Path::new("/customer/app/axp_test").exists() {
+            DeviceModel::Miyoo354
+        } else {
+            DeviceModel::Miyoo283
+        }
+    }
+
+    /// How the panel is mounted, relative to how the device is held.
+    pub fn panel_rotation(self) -> Rotation {
+        match self {
+            // The Miyoo's panel is mounted upside down
+            DeviceModel::Miyoo283 | DeviceModel::Miyoo285 | DeviceModel::Miyoo354 => {
+                Rotation::Rotate180
+            }
+            DeviceModel::Rg35xx | DeviceModel::TrimuiSmartPro => Rotation::Rotate0,
+        }
+    }
+}
+
+impl fmt::Display for DeviceModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeviceModel::Miyoo283 => write!(f, "Miyoo Mini (MY283)"),
+            DeviceModel::Miyoo285 => write!(f, "Miyoo Mini Flip (MY285)"),
+            DeviceModel::Miyoo354 => write!(f, "Miyoo Mini+ (MY354)"),
+            DeviceModel::Rg35xx => write!(f, "Anbernic RG35XX"),
+            DeviceModel::TrimuiSmartPro => write!(f, "TrimUI Smart Pro"),
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! Volume set on an ALSA mixer control through `amixer`.
+
+use anyhow::Result;
+use log::debug;
+use std::process::Command;
+
+use crate::audio::AudioSettings;
+
+pub const MIN_VOLUME: i32 = 0;
+pub const MAX_VOLUME: i32 = 20;
+
+/// Sets the volume on an ALSA mixer control, following the volume curve.
+pub fn set_volume(control: &str, volume: i32) -> Result<()> {
+    let settings = AudioSettings::load()?;
+    let volume = volume.clamp(MIN_VOLUME, MAX_VOLUME);
+    let percentage =
+        settings.volume_curve.apply(volume, MAX_VOLUME) * settings.max_volume.clamp(0, 100) as f32;
+    debug!("set alsa volume: {}%", percentage as i32);
+    Command::new("amixer")
+        .arg("-q")
+        .arg("sset")
+        .arg(control)
+        .arg(format!("{}%", percentage as i32))
+        .spawn()?
+        .wait()?;
+    Ok(())
+}
@@ -0,0 +1,37 @@
+//! Backlight and blanking through sysfs, for devices without a vendor display driver.
+
+use std::fs::{self, File};
+use std::io::Write;
+
+use anyhow::{Context, Result};
+
+/// Brightness of a sysfs backlight, as a percentage of its maximum.
+pub fn get_backlight(backlight: &str) -> Result<u8> {
+    let max = read_backlight(backlight, "max_brightness")?.max(1);
+    let brightness = read_backlight(backlight, "brightness")?;
+    Ok((brightness * 100 / max) as u8)
+}
+
+pub fn set_backlight(backlight: &str, brightness: u8) -> Result<()> {
+    let max = read_backlight(backlight, "max_brightness")?;
+    let brightness = u32::from(brightness.clamp(3, 100)) * max / 100;
+    File::create(format!("{backlight}/brightness"))
+        .context("failed to open backlight/brightness")?
+        .write_all(brightness.to_string().as_bytes())?;
+    Ok(())
+}
+
+fn read_backlight(backlight: &str, attribute: &str) -> Result<u32> {
+    Ok(fs::read_to_string(format!("{backlight}/{attribute}"))
+        .with_context(|| format!("failed to read backlight/{attribute}"))?
+        .trim()
+        .parse()?)
+}
+
+/// Blanks the framebuffer.
+pub fn blank_framebuffer(blank: bool) -> Result<()> {
+    File::create("/sys/class/graphics/fb0/blank")
+        .context("failed to open fb0/blank")?
+        .write_all(if blank { b"1" } else { b"0" })?;
+    Ok(())
+}
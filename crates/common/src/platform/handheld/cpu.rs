@@ -0,0 +1,35 @@
+use std::fs::{self, File};
+use std::io::Write;
+
+use anyhow::{Context, Result};
+
+const SCALING_GOVERNOR: &str = "/sys/devices/system/cpu/cpu0/cpufreq/scaling_governor";
+const SCALING_MAX_FREQ: &str = "/sys/devices/system/cpu/cpu0/cpufreq/scaling_max_freq";
+const CPUINFO_MAX_FREQ: &str = "/sys/devices/system/cpu/cpu0/cpufreq/cpuinfo_max_freq";
+
+pub fn get_governor() -> Result<String> {
+    Ok(fs::read_to_string(SCALING_GOVERNOR)?.trim().to_string())
+}
+
+pub fn set_governor(governor: &str) -> Result<()> {
+    File::create(SCALING_GOVERNOR)
+        .context("failed to open cpufreq/scaling_governor")?
+        .write_all(governor.as_bytes())?;
+    Ok(())
+}
+
+/// Highest frequency supported by the CPU, in kHz.
+pub fn get_hardware_max_frequency() -> Result<u32> {
+    Ok(fs::read_to_string(CPUINFO_MAX_FREQ)
+        .context("failed to read cpufreq/cpuinfo_max_freq")?
+        .trim()
+        .parse()?)
+}
+
+/// Sets the frequency the governor may scale up to, in kHz.
+pub fn set_max_frequency(frequency: u32) -> Result<()> {
+    File::create(SCALING_MAX_FREQ)
+        .context("failed to open cpufreq/scaling_max_freq")?
+        .write_all(frequency.to_string().as_bytes())?;
+    Ok(())
+}
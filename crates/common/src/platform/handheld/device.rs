@@ -0,0 +1,221 @@
+use evdev::KeyCode;
+
+use crate::platform::Key;
+use crate::platform::device::DeviceModel;
+
+/// Hardware that differs between the supported handhelds. Everything device specific is read
+/// from here, so that supporting another device is a matter of adding a profile.
+#[derive(Debug)]
+pub struct DeviceProfile {
+    pub model: DeviceModel,
+    pub framebuffer: &'static str,
+    /// Codes of the built in buttons. Keyboards and gamepads that are plugged in fall back to
+    /// `KEYBOARD_KEYMAP` and `GAMEPAD_KEYMAP`.
+    pub keymap: &'static [(KeyCode, Key)],
+    /// Hall sensor that reports whether the lid is open, on devices that have one.
+    pub lid_switch: Option<&'static str>,
+    pub screen: ScreenBackend,
+    pub battery: BatteryBackend,
+    pub volume: VolumeBackend,
+    /// Command that turns the device off.
+    pub power_off: &'static str,
+    pub has_wifi: bool,
+    /// Vibration motor, on devices that have one.
+    pub rumble: Option<RumbleBackend>,
+    /// LED class device of the power LED, e.g. `/sys/class/leds/led1`, on devices where it can
+    /// be controlled.
+    pub led: Option<&'static str>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenBackend {
+    /// Backlight PWM and SigmaStar display driver, which also sets the colour settings.
+    SigmaStar,
+    /// Backlight and blanking through sysfs. Colour settings are not supported.
+    Sysfs { backlight: &'static str },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatteryBackend {
+    /// Battery voltage read from the SAR ADC.
+    Sar,
+    /// Charge reported by the power management chip, through `axp_test`.
+    Axp223,
+    /// Charge reported by the kernel's power supply class, e.g. `/sys/class/power_supply/battery`.
+    PowerSupply(&'static str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RumbleBackend {
+    /// Motor switched on and off through a sysfs GPIO pin.
+    Gpio { pin: u64, active_low: bool },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeBackend {
+    /// Volume is set by a hardware wheel, so there is nothing to control.
+    Hardware,
+    /// Volume and audio settings are set through `myctl`.
+    Myctl,
+    /// Volume is set on an ALSA mixer control through `amixer`. Audio settings are not supported.
+    Alsa { control: &'static str },
+}
+
+/// The Miyoo reports its buttons as keyboard keys, so keyboards are mapped the same way.
+const KEYBOARD_KEYMAP: &[(KeyCode, Key)] = &[
+    (KeyCode::KEY_UP, Key::Up),
+    (KeyCode::KEY_DOWN, Key::Down),
+    (KeyCode::KEY_LEFT, Key::Left),
+    (KeyCode::KEY_RIGHT, Key::Right),
+    (KeyCode::KEY_SPACE, Key::A),
+    (KeyCode::KEY_LEFTCTRL, Key::B),
+    (KeyCode::KEY_LEFTSHIFT, Key::X),
+    (KeyCode::KEY_LEFTALT, Key::Y),
+    (KeyCode::KEY_ENTER, Key::Start),
+    (KeyCode::KEY_RIGHTCTRL, Key::Select),
+    (KeyCode::KEY_E, Key::L),
+    (KeyCode::KEY_T, Key::R),
+    (KeyCode::KEY_ESC, Key::Menu),
+    (KeyCode::KEY_TAB, Key::L2),
+    (KeyCode::KEY_BACKSPACE, Key::R2),
+    (KeyCode::KEY_POWER, Key::Power),
+    (KeyCode::KEY_VOLUMEDOWN, Key::VolDown),
+    (KeyCode::KEY_VOLUMEUP, Key::VolUp),
+];
+
+/// Gamepads with Nintendo's layout. The d-pad, if it is reported as a hat, and the analog sticks
+/// are mapped when polling.
+const GAMEPAD_KEYMAP: &[(KeyCode, Key)] = &[
+    (KeyCode::BTN_EAST, Key::A),
+    (KeyCode::BTN_SOUTH, Key::B),
+    (KeyCode::BTN_NORTH, Key::X),
+    (KeyCode::BTN_WEST, Key::Y),
+    (KeyCode::BTN_START, Key::Start),
+    (KeyCode::BTN_SELECT, Key::Select),
+    (KeyCode::BTN_TL, Key::L),
+    (KeyCode::BTN_TR, Key::R),
+    (KeyCode::BTN_TL2, Key::L2),
+    (KeyCode::BTN_TR2, Key::R2),
+    (KeyCode::BTN_THUMBL, Key::L3),
+    (KeyCode::BTN_THUMBR, Key::R3),
+    (KeyCode::BTN_MODE, Key::Menu),
+    (KeyCode::KEY_POWER, Key::Power),
+    (KeyCode::KEY_VOLUMEDOWN, Key::VolDown),
+    (KeyCode::KEY_VOLUMEUP, Key::VolUp),
+];
+
+static MIYOO_MINI: DeviceProfile = DeviceProfile {
+    model: DeviceModel::Miyoo283,
+    framebuffer: "/dev/fb0",
+    keymap: KEYBOARD_KEYMAP,
+    lid_switch: None,
+    screen: ScreenBackend::SigmaStar,
+    battery: BatteryBackend::Sar,
+    volume: VolumeBackend::Hardware,
+    power_off: "reboot",
+    has_wifi: false,
+    rumble: Some(RumbleBackend::Gpio {
+        pin: 48,
+        active_low: true,
+    }),
+    led: None,
+};
+
+static MIYOO_MINI_PLUS: DeviceProfile = DeviceProfile {
+    model: DeviceModel::Miyoo354,
+    framebuffer: "/dev/fb0",
+    keymap: KEYBOARD_KEYMAP,
+    lid_switch: None,
+    screen: ScreenBackend::SigmaStar,
+    battery: BatteryBackend::Axp223,
+    volume: VolumeBackend::Myctl,
+    power_off: "poweroff",
+    has_wifi: true,
+    rumble: Some(RumbleBackend::Gpio {
+        pin: 48,
+        active_low: true,
+    }),
+    led: Some("/sys/class/leds/led1"),
+};
+
+static MIYOO_MINI_FLIP: DeviceProfile = DeviceProfile {
+    model: DeviceModel::Miyoo285,
+    framebuffer: "/dev/fb0",
+    keymap: KEYBOARD_KEYMAP,
+    lid_switch: Some("/sys/devices/soc0/soc/soc:hall-mh248/hallvalue"),
+    screen: ScreenBackend::SigmaStar,
+    battery: BatteryBackend::Axp223,
+    volume: VolumeBackend::Myctl,
+    power_off: "poweroff",
+    has_wifi: true,
+    rumble: Some(RumbleBackend::Gpio {
+        pin: 48,
+        active_low: true,
+    }),
+    led: None,
+};
+
+static RG35XX: DeviceProfile = DeviceProfile {
+    model: DeviceModel::Rg35xx,
+    framebuffer: "/dev/fb0",
+    keymap: GAMEPAD_KEYMAP,
+    lid_switch: None,
+    screen: ScreenBackend::Sysfs {
+        backlight: "/sys/class/backlight/backlight",
+    },
+    battery: BatteryBackend::PowerSupply("/sys/class/power_supply/battery"),
+    volume: VolumeBackend::Alsa {
+        control: "DAC Volume",
+    },
+    power_off: "poweroff",
+    // Wi-Fi is only managed on the Miyoo so far
+    has_wifi: false,
+    rumble: None,
+    led: None,
+};
+
+static TRIMUI_SMART_PRO: DeviceProfile = DeviceProfile {
+    model: DeviceModel::TrimuiSmartPro,
+    framebuffer: "/dev/fb0",
+    keymap: GAMEPAD_KEYMAP,
+    lid_switch: None,
+    screen: ScreenBackend::Sysfs {
+        backlight: "/sys/class/backlight/backlight0",
+    },
+    battery: BatteryBackend::PowerSupply("/sys/class/power_supply/axp2202-battery"),
+    volume: VolumeBackend::Alsa {
+        control: "Soft Volume Master",
+    },
+    power_off: "poweroff",
+    has_wifi: false,
+    rumble: Some(RumbleBackend::Gpio {
+        pin: 227,
+        active_low: false,
+    }),
+    led: None,
+};
+
+impl DeviceProfile {
+    /// Profile of the device that Allium is running on.
+    pub fn current() -> &'static DeviceProfile {
+        match DeviceModel::current() {
+            DeviceModel::Miyoo283 => &MIYOO_MINI,
+            DeviceModel::Miyoo285 => &MIYOO_MINI_FLIP,
+            DeviceModel::Miyoo354 => &MIYOO_MINI_PLUS,
+            DeviceModel::Rg35xx => &RG35XX,
+            DeviceModel::TrimuiSmartPro => &TRIMUI_SMART_PRO,
+        }
+    }
+
+    pub fn has_lid(&self) -> bool {
+        self.lid_switch.is_some()
+    }
+
+    pub fn key(&self, code: u16) -> Key {
+        [self.keymap, KEYBOARD_KEYMAP, GAMEPAD_KEYMAP]
+            .into_iter()
+            .flatten()
+            .find(|(key_code, _)| key_code.0 == code)
+            .map_or(Key::Unknown, |(_, key)| *key)
+    }
+}
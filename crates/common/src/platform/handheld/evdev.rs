@@ -0,0 +1,297 @@
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::future::poll_fn;
+use std::io::Read;
+use std::path::PathBuf;
+use std::task::Poll;
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, bail};
+use evdev::{AbsoluteAxisCode, Device, EventStream, EventType, SwitchCode};
+use log::{info, warn};
+
+use crate::constants::MAXIMUM_FRAME_TIME;
+use crate::platform::handheld::device::DeviceProfile;
+use crate::platform::{Key, KeyEvent};
+
+/// How long an axis direction is held before it starts repeating. The kernel only repeats
+/// keys, so axes are repeated here to match.
+const AXIS_REPEAT_DELAY: Duration = Duration::from_millis(300);
+const AXIS_REPEAT_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Axes that are turned into direction keys, with the keys for their negative and positive
+/// directions: the d-pad, on devices that report it as a hat, and the analog sticks.
+const AXES: [(AbsoluteAxisCode, Key, Key); 6] = [
+    (AbsoluteAxisCode::ABS_HAT0X, Key::Left, Key::Right),
+    (AbsoluteAxisCode::ABS_HAT0Y, Key::Up, Key::Down),
+    (
+        AbsoluteAxisCode::ABS_X,
+        Key::LeftStickLeft,
+        Key::LeftStickRight,
+    ),
+    (
+        AbsoluteAxisCode::ABS_Y,
+        Key::LeftStickUp,
+        Key::LeftStickDown,
+    ),
+    (
+        AbsoluteAxisCode::ABS_RX,
+        Key::RightStickLeft,
+        Key::RightStickRight,
+    ),
+    (
+        AbsoluteAxisCode::ABS_RY,
+        Key::RightStickUp,
+        Key::RightStickDown,
+    ),
+];
+
+/// Where input devices appear, including those plugged in later, e.g. USB keyboards over OTG.
+const INPUT_DIR: &str = "/dev/input";
+/// How often `INPUT_DIR` is checked for newly plugged in devices.
+const HOTPLUG_SCAN_INTERVAL: Duration = Duration::from_secs(2);
+
+/// An open input device.
+struct Input {
+    path: PathBuf,
+    events: EventStream,
+    /// Centre of each of `AXES`, and how far from it the axis is pushed in a direction.
+    axis_ranges: [(i32, i32); AXES.len()],
+}
+
+impl Input {
+    fn open(path: PathBuf) -> Result<Self> {
+        let events = Device::open(&path)?.into_event_stream()?;
+
+        // Hats only report -1, 0 and 1, so those without axis info are treated as hats
+        let mut axis_ranges = [(0, 0); AXES.len()];
+        if let Ok(absinfo) = events.device().get_absinfo() {
+            for (code, info) in absinfo {
+                if let Some(i) = AXES.iter().position(|(axis, _, _)| *axis == code) {
+                    let centre = (info.minimum() + info.maximum()) / 2;
+                    // Pushed more than halfway
+                    axis_ranges[i] = (centre, (info.maximum() - centre) / 2);
+                }
+            }
+        }
+
+        Ok(Self {
+            path,
+            events,
+            axis_ranges,
+        })
+    }
+}
+
+/// Reads the buttons from every input device, merged into one stream of key events.
+pub struct EvdevKeys {
+    inputs: Vec<Input>,
+    device: &'static DeviceProfile,
+    lid_switch_poller: Option<LidSwitchPoller>,
+    last_scan: Instant,
+    /// Direction each of `AXES` is pushed in: -1, 0 or 1.
+    axes: [i32; AXES.len()],
+    /// Axis direction that is held, and when it next repeats.
+    axis_held: Option<(Key, Instant)>,
+    /// Events that are returned before reading more, when one input produces several.
+    pending: VecDeque<KeyEvent>,
+}
+
+impl EvdevKeys {
+    pub fn new(device: &'static DeviceProfile) -> Result<Self> {
+        let mut keys = Self {
+            inputs: Vec::new(),
+            device,
+            lid_switch_poller: device.lid_switch.map(LidSwitchPoller::new),
+            last_scan: Instant::now(),
+            axes: [0; AXES.len()],
+            axis_held: None,
+            pending: VecDeque::new(),
+        };
+        keys.scan()?;
+        if keys.inputs.is_empty() {
+            bail!("no input devices found in {}", INPUT_DIR);
+        }
+        Ok(keys)
+    }
+
+    /// Opens input devices that aren't open yet.
+    fn scan(&mut self) -> Result<()> {
+        self.last_scan = Instant::now();
+        for entry in fs::read_dir(INPUT_DIR)? {
+            let path = entry?.path();
+            let is_event_device = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("event"));
+            if !is_event_device || self.inputs.iter().any(|input| input.path == path) {
+                continue;
+            }
+            match Input::open(path.clone()) {
+                Ok(input) => {
+                    info!(
+                        "opened input device {}: {}",
+                        path.display(),
+                        input.events.device().name().unwrap_or("unknown")
+                    );
+                    self.inputs.push(input);
+                }
+                Err(e) => warn!("failed to open input device {}: {}", path.display(), e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the headphone jack switch. Devices without one never report headphones.
+    pub fn is_headphones_connected(&self) -> bool {
+        self.inputs.iter().any(|input| {
+            input
+                .events
+                .device()
+                .get_switch_state()
+                .is_ok_and(|switches| switches.contains(SwitchCode::SW_HEADPHONE_INSERT))
+        })
+    }
+
+    pub async fn poll(&mut self) -> KeyEvent {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return event;
+            }
+
+            if let Some(lid_event) = self.lid_switch_poller.as_mut().and_then(|lid| lid.poll()) {
+                info!("Lid event detected: {:?}", lid_event);
+                return lid_event;
+            }
+
+            if self.last_scan.elapsed() >= HOTPLUG_SCAN_INTERVAL
+                && let Err(e) = self.scan()
+            {
+                warn!("failed to scan for input devices: {}", e);
+            }
+
+            let timeout = self
+                .axis_held
+                .map_or(Duration::from_millis(500), |(_, at)| {
+                    at.saturating_duration_since(Instant::now())
+                });
+            let inputs = &mut self.inputs;
+            let next_event = poll_fn(|cx| {
+                for (i, input) in inputs.iter_mut().enumerate() {
+                    if let Poll::Ready(event) = input.events.poll_event(cx) {
+                        return Poll::Ready((i, event));
+                    }
+                }
+                Poll::Pending
+            });
+            let Ok((i, result)) = tokio::time::timeout(timeout, next_event).await else {
+                if let Some((key, at)) = self.axis_held.as_mut() {
+                    *at = Instant::now() + AXIS_REPEAT_INTERVAL;
+                    return KeyEvent::Autorepeat(*key);
+                }
+                continue;
+            };
+            let event = match result {
+                Ok(event) => event,
+                Err(e) => {
+                    // Unplugged
+                    let input = self.inputs.swap_remove(i);
+                    info!("closed input device {}: {}", input.path.display(), e);
+                    continue;
+                }
+            };
+            match event.event_type() {
+                EventType::KEY => {
+                    let key = self.device.key(event.code());
+                    if event.timestamp().elapsed().unwrap() > MAXIMUM_FRAME_TIME {
+                        continue;
+                    }
+                    return match event.value() {
+                        0 => KeyEvent::Released(key),
+                        1 => KeyEvent::Pressed(key),
+                        2 => KeyEvent::Autorepeat(key),
+                        _ => unreachable!(),
+                    };
+                }
+                EventType::ABSOLUTE => {
+                    let code = AbsoluteAxisCode(event.code());
+                    if let Some(axis) = AXES.iter().position(|(c, _, _)| *c == code) {
+                        let (centre, threshold) = self.inputs[i].axis_ranges[axis];
+                        let offset = event.value() - centre;
+                        let direction = if offset.abs() > threshold {
+                            offset.signum()
+                        } else {
+                            0
+                        };
+                        self.move_axis(axis, direction);
+                    }
+                }
+                EventType::SWITCH if event.code() == SwitchCode::SW_HEADPHONE_INSERT.0 => {
+                    info!("Headphone event detected: {}", event.value());
+                    return if event.value() == 0 {
+                        KeyEvent::Released(Key::Headphones)
+                    } else {
+                        KeyEvent::Pressed(Key::Headphones)
+                    };
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Turns a move of one of `AXES` into presses and releases of its direction keys.
+    fn move_axis(&mut self, axis: usize, direction: i32) {
+        let (_, negative, positive) = AXES[axis];
+        let key = |direction: i32| if direction < 0 { negative } else { positive };
+
+        let prev = std::mem::replace(&mut self.axes[axis], direction);
+        if prev == direction {
+            return;
+        }
+        if prev != 0 {
+            let key = key(prev);
+            self.pending.push_back(KeyEvent::Released(key));
+            if self.axis_held.is_some_and(|(held, _)| held == key) {
+                self.axis_held = None;
+            }
+        }
+        if direction != 0 {
+            let key = key(direction);
+            self.pending.push_back(KeyEvent::Pressed(key));
+            self.axis_held = Some((key, Instant::now() + AXIS_REPEAT_DELAY));
+        }
+    }
+}
+
+struct LidSwitchPoller {
+    path: &'static str,
+    is_lid_open: bool,
+}
+
+impl LidSwitchPoller {
+    fn new(path: &'static str) -> Self {
+        let is_lid_open = read_is_lid_open(path).expect("Failed to read lid switch state");
+        Self { path, is_lid_open }
+    }
+
+    fn poll(&mut self) -> Option<KeyEvent> {
+        let is_lid_open = read_is_lid_open(self.path).expect("Failed to read lid switch state");
+        if is_lid_open != self.is_lid_open {
+            self.is_lid_open = is_lid_open;
+            if is_lid_open {
+                Some(KeyEvent::Released(Key::LidClose))
+            } else {
+                Some(KeyEvent::Pressed(Key::LidClose))
+            }
+        } else {
+            None
+        }
+    }
+}
+
+fn read_is_lid_open(path: &str) -> Result<bool> {
+    let mut file = File::open(path).unwrap_or_else(|e| panic!("Failed to open {path}: {e}"));
+    let mut buffer = [0u8; 2];
+    file.read_exact(&mut buffer)?;
+    Ok(buffer[0] == '1' as u8)
+}
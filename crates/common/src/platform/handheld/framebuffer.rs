@@ -0,0 +1,354 @@
+use std::ops::Range;
+use std::os::fd::AsRawFd;
+
+use anyhow::{Result, anyhow, bail};
+use embedded_graphics::Pixel;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+use framebuffer::{Bitfield, Framebuffer};
+use log::{trace, warn};
+
+use crate::display::Display;
+use crate::display::color::Color;
+use crate::display::dirty::DirtyRegion;
+use crate::display::rotation::Rotation;
+use crate::display::settings::DisplaySettings;
+use crate::geom::Rect;
+
+nix::ioctl_write_ptr!(fbio_waitforvsync, b'F', 0x20, u32);
+
+/// Back buffer that everything is drawn to, before it is copied to the framebuffer on flush.
+pub struct Buffer {
+    /// Pixels in panel order.
+    buffer: Vec<u8>,
+    /// Size of the display, which is the panel's size swapped if it is rotated sideways.
+    size: Size,
+    panel_size: Size,
+    rotation: Rotation,
+    bytes_per_pixel: u32,
+    format: PixelFormat,
+    /// Areas drawn since the last flush, in display coordinates.
+    dirty: DirtyRegion,
+}
+
+pub struct FramebufferDisplay {
+    framebuffer: Buffer,
+    iface: Framebuffer,
+    saved: Vec<Vec<u8>>,
+    /// Whether flushing waits for the vertical blank.
+    vsync: bool,
+}
+
+impl FramebufferDisplay {
+    pub fn new(path: &str) -> Result<FramebufferDisplay> {
+        let iface = Framebuffer::new(path)?;
+        trace!(
+            "init fb: var_screen_info: {:?}, fix_screen_info: {:?}",
+            iface.var_screen_info, iface.fix_screen_info,
+        );
+
+        let background = iface.read_frame();
+        let panel_size = Size::new(iface.var_screen_info.xres, iface.var_screen_info.yres);
+        let settings = DisplaySettings::load().unwrap_or_default();
+        let rotation = settings.panel_rotation();
+        let (w, h) = rotation.swap_size(panel_size.width, panel_size.height);
+        let size = Size::new(w, h);
+
+        let (xoffset, yoffset) = (
+            iface.var_screen_info.xoffset as usize,
+            iface.var_screen_info.yoffset as usize,
+        );
+        let width = panel_size.width as usize;
+        let height = panel_size.height as usize;
+        let bytes_per_pixel = iface.var_screen_info.bits_per_pixel / 8;
+        let mut buffer = vec![0; width * height * bytes_per_pixel as usize];
+        let buffer_size = buffer.len();
+        let location = (yoffset * width + xoffset) * bytes_per_pixel as usize;
+        buffer[..].copy_from_slice(&background[location..location + buffer_size]);
+
+        Ok(FramebufferDisplay {
+            framebuffer: Buffer {
+                buffer,
+                size,
+                panel_size,
+                rotation,
+                bytes_per_pixel,
+                format: PixelFormat::new(&iface),
+                dirty: DirtyRegion::new(),
+            },
+            iface,
+            saved: Vec::new(),
+            vsync: settings.vsync,
+        })
+    }
+}
+
+/// Where each channel is in a pixel, which differs between devices.
+#[derive(Debug, Clone, Copy)]
+struct PixelFormat {
+    bytes_per_pixel: usize,
+    red: Channel,
+    green: Channel,
+    blue: Channel,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Channel {
+    offset: u32,
+    max: u32,
+}
+
+impl PixelFormat {
+    fn new(iface: &Framebuffer) -> Self {
+        let info = &iface.var_screen_info;
+        Self {
+            bytes_per_pixel: (info.bits_per_pixel / 8) as usize,
+            red: Channel::new(&info.red),
+            green: Channel::new(&info.green),
+            blue: Channel::new(&info.blue),
+        }
+    }
+
+    fn read(&self, bytes: &[u8]) -> Color {
+        let raw = bytes[..self.bytes_per_pixel]
+            .iter()
+            .rev()
+            .fold(0, |raw, &b| raw << 8 | b as u32);
+        Color::new(self.red.get(raw), self.green.get(raw), self.blue.get(raw))
+    }
+
+    /// Writes the colour, keeping any other bits of the pixel as they were.
+    fn write(&self, bytes: &mut [u8], color: Color) {
+        let bytes = &mut bytes[..self.bytes_per_pixel];
+        let mut raw = bytes.iter().rev().fold(0, |raw, &b| raw << 8 | b as u32);
+        raw = self.red.set(raw, color.r());
+        raw = self.green.set(raw, color.g());
+        raw = self.blue.set(raw, color.b());
+        for byte in bytes {
+            *byte = raw as u8;
+            raw >>= 8;
+        }
+    }
+}
+
+impl Channel {
+    fn new(bitfield: &Bitfield) -> Self {
+        Self {
+            offset: bitfield.offset,
+            max: (1 << bitfield.length) - 1,
+        }
+    }
+
+    fn get(self, raw: u32) -> u8 {
+        ((raw >> self.offset & self.max) * 255 / self.max.max(1)) as u8
+    }
+
+    fn set(self, raw: u32, value: u8) -> u32 {
+        let value = (value as u32 * self.max + 127) / 255;
+        raw & !(self.max << self.offset) | value << self.offset
+    }
+}
+
+impl Buffer {
+    /// Byte ranges of the rows of the buffer covered by an area of the display.
+    fn rows(&self, rect: Rect) -> impl Iterator<Item = Range<usize>> + use<> {
+        let rect = self
+            .rotation
+            .rect_to_panel(rect, self.size.width, self.size.height);
+        let width = self.panel_size.width as usize;
+        let bytespp = self.bytes_per_pixel as usize;
+        (rect.y as usize..rect.bottom() as usize).map(move |y| {
+            let from = (y * width + rect.x as usize) * bytespp;
+            from..from + rect.w as usize * bytespp
+        })
+    }
+}
+
+impl Display for FramebufferDisplay {
+    fn map_pixels<F>(&mut self, mut f: F) -> Result<()>
+    where
+        F: FnMut(Color) -> Color,
+    {
+        let format = self.framebuffer.format;
+        for pixel in self
+            .framebuffer
+            .buffer
+            .chunks_exact_mut(format.bytes_per_pixel)
+        {
+            format.write(pixel, f(format.read(pixel)));
+        }
+        let size = self.framebuffer.size;
+        self.framebuffer
+            .dirty
+            .add(Rect::new(0, 0, size.width, size.height));
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if self.framebuffer.dirty.is_empty() {
+            return Ok(());
+        }
+
+        // The copy is small enough to finish before the panel starts reading the next frame
+        if self.vsync {
+            // SAFETY: the ioctl only reads the display index from the pointer
+            if let Err(e) = unsafe { fbio_waitforvsync(self.iface.device.as_raw_fd(), &0) } {
+                warn!("failed to wait for vsync, disabling: {}", e);
+                self.vsync = false;
+            }
+        }
+
+        let (xoffset, yoffset) = (
+            self.iface.var_screen_info.xoffset as usize,
+            self.iface.var_screen_info.yoffset as usize,
+        );
+        let width = self.framebuffer.panel_size.width as usize;
+        let bytespp = self.framebuffer.bytes_per_pixel as usize;
+        let location = (yoffset * width + xoffset) * bytespp;
+
+        // Only copy the areas that changed
+        for rect in self.framebuffer.dirty.take() {
+            for row in self.framebuffer.rows(rect) {
+                self.iface.frame[location + row.start..location + row.end]
+                    .copy_from_slice(&self.framebuffer.buffer[row]);
+            }
+        }
+        Ok(())
+    }
+
+    fn set_vsync(&mut self, vsync: bool) {
+        self.vsync = vsync;
+    }
+
+    fn save(&mut self) -> Result<()> {
+        self.saved.push(self.framebuffer.buffer.clone());
+        Ok(())
+    }
+
+    fn load(&mut self, mut rect: Rect) -> Result<()> {
+        let Some(ref saved) = self.saved.last() else {
+            bail!("No saved image");
+        };
+
+        let size = self.size();
+        if rect.x < 0
+            || rect.y < 0
+            || rect.x as u32 + rect.w > size.width
+            || rect.y as u32 + rect.h > size.height
+        {
+            warn!(
+                "Area exceeds display bounds: x: {}, y: {}, w: {}, h: {}",
+                rect.x, rect.y, rect.w, rect.h,
+            );
+            rect.x = rect.x.max(0);
+            rect.y = rect.y.max(0);
+            rect.w = rect.w.min(size.width.saturating_sub(rect.x as u32));
+            rect.h = rect.h.min(size.height.saturating_sub(rect.y as u32));
+        }
+
+        for row in self.framebuffer.rows(rect) {
+            self.framebuffer.buffer[row.clone()].copy_from_slice(&saved[row]);
+        }
+        self.framebuffer.dirty.add(rect);
+
+        Ok(())
+    }
+
+    fn pop(&mut self) -> bool {
+        self.saved.pop();
+        !self.saved.is_empty()
+    }
+}
+
+impl DrawTarget for FramebufferDisplay {
+    type Color = Color;
+    type Error = anyhow::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<()>
+    where
+        I: IntoIterator<Item = embedded_graphics::Pixel<Self::Color>>,
+    {
+        self.framebuffer
+            .draw_iter(pixels)
+            .map_err(|e| anyhow!("failed to draw: {}", e))
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<()>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        self.framebuffer
+            .fill_contiguous(area, colors)
+            .map_err(|e| anyhow!("failed to draw: {}", e))
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<()> {
+        self.framebuffer
+            .fill_solid(area, color)
+            .map_err(|e| anyhow!("failed to draw: {}", e))
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<()> {
+        self.framebuffer
+            .clear(color)
+            .map_err(|e| anyhow!("failed to draw: {}", e))
+    }
+}
+
+impl OriginDimensions for FramebufferDisplay {
+    fn size(&self) -> Size {
+        self.framebuffer.size
+    }
+}
+
+impl DrawTarget for Buffer {
+    type Color = Color;
+    type Error = anyhow::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<()>
+    where
+        I: IntoIterator<Item = embedded_graphics::Pixel<Self::Color>>,
+    {
+        let width = self.size.width as i32;
+        let height = self.size.height as i32;
+        let panel_width = self.panel_size.width;
+        let bytespp = self.bytes_per_pixel;
+
+        // Bounds of the drawn pixels, in display coordinates
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (width, height, -1, -1);
+
+        for Pixel(coord, color) in pixels.into_iter() {
+            if 0 <= coord.x && coord.x < width && 0 <= coord.y && coord.y < height {
+                let (x, y) =
+                    self.rotation
+                        .to_panel(coord.x, coord.y, self.size.width, self.size.height);
+                min_x = min_x.min(coord.x);
+                min_y = min_y.min(coord.y);
+                max_x = max_x.max(coord.x);
+                max_y = max_y.max(coord.y);
+
+                let index = ((x as u32 + y as u32 * panel_width) * bytespp) as usize;
+                let pixel = &mut self.buffer[index..];
+                let curr = self.format.read(pixel);
+                self.format.write(pixel, curr.blend(color, color.a()));
+            }
+        }
+
+        if max_x >= min_x && max_y >= min_y {
+            self.dirty.add(Rect::new(
+                min_x,
+                min_y,
+                (max_x - min_x + 1) as u32,
+                (max_y - min_y + 1) as u32,
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl OriginDimensions for Buffer {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
@@ -0,0 +1,31 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use log::debug;
+
+use crate::led::LedPattern;
+
+/// Shows a pattern on an LED class device, blinking with the kernel's timer trigger.
+pub fn set_pattern(path: &str, pattern: LedPattern) -> Result<()> {
+    debug!("set LED pattern: {:?}", pattern);
+    let dir = Path::new(path);
+    match pattern {
+        LedPattern::Off => {
+            fs::write(dir.join("trigger"), "none")?;
+            fs::write(dir.join("brightness"), "0")?;
+        }
+        LedPattern::On => {
+            fs::write(dir.join("trigger"), "none")?;
+            let max = fs::read_to_string(dir.join("max_brightness"))?;
+            fs::write(dir.join("brightness"), max.trim())?;
+        }
+        LedPattern::Blink { on, off } => {
+            // The delays are created when the timer trigger is set
+            fs::write(dir.join("trigger"), "timer")?;
+            fs::write(dir.join("delay_on"), on.as_millis().to_string())?;
+            fs::write(dir.join("delay_off"), off.as_millis().to_string())?;
+        }
+    }
+    Ok(())
+}
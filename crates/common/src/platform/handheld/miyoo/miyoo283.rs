@@ -86,6 +86,10 @@ impl Battery for Miyoo283Battery {
             }
         }
     }
+
+    fn voltage(&self) -> Option<i32> {
+        (self.adc_value > 0).then_some(self.adc_value)
+    }
 }
 
 #[inline]
@@ -11,7 +11,6 @@ use crate::battery::Battery;
 #[derive(Deserialize)]
 struct BatteryCommandOutput {
     battery: i32,
-    #[allow(dead_code)]
     voltage: i32,
     charging: i32,
 }
@@ -19,6 +18,7 @@ struct BatteryCommandOutput {
 pub struct Miyoo354Battery {
     charging: bool,
     percentage: i32,
+    voltage: Option<i32>,
 }
 
 impl Miyoo354Battery {
@@ -26,6 +26,7 @@ impl Miyoo354Battery {
         Miyoo354Battery {
             charging: false,
             percentage: 100,
+            voltage: None,
         }
     }
 }
@@ -46,6 +47,7 @@ impl Battery for Miyoo354Battery {
         let output: BatteryCommandOutput = serde_json::from_reader(child.stdout.unwrap())?;
         self.percentage = output.battery;
         self.charging = output.charging == 3;
+        self.voltage = Some(output.voltage);
 
         trace!("battery: {}%", self.percentage);
         Ok(())
@@ -58,4 +60,8 @@ impl Battery for Miyoo354Battery {
     fn charging(&self) -> bool {
         self.charging
     }
+
+    fn voltage(&self) -> Option<i32> {
+        self.voltage
+    }
 }
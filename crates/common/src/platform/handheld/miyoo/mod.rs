@@ -0,0 +1,59 @@
+//! Backends only found on the Miyoo Minis: the SigmaStar display driver, their batteries, and
+//! audio through `myctl`.
+
+mod miyoo283;
+mod miyoo354;
+pub mod screen;
+pub mod volume;
+
+use std::process::Command;
+
+pub use self::miyoo283::Miyoo283Battery;
+pub use self::miyoo354::Miyoo354Battery;
+
+/// Version of the stock firmware, or an empty string if it can't be read.
+pub fn detect_firmware() -> String {
+    Command::new("/etc/fw_printenv")
+        .output()
+        .map(|output| parse_firmware(&String::from_utf8_lossy(&output.stdout)).to_string())
+        .unwrap_or_default()
+}
+
+fn parse_firmware(data: &str) -> &str {
+    for line in data.lines() {
+        if line.starts_with("miyoo_version=") {
+            return &line[14..];
+        }
+    }
+    ""
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_firmware() {
+        let data = r#"SdUpgradeImage=miyoo354_fw.img
+baudrate=115200
+bootargs=console=ttyS0,115200 root=/dev/mtdblock4 rootfstype=squashfs ro init=/linuxrc LX_MEM=0x7f00000 mma_heap=mma_heap_name0,miu=0,sz=0x1500000 mma_memblock_remove=1 highres=off mmap_reserved=fb,miu=0,sz=0x300000,max_start_off=0x7C00000,max_end_off=0x7F00000
+bootcmd=gpio output 85 1; bootlogo 0 0 0 0 0; mw 1f001cc0 11; gpio out 8 0; sf probe 0;sf read 0x22000000 ${sf_kernel_start} ${sf_kernel_size}; gpio out 8 1; sleepms 1000; gpio output 4 1; bootm 0x22000000
+bootdelay=0
+cpu_part_start=14270000
+dispout=K101_IM2BVL
+ethact=sstar_emac
+ethaddr=00:30:1b:ba:02:db
+filesize=1774c
+miyoo_version=202303262339
+sf_kernel_size=200000
+sf_kernel_start=60000
+sf_part_size=20000
+sf_part_start=270000
+stderr=serial
+stdin=serial
+stdout=serial
+usb_folder=images
+"#;
+        assert_eq!(parse_firmware(data), "202303262339");
+    }
+}
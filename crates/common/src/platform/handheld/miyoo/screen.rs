@@ -0,0 +1,79 @@
+//! Backlight and display driver of the SigmaStar SoC in the Miyoo Minis.
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+use log::warn;
+
+use crate::display::settings::DisplaySettings;
+
+pub fn get_brightness() -> Result<u8> {
+    Ok(
+        fs::read_to_string("/sys/devices/soc0/soc/1f003400.pwm/pwm/pwmchip0/pwm0/duty_cycle")?
+            .trim()
+            .parse()?,
+    )
+}
+
+pub fn set_brightness(brightness: u8) -> Result<()> {
+    File::create("/sys/devices/soc0/soc/1f003400.pwm/pwm/pwmchip0/pwm0/duty_cycle")
+        .context("failed to open pwm/duty_cycle")?
+        .write_all(format!("{}", brightness.max(3)).as_bytes())?;
+    Ok(())
+}
+
+/// Turns the display and backlight off for sleep, or the display back on, through `myctl`.
+/// The brightness is restored separately.
+pub fn sleep(sleep: bool) -> Result<()> {
+    let status = Command::new("myctl")
+        .arg("sleep")
+        .arg(sleep.to_string())
+        .status()?;
+    if !status.success() {
+        bail!("myctl sleep failed: {}", status);
+    }
+    Ok(())
+}
+
+/// Applies the colour settings through the display driver.
+pub fn set_display_settings(settings: &mut DisplaySettings) -> Result<()> {
+    if settings.contrast < 10 {
+        settings.contrast = 10;
+    }
+
+    let mut file = match File::create("/proc/mi_modules/mi_disp/mi_disp0") {
+        Ok(file) => file,
+        Err(err) => {
+            warn!("failed to open display settings file: {}", err);
+            return Ok(());
+        }
+    };
+
+    if settings.r < 15 && settings.g < 15 && settings.b < 15 {
+        settings.r = 15;
+        settings.g = 15;
+        settings.b = 15;
+    }
+
+    file.write_all(
+        format!(
+            "csc 0 3 {:.0} {:.0} {:.0} {:.0} 0 0\n",
+            settings.contrast, settings.hue, settings.luminance, settings.saturation,
+        )
+        .as_bytes(),
+    )?;
+    let (r, g, b) = settings.color_balance(chrono::Local::now().time());
+    file.write_all(
+        format!(
+            "colortemp 0 0 0 0 {:.0} {:.0} {:.0}\n",
+            b as f32 * 255.0 / 100.0,
+            g as f32 * 255.0 / 100.0,
+            r as f32 * 255.0 / 100.0,
+        )
+        .as_bytes(),
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+//! Volume and audio settings of the Miyoo Mini+ and Flip, set through `myctl`.
+
+use anyhow::Result;
+use log::debug;
+use std::process::Command;
+
+use crate::audio::AudioSettings;
+use crate::platform::handheld::alsa::{MAX_VOLUME, MIN_VOLUME};
+
+const MIN_RAW_VOLUME: f32 = -60.0;
+
+/// Set volume output between -60 and 30
+fn set_volume_raw(volume: i32) -> Result<()> {
+    Command::new("myctl")
+        .arg("volume")
+        .arg(volume.to_string())
+        .spawn()?
+        .wait()?;
+    Ok(())
+}
+
+// Default (logarithmic) volume curve at 100% max volume:
+// |   0 |   1 |   2 |   3 |   4 |   5 |   6 |   7 |   8 |   9 |  10 |  11 |  12 |  13 |  14 |  15 |  16 |  17 |  18 |  19 |  20 |
+// | -60 | -46 | -38 | -33 | -28 | -25 | -22 | -19 | -17 | -15 | -13 | -11 |  -9 |  -8 |  -7 |  -5 |  -4 |  -3 |  -2 |  -1 |   0 |
+pub fn set_volume(volume: i32) -> Result<()> {
+    let settings = AudioSettings::load()?;
+    let volume = volume.clamp(MIN_VOLUME, MAX_VOLUME);
+    let max_raw = MIN_RAW_VOLUME * (1.0 - settings.max_volume.clamp(0, 100) as f32 / 100.0);
+    let volume_raw = MIN_RAW_VOLUME
+        + settings.volume_curve.apply(volume, MAX_VOLUME) * (max_raw - MIN_RAW_VOLUME);
+    debug!("set volume: {}", volume_raw as i32);
+    set_volume_raw(volume_raw as i32)?;
+    Ok(())
+}
+
+/// Set mono downmix and left/right balance
+pub fn set_audio_settings(settings: &AudioSettings) -> Result<()> {
+    debug!(
+        "set audio settings: mono={}, balance={}",
+        settings.mono, settings.balance
+    );
+    Command::new("myctl")
+        .arg("audio")
+        .arg(settings.mono.to_string())
+        .arg(settings.balance.to_string())
+        .spawn()?
+        .wait()?;
+    Ok(())
+}
@@ -0,0 +1,242 @@
+//! Platform for the Linux handhelds that Allium runs on. Input, display, battery, backlight,
+//! volume, rumble and LED backends are picked from the device's profile, so that supporting
+//! another device is a matter of adding a profile. Backends that only exist on the Miyoo Minis
+//! are in the `miyoo` module.
+//!
+//! Only the UI is ported to the non-Miyoo profiles so far. RetroArch and the cores, Wi-Fi and the
+//! standalone screenshot, overlay and show tools are still built for the Miyoo only.
+
+mod alsa;
+mod backlight;
+mod cpu;
+mod device;
+mod evdev;
+mod framebuffer;
+mod led;
+mod miyoo;
+mod power_supply;
+mod rumble;
+
+use std::os::unix::process::CommandExt;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::warn;
+
+use crate::audio::AudioSettings;
+use crate::battery::{Battery, CalibratedBattery};
+use crate::display::rotation::Rotation;
+use crate::display::settings::DisplaySettings;
+use crate::led::LedPattern;
+use crate::performance::CpuProfile;
+use crate::platform::KeyEvent;
+use crate::platform::Platform;
+use crate::platform::long_press::LongPress;
+
+use self::device::{BatteryBackend, DeviceProfile, ScreenBackend, VolumeBackend};
+use self::evdev::EvdevKeys;
+use self::framebuffer::FramebufferDisplay;
+use self::miyoo::{Miyoo283Battery, Miyoo354Battery};
+use self::power_supply::PowerSupplyBattery;
+
+pub struct HandheldPlatform {
+    device: &'static DeviceProfile,
+    keys: EvdevKeys,
+    long_press: LongPress,
+    /// Rotation of the screen, which the d-pad is rotated to match.
+    rotation: Rotation,
+}
+
+pub struct SuspendContext {
+    brightness: u8,
+    governor: Option<String>,
+}
+
+#[async_trait(?Send)]
+impl Platform for HandheldPlatform {
+    type Display = FramebufferDisplay;
+    type Battery = Box<dyn Battery>;
+    type SuspendContext = SuspendContext;
+
+    fn new() -> Result<HandheldPlatform> {
+        let device = DeviceProfile::current();
+
+        Ok(HandheldPlatform {
+            device,
+            keys: EvdevKeys::new(device)?,
+            long_press: LongPress::new(),
+            rotation: DisplaySettings::load()?.rotation,
+        })
+    }
+
+    async fn poll(&mut self) -> KeyEvent {
+        self.long_press
+            .poll(self.keys.poll())
+            .await
+            .rotate(self.rotation)
+    }
+
+    fn display(&mut self) -> Result<FramebufferDisplay> {
+        FramebufferDisplay::new(self.device.framebuffer)
+    }
+
+    fn battery(&self) -> Result<Box<dyn Battery>> {
+        Ok(match self.device.battery {
+            BatteryBackend::Sar => Box::new(CalibratedBattery::new(Miyoo283Battery::new())),
+            BatteryBackend::Axp223 => Box::new(CalibratedBattery::new(Miyoo354Battery::new())),
+            BatteryBackend::PowerSupply(path) => {
+                Box::new(CalibratedBattery::new(PowerSupplyBattery::new(path)))
+            }
+        })
+    }
+
+    fn shutdown(&self) -> Result<()> {
+        #[cfg(unix)]
+        {
+            std::process::Command::new("sync").spawn()?.wait()?;
+            let _ = std::process::Command::new(self.device.power_off).exec();
+        }
+        Ok(())
+    }
+
+    fn suspend(&self) -> Result<Self::SuspendContext> {
+        let brightness = self.get_brightness()?;
+        let governor = cpu::get_governor()
+            .map_err(|e| warn!("failed to read cpu governor: {}", e))
+            .ok();
+        let ctx = SuspendContext {
+            brightness,
+            governor,
+        };
+        match self.device.screen {
+            ScreenBackend::SigmaStar => miyoo::screen::sleep(true)?,
+            ScreenBackend::Sysfs { .. } => backlight::blank_framebuffer(true)?,
+        }
+        // The CPU frequency is set through cpufreq, which every handheld has, rather than myctl,
+        // which only drives the SigmaStar hardware of the Miyoo Minis
+        if let Err(e) = cpu::set_governor("powersave") {
+            warn!("failed to lower cpu frequency: {}", e);
+        }
+        Ok(ctx)
+    }
+
+    fn unsuspend(&self, ctx: Self::SuspendContext) -> Result<()> {
+        if let Some(governor) = ctx.governor
+            && let Err(e) = cpu::set_governor(&governor)
+        {
+            warn!("failed to restore cpu governor: {}", e);
+        }
+        match self.device.screen {
+            ScreenBackend::SigmaStar => {
+                miyoo::screen::sleep(false)?;
+                miyoo::screen::set_brightness(ctx.brightness)?;
+            }
+            ScreenBackend::Sysfs { backlight } => {
+                backlight::blank_framebuffer(false)?;
+                backlight::set_backlight(backlight, ctx.brightness)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn set_volume(&mut self, volume: i32) -> Result<()> {
+        match self.device.volume {
+            VolumeBackend::Hardware => Ok(()),
+            VolumeBackend::Myctl => miyoo::volume::set_volume(volume),
+            VolumeBackend::Alsa { control } => alsa::set_volume(control, volume),
+        }
+    }
+
+    fn set_audio_settings(&mut self, settings: &AudioSettings) -> Result<()> {
+        match self.device.volume {
+            VolumeBackend::Hardware | VolumeBackend::Alsa { .. } => Ok(()),
+            VolumeBackend::Myctl => miyoo::volume::set_audio_settings(settings),
+        }
+    }
+
+    fn get_brightness(&self) -> Result<u8> {
+        match self.device.screen {
+            ScreenBackend::SigmaStar => miyoo::screen::get_brightness(),
+            ScreenBackend::Sysfs { backlight } => backlight::get_backlight(backlight),
+        }
+    }
+
+    fn set_brightness(&mut self, brightness: u8) -> Result<()> {
+        match self.device.screen {
+            ScreenBackend::SigmaStar => miyoo::screen::set_brightness(brightness),
+            ScreenBackend::Sysfs { backlight } => backlight::set_backlight(backlight, brightness),
+        }
+    }
+
+    fn set_display_settings(&mut self, settings: &mut DisplaySettings) -> Result<()> {
+        match self.device.screen {
+            ScreenBackend::SigmaStar => miyoo::screen::set_display_settings(settings),
+            // Colour settings are not supported
+            ScreenBackend::Sysfs { .. } => Ok(()),
+        }
+    }
+
+    fn set_cpu_profile(&self, profile: CpuProfile) -> Result<()> {
+        let max_frequency = cpu::get_hardware_max_frequency()?;
+        cpu::set_governor(profile.governor())?;
+        cpu::set_max_frequency(
+            profile
+                .max_frequency()
+                .map_or(max_frequency, |f| f.min(max_frequency)),
+        )?;
+        Ok(())
+    }
+
+    fn device_model() -> String {
+        DeviceProfile::current().model.to_string()
+    }
+
+    fn firmware() -> String {
+        miyoo::detect_firmware()
+    }
+
+    fn has_wifi() -> bool {
+        DeviceProfile::current().has_wifi
+    }
+
+    fn has_bluetooth() -> bool {
+        crate::bluetooth::is_available()
+    }
+
+    fn has_lid() -> bool {
+        DeviceProfile::current().has_lid()
+    }
+
+    fn has_rumble() -> bool {
+        DeviceProfile::current().rumble.is_some()
+    }
+
+    fn rumble(&self, duration: Duration) -> Result<()> {
+        match self.device.rumble {
+            Some(backend) => rumble::rumble(backend, duration),
+            None => Ok(()),
+        }
+    }
+
+    fn has_led() -> bool {
+        DeviceProfile::current().led.is_some()
+    }
+
+    fn set_led(&self, pattern: LedPattern) -> Result<()> {
+        match self.device.led {
+            Some(path) => led::set_pattern(path, pattern),
+            None => Ok(()),
+        }
+    }
+
+    fn is_headphones_connected(&self) -> bool {
+        self.keys.is_headphones_connected()
+    }
+}
+
+impl Default for HandheldPlatform {
+    fn default() -> Self {
+        Self::new().unwrap()
+    }
+}
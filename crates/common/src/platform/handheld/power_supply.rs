@@ -0,0 +1,62 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use log::trace;
+
+use crate::battery::Battery;
+
+/// Battery reported by the kernel's power supply class.
+pub struct PowerSupplyBattery {
+    path: PathBuf,
+    charging: bool,
+    percentage: i32,
+    voltage: Option<i32>,
+}
+
+impl PowerSupplyBattery {
+    pub fn new(path: impl AsRef<Path>) -> PowerSupplyBattery {
+        PowerSupplyBattery {
+            path: path.as_ref().to_path_buf(),
+            charging: false,
+            percentage: 100,
+            voltage: None,
+        }
+    }
+
+    fn read(&self, attribute: &str) -> Result<String> {
+        let path = self.path.join(attribute);
+        Ok(fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?
+            .trim()
+            .to_string())
+    }
+}
+
+impl Battery for PowerSupplyBattery {
+    fn update(&mut self) -> Result<()> {
+        self.percentage = self.read("capacity")?.parse()?;
+        self.charging = matches!(self.read("status")?.as_str(), "Charging" | "Full");
+        // voltage_now is in microvolts
+        self.voltage = self
+            .read("voltage_now")
+            .ok()
+            .and_then(|v| v.parse::<i32>().ok())
+            .map(|v| v / 1000);
+
+        trace!("battery: {}%", self.percentage);
+        Ok(())
+    }
+
+    fn percentage(&self) -> i32 {
+        self.percentage
+    }
+
+    fn charging(&self) -> bool {
+        self.charging
+    }
+
+    fn voltage(&self) -> Option<i32> {
+        self.voltage
+    }
+}
@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use log::warn;
+use sysfs_gpio::{Direction, Pin};
+
+use crate::platform::handheld::device::RumbleBackend;
+
+/// Switches the motor on, and off again after `duration` in the background.
+pub fn rumble(backend: RumbleBackend, duration: Duration) -> Result<()> {
+    let RumbleBackend::Gpio { pin, active_low } = backend;
+    let pin = Pin::new(pin);
+    pin.export()?;
+    pin.set_direction(Direction::Out)?;
+    let on = u8::from(!active_low);
+    pin.set_value(on)?;
+    tokio::spawn(async move {
+        tokio::time::sleep(duration).await;
+        if let Err(e) = pin.set_value(on ^ 1) {
+            warn!("failed to stop rumble: {}", e);
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+use enum_map::{Enum, EnumMap};
+use tokio::time::Instant;
+
+/// What to do after the device has been left alone for a while.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum IdleEvent {
+    /// Dim the screen. Any key event should restore the brightness.
+    Dim,
+    /// Put the device to sleep.
+    Sleep,
+    /// Save the game and shut down.
+    PowerOff,
+}
+
+/// Tracks how long it has been since the last key event. Each event fires once until there is
+/// activity again.
+#[derive(Debug)]
+pub struct IdleTimer {
+    durations: EnumMap<IdleEvent, Option<Duration>>,
+    deadlines: EnumMap<IdleEvent, Option<Instant>>,
+}
+
+impl IdleTimer {
+    /// A `None` duration disables that event.
+    pub fn new(
+        dim_after: Option<Duration>,
+        sleep_after: Option<Duration>,
+        power_off_after: Option<Duration>,
+    ) -> Self {
+        let mut this = Self {
+            durations: EnumMap::from_array([dim_after, sleep_after, power_off_after]),
+            deadlines: EnumMap::default(),
+        };
+        this.activity();
+        this
+    }
+
+    /// Records activity, restarting the timers. Returns whether the screen was dimmed, so that
+    /// the caller can restore the brightness.
+    pub fn activity(&mut self) -> bool {
+        let is_dimmed =
+            self.durations[IdleEvent::Dim].is_some() && self.deadlines[IdleEvent::Dim].is_none();
+        let now = Instant::now();
+        for (event, deadline) in &mut self.deadlines {
+            *deadline = self.durations[event].map(|d| now + d);
+        }
+        is_dimmed
+    }
+
+    /// Fires the event again after its full duration, e.g. when it could not be acted upon.
+    pub fn postpone(&mut self, event: IdleEvent) {
+        self.deadlines[event] = self.durations[event].map(|d| Instant::now() + d);
+    }
+
+    /// Waits until the next idle event is due. This is cancel safe, so it can be used in
+    /// `tokio::select!` alongside polling for key events.
+    pub async fn next(&mut self) -> IdleEvent {
+        let Some((event, deadline)) = self
+            .deadlines
+            .iter()
+            .filter_map(|(event, deadline)| deadline.map(|d| (event, d)))
+            .min_by_key(|(_, deadline)| *deadline)
+        else {
+            return std::future::pending().await;
+        };
+
+        tokio::time::sleep_until(deadline).await;
+
+        self.deadlines[event] = None;
+        event
+    }
+}
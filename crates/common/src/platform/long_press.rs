@@ -0,0 +1,49 @@
+use std::future::Future;
+
+use tokio::time::Instant;
+
+use crate::platform::{Key, KeyEvent};
+use crate::ui_keymap::UiKeymap;
+
+/// Emits `KeyEvent::LongPressed` once a key has been held for the long press duration set in
+/// [`UiKeymap`]. Only the last pressed key is tracked, and pressing another key cancels it.
+#[derive(Debug, Default)]
+pub struct LongPress {
+    held: Option<(Key, Instant)>,
+}
+
+impl LongPress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Waits for the next event from `next`, or for the held key to be long pressed, whichever
+    /// comes first. `next` is dropped if the key is long pressed, so it must be cancel safe.
+    pub async fn poll(&mut self, next: impl Future<Output = KeyEvent>) -> KeyEvent {
+        let event = match self.held {
+            Some((key, at)) => tokio::select! {
+                event = next => event,
+                _ = tokio::time::sleep_until(at) => {
+                    self.held = None;
+                    return KeyEvent::LongPressed(key);
+                }
+            },
+            None => next.await,
+        };
+        self.observe(event);
+        event
+    }
+
+    fn observe(&mut self, event: KeyEvent) {
+        match event {
+            KeyEvent::Pressed(key) => {
+                let duration = UiKeymap::current().long_press_duration();
+                self.held = Some((key, Instant::now() + duration));
+            }
+            KeyEvent::Released(key) if self.held.is_some_and(|(held, _)| held == key) => {
+                self.held = None;
+            }
+            KeyEvent::Released(_) | KeyEvent::Autorepeat(_) | KeyEvent::LongPressed(_) => {}
+        }
+    }
+}
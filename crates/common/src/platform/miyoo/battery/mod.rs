@@ -1,5 +0,0 @@
-mod miyoo283;
-mod miyoo354;
-
-pub use miyoo283::Miyoo283Battery;
-pub use miyoo354::Miyoo354Battery;
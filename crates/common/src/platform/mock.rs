@@ -1,12 +1,17 @@
+use std::time::Duration;
+
 use anyhow::Result;
 use async_trait::async_trait;
 use embedded_graphics::prelude::*;
 
+use crate::audio::AudioSettings;
 use crate::battery::Battery;
 use crate::display::Display;
 use crate::display::color::Color;
 use crate::display::settings::DisplaySettings;
 use crate::geom::Rect;
+use crate::led::LedPattern;
+use crate::performance::CpuProfile;
 use crate::platform::{KeyEvent, Platform};
 
 pub const SCREEN_WIDTH: u32 = 640;
@@ -52,6 +57,10 @@ impl Platform for MockPlatform {
         Ok(())
     }
 
+    fn set_audio_settings(&mut self, _settings: &AudioSettings) -> Result<()> {
+        Ok(())
+    }
+
     fn get_brightness(&self) -> Result<u8> {
         Ok(50)
     }
@@ -64,6 +73,10 @@ impl Platform for MockPlatform {
         Ok(())
     }
 
+    fn set_cpu_profile(&self, _profile: CpuProfile) -> Result<()> {
+        Ok(())
+    }
+
     fn device_model() -> String {
         "Mock".into()
     }
@@ -76,9 +89,33 @@ impl Platform for MockPlatform {
         false
     }
 
+    fn has_bluetooth() -> bool {
+        false
+    }
+
     fn has_lid() -> bool {
         false
     }
+
+    fn has_rumble() -> bool {
+        false
+    }
+
+    fn rumble(&self, _duration: Duration) -> Result<()> {
+        Ok(())
+    }
+
+    fn has_led() -> bool {
+        false
+    }
+
+    fn set_led(&self, _pattern: LedPattern) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_headphones_connected(&self) -> bool {
+        false
+    }
 }
 
 impl Default for MockPlatform {
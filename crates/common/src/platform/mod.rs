@@ -1,23 +1,36 @@
 #[cfg(not(any(feature = "miyoo", feature = "simulator")))]
 mod mock;
 
+pub mod device;
+pub mod idle;
+#[cfg(any(feature = "miyoo", feature = "simulator"))]
+mod long_press;
+
+// The `miyoo` feature builds for real devices, which the handheld platform supports through
+// device profiles
 #[cfg(feature = "miyoo")]
-mod miyoo;
+mod handheld;
 #[cfg(feature = "simulator")]
 mod simulator;
 
+use std::time::Duration;
+
 use anyhow::Result;
 use async_trait::async_trait;
 use enum_map::Enum;
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    audio::AudioSettings,
     battery::Battery,
-    display::{Display, settings::DisplaySettings},
+    display::{Display, rotation::Rotation, settings::DisplaySettings},
+    led::LedPattern,
+    performance::CpuProfile,
+    ui_keymap::UiKeymap,
 };
 
 #[cfg(feature = "miyoo")]
-pub type DefaultPlatform = miyoo::MiyooPlatform;
+pub type DefaultPlatform = handheld::HandheldPlatform;
 
 #[cfg(feature = "simulator")]
 pub type DefaultPlatform = simulator::SimulatorPlatform;
@@ -50,19 +63,40 @@ pub trait Platform {
 
     fn set_volume(&mut self, volume: i32) -> Result<()>;
 
+    fn set_audio_settings(&mut self, settings: &AudioSettings) -> Result<()>;
+
     fn get_brightness(&self) -> Result<u8>;
 
     fn set_brightness(&mut self, brightness: u8) -> Result<()>;
 
     fn set_display_settings(&mut self, settings: &mut DisplaySettings) -> Result<()>;
 
+    fn set_cpu_profile(&self, profile: CpuProfile) -> Result<()>;
+
     fn device_model() -> String;
 
     fn firmware() -> String;
 
     fn has_wifi() -> bool;
 
+    fn has_bluetooth() -> bool;
+
     fn has_lid() -> bool;
+
+    /// Whether the device has a vibration motor.
+    fn has_rumble() -> bool;
+
+    /// Vibrates for `duration` in the background. Does nothing on devices without a vibration
+    /// motor.
+    fn rumble(&self, duration: Duration) -> Result<()>;
+
+    /// Whether the device has an LED that can be controlled.
+    fn has_led() -> bool;
+
+    /// Shows a pattern on the LED. Does nothing on devices without a controllable LED.
+    fn set_led(&self, pattern: LedPattern) -> Result<()>;
+
+    fn is_headphones_connected(&self) -> bool;
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -70,6 +104,42 @@ pub enum KeyEvent {
     Pressed(Key),
     Released(Key),
     Autorepeat(Key),
+    /// The key has been held for the long press duration, while still held.
+    LongPressed(Key),
+}
+
+impl KeyEvent {
+    /// The event with its direction key rotated, see [`Key::rotate`].
+    pub fn rotate(self, rotation: Rotation) -> Self {
+        match self {
+            KeyEvent::Pressed(key) => KeyEvent::Pressed(key.rotate(rotation)),
+            KeyEvent::Released(key) => KeyEvent::Released(key.rotate(rotation)),
+            KeyEvent::Autorepeat(key) => KeyEvent::Autorepeat(key.rotate(rotation)),
+            KeyEvent::LongPressed(key) => KeyEvent::LongPressed(key.rotate(rotation)),
+        }
+    }
+
+    /// The event with its key mapped for navigating views, see [`Key::navigation`].
+    pub fn navigation(self) -> Self {
+        match self {
+            KeyEvent::Pressed(key) => KeyEvent::Pressed(key.navigation()),
+            KeyEvent::Released(key) => KeyEvent::Released(key.navigation()),
+            KeyEvent::Autorepeat(key) => KeyEvent::Autorepeat(key.navigation()),
+            KeyEvent::LongPressed(key) => KeyEvent::LongPressed(key.navigation()),
+        }
+    }
+
+    /// The event with its key mapped to the key that views handle: the left stick navigates, and
+    /// the UI keys are remapped as set in [`UiKeymap`].
+    pub fn ui(self) -> Self {
+        let keymap = UiKeymap::current();
+        match self.navigation() {
+            KeyEvent::Pressed(key) => KeyEvent::Pressed(keymap.to_ui(key)),
+            KeyEvent::Released(key) => KeyEvent::Released(keymap.to_ui(key)),
+            KeyEvent::Autorepeat(key) => KeyEvent::Autorepeat(keymap.to_ui(key)),
+            KeyEvent::LongPressed(key) => KeyEvent::LongPressed(keymap.to_ui(key)),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
@@ -89,9 +159,49 @@ pub enum Key {
     Menu,
     L2,
     R2,
+    /// Pressing in the left analog stick.
+    L3,
+    /// Pressing in the right analog stick.
+    R3,
+    /// The left analog stick, pushed past halfway in a direction.
+    LeftStickUp,
+    LeftStickDown,
+    LeftStickLeft,
+    LeftStickRight,
+    /// The right analog stick, pushed past halfway in a direction.
+    RightStickUp,
+    RightStickDown,
+    RightStickLeft,
+    RightStickRight,
     Power,
     VolDown,
     VolUp,
     LidClose,
+    /// Pressed when headphones are plugged in, released when they are unplugged.
+    Headphones,
     Unknown,
 }
+
+impl Key {
+    /// Maps a direction on the d-pad to the direction on a screen rotated clockwise by
+    /// `rotation`, so that up on the d-pad is up on the screen. Other keys are unchanged.
+    pub fn rotate(self, rotation: Rotation) -> Self {
+        const CLOCKWISE: [Key; 4] = [Key::Up, Key::Right, Key::Down, Key::Left];
+        match CLOCKWISE.iter().position(|k| *k == self) {
+            Some(i) => CLOCKWISE[(i + CLOCKWISE.len() - rotation as usize) % CLOCKWISE.len()],
+            None => self,
+        }
+    }
+
+    /// Maps the left analog stick to the d-pad, so that views can be navigated with either.
+    /// Other keys are unchanged.
+    pub fn navigation(self) -> Self {
+        match self {
+            Key::LeftStickUp => Key::Up,
+            Key::LeftStickDown => Key::Down,
+            Key::LeftStickLeft => Key::Left,
+            Key::LeftStickRight => Key::Right,
+            _ => self,
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use std::cell::RefCell;
+use std::env;
 use std::process;
 use std::rc::Rc;
+use std::sync::{LazyLock, Mutex};
 use std::time::Duration;
 
 use anyhow::{Result, bail};
@@ -17,18 +19,123 @@ use itertools::iproduct;
 use log::{trace, warn};
 use sdl2::keyboard::Keycode;
 
+use crate::audio::AudioSettings;
 use crate::battery::Battery;
 use crate::display::Display;
 use crate::display::color::Color;
 use crate::display::settings::DisplaySettings;
 use crate::geom::Rect;
+use crate::led::LedPattern;
+use crate::performance::CpuProfile;
+use crate::platform::long_press::LongPress;
 use crate::platform::{Key, KeyEvent, Platform};
 
 pub const SCREEN_WIDTH: u32 = 640;
 pub const SCREEN_HEIGHT: u32 = 480;
 
+/// Size of the simulated screen. Set `ALLIUM_SIMULATOR_SIZE=<width>x<height>` to check how layouts
+/// fit other resolutions, such as 752x560 for the Miyoo Mini Plus. Layouts are computed when the
+/// apps start, so the size can't be changed while they run.
+pub fn screen_size() -> Size {
+    let Ok(size) = env::var("ALLIUM_SIMULATOR_SIZE") else {
+        return Size::new(SCREEN_WIDTH, SCREEN_HEIGHT);
+    };
+    parse_size(&size).unwrap_or_else(|| {
+        warn!("invalid ALLIUM_SIMULATOR_SIZE {size:?}, expected <width>x<height>");
+        Size::new(SCREEN_WIDTH, SCREEN_HEIGHT)
+    })
+}
+
+fn parse_size(size: &str) -> Option<Size> {
+    let (w, h) = size.trim().split_once('x')?;
+    let size = Size::new(w.parse().ok()?, h.parse().ok()?);
+    (size.width > 0 && size.height > 0).then_some(size)
+}
+
+/// Charge of the fake battery, shared by every battery in the process so that it can be changed
+/// from the window. Starts at `ALLIUM_SIMULATOR_BATTERY` percent, or full.
+static BATTERY: LazyLock<Mutex<BatteryState>> = LazyLock::new(|| {
+    let percentage = env::var("ALLIUM_SIMULATOR_BATTERY")
+        .ok()
+        .map_or(100, |percentage| {
+            parse_percentage(&percentage).unwrap_or_else(|| {
+                warn!("invalid ALLIUM_SIMULATOR_BATTERY {percentage:?}, expected 0 to 100");
+                100
+            })
+        });
+    Mutex::new(BatteryState {
+        percentage,
+        charging: false,
+    })
+});
+
+fn parse_percentage(percentage: &str) -> Option<i32> {
+    percentage
+        .trim()
+        .parse()
+        .ok()
+        .filter(|p| (0..=100).contains(p))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BatteryState {
+    percentage: i32,
+    charging: bool,
+}
+
+impl BatteryState {
+    /// F1 and F2 drain and charge the battery by 10%, and F3 plugs the charger in or out. Returns
+    /// whether the key was handled.
+    fn handle_key(&mut self, keycode: Keycode) -> bool {
+        match keycode {
+            Keycode::F1 => self.percentage = (self.percentage - 10).max(0),
+            Keycode::F2 => self.percentage = (self.percentage + 10).min(100),
+            Keycode::F3 => self.charging = !self.charging,
+            _ => return false,
+        }
+        trace!("simulated battery: {:?}", self);
+        true
+    }
+}
+
+/// Waits for the next key event from the window. Cancel safe, as events are taken synchronously.
+async fn next_event(window: &RefCell<Window>) -> KeyEvent {
+    loop {
+        let event = window.borrow_mut().events().next();
+        if let Some(event) = event {
+            match event {
+                SimulatorEvent::KeyDown {
+                    keycode, repeat, ..
+                } => {
+                    if keycode == Keycode::Q {
+                        process::exit(0);
+                    }
+                    if !repeat && BATTERY.lock().unwrap().handle_key(keycode) {
+                        continue;
+                    }
+                    return if repeat {
+                        KeyEvent::Autorepeat(Key::from(keycode))
+                    } else {
+                        KeyEvent::Pressed(Key::from(keycode))
+                    };
+                }
+                SimulatorEvent::KeyUp { keycode, .. } => {
+                    return KeyEvent::Released(Key::from(keycode));
+                }
+                SimulatorEvent::Quit => {
+                    process::exit(0);
+                }
+                _ => {}
+            }
+        } else {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+}
+
 pub struct SimulatorPlatform {
     window: Rc<RefCell<Window>>,
+    long_press: LongPress,
 }
 
 #[async_trait(?Send)]
@@ -42,47 +149,22 @@ impl Platform for SimulatorPlatform {
         let window = Window::new("Allium Simulator", &output_settings);
         Ok(SimulatorPlatform {
             window: Rc::new(RefCell::new(window)),
+            long_press: LongPress::new(),
         })
     }
 
     async fn poll(&mut self) -> KeyEvent {
-        loop {
-            let event = self.window.borrow_mut().events().next();
-            if let Some(event) = event {
-                match event {
-                    SimulatorEvent::KeyDown {
-                        keycode, repeat, ..
-                    } => {
-                        if keycode == Keycode::Q {
-                            process::exit(0);
-                        }
-                        return if repeat {
-                            KeyEvent::Autorepeat(Key::from(keycode))
-                        } else {
-                            KeyEvent::Pressed(Key::from(keycode))
-                        };
-                    }
-                    SimulatorEvent::KeyUp { keycode, .. } => {
-                        return KeyEvent::Released(Key::from(keycode));
-                    }
-                    SimulatorEvent::Quit => {
-                        process::exit(0);
-                    }
-                    _ => {}
-                }
-            } else {
-                tokio::time::sleep(Duration::from_millis(10)).await;
-            }
-        }
+        self.long_press.poll(next_event(&self.window)).await
     }
 
     fn display(&mut self) -> Result<SimulatorWindow> {
-        let display = SimulatorDisplay::load_png("simulator/bg-640x480.png").unwrap_or_else(|_| {
-            SimulatorDisplay::with_default_color(
-                Size::new(SCREEN_WIDTH, SCREEN_HEIGHT),
-                Color::new(0, 0, 0),
-            )
-        });
+        let size = screen_size();
+        // Stands in for the game that the ingame menu is drawn over
+        let display =
+            SimulatorDisplay::load_png(format!("simulator/bg-{}x{}.png", size.width, size.height))
+                .ok()
+                .filter(|display: &SimulatorDisplay<Color>| display.size() == size)
+                .unwrap_or_else(|| SimulatorDisplay::with_default_color(size, Color::new(0, 0, 0)));
         Ok(SimulatorWindow {
             window: Rc::clone(&self.window),
             display,
@@ -110,6 +192,10 @@ impl Platform for SimulatorPlatform {
         Ok(())
     }
 
+    fn set_audio_settings(&mut self, _settings: &AudioSettings) -> Result<()> {
+        Ok(())
+    }
+
     fn get_brightness(&self) -> Result<u8> {
         Ok(50)
     }
@@ -122,6 +208,10 @@ impl Platform for SimulatorPlatform {
         Ok(())
     }
 
+    fn set_cpu_profile(&self, _profile: CpuProfile) -> Result<()> {
+        Ok(())
+    }
+
     fn device_model() -> String {
         "Simulator".into()
     }
@@ -134,9 +224,35 @@ impl Platform for SimulatorPlatform {
         true
     }
 
+    fn has_bluetooth() -> bool {
+        true
+    }
+
     fn has_lid() -> bool {
         true
     }
+
+    fn has_rumble() -> bool {
+        true
+    }
+
+    fn rumble(&self, duration: Duration) -> Result<()> {
+        trace!("rumble for {:?}", duration);
+        Ok(())
+    }
+
+    fn has_led() -> bool {
+        true
+    }
+
+    fn set_led(&self, pattern: LedPattern) -> Result<()> {
+        trace!("set LED: {:?}", pattern);
+        Ok(())
+    }
+
+    fn is_headphones_connected(&self) -> bool {
+        false
+    }
 }
 
 impl Default for SimulatorPlatform {
@@ -200,8 +316,8 @@ impl Display for SimulatorWindow {
             );
             rect.x = rect.x.max(0);
             rect.y = rect.y.max(0);
-            rect.w = rect.w.min(size.width - rect.x as u32);
-            rect.h = rect.h.min(size.height - rect.h);
+            rect.w = rect.w.min(size.width.saturating_sub(rect.x as u32));
+            rect.h = rect.h.min(size.height.saturating_sub(rect.y as u32));
         }
 
         let image: ImageRaw<'_, _, BigEndian> = ImageRaw::new(&saved.0, saved.1);
@@ -278,16 +394,15 @@ impl From<Keycode> for Key {
     }
 }
 
+/// Fake battery, changed with keys in the simulator window. See `BatteryState::handle_key`.
 pub struct SimulatorBattery {
-    percentage: i32,
-    charging: bool,
+    state: BatteryState,
 }
 
 impl SimulatorBattery {
     pub fn new() -> SimulatorBattery {
         SimulatorBattery {
-            percentage: 100,
-            charging: true,
+            state: *BATTERY.lock().unwrap(),
         }
     }
 }
@@ -301,18 +416,50 @@ impl Default for SimulatorBattery {
 impl Battery for SimulatorBattery {
     fn update(&mut self) -> Result<()> {
         trace!("Updating battery");
-        if self.percentage > 0 {
-            self.percentage -= 5
-        }
-        self.charging = !self.charging;
+        self.state = *BATTERY.lock().unwrap();
         Ok(())
     }
 
     fn percentage(&self) -> i32 {
-        self.percentage
+        self.state.percentage
     }
 
     fn charging(&self) -> bool {
-        self.charging
+        self.state.charging
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("752x560"), Some(Size::new(752, 560)));
+        assert_eq!(parse_size(" 640x480\n"), Some(Size::new(640, 480)));
+        assert_eq!(parse_size("0x480"), None);
+        assert_eq!(parse_size("640"), None);
+        assert_eq!(parse_size("640x-1"), None);
+    }
+
+    #[test]
+    fn test_battery_keys() {
+        assert_eq!(parse_percentage("15"), Some(15));
+        assert_eq!(parse_percentage("101"), None);
+        assert_eq!(parse_percentage("low"), None);
+
+        let mut state = BatteryState {
+            percentage: 15,
+            charging: false,
+        };
+        assert!(state.handle_key(Keycode::F1));
+        assert_eq!(state.percentage, 5);
+        assert!(state.handle_key(Keycode::F1));
+        assert_eq!(state.percentage, 0);
+        assert!(state.handle_key(Keycode::F2));
+        assert_eq!(state.percentage, 10);
+        assert!(state.handle_key(Keycode::F3));
+        assert!(state.charging);
+        assert!(!state.handle_key(Keycode::Space));
     }
 }
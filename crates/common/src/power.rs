@@ -5,20 +5,38 @@ use log::{debug, warn};
 use serde::{Deserialize, Serialize};
 use strum::FromRepr;
 
-use crate::constants::ALLIUM_POWER_SETTINGS;
+use crate::constants::{ALLIUM_POWER_SETTINGS, BATTERY_SHUTDOWN_THRESHOLD};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PowerSettings {
     pub power_button_action: PowerButtonAction,
-    pub lid_close_action: PowerButtonAction,
+    #[serde(default = "PowerButtonAction::shutdown")]
+    pub power_button_long_press_action: PowerButtonAction,
+    pub lid_close_action: LidCloseAction,
     pub auto_sleep_when_charging: bool,
     pub auto_sleep_duration_minutes: i32,
+    /// Seconds without a key press in the launcher before the screen is dimmed. 0 disables
+    /// dimming.
+    #[serde(default = "PowerSettings::default_idle_dim_seconds")]
+    pub idle_dim_seconds: i32,
+    /// Minutes without a key press in the launcher or in-game menu before the game is saved and
+    /// the device shuts down. 0 disables powering off.
+    #[serde(default = "PowerSettings::default_auto_power_off_minutes")]
+    pub auto_power_off_minutes: i32,
+    /// Battery percentage at or below which the game is saved and the device shuts down.
+    #[serde(default = "PowerSettings::default_battery_shutdown_threshold")]
+    pub battery_shutdown_threshold: i32,
+    /// Battery percentage at or below which a warning is flashed during gameplay. 0 disables the
+    /// warning.
+    #[serde(default = "PowerSettings::default_low_battery_warning")]
+    pub low_battery_warning: i32,
 }
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, FromRepr, Default)]
 pub enum PowerButtonAction {
     #[default]
     Suspend,
+    Screenshot,
     Shutdown,
     Nothing,
 }
@@ -27,6 +45,9 @@ impl PowerButtonAction {
     pub fn suspend() -> Self {
         PowerButtonAction::Suspend
     }
+    pub fn screenshot() -> Self {
+        PowerButtonAction::Screenshot
+    }
     pub fn shutdown() -> Self {
         PowerButtonAction::Shutdown
     }
@@ -35,13 +56,31 @@ impl PowerButtonAction {
     }
 }
 
+/// What to do when the lid of a clamshell device is closed.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, FromRepr, Default)]
+pub enum LidCloseAction {
+    #[default]
+    Suspend,
+    /// Pause the game until the lid is opened.
+    Pause,
+    /// Save the game to the auto save state and return to the launcher.
+    SaveAndQuit,
+    Shutdown,
+    Nothing,
+}
+
 impl Default for PowerSettings {
     fn default() -> Self {
         Self {
-            lid_close_action: PowerButtonAction::Shutdown,
+            lid_close_action: LidCloseAction::Shutdown,
             power_button_action: PowerButtonAction::Suspend,
+            power_button_long_press_action: PowerButtonAction::Shutdown,
             auto_sleep_when_charging: true,
             auto_sleep_duration_minutes: 5,
+            idle_dim_seconds: Self::default_idle_dim_seconds(),
+            auto_power_off_minutes: Self::default_auto_power_off_minutes(),
+            battery_shutdown_threshold: BATTERY_SHUTDOWN_THRESHOLD,
+            low_battery_warning: Self::default_low_battery_warning(),
         }
     }
 }
@@ -51,6 +90,22 @@ impl PowerSettings {
         Default::default()
     }
 
+    fn default_battery_shutdown_threshold() -> i32 {
+        BATTERY_SHUTDOWN_THRESHOLD
+    }
+
+    fn default_low_battery_warning() -> i32 {
+        10
+    }
+
+    fn default_idle_dim_seconds() -> i32 {
+        30
+    }
+
+    fn default_auto_power_off_minutes() -> i32 {
+        30
+    }
+
     pub fn load() -> Result<Self> {
         if ALLIUM_POWER_SETTINGS.exists() {
             debug!("found state, loading from file");
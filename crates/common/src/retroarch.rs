@@ -1,19 +1,30 @@
-use std::{borrow::Cow, time::Duration};
+use std::{borrow::Cow, fs, path::Path, time::Duration};
 
 use anyhow::Result;
-use log::{debug, error, trace};
+use log::{debug, error, trace, warn};
 use tokio::net::UdpSocket;
 
-use crate::constants::RETROARCH_UDP_SOCKET;
+use crate::constants::{RETROARCH_CORES_DIR, RETROARCH_UDP_SOCKET};
+
+/// How long to wait for a reply, per attempt.
+const DEFAULT_TIMEOUT: Duration = Duration::from_millis(250);
+/// How many more times to try a command that fails or times out.
+const DEFAULT_RETRIES: u32 = 1;
+/// Time between attempts to send a command that failed to send.
+const RETRY_DELAY: Duration = Duration::from_millis(50);
+/// Largest reply we expect, e.g. to reading core memory.
+const MAX_REPLY_LEN: usize = 4096;
 
 #[allow(unused)]
 #[derive(Debug)]
 pub enum RetroArchCommand {
     FastForward,
     FastForwardHold,
+    SlowMotionHold,
     LoadState,
     SaveState,
     FullscreenToggle,
+    CloseContent,
     Quit,
     StateSlotPlus,
     StateSlotMinus,
@@ -29,6 +40,9 @@ pub enum RetroArchCommand {
     CheatToggle,
     Screenshot,
     Mute,
+    Osk,
+    FpsToggle,
+    StatisticsToggle,
     NetplayFlip,
     SlowMotion,
     VolumeUp,
@@ -39,9 +53,16 @@ pub enum RetroArchCommand {
     DiskPrev,
     GrabMouseToggle,
     MenuToggle,
+    RecordingToggle,
+    RunaheadToggle,
     Pause,
     Unpause,
+    /// Replies with RetroArch's version, e.g. `1.19.1`.
+    Version,
+    /// Replies with [`InfoReply`]'s fields. Specific to Allium's RetroArch build.
     GetInfo,
+    /// Replies with whether content is playing or paused.
+    GetStatus,
     GetDiskCount,
     GetDiskSlot,
     SetDiskSlot(u8),
@@ -49,58 +70,49 @@ pub enum RetroArchCommand {
     SetStateSlot(i8),
     SaveStateSlot(i8),
     LoadStateSlot(i8),
+    /// Applies a shader preset. An empty path disables shaders.
+    SetShader(String),
+    /// Replies with the value of a config setting, e.g. `savestate_directory`.
+    GetConfigParam(String),
+    /// Shows a notification in RetroArch.
+    ShowMsg(String),
+    /// Reads the core's RAM. Use [`RetroArchCommand::ReadCoreMemory`] with newer RetroArch builds.
+    ReadCoreRam {
+        address: u32,
+        length: usize,
+    },
+    /// Reads the core's memory map, e.g. for achievements.
+    ReadCoreMemory {
+        address: u32,
+        length: usize,
+    },
+    /// Writes to the core's memory map. Replies with the number of bytes written.
+    WriteCoreMemory {
+        address: u32,
+        bytes: Vec<u8>,
+    },
 }
 
 impl RetroArchCommand {
+    /// Sends the command with the default client.
     pub async fn send(&self) -> Result<()> {
-        debug!("Sending RetroArch command: {}", self.as_str());
-        let socket = UdpSocket::bind("0.0.0.0:0").await?;
-        trace!("Bound UDP socket: {}", socket.local_addr()?);
-        socket.connect(RETROARCH_UDP_SOCKET).await?;
-        trace!(
-            "Connecting to RetroArch UDP socket: {}",
-            RETROARCH_UDP_SOCKET
-        );
-        socket.send(self.as_str().as_bytes()).await?;
-        Ok(())
+        RetroArchClient::default().send(self).await
     }
 
+    /// Sends the command with the default client, and waits for its reply.
     pub async fn send_recv(&self) -> Result<Option<String>> {
-        debug!("Sending and awaiting RetroArch command: {}", self.as_str(),);
-        let socket = UdpSocket::bind("0.0.0.0:0").await?;
-        trace!("Bound UDP socket: {}", socket.local_addr()?);
-        socket.connect(RETROARCH_UDP_SOCKET).await?;
-        trace!(
-            "Connecting to RetroArch UDP socket: {}",
-            RETROARCH_UDP_SOCKET
-        );
-        socket.send(self.as_str().as_bytes()).await?;
-        let mut reply = vec![0; 128];
-        match tokio::time::timeout(Duration::from_millis(250), socket.recv_from(&mut reply)).await {
-            Ok(Ok((len, _socket))) => {
-                reply.truncate(len);
-                let reply = String::from_utf8(reply)?;
-                debug!("Received reply from RetroArch: {:?}", reply);
-                Ok(Some(reply))
-            }
-            Ok(Err(e)) => {
-                error!("Error receiving from RetroArch: {}", e);
-                Err(e.into())
-            }
-            Err(e) => {
-                error!("Timeout receiving from RetroArch: {}", e);
-                Ok(None)
-            }
-        }
+        RetroArchClient::default().send_recv(self).await
     }
 
     fn as_str(&self) -> Cow<'static, str> {
         match self {
             RetroArchCommand::FastForward => Cow::Borrowed("FAST_FORWARD"),
             RetroArchCommand::FastForwardHold => Cow::Borrowed("FAST_FORWARD_HOLD"),
+            RetroArchCommand::SlowMotionHold => Cow::Borrowed("SLOWMOTION_HOLD"),
             RetroArchCommand::LoadState => Cow::Borrowed("LOAD_STATE"),
             RetroArchCommand::SaveState => Cow::Borrowed("SAVE_STATE"),
             RetroArchCommand::FullscreenToggle => Cow::Borrowed("FULLSCREEN_TOGGLE"),
+            RetroArchCommand::CloseContent => Cow::Borrowed("CLOSE_CONTENT"),
             RetroArchCommand::Quit => Cow::Borrowed("QUIT"),
             RetroArchCommand::StateSlotPlus => Cow::Borrowed("STATE_SLOT_PLUS"),
             RetroArchCommand::StateSlotMinus => Cow::Borrowed("STATE_SLOT_MINUS"),
@@ -116,6 +128,9 @@ impl RetroArchCommand {
             RetroArchCommand::CheatToggle => Cow::Borrowed("CHEAT_TOGGLE"),
             RetroArchCommand::Screenshot => Cow::Borrowed("SCREENSHOT"),
             RetroArchCommand::Mute => Cow::Borrowed("MUTE"),
+            RetroArchCommand::Osk => Cow::Borrowed("OSK"),
+            RetroArchCommand::FpsToggle => Cow::Borrowed("FPS_TOGGLE"),
+            RetroArchCommand::StatisticsToggle => Cow::Borrowed("STATISTICS_TOGGLE"),
             RetroArchCommand::NetplayFlip => Cow::Borrowed("NETPLAY_FLIP"),
             RetroArchCommand::SlowMotion => Cow::Borrowed("SLOWMOTION"),
             RetroArchCommand::VolumeUp => Cow::Borrowed("VOLUME_UP"),
@@ -126,9 +141,13 @@ impl RetroArchCommand {
             RetroArchCommand::DiskPrev => Cow::Borrowed("DISK_PREV"),
             RetroArchCommand::GrabMouseToggle => Cow::Borrowed("GRAB_MOUSE_TOGGLE"),
             RetroArchCommand::MenuToggle => Cow::Borrowed("MENU_TOGGLE"),
+            RetroArchCommand::RecordingToggle => Cow::Borrowed("RECORDING_TOGGLE"),
+            RetroArchCommand::RunaheadToggle => Cow::Borrowed("RUNAHEAD_TOGGLE"),
             RetroArchCommand::Pause => Cow::Borrowed("PAUSE"),
             RetroArchCommand::Unpause => Cow::Borrowed("UNPAUSE"),
+            RetroArchCommand::Version => Cow::Borrowed("VERSION"),
             RetroArchCommand::GetInfo => Cow::Borrowed("GET_INFO"),
+            RetroArchCommand::GetStatus => Cow::Borrowed("GET_STATUS"),
             RetroArchCommand::GetDiskCount => Cow::Borrowed("GET_DISK_COUNT"),
             RetroArchCommand::GetDiskSlot => Cow::Borrowed("GET_DISK_SLOT"),
             RetroArchCommand::SetDiskSlot(slot) => Cow::Owned(format!("SET_DISK_SLOT {slot}")),
@@ -136,6 +155,512 @@ impl RetroArchCommand {
             RetroArchCommand::SetStateSlot(slot) => Cow::Owned(format!("SET_STATE_SLOT {slot}")),
             RetroArchCommand::SaveStateSlot(slot) => Cow::Owned(format!("SAVE_STATE_SLOT {slot}")),
             RetroArchCommand::LoadStateSlot(slot) => Cow::Owned(format!("LOAD_STATE_SLOT {slot}")),
+            RetroArchCommand::SetShader(path) => Cow::Owned(format!("SET_SHADER {path}")),
+            RetroArchCommand::GetConfigParam(name) => {
+                Cow::Owned(format!("GET_CONFIG_PARAM {name}"))
+            }
+            RetroArchCommand::ShowMsg(msg) => Cow::Owned(format!("SHOW_MSG {msg}")),
+            RetroArchCommand::ReadCoreRam { address, length } => {
+                Cow::Owned(format!("READ_CORE_RAM {address:x} {length}"))
+            }
+            RetroArchCommand::ReadCoreMemory { address, length } => {
+                Cow::Owned(format!("READ_CORE_MEMORY {address:x} {length}"))
+            }
+            RetroArchCommand::WriteCoreMemory { address, bytes } => {
+                let mut command = format!("WRITE_CORE_MEMORY {address:x}");
+                for byte in bytes {
+                    command.push_str(&format!(" {byte:02x}"));
+                }
+                Cow::Owned(command)
+            }
+        }
+    }
+}
+
+/// Sends commands to RetroArch's UDP command interface, retrying commands that don't get through
+/// or don't get a reply in time.
+#[derive(Debug, Clone, Copy)]
+pub struct RetroArchClient {
+    /// How long to wait for a reply.
+    timeout: Duration,
+    /// How many more times to try a command that fails or times out.
+    retries: u32,
+}
+
+impl Default for RetroArchClient {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_TIMEOUT,
+            retries: DEFAULT_RETRIES,
+        }
+    }
+}
+
+impl RetroArchClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Sends a command that has no reply.
+    pub async fn send(&self, command: &RetroArchCommand) -> Result<()> {
+        debug!("Sending RetroArch command: {}", command.as_str());
+        let mut attempt = 0;
+        loop {
+            match Self::connect().await {
+                Ok(socket) => match socket.send(command.as_str().as_bytes()).await {
+                    Ok(_) => return Ok(()),
+                    Err(e) if attempt >= self.retries => return Err(e.into()),
+                    Err(e) => warn!("failed to send RetroArch command, retrying: {}", e),
+                },
+                Err(e) if attempt >= self.retries => return Err(e),
+                Err(e) => warn!("failed to connect to RetroArch, retrying: {}", e),
+            }
+            attempt += 1;
+            tokio::time::sleep(RETRY_DELAY).await;
+        }
+    }
+
+    /// Sends a command, then waits for RetroArch to act on it before the next command.
+    pub async fn send_and_wait(&self, command: &RetroArchCommand, wait: Duration) -> Result<()> {
+        self.send(command).await?;
+        tokio::time::sleep(wait).await;
+        Ok(())
+    }
+
+    /// Sends a command and waits for its reply. Returns None if RetroArch doesn't reply.
+    pub async fn send_recv(&self, command: &RetroArchCommand) -> Result<Option<String>> {
+        debug!(
+            "Sending and awaiting RetroArch command: {}",
+            command.as_str()
+        );
+        let socket = Self::connect().await?;
+        let mut attempt = 0;
+        loop {
+            socket.send(command.as_str().as_bytes()).await?;
+            let mut reply = vec![0; MAX_REPLY_LEN];
+            match tokio::time::timeout(self.timeout, socket.recv_from(&mut reply)).await {
+                Ok(Ok((len, _socket))) => {
+                    reply.truncate(len);
+                    let reply = String::from_utf8(reply)?;
+                    debug!("Received reply from RetroArch: {:?}", reply);
+                    return Ok(Some(reply));
+                }
+                Ok(Err(e)) if attempt >= self.retries => {
+                    error!("Error receiving from RetroArch: {}", e);
+                    return Err(e.into());
+                }
+                Ok(Err(e)) => warn!("Error receiving from RetroArch, retrying: {}", e),
+                Err(e) if attempt >= self.retries => {
+                    error!("Timeout receiving from RetroArch: {}", e);
+                    return Ok(None);
+                }
+                Err(_) => trace!("Timeout receiving from RetroArch, retrying"),
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Gets the disk and state slots, and the frame rate. Returns None if RetroArch doesn't reply.
+    pub async fn info(&self) -> Result<Option<InfoReply>> {
+        Ok(self
+            .send_recv(&RetroArchCommand::GetInfo)
+            .await?
+            .and_then(|reply| InfoReply::parse(&reply)))
+    }
+
+    /// Gets whether content is running. Returns None if RetroArch doesn't reply.
+    pub async fn status(&self) -> Result<Option<Status>> {
+        Ok(self
+            .send_recv(&RetroArchCommand::GetStatus)
+            .await?
+            .and_then(|reply| Status::parse(&reply)))
+    }
+
+    pub async fn version(&self) -> Result<Option<String>> {
+        Ok(self
+            .send_recv(&RetroArchCommand::Version)
+            .await?
+            .map(|reply| reply.trim().to_string()))
+    }
+
+    /// Gets the value of a config setting, or None if it isn't set.
+    pub async fn config_param(&self, name: &str) -> Result<Option<String>> {
+        Ok(self
+            .send_recv(&RetroArchCommand::GetConfigParam(name.to_string()))
+            .await?
+            .and_then(|reply| parse_config_param(&reply, name)))
+    }
+
+    /// Reads the core's memory map. Returns None if the memory can't be read.
+    pub async fn read_core_memory(&self, address: u32, length: usize) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .send_recv(&RetroArchCommand::ReadCoreMemory { address, length })
+            .await?
+            .and_then(|reply| parse_memory(&reply)))
+    }
+
+    /// Whether RetroArch is running and answering commands.
+    pub async fn is_alive(&self) -> bool {
+        matches!(
+            self.send_recv(&RetroArchCommand::GetInfo).await,
+            Ok(Some(_))
+        )
+    }
+
+    async fn connect() -> Result<UdpSocket> {
+        let socket = UdpSocket::bind("0.0.0.0:0").await?;
+        trace!("Bound UDP socket: {}", socket.local_addr()?);
+        socket.connect(RETROARCH_UDP_SOCKET).await?;
+        trace!(
+            "Connecting to RetroArch UDP socket: {}",
+            RETROARCH_UDP_SOCKET
+        );
+        Ok(socket)
+    }
+}
+
+/// Reply to `GET_INFO`, e.g. `GET_INFO 3 0 1 59.7`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InfoReply {
+    pub max_disk_slots: u8,
+    pub disk_slot: u8,
+    /// None if the core doesn't support save states.
+    pub state_slot: Option<i8>,
+    pub fps: Option<f32>,
+}
+
+impl InfoReply {
+    pub fn parse(reply: &str) -> Option<Self> {
+        let mut rets = reply.split_ascii_whitespace();
+        if rets.next()? != "GET_INFO" {
+            return None;
+        }
+
+        let max_disk_slots = rets.next().map_or(0, |s| s.parse().unwrap_or(0));
+        let disk_slot = rets.next().map_or(0, |s| s.parse().unwrap_or(0));
+        let state_slot = rets.next().map(|s| s.parse().unwrap_or(0));
+        let fps = rets.next().and_then(|s| s.parse().ok());
+
+        Some(Self {
+            max_disk_slots,
+            disk_slot,
+            state_slot,
+            fps,
+        })
+    }
+}
+
+/// Reply to `GET_STATUS`, e.g. `GET_STATUS PAUSED game_boy,Tetris,crc32=46df91ad`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Contentless,
+    Playing(Content),
+    Paused(Content),
+}
+
+/// Content that is loaded in RetroArch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Content {
+    pub system: String,
+    pub game: String,
+    pub crc32: Option<String>,
+}
+
+impl Status {
+    pub fn parse(reply: &str) -> Option<Self> {
+        let reply = reply.trim().strip_prefix("GET_STATUS ")?;
+        let (state, content) = reply.split_once(' ').unwrap_or((reply, ""));
+        if state == "CONTENTLESS" {
+            return Some(Status::Contentless);
+        }
+
+        let mut parts = content.split(',');
+        let system = parts.next().unwrap_or_default().to_string();
+        let game = parts.next().unwrap_or_default().to_string();
+        let crc32 = parts
+            .next()
+            .and_then(|crc32| crc32.strip_prefix("crc32="))
+            .map(str::to_string);
+        let content = Content {
+            system,
+            game,
+            crc32,
+        };
+
+        match state {
+            "PLAYING" => Some(Status::Playing(content)),
+            "PAUSED" => Some(Status::Paused(content)),
+            _ => None,
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        matches!(self, Status::Paused(_))
+    }
+}
+
+/// Parses the reply to `GET_CONFIG_PARAM`, e.g. `GET_CONFIG_PARAM video_scale 2`.
+pub fn parse_config_param(reply: &str, name: &str) -> Option<String> {
+    let value = reply
+        .trim()
+        .strip_prefix("GET_CONFIG_PARAM ")?
+        .strip_prefix(name)?
+        .strip_prefix(' ')?;
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Parses the reply to `READ_CORE_MEMORY` or `READ_CORE_RAM`, e.g.
+/// `READ_CORE_MEMORY 1a2b 0f ff 00`. Failed reads reply with `-1` in place of the bytes.
+pub fn parse_memory(reply: &str) -> Option<Vec<u8>> {
+    let mut parts = reply.split_ascii_whitespace();
+    if !matches!(parts.next()?, "READ_CORE_MEMORY" | "READ_CORE_RAM") {
+        return None;
+    }
+    let _address = parts.next()?;
+    parts
+        .map(|byte| u8::from_str_radix(byte, 16).ok())
+        .collect()
+}
+
+/// Parses RetroArch's `key = "value"` config format. Comments and malformed lines are skipped.
+pub fn parse_config(text: &str) -> Vec<(String, String)> {
+    text.lines().filter_map(parse_config_line).collect()
+}
+
+fn parse_config_line(line: &str) -> Option<(String, String)> {
+    let line = line.trim();
+    if line.starts_with('#') {
+        return None;
+    }
+    let (key, value) = line.split_once('=')?;
+    let value = value.trim();
+    let value = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value);
+    Some((key.trim().to_string(), value.to_string()))
+}
+
+/// A RetroArch config file, e.g. `retroarch.cfg` or an override. Comments and settings that
+/// aren't changed are written back as they were.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    lines: Vec<ConfigLine>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ConfigLine {
+    Entry(String, String),
+    /// A comment, blank line, or anything else that isn't a setting.
+    Other(String),
+}
+
+impl Config {
+    pub fn parse(text: &str) -> Self {
+        let lines = text
+            .lines()
+            .map(|line| match parse_config_line(line) {
+                Some((key, value)) => ConfigLine::Entry(key, value),
+                None => ConfigLine::Other(line.to_string()),
+            })
+            .collect();
+        Self { lines }
+    }
+
+    /// Loads a config file, or an empty config if it doesn't exist yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        debug!("loading RetroArch config from {}", path.display());
+        Ok(Self::parse(&fs::read_to_string(path)?))
+    }
+
+    /// Writes the config file. RetroArch may be reading it, so it is replaced in one go.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        debug!("saving RetroArch config to {}", path.display());
+        let part = path.with_extension("part");
+        fs::write(&part, self.serialize())?;
+        fs::rename(&part, path)?;
+        Ok(())
+    }
+
+    pub fn serialize(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| match line {
+                ConfigLine::Entry(key, value) => format!("{key} = \"{value}\"\n"),
+                ConfigLine::Other(line) => format!("{line}\n"),
+            })
+            .collect()
+    }
+
+    /// The settings, in file order.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.lines.iter().filter_map(|line| match line {
+            ConfigLine::Entry(key, value) => Some((key.as_str(), value.as_str())),
+            ConfigLine::Other(_) => None,
+        })
+    }
+
+    /// Whether the config has no settings.
+    pub fn is_empty(&self) -> bool {
+        self.entries().next().is_none()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries().find(|(k, _)| *k == key).map(|(_, v)| v)
+    }
+
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        self.get(key).map(|v| v == "true")
+    }
+
+    pub fn get_int(&self, key: &str) -> Option<i32> {
+        self.get(key).and_then(|v| v.parse().ok())
+    }
+
+    /// Changes a setting in place, or adds it at the end.
+    pub fn set(&mut self, key: &str, value: impl ToString) {
+        let value = value.to_string();
+        match self.entry_mut(key) {
+            Some(entry) => *entry = value,
+            None => self.lines.push(ConfigLine::Entry(key.to_string(), value)),
         }
     }
+
+    /// Removes a setting, so that RetroArch uses its default. Returns the old value.
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        let i = self
+            .lines
+            .iter()
+            .position(|line| matches!(line, ConfigLine::Entry(k, _) if k == key))?;
+        match self.lines.remove(i) {
+            ConfigLine::Entry(_, value) => Some(value),
+            ConfigLine::Other(_) => None,
+        }
+    }
+
+    fn entry_mut(&mut self, key: &str) -> Option<&mut String> {
+        self.lines.iter_mut().find_map(|line| match line {
+            ConfigLine::Entry(k, value) if k == key => Some(value),
+            _ => None,
+        })
+    }
+}
+
+/// RetroArch names per-core directories (overrides, save states) after the `corename` in the
+/// core's info file.
+pub fn core_name(libretro_core: &str) -> Option<String> {
+    let info = RETROARCH_CORES_DIR.join(format!("{libretro_core}_libretro.info"));
+    match fs::read_to_string(&info) {
+        Ok(info) => parse_config(&info)
+            .into_iter()
+            .find(|(k, _)| k == "corename")
+            .map(|(_, v)| v),
+        Err(e) => {
+            warn!("failed to read core info {}: {}", info.display(), e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config() {
+        let mut config = Config::parse(
+            "# Comment\nvideo_scale = \"2\"\n\nrewind_enable = true\ninput_player1_a = \"x\"\n",
+        );
+        assert_eq!(config.get("video_scale"), Some("2"));
+        assert_eq!(config.get_bool("rewind_enable"), Some(true));
+        assert_eq!(config.get_int("video_scale"), Some(2));
+
+        config.set("video_scale", 3);
+        config.set("fps_show", true);
+        assert_eq!(config.remove("input_player1_a"), Some("x".into()));
+        assert_eq!(config.remove("input_player1_a"), None);
+        assert_eq!(
+            config.serialize(),
+            "# Comment\nvideo_scale = \"3\"\n\nrewind_enable = \"true\"\nfps_show = \"true\"\n"
+        );
+        assert!(!config.is_empty());
+        assert!(Config::parse("# Only a comment\n").is_empty());
+    }
+
+    #[test]
+    fn test_info_reply() {
+        assert_eq!(
+            InfoReply::parse("GET_INFO 3 1 -1 59.7"),
+            Some(InfoReply {
+                max_disk_slots: 3,
+                disk_slot: 1,
+                state_slot: Some(-1),
+                fps: Some(59.7),
+            })
+        );
+        let info = InfoReply::parse("GET_INFO 0 0").unwrap();
+        assert_eq!(info.state_slot, None);
+        assert_eq!(info.fps, None);
+        assert_eq!(InfoReply::parse("GET_STATUS CONTENTLESS"), None);
+    }
+
+    #[test]
+    fn test_status() {
+        assert_eq!(
+            Status::parse("GET_STATUS PAUSED game_boy,Tetris,crc32=46df91ad\n"),
+            Some(Status::Paused(Content {
+                system: "game_boy".into(),
+                game: "Tetris".into(),
+                crc32: Some("46df91ad".into()),
+            }))
+        );
+        let status = Status::parse("GET_STATUS PLAYING super_nes,Game").unwrap();
+        assert!(!status.is_paused());
+        assert_eq!(
+            Status::parse("GET_STATUS CONTENTLESS"),
+            Some(Status::Contentless)
+        );
+        assert_eq!(Status::parse("GET_INFO 0 0"), None);
+    }
+
+    #[test]
+    fn test_parse_replies() {
+        assert_eq!(
+            parse_config_param("GET_CONFIG_PARAM video_scale 2\n", "video_scale"),
+            Some("2".into())
+        );
+        assert_eq!(
+            parse_config_param("GET_CONFIG_PARAM video_scale_integer true", "video_scale"),
+            None
+        );
+        assert_eq!(
+            parse_memory("READ_CORE_MEMORY 1a2b 0f ff 00"),
+            Some(vec![0x0f, 0xff, 0x00])
+        );
+        assert_eq!(parse_memory("READ_CORE_RAM 1a2b -1"), None);
+        assert_eq!(
+            RetroArchCommand::WriteCoreMemory {
+                address: 0x1a2b,
+                bytes: vec![0x0f, 0xff],
+            }
+            .as_str(),
+            "WRITE_CORE_MEMORY 1a2b 0f ff"
+        );
+    }
 }
@@ -0,0 +1,217 @@
+//! RetroArch hotkey bindings in `retroarch.cfg`, and conflicts between them and Allium's own
+//! global hotkeys. RetroArch reads the Miyoo's buttons as keyboard keys.
+
+use anyhow::Result;
+use strum::{EnumCount, FromRepr};
+
+use crate::constants::RETROARCH_CONFIG;
+use crate::platform::Key;
+use crate::retroarch::Config;
+
+/// Buttons that can be bound to a hotkey.
+pub const HOTKEY_BUTTONS: [Key; 15] = [
+    Key::A,
+    Key::B,
+    Key::X,
+    Key::Y,
+    Key::L,
+    Key::R,
+    Key::L2,
+    Key::R2,
+    Key::Start,
+    Key::Select,
+    Key::Menu,
+    Key::Up,
+    Key::Down,
+    Key::Left,
+    Key::Right,
+];
+
+/// Buttons that alliumd handles while Menu is held, e.g. Menu + Up for brightness.
+pub(crate) const ALLIUM_MENU_COMBOS: [Key; 7] = [
+    Key::Up,
+    Key::Down,
+    Key::Left,
+    Key::Right,
+    Key::L2,
+    Key::R2,
+    Key::Select,
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, FromRepr, EnumCount)]
+pub enum Hotkey {
+    /// Held to enable the other hotkeys. If unbound, hotkeys work on their own.
+    Enable,
+    MenuToggle,
+    SaveState,
+    LoadState,
+    FastForward,
+}
+
+impl Hotkey {
+    pub fn config_key(self) -> &'static str {
+        match self {
+            Hotkey::Enable => "input_enable_hotkey",
+            Hotkey::MenuToggle => "input_menu_toggle",
+            Hotkey::SaveState => "input_save_state",
+            Hotkey::LoadState => "input_load_state",
+            Hotkey::FastForward => "input_toggle_fast_forward",
+        }
+    }
+}
+
+/// Why a hotkey binding doesn't work as expected.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Conflict {
+    /// Allium handles the button combination first.
+    Allium,
+    /// Another hotkey is bound to the same button.
+    Hotkey(Hotkey),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RetroArchHotkeys {
+    bindings: [Option<Key>; Hotkey::COUNT],
+}
+
+impl RetroArchHotkeys {
+    pub fn load() -> Result<Self> {
+        Ok(Self::from_config(&Config::load(&RETROARCH_CONFIG)?))
+    }
+
+    fn from_config(config: &Config) -> Self {
+        let mut bindings = [None; Hotkey::COUNT];
+        for (i, binding) in bindings.iter_mut().enumerate() {
+            let hotkey = Hotkey::from_repr(i).unwrap();
+            *binding = config.get(hotkey.config_key()).and_then(key_from_retroarch);
+        }
+        Self { bindings }
+    }
+
+    /// Writes the bindings to `retroarch.cfg`, keeping its other settings.
+    pub fn save(&self) -> Result<()> {
+        let mut config = Config::load(&RETROARCH_CONFIG)?;
+        for (i, binding) in self.bindings.iter().enumerate() {
+            let key = Hotkey::from_repr(i).unwrap().config_key();
+            config.set(key, binding.map_or("nul", retroarch_key_name));
+        }
+        config.save(&RETROARCH_CONFIG)
+    }
+
+    pub fn get(&self, hotkey: Hotkey) -> Option<Key> {
+        self.bindings[hotkey as usize]
+    }
+
+    pub fn set(&mut self, hotkey: Hotkey, key: Option<Key>) {
+        self.bindings[hotkey as usize] = key;
+    }
+
+    /// Finds what stops a hotkey from working, if anything.
+    pub fn conflict(&self, hotkey: Hotkey) -> Option<Conflict> {
+        let key = self.get(hotkey)?;
+        let modifier = self.get(Hotkey::Enable);
+
+        if hotkey == Hotkey::Enable {
+            // Allium's combos take precedence over any hotkey held with Menu
+            let has_combo = (1..Hotkey::COUNT)
+                .filter_map(|i| self.bindings[i])
+                .any(|k| ALLIUM_MENU_COMBOS.contains(&k));
+            return (key == Key::Menu && has_combo).then_some(Conflict::Allium);
+        }
+
+        if modifier.is_none() && key == Key::Menu
+            || modifier == Some(Key::Menu) && ALLIUM_MENU_COMBOS.contains(&key)
+        {
+            return Some(Conflict::Allium);
+        }
+
+        (1..Hotkey::COUNT)
+            .map(|i| Hotkey::from_repr(i).unwrap())
+            .find(|other| *other != hotkey && self.get(*other) == Some(key))
+            .map(Conflict::Hotkey)
+    }
+}
+
+/// RetroArch's name for the keyboard key that a button sends.
+fn retroarch_key_name(key: Key) -> &'static str {
+    match key {
+        Key::Up => "up",
+        Key::Down => "down",
+        Key::Left => "left",
+        Key::Right => "right",
+        Key::A => "space",
+        Key::B => "ctrl",
+        Key::X => "shift",
+        Key::Y => "alt",
+        Key::Start => "enter",
+        Key::Select => "rctrl",
+        Key::L => "e",
+        Key::R => "t",
+        Key::Menu => "escape",
+        Key::L2 => "tab",
+        Key::R2 => "backspace",
+        _ => "nul",
+    }
+}
+
+fn key_from_retroarch(name: &str) -> Option<Key> {
+    HOTKEY_BUTTONS
+        .into_iter()
+        .find(|key| retroarch_key_name(*key) == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hotkeys(config: &[(&str, &str)]) -> RetroArchHotkeys {
+        let mut hotkeys = Config::default();
+        for (key, value) in config {
+            hotkeys.set(key, value);
+        }
+        RetroArchHotkeys::from_config(&hotkeys)
+    }
+
+    #[test]
+    fn test_from_config() {
+        let hotkeys = hotkeys(&[
+            ("input_enable_hotkey", "rctrl"),
+            ("input_menu_toggle", "escape"),
+            ("input_save_state", "nul"),
+            ("input_toggle_fast_forward", "tab"),
+        ]);
+        assert_eq!(hotkeys.get(Hotkey::Enable), Some(Key::Select));
+        assert_eq!(hotkeys.get(Hotkey::MenuToggle), Some(Key::Menu));
+        assert_eq!(hotkeys.get(Hotkey::SaveState), None);
+        assert_eq!(hotkeys.get(Hotkey::LoadState), None);
+        assert_eq!(hotkeys.get(Hotkey::FastForward), Some(Key::L2));
+    }
+
+    #[test]
+    fn test_conflict() {
+        let mut hotkeys = hotkeys(&[
+            ("input_enable_hotkey", "rctrl"),
+            ("input_save_state", "e"),
+            ("input_load_state", "t"),
+        ]);
+        assert_eq!(hotkeys.conflict(Hotkey::SaveState), None);
+        assert_eq!(hotkeys.conflict(Hotkey::MenuToggle), None);
+
+        hotkeys.set(Hotkey::LoadState, Some(Key::L));
+        assert_eq!(
+            hotkeys.conflict(Hotkey::LoadState),
+            Some(Conflict::Hotkey(Hotkey::SaveState))
+        );
+
+        // Menu alone opens Allium's menu
+        hotkeys.set(Hotkey::Enable, None);
+        hotkeys.set(Hotkey::MenuToggle, Some(Key::Menu));
+        assert_eq!(hotkeys.conflict(Hotkey::MenuToggle), Some(Conflict::Allium));
+
+        // Menu + Up changes the brightness
+        hotkeys.set(Hotkey::Enable, Some(Key::Menu));
+        hotkeys.set(Hotkey::MenuToggle, Some(Key::Up));
+        assert_eq!(hotkeys.conflict(Hotkey::MenuToggle), Some(Conflict::Allium));
+        assert_eq!(hotkeys.conflict(Hotkey::Enable), Some(Conflict::Allium));
+    }
+}
@@ -0,0 +1,174 @@
+//! Imports core options, overrides, and input remaps from another RetroArch install into
+//! Allium's RetroArch config folder. Changes are previewed before they are applied, and existing
+//! settings that the import doesn't mention are kept.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use log::{debug, info};
+
+use crate::constants::{RETROARCH_CONFIG_DIR, RETROARCH_IMPORT_DIRS};
+use crate::retroarch::Config;
+
+/// Core options, overrides, and remaps.
+const CONFIG_EXTENSIONS: [&str; 3] = ["opt", "cfg", "rmp"];
+
+/// A setting that the import adds or changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyChange {
+    pub key: String,
+    /// Current value, or None if the setting is new.
+    pub old: Option<String>,
+    pub new: String,
+}
+
+/// A config file that the import creates or changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    /// Path relative to the config folder, e.g. `remaps/mGBA/mGBA.rmp`.
+    pub name: PathBuf,
+    source: PathBuf,
+    /// Whether the file doesn't exist yet.
+    pub is_new: bool,
+    pub keys: Vec<KeyChange>,
+}
+
+/// Lists the changes that importing would make, sorted by file.
+pub fn preview() -> Result<Vec<FileChange>> {
+    // Later folders take precedence
+    let mut sources = BTreeMap::new();
+    for dir in RETROARCH_IMPORT_DIRS.iter() {
+        let dir = if dir.join("config").is_dir() {
+            dir.join("config")
+        } else {
+            dir.clone()
+        };
+        if dir == *RETROARCH_CONFIG_DIR || !dir.is_dir() {
+            continue;
+        }
+        debug!("finding RetroArch configs in {}", dir.display());
+        for source in config_files(&dir) {
+            if let Ok(name) = source.strip_prefix(&dir) {
+                sources.insert(name.to_path_buf(), source.clone());
+            }
+        }
+    }
+
+    let mut changes = Vec::new();
+    for (name, source) in sources {
+        let dest = RETROARCH_CONFIG_DIR.join(&name);
+        let is_new = !dest.exists();
+        let keys = diff(&Config::load(&dest)?, &Config::load(&source)?);
+        if !keys.is_empty() {
+            changes.push(FileChange {
+                name,
+                source,
+                is_new,
+                keys,
+            });
+        }
+    }
+    Ok(changes)
+}
+
+/// Applies the changes, merging them into the existing config files.
+pub fn apply(changes: &[FileChange]) -> Result<()> {
+    for change in changes {
+        let dest = RETROARCH_CONFIG_DIR.join(&change.name);
+        info!(
+            "importing {} to {}",
+            change.source.display(),
+            dest.display()
+        );
+        let mut config = Config::load(&dest)?;
+        merge(&mut config, &change.keys);
+        config.save(&dest)?;
+    }
+    Ok(())
+}
+
+/// Finds the config files in a folder, recursively.
+fn config_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut queue = vec![dir.to_path_buf()];
+    while let Some(dir) = queue.pop() {
+        for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                queue.push(path);
+            } else if path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| CONFIG_EXTENSIONS.contains(&ext))
+            {
+                files.push(path);
+            }
+        }
+    }
+    files
+}
+
+/// Lists the settings in `new` that are missing from or different in `current`.
+fn diff(current: &Config, new: &Config) -> Vec<KeyChange> {
+    new.entries()
+        .filter_map(|(key, value)| {
+            let old = current.get(key);
+            (old != Some(value)).then(|| KeyChange {
+                key: key.to_string(),
+                old: old.map(str::to_string),
+                new: value.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn merge(config: &mut Config, changes: &[KeyChange]) {
+    for change in changes {
+        config.set(&change.key, &change.new);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(entries: &[(&str, &str)]) -> Config {
+        let mut config = Config::default();
+        for (key, value) in entries {
+            config.set(key, value);
+        }
+        config
+    }
+
+    #[test]
+    fn test_diff_and_merge() {
+        let mut current = entries(&[("video_scale", "2"), ("input_player1_a", "8")]);
+        let new = entries(&[("video_scale", "3"), ("input_player1_a", "8"), ("fps", "1")]);
+
+        let changes = diff(&current, &new);
+        assert_eq!(
+            changes,
+            [
+                KeyChange {
+                    key: "video_scale".into(),
+                    old: Some("2".into()),
+                    new: "3".into(),
+                },
+                KeyChange {
+                    key: "fps".into(),
+                    old: None,
+                    new: "1".into(),
+                },
+            ]
+        );
+
+        merge(&mut current, &changes);
+        assert_eq!(
+            current,
+            entries(&[("video_scale", "3"), ("input_player1_a", "8"), ("fps", "1")])
+        );
+        assert!(diff(&current, &new).is_empty());
+    }
+}
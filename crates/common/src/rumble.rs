@@ -0,0 +1,110 @@
+//! Vibration feedback, on devices with a vibration motor.
+
+use std::fs::{self, File};
+use std::time::Duration;
+
+use anyhow::Result;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{
+    ALLIUM_RUMBLE_SETTINGS, RUMBLE_CONFIRM_DURATION, RUMBLE_NAVIGATION_DURATION,
+};
+use crate::platform::{Key, KeyEvent, Platform};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RumbleSettings {
+    /// Vibrate when the selection moves.
+    #[serde(default)]
+    pub navigation: bool,
+    /// Vibrate when confirming a selection.
+    #[serde(default)]
+    pub confirm: bool,
+    /// Vibrate with low battery warnings.
+    #[serde(default = "RumbleSettings::default_low_battery")]
+    pub low_battery: bool,
+}
+
+impl Default for RumbleSettings {
+    fn default() -> Self {
+        Self {
+            navigation: false,
+            confirm: false,
+            low_battery: Self::default_low_battery(),
+        }
+    }
+}
+
+impl RumbleSettings {
+    fn default_low_battery() -> bool {
+        true
+    }
+
+    pub fn load() -> Result<Self> {
+        if ALLIUM_RUMBLE_SETTINGS.exists() {
+            debug!("found state, loading from file");
+            let file = File::open(ALLIUM_RUMBLE_SETTINGS.as_path())?;
+            if let Ok(json) = serde_json::from_reader(file) {
+                return Ok(json);
+            }
+            warn!("failed to read rumble settings file, removing");
+            fs::remove_file(ALLIUM_RUMBLE_SETTINGS.as_path())?;
+        }
+        Ok(Self::default())
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let file = File::create(ALLIUM_RUMBLE_SETTINGS.as_path())?;
+        serde_json::to_writer(file, &self)?;
+        Ok(())
+    }
+
+    /// How long to vibrate for a key event that was handled by a view, if at all.
+    pub fn duration_for_key_event(&self, event: KeyEvent) -> Option<Duration> {
+        match event {
+            KeyEvent::Pressed(Key::Up | Key::Down | Key::Left | Key::Right | Key::L | Key::R)
+            | KeyEvent::Autorepeat(
+                Key::Up | Key::Down | Key::Left | Key::Right | Key::L | Key::R,
+            ) if self.navigation => Some(RUMBLE_NAVIGATION_DURATION),
+            KeyEvent::Pressed(Key::A) if self.confirm => Some(RUMBLE_CONFIRM_DURATION),
+            _ => None,
+        }
+    }
+}
+
+/// Vibrates for a key event that was handled by a view, if enabled.
+pub fn rumble_for_key_event(platform: &impl Platform, settings: &RumbleSettings, event: KeyEvent) {
+    if let Some(duration) = settings.duration_for_key_event(event)
+        && let Err(e) = platform.rumble(duration)
+    {
+        warn!("failed to rumble: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duration_for_key_event() {
+        let mut settings = RumbleSettings::default();
+        assert_eq!(
+            settings.duration_for_key_event(KeyEvent::Pressed(Key::Down)),
+            None
+        );
+
+        settings.navigation = true;
+        assert_eq!(
+            settings.duration_for_key_event(KeyEvent::Autorepeat(Key::Down)),
+            Some(RUMBLE_NAVIGATION_DURATION)
+        );
+        assert_eq!(
+            settings.duration_for_key_event(KeyEvent::Pressed(Key::A)),
+            None
+        );
+        assert_eq!(
+            settings.duration_for_key_event(KeyEvent::Released(Key::Down)),
+            None
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use std::cmp::Reverse;
+use std::fs::{self, File};
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::{Local, NaiveDateTime};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{ALLIUM_SAVE_BACKUP_SETTINGS, ALLIUM_SAVE_BACKUPS_DIR, RETROARCH_SAVES_DIR};
+
+const BACKUP_TIME_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveBackupSettings {
+    /// Number of save file backups to keep per game. 0 disables backups.
+    pub save_backups: i32,
+    /// Number of previous versions to keep per save state slot. 0 disables backups.
+    pub state_backups: i32,
+}
+
+impl Default for SaveBackupSettings {
+    fn default() -> Self {
+        Self {
+            save_backups: 10,
+            state_backups: 3,
+        }
+    }
+}
+
+impl SaveBackupSettings {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn load() -> Result<Self> {
+        if ALLIUM_SAVE_BACKUP_SETTINGS.exists() {
+            debug!("found state, loading from file");
+            let file = File::open(ALLIUM_SAVE_BACKUP_SETTINGS.as_path())?;
+            if let Ok(json) = serde_json::from_reader(file) {
+                return Ok(json);
+            }
+            warn!("failed to read save backup file, removing");
+            fs::remove_file(ALLIUM_SAVE_BACKUP_SETTINGS.as_path())?;
+        }
+        Ok(Self::new())
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let file = File::create(ALLIUM_SAVE_BACKUP_SETTINGS.as_path())?;
+        serde_json::to_writer(file, &self)?;
+        Ok(())
+    }
+}
+
+/// Path of a game's battery save. RetroArch sorts saves by core name, and names them after the
+/// game file without its extension.
+pub fn save_path(core_name: &str, stem: &str) -> PathBuf {
+    RETROARCH_SAVES_DIR
+        .join(core_name)
+        .join(format!("{stem}.srm"))
+}
+
+fn backup_dir(core_name: &str, stem: &str) -> PathBuf {
+    ALLIUM_SAVE_BACKUPS_DIR.join(core_name).join(stem)
+}
+
+/// A dated copy of a game's battery save.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveBackup {
+    pub path: PathBuf,
+    pub time: NaiveDateTime,
+}
+
+impl SaveBackup {
+    /// Lists the backups of a game, newest first.
+    pub fn list(core_name: &str, stem: &str) -> Result<Vec<Self>> {
+        let dir = backup_dir(core_name, stem);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut backups = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "srm") {
+                continue;
+            }
+            let Some(time) = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(|s| NaiveDateTime::parse_from_str(s, BACKUP_TIME_FORMAT).ok())
+            else {
+                continue;
+            };
+            backups.push(Self { path, time });
+        }
+        backups.sort_unstable_by_key(|b| Reverse(b.time));
+        Ok(backups)
+    }
+
+    /// Replaces the game's save with this backup. The current save is backed up first.
+    pub fn restore(&self, core_name: &str, stem: &str) -> Result<()> {
+        snapshot(core_name, stem)?;
+        let path = save_path(core_name, stem);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        info!("restoring {} to {}", self.path.display(), path.display());
+        fs::copy(&self.path, path)?;
+        Ok(())
+    }
+
+    pub fn delete(&self) -> Result<()> {
+        fs::remove_file(&self.path)?;
+        Ok(())
+    }
+}
+
+/// Copies the game's battery save into a dated backup, unless it is unchanged since the last
+/// backup, then deletes backups beyond `max_backups`.
+pub fn backup(core_name: &str, stem: &str, max_backups: usize) -> Result<()> {
+    if max_backups == 0 {
+        return Ok(());
+    }
+
+    snapshot(core_name, stem)?;
+
+    for backup in SaveBackup::list(core_name, stem)?.iter().skip(max_backups) {
+        debug!("removing old backup {}", backup.path.display());
+        backup.delete()?;
+    }
+
+    Ok(())
+}
+
+fn snapshot(core_name: &str, stem: &str) -> Result<()> {
+    let path = save_path(core_name, stem);
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let save = fs::read(&path)?;
+    let is_unchanged = SaveBackup::list(core_name, stem)?
+        .first()
+        .is_some_and(|latest| fs::read(&latest.path).is_ok_and(|b| b == save));
+    if is_unchanged {
+        return Ok(());
+    }
+
+    let dir = backup_dir(core_name, stem);
+    fs::create_dir_all(&dir)?;
+    let backup_path = dir.join(format!("{}.srm", Local::now().format(BACKUP_TIME_FORMAT)));
+    info!("backing up {} to {}", path.display(), backup_path.display());
+    fs::write(&backup_path, save)?;
+    Ok(())
+}
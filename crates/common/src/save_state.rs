@@ -0,0 +1,263 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use base32::encode;
+use sha2::{Digest, Sha256};
+
+use crate::constants::{ALLIUM_SCREENSHOTS_DIR, RETROARCH_STATES_DIR};
+
+/// Slot used by RetroArch for the auto save state.
+pub const AUTO_SLOT: i8 = -1;
+
+/// Path of the screenshot that is taken when saving to a state slot.
+/// `path` is the canonicalized game path and `core` is the Allium core name.
+pub fn screenshot_path(path: &str, core: &str, slot: i8) -> PathBuf {
+    let mut hasher = Sha256::new();
+    hasher.update(path);
+    hasher.update(core);
+    hasher.update(slot.to_le_bytes());
+    hashed_path(hasher)
+}
+
+/// Previously, the hash did not include the core name.
+pub fn legacy_screenshot_path(path: &str, slot: i8) -> PathBuf {
+    let mut hasher = Sha256::new();
+    hasher.update(path);
+    hasher.update(slot.to_le_bytes());
+    hashed_path(hasher)
+}
+
+fn hashed_path(hasher: Sha256) -> PathBuf {
+    let hash = hasher.finalize();
+    let base32 = encode(base32::Alphabet::Crockford, &hash);
+    ALLIUM_SCREENSHOTS_DIR.join(format!("{}.png", base32))
+}
+
+/// A RetroArch save state file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveState {
+    pub slot: i8,
+    pub path: PathBuf,
+}
+
+impl SaveState {
+    /// Path of the state file for a slot. RetroArch sorts save states by core name, and names
+    /// them after the game file without its extension.
+    pub fn path(core_name: &str, stem: &str, slot: i8) -> PathBuf {
+        let extension = match slot {
+            AUTO_SLOT => "state.auto".to_string(),
+            0 => "state".to_string(),
+            slot => format!("state{slot}"),
+        };
+        RETROARCH_STATES_DIR
+            .join(core_name)
+            .join(format!("{stem}.{extension}"))
+    }
+
+    /// Lists the save states of a game, sorted by slot.
+    pub fn list(core_name: &str, stem: &str) -> Result<Vec<Self>> {
+        let dir = RETROARCH_STATES_DIR.join(core_name);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let prefix = format!("{stem}.state");
+        let mut states = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let Some(slot) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix(&prefix))
+                .and_then(parse_slot)
+            else {
+                continue;
+            };
+            states.push(Self { slot, path });
+        }
+        states.sort_unstable_by_key(|s| s.slot);
+        Ok(states)
+    }
+
+    /// Thumbnail that RetroArch writes next to the state, if enabled.
+    pub fn thumbnail(&self) -> PathBuf {
+        with_suffix(&self.path, ".png")
+    }
+
+    /// Path of a previous version of the state, kept when the slot was overwritten. Generation 1
+    /// is the most recent.
+    pub fn backup_path(&self, generation: usize) -> PathBuf {
+        with_suffix(&self.path, &format!(".bak{generation}"))
+    }
+
+    /// Number of previous versions of the state.
+    pub fn backup_count(&self) -> usize {
+        (1..)
+            .take_while(|&generation| self.backup_path(generation).exists())
+            .count()
+    }
+
+    /// Keeps the current state as the most recent of up to `max_backups` previous versions. This
+    /// should be called before the slot is overwritten.
+    pub fn backup(&self, max_backups: usize) -> Result<()> {
+        if max_backups == 0 || !self.path.exists() {
+            return Ok(());
+        }
+
+        for generation in (max_backups..=self.backup_count()).rev() {
+            remove_with_thumbnail(&self.backup_path(generation))?;
+        }
+        for generation in (1..max_backups).rev() {
+            let path = self.backup_path(generation);
+            if path.exists() {
+                rename_with_thumbnail(&path, &self.backup_path(generation + 1))?;
+            }
+        }
+
+        let backup = self.backup_path(1);
+        fs::copy(&self.path, &backup)?;
+        let thumbnail = self.thumbnail();
+        if thumbnail.exists() {
+            fs::copy(thumbnail, with_suffix(&backup, ".png"))?;
+        }
+        Ok(())
+    }
+
+    /// Swaps the state with its most recent previous version, so that restoring again undoes the
+    /// restore.
+    pub fn restore_previous(&self) -> Result<()> {
+        let previous = self.backup_path(1);
+        if !previous.exists() {
+            return Ok(());
+        }
+
+        let current = with_suffix(&self.path, ".swap");
+        if self.path.exists() {
+            rename_with_thumbnail(&self.path, &current)?;
+        }
+        rename_with_thumbnail(&previous, &self.path)?;
+        if current.exists() {
+            rename_with_thumbnail(&current, &previous)?;
+        }
+        Ok(())
+    }
+
+    /// Deletes the state, its previous versions and its RetroArch thumbnail.
+    pub fn delete(&self) -> Result<()> {
+        for generation in (1..=self.backup_count()).rev() {
+            remove_with_thumbnail(&self.backup_path(generation))?;
+        }
+        remove_with_thumbnail(&self.path)
+    }
+
+    /// Copies the state and its RetroArch thumbnail to `path`.
+    pub fn copy_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&self.path, path)?;
+        let thumbnail = self.thumbnail();
+        if thumbnail.exists() {
+            fs::copy(thumbnail, with_suffix(path, ".png"))?;
+        }
+        Ok(())
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.to_path_buf().into_os_string();
+    path.push(suffix);
+    path.into()
+}
+
+fn remove_with_thumbnail(path: &Path) -> Result<()> {
+    fs::remove_file(path)?;
+    let thumbnail = with_suffix(path, ".png");
+    if thumbnail.exists() {
+        fs::remove_file(thumbnail)?;
+    }
+    Ok(())
+}
+
+fn rename_with_thumbnail(from: &Path, to: &Path) -> Result<()> {
+    fs::rename(from, to)?;
+    let thumbnail = with_suffix(from, ".png");
+    let dest = with_suffix(to, ".png");
+    if thumbnail.exists() {
+        fs::rename(thumbnail, dest)?;
+    } else if dest.exists() {
+        fs::remove_file(dest)?;
+    }
+    Ok(())
+}
+
+/// Parses the slot from the part of the file name after `.state`.
+fn parse_slot(suffix: &str) -> Option<i8> {
+    match suffix {
+        "" => Some(0),
+        ".auto" => Some(AUTO_SLOT),
+        n => n.parse().ok().filter(|n| *n > 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_slot() {
+        assert_eq!(parse_slot(""), Some(0));
+        assert_eq!(parse_slot(".auto"), Some(AUTO_SLOT));
+        assert_eq!(parse_slot("3"), Some(3));
+        assert_eq!(parse_slot("3.png"), None);
+        assert_eq!(parse_slot(".png"), None);
+        assert_eq!(parse_slot("0"), None);
+        assert_eq!(parse_slot(".bak1"), None);
+        assert_eq!(parse_slot("3.bak1"), None);
+    }
+
+    #[test]
+    fn test_backup_restore_delete() {
+        let dir = env::temp_dir().join("allium-test-save-state");
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+        let state = SaveState {
+            slot: 1,
+            path: dir.join("Game.state1"),
+        };
+        let read = |path: &Path| fs::read_to_string(path).unwrap();
+
+        fs::write(&state.path, "first").unwrap();
+        fs::write(state.thumbnail(), "first.png").unwrap();
+        state.backup(2).unwrap();
+        fs::write(&state.path, "second").unwrap();
+        fs::remove_file(state.thumbnail()).unwrap();
+        state.backup(2).unwrap();
+        fs::write(&state.path, "third").unwrap();
+        state.backup(2).unwrap();
+        assert_eq!(state.backup_count(), 2);
+        assert_eq!(read(&state.backup_path(1)), "third");
+        assert_eq!(read(&state.backup_path(2)), "second");
+
+        fs::write(&state.path, "fourth").unwrap();
+        state.restore_previous().unwrap();
+        assert_eq!(read(&state.path), "third");
+        assert_eq!(read(&state.backup_path(1)), "fourth");
+        state.restore_previous().unwrap();
+        assert_eq!(read(&state.path), "fourth");
+
+        let copy = dir.join("Other/Game.state2");
+        state.copy_to(&copy).unwrap();
+        assert_eq!(read(&copy), "fourth");
+
+        state.delete().unwrap();
+        assert!(!state.path.exists());
+        assert_eq!(state.backup_count(), 0);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
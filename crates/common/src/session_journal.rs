@@ -0,0 +1,247 @@
+//! Write-ahead journal for play sessions. While a game runs, a marker with the play time that
+//! isn't recorded in the database yet is kept up to date. If the device crashes or runs out of
+//! battery, the marker is left behind, and its play time is recovered on the next boot.
+
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::ALLIUM_SESSION_JOURNAL;
+use crate::database::Database;
+use crate::game_info::GameInfo;
+
+/// Recovered play time longer than this is assumed to be a clock jump, and dropped.
+const MAX_RECOVERED_PLAY_TIME: Duration = Duration::hours(24);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionJournal {
+    pub session_id: i64,
+    /// Path to the game rom.
+    pub path: PathBuf,
+    /// Start of the play time that isn't recorded in the database yet.
+    pub start_time: DateTime<Utc>,
+    /// When the game was last known to be running.
+    pub last_seen: DateTime<Utc>,
+}
+
+impl SessionJournal {
+    /// Marks the game's session as in progress.
+    pub fn open(game_info: &GameInfo) -> Result<()> {
+        Self {
+            session_id: game_info.session_id,
+            path: game_info.path.clone(),
+            start_time: game_info.start_time,
+            last_seen: Utc::now(),
+        }
+        .save(&ALLIUM_SESSION_JOURNAL)
+    }
+
+    pub fn load() -> Result<Option<Self>> {
+        Self::load_from(&ALLIUM_SESSION_JOURNAL)
+    }
+
+    fn load_from(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let file = File::open(path)?;
+        match serde_json::from_reader(file) {
+            Ok(journal) => Ok(Some(journal)),
+            Err(e) => {
+                warn!("failed to read session journal, removing: {}", e);
+                remove(path)?;
+                Ok(None)
+            }
+        }
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        // Written in one go, so that a crash mid-write doesn't lose the previous marker
+        let part = path.with_extension("part");
+        serde_json::to_writer(File::create(&part)?, self)?;
+        fs::rename(&part, path)?;
+        Ok(())
+    }
+
+    /// Marks the session as ended cleanly, after its play time has been recorded.
+    pub fn close() -> Result<()> {
+        remove(&ALLIUM_SESSION_JOURNAL)
+    }
+
+    /// Play time that would be lost if the session ended now.
+    pub fn unrecorded_play_time(&self) -> Duration {
+        self.last_seen.signed_duration_since(self.start_time)
+    }
+
+    /// Records the play time of a session that didn't end cleanly. Returns the recovered play
+    /// time, if any.
+    pub fn recover(database: &Database) -> Result<Option<Duration>> {
+        Self::recover_from(&ALLIUM_SESSION_JOURNAL, database)
+    }
+
+    fn recover_from(path: &Path, database: &Database) -> Result<Option<Duration>> {
+        let Some(journal) = Self::load_from(path)? else {
+            return Ok(None);
+        };
+        let play_time = journal.unrecorded_play_time();
+        remove(path)?;
+
+        if play_time <= Duration::zero() || play_time > MAX_RECOVERED_PLAY_TIME {
+            warn!(
+                "discarding {}s of play time from session {}",
+                play_time.num_seconds(),
+                journal.session_id
+            );
+            return Ok(None);
+        }
+
+        info!(
+            "recovering {}s of play time from session {} of {}",
+            play_time.num_seconds(),
+            journal.session_id,
+            journal.path.display()
+        );
+        database.add_play_time(&journal.path, play_time)?;
+        Ok(Some(play_time))
+    }
+}
+
+fn remove(path: &Path) -> Result<()> {
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use crate::database::NewGame;
+
+    use super::*;
+
+    const GAME: &str = "Roms/GB/Tetris.gb";
+
+    fn database() -> Database {
+        let database = Database::in_memory().unwrap();
+        database
+            .update_games(&[NewGame {
+                name: "Tetris".to_owned(),
+                path: PathBuf::from(GAME),
+                image: None,
+                core: None,
+                rating: None,
+                release_date: None,
+                developer: None,
+                publisher: None,
+                genres: Vec::new(),
+                favorite: false,
+            }])
+            .unwrap();
+        database
+    }
+
+    fn play_time(database: &Database) -> Duration {
+        database
+            .select_game(Path::new(GAME))
+            .unwrap()
+            .unwrap()
+            .play_time
+    }
+
+    fn journal_with(play_time: Duration) -> SessionJournal {
+        let start_time = Utc::now() - Duration::days(2);
+        SessionJournal {
+            session_id: 1,
+            path: PathBuf::from(GAME),
+            start_time,
+            last_seen: start_time + play_time,
+        }
+    }
+
+    #[test]
+    fn test_recover() {
+        let dir = env::temp_dir().join("allium-test-session-journal");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session_journal.json");
+        let database = database();
+
+        assert_eq!(
+            SessionJournal::recover_from(&path, &database).unwrap(),
+            None
+        );
+
+        let journal = journal_with(Duration::minutes(30));
+        assert_eq!(journal.unrecorded_play_time(), Duration::minutes(30));
+        journal.save(&path).unwrap();
+        assert_eq!(
+            SessionJournal::load_from(&path).unwrap(),
+            Some(journal.clone())
+        );
+        assert_eq!(
+            SessionJournal::recover_from(&path, &database).unwrap(),
+            Some(Duration::minutes(30))
+        );
+        assert_eq!(play_time(&database), Duration::minutes(30));
+        assert!(!path.exists());
+
+        // Recovering twice doesn't count the play time twice
+        assert_eq!(
+            SessionJournal::recover_from(&path, &database).unwrap(),
+            None
+        );
+        assert_eq!(play_time(&database), Duration::minutes(30));
+
+        // Up to the limit is recovered
+        journal_with(MAX_RECOVERED_PLAY_TIME).save(&path).unwrap();
+        assert_eq!(
+            SessionJournal::recover_from(&path, &database).unwrap(),
+            Some(MAX_RECOVERED_PLAY_TIME)
+        );
+        assert_eq!(
+            play_time(&database),
+            Duration::minutes(30) + MAX_RECOVERED_PLAY_TIME
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_recover_discards() {
+        let dir = env::temp_dir().join("allium-test-session-journal-discard");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session_journal.json");
+        let database = database();
+
+        // No play time, the clock jumping back, and the clock jumping forward
+        for play_time in [
+            Duration::zero(),
+            Duration::seconds(-5),
+            MAX_RECOVERED_PLAY_TIME + Duration::seconds(1),
+        ] {
+            journal_with(play_time).save(&path).unwrap();
+            assert_eq!(
+                SessionJournal::recover_from(&path, &database).unwrap(),
+                None
+            );
+            assert!(!path.exists());
+        }
+
+        // A corrupt journal is removed
+        fs::write(&path, "{").unwrap();
+        assert_eq!(
+            SessionJournal::recover_from(&path, &database).unwrap(),
+            None
+        );
+        assert!(!path.exists());
+
+        assert_eq!(play_time(&database), Duration::zero());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,200 @@
+//! Exports settings, launcher state, console and core config, and the database to a single
+//! archive, and restores them, so that a re-imaged SD card can pick up where it left off.
+
+use std::fs::{self, File};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+use log::{debug, info, warn};
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::constants::{ALLIUM_BASE_DIR, ALLIUM_DATABASE};
+use crate::database::Database;
+
+/// Name of the database in the archive.
+const DATABASE_ENTRY: &str = "allium.db";
+/// Folders under the base directory whose files are backed up.
+const SETTINGS_DIRS: [&str; 2] = ["state", "config"];
+/// State files that only matter until the next launch.
+const TRANSIENT_STATE: [&str; 4] = [
+    "current_game",
+    "launch_request",
+    "session_journal.json",
+    "notifications.json",
+];
+
+/// Exports the settings to an archive, returning the number of files in it.
+pub fn export(path: &Path) -> Result<usize> {
+    let database = ALLIUM_BASE_DIR.join("cache/settings-backup.db");
+    if let Some(parent) = database.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if database.exists() {
+        fs::remove_file(&database)?;
+    }
+    Database::new()?.copy_to(&database)?;
+
+    info!("exporting settings to {}", path.display());
+    let result = write_archive(path, &ALLIUM_BASE_DIR, &database);
+    fs::remove_file(&database).ok();
+    result
+}
+
+/// Restores the settings from an archive, returning the number of files restored. The launcher
+/// must be restarted to pick them up.
+pub fn restore(path: &Path) -> Result<usize> {
+    info!("restoring settings from {}", path.display());
+    extract_archive(path, &ALLIUM_BASE_DIR, &ALLIUM_DATABASE)
+}
+
+fn write_archive(path: &Path, base_dir: &Path, database: &Path) -> Result<usize> {
+    let mut files = settings_files(base_dir)?;
+    files.push((DATABASE_ENTRY.to_owned(), database.to_path_buf()));
+
+    // Written to a temporary file first, so that a failed export doesn't replace a good backup
+    let part = part_path(path);
+    let mut zip = ZipWriter::new(File::create(&part)?);
+    for (name, file) in &files {
+        debug!("adding {} to settings backup", name);
+        zip.start_file(name.as_str(), SimpleFileOptions::default())?;
+        io::copy(&mut File::open(file)?, &mut zip)?;
+    }
+    zip.finish()?;
+    fs::rename(&part, path)?;
+    Ok(files.len())
+}
+
+/// Lists the settings files under the base directory, by their name in the archive.
+fn settings_files(base_dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let mut files = Vec::new();
+    for dir in SETTINGS_DIRS {
+        let Ok(entries) = fs::read_dir(base_dir.join(dir)) else {
+            continue;
+        };
+        for entry in entries {
+            let entry = entry?;
+            if !entry.file_type()?.is_file() {
+                continue;
+            }
+            let Ok(name) = entry.file_name().into_string() else {
+                continue;
+            };
+            if dir == "state" && TRANSIENT_STATE.contains(&name.as_str()) {
+                continue;
+            }
+            files.push((format!("{dir}/{name}"), entry.path()));
+        }
+    }
+    files.sort_unstable();
+    Ok(files)
+}
+
+fn extract_archive(path: &Path, base_dir: &Path, database: &Path) -> Result<usize> {
+    let mut archive = ZipArchive::new(File::open(path)?)?;
+    let mut count = 0;
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let Some(name) = file.enclosed_name() else {
+            warn!("skipping invalid path in settings backup: {}", file.name());
+            continue;
+        };
+        let dest = if name == Path::new(DATABASE_ENTRY) {
+            database.to_path_buf()
+        } else if is_settings_file(&name) {
+            base_dir.join(&name)
+        } else {
+            warn!(
+                "skipping unknown file in settings backup: {}",
+                name.display()
+            );
+            continue;
+        };
+
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Replaced rather than overwritten, so that the open database isn't changed under the
+        // launcher before it restarts
+        let part = part_path(&dest);
+        io::copy(&mut file, &mut File::create(&part)?)?;
+        fs::rename(&part, &dest)?;
+        count += 1;
+    }
+
+    if count == 0 {
+        return Err(anyhow!("no settings found in {}", path.display()));
+    }
+    Ok(count)
+}
+
+/// Whether an archive path is a file directly inside one of the settings folders.
+fn is_settings_file(name: &Path) -> bool {
+    let components: Vec<_> = name.components().collect();
+    match components.as_slice() {
+        [Component::Normal(dir), Component::Normal(_)] => SETTINGS_DIRS.iter().any(|d| *dir == *d),
+        _ => false,
+    }
+}
+
+fn part_path(path: &Path) -> PathBuf {
+    path.with_file_name(format!(
+        ".{}.part",
+        path.file_name().unwrap_or_default().to_string_lossy()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn test_is_settings_file() {
+        assert!(is_settings_file(Path::new("state/stylesheet.json")));
+        assert!(is_settings_file(Path::new("config/cores.toml")));
+        assert!(!is_settings_file(Path::new("bin/allium-launcher")));
+        assert!(!is_settings_file(Path::new("state/ssh/host_key")));
+        assert!(!is_settings_file(Path::new("state")));
+    }
+
+    #[test]
+    fn test_export_and_restore() {
+        let dir = env::temp_dir().join("allium-test-settings-backup");
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+        let base_dir = dir.join(".allium");
+        fs::create_dir_all(base_dir.join("state")).unwrap();
+        fs::create_dir_all(base_dir.join("config")).unwrap();
+        fs::write(base_dir.join("state/stylesheet.json"), "{}").unwrap();
+        fs::write(base_dir.join("state/current_game"), "game").unwrap();
+        fs::write(base_dir.join("config/cores.toml"), "[cores]").unwrap();
+        let database = dir.join("allium.db");
+        fs::write(&database, "database").unwrap();
+
+        let archive = dir.join("allium-settings.zip");
+        assert_eq!(write_archive(&archive, &base_dir, &database).unwrap(), 3);
+
+        let restored = dir.join("restored");
+        let restored_database = restored.join("Saves/allium.db");
+        assert_eq!(
+            extract_archive(&archive, &restored, &restored_database).unwrap(),
+            3
+        );
+        assert_eq!(
+            fs::read_to_string(restored.join("state/stylesheet.json")).unwrap(),
+            "{}"
+        );
+        assert_eq!(
+            fs::read_to_string(restored.join("config/cores.toml")).unwrap(),
+            "[cores]"
+        );
+        assert_eq!(fs::read_to_string(restored_database).unwrap(), "database");
+        assert!(!restored.join("state/current_game").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
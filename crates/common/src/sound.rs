@@ -0,0 +1,100 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use log::warn;
+
+use crate::constants::ALLIUM_SOUNDS_DIR;
+use crate::platform::{Key, KeyEvent};
+use crate::stylesheet::Stylesheet;
+
+/// Short sounds played while navigating the UI, if enabled in the stylesheet.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UiSound {
+    /// Moving the selection.
+    Click,
+    /// Selecting an entry.
+    Confirm,
+    /// Going back.
+    Back,
+}
+
+impl UiSound {
+    /// Sound for a key event that was handled by a view.
+    pub fn for_key_event(event: KeyEvent) -> Option<Self> {
+        match event {
+            KeyEvent::Pressed(Key::Up | Key::Down | Key::Left | Key::Right | Key::L | Key::R)
+            | KeyEvent::Autorepeat(
+                Key::Up | Key::Down | Key::Left | Key::Right | Key::L | Key::R,
+            ) => Some(UiSound::Click),
+            KeyEvent::Pressed(Key::A) => Some(UiSound::Confirm),
+            KeyEvent::Pressed(Key::B) => Some(UiSound::Back),
+            _ => None,
+        }
+    }
+
+    fn path(self, dir: &Path) -> PathBuf {
+        dir.join(match self {
+            UiSound::Click => "click.wav",
+            UiSound::Confirm => "confirm.wav",
+            UiSound::Back => "back.wav",
+        })
+    }
+
+    /// Plays the sound in the background. It is sent to the same audio output as the system
+    /// volume, so it follows the volume and mute state.
+    pub fn play(self, styles: &Stylesheet) -> Result<()> {
+        let path = self.path(styles.sounds_dir.as_deref().unwrap_or(&ALLIUM_SOUNDS_DIR));
+
+        #[cfg(feature = "miyoo")]
+        {
+            use std::io::Write;
+
+            let mut player = PLAYER.lock().unwrap();
+            let stdin = match player.as_mut() {
+                Some(stdin) => stdin,
+                None => player.insert(spawn_player()?),
+            };
+            let result = writeln!(stdin, "{}", path.display());
+            if result.is_err() {
+                // The player exited, so it is started again for the next sound
+                *player = None;
+            }
+            result?;
+        }
+
+        #[cfg(not(feature = "miyoo"))]
+        log::trace!("play sound: {}", path.display());
+
+        Ok(())
+    }
+}
+
+/// Input of the `myctl sounds` process that plays this process's UI sounds. It keeps the audio
+/// output open between sounds, and exits along with this process when its input is closed. The
+/// audio SDK is only linked into myctl, so sounds are not played in this process.
+#[cfg(feature = "miyoo")]
+static PLAYER: std::sync::Mutex<Option<std::process::ChildStdin>> = std::sync::Mutex::new(None);
+
+#[cfg(feature = "miyoo")]
+fn spawn_player() -> Result<std::process::ChildStdin> {
+    let mut child = std::process::Command::new("myctl")
+        .arg("sounds")
+        .stdin(std::process::Stdio::piped())
+        .spawn()?;
+    child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow::anyhow!("myctl sounds has no stdin"))
+}
+
+/// Plays the sound for a key event that was handled by a view, if UI sounds are enabled.
+pub fn play_for_key_event(styles: &Stylesheet, event: KeyEvent) {
+    if !styles.ui_sounds {
+        return;
+    }
+    if let Some(sound) = UiSound::for_key_event(event)
+        && let Err(e) = sound.play(styles)
+    {
+        warn!("failed to play sound: {}", e);
+    }
+}
@@ -0,0 +1,118 @@
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, bail};
+use embedded_graphics::Drawable;
+use embedded_graphics::image::{Image, ImageRaw};
+use image::imageops::FilterType;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{ALLIUM_SPLASH, ALLIUM_SPLASH_DIR, ALLIUM_SPLASH_SETTINGS};
+use crate::display::Display;
+use crate::display::color::Color;
+use crate::geom::Size;
+
+/// Boot splash shown while the launcher initializes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SplashSettings {
+    /// Source image chosen from the splash directory, or `None` for no splash.
+    pub image: Option<PathBuf>,
+}
+
+impl SplashSettings {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn load() -> Result<Self> {
+        if ALLIUM_SPLASH_SETTINGS.exists() {
+            debug!("found state, loading from file");
+            let file = File::open(ALLIUM_SPLASH_SETTINGS.as_path())?;
+            if let Ok(json) = serde_json::from_reader(file) {
+                return Ok(json);
+            }
+            warn!("failed to read splash file, removing");
+            fs::remove_file(ALLIUM_SPLASH_SETTINGS.as_path())?;
+        }
+        Ok(Self::new())
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let file = File::create(ALLIUM_SPLASH_SETTINGS.as_path())?;
+        serde_json::to_writer(file, &self)?;
+        Ok(())
+    }
+
+    /// Images that can be chosen as the boot splash.
+    pub fn available_images() -> Result<Vec<PathBuf>> {
+        if !ALLIUM_SPLASH_DIR.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut images: Vec<_> = fs::read_dir(ALLIUM_SPLASH_DIR.as_path())?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| {
+                        matches!(ext.to_lowercase().as_str(), "png" | "jpg" | "jpeg" | "bmp")
+                    })
+            })
+            .collect();
+        images.sort();
+        Ok(images)
+    }
+
+    /// Sets the splash image, converting it to the framebuffer format for the given screen size.
+    /// Fails if the image is smaller than the screen.
+    pub fn set_image(&mut self, image: Option<PathBuf>, size: Size) -> Result<()> {
+        match &image {
+            Some(path) => convert(path, size)?,
+            None => {
+                if ALLIUM_SPLASH.exists() {
+                    fs::remove_file(ALLIUM_SPLASH.as_path())?;
+                }
+            }
+        }
+        self.image = image;
+        self.save()
+    }
+}
+
+fn convert(path: &Path, size: Size) -> Result<()> {
+    let image = ::image::open(path)?;
+    if image.width() < size.w || image.height() < size.h {
+        bail!(
+            "splash image is {}x{}, expected at least {}x{}",
+            image.width(),
+            image.height(),
+            size.w,
+            size.h
+        );
+    }
+
+    let image = image
+        .resize_to_fill(size.w, size.h, FilterType::Lanczos3)
+        .into_rgba8();
+    fs::write(ALLIUM_SPLASH.as_path(), image.as_raw())?;
+    Ok(())
+}
+
+/// Draws the converted boot splash, if one is set. Returns whether it was drawn.
+pub fn draw(display: &mut impl Display) -> Result<bool> {
+    if !ALLIUM_SPLASH.exists() {
+        return Ok(false);
+    }
+
+    let size = display.bounding_box().size;
+    let data = fs::read(ALLIUM_SPLASH.as_path())?;
+    if data.len() != (size.width * size.height * 4) as usize {
+        warn!("boot splash does not match the screen size, ignoring");
+        return Ok(false);
+    }
+
+    let image: ImageRaw<'_, Color> = ImageRaw::new(&data, size.width);
+    Image::new(&image, display.bounding_box().top_left).draw(display)?;
+    Ok(true)
+}
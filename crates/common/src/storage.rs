@@ -0,0 +1,161 @@
+//! Storage that games can be on: the SD card, and secondary storage such as a USB drive or a
+//! second partition. Each has its own Roms directory.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+use log::debug;
+use tokio::fs;
+
+use crate::constants::{
+    ALLIUM_GAMES_DIR, ALLIUM_NETWORK_SHARE_CACHE_DIR, ALLIUM_NETWORK_SHARE_ROOT, ALLIUM_SD_ROOT,
+    ALLIUM_SECONDARY_ROOTS,
+};
+
+/// Roms directories of the mounted storage, starting with the SD card's.
+pub fn games_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![ALLIUM_GAMES_DIR.clone()];
+    dirs.extend(
+        ALLIUM_SECONDARY_ROOTS
+            .iter()
+            .map(|root| root.join("Roms"))
+            .filter(|dir| dir.is_dir()),
+    );
+    dirs
+}
+
+/// Whether a path is the root of the SD card or of secondary storage.
+pub fn is_root(path: &Path) -> bool {
+    path == ALLIUM_SD_ROOT.as_path() || ALLIUM_SECONDARY_ROOTS.iter().any(|root| root == path)
+}
+
+/// The secondary storage root that a path is on, if any.
+pub fn secondary_root(path: &Path) -> Option<&'static Path> {
+    ALLIUM_SECONDARY_ROOTS
+        .iter()
+        .find(|root| path.starts_with(root))
+        .map(PathBuf::as_path)
+}
+
+/// The path relative to the Roms directory it is in.
+pub fn strip_games_dir(path: &Path) -> Option<&Path> {
+    path.strip_prefix(ALLIUM_GAMES_DIR.as_path())
+        .ok()
+        .or_else(|| secondary_root(path).and_then(|root| path.strip_prefix(root.join("Roms")).ok()))
+}
+
+/// Whether a path is the Roms directory of the SD card or of secondary storage.
+pub fn is_games_dir(path: &Path) -> bool {
+    strip_games_dir(path).is_some_and(|rest| rest.as_os_str().is_empty())
+}
+
+/// Whether a path is on secondary storage that isn't mounted, e.g. an unplugged USB drive.
+/// Games there are kept in the database until the storage comes back.
+pub fn is_unmounted(path: &Path) -> bool {
+    secondary_root(path).is_some_and(|root| !root.join("Roms").is_dir())
+}
+
+/// Whether a path is on the network share.
+pub fn is_network_share(path: &Path) -> bool {
+    path.starts_with(ALLIUM_NETWORK_SHARE_ROOT.as_path())
+}
+
+/// Where a game on the network share is copied to on the SD card before it is launched.
+pub fn network_cache_path(path: &Path) -> Result<PathBuf> {
+    let relative = path
+        .strip_prefix(ALLIUM_NETWORK_SHARE_ROOT.as_path())
+        .map_err(|_| anyhow!("{} is not on the network share", path.display()))?;
+    Ok(ALLIUM_NETWORK_SHARE_CACHE_DIR.join(relative))
+}
+
+/// Copies a game on the network share to the SD card, so that the emulator doesn't read it over
+/// the network. Only the most recently played game is kept; playing it again reuses the copy.
+pub async fn cache_network_game(path: &Path) -> Result<PathBuf> {
+    let out = network_cache_path(path)?;
+    cache_game(path, &out, &ALLIUM_NETWORK_SHARE_CACHE_DIR).await?;
+    Ok(out)
+}
+
+async fn cache_game(path: &Path, out: &Path, cache_dir: &Path) -> Result<()> {
+    let size = fs::metadata(path).await?.len();
+    if fs::metadata(out)
+        .await
+        .is_ok_and(|cached| cached.len() == size)
+    {
+        debug!("using cached copy of {}", path.display());
+        return Ok(());
+    }
+
+    if fs::try_exists(cache_dir).await? {
+        fs::remove_dir_all(cache_dir).await?;
+    }
+    if let Some(parent) = out.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    debug!("caching {} to {}", path.display(), out.display());
+    fs::copy(path, out).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn test_paths() {
+        let usb = ALLIUM_SECONDARY_ROOTS[0].as_path();
+        let game = usb.join("Roms/GBA/game.gba");
+
+        assert!(is_root(&ALLIUM_SD_ROOT));
+        assert!(is_root(usb));
+        assert!(!is_root(&usb.join("Roms")));
+
+        assert_eq!(secondary_root(&game), Some(usb));
+        assert_eq!(secondary_root(&ALLIUM_GAMES_DIR.join("GBA")), None);
+
+        assert_eq!(strip_games_dir(&game), Some(Path::new("GBA/game.gba")));
+        assert_eq!(
+            strip_games_dir(&ALLIUM_GAMES_DIR.join("GBA")),
+            Some(Path::new("GBA"))
+        );
+        assert_eq!(strip_games_dir(&usb.join("Other/game.gba")), None);
+
+        assert!(is_games_dir(&ALLIUM_GAMES_DIR));
+        assert!(is_games_dir(&usb.join("Roms")));
+        assert!(!is_games_dir(&usb.join("Roms/GBA")));
+
+        let shared = ALLIUM_NETWORK_SHARE_ROOT.join("Roms/GBA/game.gba");
+        assert!(is_network_share(&shared));
+        assert!(!is_network_share(&game));
+        assert_eq!(
+            network_cache_path(&shared).unwrap(),
+            ALLIUM_NETWORK_SHARE_CACHE_DIR.join("Roms/GBA/game.gba")
+        );
+        assert!(network_cache_path(&game).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cache_game() {
+        let dir = env::temp_dir().join("allium-test-cache-game");
+        let cache_dir = dir.join("cache");
+        std::fs::create_dir_all(&dir).unwrap();
+        let first = dir.join("first.gba");
+        let second = dir.join("second.gba");
+        std::fs::write(&first, "first").unwrap();
+        std::fs::write(&second, "second").unwrap();
+
+        let out = cache_dir.join("GBA/first.gba");
+        cache_game(&first, &out, &cache_dir).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "first");
+
+        // Only the most recently played game is kept
+        let other = cache_dir.join("GBA/second.gba");
+        cache_game(&second, &other, &cache_dir).await.unwrap();
+        assert!(!out.exists());
+        assert_eq!(std::fs::read_to_string(&other).unwrap(), "second");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,18 +1,28 @@
+use std::collections::HashSet;
 use std::fs::{self, File};
 use std::io::Write;
 use std::mem;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 
-use anyhow::Result;
+use anyhow::{Result, anyhow, bail};
 use log::{debug, error, warn};
 use rusttype::Font;
 use serde::{Deserialize, Serialize};
+use strum::{EnumCount, FromRepr};
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
 
 use crate::{
-    constants::{ALLIUM_FONTS_DIR, ALLIUM_STYLESHEET},
+    constants::{
+        ALLIUM_BACKGROUNDS_DIR, ALLIUM_FONTS_DIR, ALLIUM_SD_ROOT, ALLIUM_SOUNDS_DIR,
+        ALLIUM_STYLESHEET, ALLIUM_THEME_PACKS_DIR, ALLIUM_THEMES_DIR, SELECTION_MARGIN,
+    },
     display::color::Color,
 };
 
+/// Stylesheet inside a theme pack. Paths in it are relative to the pack.
+const THEME_PACK_STYLESHEET: &str = "theme.toml";
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum StylesheetColor {
     Foreground,
@@ -123,13 +133,94 @@ impl StylesheetFont {
     }
 }
 
+/// How the selected item in a list is highlighted.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize, FromRepr, EnumCount,
+)]
+pub enum SelectionStyle {
+    /// Fill a rounded rectangle behind the item.
+    #[default]
+    Pill,
+    /// Draw a line under the item.
+    Underline,
+}
+
+/// How the face buttons in button hints are drawn.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize, FromRepr, EnumCount,
+)]
+pub enum ButtonHintStyle {
+    /// A filled circle in the button color.
+    #[default]
+    Filled,
+    /// A circle outlined in the button color.
+    Outline,
+}
+
+/// Background images for each launcher tab.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TabBackgroundImages {
+    pub recents: Option<PathBuf>,
+    pub games: Option<PathBuf>,
+    pub apps: Option<PathBuf>,
+    pub settings: Option<PathBuf>,
+}
+
+impl TabBackgroundImages {
+    /// Background image for the tab at the given index.
+    pub fn get(&self, tab: usize) -> Option<&Path> {
+        match tab {
+            0 => self.recents.as_deref(),
+            1 => self.games.as_deref(),
+            2 => self.apps.as_deref(),
+            3 => self.settings.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Option<PathBuf>> {
+        [
+            &mut self.recents,
+            &mut self.games,
+            &mut self.apps,
+            &mut self.settings,
+        ]
+        .into_iter()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Stylesheet {
-    pub wallpaper: Option<PathBuf>,
+    /// Image drawn behind every top-level view, relative to the SD card root.
+    #[serde(default, alias = "wallpaper")]
+    pub background_image: Option<PathBuf>,
+    /// How much to dim the background image towards the background color, in percent.
+    #[serde(default)]
+    pub background_dim: u8,
+    /// Per-tab background images for the launcher, overriding `background_image`.
+    #[serde(default)]
+    pub tab_background_images: TabBackgroundImages,
     pub show_battery_level: bool,
     pub show_clock: bool,
     #[serde(default)]
     pub use_recents_carousel: bool,
+    /// Play sounds when navigating the UI.
+    #[serde(default)]
+    pub ui_sounds: bool,
+    /// Skip UI animations.
+    #[serde(default)]
+    pub reduce_motion: bool,
+    /// Directory containing the UI sounds, if not the built-in ones.
+    #[serde(default)]
+    pub sounds_dir: Option<PathBuf>,
+    /// Directory of folder icons named after the folder, e.g. `GBA.png`, shown for folders that
+    /// have no image of their own.
+    #[serde(default)]
+    pub icons_dir: Option<PathBuf>,
+    /// Name of the theme pack the stylesheet was loaded from.
+    #[serde(default)]
+    pub theme_pack: Option<String>,
     #[serde(default = "Stylesheet::default_boxart_width")]
     pub boxart_width: u32,
     #[serde(default = "Stylesheet::default_foreground_color")]
@@ -156,7 +247,7 @@ pub struct Stylesheet {
     pub ui_font: StylesheetFont,
     #[serde(default = "StylesheetFont::guide_font")]
     pub guide_font: StylesheetFont,
-    #[serde(skip, default = "StylesheetFont::cjk_font")]
+    #[serde(default = "StylesheetFont::cjk_font")]
     pub cjk_font: StylesheetFont,
     #[serde(default = "Stylesheet::default_tab_font_size")]
     pub tab_font_size: f32,
@@ -164,6 +255,25 @@ pub struct Stylesheet {
     pub status_bar_font_size: f32,
     #[serde(default = "Stylesheet::default_button_hint_font_size")]
     pub button_hint_font_size: f32,
+    /// Roundness of corners, in percent of the largest radius (half the height).
+    #[serde(default = "Stylesheet::default_border_radius")]
+    pub border_radius: u32,
+    #[serde(default)]
+    pub selection_style: SelectionStyle,
+    /// Vertical space between rows in lists, in pixels.
+    #[serde(default = "Stylesheet::default_row_spacing")]
+    pub row_spacing: u32,
+    #[serde(default)]
+    pub button_hint_style: ButtonHintStyle,
+    /// Speed of scrolling text that doesn't fit, in characters per second.
+    #[serde(default = "Stylesheet::default_marquee_speed")]
+    pub marquee_speed: u32,
+    /// Pause before text starts scrolling, in milliseconds.
+    #[serde(default = "Stylesheet::default_marquee_delay")]
+    pub marquee_delay: u32,
+    /// Only scroll the selected entry of a list, instead of every entry that doesn't fit.
+    #[serde(default = "Stylesheet::default_marquee_selected_only")]
+    pub marquee_selected_only: bool,
     #[serde(default = "Stylesheet::default_alt_foreground_color")]
     alt_foreground_color: Color,
     #[serde(default = "Stylesheet::default_alt_background_color")]
@@ -249,6 +359,149 @@ impl Stylesheet {
         Ok(())
     }
 
+    /// Images that can be chosen as the background image.
+    pub fn available_background_images() -> Result<Vec<PathBuf>> {
+        if !ALLIUM_BACKGROUNDS_DIR.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut images: Vec<_> = fs::read_dir(ALLIUM_BACKGROUNDS_DIR.as_path())?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| {
+                        matches!(ext.to_lowercase().as_str(), "png" | "jpg" | "jpeg" | "bmp")
+                    })
+            })
+            .collect();
+        images.sort();
+        Ok(images)
+    }
+
+    /// Theme packs that can be switched to.
+    pub fn available_packs() -> Result<Vec<PathBuf>> {
+        if !ALLIUM_THEMES_DIR.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut packs: Vec<_> = fs::read_dir(ALLIUM_THEMES_DIR.as_path())?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "zip"))
+            .collect();
+        packs.sort();
+        Ok(packs)
+    }
+
+    /// Icon for a folder from the theme's icons, if there is one.
+    pub fn folder_icon(&self, name: &str) -> Option<PathBuf> {
+        let icons_dir = self.icons_dir.as_deref()?;
+        ["png", "jpg", "jpeg", "gif"]
+            .iter()
+            .map(|ext| icons_dir.join(format!("{name}.{ext}")))
+            .find(|path| path.is_file())
+    }
+
+    /// Saves the stylesheet as a theme pack, bundling its fonts, background images, UI sounds
+    /// and folder icons.
+    pub fn save_as_pack(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut pack = ThemePackWriter {
+            zip: ZipWriter::new(File::create(path)?),
+            names: HashSet::new(),
+        };
+
+        let mut styles = self.clone();
+        styles.theme_pack = None;
+        styles.ui_font.path = pack.add_file("fonts", &self.ui_font.path)?;
+        styles.guide_font.path = pack.add_file("fonts", &self.guide_font.path)?;
+        styles.cjk_font.path = pack.add_file("fonts", &self.cjk_font.path)?;
+        if let Some(image) = &self.background_image {
+            styles.background_image =
+                Some(pack.add_file("backgrounds", &ALLIUM_SD_ROOT.join(image))?);
+        }
+        for image in styles.tab_background_images.iter_mut() {
+            if let Some(path) = image.as_deref() {
+                *image = Some(pack.add_file("backgrounds", &ALLIUM_SD_ROOT.join(path))?);
+            }
+        }
+
+        let sounds_dir = self.sounds_dir.as_deref().unwrap_or(&ALLIUM_SOUNDS_DIR);
+        if sounds_dir.exists() {
+            for entry in fs::read_dir(sounds_dir)? {
+                pack.add_file("sounds", &entry?.path())?;
+            }
+            styles.sounds_dir = Some(PathBuf::from("sounds"));
+        }
+
+        if let Some(icons_dir) = self.icons_dir.as_deref().filter(|dir| dir.exists()) {
+            for entry in fs::read_dir(icons_dir)? {
+                pack.add_file("icons", &entry?.path())?;
+            }
+            styles.icons_dir = Some(PathBuf::from("icons"));
+        }
+
+        pack.zip
+            .start_file(THEME_PACK_STYLESHEET, SimpleFileOptions::default())?;
+        pack.zip.write_all(toml::to_string(&styles)?.as_bytes())?;
+        pack.zip.finish()?;
+        Ok(())
+    }
+
+    /// Installs a theme pack and loads its stylesheet.
+    pub fn load_from_pack(path: &Path) -> Result<Self> {
+        let name = path
+            .file_stem()
+            .ok_or_else(|| anyhow!("invalid theme pack path: {}", path.display()))?;
+        let mut styles = Self::extract_pack(path, &ALLIUM_THEME_PACKS_DIR.join(name))?;
+        styles.theme_pack = Some(name.to_string_lossy().to_string());
+        styles.load_fonts()?;
+        Ok(styles)
+    }
+
+    /// Extracts a theme pack into `dir`, and returns its stylesheet with paths pointing to the
+    /// extracted files.
+    fn extract_pack(path: &Path, dir: &Path) -> Result<Self> {
+        if dir.exists() {
+            fs::remove_dir_all(dir)?;
+        }
+        ZipArchive::new(File::open(path)?)?.extract(dir)?;
+
+        let toml = fs::read_to_string(dir.join(THEME_PACK_STYLESHEET))?;
+        let mut styles: Self = toml::from_str(&toml)?;
+        styles.ui_font.path = pack_path(dir, &styles.ui_font.path)?;
+        styles.guide_font.path = pack_path(dir, &styles.guide_font.path)?;
+        styles.cjk_font.path = pack_path(dir, &styles.cjk_font.path)?;
+        styles.background_image = styles
+            .background_image
+            .map(|image| pack_path(dir, &image))
+            .transpose()?;
+        for image in styles.tab_background_images.iter_mut() {
+            *image = image
+                .take()
+                .map(|image| pack_path(dir, &image))
+                .transpose()?;
+        }
+        styles.sounds_dir = styles
+            .sounds_dir
+            .map(|sounds_dir| pack_path(dir, &sounds_dir))
+            .transpose()?;
+        styles.icons_dir = styles
+            .icons_dir
+            .map(|icons_dir| pack_path(dir, &icons_dir))
+            .transpose()?;
+        Ok(styles)
+    }
+
+    /// Background image for a launcher tab, falling back to the global background image.
+    pub fn tab_background_image(&self, tab: usize) -> Option<&Path> {
+        self.tab_background_images
+            .get(tab)
+            .or(self.background_image.as_deref())
+    }
+
     pub fn toggle_dark_mode(&mut self) {
         mem::swap(&mut self.foreground_color, &mut self.alt_foreground_color);
         mem::swap(&mut self.background_color, &mut self.alt_background_color);
@@ -283,6 +536,18 @@ impl Stylesheet {
         self.ui_font.size as f32 * self.button_hint_font_size
     }
 
+    /// Height of a row in lists.
+    #[inline]
+    pub fn row_height(&self) -> u32 {
+        self.ui_font.size + self.row_spacing
+    }
+
+    /// Corner radius for a rounded rectangle of the given height.
+    #[inline]
+    pub fn corner_radius(&self, height: u32) -> u32 {
+        height / 2 * self.border_radius.min(100) / 100
+    }
+
     #[inline]
     pub fn status_bar_font_size(&self) -> f32 {
         self.ui_font.size as f32 * self.status_bar_font_size
@@ -324,6 +589,31 @@ rgui_particle_color = "0xFF{highlight:X}"
         0.9
     }
 
+    #[inline]
+    fn default_border_radius() -> u32 {
+        100
+    }
+
+    #[inline]
+    fn default_row_spacing() -> u32 {
+        SELECTION_MARGIN
+    }
+
+    #[inline]
+    fn default_marquee_speed() -> u32 {
+        6
+    }
+
+    #[inline]
+    fn default_marquee_delay() -> u32 {
+        1000
+    }
+
+    #[inline]
+    fn default_marquee_selected_only() -> bool {
+        true
+    }
+
     #[inline]
     fn default_boxart_width() -> u32 {
         250
@@ -433,10 +723,17 @@ rgui_particle_color = "0xFF{highlight:X}"
 impl Default for Stylesheet {
     fn default() -> Self {
         Self {
-            wallpaper: None,
+            background_image: None,
+            background_dim: 0,
+            tab_background_images: Default::default(),
             show_battery_level: false,
             show_clock: true,
             use_recents_carousel: false,
+            ui_sounds: false,
+            reduce_motion: false,
+            sounds_dir: None,
+            icons_dir: None,
+            theme_pack: None,
             boxart_width: Self::default_boxart_width(),
             foreground_color: Self::default_foreground_color(),
             background_color: Self::default_background_color(),
@@ -454,6 +751,13 @@ impl Default for Stylesheet {
             tab_font_size: Self::default_tab_font_size(),
             status_bar_font_size: Self::default_status_bar_font_size(),
             button_hint_font_size: Self::default_button_hint_font_size(),
+            border_radius: Self::default_border_radius(),
+            selection_style: SelectionStyle::default(),
+            row_spacing: Self::default_row_spacing(),
+            button_hint_style: ButtonHintStyle::default(),
+            marquee_speed: Self::default_marquee_speed(),
+            marquee_delay: Self::default_marquee_delay(),
+            marquee_selected_only: Self::default_marquee_selected_only(),
             alt_foreground_color: Self::default_alt_foreground_color(),
             alt_background_color: Self::default_alt_background_color(),
             alt_highlight_color: Self::default_alt_highlight_color(),
@@ -467,3 +771,119 @@ impl Default for Stylesheet {
         }
     }
 }
+
+struct ThemePackWriter {
+    zip: ZipWriter<File>,
+    names: HashSet<PathBuf>,
+}
+
+impl ThemePackWriter {
+    /// Adds a file to a directory in the pack, returning its path in the pack.
+    fn add_file(&mut self, dir: &str, path: &Path) -> Result<PathBuf> {
+        let name = Path::new(dir).join(
+            path.file_name()
+                .ok_or_else(|| anyhow!("invalid theme file: {}", path.display()))?,
+        );
+        // Fonts may be shared, e.g. the UI and guide fonts
+        if self.names.insert(name.clone()) {
+            self.zip
+                .start_file_from_path(&name, SimpleFileOptions::default())?;
+            self.zip.write_all(&fs::read(path)?)?;
+        }
+        Ok(name)
+    }
+}
+
+/// Resolves a path from a theme pack's stylesheet, rejecting paths that point outside of the
+/// pack, such as absolute paths or ones with `..`.
+fn pack_path(dir: &Path, path: &Path) -> Result<PathBuf> {
+    if !path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        bail!("invalid path in theme pack: {}", path.display());
+    }
+    Ok(dir.join(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn test_theme_pack_round_trip() {
+        let dir = env::temp_dir().join("allium-test-theme-pack");
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+        for sub in ["fonts", "sounds", "icons"] {
+            fs::create_dir_all(dir.join(sub)).unwrap();
+        }
+        fs::write(dir.join("fonts/Font.ttf"), "font").unwrap();
+        fs::write(dir.join("fonts/CJK.otf"), "cjk font").unwrap();
+        fs::write(dir.join("sounds/click.wav"), "sound").unwrap();
+        fs::write(dir.join("icons/GBA.png"), "icon").unwrap();
+
+        let mut styles = Stylesheet::default();
+        styles.ui_font.path = dir.join("fonts/Font.ttf");
+        styles.guide_font.path = dir.join("fonts/Font.ttf");
+        styles.cjk_font.path = dir.join("fonts/CJK.otf");
+        styles.sounds_dir = Some(dir.join("sounds"));
+        styles.icons_dir = Some(dir.join("icons"));
+        styles.boxart_width = 123;
+        let pack = dir.join("Theme.zip");
+        styles.save_as_pack(&pack).unwrap();
+
+        let out = dir.join("out");
+        let loaded = Stylesheet::extract_pack(&pack, &out).unwrap();
+        assert_eq!(loaded.boxart_width, 123);
+        assert_eq!(loaded.ui_font.path, out.join("fonts/Font.ttf"));
+        assert_eq!(loaded.guide_font.path, out.join("fonts/Font.ttf"));
+        assert_eq!(loaded.cjk_font.path, out.join("fonts/CJK.otf"));
+        assert_eq!(
+            fs::read_to_string(out.join("fonts/CJK.otf")).unwrap(),
+            "cjk font"
+        );
+        assert_eq!(loaded.sounds_dir, Some(out.join("sounds")));
+        assert_eq!(
+            fs::read_to_string(out.join("sounds/click.wav")).unwrap(),
+            "sound"
+        );
+        assert_eq!(loaded.folder_icon("GBA"), Some(out.join("icons/GBA.png")));
+        assert_eq!(loaded.folder_icon("SNES"), None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_theme_pack_rejects_outside_paths() {
+        let dir = env::temp_dir().join("allium-test-theme-pack-outside");
+        fs::create_dir_all(&dir).unwrap();
+        for (font, outside) in ["../../Font.ttf", "/etc/Font.ttf"]
+            .into_iter()
+            .flat_map(|font| (0..3).map(move |outside| (font, outside)))
+        {
+            let mut styles = Stylesheet::default();
+            let fonts = [
+                &mut styles.ui_font,
+                &mut styles.guide_font,
+                &mut styles.cjk_font,
+            ];
+            for (i, f) in fonts.into_iter().enumerate() {
+                f.path = PathBuf::from(if i == outside { font } else { "fonts/Font.ttf" });
+            }
+            let pack = dir.join("Theme.zip");
+            let mut zip = ZipWriter::new(File::create(&pack).unwrap());
+            zip.start_file(THEME_PACK_STYLESHEET, SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(toml::to_string(&styles).unwrap().as_bytes())
+                .unwrap();
+            zip.finish().unwrap();
+
+            assert!(Stylesheet::extract_pack(&pack, &dir.join("out")).is_err());
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,194 @@
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, bail};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use crate::constants::{
+    ALLIUM_THEME_INDEX_URL, ALLIUM_THEME_PREVIEWS_DIR, ALLIUM_THEME_STORE_SETTINGS,
+    ALLIUM_THEMES_DIR,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThemeStoreSettings {
+    /// URL of the theme index, e.g. to use a mirror or a private index.
+    #[serde(default = "ThemeStoreSettings::default_index_url")]
+    pub index_url: String,
+}
+
+impl Default for ThemeStoreSettings {
+    fn default() -> Self {
+        Self {
+            index_url: Self::default_index_url(),
+        }
+    }
+}
+
+impl ThemeStoreSettings {
+    fn default_index_url() -> String {
+        ALLIUM_THEME_INDEX_URL.to_owned()
+    }
+
+    pub fn load() -> Result<Self> {
+        if ALLIUM_THEME_STORE_SETTINGS.exists() {
+            debug!("found state, loading from file");
+            let file = File::open(ALLIUM_THEME_STORE_SETTINGS.as_path())?;
+            if let Ok(json) = serde_json::from_reader(file) {
+                return Ok(json);
+            }
+            warn!("failed to read theme store settings file, removing");
+            fs::remove_file(ALLIUM_THEME_STORE_SETTINGS.as_path())?;
+        }
+        Ok(Self::default())
+    }
+}
+
+/// A theme pack listed in the community theme index.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ThemeIndexEntry {
+    pub name: String,
+    #[serde(default)]
+    pub author: Option<String>,
+    /// URL of a preview image.
+    #[serde(default)]
+    pub preview: Option<String>,
+    /// URL of the theme pack.
+    pub url: String,
+}
+
+/// Fetches the community theme index.
+pub async fn fetch_index() -> Result<Vec<ThemeIndexEntry>> {
+    let url = ThemeStoreSettings::load()?.index_url;
+    debug!("fetching theme index: {}", url);
+    let output = curl(&url, None)?.output().await?;
+    if !output.status.success() {
+        bail!(
+            "failed to fetch theme index: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(serde_json::from_slice(&output.stdout)?)
+}
+
+impl ThemeIndexEntry {
+    /// Downloads the preview image, if there is one. Previews are cached.
+    pub async fn download_preview(&self) -> Result<Option<PathBuf>> {
+        let Some(url) = &self.preview else {
+            return Ok(None);
+        };
+        let path = ALLIUM_THEME_PREVIEWS_DIR.join(self.file_name()?);
+        if !path.exists() {
+            download(url, &path).await?;
+        }
+        Ok(Some(path))
+    }
+
+    /// Downloads the theme pack into the themes directory, where it can be switched to.
+    pub async fn install(&self) -> Result<PathBuf> {
+        let path = ALLIUM_THEMES_DIR.join(format!("{}.zip", self.file_name()?));
+        download(&self.url, &path).await?;
+        Ok(path)
+    }
+
+    /// Name of the pack's files, which must stay inside the directory they're saved in.
+    fn file_name(&self) -> Result<String> {
+        let name = self.name.replace(['/', '\\', ':'], "_");
+        if matches!(name.as_str(), "" | "." | "..") {
+            bail!("invalid theme name: {:?}", self.name);
+        }
+        Ok(name)
+    }
+}
+
+/// URLs come from a remote index, so only http(s) is fetched. Anything else could read local
+/// files, or be taken by curl as an option.
+fn check_url(url: &str) -> Result<()> {
+    let lowercase = url.to_ascii_lowercase();
+    if !lowercase.starts_with("http://") && !lowercase.starts_with("https://") {
+        bail!("unsupported URL: {}", url);
+    }
+    Ok(())
+}
+
+fn curl(url: &str, output: Option<&Path>) -> Result<Command> {
+    check_url(url)?;
+    let mut command = Command::new("curl");
+    command
+        .arg("--silent")
+        .arg("--show-error")
+        .arg("--fail")
+        .arg("--location")
+        .arg("--proto")
+        .arg("=http,https")
+        .arg("--proto-redir")
+        .arg("=http,https")
+        .arg("--max-time")
+        .arg("60");
+    if let Some(output) = output {
+        command.arg("--output").arg(output);
+    }
+    command.arg("--").arg(url);
+    Ok(command)
+}
+
+/// Downloads to a temporary file first, so that failed downloads don't leave partial files.
+pub(crate) async fn download(url: &str, path: &Path) -> Result<()> {
+    debug!("downloading {} to {}", url, path.display());
+    let tmp = path.with_extension("part");
+    let mut command = curl(url, Some(&tmp))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let output = command.output().await?;
+    if !output.status.success() {
+        let _ = fs::remove_file(&tmp);
+        bail!(
+            "failed to download {}: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str) -> ThemeIndexEntry {
+        ThemeIndexEntry {
+            name: name.to_owned(),
+            author: None,
+            preview: None,
+            url: "https://example.com/theme.zip".to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_check_url() {
+        assert!(check_url("https://example.com/index.json").is_ok());
+        assert!(check_url("http://example.com/index.json").is_ok());
+        assert!(check_url("HTTPS://example.com/index.json").is_ok());
+
+        assert!(check_url("file:///etc/passwd").is_err());
+        assert!(check_url("-o/mnt/SDCARD/evil").is_err());
+        assert!(check_url("--config=/tmp/curlrc").is_err());
+        assert!(check_url("ftp://example.com/theme.zip").is_err());
+        assert!(check_url("").is_err());
+    }
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(entry("Catppuccin").file_name().unwrap(), "Catppuccin");
+        assert_eq!(entry("a/b\\c:d").file_name().unwrap(), "a_b_c_d");
+        assert_eq!(entry("../themes").file_name().unwrap(), ".._themes");
+        assert_eq!(entry("...").file_name().unwrap(), "...");
+
+        assert!(entry("").file_name().is_err());
+        assert!(entry(".").file_name().is_err());
+        assert!(entry("..").file_name().is_err());
+    }
+}
@@ -0,0 +1,186 @@
+//! Remapping of the keys that Allium's own UI responds to. Views handle the default keys, and
+//! key events are translated from the physical keys before they reach them.
+
+use std::fs::{self, File};
+use std::sync::{LazyLock, RwLock};
+use std::time::Duration;
+
+use anyhow::Result;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{ALLIUM_UI_KEYMAP, LONG_PRESS_DURATION};
+use crate::platform::Key;
+
+/// Keys that can open search or the menu. A and B are left out, as they are confirm and back.
+pub const UI_KEY_CHOICES: [Key; 6] = [Key::X, Key::Y, Key::Start, Key::Select, Key::L2, Key::R2];
+
+static CURRENT: LazyLock<RwLock<UiKeymap>> = LazyLock::new(|| {
+    RwLock::new(UiKeymap::load().unwrap_or_else(|e| {
+        warn!("failed to load UI keymap: {}", e);
+        UiKeymap::default()
+    }))
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UiKeymap {
+    /// Whether B confirms and A goes back, as on Xbox controllers, instead of the other way around.
+    #[serde(default)]
+    pub swap_confirm_back: bool,
+    /// Key that opens search, X by default.
+    #[serde(default = "UiKeymap::default_search")]
+    search: Key,
+    /// Key that opens the menu of the selected entry, Select by default.
+    #[serde(default = "UiKeymap::default_menu")]
+    menu: Key,
+    /// How long a key is held before it is long pressed.
+    #[serde(default = "UiKeymap::default_long_press_ms")]
+    pub long_press_ms: i32,
+}
+
+impl Default for UiKeymap {
+    fn default() -> Self {
+        Self {
+            swap_confirm_back: false,
+            search: Self::default_search(),
+            menu: Self::default_menu(),
+            long_press_ms: Self::default_long_press_ms(),
+        }
+    }
+}
+
+impl UiKeymap {
+    fn default_search() -> Key {
+        Key::X
+    }
+
+    fn default_menu() -> Key {
+        Key::Select
+    }
+
+    fn default_long_press_ms() -> i32 {
+        LONG_PRESS_DURATION.as_millis() as i32
+    }
+
+    /// The keymap in use by this process.
+    pub fn current() -> UiKeymap {
+        *CURRENT.read().unwrap()
+    }
+
+    /// Replaces the keymap in use by this process, e.g. after it is changed in settings.
+    pub fn set_current(keymap: UiKeymap) {
+        *CURRENT.write().unwrap() = keymap;
+    }
+
+    pub fn load() -> Result<Self> {
+        if ALLIUM_UI_KEYMAP.exists() {
+            debug!("found state, loading from file");
+            let file = File::open(ALLIUM_UI_KEYMAP.as_path())?;
+            if let Ok(json) = serde_json::from_reader(file) {
+                return Ok(json);
+            }
+            warn!("failed to read UI keymap file, removing");
+            fs::remove_file(ALLIUM_UI_KEYMAP.as_path())?;
+        }
+        Ok(Self::default())
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let file = File::create(ALLIUM_UI_KEYMAP.as_path())?;
+        serde_json::to_writer(file, &self)?;
+        Ok(())
+    }
+
+    pub fn search(&self) -> Key {
+        self.search
+    }
+
+    pub fn menu(&self) -> Key {
+        self.menu
+    }
+
+    pub fn long_press_duration(&self) -> Duration {
+        Duration::from_millis(self.long_press_ms.max(0) as u64)
+    }
+
+    /// Sets the key that opens search. If the menu was on that key, it moves to search's old key.
+    pub fn set_search(&mut self, key: Key) {
+        if self.menu == key {
+            self.menu = self.search;
+        }
+        self.search = key;
+    }
+
+    /// Sets the key that opens the menu. If search was on that key, it moves to menu's old key.
+    pub fn set_menu(&mut self, key: Key) {
+        if self.search == key {
+            self.search = self.menu;
+        }
+        self.menu = key;
+    }
+
+    /// Keys that are moved, as pairs of the key views handle and the physical key for it.
+    fn moves(&self) -> Vec<(Key, Key)> {
+        let mut moves = vec![(Key::X, self.search), (Key::Select, self.menu)];
+        // Keys whose physical key was taken are moved to the physical keys that were freed
+        let taken: Vec<Key> = moves
+            .iter()
+            .map(|(_, physical)| *physical)
+            .filter(|physical| !moves.iter().any(|(key, _)| key == physical))
+            .collect();
+        let freed: Vec<Key> = moves
+            .iter()
+            .map(|(key, _)| *key)
+            .filter(|key| !moves.iter().any(|(_, physical)| physical == key))
+            .collect();
+        moves.extend(taken.into_iter().zip(freed));
+        if self.swap_confirm_back {
+            moves.extend([(Key::A, Key::B), (Key::B, Key::A)]);
+        }
+        moves
+    }
+
+    /// The key that views handle for a physical key.
+    pub fn to_ui(&self, physical: Key) -> Key {
+        self.moves()
+            .into_iter()
+            .find(|(_, p)| *p == physical)
+            .map_or(physical, |(key, _)| key)
+    }
+
+    /// The physical key for a key that views handle, e.g. to show in button hints.
+    pub fn to_physical(&self, key: Key) -> Key {
+        self.moves()
+            .into_iter()
+            .find(|(k, _)| *k == key)
+            .map_or(key, |(_, physical)| physical)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ui_keymap_round_trip() {
+        let mut keymap = UiKeymap::default();
+        assert_eq!(keymap.to_ui(Key::X), Key::X);
+
+        keymap.swap_confirm_back = true;
+        keymap.set_search(Key::Y);
+        assert_eq!(keymap.to_ui(Key::B), Key::A);
+        assert_eq!(keymap.to_ui(Key::Y), Key::X);
+        assert_eq!(keymap.to_ui(Key::X), Key::Y);
+
+        // Moving the menu to search's key swaps the two
+        keymap.set_menu(Key::Y);
+        assert_eq!(keymap.search(), Key::Select);
+        assert_eq!(keymap.to_ui(Key::Y), Key::Select);
+        assert_eq!(keymap.to_ui(Key::Select), Key::X);
+        assert_eq!(keymap.to_ui(Key::X), Key::Y);
+
+        for key in UI_KEY_CHOICES.into_iter().chain([Key::A, Key::B, Key::Up]) {
+            assert_eq!(keymap.to_physical(keymap.to_ui(key)), key, "{key:?}");
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+static REDUCE_MOTION: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether animations should be skipped, jumping straight to their end value.
+pub fn set_reduce_motion(reduce_motion: bool) {
+    REDUCE_MOTION.store(reduce_motion, Ordering::Relaxed);
+}
+
+/// Returns true if animations should be skipped.
+pub fn reduce_motion() -> bool {
+    REDUCE_MOTION.load(Ordering::Relaxed)
+}
+
+/// Easing function applied to the progress of an animation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    Linear,
+    #[default]
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    /// Maps linear progress in `[0, 1]` to eased progress in `[0, 1]`.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
+
+/// Eased interpolation of a value over time. Views advance it from `View::update` and read
+/// `value()` when drawing.
+#[derive(Debug, Clone, Copy)]
+pub struct Animation {
+    from: f32,
+    to: f32,
+    duration: Duration,
+    elapsed: Duration,
+    easing: Easing,
+}
+
+impl Animation {
+    /// Starts an animation from `from` to `to`. If reduce motion is enabled, the animation is
+    /// already finished.
+    pub fn new(from: f32, to: f32, duration: Duration, easing: Easing) -> Self {
+        let elapsed = if reduce_motion() {
+            duration
+        } else {
+            Duration::ZERO
+        };
+        Self {
+            from,
+            to,
+            duration,
+            elapsed,
+            easing,
+        }
+    }
+
+    /// An animation that has already finished at `value`.
+    pub fn finished(value: f32) -> Self {
+        Self {
+            from: value,
+            to: value,
+            duration: Duration::ZERO,
+            elapsed: Duration::ZERO,
+            easing: Easing::Linear,
+        }
+    }
+
+    /// Advances the animation. Returns true if the value changed.
+    pub fn update(&mut self, dt: Duration) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+        self.elapsed = (self.elapsed + dt).min(self.duration);
+        true
+    }
+
+    /// Current value of the animation.
+    pub fn value(&self) -> f32 {
+        if self.is_finished() {
+            return self.to;
+        }
+        let t = self.elapsed.as_secs_f32() / self.duration.as_secs_f32();
+        self.from + (self.to - self.from) * self.easing.apply(t)
+    }
+
+    /// Value the animation ends at.
+    pub fn target(&self) -> f32 {
+        self.to
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_animation() {
+        let mut animation = Animation::new(0.0, 100.0, Duration::from_millis(100), Easing::Linear);
+        assert_eq!(animation.value(), 0.0);
+        assert!(animation.update(Duration::from_millis(50)));
+        assert_eq!(animation.value(), 50.0);
+        assert!(animation.update(Duration::from_millis(100)));
+        assert!(animation.is_finished());
+        assert_eq!(animation.value(), 100.0);
+        assert!(!animation.update(Duration::from_millis(10)));
+    }
+
+    #[test]
+    fn test_easing_bounds() {
+        for easing in [Easing::Linear, Easing::EaseOut, Easing::EaseInOut] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+        }
+    }
+}
@@ -14,7 +14,7 @@ use tokio::sync::mpsc::Sender;
 use crate::battery::Battery;
 use crate::constants::BATTERY_UPDATE_INTERVAL;
 use crate::display::Display;
-use crate::geom::{Point, Rect};
+use crate::geom::{self, Point, Rect};
 use crate::platform::{DefaultPlatform, KeyEvent, Platform};
 use crate::resources::Resources;
 use crate::stylesheet::Stylesheet;
@@ -286,7 +286,8 @@ where
     fn set_position(&mut self, point: Point) {
         self.point = point;
         if let Some(ref mut label) = self.label {
-            label.set_position(point);
+            // The battery is drawn at the point as given, so the label must be too
+            geom::without_mirroring(|| label.set_position(point));
         }
     }
 }
@@ -6,10 +6,11 @@ use async_trait::async_trait;
 use tokio::sync::mpsc::Sender;
 
 use crate::display::Display;
-use crate::geom::{Alignment, Point, Rect};
+use crate::geom::{self, Alignment, Point, Rect};
 use crate::platform::{DefaultPlatform, Key, KeyEvent, Platform};
 use crate::resources::Resources;
 use crate::stylesheet::Stylesheet;
+use crate::ui_keymap::UiKeymap;
 use crate::view::{ButtonIcon, Command, Label, View};
 
 #[derive(Debug, Clone)]
@@ -29,14 +30,22 @@ impl<S> ButtonHint<S>
 where
     S: AsRef<str> + PartialEq + Send,
 {
+    /// Creates a button hint at `point`. In a right-to-left layout, the point and alignment are
+    /// mirrored, and the button is placed after the text.
     pub fn new(res: Resources, point: Point, button: Key, text: S, alignment: Alignment) -> Self {
         let styles = res.get::<Stylesheet>();
         let mut label = Label::new(Point::zero(), text, alignment, None);
         label.font_size(styles.button_hint_font_size);
-        let button = ButtonIcon::new(Point::zero(), button, alignment);
+        let alignment = alignment.directional();
+        // Hints are for the key that views handle, so they show the physical key it is mapped from
+        let button = ButtonIcon::new(
+            Point::zero(),
+            UiKeymap::current().to_physical(button),
+            alignment,
+        );
 
         Self {
-            point,
+            point: point.directional(),
             button,
             label,
             alignment,
@@ -52,15 +61,26 @@ where
     }
 
     fn layout(&mut self, styles: &Stylesheet) {
-        match self.alignment {
+        // The hint's point is already mirrored, so the button and label are placed in screen
+        // coordinates
+        geom::without_mirroring(|| match self.alignment {
             Alignment::Left => self.layout_left(styles),
             Alignment::Center => unimplemented!("alignment should be Left or Right"),
             Alignment::Right => self.layout_right(styles),
-        }
+        });
         self.has_layout = true;
     }
 
     fn layout_left(&mut self, styles: &Stylesheet) {
+        if geom::right_to_left() {
+            self.label
+                .set_position(Point::new(self.point.x, self.point.y + 2));
+            let rect = self.label.bounding_box(styles);
+            self.button
+                .set_position(Point::new(rect.x + rect.w as i32 + 8, self.point.y));
+            return;
+        }
+
         self.button.set_position(self.point);
         let width = self.button.bounding_box(styles).w;
         self.label.set_position(Point::new(
@@ -70,6 +90,15 @@ where
     }
 
     fn layout_right(&mut self, styles: &Stylesheet) {
+        if geom::right_to_left() {
+            self.button.set_position(self.point);
+            self.label.set_position(Point::new(
+                self.button.bounding_box(styles).x - 8,
+                self.point.y + 2,
+            ));
+            return;
+        }
+
         self.label
             .set_position(Point::new(self.point.x, self.point.y + 2));
         self.button.set_position(Point::new(
@@ -150,7 +179,7 @@ where
     }
 
     fn set_position(&mut self, point: Point) {
-        self.point = point;
+        self.point = point.directional();
         self.has_layout = false;
     }
 }
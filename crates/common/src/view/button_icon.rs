@@ -5,7 +5,8 @@ use async_trait::async_trait;
 use embedded_graphics::Drawable;
 use embedded_graphics::prelude::{Dimensions, Size};
 use embedded_graphics::primitives::{
-    Circle, CornerRadii, CornerRadiiBuilder, Primitive, PrimitiveStyle, Rectangle, RoundedRectangle,
+    Circle, CornerRadii, CornerRadiiBuilder, Primitive, PrimitiveStyle, PrimitiveStyleBuilder,
+    Rectangle, RoundedRectangle, StrokeAlignment,
 };
 use embedded_graphics::text::{Text, TextStyleBuilder};
 use serde::{Deserialize, Serialize};
@@ -14,7 +15,7 @@ use tokio::sync::mpsc::Sender;
 use crate::display::font::FontTextStyleBuilder;
 use crate::geom::{Alignment, Point, Rect};
 use crate::platform::{DefaultPlatform, Key, KeyEvent, Platform};
-use crate::stylesheet::Stylesheet;
+use crate::stylesheet::{ButtonHintStyle, Stylesheet};
 use crate::view::{Command, View};
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -63,10 +64,21 @@ impl View for ButtonIcon {
             Key::Menu => (styles.disabled_color, "MENU"),
             Key::L2 => (styles.disabled_color, "L2"),
             Key::R2 => (styles.disabled_color, "R2"),
+            Key::L3 => (styles.disabled_color, "L3"),
+            Key::R3 => (styles.disabled_color, "R3"),
+            Key::LeftStickUp
+            | Key::LeftStickDown
+            | Key::LeftStickLeft
+            | Key::LeftStickRight
+            | Key::RightStickUp
+            | Key::RightStickDown
+            | Key::RightStickLeft
+            | Key::RightStickRight => (styles.disabled_color, ""),
             Key::Power => (styles.disabled_color, "POWER"),
             Key::VolDown => (styles.disabled_color, "VOL-"),
             Key::VolUp => (styles.disabled_color, "VOL+"),
             Key::LidClose => (styles.disabled_color, "LID"),
+            Key::Headphones => (styles.disabled_color, "HP"),
             Key::Unknown => unimplemented!("unknown button"),
         };
 
@@ -84,10 +96,17 @@ impl View for ButtonIcon {
             }
         };
 
+        let outline = styles.button_hint_style == ButtonHintStyle::Outline
+            && matches!(self.button, Key::A | Key::B | Key::X | Key::Y);
+
         let text_style = FontTextStyleBuilder::new(styles.ui_font.font())
             .font_fallback(styles.cjk_font.font())
             .font_size(diameter * 3 / 4)
-            .text_color(styles.foreground_color)
+            .text_color(if outline {
+                color
+            } else {
+                styles.foreground_color
+            })
             .build();
         let mut text = Text::with_text_style(
             text,
@@ -104,8 +123,17 @@ impl View for ButtonIcon {
         let mut draw_bg = false;
         let rect = match self.button {
             Key::A | Key::B | Key::X | Key::Y => {
+                let style = if outline {
+                    PrimitiveStyleBuilder::new()
+                        .stroke_color(color)
+                        .stroke_width((diameter / 12).max(2))
+                        .stroke_alignment(StrokeAlignment::Inside)
+                        .build()
+                } else {
+                    PrimitiveStyle::with_fill(color)
+                };
                 Circle::new(point, diameter)
-                    .into_styled(PrimitiveStyle::with_fill(color))
+                    .into_styled(style)
                     .draw(display)?;
                 Rect::new(point.x, point.y, diameter, diameter)
             }
@@ -279,10 +307,21 @@ impl View for ButtonIcon {
             Key::Menu => "MENU",
             Key::L2 => "L2",
             Key::R2 => "R2",
+            Key::L3 => "L3",
+            Key::R3 => "R3",
+            Key::LeftStickUp
+            | Key::LeftStickDown
+            | Key::LeftStickLeft
+            | Key::LeftStickRight
+            | Key::RightStickUp
+            | Key::RightStickDown
+            | Key::RightStickLeft
+            | Key::RightStickRight => "",
             Key::Power => "POWER",
             Key::VolDown => "VOL-",
             Key::VolUp => "VOL+",
             Key::LidClose => "LID",
+            Key::Headphones => "HP",
             Key::Unknown => unimplemented!("unknown button"),
         };
 
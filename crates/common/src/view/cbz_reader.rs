@@ -0,0 +1,425 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::Read;
+use std::iter::Peekable;
+use std::path::{Path, PathBuf};
+use std::str::Chars;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use image::RgbaImage;
+use image::imageops::{self, FilterType};
+use log::error;
+use tokio::sync::mpsc::Sender;
+use zip::ZipArchive;
+
+use crate::command::Command;
+use crate::database::Database;
+use crate::geom::{Alignment, Point, Rect};
+use crate::locale::Locale;
+use crate::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use crate::resources::Resources;
+use crate::stylesheet::Stylesheet;
+use crate::view::{ButtonHint, ButtonIcon, PageViewport, Row, View};
+
+const IMAGE_EXTENSIONS: [&str; 4] = ["png", "jpg", "jpeg", "gif"];
+
+/// How a page is scaled to the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FitMode {
+    /// The whole page is visible.
+    Page,
+    /// The page fills the screen width, and is scrolled vertically.
+    Width,
+}
+
+impl FitMode {
+    fn button_hint(self, locale: &Locale) -> String {
+        match self {
+            FitMode::Page => locale.t("cbz-reader-fit-page"),
+            FitMode::Width => locale.t("cbz-reader-fit-width"),
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            FitMode::Page => FitMode::Width,
+            FitMode::Width => FitMode::Page,
+        }
+    }
+}
+
+/// Reader for comics and manuals stored as a CBZ, a zip archive of page images.
+#[derive(Debug)]
+pub struct CbzReader {
+    rect: Rect,
+    res: Resources,
+    path: PathBuf,
+    archive: Option<ZipArchive<File>>,
+    /// Names of the page images in the archive, in reading order.
+    pages: Vec<String>,
+    /// Current page, starting from 0.
+    page: usize,
+    fit: FitMode,
+    viewport: PageViewport,
+    button_hints: Row<ButtonHint<String>>,
+}
+
+impl CbzReader {
+    #[must_use]
+    pub fn new(rect: Rect, res: Resources, path: PathBuf) -> Self {
+        let (archive, pages) = match open(&path) {
+            Ok((archive, pages)) => (Some(archive), pages),
+            Err(e) => {
+                error!("failed to open cbz: {}", e);
+                (None, Vec::new())
+            }
+        };
+
+        let page = res
+            .get::<Database>()
+            .get_guide_cursor(&path)
+            .map_err(|e| error!("failed to load guide cursor from database: {}", e))
+            .unwrap_or_default() as usize;
+
+        let Rect { x, y, w, h } = rect;
+
+        let locale = res.get::<Locale>();
+        let styles = res.get::<Stylesheet>();
+
+        let fit = FitMode::Page;
+        let button_hints = Row::new(
+            Point::new(
+                x + w as i32 - 12,
+                y + h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            vec![
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::R,
+                    locale.t("guide-button-next-page"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::Select,
+                    fit.button_hint(&locale),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::X,
+                    locale.t("guide-button-zoom-in"),
+                    Alignment::Right,
+                ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::B,
+                    locale.t("button-back"),
+                    Alignment::Right,
+                ),
+            ],
+            Alignment::Right,
+            12,
+        );
+
+        let viewport = PageViewport::new(rect, &styles);
+
+        drop(locale);
+        drop(styles);
+
+        let mut this = Self {
+            rect,
+            res,
+            path,
+            archive,
+            page: page.min(pages.len().saturating_sub(1)),
+            pages,
+            fit,
+            viewport,
+            button_hints,
+        };
+        this.render();
+        this
+    }
+
+    /// Path to the CBZ.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn save_page(&self) {
+        self.res
+            .get::<Database>()
+            .update_guide_cursor(&self.path, self.page as u64)
+            .map_err(|e| error!("failed to update guide cursor to database: {}", e))
+            .ok();
+    }
+
+    /// Decodes and scales the current page for the current fit mode and zoom.
+    fn render(&mut self) {
+        self.viewport.set_image(None);
+        let (Some(archive), Some(name)) = (self.archive.as_mut(), self.pages.get(self.page)) else {
+            return;
+        };
+
+        let image = load_page(
+            archive,
+            name,
+            self.viewport.rect(),
+            self.fit,
+            self.viewport.zoom(),
+        )
+        .map_err(|e| error!("failed to render cbz page: {}", e))
+        .ok();
+        self.viewport.set_image(image);
+    }
+
+    fn set_page(&mut self, page: usize) {
+        if page < self.pages.len() && page != self.page {
+            self.page = page;
+            self.viewport.reset_scroll();
+            self.render();
+        }
+    }
+
+    fn set_zoom(&mut self, zoom: u32) {
+        if self.viewport.set_zoom(zoom) {
+            self.render();
+        }
+    }
+
+    fn set_fit(&mut self, fit: FitMode) {
+        self.fit = fit;
+        self.viewport.set_zoom(1);
+        self.viewport.reset_scroll();
+        self.button_hints
+            .get_mut(1)
+            .unwrap()
+            .set_text(fit.button_hint(&self.res.get::<Locale>()));
+        self.render();
+    }
+}
+
+/// Decodes a page image, scaled to `viewport` for the fit mode and zoom.
+fn load_page(
+    archive: &mut ZipArchive<File>,
+    name: &str,
+    viewport: Rect,
+    fit: FitMode,
+    zoom: u32,
+) -> Result<RgbaImage> {
+    let mut bytes = Vec::new();
+    archive.by_name(name)?.read_to_end(&mut bytes)?;
+    let image = image::load_from_memory(&bytes)?;
+
+    let scale = match fit {
+        FitMode::Page => f32::min(
+            viewport.w as f32 / image.width() as f32,
+            viewport.h as f32 / image.height() as f32,
+        ),
+        FitMode::Width => viewport.w as f32 / image.width() as f32,
+    } * zoom as f32;
+    Ok(imageops::resize(
+        &image,
+        ((image.width() as f32 * scale) as u32).max(1),
+        ((image.height() as f32 * scale) as u32).max(1),
+        FilterType::Triangle,
+    ))
+}
+
+/// Opens a CBZ, and returns the names of its page images sorted by name.
+fn open(path: &Path) -> Result<(ZipArchive<File>, Vec<String>)> {
+    let archive = ZipArchive::new(File::open(path)?)?;
+    let mut pages: Vec<String> = archive
+        .file_names()
+        .filter(|name| {
+            Path::new(name)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| IMAGE_EXTENSIONS.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+        })
+        .map(str::to_owned)
+        .collect();
+    pages.sort_by(|a, b| natural_cmp(a, b));
+    Ok((archive, pages))
+}
+
+/// Compares names case-insensitively, ordering runs of digits by their value so that "page 2"
+/// comes before "page 10".
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+    loop {
+        let ordering = match (a.peek(), b.peek()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let x = take_number(&mut a);
+                let y = take_number(&mut b);
+                x.len().cmp(&y.len()).then_with(|| x.cmp(&y))
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                a.next();
+                b.next();
+                ordering
+            }
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+/// Takes a run of digits, without leading zeros.
+fn take_number(chars: &mut Peekable<Chars<'_>>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        digits.push(c);
+    }
+    digits.trim_start_matches('0').to_owned()
+}
+
+#[async_trait(?Send)]
+impl View for CbzReader {
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        let mut drawn = false;
+
+        if self.viewport.should_draw() {
+            let locale = self.res.get::<Locale>();
+            let text = if self.viewport.has_image() {
+                let mut map = HashMap::new();
+                map.insert("page".into(), (self.page + 1).into());
+                map.insert("pages".into(), self.pages.len().into());
+                locale.ta("guide-page", &map)
+            } else {
+                locale.t("cbz-reader-error")
+            };
+            drawn |= self.viewport.draw(display, styles, &text)?;
+        }
+
+        drawn |= self.button_hints.draw(display, styles)?;
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.viewport.should_draw() || self.button_hints.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.viewport.set_should_draw();
+        self.button_hints.set_should_draw();
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        _commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        if self.viewport.handle_key_event(event) {
+            return Ok(true);
+        }
+        match event {
+            KeyEvent::Pressed(Key::L) | KeyEvent::Autorepeat(Key::L) => {
+                self.set_page(self.page.saturating_sub(1))
+            }
+            KeyEvent::Pressed(Key::R) | KeyEvent::Autorepeat(Key::R) => {
+                self.set_page(self.page + 1)
+            }
+            KeyEvent::Pressed(Key::Select) => self.set_fit(self.fit.next()),
+            KeyEvent::Pressed(Key::X) => self.set_zoom(self.viewport.zoom() + 1),
+            KeyEvent::Pressed(Key::Y) => self.set_zoom(self.viewport.zoom().saturating_sub(1)),
+            KeyEvent::Pressed(Key::B) => {
+                self.save_page();
+                bubble.push_back(Command::CloseView);
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::io::Write;
+
+    use zip::ZipWriter;
+    use zip::write::SimpleFileOptions;
+
+    use super::*;
+
+    #[test]
+    fn test_natural_cmp() {
+        let mut names = vec![
+            "page10.jpg",
+            "Page2.jpg",
+            "page1.jpg",
+            "page02b.jpg",
+            "cover.png",
+            "ch2/page1.jpg",
+            "ch10/page1.jpg",
+        ];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(
+            names,
+            [
+                "ch2/page1.jpg",
+                "ch10/page1.jpg",
+                "cover.png",
+                "page1.jpg",
+                "Page2.jpg",
+                "page02b.jpg",
+                "page10.jpg",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_open() {
+        let dir = env::temp_dir().join("allium-test-cbz");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("Comic.cbz");
+        let mut zip = ZipWriter::new(File::create(&path).unwrap());
+        for name in ["10.JPG", "9.png", "ComicInfo.xml", "1.gif"] {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(name.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let (_, pages) = open(&path).unwrap();
+        assert_eq!(pages, ["1.gif", "9.png", "10.JPG"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -4,6 +4,7 @@ use std::path::{Path, PathBuf};
 use anyhow::Result;
 use async_trait::async_trait;
 use embedded_graphics::Drawable;
+use embedded_graphics::draw_target::DrawTargetExt;
 use embedded_graphics::image::ImageRaw;
 use image::{RgbaImage, imageops};
 use log::{error, trace};
@@ -38,6 +39,9 @@ pub struct Image {
     mode: ImageMode,
     border_radius: u32,
     alignment: Alignment,
+    /// Horizontal offset to draw the image at, clipped to the image's rect.
+    #[serde(skip)]
+    offset_x: i32,
     dirty: bool,
 }
 
@@ -50,6 +54,7 @@ impl Image {
             mode,
             border_radius: 0,
             alignment: Alignment::Left,
+            offset_x: 0,
             dirty: true,
         }
     }
@@ -68,6 +73,7 @@ impl Image {
             mode,
             border_radius: 0,
             alignment: Alignment::Left,
+            offset_x: 0,
             dirty: true,
         }
     }
@@ -81,6 +87,15 @@ impl Image {
         self
     }
 
+    /// Draws the image shifted horizontally, e.g. to slide it in.
+    pub fn set_offset_x(&mut self, offset_x: i32) -> &mut Self {
+        if offset_x != self.offset_x {
+            self.offset_x = offset_x;
+            self.dirty = true;
+        }
+        self
+    }
+
     pub fn set_alignment(&mut self, alignment: Alignment) -> &mut Self {
         self.alignment = alignment;
         self
@@ -162,9 +177,10 @@ impl View for Image {
         display.load(self.rect)?;
         if let Some(ref image) = self.image {
             let image: ImageRaw<'_, Color> = ImageRaw::new(image, self.rect.w);
-            let image = embedded_graphics::image::Image::new(&image, self.rect.top_left().into());
+            let point = Point::new(self.rect.x + self.offset_x, self.rect.y);
+            let image = embedded_graphics::image::Image::new(&image, point.into());
             trace!("drawing image: {:?}", self.rect);
-            image.draw(display)?;
+            image.draw(&mut display.clipped(&self.rect.into()))?;
         }
 
         self.dirty = !image_loaded && self.path.is_some();
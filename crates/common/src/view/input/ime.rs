@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+
+lazy_static! {
+    /// Common characters for each pinyin syllable, most frequent first.
+    static ref PINYIN: HashMap<&'static str, Vec<char>> = include_str!("pinyin.txt")
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(syllable, chars)| (syllable, chars.chars().collect()))
+        .collect();
+}
+
+/// Longest pinyin syllable, e.g. "zhuang".
+const MAX_SYLLABLE_LEN: usize = 6;
+
+#[rustfmt::skip]
+const ROMAJI: &[(&str, &str)] = &[
+    ("a", "あ"), ("i", "い"), ("u", "う"), ("e", "え"), ("o", "お"),
+    ("ka", "か"), ("ki", "き"), ("ku", "く"), ("ke", "け"), ("ko", "こ"),
+    ("kya", "きゃ"), ("kyu", "きゅ"), ("kyo", "きょ"),
+    ("sa", "さ"), ("shi", "し"), ("si", "し"), ("su", "す"), ("se", "せ"), ("so", "そ"),
+    ("sha", "しゃ"), ("shu", "しゅ"), ("sho", "しょ"), ("sya", "しゃ"), ("syu", "しゅ"), ("syo", "しょ"),
+    ("ta", "た"), ("chi", "ち"), ("ti", "ち"), ("tsu", "つ"), ("tu", "つ"), ("te", "て"), ("to", "と"),
+    ("cha", "ちゃ"), ("chu", "ちゅ"), ("cho", "ちょ"), ("tya", "ちゃ"), ("tyu", "ちゅ"), ("tyo", "ちょ"),
+    ("na", "な"), ("ni", "に"), ("nu", "ぬ"), ("ne", "ね"), ("no", "の"),
+    ("nya", "にゃ"), ("nyu", "にゅ"), ("nyo", "にょ"),
+    ("ha", "は"), ("hi", "ひ"), ("fu", "ふ"), ("hu", "ふ"), ("he", "へ"), ("ho", "ほ"),
+    ("hya", "ひゃ"), ("hyu", "ひゅ"), ("hyo", "ひょ"),
+    ("ma", "ま"), ("mi", "み"), ("mu", "む"), ("me", "め"), ("mo", "も"),
+    ("mya", "みゃ"), ("myu", "みゅ"), ("myo", "みょ"),
+    ("ya", "や"), ("yu", "ゆ"), ("yo", "よ"),
+    ("ra", "ら"), ("ri", "り"), ("ru", "る"), ("re", "れ"), ("ro", "ろ"),
+    ("rya", "りゃ"), ("ryu", "りゅ"), ("ryo", "りょ"),
+    ("wa", "わ"), ("wo", "を"),
+    ("ga", "が"), ("gi", "ぎ"), ("gu", "ぐ"), ("ge", "げ"), ("go", "ご"),
+    ("gya", "ぎゃ"), ("gyu", "ぎゅ"), ("gyo", "ぎょ"),
+    ("za", "ざ"), ("ji", "じ"), ("zi", "じ"), ("zu", "ず"), ("ze", "ぜ"), ("zo", "ぞ"),
+    ("ja", "じゃ"), ("ju", "じゅ"), ("jo", "じょ"),
+    ("da", "だ"), ("di", "ぢ"), ("du", "づ"), ("de", "で"), ("do", "ど"),
+    ("ba", "ば"), ("bi", "び"), ("bu", "ぶ"), ("be", "べ"), ("bo", "ぼ"),
+    ("bya", "びゃ"), ("byu", "びゅ"), ("byo", "びょ"),
+    ("pa", "ぱ"), ("pi", "ぴ"), ("pu", "ぷ"), ("pe", "ぺ"), ("po", "ぽ"),
+    ("pya", "ぴゃ"), ("pyu", "ぴゅ"), ("pyo", "ぴょ"),
+    ("xa", "ぁ"), ("xi", "ぃ"), ("xu", "ぅ"), ("xe", "ぇ"), ("xo", "ぉ"),
+    ("xya", "ゃ"), ("xyu", "ゅ"), ("xyo", "ょ"), ("xtsu", "っ"), ("xtu", "っ"),
+    ("-", "ー"),
+];
+
+/// Input method for composing text that can't be typed directly on the keyboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputMethod {
+    /// Romaji is converted to hiragana or katakana.
+    Kana,
+    /// Pinyin syllables are converted to Chinese characters.
+    Pinyin,
+}
+
+impl InputMethod {
+    /// Candidates for the start of `composition`, and how many bytes of it a candidate replaces.
+    pub fn candidates(self, composition: &str) -> (Vec<String>, usize) {
+        if composition.is_empty() {
+            return (Vec::new(), 0);
+        }
+
+        match self {
+            InputMethod::Kana => {
+                let hiragana = romaji_to_hiragana(composition);
+                let katakana = hiragana_to_katakana(&hiragana);
+                (vec![hiragana, katakana], composition.len())
+            }
+            InputMethod::Pinyin => {
+                let syllable = (1..=composition.len().min(MAX_SYLLABLE_LEN))
+                    .rev()
+                    .map(|len| &composition[..len])
+                    .find(|s| PINYIN.contains_key(s));
+                match syllable {
+                    Some(syllable) => (
+                        PINYIN[syllable].iter().map(char::to_string).collect(),
+                        syllable.len(),
+                    ),
+                    None => (vec![composition.to_owned()], composition.len()),
+                }
+            }
+        }
+    }
+}
+
+/// Converts romaji to hiragana. Anything that can't be converted is kept as is.
+fn romaji_to_hiragana(romaji: &str) -> String {
+    const VOWELS: &str = "aiueo";
+
+    let chars = romaji.to_lowercase().chars().collect::<Vec<_>>();
+    let mut hiragana = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        // Doubled consonants are a small tsu, e.g. "kk" in "gakkou"
+        if c.is_ascii_alphabetic() && !VOWELS.contains(c) && c != 'n' && next == Some(c) {
+            hiragana.push('っ');
+            i += 1;
+            continue;
+        }
+
+        // "n" not followed by a vowel or "y" is ん, e.g. "kanji". "nn" is ん too, unless the
+        // second "n" starts a syllable of its own, e.g. "konnichiha"
+        if c == 'n' && next.is_none_or(|n| !VOWELS.contains(n) && n != 'y') {
+            let starts_syllable = next == Some('n')
+                && chars
+                    .get(i + 2)
+                    .is_some_and(|n| VOWELS.contains(*n) || *n == 'y');
+            hiragana.push('ん');
+            i += if next == Some('n') && !starts_syllable {
+                2
+            } else {
+                1
+            };
+            continue;
+        }
+
+        let matched = (1..=4).rev().find_map(|len| {
+            let syllable = chars.get(i..i + len)?.iter().collect::<String>();
+            ROMAJI
+                .iter()
+                .find(|(r, _)| *r == syllable)
+                .map(|(_, kana)| (len, *kana))
+        });
+        match matched {
+            Some((len, kana)) => {
+                hiragana.push_str(kana);
+                i += len;
+            }
+            None => {
+                hiragana.push(c);
+                i += 1;
+            }
+        }
+    }
+    hiragana
+}
+
+fn hiragana_to_katakana(hiragana: &str) -> String {
+    hiragana
+        .chars()
+        .map(|c| match c {
+            'ぁ'..='ゖ' => char::from_u32(c as u32 + 0x60).unwrap_or(c),
+            c => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kana() {
+        assert_eq!(romaji_to_hiragana("konnichiha"), "こんにちは");
+        assert_eq!(romaji_to_hiragana("gakkou"), "がっこう");
+        assert_eq!(romaji_to_hiragana("kanji"), "かんじ");
+        assert_eq!(romaji_to_hiragana("onnna"), "おんな");
+        assert_eq!(romaji_to_hiragana("pokemon"), "ぽけもん");
+        assert_eq!(hiragana_to_katakana("ぽけもん"), "ポケモン");
+
+        let (candidates, len) = InputMethod::Kana.candidates("zeruda");
+        assert_eq!(candidates, ["ぜるだ", "ゼルダ"]);
+        assert_eq!(len, 6);
+    }
+
+    #[test]
+    fn test_pinyin() {
+        let (candidates, len) = InputMethod::Pinyin.candidates("baoke");
+        assert_eq!(candidates[0], "宝");
+        assert_eq!(len, 3);
+
+        let (candidates, len) = InputMethod::Pinyin.candidates("zhuang");
+        assert!(candidates.contains(&"装".to_owned()));
+        assert_eq!(len, 6);
+
+        let (candidates, len) = InputMethod::Pinyin.candidates("v");
+        assert_eq!(candidates, ["v"]);
+        assert_eq!(len, 1);
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::VecDeque;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use async_trait::async_trait;
@@ -14,17 +15,35 @@ use tokio::sync::mpsc::Sender;
 use crate::command::{Command, Value};
 use crate::display::{Display, font::FontTextStyleBuilder};
 use crate::geom::{self, Alignment, Point, Rect};
-use crate::locale::Locale;
+use crate::locale::{KeyboardLayout, Locale, LocaleSettings};
 use crate::platform::{DefaultPlatform, Key, KeyEvent, Platform};
 use crate::resources::Resources;
 use crate::stylesheet::Stylesheet;
+use crate::view::input::ime::InputMethod;
 use crate::view::{ButtonHint, ButtonIcon, Row, View};
 
 #[derive(Debug, Clone)]
 pub struct Keyboard {
     value: String,
+    /// Byte offset of the text cursor in `value`.
+    text_cursor: usize,
+    /// Whether the whole value is selected, to be replaced by the next input.
+    selected_all: bool,
     cursor: rusttype::Point<usize>,
     mode: KeyboardMode,
+    /// Whether T9 entry is one of the modes.
+    t9: bool,
+    /// Index of the selected T9 key.
+    t9_cursor: usize,
+    /// Last tapped T9 key, how many times it was tapped, and when.
+    t9_tap: Option<(usize, usize, Instant)>,
+    layout: KeyboardLayout,
+    /// Accented variants of the last typed letter, shown after a long press.
+    accents: Option<Vec<String>>,
+    /// Romaji or pinyin typed in an input method mode, not yet converted.
+    composition: String,
+    /// Index of the highlighted conversion candidate.
+    candidate: usize,
     is_password: bool,
     button_hints: Row<ButtonHint<String>>,
     dirty: bool,
@@ -60,28 +79,176 @@ impl Keyboard {
                 ButtonHint::new(
                     res.clone(),
                     Point::zero(),
-                    Key::R,
+                    Key::Y,
                     locale.t("keyboard-button-backspace"),
                     Alignment::Right,
                 ),
+                ButtonHint::new(
+                    res.clone(),
+                    Point::zero(),
+                    Key::X,
+                    locale.t("keyboard-button-select-all"),
+                    Alignment::Right,
+                ),
             ],
             Alignment::Right,
             12,
         );
 
         Self {
+            text_cursor: value.len(),
+            selected_all: false,
             value,
             cursor: rusttype::Point { x: 5, y: 2 },
             mode: KeyboardMode::Lowercase,
+            t9: false,
+            t9_cursor: 4,
+            t9_tap: None,
+            layout: LocaleSettings::load()
+                .map(|s| s.keyboard_layout)
+                .unwrap_or_default(),
+            accents: None,
+            composition: String::new(),
+            candidate: 0,
             is_password,
             button_hints,
             dirty: true,
         }
     }
 
+    /// Keyboard for search queries, which starts in T9 mode if it's enabled.
+    pub fn search(res: Resources, value: String) -> Self {
+        let mut keyboard = Self::new(res, value, false);
+        if LocaleSettings::load().is_ok_and(|s| s.t9_search) {
+            keyboard.t9 = true;
+            keyboard.mode = KeyboardMode::T9;
+        }
+        keyboard
+    }
+
     pub fn value(&self) -> &str {
         &self.value
     }
+
+    fn candidates(&self) -> (Vec<String>, usize) {
+        if let Some(accents) = &self.accents {
+            return (accents.clone(), 0);
+        }
+        match self.mode.input_method() {
+            Some(ime) => ime.candidates(&self.composition),
+            None => (Vec::new(), 0),
+        }
+    }
+
+    /// Replaces the start of the composition with the highlighted candidate.
+    fn commit_candidate(&mut self) {
+        let (candidates, len) = self.candidates();
+        if let Some(candidate) = candidates.get(self.candidate).or(candidates.first()) {
+            let candidate = candidate.clone();
+            self.insert(&candidate);
+            self.composition.drain(..len);
+        }
+        self.candidate = 0;
+    }
+
+    /// Inserts `text` at the text cursor, replacing the value if it's all selected.
+    fn insert(&mut self, text: &str) {
+        if self.selected_all {
+            self.value.clear();
+            self.text_cursor = 0;
+            self.selected_all = false;
+        }
+        self.value.insert_str(self.text_cursor, text);
+        self.text_cursor += text.len();
+    }
+
+    /// Deletes the character before the text cursor, or the whole value if it's all selected.
+    fn backspace(&mut self) {
+        if self.selected_all {
+            self.value.clear();
+            self.text_cursor = 0;
+            self.selected_all = false;
+        } else if let Some(c) = self.value[..self.text_cursor].chars().next_back() {
+            self.text_cursor -= c.len_utf8();
+            self.value.remove(self.text_cursor);
+        }
+    }
+
+    fn move_left(&mut self, word: bool) {
+        self.selected_all = false;
+        self.text_cursor = if word {
+            prev_word_boundary(&self.value, self.text_cursor)
+        } else {
+            self.value[..self.text_cursor]
+                .chars()
+                .next_back()
+                .map_or(0, |c| self.text_cursor - c.len_utf8())
+        };
+    }
+
+    fn move_right(&mut self, word: bool) {
+        self.selected_all = false;
+        self.text_cursor = if word {
+            next_word_boundary(&self.value, self.text_cursor)
+        } else {
+            self.value[self.text_cursor..]
+                .chars()
+                .next()
+                .map_or(self.value.len(), |c| self.text_cursor + c.len_utf8())
+        };
+    }
+
+    /// Handles navigating and tapping the T9 keys. Tapping a key again within
+    /// `T9_TAP_TIMEOUT` cycles through its characters.
+    fn handle_t9_key_event(&mut self, event: KeyEvent) -> bool {
+        let (col, row) = (
+            self.t9_cursor as i32 % T9_COLUMNS,
+            self.t9_cursor as i32 / T9_COLUMNS,
+        );
+        let (col, row) = match event {
+            KeyEvent::Pressed(Key::Up) | KeyEvent::Autorepeat(Key::Up) => {
+                (col, (row - 1).rem_euclid(T9_ROWS))
+            }
+            KeyEvent::Pressed(Key::Down) | KeyEvent::Autorepeat(Key::Down) => {
+                (col, (row + 1).rem_euclid(T9_ROWS))
+            }
+            KeyEvent::Pressed(Key::Left) | KeyEvent::Autorepeat(Key::Left) => {
+                ((col - 1).rem_euclid(T9_COLUMNS), row)
+            }
+            KeyEvent::Pressed(Key::Right) | KeyEvent::Autorepeat(Key::Right) => {
+                ((col + 1).rem_euclid(T9_COLUMNS), row)
+            }
+            KeyEvent::Pressed(Key::A) => {
+                let chars = T9_KEYS[self.t9_cursor].1.chars().collect::<Vec<_>>();
+                let taps = match self.t9_tap {
+                    Some((key, taps, at))
+                        if key == self.t9_cursor && at.elapsed() < T9_TAP_TIMEOUT =>
+                    {
+                        self.backspace();
+                        taps + 1
+                    }
+                    _ => 0,
+                };
+                self.insert(&chars[taps % chars.len()].to_string());
+                self.t9_tap = Some((self.t9_cursor, taps, Instant::now()));
+                self.dirty = true;
+                return true;
+            }
+            _ => return false,
+        };
+        self.t9_cursor = (col + row * T9_COLUMNS) as usize;
+        self.t9_tap = None;
+        self.dirty = true;
+        true
+    }
+
+    /// Converts the whole composition using the first candidates.
+    fn commit_composition(&mut self) {
+        self.candidate = 0;
+        while !self.composition.is_empty() {
+            self.commit_candidate();
+        }
+    }
 }
 
 #[async_trait(?Send)]
@@ -138,7 +305,45 @@ impl View for Keyboard {
             .into_styled(fill_style)
             .draw(display)?;
 
-            for (i, key) in KeyboardKey::iter().enumerate().take(KeyboardKey::COUNT - 1) {
+            if self.mode == KeyboardMode::T9 {
+                let (cw, ch) = (w / T9_COLUMNS, h / T9_ROWS);
+                for (i, (label, _)) in T9_KEYS.iter().enumerate() {
+                    let (col, row) = (i as i32 % T9_COLUMNS, i as i32 / T9_COLUMNS);
+                    let selected = self.t9_cursor == i;
+                    if selected {
+                        RoundedRectangle::with_equal_corners(
+                            Rect::new(x0 + col * cw, y0 + row * ch, cw as u32, ch as u32).into(),
+                            Size::new(12, 12),
+                        )
+                        .into_styled(selected_btn_style)
+                        .draw(display)?;
+                    }
+
+                    Text::with_alignment(
+                        label,
+                        Point::new(
+                            x0 + col * cw + cw / 2,
+                            y0 + row * ch + ch / 2 - styles.ui_font.size as i32 / 2,
+                        )
+                        .into(),
+                        if selected {
+                            selected_text_style.clone()
+                        } else {
+                            text_style.clone()
+                        },
+                        Alignment::Center.into(),
+                    )
+                    .draw(display)?;
+                }
+            }
+
+            // T9 has its own grid in place of the keys and spacebar
+            let keys = if self.mode == KeyboardMode::T9 {
+                0
+            } else {
+                KeyboardKey::COUNT - 1
+            };
+            for (i, key) in KeyboardKey::iter().enumerate().take(keys) {
                 let i = i as i32;
                 let x = i % KEYBOARD_COLUMNS * w / KEYBOARD_COLUMNS;
                 let y = i / KEYBOARD_COLUMNS * h / KEYBOARD_ROWS;
@@ -155,7 +360,7 @@ impl View for Keyboard {
                 }
 
                 Text::with_alignment(
-                    key.key(self.mode),
+                    key.key(self.mode, self.layout),
                     Point::new(
                         x0 + x + key_size as i32 / 2,
                         y0 + y + key_size as i32 / 2 - styles.ui_font.size as i32 / 2,
@@ -172,7 +377,7 @@ impl View for Keyboard {
             }
 
             // Spacebar
-            {
+            if self.mode != KeyboardMode::T9 {
                 let y = 4 * h / KEYBOARD_ROWS;
                 let selected = self.cursor.y == 4;
                 if selected {
@@ -185,14 +390,14 @@ impl View for Keyboard {
                 }
 
                 Text::with_alignment(
-                    "space",
+                    &self.mode.space_label(self.layout),
                     Point::new(
                         x0 + w / 2,
                         y0 + y + key_size as i32 / 2 - styles.ui_font.size as i32 / 2,
                     )
                     .into(),
                     if selected {
-                        selected_text_style
+                        selected_text_style.clone()
                     } else {
                         text_style.clone()
                     },
@@ -201,15 +406,79 @@ impl View for Keyboard {
                 .draw(display)?;
             }
 
-            Text::with_alignment(
-                &masked_value(&self.value, self.is_password),
-                Point::new(
-                    display.size().width as i32 / 2,
-                    display.size().height as i32 - h - 48 - styles.ui_font.size as i32,
-                )
-                .into(),
-                text_style,
+            // Candidate bar
+            let bar = Rect::new(
+                8,
+                y0 - styles.ui_font.size as i32 * 2 - 8 - 16,
+                display.size().width - 16,
+                styles.ui_font.size + 8,
+            );
+            display.load(bar)?;
+            let (candidates, _) = self.candidates();
+            if !candidates.is_empty() {
+                RoundedRectangle::with_equal_corners(bar.into(), Size::new_equal(8))
+                    .into_styled(fill_style)
+                    .draw(display)?;
+
+                let mut x = bar.x + 8;
+                for (i, candidate) in candidates.iter().enumerate() {
+                    let text = Text::with_alignment(
+                        candidate,
+                        Point::new(x, bar.y + 4).into(),
+                        if i == self.candidate {
+                            selected_text_style.clone()
+                        } else {
+                            text_style.clone()
+                        },
+                        Alignment::Left.into(),
+                    );
+                    x += text.bounding_box().size.width as i32 + 16;
+                    if x > bar.x + bar.w as i32 {
+                        break;
+                    }
+                    text.draw(display)?;
+                }
+            }
+
+            // The composition is shown at the text cursor until it's converted
+            let before =
+                masked_value(&self.value[..self.text_cursor], self.is_password) + &self.composition;
+            let after = masked_value(&self.value[self.text_cursor..], self.is_password);
+            let value_point = Point::new(
+                display.size().width as i32 / 2,
+                display.size().height as i32 - h - 48 - styles.ui_font.size as i32,
+            );
+            let full = before.clone() + &after;
+            let value = Text::with_alignment(
+                &full,
+                value_point.into(),
+                if self.selected_all {
+                    selected_text_style.clone()
+                } else {
+                    text_style.clone()
+                },
                 Alignment::Center.into(),
+            );
+            let value_left = value.bounding_box().top_left.x;
+            value.draw(display)?;
+
+            let before_width = Text::with_alignment(
+                &before,
+                value_point.into(),
+                text_style,
+                Alignment::Left.into(),
+            )
+            .bounding_box()
+            .size
+            .width;
+            Rectangle::new(
+                Point::new(value_left + before_width as i32, value_point.y).into(),
+                Size::new(2, styles.ui_font.size),
+            )
+            .into_styled(
+                PrimitiveStyleBuilder::new()
+                    .fill_color(styles.foreground_color)
+                    .build(),
             )
             .draw(display)?;
 
@@ -246,6 +515,41 @@ impl View for Keyboard {
         commands: Sender<Command>,
         bubble: &mut VecDeque<Command>,
     ) -> Result<bool> {
+        if let Some(accents) = &self.accents {
+            match event {
+                KeyEvent::Pressed(Key::Left) | KeyEvent::Autorepeat(Key::Left) => {
+                    self.candidate = (self.candidate + accents.len() - 1) % accents.len();
+                }
+                KeyEvent::Pressed(Key::Right) | KeyEvent::Autorepeat(Key::Right) => {
+                    self.candidate = (self.candidate + 1) % accents.len();
+                }
+                KeyEvent::Pressed(Key::A) => {
+                    // Replace the letter that was typed by the long press
+                    let accent = accents[self.candidate].clone();
+                    self.backspace();
+                    self.insert(&accent);
+                    self.accents = None;
+                    self.candidate = 0;
+                }
+                KeyEvent::Pressed(Key::B) => {
+                    self.accents = None;
+                    self.candidate = 0;
+                }
+                _ => return Ok(true),
+            }
+            self.dirty = true;
+            return Ok(true);
+        }
+
+        if self.mode == KeyboardMode::T9 {
+            if self.handle_t9_key_event(event) {
+                return Ok(true);
+            }
+            if matches!(event, KeyEvent::Pressed(_)) {
+                self.t9_tap = None;
+            }
+        }
+
         match event {
             KeyEvent::Pressed(Key::Up) | KeyEvent::Autorepeat(Key::Up) => {
                 self.cursor.y = (self.cursor.y as i32 - 1).rem_euclid(KEYBOARD_ROWS) as usize;
@@ -265,18 +569,71 @@ impl View for Keyboard {
             }
             KeyEvent::Pressed(Key::A) => {
                 if self.cursor.y == 4 {
-                    self.value.push(' ');
+                    if self.composition.is_empty() {
+                        self.insert(" ");
+                    } else {
+                        self.commit_candidate();
+                    }
                 } else {
-                    self.value += KeyboardKey::from_repr(
+                    let key = KeyboardKey::from_repr(
                         self.cursor.x + self.cursor.y * KEYBOARD_COLUMNS as usize,
                     )
-                    .unwrap()
-                    .key(self.mode)
+                    .unwrap();
+                    let key = key.key(self.mode, self.layout);
+                    if self.mode.input_method().is_some()
+                        && key.chars().all(|c| c.is_ascii_lowercase() || c == '-')
+                    {
+                        self.composition += key;
+                        self.candidate = 0;
+                    } else {
+                        self.commit_composition();
+                        self.insert(key);
+                    }
+                }
+                self.dirty = true;
+            }
+            KeyEvent::Autorepeat(Key::A) if self.cursor.y < 4 && self.mode.is_letters() => {
+                let key = KeyboardKey::from_repr(
+                    self.cursor.x + self.cursor.y * KEYBOARD_COLUMNS as usize,
+                )
+                .unwrap();
+                let accents = diacritics(key.key(self.mode, self.layout));
+                if !accents.is_empty() {
+                    self.accents = Some(accents);
+                    self.candidate = 0;
+                    self.dirty = true;
+                }
+            }
+            KeyEvent::Pressed(Key::L2) => {
+                self.layout =
+                    KeyboardLayout::from_repr((self.layout as usize + 1) % KeyboardLayout::COUNT)
+                        .unwrap();
+                self.dirty = true;
+            }
+            KeyEvent::Pressed(Key::R2) | KeyEvent::Autorepeat(Key::R2) => {
+                let (candidates, _) = self.candidates();
+                if !candidates.is_empty() {
+                    self.candidate = (self.candidate + 1) % candidates.len();
+                }
+                self.dirty = true;
+            }
+            KeyEvent::Pressed(Key::Y) | KeyEvent::Autorepeat(Key::Y) => {
+                if self.composition.pop().is_some() {
+                    self.candidate = 0;
+                } else {
+                    self.backspace();
                 }
                 self.dirty = true;
             }
-            KeyEvent::Pressed(Key::R) | KeyEvent::Pressed(Key::L) => {
-                self.value.pop();
+            KeyEvent::Pressed(Key::L) | KeyEvent::Autorepeat(Key::L) => {
+                // Holding jumps by words
+                self.commit_composition();
+                self.move_left(matches!(event, KeyEvent::Autorepeat(_)));
+                self.dirty = true;
+            }
+            KeyEvent::Pressed(Key::R) | KeyEvent::Autorepeat(Key::R) => {
+                self.commit_composition();
+                self.move_right(matches!(event, KeyEvent::Autorepeat(_)));
                 self.dirty = true;
             }
             KeyEvent::Pressed(Key::B) => {
@@ -284,18 +641,30 @@ impl View for Keyboard {
                 commands.send(Command::Redraw).await?;
             }
             KeyEvent::Pressed(Key::X) => {
-                self.value.clear();
+                // Pressing again while everything is selected clears the value
+                self.composition.clear();
+                if self.selected_all {
+                    self.backspace();
+                } else {
+                    self.selected_all = !self.value.is_empty();
+                }
                 self.dirty = true;
             }
             KeyEvent::Pressed(Key::Select) => {
+                self.commit_composition();
                 self.mode = match self.mode {
                     KeyboardMode::Lowercase => KeyboardMode::Uppercase,
                     KeyboardMode::Uppercase => KeyboardMode::Symbols,
-                    KeyboardMode::Symbols => KeyboardMode::Lowercase,
+                    KeyboardMode::Symbols => KeyboardMode::Extended,
+                    KeyboardMode::Extended => KeyboardMode::Kana,
+                    KeyboardMode::Kana => KeyboardMode::Pinyin,
+                    KeyboardMode::Pinyin if self.t9 => KeyboardMode::T9,
+                    KeyboardMode::Pinyin | KeyboardMode::T9 => KeyboardMode::Lowercase,
                 };
                 self.dirty = true;
             }
             KeyEvent::Pressed(Key::Start) => {
+                self.commit_composition();
                 bubble.push_back(Command::ValueChanged(0, Value::String(self.value.clone())));
                 bubble.push_back(Command::CloseView);
                 commands.send(Command::Redraw).await?;
@@ -342,8 +711,27 @@ enum KeyboardKey {
 const KEYBOARD_COLUMNS: i32 = 11;
 const KEYBOARD_ROWS: i32 = 5;
 
+/// Label and characters of each T9 key.
+const T9_KEYS: [(&str, &str); 12] = [
+    ("1 .,?!", ".,?!'-1"),
+    ("2 abc", "abc2"),
+    ("3 def", "def3"),
+    ("4 ghi", "ghi4"),
+    ("5 jkl", "jkl5"),
+    ("6 mno", "mno6"),
+    ("7 pqrs", "pqrs7"),
+    ("8 tuv", "tuv8"),
+    ("9 wxyz", "wxyz9"),
+    ("*", "*"),
+    ("0 ␣", " 0"),
+    ("#", "#"),
+];
+const T9_COLUMNS: i32 = 3;
+const T9_ROWS: i32 = 4;
+const T9_TAP_TIMEOUT: Duration = Duration::from_millis(1000);
+
 impl KeyboardKey {
-    fn lowercase(&self) -> &str {
+    fn lowercase(&self) -> &'static str {
         #[allow(clippy::enum_glob_use)]
         use KeyboardKey::*;
         match self {
@@ -395,7 +783,7 @@ impl KeyboardKey {
         }
     }
 
-    fn uppercase(&self) -> &str {
+    fn uppercase(&self) -> &'static str {
         #[allow(clippy::enum_glob_use)]
         use KeyboardKey::*;
         match self {
@@ -447,7 +835,7 @@ impl KeyboardKey {
         }
     }
 
-    fn symbol(&self) -> &str {
+    fn symbol(&self) -> &'static str {
         #[allow(clippy::enum_glob_use)]
         use KeyboardKey::*;
         match self {
@@ -499,26 +887,378 @@ impl KeyboardKey {
         }
     }
 
-    fn key(&self, mode: KeyboardMode) -> &str {
+    fn extended(&self) -> &'static str {
+        #[allow(clippy::enum_glob_use)]
+        use KeyboardKey::*;
+        match self {
+            K1 => "¡",
+            K2 => "¿",
+            K3 => "€",
+            K4 => "£",
+            K5 => "¥",
+            K6 => "¢",
+            K7 => "§",
+            K8 => "¶",
+            K9 => "°",
+            K0 => "±",
+            Minus => "×",
+            Q => "÷",
+            W => "©",
+            E => "®",
+            R => "™",
+            T => "µ",
+            Y => "«",
+            U => "»",
+            I => "‹",
+            O => "›",
+            P => "…",
+            Backslash => "•",
+            A => "←",
+            S => "→",
+            D => "↑",
+            F => "↓",
+            G => "♥",
+            H => "★",
+            J => "♪",
+            K => "☆",
+            L => "√",
+            Semicolon => "∞",
+            Quote => "≈",
+            Z => "≠",
+            X => "≤",
+            C => "≥",
+            V => "¼",
+            B => "½",
+            N => "¾",
+            M => "¹",
+            Comma => "²",
+            Period => "³",
+            QuestionMark => "‰",
+            ExclamationMark => "·",
+            Space => " ",
+        }
+    }
+
+    /// The key whose letters are shown in this position on `layout`.
+    fn remap(&self, layout: KeyboardLayout) -> &Self {
+        #[allow(clippy::enum_glob_use)]
+        use KeyboardKey::*;
+        match (layout, self) {
+            (KeyboardLayout::Azerty, Q) => &A,
+            (KeyboardLayout::Azerty, A) => &Q,
+            (KeyboardLayout::Azerty, W) => &Z,
+            (KeyboardLayout::Azerty, Z) => &W,
+            (KeyboardLayout::Azerty, Semicolon) => &M,
+            (KeyboardLayout::Azerty, M) => &Semicolon,
+            (KeyboardLayout::Qwertz, Y) => &Z,
+            (KeyboardLayout::Qwertz, Z) => &Y,
+            _ => self,
+        }
+    }
+
+    fn key(&self, mode: KeyboardMode, layout: KeyboardLayout) -> &'static str {
         match mode {
-            KeyboardMode::Lowercase => self.lowercase(),
-            KeyboardMode::Uppercase => self.uppercase(),
+            KeyboardMode::Lowercase
+            | KeyboardMode::Kana
+            | KeyboardMode::Pinyin
+            | KeyboardMode::T9 => self.remap(layout).lowercase(),
+            KeyboardMode::Uppercase => self.remap(layout).uppercase(),
             KeyboardMode::Symbols => self.symbol(),
+            KeyboardMode::Extended => self.extended(),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum KeyboardMode {
     Lowercase,
     Uppercase,
     Symbols,
+    Extended,
+    Kana,
+    Pinyin,
+    /// Phone style multi-tap entry on a 3x4 grid.
+    T9,
+}
+
+impl KeyboardMode {
+    fn input_method(self) -> Option<InputMethod> {
+        match self {
+            KeyboardMode::Kana => Some(InputMethod::Kana),
+            KeyboardMode::Pinyin => Some(InputMethod::Pinyin),
+            _ => None,
+        }
+    }
+
+    /// Whether the keys are letters that can have diacritics.
+    fn is_letters(self) -> bool {
+        matches!(self, KeyboardMode::Lowercase | KeyboardMode::Uppercase)
+    }
+
+    fn space_label(self, layout: KeyboardLayout) -> String {
+        match self {
+            KeyboardMode::Kana => "space (かな)".to_owned(),
+            KeyboardMode::Pinyin => "space (拼音)".to_owned(),
+            _ if self.is_letters() && layout != KeyboardLayout::Qwerty => {
+                format!("space ({})", layout.name())
+            }
+            _ => "space".to_owned(),
+        }
+    }
+}
+
+/// Accented variants of `key`, offered when it's held down.
+fn diacritics(key: &str) -> Vec<String> {
+    let variants = match key.to_lowercase().as_str() {
+        "a" => "àáâäãåæ",
+        "c" => "çćč",
+        "e" => "éèêëę",
+        "i" => "íìîï",
+        "l" => "ł",
+        "n" => "ñń",
+        "o" => "óòôöõøœ",
+        "s" => "ßśš",
+        "u" => "úùûü",
+        "y" => "ýÿ",
+        "z" => "źżž",
+        _ => "",
+    };
+    let uppercase = key.chars().any(char::is_uppercase);
+    variants
+        .chars()
+        .map(|c| {
+            if uppercase {
+                c.to_uppercase().to_string()
+            } else {
+                c.to_string()
+            }
+        })
+        .collect()
 }
 
 fn masked_value(value: &str, is_password: bool) -> String {
     if is_password {
-        "*".repeat(value.len())
+        "*".repeat(value.chars().count())
     } else {
         value.to_owned()
     }
 }
+
+/// Byte offset of the start of the word before `i`.
+fn prev_word_boundary(value: &str, i: usize) -> usize {
+    let before = value[..i].trim_end();
+    before
+        .rfind(char::is_whitespace)
+        .map_or(0, |j| j + before[j..].chars().next().unwrap().len_utf8())
+}
+
+/// Byte offset of the end of the word after `i`.
+fn next_word_boundary(value: &str, i: usize) -> usize {
+    let after = &value[i..];
+    let start = after.len() - after.trim_start().len();
+    after[start..]
+        .find(char::is_whitespace)
+        .map_or(value.len(), |j| i + start + j)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyboard(value: &str, mode: KeyboardMode) -> Keyboard {
+        Keyboard {
+            value: value.to_owned(),
+            text_cursor: value.len(),
+            selected_all: false,
+            cursor: rusttype::Point { x: 5, y: 2 },
+            mode,
+            t9: mode == KeyboardMode::T9,
+            t9_cursor: 4,
+            t9_tap: None,
+            layout: KeyboardLayout::Qwerty,
+            accents: None,
+            composition: String::new(),
+            candidate: 0,
+            is_password: false,
+            button_hints: Row::new(Point::zero(), Vec::new(), Alignment::Right, 12),
+            dirty: true,
+        }
+    }
+
+    async fn press(keyboard: &mut Keyboard, event: KeyEvent) {
+        let (tx, _rx) = tokio::sync::mpsc::channel(1);
+        keyboard
+            .handle_key_event(event, tx, &mut VecDeque::new())
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_layouts() {
+        let key = |key: KeyboardKey, mode, layout| key.key(mode, layout);
+
+        assert_eq!(
+            key(
+                KeyboardKey::Q,
+                KeyboardMode::Lowercase,
+                KeyboardLayout::Qwerty
+            ),
+            "q"
+        );
+        assert_eq!(
+            key(
+                KeyboardKey::Q,
+                KeyboardMode::Lowercase,
+                KeyboardLayout::Azerty
+            ),
+            "a"
+        );
+        assert_eq!(
+            key(
+                KeyboardKey::A,
+                KeyboardMode::Uppercase,
+                KeyboardLayout::Azerty
+            ),
+            "Q"
+        );
+        assert_eq!(
+            key(
+                KeyboardKey::W,
+                KeyboardMode::Lowercase,
+                KeyboardLayout::Azerty
+            ),
+            "z"
+        );
+        assert_eq!(
+            key(
+                KeyboardKey::Semicolon,
+                KeyboardMode::Lowercase,
+                KeyboardLayout::Azerty
+            ),
+            "m"
+        );
+        assert_eq!(
+            key(
+                KeyboardKey::Y,
+                KeyboardMode::Lowercase,
+                KeyboardLayout::Qwertz
+            ),
+            "z"
+        );
+        assert_eq!(
+            key(
+                KeyboardKey::Z,
+                KeyboardMode::Uppercase,
+                KeyboardLayout::Qwertz
+            ),
+            "Y"
+        );
+
+        // Symbols don't move with the letters
+        assert_eq!(
+            key(
+                KeyboardKey::Q,
+                KeyboardMode::Symbols,
+                KeyboardLayout::Azerty
+            ),
+            key(
+                KeyboardKey::Q,
+                KeyboardMode::Symbols,
+                KeyboardLayout::Qwerty
+            ),
+        );
+
+        assert_eq!(
+            KeyboardMode::Lowercase.space_label(KeyboardLayout::Qwerty),
+            "space"
+        );
+        assert_eq!(
+            KeyboardMode::Uppercase.space_label(KeyboardLayout::Qwertz),
+            "space (QWERTZ)"
+        );
+        assert_eq!(
+            KeyboardMode::Symbols.space_label(KeyboardLayout::Azerty),
+            "space"
+        );
+
+        assert_eq!(diacritics("e")[0], "é");
+        assert_eq!(diacritics("E")[0], "É");
+        assert!(diacritics("q").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cursor_movement() {
+        let mut keyboard = keyboard("hello world", KeyboardMode::Lowercase);
+
+        press(&mut keyboard, KeyEvent::Pressed(Key::L)).await;
+        assert_eq!(keyboard.text_cursor, 10);
+
+        // Holding jumps by words
+        press(&mut keyboard, KeyEvent::Autorepeat(Key::L)).await;
+        assert_eq!(keyboard.text_cursor, 6);
+        press(&mut keyboard, KeyEvent::Autorepeat(Key::L)).await;
+        assert_eq!(keyboard.text_cursor, 0);
+        press(&mut keyboard, KeyEvent::Autorepeat(Key::R)).await;
+        assert_eq!(keyboard.text_cursor, 5);
+
+        // Typing and deleting happen at the text cursor
+        keyboard.cursor = rusttype::Point { x: 0, y: 0 };
+        press(&mut keyboard, KeyEvent::Pressed(Key::A)).await;
+        assert_eq!(keyboard.value(), "hello1 world");
+        press(&mut keyboard, KeyEvent::Pressed(Key::Y)).await;
+        press(&mut keyboard, KeyEvent::Pressed(Key::Y)).await;
+        assert_eq!(keyboard.value(), "hell world");
+        assert_eq!(keyboard.text_cursor, 4);
+
+        // The key cursor wraps around the grid
+        press(&mut keyboard, KeyEvent::Pressed(Key::Left)).await;
+        press(&mut keyboard, KeyEvent::Pressed(Key::Up)).await;
+        assert_eq!(keyboard.cursor, rusttype::Point { x: 10, y: 4 });
+
+        // Selecting all replaces the value with the next input
+        press(&mut keyboard, KeyEvent::Pressed(Key::X)).await;
+        press(&mut keyboard, KeyEvent::Pressed(Key::A)).await;
+        assert_eq!(keyboard.value(), " ");
+    }
+
+    #[tokio::test]
+    async fn test_t9() {
+        let mut keyboard = keyboard("", KeyboardMode::T9);
+
+        // Tapping a key again cycles through its characters
+        for expected in ["j", "k", "l", "5", "j"] {
+            press(&mut keyboard, KeyEvent::Pressed(Key::A)).await;
+            assert_eq!(keyboard.value(), expected);
+        }
+
+        // Moving to another key starts a new character
+        press(&mut keyboard, KeyEvent::Pressed(Key::Right)).await;
+        press(&mut keyboard, KeyEvent::Pressed(Key::A)).await;
+        press(&mut keyboard, KeyEvent::Pressed(Key::A)).await;
+        assert_eq!(keyboard.value(), "jn");
+
+        // So does tapping the same key after the timeout
+        if let Some((_, _, at)) = &mut keyboard.t9_tap {
+            *at -= T9_TAP_TIMEOUT;
+        }
+        press(&mut keyboard, KeyEvent::Pressed(Key::A)).await;
+        assert_eq!(keyboard.value(), "jnm");
+
+        // The grid wraps around
+        press(&mut keyboard, KeyEvent::Pressed(Key::Up)).await;
+        press(&mut keyboard, KeyEvent::Pressed(Key::Up)).await;
+        assert_eq!(keyboard.t9_cursor, 11);
+        press(&mut keyboard, KeyEvent::Pressed(Key::Right)).await;
+        assert_eq!(keyboard.t9_cursor, 9);
+
+        // Backspace still works, and select switches back to the full keyboard
+        press(&mut keyboard, KeyEvent::Pressed(Key::Y)).await;
+        assert_eq!(keyboard.value(), "jn");
+        press(&mut keyboard, KeyEvent::Pressed(Key::Select)).await;
+        assert_eq!(keyboard.mode, KeyboardMode::Lowercase);
+
+        keyboard.mode = KeyboardMode::Pinyin;
+        press(&mut keyboard, KeyEvent::Pressed(Key::Select)).await;
+        assert_eq!(keyboard.mode, KeyboardMode::T9);
+    }
+}
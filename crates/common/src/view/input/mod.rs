@@ -1,6 +1,7 @@
 pub mod button;
 pub mod color_picker;
 pub mod datetime;
+mod ime;
 pub mod keyboard;
 pub mod number;
 pub mod percentage;
@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+/// Approximate rate of key autorepeat events, per second.
+const AUTOREPEAT_RATE: f32 = 30.0;
+
+/// How long scrolling coasts for after releasing a held direction at full speed.
+const COAST_DURATION: f32 = 0.4;
+
+/// Scroll acceleration for lists. Holding a direction scrolls further per autorepeat the longer
+/// it is held, and releasing it after accelerating coasts to a stop.
+#[derive(Debug, Clone, Default)]
+pub struct KineticScroll {
+    direction: isize,
+    repeats: u32,
+    /// Coasting velocity, in items per second.
+    velocity: f32,
+    /// Deceleration while coasting, in items per second squared.
+    deceleration: f32,
+    /// Fraction of an item scrolled while coasting.
+    remainder: f32,
+}
+
+impl KineticScroll {
+    /// Handles a key press in `direction` (-1 or 1). Returns how many items to move.
+    pub fn press(&mut self, direction: isize) -> isize {
+        self.stop();
+        self.direction = direction;
+        self.repeats = 0;
+        direction
+    }
+
+    /// Handles an autorepeat in `direction` (-1 or 1). Returns how many items to move.
+    pub fn repeat(&mut self, direction: isize) -> isize {
+        if direction != self.direction {
+            self.press(direction);
+        }
+        self.repeats += 1;
+        direction * self.step()
+    }
+
+    /// Handles the held direction being released, coasting if scrolling had accelerated.
+    pub fn release(&mut self) {
+        let step = self.step();
+        if step > 1 {
+            self.velocity = (self.direction * step) as f32 * AUTOREPEAT_RATE;
+            self.deceleration = self.velocity.abs() / COAST_DURATION;
+            self.remainder = 0.0;
+        }
+        self.repeats = 0;
+    }
+
+    /// Stops coasting, e.g. when the end of the list is reached.
+    pub fn stop(&mut self) {
+        self.velocity = 0.0;
+        self.remainder = 0.0;
+    }
+
+    /// Advances coasting. Returns how many items to move.
+    pub fn update(&mut self, dt: Duration) -> isize {
+        if !self.is_coasting() {
+            return 0;
+        }
+
+        let dt = dt.as_secs_f32();
+        let speed = (self.velocity.abs() - self.deceleration * dt).max(0.0);
+        let velocity = speed.copysign(self.velocity);
+        self.remainder += (self.velocity + velocity) / 2.0 * dt;
+        self.velocity = velocity;
+
+        let items = self.remainder.trunc();
+        self.remainder -= items;
+        items as isize
+    }
+
+    pub fn is_coasting(&self) -> bool {
+        self.velocity != 0.0
+    }
+
+    /// Whether the current movement has accelerated past single steps.
+    pub fn is_accelerated(&self) -> bool {
+        self.step() > 1 || self.is_coasting()
+    }
+
+    /// Items to move per autorepeat, increasing the longer a direction is held.
+    fn step(&self) -> isize {
+        match self.repeats {
+            0..10 => 1,
+            10..25 => 2,
+            25..50 => 5,
+            _ => 10,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kinetic_scroll() {
+        let mut scroll = KineticScroll::default();
+        assert_eq!(scroll.press(1), 1);
+        assert_eq!(scroll.repeat(1), 1);
+        for _ in 0..60 {
+            scroll.repeat(1);
+        }
+        assert_eq!(scroll.repeat(1), 10);
+
+        scroll.release();
+        assert!(scroll.is_coasting());
+        let mut moved = 0;
+        for _ in 0..100 {
+            moved += scroll.update(Duration::from_millis(10));
+        }
+        assert!(!scroll.is_coasting());
+        assert!(moved > 0);
+
+        assert_eq!(scroll.repeat(-1), -1);
+    }
+}
@@ -21,6 +21,10 @@ use crate::view::View;
 struct Scrolling {
     offset: usize,
     dt: Duration,
+    /// Pause before scrolling starts, from the stylesheet.
+    delay: Duration,
+    /// Time per character scrolled, from the stylesheet.
+    interval: Duration,
 }
 
 #[derive(Debug, Clone)]
@@ -47,12 +51,14 @@ impl<S> Label<S>
 where
     S: AsRef<str> + PartialEq + Send,
 {
+    /// Creates a label at `point`. In a right-to-left layout, the point and alignment are
+    /// mirrored.
     pub fn new(point: Point, text: S, alignment: Alignment, width: Option<u32>) -> Self {
         Self {
             rect: None,
-            point,
+            point: point.directional(),
             text,
-            alignment,
+            alignment: alignment.directional(),
             width,
             truncated_text: None,
             color: StylesheetColor::Foreground,
@@ -67,6 +73,8 @@ where
             self.scrolling = Some(Scrolling {
                 offset: 0,
                 dt: Duration::from_millis(0),
+                delay: SCROLL_DELAY,
+                interval: SCROLL_INTERVAL,
             });
             self.truncated_text = None;
         } else {
@@ -108,6 +116,11 @@ where
 
         self.dirty = true;
 
+        if let Some(scrolling) = self.scrolling.as_mut() {
+            scrolling.delay = Duration::from_millis(styles.marquee_delay as u64);
+            scrolling.interval = Duration::from_secs(1) / styles.marquee_speed.max(1);
+        }
+
         let text_style = FontTextStyleBuilder::<Color>::new(styles.ui_font.font())
             .font_fallback(styles.cjk_font.font())
             .font_size((styles.ui_font.size as f32 * self.font_size) as u32)
@@ -206,8 +219,8 @@ where
         scrolling.dt += dt;
 
         let offset = scrolling.offset;
-        while scrolling.dt > SCROLL_DELAY {
-            scrolling.dt -= SCROLL_INTERVAL;
+        while scrolling.dt > scrolling.delay {
+            scrolling.dt = scrolling.dt.saturating_sub(scrolling.interval);
             scrolling.offset += 1;
         }
 
@@ -299,7 +312,37 @@ where
     }
 
     fn set_position(&mut self, point: Point) {
-        self.point = point;
+        self.point = point.directional();
         self.dirty = true;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::geom;
+
+    use super::*;
+
+    #[test]
+    fn test_right_to_left() {
+        geom::set_right_to_left(true, 640);
+
+        let mut label = Label::new(Point::new(12, 8), "text", Alignment::Left, None);
+        assert_eq!(label.point, Point::new(628, 8));
+        assert_eq!(label.alignment, Alignment::Right);
+
+        // Moving the label mirrors the new position too
+        label.set_position(Point::new(40, 20));
+        assert_eq!(label.point, Point::new(600, 20));
+        assert_eq!(label.alignment, Alignment::Right);
+
+        // Containers that lay out in screen coordinates have already mirrored them
+        geom::without_mirroring(|| label.set_position(Point::new(40, 20)));
+        assert_eq!(label.point, Point::new(40, 20));
+        assert_eq!(Point::new(40, 20).directional(), Point::new(600, 20));
+
+        geom::set_right_to_left(false, 640);
+        label.set_position(Point::new(40, 20));
+        assert_eq!(label.point, Point::new(40, 20));
+    }
+}
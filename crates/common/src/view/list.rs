@@ -2,15 +2,13 @@ use std::collections::VecDeque;
 
 use anyhow::Result;
 use async_trait::async_trait;
-use embedded_graphics::Drawable;
-use embedded_graphics::prelude::Size;
-use embedded_graphics::primitives::{Primitive, PrimitiveStyle, Rectangle, RoundedRectangle};
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::Sender;
 
 use crate::command::Command;
 use crate::display::Display;
-use crate::geom::{Alignment, Point, Rect};
+use crate::display::highlight::draw_highlight;
+use crate::geom::{self, Alignment, Point, Rect};
 use crate::platform::{DefaultPlatform, Key, KeyEvent, Platform};
 use crate::stylesheet::Stylesheet;
 use crate::view::View;
@@ -73,7 +71,7 @@ where
             let mut y = self.rect.y + 8;
             for child in &mut self.children {
                 let rect = child.bounding_box(styles);
-                child.set_position(Point::new(self.rect.x + 12, y));
+                geom::without_mirroring(|| child.set_position(Point::new(self.rect.x + 12, y)));
                 y += rect.h as i32 + self.margin as i32 + 8;
             }
             self.has_layout = true;
@@ -87,16 +85,12 @@ where
 
             let rect = selected.bounding_box(styles);
 
-            let fill_style = PrimitiveStyle::with_fill(styles.highlight_color);
-            RoundedRectangle::with_equal_corners(
-                Rectangle::new(
-                    embedded_graphics::prelude::Point::new(rect.x - 12, rect.y - 4),
-                    Size::new(rect.w + 24, rect.h + 8),
-                ),
-                Size::new_equal(rect.h),
-            )
-            .into_styled(fill_style)
-            .draw(display)?;
+            draw_highlight(
+                display,
+                styles,
+                Rect::new(rect.x - 12, rect.y - 4, rect.w + 24, rect.h + 8),
+                styles.highlight_color,
+            )?;
 
             for child in &mut self.children.iter_mut() {
                 child.draw(display, styles)?;
@@ -1,23 +1,32 @@
+pub mod animation;
 mod battery_indicator;
 mod button_hint;
 mod button_icon;
+mod cbz_reader;
 mod clock;
 mod image;
 mod input;
+mod kinetic_scroll;
 mod label;
 mod list;
+mod notification_indicator;
 mod null;
+mod page_viewport;
+mod power_profile_indicator;
 mod row;
 mod scroll_list;
 mod settings_list;
+mod toast;
 
 use std::collections::VecDeque;
 use std::fmt;
 use std::time::Duration;
 
+pub use self::animation::{Animation, Easing};
 pub use self::battery_indicator::BatteryIndicator;
 pub use self::button_hint::ButtonHint;
 pub use self::button_icon::ButtonIcon;
+pub use self::cbz_reader::{CbzReader, FitMode};
 pub use self::clock::Clock;
 pub use self::image::{Image, ImageMode};
 pub use self::input::button::Button;
@@ -29,12 +38,17 @@ pub use self::input::percentage::Percentage;
 pub use self::input::select::Select;
 pub use self::input::text_box::TextBox;
 pub use self::input::toggle::Toggle;
+pub use self::kinetic_scroll::KineticScroll;
 pub use self::label::Label;
 pub use self::list::List;
+pub use self::notification_indicator::NotificationIndicator;
 pub use self::null::NullView;
+pub use self::page_viewport::PageViewport;
+pub use self::power_profile_indicator::PowerProfileIndicator;
 pub use self::row::Row;
 pub use self::scroll_list::ScrollList;
 pub use self::settings_list::SettingsList;
+pub use self::toast::{Toast, ToastQueue};
 
 use anyhow::Result;
 use async_trait::async_trait;
@@ -65,6 +79,21 @@ pub trait View {
     /// Sets whether the view should be drawn.
     fn set_should_draw(&mut self);
 
+    /// Returns true if the view is animating, and should be updated and drawn every frame.
+    fn is_animating(&self) -> bool {
+        self.children().iter().any(|c| c.is_animating())
+    }
+
+    /// Called when the `Locale` in the resources has been replaced. Views that hold translated
+    /// strings pull them again; by default, it is passed on to the children.
+    fn locale_changed(&mut self) -> Result<()> {
+        for child in self.children_mut() {
+            child.locale_changed()?;
+        }
+        self.set_should_draw();
+        Ok(())
+    }
+
     /// Handle a key event. Returns true if the event was consumed.
     async fn handle_key_event(
         &mut self,
@@ -121,6 +150,14 @@ impl View for Box<dyn View> {
         (**self).set_should_draw()
     }
 
+    fn is_animating(&self) -> bool {
+        (**self).is_animating()
+    }
+
+    fn locale_changed(&mut self) -> Result<()> {
+        (**self).locale_changed()
+    }
+
     /// Handle a key event. Returns true if the event was consumed.
     async fn handle_key_event(
         &mut self,
@@ -0,0 +1,109 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::sync::mpsc::Sender;
+
+use crate::constants::BATTERY_UPDATE_INTERVAL;
+use crate::display::Display;
+use crate::geom::{Alignment, Point, Rect};
+use crate::locale::Locale;
+use crate::notifications::NotificationHistory;
+use crate::platform::{DefaultPlatform, KeyEvent, Platform};
+use crate::resources::Resources;
+use crate::stylesheet::Stylesheet;
+use crate::view::{Command, Label, View};
+
+/// Shows the number of unread notifications in the status bar. Notifications are read in the
+/// notification history in settings.
+#[derive(Debug, Clone)]
+pub struct NotificationIndicator {
+    res: Resources,
+    label: Label<String>,
+    last_updated: Instant,
+}
+
+impl NotificationIndicator {
+    pub fn new(res: Resources, point: Point, alignment: Alignment) -> Self {
+        let styles = res.get::<Stylesheet>();
+        let mut label = Label::new(point, String::new(), alignment, None);
+        label.font_size(styles.status_bar_font_size);
+        drop(styles);
+
+        let mut this = Self {
+            res,
+            label,
+            last_updated: Instant::now(),
+        };
+        this.label.set_text(this.text());
+        this
+    }
+
+    fn text(&self) -> String {
+        let unread = NotificationHistory::load()
+            .map(|history| history.unread())
+            .unwrap_or_default();
+        if unread == 0 {
+            return String::new();
+        }
+        let mut map = HashMap::new();
+        map.insert("count".into(), unread.into());
+        self.res
+            .get::<Locale>()
+            .ta("status-bar-notifications", &map)
+    }
+}
+
+#[async_trait(?Send)]
+impl View for NotificationIndicator {
+    fn update(&mut self, _dt: Duration) {
+        if self.last_updated.elapsed() >= BATTERY_UPDATE_INTERVAL {
+            let text = self.text();
+            self.label.set_text(text);
+            self.last_updated = Instant::now();
+        }
+    }
+
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        display.load(self.bounding_box(styles))?;
+        self.label.draw(display, styles)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.label.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.label.set_should_draw();
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        _event: KeyEvent,
+        _commands: Sender<Command>,
+        _bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        Ok(false)
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.label]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![&mut self.label]
+    }
+
+    fn bounding_box(&mut self, styles: &Stylesheet) -> Rect {
+        self.label.bounding_box(styles)
+    }
+
+    fn set_position(&mut self, point: Point) {
+        self.label.set_position(point);
+    }
+}
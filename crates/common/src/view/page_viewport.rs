@@ -0,0 +1,241 @@
+use anyhow::Result;
+use embedded_graphics::Drawable;
+use embedded_graphics::image::ImageRaw;
+use embedded_graphics::prelude::Size;
+use embedded_graphics::primitives::{Primitive, PrimitiveStyle, RoundedRectangle};
+use embedded_graphics::text::Text;
+use image::RgbaImage;
+use image::imageops;
+
+use crate::display::color::Color;
+use crate::display::font::FontTextStyleBuilder;
+use crate::geom::{Alignment, Point, Rect};
+use crate::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use crate::stylesheet::Stylesheet;
+use crate::view::ButtonIcon;
+
+const MAX_ZOOM: u32 = 4;
+
+/// The visible part of a rendered page, which can be zoomed in and scrolled around. Used by the
+/// guide and comic readers, which render the page image themselves at the current zoom.
+#[derive(Debug)]
+pub struct PageViewport {
+    /// Area the page is drawn in.
+    rect: Rect,
+    image: Option<RgbaImage>,
+    zoom: u32,
+    /// Top left corner of the visible part of the page.
+    scroll: (u32, u32),
+    dirty: bool,
+}
+
+impl PageViewport {
+    /// Creates a viewport filling a reader's `rect`, leaving room for button hints below.
+    pub fn new(rect: Rect, styles: &Stylesheet) -> Self {
+        Self {
+            rect: Rect::new(
+                rect.x + 12,
+                rect.y + 12,
+                rect.w - 24,
+                rect.h - 12 - 8 - ButtonIcon::diameter(styles) - 8,
+            ),
+            image: None,
+            zoom: 1,
+            scroll: (0, 0),
+            dirty: true,
+        }
+    }
+
+    /// Area the page is drawn in.
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    pub fn zoom(&self) -> u32 {
+        self.zoom
+    }
+
+    pub fn has_image(&self) -> bool {
+        self.image.is_some()
+    }
+
+    /// Replaces the rendered page, keeping the scroll position within it.
+    pub fn set_image(&mut self, image: Option<RgbaImage>) {
+        self.image = image;
+        self.clamp_scroll();
+        self.dirty = true;
+    }
+
+    /// Scrolls back to the top left corner of the page.
+    pub fn reset_scroll(&mut self) {
+        self.scroll = (0, 0);
+        self.dirty = true;
+    }
+
+    /// Sets the zoom, keeping the same part of the page in view. Returns whether the zoom
+    /// changed, in which case the page needs to be rendered again.
+    pub fn set_zoom(&mut self, zoom: u32) -> bool {
+        let zoom = zoom.clamp(1, MAX_ZOOM);
+        if zoom == self.zoom {
+            return false;
+        }
+        self.scroll = (
+            self.scroll.0 * zoom / self.zoom,
+            self.scroll.1 * zoom / self.zoom,
+        );
+        self.zoom = zoom;
+        true
+    }
+
+    pub fn scroll_by(&mut self, dx: i32, dy: i32) {
+        self.scroll = (
+            self.scroll.0.saturating_add_signed(dx),
+            self.scroll.1.saturating_add_signed(dy),
+        );
+        self.clamp_scroll();
+        self.dirty = true;
+    }
+
+    fn clamp_scroll(&mut self) {
+        if let Some(image) = self.image.as_ref() {
+            self.scroll = (
+                self.scroll.0.min(image.width().saturating_sub(self.rect.w)),
+                self.scroll
+                    .1
+                    .min(image.height().saturating_sub(self.rect.h)),
+            );
+        }
+    }
+
+    /// Scrolls a quarter of the viewport with the d-pad. Returns whether the event was handled.
+    pub fn handle_key_event(&mut self, event: KeyEvent) -> bool {
+        let (dx, dy) = (self.rect.w as i32 / 4, self.rect.h as i32 / 4);
+        match event {
+            KeyEvent::Pressed(Key::Up) | KeyEvent::Autorepeat(Key::Up) => self.scroll_by(0, -dy),
+            KeyEvent::Pressed(Key::Down) | KeyEvent::Autorepeat(Key::Down) => self.scroll_by(0, dy),
+            KeyEvent::Pressed(Key::Left) | KeyEvent::Autorepeat(Key::Left) => {
+                self.scroll_by(-dx, 0)
+            }
+            KeyEvent::Pressed(Key::Right) | KeyEvent::Autorepeat(Key::Right) => {
+                self.scroll_by(dx, 0)
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Draws the visible part of the page with `text` in the bottom right corner, or `text`
+    /// centered if there is no page.
+    pub fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+        text: &str,
+    ) -> Result<bool> {
+        if !self.dirty {
+            return Ok(false);
+        }
+
+        let viewport = self.rect;
+        RoundedRectangle::with_equal_corners(viewport.into(), Size::new_equal(8))
+            .into_styled(PrimitiveStyle::with_fill(styles.background_color))
+            .draw(display)?;
+
+        let text_style = FontTextStyleBuilder::new(styles.guide_font.font())
+            .font_fallback(styles.cjk_font.font())
+            .font_size(styles.guide_font.size)
+            .background_color(styles.background_color)
+            .text_color(styles.foreground_color)
+            .build();
+
+        if let Some(image) = self.image.as_ref() {
+            let w = viewport.w.min(image.width());
+            let h = viewport.h.min(image.height());
+            let visible = imageops::crop_imm(image, self.scroll.0, self.scroll.1, w, h).to_image();
+            let raw: ImageRaw<'_, Color> = ImageRaw::new(&visible, w);
+            // Center pages that are smaller than the screen
+            let point = Point::new(
+                viewport.x + (viewport.w - w) as i32 / 2,
+                viewport.y + (viewport.h - h) as i32 / 2,
+            );
+            embedded_graphics::image::Image::new(&raw, point.into()).draw(display)?;
+
+            Text::with_alignment(
+                text,
+                Point::new(
+                    viewport.x + viewport.w as i32 - 4,
+                    viewport.y + viewport.h as i32 - styles.guide_font.size as i32,
+                )
+                .into(),
+                text_style,
+                Alignment::Right.into(),
+            )
+            .draw(display)?;
+        } else {
+            Text::with_alignment(
+                text,
+                Point::new(
+                    viewport.x + viewport.w as i32 / 2,
+                    viewport.y + viewport.h as i32 / 2,
+                )
+                .into(),
+                text_style,
+                Alignment::Center.into(),
+            )
+            .draw(display)?;
+        }
+
+        self.dirty = false;
+        Ok(true)
+    }
+
+    pub fn should_draw(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn set_should_draw(&mut self) {
+        self.dirty = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scroll_and_zoom() {
+        let mut viewport = PageViewport {
+            rect: Rect::new(0, 0, 100, 100),
+            image: None,
+            zoom: 1,
+            scroll: (0, 0),
+            dirty: false,
+        };
+
+        viewport.set_image(Some(RgbaImage::new(200, 300)));
+        viewport.scroll_by(1000, 1000);
+        assert_eq!(viewport.scroll, (100, 200));
+        viewport.scroll_by(-150, -50);
+        assert_eq!(viewport.scroll, (0, 150));
+
+        assert!(viewport.set_zoom(2));
+        assert_eq!(viewport.scroll, (0, 300));
+        assert!(!viewport.set_zoom(2));
+        assert!(viewport.set_zoom(10));
+        assert_eq!(viewport.zoom(), MAX_ZOOM);
+        assert!(viewport.set_zoom(0));
+        assert_eq!(viewport.zoom(), 1);
+
+        // A smaller page after zooming out pulls the scroll position back in
+        viewport.set_image(Some(RgbaImage::new(50, 120)));
+        assert_eq!(viewport.scroll, (0, 20));
+
+        assert!(viewport.handle_key_event(KeyEvent::Pressed(Key::Up)));
+        assert_eq!(viewport.scroll, (0, 0));
+        assert!(!viewport.handle_key_event(KeyEvent::Pressed(Key::A)));
+
+        viewport.scroll_by(0, 10);
+        viewport.reset_scroll();
+        assert_eq!(viewport.scroll, (0, 0));
+    }
+}
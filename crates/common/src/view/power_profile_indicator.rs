@@ -0,0 +1,110 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::sync::mpsc::Sender;
+
+use crate::constants::BATTERY_UPDATE_INTERVAL;
+use crate::display::Display;
+use crate::geom::{Alignment, Point, Rect};
+use crate::locale::Locale;
+use crate::performance::{PerformanceSettings, PowerProfile};
+use crate::platform::{DefaultPlatform, KeyEvent, Platform};
+use crate::resources::Resources;
+use crate::stylesheet::Stylesheet;
+use crate::view::{Command, Label, View};
+
+/// Shows the power profile in the status bar, unless it is the default balanced profile. The
+/// profile can be switched with a hotkey at any time, so it is reloaded periodically.
+#[derive(Debug, Clone)]
+pub struct PowerProfileIndicator {
+    label: Label<String>,
+    battery_saver: String,
+    performance: String,
+    last_updated: Instant,
+}
+
+impl PowerProfileIndicator {
+    pub fn new(res: Resources, point: Point, alignment: Alignment) -> Self {
+        let styles = res.get::<Stylesheet>();
+        let locale = res.get::<Locale>();
+
+        let battery_saver = locale.t("status-bar-power-profile-battery-saver");
+        let performance = locale.t("status-bar-power-profile-performance");
+
+        let mut this = Self {
+            label: Label::new(point, String::new(), alignment, None),
+            battery_saver,
+            performance,
+            last_updated: Instant::now(),
+        };
+        this.label.font_size(styles.status_bar_font_size);
+        this.label.set_text(this.text());
+        this
+    }
+
+    fn text(&self) -> String {
+        match PerformanceSettings::load()
+            .map(|s| s.profile)
+            .unwrap_or_default()
+        {
+            PowerProfile::BatterySaver => self.battery_saver.clone(),
+            PowerProfile::Balanced => String::new(),
+            PowerProfile::Performance => self.performance.clone(),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl View for PowerProfileIndicator {
+    fn update(&mut self, _dt: Duration) {
+        if self.last_updated.elapsed() >= BATTERY_UPDATE_INTERVAL {
+            let text = self.text();
+            self.label.set_text(text);
+            self.last_updated = Instant::now();
+        }
+    }
+
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        display.load(self.bounding_box(styles))?;
+        self.label.draw(display, styles)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.label.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.label.set_should_draw();
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        _event: KeyEvent,
+        _commands: Sender<Command>,
+        _bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        Ok(false)
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.label]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![&mut self.label]
+    }
+
+    fn bounding_box(&mut self, styles: &Stylesheet) -> Rect {
+        self.label.bounding_box(styles)
+    }
+
+    fn set_position(&mut self, point: Point) {
+        self.label.set_position(point);
+    }
+}
@@ -8,7 +8,7 @@ use tokio::sync::mpsc::Sender;
 
 use crate::command::Command;
 use crate::display::Display;
-use crate::geom::{Alignment, Point, Rect};
+use crate::geom::{self, Alignment, Point, Rect};
 use crate::platform::{DefaultPlatform, KeyEvent, Platform};
 use crate::stylesheet::Stylesheet;
 use crate::view::View;
@@ -31,11 +31,13 @@ impl<V> Row<V>
 where
     V: View,
 {
+    /// Creates a row at `point`. In a right-to-left layout, the point and alignment are mirrored,
+    /// so children are laid out from the other side.
     pub fn new(point: Point, children: Vec<V>, alignment: Alignment, margin: i32) -> Self {
         Self {
-            point,
+            point: point.directional(),
             children,
-            alignment,
+            alignment: alignment.directional(),
             margin,
             dirty: true,
             has_layout: false,
@@ -88,11 +90,12 @@ where
     }
 
     fn layout(&mut self, styles: &Stylesheet) {
-        match self.alignment {
+        // The row's point is already mirrored, so children are placed in screen coordinates
+        geom::without_mirroring(|| match self.alignment {
             Alignment::Left => self.layout_left(styles),
             Alignment::Center => unimplemented!("alignment should be Left or Right"),
             Alignment::Right => self.layout_right(styles),
-        }
+        });
         self.has_layout = true;
         self.set_should_draw();
     }
@@ -195,7 +198,7 @@ where
     }
 
     fn set_position(&mut self, point: Point) {
-        self.point = point;
+        self.point = point.directional();
         self.has_layout = false;
         self.set_should_draw();
     }
@@ -1,20 +1,17 @@
 use std::collections::VecDeque;
+use std::time::Duration;
 
 use anyhow::Result;
 use async_trait::async_trait;
-use embedded_graphics::Drawable;
-use embedded_graphics::prelude::Size;
-use embedded_graphics::primitives::{
-    CornerRadii, Primitive, PrimitiveStyle, Rectangle, RoundedRectangle,
-};
 
 use tokio::sync::mpsc::Sender;
 
 use crate::display::Display;
+use crate::display::highlight::{draw_highlight, draw_rounded_rect};
 use crate::geom::{Alignment, Point, Rect};
 use crate::platform::{DefaultPlatform, Key, KeyEvent, Platform};
 use crate::stylesheet::{Stylesheet, StylesheetColor};
-use crate::view::{Command, Label, View};
+use crate::view::{Command, KineticScroll, Label, View};
 
 /// A listing of selectable entries. Assumes that all entries have the same size.
 #[derive(Debug, Clone)]
@@ -24,11 +21,18 @@ pub struct ScrollList {
     items: Vec<String>,
     /// Visible entries.
     children: Vec<Label<String>>,
+    /// Secondary text of all entries, shown right-aligned. Empty if entries have none.
+    details: Vec<String>,
+    /// Secondary text of visible entries.
+    detail_labels: Vec<Label<String>>,
     alignment: Alignment,
     entry_height: u32,
     top: usize,
     selected: usize,
     background_color: Option<StylesheetColor>,
+    kinetic: KineticScroll,
+    /// Scroll every visible entry that doesn't fit, not just the selected one.
+    scroll_all: bool,
     dirty: bool,
 }
 
@@ -52,11 +56,15 @@ impl ScrollList {
             rect,
             items: Vec::new(),
             children: Vec::new(),
+            details: Vec::new(),
+            detail_labels: Vec::new(),
             alignment,
             entry_height,
             top: 0,
             selected: 0,
             background_color: None,
+            kinetic: KineticScroll::default(),
+            scroll_all: false,
             dirty: true,
         };
 
@@ -76,11 +84,32 @@ impl ScrollList {
         }
 
         self.items[index].clone_from(&item);
-        self.children[index - self.top].set_text(item);
+        if let Some(child) = index
+            .checked_sub(self.top)
+            .and_then(|i| self.children.get_mut(i))
+        {
+            child.set_text(item);
+            if self.scroll_all {
+                child.scroll(true);
+            }
+        }
         self.dirty = true;
     }
 
     pub fn set_items(&mut self, items: Vec<String>, preserve_selection: bool) {
+        self.set_items_with_details(items, Vec::new(), preserve_selection);
+    }
+
+    /// Sets the entries along with secondary text for each, e.g. the console of a game.
+    pub fn set_items_with_details(
+        &mut self,
+        items: Vec<String>,
+        details: Vec<String>,
+        preserve_selection: bool,
+    ) {
+        self.details = details;
+        self.detail_labels.clear();
+
         if items.is_empty() {
             self.items = items;
             self.children.clear();
@@ -98,6 +127,12 @@ impl ScrollList {
         };
         self.items = items;
 
+        let (width, detail_width) = if self.details.is_empty() {
+            (self.rect.w - 24, 0)
+        } else {
+            ((self.rect.w - 24) * 2 / 3, (self.rect.w - 24) / 3 - 12)
+        };
+
         self.children.clear();
         let mut y = self.rect.y + 4;
         for i in 0..self.visible_count() {
@@ -105,13 +140,26 @@ impl ScrollList {
                 Point::new(self.rect.x + 12 * self.alignment.sign(), y),
                 self.items[i].to_owned(),
                 self.alignment,
-                Some(self.rect.w - 24),
+                Some(width),
             ));
+            if !self.details.is_empty() {
+                let mut label = Label::new(
+                    Point::new(self.rect.x + self.rect.w as i32 - 12, y),
+                    String::new(),
+                    Alignment::Right,
+                    Some(detail_width),
+                );
+                label.color(StylesheetColor::Disabled);
+                self.detail_labels.push(label);
+            }
             y += self.entry_height as i32;
         }
 
         self.select(selected);
         self.update_children();
+        if self.scroll_all {
+            self.update_scrolling();
+        }
 
         self.dirty = true;
     }
@@ -121,10 +169,13 @@ impl ScrollList {
             return;
         }
 
-        self.children
-            .get_mut(self.selected - self.top)
-            .map(|v| v.scroll(false));
+        if !self.scroll_all {
+            self.children
+                .get_mut(self.selected - self.top)
+                .map(|v| v.scroll(false));
+        }
 
+        let top = self.top;
         index = index.clamp(0, self.items.len() - 1);
         if index >= self.top + self.visible_count() {
             self.top = (index - self.visible_count() + 1).min(self.items.len() - 1);
@@ -134,9 +185,13 @@ impl ScrollList {
         self.selected = index;
         self.update_children();
 
-        self.children
-            .get_mut(self.selected - self.top)
-            .map(|v| v.scroll(true));
+        if !self.scroll_all {
+            self.children
+                .get_mut(self.selected - self.top)
+                .map(|v| v.scroll(true));
+        } else if self.top != top {
+            self.update_scrolling();
+        }
 
         self.dirty = true;
     }
@@ -153,16 +208,57 @@ impl ScrollList {
         for (i, child) in self.children.iter_mut().enumerate() {
             child.set_text(self.items[self.top + i].to_owned());
         }
+        for (i, label) in self.detail_labels.iter_mut().enumerate() {
+            label.set_text(self.details.get(self.top + i).cloned().unwrap_or_default());
+        }
+    }
+
+    /// Restarts scrolling of the visible entries that should scroll.
+    fn update_scrolling(&mut self) {
+        let selected = self.selected - self.top;
+        for (i, child) in self.children.iter_mut().enumerate() {
+            child.scroll(self.scroll_all || i == selected);
+        }
+    }
+
+    /// Moves the selection by `n` items. Single steps wrap around, accelerated scrolling stops at
+    /// either end.
+    fn scroll_by(&mut self, n: isize) {
+        let len = self.items.len() as isize;
+        let index = self.selected as isize + n;
+        let index = if self.kinetic.is_accelerated() {
+            if !(0..len).contains(&index) {
+                self.kinetic.stop();
+            }
+            index.clamp(0, len - 1)
+        } else {
+            index.rem_euclid(len)
+        };
+        self.select(index as usize);
+        self.dirty = true;
     }
 }
 
 #[async_trait(?Send)]
 impl View for ScrollList {
+    fn update(&mut self, dt: Duration) {
+        let n = self.kinetic.update(dt);
+        if n != 0 && !self.items.is_empty() {
+            self.scroll_by(n);
+        }
+        self.children.iter_mut().for_each(|c| c.update(dt));
+    }
+
     fn draw(
         &mut self,
         display: &mut <DefaultPlatform as Platform>::Display,
         styles: &Stylesheet,
     ) -> Result<bool> {
+        if self.scroll_all == styles.marquee_selected_only {
+            self.scroll_all = !styles.marquee_selected_only;
+            self.update_scrolling();
+        }
+
         if self.should_draw() {
             if let Some(color) = self.background_color {
                 let mut rect = self
@@ -175,12 +271,7 @@ impl View for ScrollList {
                 rect.w += 24;
                 rect.y -= 4;
                 rect.h += 8;
-                RoundedRectangle::new(
-                    rect.into(),
-                    CornerRadii::new(Size::new_equal((styles.ui_font.size + 8) / 2)),
-                )
-                .into_styled(PrimitiveStyle::with_fill(color.to_color(styles)))
-                .draw(display)?;
+                draw_rounded_rect(display, styles, rect, color.to_color(styles))?;
             } else {
                 display.load(self.bounding_box(styles))?;
             }
@@ -188,21 +279,20 @@ impl View for ScrollList {
             if let Some(selected) = self.children.get_mut(self.selected - self.top) {
                 let rect = selected.bounding_box(styles);
 
-                let fill_style = PrimitiveStyle::with_fill(styles.highlight_color);
-                RoundedRectangle::with_equal_corners(
-                    Rectangle::new(
-                        embedded_graphics::prelude::Point::new(rect.x - 12, rect.y - 4),
-                        Size::new(rect.w + 24, rect.h + 8),
-                    ),
-                    Size::new_equal(rect.h),
-                )
-                .into_styled(fill_style)
-                .draw(display)?;
+                draw_highlight(
+                    display,
+                    styles,
+                    Rect::new(rect.x - 12, rect.y - 4, rect.w + 24, rect.h + 8),
+                    styles.highlight_color,
+                )?;
             }
 
             for child in self.children.iter_mut() {
                 child.draw(display, styles)?;
             }
+            for label in self.detail_labels.iter_mut() {
+                label.draw(display, styles)?;
+            }
 
             self.dirty = false;
 
@@ -220,7 +310,9 @@ impl View for ScrollList {
     }
 
     fn should_draw(&self) -> bool {
-        self.dirty || self.children.iter().any(|v| v.should_draw())
+        self.dirty
+            || self.children.iter().any(|v| v.should_draw())
+            || self.detail_labels.iter().any(|v| v.should_draw())
     }
 
     fn set_should_draw(&mut self) {
@@ -228,6 +320,13 @@ impl View for ScrollList {
         for entry in &mut self.children {
             entry.set_should_draw();
         }
+        for label in &mut self.detail_labels {
+            label.set_should_draw();
+        }
+    }
+
+    fn is_animating(&self) -> bool {
+        self.kinetic.is_coasting()
     }
 
     async fn handle_key_event(
@@ -238,18 +337,30 @@ impl View for ScrollList {
     ) -> Result<bool> {
         if !self.items.is_empty() {
             match event {
-                KeyEvent::Pressed(Key::Up) | KeyEvent::Autorepeat(Key::Up) => {
-                    self.select(
-                        (self.selected as isize - 1).rem_euclid(self.items.len() as isize) as usize,
-                    );
-                    self.dirty = true;
+                KeyEvent::Pressed(Key::Up) => {
+                    let n = self.kinetic.press(-1);
+                    self.scroll_by(n);
                     Ok(true)
                 }
-                KeyEvent::Pressed(Key::Down) | KeyEvent::Autorepeat(Key::Down) => {
-                    self.select((self.selected + 1).rem_euclid(self.items.len()));
-                    self.dirty = true;
+                KeyEvent::Autorepeat(Key::Up) => {
+                    let n = self.kinetic.repeat(-1);
+                    self.scroll_by(n);
+                    Ok(true)
+                }
+                KeyEvent::Pressed(Key::Down) => {
+                    let n = self.kinetic.press(1);
+                    self.scroll_by(n);
+                    Ok(true)
+                }
+                KeyEvent::Autorepeat(Key::Down) => {
+                    let n = self.kinetic.repeat(1);
+                    self.scroll_by(n);
                     Ok(true)
                 }
+                KeyEvent::Released(Key::Up | Key::Down) => {
+                    self.kinetic.release();
+                    Ok(false)
+                }
                 KeyEvent::Pressed(Key::L) | KeyEvent::Autorepeat(Key::L) => {
                     self.select(
                         (self.selected as isize - 5).clamp(0, self.items.len() as isize - 1)
@@ -271,12 +382,17 @@ impl View for ScrollList {
     }
 
     fn children(&self) -> Vec<&dyn View> {
-        self.children.iter().map(|c| c as &dyn View).collect()
+        self.children
+            .iter()
+            .chain(self.detail_labels.iter())
+            .map(|c| c as &dyn View)
+            .collect()
     }
 
     fn children_mut(&mut self) -> Vec<&mut dyn View> {
         self.children
             .iter_mut()
+            .chain(self.detail_labels.iter_mut())
             .map(|c| c as &mut dyn View)
             .collect()
     }
@@ -308,6 +424,12 @@ impl View for ScrollList {
                 point.y + 8 + i as i32 * self.entry_height as i32,
             ));
         }
+        for (i, label) in self.detail_labels.iter_mut().enumerate() {
+            label.set_position(Point::new(
+                point.x + self.rect.w as i32 - 12,
+                point.y + 8 + i as i32 * self.entry_height as i32,
+            ));
+        }
 
         self.dirty = true;
     }
@@ -1,17 +1,16 @@
 use std::collections::VecDeque;
+use std::time::Duration;
 
 use anyhow::Result;
 use async_trait::async_trait;
-use embedded_graphics::Drawable;
-use embedded_graphics::prelude::Size;
-use embedded_graphics::primitives::{Primitive, PrimitiveStyle, Rectangle, RoundedRectangle};
 use tokio::sync::mpsc::Sender;
 
 use crate::display::Display;
-use crate::geom::{Alignment, Point, Rect};
+use crate::display::highlight::{draw_highlight, draw_rounded_rect};
+use crate::geom::{self, Alignment, Point, Rect};
 use crate::platform::{DefaultPlatform, Key, KeyEvent, Platform};
 use crate::stylesheet::Stylesheet;
-use crate::view::{Command, Label, View};
+use crate::view::{Command, KineticScroll, Label, View};
 
 /// A listing of selectable entries. Assumes that all entries have the same size.
 #[derive(Debug)]
@@ -24,6 +23,7 @@ pub struct SettingsList {
     top: usize,
     selected: usize,
     focused: bool,
+    kinetic: KineticScroll,
     dirty: bool,
     has_layout: bool,
 }
@@ -44,6 +44,7 @@ impl SettingsList {
             top: 0,
             selected: 0,
             focused: false,
+            kinetic: KineticScroll::default(),
             dirty: true,
             has_layout: false,
         };
@@ -58,14 +59,17 @@ impl SettingsList {
         self.right = right;
         self.left.clear();
 
+        let x = self.label_x();
         let mut y = self.rect.y + 4;
         for i in 0..self.visible_count() {
-            self.left.push(Label::new(
-                Point::new(self.rect.x + 12, y),
+            let mut label = Label::new(
+                Point::zero(),
                 self.labels[i].to_owned(),
                 Alignment::Left,
                 Some((self.rect.w - 24) * 2 / 3),
-            ));
+            );
+            geom::without_mirroring(|| label.set_position(Point::new(x, y)));
+            self.left.push(label);
             y += self.entry_height as i32;
         }
 
@@ -80,6 +84,14 @@ impl SettingsList {
         self.dirty = true;
     }
 
+    pub fn set_left(&mut self, i: usize, left: String) {
+        if let Some(label) = i.checked_sub(self.top).and_then(|i| self.left.get_mut(i)) {
+            label.set_text(left.clone());
+        }
+        self.labels[i] = left;
+        self.dirty = true;
+    }
+
     pub fn set_right(&mut self, i: usize, right: Box<dyn View>) {
         self.right[i] = right;
         self.has_layout = false;
@@ -128,6 +140,32 @@ impl SettingsList {
             .min(self.right.len())
     }
 
+    /// Moves the selection by `n` items. Single steps wrap around, accelerated scrolling stops at
+    /// either end.
+    fn scroll_by(&mut self, n: isize) {
+        let len = self.right.len() as isize;
+        let index = self.selected as isize + n;
+        let index = if self.kinetic.is_accelerated() {
+            if !(0..len).contains(&index) {
+                self.kinetic.stop();
+            }
+            index.clamp(0, len - 1)
+        } else {
+            index.rem_euclid(len)
+        };
+        self.select(index as usize);
+        self.dirty = true;
+    }
+
+    /// Labels are mirrored within the list rather than across the screen.
+    fn label_x(&self) -> i32 {
+        if geom::right_to_left() {
+            self.rect.x + self.rect.w as i32 - 12
+        } else {
+            self.rect.x + 12
+        }
+    }
+
     fn update_children(&mut self) {
         for (i, child) in self.left.iter_mut().enumerate() {
             child.set_text(self.labels[self.top + i].to_owned());
@@ -137,6 +175,14 @@ impl SettingsList {
 
 #[async_trait(?Send)]
 impl View for SettingsList {
+    fn update(&mut self, dt: Duration) {
+        let n = self.kinetic.update(dt);
+        if n != 0 && !self.focused && !self.right.is_empty() {
+            self.scroll_by(n);
+        }
+        self.children_mut().iter_mut().for_each(|c| c.update(dt));
+    }
+
     fn draw(
         &mut self,
         display: &mut <DefaultPlatform as Platform>::Display,
@@ -146,10 +192,19 @@ impl View for SettingsList {
             if !self.has_layout {
                 for i in 0..self.visible_count() {
                     let child = &mut self.right[self.top + i];
-                    child.set_position(Point::new(
+                    let point = Point::new(
                         self.rect.x + self.rect.w as i32 - 13,
                         self.rect.y + 4 + i as i32 * self.entry_height as i32,
-                    ));
+                    );
+                    geom::without_mirroring(|| {
+                        child.set_position(point);
+                        if geom::right_to_left() {
+                            // Mirror the child within the list
+                            let rect = child.bounding_box(styles);
+                            let x = 2 * self.rect.x + self.rect.w as i32 - rect.x - rect.w as i32;
+                            child.set_position(Point::new(point.x + x - rect.x, point.y));
+                        }
+                    });
                     self.has_layout = true;
                 }
             }
@@ -170,30 +225,22 @@ impl View for SettingsList {
             // Highlight Background
             if right.w != 0 && right.h != 0 {
                 let rect = left.union(&right);
-                RoundedRectangle::with_equal_corners(
-                    Rectangle::new(
-                        embedded_graphics::prelude::Point::new(self.rect.x, rect.y - 4),
-                        Size::new(self.rect.w, rect.h + 8),
-                    ),
-                    Size::new_equal(rect.h),
-                )
-                .into_styled(PrimitiveStyle::with_fill(
+                draw_rounded_rect(
+                    display,
+                    styles,
+                    Rect::new(self.rect.x, rect.y - 4, self.rect.w, rect.h + 8),
                     styles.highlight_color.with_a(128),
-                ))
-                .draw(display)?;
+                )?;
             }
 
             // Highlight
             let rect = if self.focused { right } else { left };
-            RoundedRectangle::with_equal_corners(
-                Rectangle::new(
-                    embedded_graphics::prelude::Point::new(rect.x - 12, rect.y - 4),
-                    Size::new(rect.w + 24, rect.h + 8),
-                ),
-                Size::new_equal(rect.h),
-            )
-            .into_styled(PrimitiveStyle::with_fill(styles.highlight_color))
-            .draw(display)?;
+            draw_highlight(
+                display,
+                styles,
+                Rect::new(rect.x - 12, rect.y - 4, rect.w + 24, rect.h + 8),
+                styles.highlight_color,
+            )?;
 
             for (i, left) in self.left.iter_mut().enumerate() {
                 left.set_should_draw();
@@ -229,29 +276,26 @@ impl View for SettingsList {
             if right_rect.w != 0 && right_rect.h != 0 {
                 let rect = left_rect.union(&right_rect);
                 display.load(Rect::new(rect.x - 12, rect.y - 4, rect.w + 24, rect.h + 8))?;
-                RoundedRectangle::with_equal_corners(
-                    Rectangle::new(
-                        embedded_graphics::prelude::Point::new(self.rect.x, rect.y - 4),
-                        Size::new(self.rect.w, rect.h + 8),
-                    ),
-                    Size::new_equal(rect.h),
-                )
-                .into_styled(PrimitiveStyle::with_fill(
+                draw_rounded_rect(
+                    display,
+                    styles,
+                    Rect::new(self.rect.x, rect.y - 4, self.rect.w, rect.h + 8),
                     styles.highlight_color.with_a(128),
-                ))
-                .draw(display)?;
+                )?;
             }
 
             // Highlight
-            RoundedRectangle::with_equal_corners(
-                Rectangle::new(
-                    embedded_graphics::prelude::Point::new(right_rect.x - 12, right_rect.y - 4),
-                    Size::new(right_rect.w + 24, right_rect.h + 8),
+            draw_highlight(
+                display,
+                styles,
+                Rect::new(
+                    right_rect.x - 12,
+                    right_rect.y - 4,
+                    right_rect.w + 24,
+                    right_rect.h + 8,
                 ),
-                Size::new_equal(right_rect.h),
-            )
-            .into_styled(PrimitiveStyle::with_fill(styles.highlight_color))
-            .draw(display)?;
+                styles.highlight_color,
+            )?;
 
             left.draw(display, styles)?;
             right.draw(display, styles)?;
@@ -282,6 +326,11 @@ impl View for SettingsList {
             .take(visible_count)
             .for_each(|c| c.set_should_draw());
     }
+
+    fn is_animating(&self) -> bool {
+        self.kinetic.is_coasting() || self.children().iter().any(|c| c.is_animating())
+    }
+
     async fn handle_key_event(
         &mut self,
         event: KeyEvent,
@@ -299,7 +348,7 @@ impl View for SettingsList {
                         self.dirty = true;
                         false
                     }
-                    Command::ValueChanged(i, _) => {
+                    Command::ValueChanged(i, _) | Command::ValuePreview(i, _) => {
                         *i = self.selected;
                         true
                     }
@@ -310,18 +359,30 @@ impl View for SettingsList {
             Ok(false)
         } else if !self.left.is_empty() {
             match event {
-                KeyEvent::Pressed(Key::Up) | KeyEvent::Autorepeat(Key::Up) => {
-                    self.select(
-                        (self.selected as isize - 1).rem_euclid(self.right.len() as isize) as usize,
-                    );
-                    self.dirty = true;
+                KeyEvent::Pressed(Key::Up) => {
+                    let n = self.kinetic.press(-1);
+                    self.scroll_by(n);
                     Ok(true)
                 }
-                KeyEvent::Pressed(Key::Down) | KeyEvent::Autorepeat(Key::Down) => {
-                    self.select((self.selected + 1).rem_euclid(self.right.len()));
-                    self.dirty = true;
+                KeyEvent::Autorepeat(Key::Up) => {
+                    let n = self.kinetic.repeat(-1);
+                    self.scroll_by(n);
+                    Ok(true)
+                }
+                KeyEvent::Pressed(Key::Down) => {
+                    let n = self.kinetic.press(1);
+                    self.scroll_by(n);
+                    Ok(true)
+                }
+                KeyEvent::Autorepeat(Key::Down) => {
+                    let n = self.kinetic.repeat(1);
+                    self.scroll_by(n);
                     Ok(true)
                 }
+                KeyEvent::Released(Key::Up | Key::Down) => {
+                    self.kinetic.release();
+                    Ok(false)
+                }
                 KeyEvent::Pressed(Key::L) | KeyEvent::Autorepeat(Key::L) => {
                     self.select(
                         (self.selected as isize - 5).clamp(0, self.right.len() as isize - 1)
@@ -385,12 +446,16 @@ impl View for SettingsList {
     fn set_position(&mut self, point: Point) {
         self.rect.x = point.x;
         self.rect.y = point.y;
+        let x = self.label_x();
         for (i, child) in self.left.iter_mut().enumerate() {
-            child.set_position(Point::new(
-                point.x + 12,
-                point.y + 8 + i as i32 * self.entry_height as i32,
-            ));
+            geom::without_mirroring(|| {
+                child.set_position(Point::new(
+                    x,
+                    point.y + 4 + i as i32 * self.entry_height as i32,
+                ))
+            });
         }
+        self.has_layout = false;
 
         self.dirty = true;
     }
@@ -4,13 +4,13 @@ use std::time::{Duration, Instant};
 use anyhow::Result;
 use async_trait::async_trait;
 
-use common::command::Command;
-use common::display::color::Color;
-use common::display::font::FontTextStyleBuilder;
-use common::geom::{Point, Rect};
-use common::platform::{DefaultPlatform, KeyEvent, Platform};
-use common::stylesheet::Stylesheet;
-use common::view::View;
+use crate::command::Command;
+use crate::display::color::Color;
+use crate::display::font::FontTextStyleBuilder;
+use crate::geom::{Point, Rect};
+use crate::platform::{DefaultPlatform, KeyEvent, Platform};
+use crate::stylesheet::Stylesheet;
+use crate::view::View;
 use embedded_graphics::Drawable;
 use embedded_graphics::image::ImageRaw;
 use embedded_graphics::prelude::{Dimensions, OriginDimensions, Size};
@@ -21,10 +21,16 @@ use embedded_graphics::text::{Alignment, Text};
 use image::{ImageBuffer, Rgba};
 use tokio::sync::mpsc::Sender;
 
+/// Most toasts that can wait to be shown. Older ones are dropped first.
+const MAX_PENDING_TOASTS: usize = 5;
+
 #[derive(Debug, Clone)]
 pub struct Toast {
     image: Option<ImageBuffer<Rgba<u8>, Vec<u8>>>,
     text: String,
+    /// How long the toast is shown for, or None until it is dismissed.
+    duration: Option<Duration>,
+    /// Set when the toast is first drawn, so that queued toasts are shown for their full duration.
     expires: Option<Instant>,
 }
 
@@ -33,7 +39,8 @@ impl Toast {
         Self {
             image: None,
             text,
-            expires: duration.map(|duration| Instant::now() + duration),
+            duration,
+            expires: None,
         }
     }
 
@@ -45,17 +52,78 @@ impl Toast {
         Self {
             image: Some(image),
             text,
-            expires: duration.map(|duration| Instant::now() + duration),
+            duration,
+            expires: None,
         }
     }
 
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Whether the toast stays until dismissed, e.g. to show progress.
+    pub fn is_persistent(&self) -> bool {
+        self.duration.is_none()
+    }
+
     pub fn has_expired(&self) -> bool {
-        if let Some(expires) = self.expires {
-            Instant::now() > expires
-        } else {
-            false
+        self.expires.is_some_and(|expires| Instant::now() > expires)
+    }
+}
+
+/// Shows toasts one after another, so that a toast isn't hidden by the next one before it can be
+/// read. Persistent toasts are replaced straight away, as they only show progress.
+#[derive(Debug, Clone, Default)]
+pub struct ToastQueue {
+    current: Option<Toast>,
+    pending: VecDeque<Toast>,
+}
+
+impl ToastQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a toast. Returns true if it replaced a persistent toast.
+    pub fn push(&mut self, toast: Toast) -> bool {
+        match self.current.as_ref() {
+            Some(current) if current.is_persistent() => {
+                self.current = Some(toast);
+                true
+            }
+            Some(_) => {
+                if self.pending.len() >= MAX_PENDING_TOASTS {
+                    self.pending.pop_front();
+                }
+                self.pending.push_back(toast);
+                false
+            }
+            None => {
+                self.current = Some(toast);
+                false
+            }
         }
     }
+
+    /// The toast being shown.
+    pub fn current_mut(&mut self) -> Option<&mut Toast> {
+        self.current.as_mut()
+    }
+
+    /// Hides the toast being shown, showing the next one if any.
+    pub fn dismiss(&mut self) {
+        self.current = self.pending.pop_front();
+    }
+
+    /// Hides all toasts.
+    pub fn clear(&mut self) {
+        self.current = None;
+        self.pending.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.current.is_none()
+    }
 }
 
 #[async_trait(?Send)]
@@ -65,6 +133,12 @@ impl View for Toast {
         display: &mut <DefaultPlatform as Platform>::Display,
         styles: &Stylesheet,
     ) -> Result<bool> {
+        if self.expires.is_none()
+            && let Some(duration) = self.duration
+        {
+            self.expires = Some(Instant::now() + duration);
+        }
+
         let w = display.size().width;
         let h = display.size().height;
 
@@ -100,7 +174,7 @@ impl View for Toast {
 
         let mut rect = text.bounding_box();
         if let Some(image_rect) = image_rect {
-            rect = common::geom::Rect::union(&rect.into(), &image_rect).into();
+            rect = Rect::union(&rect.into(), &image_rect).into();
         }
 
         let x = rect.top_left.x;
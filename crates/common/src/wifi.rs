@@ -1,13 +1,19 @@
 use std::fs::{self, File};
 use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::Path;
 #[cfg(feature = "miyoo")]
 use tokio::process::Command;
 
-use anyhow::Result;
+use anyhow::{Result, bail};
 use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
 
-use crate::constants::ALLIUM_WIFI_SETTINGS;
+use crate::constants::{ALLIUM_ACCESS_PIN, ALLIUM_WIFI_SETTINGS};
+
+/// Digits in the access PIN.
+const ACCESS_PIN_LENGTH: usize = 6;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WiFiSettings {
@@ -17,8 +23,26 @@ pub struct WiFiSettings {
     pub ntp: bool,
     pub web_file_browser: bool,
     pub telnet: bool,
+    #[serde(default)]
+    pub ssh: bool,
     pub ftp: bool,
     pub syncthing: bool,
+    /// Serve the web UI for managing the library from a browser.
+    #[serde(default)]
+    pub web_ui: bool,
+    /// Accept JSON-RPC remote control requests over TCP, handled by alliumd.
+    #[serde(default)]
+    pub remote_control: bool,
+    /// Mount a network share, so that games in its Roms folder appear in the Games tab.
+    #[serde(default)]
+    pub network_share: bool,
+    /// Address of the share, e.g. `smb://192.168.1.2/games` or `nfs://192.168.1.2/srv/games`.
+    #[serde(default)]
+    pub network_share_url: String,
+    #[serde(default)]
+    pub network_share_username: String,
+    #[serde(default)]
+    pub network_share_password: String,
 }
 
 impl WiFiSettings {
@@ -30,8 +54,15 @@ impl WiFiSettings {
             ntp: false,
             web_file_browser: false,
             telnet: false,
+            ssh: false,
             ftp: false,
             syncthing: false,
+            web_ui: false,
+            remote_control: false,
+            network_share: false,
+            network_share_url: String::new(),
+            network_share_username: String::new(),
+            network_share_password: String::new(),
         }
     }
 
@@ -61,6 +92,10 @@ impl WiFiSettings {
                 info!("Starting Telnet...");
                 telnet_on()?;
             }
+            if self.ssh {
+                info!("Starting SSH...");
+                ssh_on()?;
+            }
             if self.ftp {
                 info!("Starting FTP...");
                 ftp_on()?;
@@ -73,6 +108,14 @@ impl WiFiSettings {
                 info!("Starting Syncthing...");
                 syncthing_on()?;
             }
+            if self.web_ui {
+                info!("Starting Web UI...");
+                web_ui_on()?;
+            }
+            if self.network_share {
+                info!("Mounting network share...");
+                self.mount_network_share()?;
+            }
         }
         Ok(())
     }
@@ -137,12 +180,19 @@ network={{
         if self.wifi {
             wifi_on()?;
             let telnet = self.telnet;
+            let ssh = self.ssh;
             let ftp = self.ftp;
+            if self.network_share {
+                self.mount_network_share()?;
+            }
             tokio::spawn(async move {
                 if wait_for_wifi().await.is_ok() {
                     if telnet {
                         telnet_on().ok();
                     }
+                    if ssh {
+                        ssh_on().ok();
+                    }
                     if ftp {
                         ftp_on().ok();
                     }
@@ -153,9 +203,15 @@ network={{
             if self.telnet {
                 telnet_off().ok();
             }
+            if self.ssh {
+                ssh_off().ok();
+            }
             if self.ftp {
                 ftp_off().ok();
             }
+            if self.network_share {
+                network_share_unmount().ok();
+            }
         }
         Ok(())
     }
@@ -204,6 +260,16 @@ network={{
         Ok(())
     }
 
+    pub fn toggle_ssh(&mut self, enabled: bool) -> Result<()> {
+        self.ssh = enabled;
+        if self.ssh {
+            ssh_on()?;
+        } else {
+            ssh_off()?;
+        }
+        Ok(())
+    }
+
     pub fn toggle_ftp(&mut self, enabled: bool) -> Result<()> {
         self.ftp = enabled;
         if self.ftp {
@@ -223,6 +289,74 @@ network={{
         }
         Ok(())
     }
+
+    pub fn toggle_web_ui(&mut self, enabled: bool) -> Result<()> {
+        self.web_ui = enabled;
+        if self.web_ui {
+            web_ui_on()?;
+        } else {
+            web_ui_off()?;
+        }
+        Ok(())
+    }
+
+    pub fn toggle_network_share(&mut self, enabled: bool) -> Result<()> {
+        self.network_share = enabled;
+        if self.network_share {
+            self.mount_network_share()?;
+        } else {
+            network_share_unmount()?;
+        }
+        Ok(())
+    }
+
+    pub fn set_network_share_url(&mut self, url: String) -> Result<()> {
+        self.network_share_url = url;
+        self.remount_network_share()
+    }
+
+    pub fn set_network_share_username(&mut self, username: String) -> Result<()> {
+        self.network_share_username = username;
+        self.remount_network_share()
+    }
+
+    pub fn set_network_share_password(&mut self, password: String) -> Result<()> {
+        self.network_share_password = password;
+        self.remount_network_share()
+    }
+
+    fn remount_network_share(&self) -> Result<()> {
+        if self.wifi && self.network_share {
+            network_share_unmount()?;
+            self.mount_network_share()?;
+        }
+        Ok(())
+    }
+
+    fn mount_network_share(&self) -> Result<()> {
+        if self.network_share_url.is_empty() {
+            return Ok(());
+        }
+        let credentials = crate::constants::ALLIUM_NETWORK_SHARE_CREDENTIALS.as_path();
+        if self.network_share_url.starts_with("smb://") {
+            write_credentials(
+                credentials,
+                &self.network_share_username,
+                &self.network_share_password,
+            )?;
+        }
+        match mount_args(
+            &self.network_share_url,
+            credentials,
+            &crate::constants::ALLIUM_NETWORK_SHARE_ROOT,
+        ) {
+            Ok(args) => network_share_mount(args),
+            Err(e) => {
+                warn!("failed to mount network share: {}", e);
+                Ok(())
+            }
+        }
+    }
 }
 
 impl Default for WiFiSettings {
@@ -311,6 +445,46 @@ pub fn telnet_off() -> Result<()> {
     Ok(())
 }
 
+pub fn ssh_on() -> Result<()> {
+    #[cfg(feature = "miyoo")]
+    tokio::spawn(async {
+        Command::new(crate::constants::ALLIUM_SCRIPTS_DIR.join("ssh-on.sh"))
+            .spawn()
+            .map_err(|e| {
+                log::error!("failed to spawn ssh-on.sh: {}", e);
+                e
+            })
+            .unwrap()
+            .wait()
+            .await
+            .map_err(|e| {
+                log::error!("ssh-on.sh failed: {}", e);
+                e
+            })
+    });
+    Ok(())
+}
+
+pub fn ssh_off() -> Result<()> {
+    #[cfg(feature = "miyoo")]
+    tokio::spawn(async {
+        Command::new(crate::constants::ALLIUM_SCRIPTS_DIR.join("ssh-off.sh"))
+            .spawn()
+            .map_err(|e| {
+                log::error!("failed to spawn ssh-off.sh: {}", e);
+                e
+            })
+            .unwrap()
+            .wait()
+            .await
+            .map_err(|e| {
+                log::error!("ssh-off.sh failed: {}", e);
+                e
+            })
+    });
+    Ok(())
+}
+
 pub fn ftp_on() -> Result<()> {
     #[cfg(feature = "miyoo")]
     tokio::spawn(async {
@@ -470,6 +644,142 @@ pub fn syncthing_off() -> Result<()> {
     Ok(())
 }
 
+pub fn web_ui_on() -> Result<()> {
+    #[cfg(feature = "miyoo")]
+    tokio::spawn(async {
+        Command::new(crate::constants::ALLIUM_SCRIPTS_DIR.join("web-ui-on.sh"))
+            .spawn()
+            .map_err(|e| {
+                log::error!("failed to spawn web-ui-on.sh: {}", e);
+                e
+            })
+            .unwrap()
+            .wait()
+            .await
+            .map_err(|e| {
+                log::error!("web-ui-on.sh failed: {}", e);
+                e
+            })
+    });
+    Ok(())
+}
+
+pub fn web_ui_off() -> Result<()> {
+    #[cfg(feature = "miyoo")]
+    tokio::spawn(async {
+        Command::new(crate::constants::ALLIUM_SCRIPTS_DIR.join("web-ui-off.sh"))
+            .spawn()
+            .map_err(|e| {
+                log::error!("failed to spawn web-ui-off.sh: {}", e);
+                e
+            })
+            .unwrap()
+            .wait()
+            .await
+            .map_err(|e| {
+                log::error!("web-ui-off.sh failed: {}", e);
+                e
+            })
+    });
+    Ok(())
+}
+
+/// Writes the SMB username and password to a file that only its owner can read, for the
+/// `credentials=` mount option. Passing them as options would show them in `ps`.
+fn write_credentials(path: &Path, username: &str, password: &str) -> Result<()> {
+    if username.contains('\n') || password.contains('\n') {
+        bail!("network share credentials can't contain line breaks");
+    }
+    let username = if username.is_empty() {
+        "guest"
+    } else {
+        username
+    };
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options.open(path)?;
+    // The mode only applies to new files
+    #[cfg(unix)]
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    writeln!(file, "username={username}")?;
+    writeln!(file, "password={password}")?;
+    Ok(())
+}
+
+/// Arguments to `mount` for a network share address. SMB shares read the username and password
+/// from the `credentials` file.
+fn mount_args(url: &str, credentials: &Path, target: &Path) -> Result<Vec<String>> {
+    let target = target.display().to_string();
+    if let Some(share) = url.strip_prefix("smb://") {
+        // Commas separate mount options, so they're escaped by doubling them
+        let options = format!(
+            "credentials={},iocharset=utf8",
+            credentials.display().to_string().replace(',', ",,")
+        );
+        Ok(vec![
+            "-t".to_string(),
+            "cifs".to_string(),
+            format!("//{share}"),
+            target,
+            "-o".to_string(),
+            options,
+        ])
+    } else if let Some(share) = url.strip_prefix("nfs://") {
+        let Some((host, path)) = share.split_once('/') else {
+            bail!("network share address has no path: {}", url);
+        };
+        Ok(vec![
+            "-t".to_string(),
+            "nfs".to_string(),
+            format!("{host}:/{path}"),
+            target,
+            "-o".to_string(),
+            "nolock".to_string(),
+        ])
+    } else {
+        bail!("unsupported network share address: {}", url);
+    }
+}
+
+pub fn network_share_mount(args: Vec<String>) -> Result<()> {
+    #[cfg(feature = "miyoo")]
+    tokio::spawn(async move {
+        wait_for_wifi().await.ok();
+        fs::create_dir_all(crate::constants::ALLIUM_NETWORK_SHARE_ROOT.as_path())?;
+        Command::new("mount")
+            .args(&args)
+            .spawn()
+            .map_err(|e| {
+                log::error!("failed to spawn mount: {}", e);
+                e
+            })?
+            .wait()
+            .await
+            .map_err(|e| {
+                log::error!("mounting network share failed: {}", e);
+                e
+            })
+    });
+    #[cfg(not(feature = "miyoo"))]
+    drop(args);
+    Ok(())
+}
+
+pub fn network_share_unmount() -> Result<()> {
+    #[cfg(feature = "miyoo")]
+    std::process::Command::new("umount")
+        .arg("-l")
+        .arg(crate::constants::ALLIUM_NETWORK_SHARE_ROOT.as_path())
+        .status()
+        .map_err(|e| {
+            log::error!("failed to unmount network share: {}", e);
+            e
+        })?;
+    Ok(())
+}
+
 pub async fn wait_for_wifi() -> Result<()> {
     #[cfg(feature = "miyoo")]
     Command::new(crate::constants::ALLIUM_SCRIPTS_DIR.join("wait-for-wifi.sh"))
@@ -486,6 +796,35 @@ pub async fn wait_for_wifi() -> Result<()> {
     Ok(())
 }
 
+/// PIN that the web UI and the remote control API ask for, so that they can only be used by
+/// someone who can see the device's screen. It is created when first needed.
+pub fn access_pin() -> Result<String> {
+    if let Ok(pin) = fs::read_to_string(ALLIUM_ACCESS_PIN.as_path()) {
+        let pin = pin.trim();
+        if pin.len() == ACCESS_PIN_LENGTH && pin.bytes().all(|b| b.is_ascii_digit()) {
+            return Ok(pin.to_owned());
+        }
+    }
+    let pin = format!(
+        "{:0width$}",
+        rand::random_range(0..10u32.pow(ACCESS_PIN_LENGTH as u32)),
+        width = ACCESS_PIN_LENGTH
+    );
+    fs::write(ALLIUM_ACCESS_PIN.as_path(), &pin)?;
+    Ok(pin)
+}
+
+/// Compares a PIN given by a client with the access PIN, taking the same time wherever they
+/// differ.
+pub fn check_access_pin(pin: &str, given: &str) -> bool {
+    pin.len() == given.len()
+        && pin
+            .bytes()
+            .zip(given.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
 pub fn ip_address() -> Option<String> {
     #[cfg(feature = "miyoo")]
     {
@@ -511,3 +850,69 @@ pub fn ip_address() -> Option<String> {
     #[cfg(not(any(feature = "miyoo", feature = "simulator")))]
     return None;
 }
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    #[cfg(unix)]
+    use std::os::unix::fs::MetadataExt;
+
+    use super::*;
+
+    #[test]
+    fn test_check_access_pin() {
+        assert!(check_access_pin("012345", "012345"));
+        assert!(!check_access_pin("012345", "012346"));
+        assert!(!check_access_pin("012345", "01234"));
+        assert!(!check_access_pin("012345", ""));
+    }
+
+    #[test]
+    fn test_mount_args() {
+        let credentials = Path::new("/tmp/credentials");
+        let target = Path::new("/mnt/share");
+        assert_eq!(
+            mount_args("smb://nas/games", credentials, target).unwrap(),
+            [
+                "-t",
+                "cifs",
+                "//nas/games",
+                "/mnt/share",
+                "-o",
+                "credentials=/tmp/credentials,iocharset=utf8"
+            ]
+        );
+        assert_eq!(
+            mount_args("smb://nas/games", Path::new("/tmp/a,b"), target).unwrap()[5],
+            "credentials=/tmp/a,,b,iocharset=utf8"
+        );
+        assert_eq!(
+            mount_args("nfs://nas/srv/games", credentials, target).unwrap(),
+            ["-t", "nfs", "nas:/srv/games", "/mnt/share", "-o", "nolock"]
+        );
+        assert!(mount_args("nfs://nas", credentials, target).is_err());
+        assert!(mount_args("ftp://nas/games", credentials, target).is_err());
+    }
+
+    #[test]
+    fn test_write_credentials() {
+        let path = env::temp_dir().join("allium-test-share-credentials");
+
+        write_credentials(&path, "me", "a,b=c").unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "username=me\npassword=a,b=c\n"
+        );
+        #[cfg(unix)]
+        assert_eq!(fs::metadata(&path).unwrap().mode() & 0o777, 0o600);
+
+        write_credentials(&path, "", "").unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "username=guest\npassword=\n"
+        );
+        assert!(write_credentials(&path, "me", "a\nb").is_err());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
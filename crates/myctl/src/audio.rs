@@ -0,0 +1,53 @@
+use anyhow::Result;
+
+const MIN_BALANCE: i32 = -10;
+const MAX_BALANCE: i32 = 10;
+
+/// Attenuation per balance step, in dB. At full balance the other channel is muted.
+const BALANCE_STEP_DB: i32 = 6;
+
+// Real implementations for the device target (ARM)
+#[cfg(target_arch = "arm")]
+pub fn set(mono: bool, balance: i32) -> Result<()> {
+    let mut attr: ffi::MI_AUDIO_Attr_t = unsafe { std::mem::zeroed() };
+    unsafe { ffi::MI_AO_GetPubAttr(0, &mut attr) };
+
+    let sound_mode = if mono {
+        ffi::MI_AUDIO_SoundMode_e_E_MI_AUDIO_SOUND_MODE_MONO
+    } else {
+        ffi::MI_AUDIO_SoundMode_e_E_MI_AUDIO_SOUND_MODE_STEREO
+    };
+    if attr.eSoundmode != sound_mode {
+        attr.eSoundmode = sound_mode;
+        unsafe { ffi::MI_AO_SetPubAttr(0, &mut attr) };
+    }
+
+    let (left, right) = channel_gains(balance);
+    set_channel_gain(0, left);
+    set_channel_gain(1, right);
+
+    Ok(())
+}
+
+#[cfg(target_arch = "arm")]
+fn set_channel_gain(channel: i32, gain: i16) {
+    let mut param: ffi::MI_AO_ChnParam_t = unsafe { std::mem::zeroed() };
+    param.stChnGain.bEnableGainSet = true as u8;
+    param.stChnGain.s16Gain = gain;
+    unsafe { ffi::MI_AO_SetChnParam(0, channel, &mut param) };
+}
+
+// Stub implementations for host builds (e.g., CI/testing on x86_64)
+#[cfg(not(target_arch = "arm"))]
+pub fn set(_mono: bool, _balance: i32) -> Result<()> {
+    Ok(())
+}
+
+/// Gains for the left and right channels in dB, attenuating the channel opposite the balance.
+#[cfg_attr(not(target_arch = "arm"), allow(dead_code))]
+fn channel_gains(balance: i32) -> (i16, i16) {
+    let balance = balance.clamp(MIN_BALANCE, MAX_BALANCE);
+    let left = -balance.max(0) * BALANCE_STEP_DB;
+    let right = balance.min(0) * BALANCE_STEP_DB;
+    (left as i16, right as i16)
+}
@@ -0,0 +1,28 @@
+use std::fs::{self, File};
+use std::io::Write;
+
+use anyhow::{Context, Result};
+
+const DUTY_CYCLE: &str = "/sys/devices/soc0/soc/1f003400.pwm/pwm/pwmchip0/pwm0/duty_cycle";
+
+/// The backlight turns off below this duty cycle.
+const MIN_BRIGHTNESS: u8 = 3;
+
+pub fn set(brightness: u8) -> Result<()> {
+    File::create(DUTY_CYCLE)
+        .context("failed to open pwm/duty_cycle")?
+        .write_all(brightness.clamp(MIN_BRIGHTNESS, 100).to_string().as_bytes())?;
+    Ok(())
+}
+
+/// Turns the backlight off, until the brightness is set again.
+pub fn off() -> Result<()> {
+    File::create(DUTY_CYCLE)
+        .context("failed to open pwm/duty_cycle")?
+        .write_all(b"0")?;
+    Ok(())
+}
+
+pub fn get() -> Result<u8> {
+    Ok(fs::read_to_string(DUTY_CYCLE)?.trim().parse()?)
+}
@@ -1,8 +1,13 @@
+use std::path::PathBuf;
+
 use anyhow::Result;
 use clap::{Command, arg, value_parser};
 use simple_logger::SimpleLogger;
 
+mod audio;
+mod brightness;
 mod display;
+mod sound;
 mod volume;
 
 fn cli() -> Command {
@@ -18,14 +23,44 @@ fn cli() -> Command {
                     .value_parser(value_parser!(i32)),
             ),
         )
+        .subcommand(
+            Command::new("audio")
+                .arg_required_else_help(true)
+                .arg(
+                    arg!(<MONO> "Mix both channels down to mono").value_parser(value_parser!(bool)),
+                )
+                .arg(
+                    arg!(<BALANCE> "Left/right balance, from -10 to 10")
+                        .allow_negative_numbers(true)
+                        .value_parser(value_parser!(i32)),
+                ),
+        )
+        .subcommand(Command::new("brightness").arg(
+            arg!([BRIGHTNESS] "Brightness to set, from 0 to 100").value_parser(value_parser!(u8)),
+        ))
+        .subcommand(
+            Command::new("play").arg_required_else_help(true).arg(
+                arg!(<FILE> "16-bit PCM WAV file to play").value_parser(value_parser!(PathBuf)),
+            ),
+        )
+        .subcommand(
+            Command::new("sounds")
+                .about("Plays the WAV files whose paths are read from stdin, one per line"),
+        )
         .subcommand(
             Command::new("display")
                 .arg_required_else_help(true)
                 .subcommand(
                     Command::new("blank")
-                        .arg(arg!([TOGGLE] "blank or unblank").value_parser(value_parser!(bool))),
+                        .arg(arg!([BLANK] "blank or unblank").value_parser(value_parser!(bool))),
                 ),
         )
+        .subcommand(
+            Command::new("sleep")
+                .about("Turns the display and backlight off for sleep, or back on")
+                .arg_required_else_help(true)
+                .arg(arg!(<SLEEP> "sleep or wake").value_parser(value_parser!(bool))),
+        )
 }
 
 fn main() -> Result<()> {
@@ -41,6 +76,24 @@ fn main() -> Result<()> {
                 println!("{}", volume::get()?);
             }
         }
+        Some(("audio", sub_matches)) => {
+            let mono = *sub_matches.get_one::<bool>("MONO").unwrap();
+            let balance = *sub_matches.get_one::<i32>("BALANCE").unwrap();
+            audio::set(mono, balance)?;
+        }
+        Some(("brightness", sub_matches)) => {
+            if let Some(brightness) = sub_matches.get_one::<u8>("BRIGHTNESS") {
+                brightness::set(*brightness)?;
+            } else {
+                println!("{}", brightness::get()?);
+            }
+        }
+        Some(("play", sub_matches)) => {
+            sound::play(sub_matches.get_one::<PathBuf>("FILE").unwrap())?;
+        }
+        Some(("sounds", _)) => {
+            sound::serve()?;
+        }
         Some(("display", sub_matches)) => {
             if let Some(sub_matches) = sub_matches.subcommand() {
                 match sub_matches {
@@ -57,6 +110,14 @@ fn main() -> Result<()> {
                 unreachable!()
             }
         }
+        Some(("sleep", sub_matches)) => {
+            if *sub_matches.get_one::<bool>("SLEEP").unwrap() {
+                display::blank()?;
+                brightness::off()?;
+            } else {
+                display::unblank()?;
+            }
+        }
         _ => unreachable!(),
     }
 
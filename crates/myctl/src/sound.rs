@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, bail};
+
+/// 16-bit PCM samples read from a WAV file.
+#[cfg_attr(not(target_arch = "arm"), allow(dead_code))]
+struct Wav {
+    sample_rate: u32,
+    channels: u16,
+    data: Vec<u8>,
+}
+
+impl Wav {
+    fn load(path: &Path) -> Result<Self> {
+        let bytes = fs::read(path)?;
+        if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+            bail!("not a WAV file: {}", path.display());
+        }
+
+        let mut format = None;
+        let mut data = None;
+        let mut chunks = &bytes[12..];
+        while chunks.len() >= 8 {
+            let id = &chunks[0..4];
+            let len = u32::from_le_bytes(chunks[4..8].try_into()?) as usize;
+            let body = &chunks[8..chunks.len().min(8 + len)];
+            match id {
+                b"fmt " if body.len() >= 16 => {
+                    let tag = u16::from_le_bytes(body[0..2].try_into()?);
+                    let channels = u16::from_le_bytes(body[2..4].try_into()?);
+                    let sample_rate = u32::from_le_bytes(body[4..8].try_into()?);
+                    let bits = u16::from_le_bytes(body[14..16].try_into()?);
+                    if tag != 1 || bits != 16 || !(1..=2).contains(&channels) {
+                        bail!("unsupported WAV format: {}", path.display());
+                    }
+                    format = Some((sample_rate, channels));
+                }
+                b"data" => data = Some(body.to_vec()),
+                _ => {}
+            }
+            // Chunks are padded to an even length
+            chunks = &chunks[(8 + len + (len & 1)).min(chunks.len())..];
+        }
+
+        match (format, data) {
+            (Some((sample_rate, channels)), Some(data)) => Ok(Self {
+                sample_rate,
+                channels,
+                data,
+            }),
+            _ => bail!("missing WAV chunks: {}", path.display()),
+        }
+    }
+}
+
+/// Plays one WAV file.
+pub fn play(path: &Path) -> Result<()> {
+    let wav = Wav::load(path)?;
+    let mut output = Output::default();
+    output.play(&wav);
+    output.drain(&wav);
+    Ok(())
+}
+
+/// Plays the WAV files whose paths are read from stdin, one per line, until stdin is closed. The
+/// audio output is kept open in between, so that UI sounds don't set it up for every key press.
+pub fn serve() -> Result<()> {
+    let mut output = Output::default();
+    let mut sounds: HashMap<PathBuf, Wav> = HashMap::new();
+    for line in io::stdin().lock().lines() {
+        let path = PathBuf::from(line?);
+        if !sounds.contains_key(&path) {
+            match Wav::load(&path) {
+                Ok(wav) => {
+                    sounds.insert(path.clone(), wav);
+                }
+                Err(e) => {
+                    log::warn!("failed to load sound: {}", e);
+                    continue;
+                }
+            }
+        }
+        output.play(&sounds[&path]);
+    }
+    Ok(())
+}
+
+/// The audio output, opened for the format of the first sound played and reopened only if a later
+/// sound has a different format. It is closed when dropped.
+#[derive(Default)]
+#[cfg_attr(not(target_arch = "arm"), allow(dead_code))]
+struct Output {
+    format: Option<(u32, u16)>,
+}
+
+#[cfg(target_arch = "arm")]
+impl Output {
+    const SAMPLES_PER_FRAME: usize = 1024;
+
+    fn attr(sample_rate: u32, channels: u16) -> ffi::MI_AUDIO_Attr_t {
+        let mut attr: ffi::MI_AUDIO_Attr_t = unsafe { std::mem::zeroed() };
+        attr.eSamplerate = sample_rate as ffi::MI_AUDIO_SampleRate_e;
+        attr.eBitwidth = ffi::MI_AUDIO_BitWidth_e_E_MI_AUDIO_BIT_WIDTH_16;
+        attr.eWorkmode = ffi::MI_AUDIO_Mode_e_E_MI_AUDIO_MODE_I2S_MASTER;
+        attr.eSoundmode = if channels == 1 {
+            ffi::MI_AUDIO_SoundMode_e_E_MI_AUDIO_SOUND_MODE_MONO
+        } else {
+            ffi::MI_AUDIO_SoundMode_e_E_MI_AUDIO_SOUND_MODE_STEREO
+        };
+        attr.u32FrmNum = 6;
+        attr.u32PtNumPerFrm = Self::SAMPLES_PER_FRAME as u32;
+        attr.u32ChnCnt = 1;
+        attr
+    }
+
+    fn open(&mut self, sample_rate: u32, channels: u16) {
+        if self.format == Some((sample_rate, channels)) {
+            return;
+        }
+        self.close();
+        let mut attr = Self::attr(sample_rate, channels);
+        unsafe {
+            ffi::MI_AO_SetPubAttr(0, &mut attr);
+            ffi::MI_AO_Enable(0);
+            ffi::MI_AO_EnableChn(0, 0);
+        }
+        self.format = Some((sample_rate, channels));
+    }
+
+    fn close(&mut self) {
+        if self.format.take().is_some() {
+            unsafe {
+                ffi::MI_AO_DisableChn(0, 0);
+                ffi::MI_AO_Disable(0);
+            }
+        }
+    }
+
+    /// Queues the sound, returning once the last frame is queued.
+    fn play(&mut self, wav: &Wav) {
+        self.open(wav.sample_rate, wav.channels);
+        let attr = Self::attr(wav.sample_rate, wav.channels);
+        let frame_len = Self::SAMPLES_PER_FRAME * 2 * wav.channels as usize;
+        for chunk in wav.data.chunks(frame_len) {
+            let mut buf = chunk.to_vec();
+            buf.resize(frame_len, 0);
+
+            let mut frame: ffi::MI_AUDIO_Frame_t = unsafe { std::mem::zeroed() };
+            frame.eBitwidth = attr.eBitwidth;
+            frame.eSoundmode = attr.eSoundmode;
+            frame.apVirAddr[0] = buf.as_mut_ptr() as *mut _;
+            frame.u32Len = frame_len as u32;
+            unsafe { ffi::MI_AO_SendFrame(0, 0, &mut frame, -1) };
+        }
+    }
+
+    /// Waits for the queued frames of the sound to finish playing.
+    fn drain(&self, wav: &Wav) {
+        let duration = wav.data.len() as f32 / (2.0 * wav.channels as f32 * wav.sample_rate as f32);
+        std::thread::sleep(std::time::Duration::from_secs_f32(duration));
+    }
+}
+
+#[cfg(target_arch = "arm")]
+impl Drop for Output {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+// Stub implementations for host builds (e.g., CI/testing on x86_64)
+#[cfg(not(target_arch = "arm"))]
+impl Output {
+    fn play(&mut self, _wav: &Wav) {}
+
+    fn drain(&self, _wav: &Wav) {}
+}
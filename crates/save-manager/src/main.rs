@@ -0,0 +1,19 @@
+mod save_games;
+mod save_manager;
+mod view;
+
+use anyhow::Result;
+
+use common::platform::{DefaultPlatform, Platform};
+
+use crate::save_manager::SaveManager;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    common::logging::init("save-manager")?;
+
+    let platform = DefaultPlatform::new()?;
+    let mut app = SaveManager::new(platform)?;
+    app.run_event_loop().await?;
+    Ok(())
+}
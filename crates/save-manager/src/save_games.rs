@@ -0,0 +1,190 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::iter;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use common::constants::{ALLIUM_CONFIG_CORES, ALLIUM_SAVE_BACKUPS_DIR, RETROARCH_STATES_DIR};
+use common::database::{Database, Game};
+use common::retroarch::core_name;
+use common::save_backup::SaveBackup;
+use common::save_state::{self, SaveState};
+use log::warn;
+use serde::Deserialize;
+
+/// A game that has save states or save file backups.
+#[derive(Debug, Clone)]
+pub struct SaveGame {
+    pub name: String,
+    /// RetroArch core name, which is also the name of the states directory.
+    pub core_name: String,
+    /// Game file name without extension, which RetroArch names states after.
+    pub stem: String,
+    /// Canonicalized game path, if the game is in the database.
+    pub path: Option<String>,
+    /// Allium cores that may have been used to play the game.
+    pub cores: Vec<String>,
+    pub states: Vec<SaveState>,
+    pub backups: Vec<SaveBackup>,
+}
+
+impl SaveGame {
+    /// Screenshot taken by the in-game menu when saving to the slot, if there is one.
+    pub fn screenshot(&self, slot: i8) -> Option<PathBuf> {
+        let path = self.path.as_ref()?;
+        self.cores
+            .iter()
+            .map(|core| save_state::screenshot_path(path, core, slot))
+            .chain(iter::once(save_state::legacy_screenshot_path(path, slot)))
+            .find(|p| p.exists())
+    }
+
+    /// Where the in-game menu would save the screenshot for the slot.
+    pub fn new_screenshot(&self, slot: i8) -> Option<PathBuf> {
+        let path = self.path.as_ref()?;
+        let core = self.cores.first()?;
+        Some(save_state::screenshot_path(path, core, slot))
+    }
+
+    pub fn reload(&mut self) -> Result<()> {
+        self.states = SaveState::list(&self.core_name, &self.stem)?;
+        self.backups = SaveBackup::list(&self.core_name, &self.stem)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CoresConfig {
+    cores: HashMap<String, CoreConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CoreConfig {
+    retroarch: Option<String>,
+}
+
+/// Finds every game with save states or save file backups, sorted by name.
+pub fn load(database: &Database) -> Result<Vec<SaveGame>> {
+    let cores = allium_cores_by_core_name();
+
+    let mut games_by_stem: HashMap<String, Vec<Game>> = HashMap::new();
+    for game in database.select_all_games()? {
+        if let Some(stem) = game.path.file_stem() {
+            games_by_stem
+                .entry(stem.to_string_lossy().to_string())
+                .or_default()
+                .push(game);
+        }
+    }
+
+    // States are stored as `<core name>/<stem>.state*`, and backups as `<core name>/<stem>/*.srm`.
+    let mut keys = BTreeSet::new();
+    for (core_name, dir) in core_dirs(&RETROARCH_STATES_DIR)? {
+        for entry in fs::read_dir(&dir)? {
+            let name = entry?.file_name().to_string_lossy().to_string();
+            if let Some((stem, _)) = name.rsplit_once(".state") {
+                keys.insert((core_name.clone(), stem.to_string()));
+            }
+        }
+    }
+    for (core_name, dir) in core_dirs(&ALLIUM_SAVE_BACKUPS_DIR)? {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                let stem = entry.file_name().to_string_lossy().to_string();
+                keys.insert((core_name.clone(), stem));
+            }
+        }
+    }
+
+    let mut save_games = Vec::new();
+    for (core_name, stem) in keys {
+        let states = SaveState::list(&core_name, &stem)?;
+        let backups = SaveBackup::list(&core_name, &stem)?;
+        if states.is_empty() && backups.is_empty() {
+            continue;
+        }
+
+        let core_cores = cores.get(&core_name).cloned().unwrap_or_default();
+
+        let game = games_by_stem.get(&stem).and_then(|games| {
+            games
+                .iter()
+                .find(|g| g.core.as_ref().is_some_and(|c| core_cores.contains(c)))
+                .or_else(|| games.first())
+        });
+
+        let cores = match game.and_then(|g| g.core.clone()) {
+            Some(core) => vec![core],
+            None => core_cores,
+        };
+
+        save_games.push(SaveGame {
+            name: game.map_or_else(|| stem.clone(), |g| g.name.clone()),
+            path: game.and_then(|g| {
+                g.path
+                    .canonicalize()
+                    .ok()
+                    .map(|p| p.to_string_lossy().to_string())
+            }),
+            core_name,
+            stem,
+            cores,
+            states,
+            backups,
+        });
+    }
+
+    save_games.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+    Ok(save_games)
+}
+
+/// Lists the per-core subdirectories of `dir`, by core name.
+fn core_dirs(dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut dirs = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        if let Some(core_name) = path.file_name().map(|n| n.to_string_lossy().to_string()) {
+            dirs.push((core_name, path));
+        }
+    }
+    Ok(dirs)
+}
+
+/// Maps RetroArch core names to the Allium cores that use them.
+fn allium_cores_by_core_name() -> HashMap<String, Vec<String>> {
+    let config = match fs::read_to_string(ALLIUM_CONFIG_CORES.as_path())
+        .map_err(anyhow::Error::from)
+        .and_then(|s| Ok(toml::from_str::<CoresConfig>(&s)?))
+    {
+        Ok(config) => config,
+        Err(e) => {
+            warn!("failed to load cores.toml: {}", e);
+            return HashMap::new();
+        }
+    };
+
+    let mut core_names: HashMap<String, Option<String>> = HashMap::new();
+    let mut cores: HashMap<String, Vec<String>> = HashMap::new();
+    for (id, core) in config.cores {
+        let Some(libretro_core) = core.retroarch else {
+            continue;
+        };
+        let name = core_names
+            .entry(libretro_core.clone())
+            .or_insert_with(|| core_name(&libretro_core));
+        if let Some(name) = name {
+            cores.entry(name.clone()).or_default().push(id);
+        }
+    }
+    for ids in cores.values_mut() {
+        ids.sort_unstable();
+    }
+    cores
+}
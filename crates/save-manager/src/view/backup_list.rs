@@ -0,0 +1,298 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use common::command::Command;
+use common::display::Display;
+use common::geom::{Alignment, Point, Rect};
+use common::locale::Locale;
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::save_backup::SaveBackup;
+use common::stylesheet::Stylesheet;
+use common::view::{ButtonHint, ButtonIcon, Label, Row, SettingsList, View};
+use embedded_graphics::prelude::OriginDimensions;
+use log::error;
+use tokio::sync::mpsc::Sender;
+
+use crate::save_games::SaveGame;
+use crate::view::SaveGameChild;
+
+/// Lists the save file backups of a game, with restore and delete.
+#[derive(Debug)]
+pub struct BackupList {
+    rect: Rect,
+    res: Resources,
+    game: SaveGame,
+    title: Label<String>,
+    list: SettingsList,
+    button_hints: Row<ButtonHint<String>>,
+    /// Whether deleting the selected backup is waiting to be confirmed.
+    confirm_delete: bool,
+    dirty: bool,
+}
+
+impl BackupList {
+    pub fn new(rect: Rect, res: Resources, game: SaveGame) -> Self {
+        let Rect { x, y, w, h } = rect;
+
+        let styles = res.get::<Stylesheet>();
+
+        let title = Label::new(
+            Point::new(x + 12, y),
+            game.name.clone(),
+            Alignment::Left,
+            Some(w - 24),
+        );
+
+        let list = SettingsList::new(
+            Rect::new(
+                x + 12,
+                y + styles.ui_font.size as i32 + 8,
+                w - 24,
+                h - styles.ui_font.size - 8 - 8 - ButtonIcon::diameter(&styles),
+            ),
+            Vec::new(),
+            Vec::new(),
+            styles.row_height(),
+        );
+
+        let button_hints = Row::new(
+            Point::new(
+                x + w as i32 - 12,
+                y + h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            {
+                let locale = res.get::<Locale>();
+                vec![
+                    ButtonHint::new(
+                        res.clone(),
+                        Point::zero(),
+                        Key::A,
+                        locale.t("save-manager-restore"),
+                        Alignment::Right,
+                    ),
+                    ButtonHint::new(
+                        res.clone(),
+                        Point::zero(),
+                        Key::X,
+                        locale.t("save-manager-delete"),
+                        Alignment::Right,
+                    ),
+                    ButtonHint::new(
+                        res.clone(),
+                        Point::zero(),
+                        Key::B,
+                        locale.t("button-back"),
+                        Alignment::Right,
+                    ),
+                ]
+            },
+            Alignment::Right,
+            12,
+        );
+
+        drop(styles);
+
+        let mut this = Self {
+            rect,
+            res,
+            game,
+            title,
+            list,
+            button_hints,
+            confirm_delete: false,
+            dirty: true,
+        };
+
+        this.update_list();
+
+        this
+    }
+
+    fn update_list(&mut self) {
+        let selected = self.list.selected();
+        self.list.set_items(
+            self.game
+                .backups
+                .iter()
+                .map(|b| b.time.format("%Y-%m-%d %H:%M:%S").to_string())
+                .collect(),
+            self.game
+                .backups
+                .iter()
+                .map(|_| {
+                    Box::new(Label::new(
+                        Point::zero(),
+                        String::new(),
+                        Alignment::Right,
+                        None,
+                    )) as Box<dyn View>
+                })
+                .collect(),
+        );
+        if !self.game.backups.is_empty() {
+            self.list.select(selected.min(self.game.backups.len() - 1));
+        }
+    }
+
+    fn selected(&self) -> Option<&SaveBackup> {
+        self.game.backups.get(self.list.selected())
+    }
+
+    fn restore(&mut self) -> Result<()> {
+        let Some(backup) = self.selected().cloned() else {
+            return Ok(());
+        };
+        backup.restore(&self.game.core_name, &self.game.stem)?;
+
+        self.game.reload()?;
+        self.update_list();
+        Ok(())
+    }
+
+    fn set_confirm_delete(&mut self, confirm_delete: bool) {
+        self.confirm_delete = confirm_delete;
+        let text = if confirm_delete {
+            self.res.get::<Locale>().t("save-manager-confirm-delete")
+        } else {
+            String::new()
+        };
+        self.list.set_right(
+            self.list.selected(),
+            Box::new(Label::new(Point::zero(), text, Alignment::Right, None)),
+        );
+    }
+
+    fn delete(&mut self) -> Result<()> {
+        let Some(backup) = self.selected().cloned() else {
+            return Ok(());
+        };
+        backup.delete()?;
+
+        self.game.reload()?;
+        self.update_list();
+        Ok(())
+    }
+}
+
+impl SaveGameChild for BackupList {
+    fn into_game(self: Box<Self>) -> SaveGame {
+        self.game
+    }
+}
+
+#[async_trait(?Send)]
+impl View for BackupList {
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        let mut drawn = false;
+
+        if self.dirty {
+            display.load(self.rect)?;
+            self.dirty = false;
+            drawn = true;
+        }
+
+        drawn |= self.title.should_draw() && self.title.draw(display, styles)?;
+        drawn |= self.list.should_draw() && self.list.draw(display, styles)?;
+
+        if self.button_hints.should_draw() {
+            display.load(Rect::new(
+                0,
+                display.size().height as i32 - 48,
+                display.size().width,
+                48,
+            ))?;
+            self.button_hints.set_should_draw();
+            drawn |= self.button_hints.draw(display, styles)?;
+        }
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.dirty
+            || self.title.should_draw()
+            || self.list.should_draw()
+            || self.button_hints.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.dirty = true;
+        self.title.set_should_draw();
+        self.list.set_should_draw();
+        self.button_hints.set_should_draw();
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        if self.confirm_delete {
+            match event {
+                KeyEvent::Pressed(Key::A) => {
+                    self.confirm_delete = false;
+                    if let Err(e) = self.delete() {
+                        error!("failed to delete save backup: {}", e);
+                    }
+                    if self.game.backups.is_empty() {
+                        bubble.push_back(Command::CloseView);
+                    }
+                }
+                KeyEvent::Pressed(_) => self.set_confirm_delete(false),
+                _ => {}
+            }
+            return Ok(true);
+        }
+
+        match event {
+            KeyEvent::Pressed(Key::A) => {
+                let text = match self.restore() {
+                    Ok(()) => self.res.get::<Locale>().t("save-manager-restored"),
+                    Err(e) => {
+                        error!("failed to restore save backup: {}", e);
+                        self.res.get::<Locale>().t("save-manager-restore-failed")
+                    }
+                };
+                commands
+                    .send(Command::Toast(text, Some(Duration::from_secs(2))))
+                    .await?;
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::X) => {
+                if self.selected().is_some() {
+                    self.set_confirm_delete(true);
+                }
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::B) => {
+                bubble.push_back(Command::CloseView);
+                Ok(true)
+            }
+            event => self.list.handle_key_event(event, commands, bubble).await,
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.title, &self.list, &self.button_hints]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![&mut self.title, &mut self.list, &mut self.button_hints]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
@@ -0,0 +1,313 @@
+use std::collections::{HashMap, VecDeque};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use common::command::Command;
+use common::database::Database;
+use common::display::Display;
+use common::geom::{Alignment, Point, Rect};
+use common::locale::Locale;
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::stylesheet::Stylesheet;
+use common::view::{ButtonHint, ButtonIcon, Label, Row, SettingsList, View};
+use embedded_graphics::prelude::OriginDimensions;
+use tokio::sync::mpsc::Sender;
+
+use crate::save_games::{self, SaveGame};
+use crate::view::{BackupList, SaveGameChild, StateList};
+
+/// Lists the games that have save states or save file backups.
+#[derive(Debug)]
+pub struct GameList {
+    rect: Rect,
+    res: Resources,
+    games: Vec<SaveGame>,
+    /// Indices into `games` of the games shown in the current mode.
+    entries: Vec<usize>,
+    mode: Mode,
+    list: SettingsList,
+    button_hints: Row<ButtonHint<String>>,
+    child: Option<Box<dyn SaveGameChild>>,
+}
+
+impl GameList {
+    pub fn new(rect: Rect, res: Resources) -> Result<Self> {
+        let Rect { x, y, w, h } = rect;
+
+        let styles = res.get::<Stylesheet>();
+
+        let list = SettingsList::new(
+            Rect::new(x + 12, y, w - 24, h - 8 - ButtonIcon::diameter(&styles)),
+            Vec::new(),
+            Vec::new(),
+            styles.row_height(),
+        );
+
+        let button_hints = Row::new(
+            Point::new(
+                x + w as i32 - 12,
+                y + h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            {
+                let locale = res.get::<Locale>();
+                vec![
+                    ButtonHint::new(
+                        res.clone(),
+                        Point::zero(),
+                        Key::A,
+                        locale.t("button-select"),
+                        Alignment::Right,
+                    ),
+                    ButtonHint::new(
+                        res.clone(),
+                        Point::zero(),
+                        Key::B,
+                        locale.t("button-back"),
+                        Alignment::Right,
+                    ),
+                    ButtonHint::new(
+                        res.clone(),
+                        Point::zero(),
+                        Key::Y,
+                        Mode::States.button_hint(&locale),
+                        Alignment::Right,
+                    ),
+                ]
+            },
+            Alignment::Right,
+            12,
+        );
+
+        drop(styles);
+
+        let games = save_games::load(&res.get::<Database>())?;
+
+        let mut this = Self {
+            rect,
+            res,
+            games,
+            entries: Vec::new(),
+            mode: Mode::States,
+            list,
+            button_hints,
+            child: None,
+        };
+
+        this.update_list();
+
+        Ok(this)
+    }
+
+    fn update_list(&mut self) {
+        let mode = self.mode;
+        self.entries = self
+            .games
+            .iter()
+            .enumerate()
+            .filter(|(_, g)| mode.count(g) > 0)
+            .map(|(i, _)| i)
+            .collect();
+
+        let locale = self.res.get::<Locale>();
+        self.list.set_items(
+            self.entries
+                .iter()
+                .map(|&i| self.games[i].name.clone())
+                .collect(),
+            self.entries
+                .iter()
+                .map(|&i| {
+                    let mut map = HashMap::new();
+                    map.insert("count".into(), mode.count(&self.games[i]).into());
+                    Box::new(Label::new(
+                        Point::zero(),
+                        locale.ta(mode.count_key(), &map),
+                        Alignment::Right,
+                        Some(self.rect.w / 3),
+                    )) as Box<dyn View>
+                })
+                .collect(),
+        );
+    }
+
+    /// Picks up changes made in the child view.
+    fn close_child(&mut self) {
+        let Some(child) = self.child.take() else {
+            return;
+        };
+        let selected = self.list.selected();
+        let game = child.into_game();
+        if let Some(&i) = self.entries.get(selected) {
+            if game.states.is_empty() && game.backups.is_empty() {
+                self.games.remove(i);
+            } else {
+                self.games[i] = game;
+            }
+        }
+        self.update_list();
+        self.list
+            .select(selected.min(self.entries.len().saturating_sub(1)));
+        self.set_should_draw();
+    }
+}
+
+#[async_trait(?Send)]
+impl View for GameList {
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        if let Some(child) = self.child.as_mut() {
+            return Ok(child.should_draw() && child.draw(display, styles)?);
+        }
+
+        let mut drawn = false;
+
+        drawn |= self.list.should_draw() && self.list.draw(display, styles)?;
+
+        if self.button_hints.should_draw() {
+            display.load(Rect::new(
+                0,
+                display.size().height as i32 - 48,
+                display.size().width,
+                48,
+            ))?;
+            self.button_hints.set_should_draw();
+            if self.button_hints.draw(display, styles)? {
+                drawn = true;
+            }
+        }
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        if let Some(child) = self.child.as_ref() {
+            child.should_draw()
+        } else {
+            self.list.should_draw() || self.button_hints.should_draw()
+        }
+    }
+
+    fn set_should_draw(&mut self) {
+        if let Some(child) = self.child.as_mut() {
+            child.set_should_draw();
+        } else {
+            self.list.set_should_draw();
+            self.button_hints.set_should_draw();
+        }
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        if let Some(child) = self.child.as_mut() {
+            if child
+                .handle_key_event(event, commands.clone(), bubble)
+                .await?
+            {
+                let mut close = false;
+                bubble.retain(|cmd| match cmd {
+                    Command::CloseView => {
+                        close = true;
+                        false
+                    }
+                    _ => true,
+                });
+                if close {
+                    self.close_child();
+                    commands.send(Command::Redraw).await?;
+                }
+            }
+            return Ok(true);
+        }
+
+        match event {
+            KeyEvent::Pressed(Key::A) => {
+                if let Some(&i) = self.entries.get(self.list.selected()) {
+                    let game = self.games[i].clone();
+                    self.child = Some(match self.mode {
+                        Mode::States => Box::new(StateList::new(self.rect, self.res.clone(), game)),
+                        Mode::SaveFiles => {
+                            Box::new(BackupList::new(self.rect, self.res.clone(), game))
+                        }
+                    });
+                    commands.send(Command::Redraw).await?;
+                }
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::Y) => {
+                self.mode = self.mode.next();
+                self.button_hints
+                    .get_mut(2)
+                    .unwrap()
+                    .set_text(self.mode.button_hint(&self.res.get::<Locale>()));
+                self.update_list();
+                self.list.select(0);
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::B) => {
+                commands.send(Command::Exit).await?;
+                Ok(true)
+            }
+            _ => self.list.handle_key_event(event, commands, bubble).await,
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.list, &self.button_hints]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![&mut self.list, &mut self.button_hints]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Mode {
+    States,
+    SaveFiles,
+}
+
+impl Mode {
+    fn button_hint(&self, locale: &Locale) -> String {
+        match self {
+            Mode::States => locale.t("save-manager-states"),
+            Mode::SaveFiles => locale.t("save-manager-save-files"),
+        }
+    }
+
+    fn count_key(&self) -> &'static str {
+        match self {
+            Mode::States => "save-manager-state-count",
+            Mode::SaveFiles => "save-manager-backup-count",
+        }
+    }
+
+    fn count(&self, game: &SaveGame) -> usize {
+        match self {
+            Mode::States => game.states.len(),
+            Mode::SaveFiles => game.backups.len(),
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            Mode::States => Mode::SaveFiles,
+            Mode::SaveFiles => Mode::States,
+        }
+    }
+}
@@ -0,0 +1,21 @@
+mod app;
+mod backup_list;
+mod game_list;
+mod state_list;
+
+pub use app::App;
+pub use backup_list::BackupList;
+pub use game_list::GameList;
+pub use state_list::StateList;
+
+use std::fmt::Debug;
+
+use common::view::View;
+
+use crate::save_games::SaveGame;
+
+/// A view opened from the game list to manage one game.
+pub trait SaveGameChild: View + Debug {
+    /// Returns the game, with any changes made in the view.
+    fn into_game(self: Box<Self>) -> SaveGame;
+}
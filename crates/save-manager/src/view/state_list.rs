@@ -0,0 +1,496 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use common::command::Command;
+use common::constants::{ALLIUM_EXPORTS_DIR, SAVE_STATE_IMAGE_WIDTH};
+use common::display::Display;
+use common::geom::{Alignment, Point, Rect};
+use common::locale::Locale;
+use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
+use common::resources::Resources;
+use common::save_backup::SaveBackupSettings;
+use common::save_state::{AUTO_SLOT, SaveState};
+use common::stylesheet::Stylesheet;
+use common::view::{ButtonHint, ButtonIcon, Image, ImageMode, Label, Row, SettingsList, View};
+use embedded_graphics::prelude::OriginDimensions;
+use log::error;
+use tokio::sync::mpsc::Sender;
+
+use crate::save_games::SaveGame;
+use crate::view::SaveGameChild;
+
+/// Lists the save states of a game, with delete, copy, export and restoring previous versions.
+#[derive(Debug)]
+pub struct StateList {
+    rect: Rect,
+    res: Resources,
+    game: SaveGame,
+    title: Label<String>,
+    list: SettingsList,
+    image: Image,
+    button_hints: Row<ButtonHint<String>>,
+    /// Target slot while copying the selected state.
+    copy_target: Option<i8>,
+    /// Whether deleting the selected state is waiting to be confirmed.
+    confirm_delete: bool,
+    dirty: bool,
+}
+
+impl StateList {
+    pub fn new(rect: Rect, res: Resources, game: SaveGame) -> Self {
+        let Rect { x, y, w, h } = rect;
+
+        let styles = res.get::<Stylesheet>();
+
+        let title = Label::new(
+            Point::new(x + 12, y),
+            game.name.clone(),
+            Alignment::Left,
+            Some(w - 24),
+        );
+
+        let list = SettingsList::new(
+            Rect::new(
+                x + 12,
+                y + styles.ui_font.size as i32 + 8,
+                w - SAVE_STATE_IMAGE_WIDTH - 12 - 12 - 24,
+                h - styles.ui_font.size - 8 - 8 - ButtonIcon::diameter(&styles),
+            ),
+            Vec::new(),
+            Vec::new(),
+            styles.row_height(),
+        );
+
+        let mut image = Image::empty(
+            Rect::new(
+                x + w as i32 - SAVE_STATE_IMAGE_WIDTH as i32 - 24,
+                y + styles.ui_font.size as i32 + 8,
+                SAVE_STATE_IMAGE_WIDTH,
+                h - styles.ui_font.size - 8 - ButtonIcon::diameter(&styles) - 8 - 8,
+            ),
+            ImageMode::Contain,
+        );
+        image.set_border_radius(12);
+        image.set_alignment(Alignment::Right);
+
+        let button_hints = Row::new(
+            Point::new(
+                x + w as i32 - 12,
+                y + h as i32 - ButtonIcon::diameter(&styles) as i32 - 8,
+            ),
+            {
+                let locale = res.get::<Locale>();
+                vec![
+                    ButtonHint::new(
+                        res.clone(),
+                        Point::zero(),
+                        Key::A,
+                        locale.t("save-manager-copy"),
+                        Alignment::Right,
+                    ),
+                    ButtonHint::new(
+                        res.clone(),
+                        Point::zero(),
+                        Key::X,
+                        locale.t("save-manager-delete"),
+                        Alignment::Right,
+                    ),
+                    ButtonHint::new(
+                        res.clone(),
+                        Point::zero(),
+                        Key::Y,
+                        locale.t("save-manager-export"),
+                        Alignment::Right,
+                    ),
+                    ButtonHint::new(
+                        res.clone(),
+                        Point::zero(),
+                        Key::Select,
+                        locale.t("save-manager-restore-previous"),
+                        Alignment::Right,
+                    ),
+                    ButtonHint::new(
+                        res.clone(),
+                        Point::zero(),
+                        Key::B,
+                        locale.t("button-back"),
+                        Alignment::Right,
+                    ),
+                ]
+            },
+            Alignment::Right,
+            12,
+        );
+
+        drop(styles);
+
+        let mut this = Self {
+            rect,
+            res,
+            game,
+            title,
+            list,
+            image,
+            button_hints,
+            copy_target: None,
+            confirm_delete: false,
+            dirty: true,
+        };
+
+        this.update_list();
+
+        this
+    }
+
+    fn slot_label(&self, slot: i8) -> String {
+        let locale = self.res.get::<Locale>();
+        if slot == AUTO_SLOT {
+            locale.t("ingame-menu-slot-auto")
+        } else {
+            let mut map = HashMap::new();
+            map.insert("slot".into(), slot.into());
+            locale.ta("ingame-menu-slot", &map)
+        }
+    }
+
+    fn update_list(&mut self) {
+        let selected = self.list.selected();
+        self.list.set_items(
+            self.game
+                .states
+                .iter()
+                .map(|s| self.slot_label(s.slot))
+                .collect(),
+            self.game
+                .states
+                .iter()
+                .map(|s| {
+                    Box::new(Label::new(
+                        Point::zero(),
+                        self.previous_label(s),
+                        Alignment::Right,
+                        None,
+                    )) as Box<dyn View>
+                })
+                .collect(),
+        );
+        if !self.game.states.is_empty() {
+            self.list.select(selected.min(self.game.states.len() - 1));
+        }
+        self.update_image();
+    }
+
+    fn previous_label(&self, state: &SaveState) -> String {
+        match state.backup_count() {
+            0 => String::new(),
+            count => {
+                let mut map = HashMap::new();
+                map.insert("count".into(), count.into());
+                self.res
+                    .get::<Locale>()
+                    .ta("save-manager-previous-count", &map)
+            }
+        }
+    }
+
+    fn update_image(&mut self) {
+        let path = self
+            .selected()
+            .and_then(|state| self.game.screenshot(state.slot));
+        self.image.set_path(path);
+    }
+
+    fn selected(&self) -> Option<&SaveState> {
+        self.game.states.get(self.list.selected())
+    }
+
+    fn set_copy_target(&mut self, target: Option<i8>) {
+        self.copy_target = target;
+        let text = match target {
+            Some(slot) => {
+                let mut map = HashMap::new();
+                map.insert("slot".into(), self.slot_label(slot).into());
+                self.res.get::<Locale>().ta("save-manager-copy-to", &map)
+            }
+            None => self
+                .selected()
+                .map(|state| self.previous_label(state))
+                .unwrap_or_default(),
+        };
+        self.list.set_right(
+            self.list.selected(),
+            Box::new(Label::new(Point::zero(), text, Alignment::Right, None)),
+        );
+    }
+
+    fn set_confirm_delete(&mut self, confirm_delete: bool) {
+        self.confirm_delete = confirm_delete;
+        let text = if confirm_delete {
+            self.res.get::<Locale>().t("save-manager-confirm-delete")
+        } else {
+            self.selected()
+                .map(|state| self.previous_label(state))
+                .unwrap_or_default()
+        };
+        self.list.set_right(
+            self.list.selected(),
+            Box::new(Label::new(Point::zero(), text, Alignment::Right, None)),
+        );
+    }
+
+    fn copy(&mut self, target: i8) -> Result<()> {
+        let Some(state) = self.selected().cloned() else {
+            return Ok(());
+        };
+        if state.slot == target {
+            return Ok(());
+        }
+
+        let target_state = SaveState {
+            slot: target,
+            path: SaveState::path(&self.game.core_name, &self.game.stem, target),
+        };
+        target_state.backup(SaveBackupSettings::load()?.state_backups.max(0) as usize)?;
+        state.copy_to(&target_state.path)?;
+        if let Some(screenshot) = self.game.screenshot(state.slot)
+            && let Some(dest) = self.game.new_screenshot(target)
+        {
+            fs::copy(screenshot, dest)?;
+        }
+
+        self.game.reload()?;
+        self.update_list();
+        Ok(())
+    }
+
+    fn delete(&mut self) -> Result<()> {
+        let Some(state) = self.selected().cloned() else {
+            return Ok(());
+        };
+        state.delete()?;
+        if let Some(screenshot) = self.game.screenshot(state.slot) {
+            fs::remove_file(screenshot)?;
+        }
+
+        self.game.reload()?;
+        self.update_list();
+        Ok(())
+    }
+
+    fn restore_previous(&mut self) -> Result<()> {
+        let Some(state) = self.selected().cloned() else {
+            return Ok(());
+        };
+        state.restore_previous()?;
+
+        self.game.reload()?;
+        self.update_list();
+        Ok(())
+    }
+
+    /// Copies the state to the exports directory, keeping RetroArch's layout so that it can be
+    /// copied back to another device.
+    fn export(&self) -> Result<String> {
+        let Some(state) = self.selected() else {
+            return Ok(String::new());
+        };
+        let dir = ALLIUM_EXPORTS_DIR.join(&self.game.core_name);
+        let file_name = state.path.file_name().unwrap_or_default();
+        state.copy_to(&dir.join(file_name))?;
+        if let Some(screenshot) = self.game.screenshot(state.slot) {
+            let mut name = file_name.to_os_string();
+            name.push(".png");
+            fs::copy(screenshot, dir.join(name))?;
+        }
+        Ok(dir.display().to_string())
+    }
+}
+
+impl SaveGameChild for StateList {
+    fn into_game(self: Box<Self>) -> SaveGame {
+        self.game
+    }
+}
+
+#[async_trait(?Send)]
+impl View for StateList {
+    fn draw(
+        &mut self,
+        display: &mut <DefaultPlatform as Platform>::Display,
+        styles: &Stylesheet,
+    ) -> Result<bool> {
+        let mut drawn = false;
+
+        if self.dirty {
+            display.load(self.rect)?;
+            self.dirty = false;
+            drawn = true;
+        }
+
+        drawn |= self.title.should_draw() && self.title.draw(display, styles)?;
+        drawn |= self.list.should_draw() && self.list.draw(display, styles)?;
+        drawn |= self.image.should_draw() && self.image.draw(display, styles)?;
+
+        if self.button_hints.should_draw() {
+            display.load(Rect::new(
+                0,
+                display.size().height as i32 - 48,
+                display.size().width,
+                48,
+            ))?;
+            self.button_hints.set_should_draw();
+            drawn |= self.button_hints.draw(display, styles)?;
+        }
+
+        Ok(drawn)
+    }
+
+    fn should_draw(&self) -> bool {
+        self.dirty
+            || self.title.should_draw()
+            || self.list.should_draw()
+            || self.image.should_draw()
+            || self.button_hints.should_draw()
+    }
+
+    fn set_should_draw(&mut self) {
+        self.dirty = true;
+        self.title.set_should_draw();
+        self.list.set_should_draw();
+        self.image.set_should_draw();
+        self.button_hints.set_should_draw();
+    }
+
+    async fn handle_key_event(
+        &mut self,
+        event: KeyEvent,
+        commands: Sender<Command>,
+        bubble: &mut VecDeque<Command>,
+    ) -> Result<bool> {
+        if let Some(target) = self.copy_target {
+            match event {
+                KeyEvent::Pressed(Key::Left) | KeyEvent::Autorepeat(Key::Left) => {
+                    self.set_copy_target(Some((target - 1).max(AUTO_SLOT)));
+                }
+                KeyEvent::Pressed(Key::Right) | KeyEvent::Autorepeat(Key::Right) => {
+                    self.set_copy_target(Some(target.saturating_add(1)));
+                }
+                KeyEvent::Pressed(Key::A) => {
+                    self.set_copy_target(None);
+                    if let Err(e) = self.copy(target) {
+                        error!("failed to copy save state: {}", e);
+                    }
+                }
+                KeyEvent::Pressed(Key::B) => {
+                    self.set_copy_target(None);
+                }
+                _ => {}
+            }
+            return Ok(true);
+        }
+
+        if self.confirm_delete {
+            match event {
+                KeyEvent::Pressed(Key::A) => {
+                    self.confirm_delete = false;
+                    if let Err(e) = self.delete() {
+                        error!("failed to delete save state: {}", e);
+                    }
+                    if self.game.states.is_empty() {
+                        bubble.push_back(Command::CloseView);
+                    }
+                }
+                KeyEvent::Pressed(_) => self.set_confirm_delete(false),
+                _ => {}
+            }
+            return Ok(true);
+        }
+
+        match event {
+            KeyEvent::Pressed(Key::A) => {
+                if let Some(state) = self.selected() {
+                    let target = state.slot.saturating_add(1);
+                    self.set_copy_target(Some(target));
+                }
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::X) => {
+                if self.selected().is_some() {
+                    self.set_confirm_delete(true);
+                }
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::Y) => {
+                let text = match self.export() {
+                    Ok(dir) => {
+                        let mut map = HashMap::new();
+                        map.insert("path".into(), dir.into());
+                        self.res.get::<Locale>().ta("save-manager-exported", &map)
+                    }
+                    Err(e) => {
+                        error!("failed to export save state: {}", e);
+                        self.res.get::<Locale>().t("save-manager-export-failed")
+                    }
+                };
+                commands
+                    .send(Command::Toast(text, Some(Duration::from_secs(2))))
+                    .await?;
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::Select) => {
+                if self
+                    .selected()
+                    .is_none_or(|state| state.backup_count() == 0)
+                {
+                    return Ok(true);
+                }
+                let text = match self.restore_previous() {
+                    Ok(()) => self.res.get::<Locale>().t("save-manager-restored-previous"),
+                    Err(e) => {
+                        error!("failed to restore previous save state: {}", e);
+                        self.res.get::<Locale>().t("save-manager-restore-failed")
+                    }
+                };
+                commands
+                    .send(Command::Toast(text, Some(Duration::from_secs(2))))
+                    .await?;
+                Ok(true)
+            }
+            KeyEvent::Pressed(Key::B) => {
+                bubble.push_back(Command::CloseView);
+                Ok(true)
+            }
+            event => {
+                let consumed = self.list.handle_key_event(event, commands, bubble).await?;
+                if consumed {
+                    self.update_image();
+                }
+                Ok(consumed)
+            }
+        }
+    }
+
+    fn children(&self) -> Vec<&dyn View> {
+        vec![&self.title, &self.list, &self.image, &self.button_hints]
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut dyn View> {
+        vec![
+            &mut self.title,
+            &mut self.list,
+            &mut self.image,
+            &mut self.button_hints,
+        ]
+    }
+
+    fn bounding_box(&mut self, _styles: &Stylesheet) -> Rect {
+        self.rect
+    }
+
+    fn set_position(&mut self, _point: Point) {
+        unimplemented!()
+    }
+}
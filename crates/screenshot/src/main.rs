@@ -1,17 +1,36 @@
 #![warn(clippy::all, rust_2018_idioms)]
 
+use std::collections::VecDeque;
+use std::fs::File;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use clap::Parser;
+use common::display::rotation::Rotation;
 use framebuffer::Framebuffer;
-use image::{Pixel, Rgb, RgbImage, imageops};
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, DynamicImage, Frame, Pixel, Rgb, RgbImage, imageops};
+use log::{error, info};
+use nix::sys::signal::{self, SigHandler, Signal};
 use sysfs_gpio::{Direction, Pin};
 
+/// Frames per second recorded for GIFs.
+const GIF_FPS: u32 = 10;
+/// How much gameplay is kept for the next GIF.
+const GIF_DURATION: Duration = Duration::from_secs(5);
+/// GIF frames are recorded at a fraction of the screen resolution to save memory.
+const GIF_SCALE: usize = 2;
+
+/// Set by SIGUSR1 when recording, to save the recorded frames as a GIF.
+static SAVE_REQUESTED: AtomicBool = AtomicBool::new(false);
+
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
-    /// Path to the image to display
+    /// Path to save the image to, or the directory to save GIFs to when recording
     path: PathBuf,
 
     /// Whether to vibrate the device
@@ -27,11 +46,24 @@ struct Cli {
     /// Crop black borders
     #[arg(short, long)]
     crop: bool,
+
+    /// Keep recording the screen until terminated, saving the last few seconds as a GIF on SIGUSR1
+    #[arg(long)]
+    record: bool,
+
+    /// Name to include in the file names of saved GIFs
+    #[arg(long, default_value = "Allium")]
+    name: String,
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    if cli.record {
+        common::logging::init("screenshot")?;
+        return record(&cli.path, &cli.name);
+    }
+
     if cli.rumble {
         rumble(1)?;
     }
@@ -54,23 +86,7 @@ fn screenshot(
     crop: bool,
 ) -> Result<()> {
     let fb = Framebuffer::new("/dev/fb0")?;
-
-    let x0 = fb.var_screen_info.xoffset as usize;
-    let y0 = fb.var_screen_info.yoffset as usize;
-    let w = fb.var_screen_info.xres as usize;
-    let h = fb.var_screen_info.yres as usize;
-    let bpp = fb.var_screen_info.bits_per_pixel as usize / 8;
-
-    let mut image = image::RgbImage::new(w as u32, h as u32);
-    let frame = fb.read_frame();
-
-    for y in 0..h {
-        for x in 0..w {
-            let i = ((y0 + y) * w + (x0 + x)) * bpp;
-            let pixel = Rgb([frame[i + 2], frame[i + 1], frame[i]]);
-            image.put_pixel((w - x - 1) as u32, (h - y - 1) as u32, pixel);
-        }
-    }
+    let mut image = capture(&fb, Rotation::current(), 1);
 
     if crop {
         let (x, y, w, h) = dbg!(cropped_bounding_box(&image));
@@ -94,6 +110,84 @@ fn screenshot(
     Ok(())
 }
 
+/// Copies the page of the framebuffer that is being displayed, keeping every `step`th pixel.
+fn capture(fb: &Framebuffer, rotation: Rotation, step: usize) -> RgbImage {
+    let x0 = fb.var_screen_info.xoffset as usize;
+    let y0 = fb.var_screen_info.yoffset as usize;
+    let w = fb.var_screen_info.xres as usize;
+    let h = fb.var_screen_info.yres as usize;
+    let bpp = fb.var_screen_info.bits_per_pixel as usize / 8;
+
+    let (sw, sh) = ((w / step) as u32, (h / step) as u32);
+    let (image_w, image_h) = rotation.swap_size(sw, sh);
+    let mut image = RgbImage::new(image_w, image_h);
+    let frame = &fb.frame;
+
+    for y in 0..h / step {
+        for x in 0..w / step {
+            let i = ((y0 + y * step) * w + (x0 + x * step)) * bpp;
+            let pixel = Rgb([frame[i + 2], frame[i + 1], frame[i]]);
+            let (x, y) = rotation.to_display(x as i32, y as i32, sw, sh);
+            image.put_pixel(x as u32, y as u32, pixel);
+        }
+    }
+
+    image
+}
+
+extern "C" fn request_save(_: nix::libc::c_int) {
+    SAVE_REQUESTED.store(true, Ordering::Relaxed);
+}
+
+/// Records the screen into a ring buffer of the last `GIF_DURATION`, saving it to `dir` whenever
+/// SIGUSR1 is received.
+fn record(dir: &Path, name: &str) -> Result<()> {
+    // SAFETY: the handler only stores to an atomic.
+    unsafe { signal::signal(Signal::SIGUSR1, SigHandler::Handler(request_save)) }?;
+
+    let fb = Framebuffer::new("/dev/fb0")?;
+    let rotation = Rotation::current();
+    let interval = Duration::from_secs(1) / GIF_FPS;
+    let capacity = (GIF_DURATION.as_secs() as u32 * GIF_FPS) as usize;
+    let mut frames = VecDeque::with_capacity(capacity);
+
+    loop {
+        let start = Instant::now();
+
+        if frames.len() == capacity {
+            frames.pop_front();
+        }
+        frames.push_back(capture(&fb, rotation, GIF_SCALE));
+
+        if SAVE_REQUESTED.swap(false, Ordering::Relaxed) {
+            let path = dir.join(format!(
+                "{}-{}.gif",
+                chrono::Local::now().format("%Y-%m-%d_%H-%M-%S"),
+                name,
+            ));
+            info!("saving gif to {:?}", path);
+            if let Err(e) = save_gif(&path, frames.drain(..), interval) {
+                error!("failed to save gif: {}", e);
+            }
+        }
+
+        thread::sleep(interval.saturating_sub(start.elapsed()));
+    }
+}
+
+fn save_gif(path: &Path, frames: impl Iterator<Item = RgbImage>, interval: Duration) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut encoder = GifEncoder::new_with_speed(File::create(path)?, 10);
+    encoder.set_repeat(Repeat::Infinite)?;
+    let delay = Delay::from_saturating_duration(interval);
+    encoder.encode_frames(
+        frames.map(|frame| Frame::from_parts(DynamicImage::from(frame).into_rgba8(), 0, 0, delay)),
+    )?;
+    Ok(())
+}
+
 fn rumble(val: u8) -> Result<()> {
     let pin = Pin::new(48);
     pin.export()?;
@@ -4,6 +4,7 @@ use std::process;
 use anyhow::Result;
 use common::command::Command;
 use common::display::Display;
+use common::geom::{self, Rect};
 use common::locale::{Locale, LocaleSettings};
 use common::platform::{DefaultPlatform, Platform};
 use common::resources::Resources;
@@ -31,11 +32,14 @@ where
 impl App<DefaultPlatform> {
     pub async fn new(mut platform: DefaultPlatform) -> Result<Self> {
         let display = platform.display()?;
-        let rect = display.bounding_box().into();
+        let rect: Rect = display.bounding_box().into();
+
+        let locale = Locale::new(&LocaleSettings::load()?);
+        geom::set_right_to_left(locale.is_right_to_left(), rect.w);
 
         let mut res = TypeMap::new();
         res.insert(Stylesheet::load()?);
-        res.insert(Locale::new(&LocaleSettings::load()?.lang));
+        res.insert(locale);
         let res = Resources::new(res);
 
         Ok(App {
@@ -73,6 +77,7 @@ impl App<DefaultPlatform> {
                     self.handle_command(command)?;
                 }
                 event = self.platform.poll() => {
+                    let event = event.ui();
                     let mut bubble = VecDeque::new();
                     self.view.handle_key_event(event, tx.clone(), &mut bubble).await?;
                 }
@@ -85,6 +90,7 @@ impl App<DefaultPlatform> {
                     self.handle_command(command)?;
                 }
                 event = self.platform.poll() => {
+                    let event = event.ui();
                     let mut bubble = VecDeque::new();
                     self.view.handle_key_event(event, tx.clone(), &mut bubble).await?;
                 }
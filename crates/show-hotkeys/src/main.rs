@@ -8,11 +8,10 @@ use anyhow::Result;
 
 use app::App;
 use common::platform::{DefaultPlatform, Platform};
-use simple_logger::SimpleLogger;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    SimpleLogger::new().env().init().unwrap();
+    common::logging::init("show-hotkeys")?;
 
     let platform = DefaultPlatform::new()?;
     let mut app = App::new(platform).await?;
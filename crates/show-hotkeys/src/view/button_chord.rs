@@ -7,7 +7,7 @@ use common::command::Command;
 use tokio::sync::mpsc::Sender;
 
 use common::display::Display;
-use common::geom::{Alignment, Point, Rect};
+use common::geom::{self, Alignment, Point, Rect};
 use common::platform::{DefaultPlatform, Key, KeyEvent, Platform};
 use common::stylesheet::Stylesheet;
 use common::view::{ButtonIcon, Label, View};
@@ -58,11 +58,12 @@ where
     }
 
     fn layout(&mut self, styles: &Stylesheet) {
-        match self.alignment {
+        // Buttons and the label are placed in screen coordinates
+        geom::without_mirroring(|| match self.alignment {
             Alignment::Left => self.layout_left(styles),
             Alignment::Center => unimplemented!("alignment should be Left or Right"),
             Alignment::Right => self.layout_right(styles),
-        }
+        });
         self.has_layout = true;
     }
 
@@ -38,13 +38,14 @@ impl Hotkeys {
         );
         y += styles.ui_font.size as i32 + 8;
 
-        let mut global_hotkeys = Vec::with_capacity(5);
+        let mut global_hotkeys = Vec::with_capacity(6);
         let global_hotkeys_data = [
-            (Key::Power, locale.t("hotkeys-screenshot")),
+            (Key::R2, locale.t("hotkeys-screenshot")),
             (Key::Up, locale.t("hotkeys-brightness-up")),
             (Key::Down, locale.t("hotkeys-brightness-down")),
             (Key::Right, locale.t("hotkeys-volume-up")),
             (Key::Left, locale.t("hotkeys-volume-down")),
+            (Key::Select, locale.t("hotkeys-power-profile")),
         ];
         for (key, label) in global_hotkeys_data {
             global_hotkeys.push(ButtonChordHint::new(
@@ -66,10 +67,11 @@ impl Hotkeys {
         );
         y += styles.ui_font.size as i32 + 8;
 
-        let mut ingame_hotkeys = Vec::with_capacity(2);
+        let mut ingame_hotkeys = Vec::with_capacity(3);
         let ingame_hotkeys_data = [
             (Key::Start, locale.t("hotkeys-toggle-aspect-ratio")),
             (Key::X, locale.t("hotkeys-toggle-fps")),
+            (Key::L2, locale.t("hotkeys-gif-capture")),
         ];
         for (key, label) in ingame_hotkeys_data {
             ingame_hotkeys.push(ButtonChordHint::new(
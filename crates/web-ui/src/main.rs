@@ -0,0 +1,22 @@
+mod server;
+
+use anyhow::Result;
+
+/// Port to serve on, if not given as the first argument.
+const DEFAULT_PORT: u16 = 8080;
+
+/// Address to listen on, if not given as the second argument. Only the device itself can connect.
+const DEFAULT_ADDRESS: &str = "127.0.0.1";
+
+fn main() -> Result<()> {
+    common::logging::init("web-ui")?;
+
+    let port = match std::env::args().nth(1) {
+        Some(port) => port.parse()?,
+        None => DEFAULT_PORT,
+    };
+    let address = std::env::args()
+        .nth(2)
+        .unwrap_or_else(|| DEFAULT_ADDRESS.to_owned());
+    server::run(&address, port)
+}
@@ -0,0 +1,406 @@
+//! Serves the web UI and the JSON API it uses to browse the library, edit metadata, and upload
+//! games and box art.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use chrono::NaiveDate;
+use common::constants::ALLIUM_GAMES_DIR;
+use common::database::{Database, Game, NewGame};
+use common::storage;
+use common::wifi;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+type HttpResponse = Response<Cursor<Vec<u8>>>;
+
+const INDEX_HTML: &str = include_str!("index.html");
+
+const IMAGE_EXTENSIONS: [&str; 4] = ["png", "jpg", "jpeg", "gif"];
+
+/// Cookie that holds the session token once the PIN has been entered.
+const SESSION_COOKIE: &str = "allium_session";
+
+/// Most sessions kept. All are dropped when there are more, and the PIN has to be entered again.
+const MAX_SESSIONS: usize = 32;
+
+/// Delay after a wrong PIN, so that it can't be guessed quickly.
+const LOGIN_FAILURE_DELAY: Duration = Duration::from_secs(2);
+
+/// A game as listed by the API.
+#[derive(Debug, Serialize)]
+struct GameJson {
+    name: String,
+    path: PathBuf,
+    console: Option<String>,
+    has_image: bool,
+    play_count: i64,
+    /// Play time in seconds.
+    play_time: i64,
+    last_played: i64,
+    rating: Option<u8>,
+    release_date: Option<NaiveDate>,
+    developer: Option<String>,
+    publisher: Option<String>,
+    genres: Vec<String>,
+    favorite: bool,
+}
+
+impl From<Game> for GameJson {
+    fn from(game: Game) -> Self {
+        let console = storage::strip_games_dir(&game.path)
+            .and_then(|path| path.iter().next())
+            .map(|console| console.to_string_lossy().into_owned());
+        Self {
+            console,
+            has_image: game.image.as_ref().is_some_and(|image| image.is_file()),
+            play_count: game.play_count,
+            play_time: game.play_time.num_seconds(),
+            last_played: game.last_played,
+            rating: game.rating,
+            release_date: game.release_date,
+            developer: game.developer,
+            publisher: game.publisher,
+            genres: game.genres,
+            favorite: game.favorite,
+            name: game.name,
+            path: game.path,
+        }
+    }
+}
+
+/// Metadata that can be edited from the web UI.
+#[derive(Debug, Deserialize)]
+struct Metadata {
+    name: String,
+    rating: Option<u8>,
+    release_date: Option<NaiveDate>,
+    developer: Option<String>,
+    publisher: Option<String>,
+    #[serde(default)]
+    genres: Vec<String>,
+}
+
+/// Requests are only served if they are addressed to this device, don't come from another site,
+/// and, for the API, come with a session started by entering the PIN shown on the device.
+struct Auth {
+    pin: String,
+    /// Expected Host header, which also guards against DNS rebinding.
+    host: String,
+    sessions: HashSet<String>,
+}
+
+impl Auth {
+    /// Returns a response if the request is rejected.
+    fn check(&self, request: &Request) -> Option<HttpResponse> {
+        if header(request, "Host") != Some(self.host.as_str()) {
+            return Some(text(421, "wrong host"));
+        }
+        if let Some(origin) = header(request, "Origin")
+            && origin != format!("http://{}", self.host)
+        {
+            return Some(text(403, "cross-origin requests are not allowed"));
+        }
+        let path = request.url().split('?').next().unwrap_or_default();
+        if path.starts_with("/api/") && path != "/api/login" && !self.has_session(request) {
+            return Some(text(401, "PIN required"));
+        }
+        None
+    }
+
+    fn has_session(&self, request: &Request) -> bool {
+        header(request, "Cookie").is_some_and(|cookies| {
+            cookies
+                .split(';')
+                .filter_map(|cookie| cookie.trim().split_once('='))
+                .any(|(name, token)| name == SESSION_COOKIE && self.sessions.contains(token))
+        })
+    }
+
+    fn login(&mut self, request: &mut Request) -> Result<HttpResponse> {
+        let mut pin = String::new();
+        request.as_reader().take(64).read_to_string(&mut pin)?;
+        if !wifi::check_access_pin(&self.pin, pin.trim()) {
+            warn!("wrong PIN entered");
+            thread::sleep(LOGIN_FAILURE_DELAY);
+            return Ok(text(403, "wrong PIN"));
+        }
+
+        if self.sessions.len() >= MAX_SESSIONS {
+            self.sessions.clear();
+        }
+        let token = format!("{:032x}", rand::random::<u128>());
+        self.sessions.insert(token.clone());
+        let cookie = format!("{SESSION_COOKIE}={token}; Path=/; HttpOnly; SameSite=Strict");
+        Ok(text(200, "ok")
+            .with_header(Header::from_bytes(&b"Set-Cookie"[..], cookie.as_bytes()).unwrap()))
+    }
+}
+
+pub fn run(address: &str, port: u16) -> Result<()> {
+    let server = Server::http((address, port))
+        .map_err(|e| anyhow!("failed to start server on {}:{}: {}", address, port, e))?;
+    info!("serving web UI on {}:{}", address, port);
+
+    let mut auth = Auth {
+        pin: wifi::access_pin()?,
+        host: match port {
+            80 => address.to_owned(),
+            _ => format!("{address}:{port}"),
+        },
+        sessions: HashSet::new(),
+    };
+
+    for mut request in server.incoming_requests() {
+        let response = match auth.check(&request) {
+            Some(response) => Ok(response),
+            None if *request.method() == Method::Post && request.url() == "/api/login" => {
+                auth.login(&mut request)
+            }
+            None => handle(&mut request),
+        }
+        .unwrap_or_else(|e| {
+            warn!("{} {} failed: {}", request.method(), request.url(), e);
+            text(500, &e.to_string())
+        });
+        if let Err(e) = request.respond(response) {
+            warn!("failed to respond: {}", e);
+        }
+    }
+    Ok(())
+}
+
+fn handle(request: &mut Request) -> Result<HttpResponse> {
+    let method = request.method().clone();
+    let (path, query) = request.url().split_once('?').unwrap_or((request.url(), ""));
+    let path = path.to_owned();
+    let query: HashMap<String, String> = form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect();
+    debug!("{} {} {:?}", method, path, query);
+
+    let param = |key: &str| query.get(key).map(String::as_str);
+    match (method, path.as_str()) {
+        (Method::Get, "/") => Ok(content(
+            INDEX_HTML.as_bytes().to_vec(),
+            "text/html; charset=utf-8",
+        )),
+        (Method::Get, "/api/games") => games(),
+        (Method::Get, "/api/consoles") => consoles(),
+        (Method::Get, "/api/image") => match param("path") {
+            Some(path) => image(Path::new(path)),
+            None => Ok(text(400, "missing path")),
+        },
+        (Method::Post, "/api/game") => match param("path") {
+            Some(path) => {
+                let mut body = String::new();
+                request.as_reader().read_to_string(&mut body)?;
+                update_game(Path::new(path), &body)
+            }
+            None => Ok(text(400, "missing path")),
+        },
+        (Method::Post, "/api/roms") => match (param("console"), param("name")) {
+            (Some(console), Some(name)) => upload_rom(console, name, request.as_reader()),
+            _ => Ok(text(400, "missing console or name")),
+        },
+        (Method::Post, "/api/boxart") => match (param("path"), param("ext")) {
+            (Some(path), Some(ext)) => upload_boxart(Path::new(path), ext, request.as_reader()),
+            _ => Ok(text(400, "missing path or ext")),
+        },
+        _ => Ok(text(404, "not found")),
+    }
+}
+
+fn games() -> Result<HttpResponse> {
+    let games = Database::new()?
+        .select_all_games()?
+        .into_iter()
+        .map(GameJson::from)
+        .collect::<Vec<_>>();
+    json(&games)
+}
+
+/// Console folders that games can be uploaded to.
+fn consoles() -> Result<HttpResponse> {
+    let mut consoles = fs::read_dir(ALLIUM_GAMES_DIR.as_path())?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| !name.starts_with('.') && name != "Imgs")
+        .collect::<Vec<_>>();
+    consoles.sort_unstable_by_key(|name| name.to_lowercase());
+    json(&consoles)
+}
+
+fn image(path: &Path) -> Result<HttpResponse> {
+    let Some(image) = Database::new()?
+        .select_game(path)?
+        .and_then(|game| game.image)
+        .filter(|image| image.is_file())
+    else {
+        return Ok(text(404, "no image"));
+    };
+    let content_type = match image.extension().and_then(|ext| ext.to_str()) {
+        Some("png") => "image/png",
+        Some("gif") => "image/gif",
+        _ => "image/jpeg",
+    };
+    Ok(content(fs::read(image)?, content_type))
+}
+
+fn update_game(path: &Path, body: &str) -> Result<HttpResponse> {
+    let database = Database::new()?;
+    let Some(game) = database.select_game(path)? else {
+        return Ok(text(404, "no such game"));
+    };
+    let metadata: Metadata = match serde_json::from_str(body) {
+        Ok(metadata) => metadata,
+        Err(e) => return Ok(text(400, &e.to_string())),
+    };
+    let name = metadata.name.trim();
+    if name.is_empty() {
+        return Ok(text(400, "name is empty"));
+    }
+
+    info!("updating metadata of {}", path.display());
+    database.update_games(&[NewGame {
+        name: name.to_owned(),
+        path: game.path,
+        image: game.image,
+        core: game.core,
+        rating: metadata.rating,
+        release_date: metadata.release_date,
+        developer: metadata.developer.filter(|s| !s.is_empty()),
+        publisher: metadata.publisher.filter(|s| !s.is_empty()),
+        genres: metadata.genres,
+        favorite: game.favorite,
+    }])?;
+    Ok(text(200, "ok"))
+}
+
+fn upload_rom(console: &str, name: &str, body: &mut dyn Read) -> Result<HttpResponse> {
+    let (Some(console), Some(name)) = (file_name(console), file_name(name)) else {
+        return Ok(text(400, "invalid console or name"));
+    };
+    let dir = ALLIUM_GAMES_DIR.join(console);
+    if !dir.is_dir() {
+        return Ok(text(404, "no such console"));
+    }
+    let path = dir.join(name);
+    if path.exists() {
+        return Ok(text(409, "game already exists"));
+    }
+
+    info!("uploading {}", path.display());
+    write_file(&path, body)?;
+    Ok(text(200, "ok"))
+}
+
+fn upload_boxart(path: &Path, ext: &str, body: &mut dyn Read) -> Result<HttpResponse> {
+    let ext = ext.to_lowercase();
+    if !IMAGE_EXTENSIONS.contains(&ext.as_str()) {
+        return Ok(text(400, "unsupported image type"));
+    }
+    let database = Database::new()?;
+    let Some(game) = database.select_game(path)? else {
+        return Ok(text(404, "no such game"));
+    };
+    let (Some(dir), Some(stem)) = (game.path.parent(), game.path.file_stem()) else {
+        return Ok(text(400, "invalid game path"));
+    };
+
+    // Images of other types would take precedence, or be left behind
+    let image = dir.join("Imgs").join(stem);
+    for ext in IMAGE_EXTENSIONS {
+        let old = image.with_extension(ext);
+        if old.is_file() {
+            fs::remove_file(old)?;
+        }
+    }
+    let image = image.with_extension(ext);
+    fs::create_dir_all(dir.join("Imgs"))?;
+    info!("uploading box art {}", image.display());
+    write_file(&image, body)?;
+
+    database.update_games(&[NewGame {
+        name: game.name,
+        path: game.path,
+        image: Some(image),
+        core: game.core,
+        rating: game.rating,
+        release_date: game.release_date,
+        developer: game.developer,
+        publisher: game.publisher,
+        genres: game.genres,
+        favorite: game.favorite,
+    }])?;
+    Ok(text(200, "ok"))
+}
+
+/// Writes an upload to a temporary file first, so that an interrupted upload doesn't leave a
+/// partial file behind.
+fn write_file(path: &Path, body: &mut dyn Read) -> Result<()> {
+    let part = path.with_file_name(format!(
+        ".{}.part",
+        path.file_name().unwrap_or_default().to_string_lossy()
+    ));
+    let result = io::copy(body, &mut File::create(&part)?);
+    if let Err(e) = result {
+        fs::remove_file(&part).ok();
+        return Err(e.into());
+    }
+    fs::rename(&part, path)?;
+    Ok(())
+}
+
+/// Returns the name if it's a single path component, so that uploads can't escape their folder.
+fn file_name(name: &str) -> Option<&str> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && !name.contains(['/', '\\'])
+        && Path::new(name).file_name().is_some_and(|n| n == name);
+    valid.then_some(name)
+}
+
+fn header<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv(name))
+        .map(|header| header.value.as_str())
+}
+
+fn json(value: &impl Serialize) -> Result<HttpResponse> {
+    Ok(content(serde_json::to_vec(value)?, "application/json"))
+}
+
+fn text(status: u16, text: &str) -> HttpResponse {
+    content(text.as_bytes().to_vec(), "text/plain; charset=utf-8").with_status_code(status)
+}
+
+fn content(data: Vec<u8>, content_type: &str) -> HttpResponse {
+    let header = Header::from_bytes(&b"Content-Type"[..], content_type.as_bytes()).unwrap();
+    Response::from_data(data).with_header(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(file_name("Game (USA).gba"), Some("Game (USA).gba"));
+        assert_eq!(file_name(""), None);
+        assert_eq!(file_name(".."), None);
+        assert_eq!(file_name(".hidden"), None);
+        assert_eq!(file_name("../Game.gba"), None);
+        assert_eq!(file_name("GBA/Game.gba"), None);
+        assert_eq!(file_name("GBA\\Game.gba"), None);
+    }
+}
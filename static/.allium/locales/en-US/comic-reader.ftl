@@ -0,0 +1,2 @@
+comic-reader-title = Comic Reader
+comic-reader-no-comics = No comics found. Put .cbz files in the Comics folder.
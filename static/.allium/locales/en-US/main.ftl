@@ -4,11 +4,16 @@ tab-games = Games
 tab-apps = Apps
 tab-settings = Settings
 
+games-all-games = All Games
+games-by-genre = By Genre
+
 sort-alphabetical = Sort: A-Z
 sort-last-played = Sort: Recent
 sort-most-played = Sort: Playtime
 sort-rating = Sort: Rating
 sort-release-date = Sort: Release Date
+sort-genre = Sort: Genre
+sort-recently-added = Sort: Recently Added
 sort-random = Sort: Random
 sort-search = Sort: Search
 sort-favorites = Sort: Favorites
@@ -19,14 +24,30 @@ populating-database = Populating database...
     This may take several minutes.
     Go grab a coffee!
 populating-games = Populating games... ({ $directory })
+verifying-roms = Verifying ROMs...
+verify-roms-no-dats = No DAT files found. Put No-Intro .dat files in the Dats folder.
+verify-roms-summary = { $verified } verified, { $bad } bad, { $overdump } overdumped, { $unknown } unknown
 
 menu-set-as-favorite = Set as Favorite
 menu-unset-as-favorite = Remove from Favorites
 menu-launch = Launch
 menu-launch-with-core = Launch with { $core }
+menu-cpu-profile = CPU: { $profile }
 menu-reset = Reset
 menu-remove-from-recents = Remove from Recents
 menu-repopulate-database = Repopulate Database
+menu-verify-roms = Verify ROMs
+menu-surprise-me = Surprise Me
+menu-surprise-me-favorites = Surprise Me: Favorites
+menu-rename = Rename
+menu-move-to-folder = Move to Folder
+menu-delete = Delete
+menu-parent-folder = Parent Folder
+menu-confirm-rename = Rename to { $name }
+menu-confirm-move = Move to { $folder }
+menu-confirm-delete = Delete Permanently
+menu-cancel = Cancel
+surprise-me-no-games = No games found
 
 settings-wifi = Wi-Fi
 settings-wifi-wifi-enabled = Wi-Fi Enabled
@@ -36,10 +57,35 @@ settings-wifi-wifi-password = Wi-Fi Password
 settings-wifi-ntp-enabled = NTP Enabled
 settings-wifi-web-file-explorer = Web File Explorer
 settings-wifi-telnet-enabled = Telnet Enabled
+settings-wifi-ssh-enabled = SSH Enabled
+settings-wifi-ssh-login = user root, key login only. Add public keys to .allium/state/ssh/authorized_keys
 settings-wifi-ftp-enabled = FTP Enabled
+settings-wifi-ftp-login = user anonymous, no password
 settings-wifi-syncthing = Syncthing Enabled
+settings-wifi-web-ui = Web Management
+settings-wifi-web-ui-login = PIN { $pin }
+settings-wifi-remote-control = Remote Control API
+settings-wifi-remote-control-enabled = Listening for JSON-RPC on port { $port }, PIN { $pin }
+settings-wifi-network-share = Network Share
+settings-wifi-network-share-address = Share Address
+settings-wifi-network-share-username = Share Username
+settings-wifi-network-share-password = Share Password
+settings-wifi-network-share-no-address = Set the share address, e.g. smb://192.168.1.2/games
+network-share-copying = Copying game from the network share...
+network-share-copy-failed = Couldn't copy the game from the network share
 settings-wifi-connecting= Connecting...
 
+settings-bluetooth = Bluetooth
+settings-bluetooth-empty = No controllers found. Put your controller in pairing mode, then press Y to scan.
+settings-bluetooth-paired = Paired
+settings-bluetooth-scanning = Scanning for controllers...
+settings-bluetooth-scan-failed = Couldn't scan for controllers
+settings-bluetooth-pairing = Pairing { $name }...
+settings-bluetooth-paired-with = Paired { $name }
+settings-bluetooth-pair-failed = Couldn't pair { $name }. Make sure it is in pairing mode.
+settings-bluetooth-forgotten = Forgot { $name }
+settings-bluetooth-forget-failed = Couldn't forget { $name }
+
 settings-clock = Date & Time
 settings-clock-datetime = Date & Time
 settings-clock-timezone = Timezone
@@ -53,6 +99,31 @@ settings-display-red = Red
 settings-display-green = Green
 settings-display-blue = Blue
 settings-display-screen-resolution = Screen Resolution
+settings-display-night-mode = Night Mode
+settings-display-night-mode-off = Off
+settings-display-night-mode-on = On
+settings-display-night-mode-scheduled = Scheduled
+settings-display-night-mode-strength = Night Mode Strength
+settings-display-night-mode-start = Night Mode Start
+settings-display-night-mode-end = Night Mode End
+settings-display-vsync = Sync to Screen Refresh
+settings-display-rotation = Screen Rotation
+settings-display-rotation-0 = Normal
+settings-display-rotation-90 = 90° Clockwise
+settings-display-rotation-180 = Upside Down
+settings-display-rotation-270 = 90° Counter-Clockwise
+
+settings-sound = Sound
+settings-sound-mono = Mono Audio
+settings-sound-balance = Balance
+settings-sound-balance-center = Center
+settings-sound-balance-left = L
+settings-sound-balance-right = R
+settings-sound-volume-curve = Volume Curve
+settings-sound-volume-curve-linear = Linear
+settings-sound-volume-curve-smooth = Smooth
+settings-sound-volume-curve-logarithmic = Logarithmic
+settings-sound-max-volume = Max Volume
 
 settings-theme = Theme
 settings-theme-dark-mode = Dark Mode
@@ -77,22 +148,169 @@ settings-theme-button-a-color = Button A Color
 settings-theme-button-b-color = Button B Color
 settings-theme-button-x-color = Button X Color
 settings-theme-button-y-color = Button Y Color
+settings-theme-ui-sounds = UI Sounds
+settings-theme-font-preview = The quick brown fox
+settings-theme-background-image = Background Image
+settings-theme-background-image-none = None
+settings-theme-background-dim = Background Dimming
+settings-theme-border-radius = Corner Roundness
+settings-theme-selection-style = Selection Style
+settings-theme-selection-style-pill = Pill
+settings-theme-selection-style-underline = Underline
+settings-theme-row-spacing = Row Spacing
+settings-theme-button-hint-style = Button Hint Style
+settings-theme-button-hint-style-filled = Filled
+settings-theme-button-hint-style-outline = Outline
+settings-theme-reduce-motion = Reduce Motion
+settings-theme-marquee-speed = Text Scroll Speed
+settings-theme-marquee-delay = Text Scroll Delay
+settings-theme-marquee-selected-only = Scroll Selected Text Only
+settings-theme-theme-pack = Theme Pack
+settings-theme-theme-pack-custom = Custom
+settings-theme-theme-pack-failed = Failed to load theme pack
+settings-theme-export-theme-pack = Export Theme Pack
+settings-theme-export-theme-pack-button = Export
+settings-theme-export-theme-pack-success = Exported theme pack "{ $name }"
+settings-theme-export-theme-pack-failed = Failed to export theme pack
+
+settings-theme-store = Get More Themes
+settings-theme-store-loading = Loading themes...
+settings-theme-store-failed = Couldn't load themes. Check your WiFi connection.
+settings-theme-store-empty = No themes available
+settings-theme-store-downloading = Downloading { $name }...
+settings-theme-store-installed = Installed { $name }. Switch to it under Theme Pack.
+settings-theme-store-install-failed = Failed to download { $name }
+
+settings-boot-splash = Boot Splash
+settings-boot-splash-image = Splash Image
+settings-boot-splash-none = None
+settings-boot-splash-invalid = Splash images must be at least { $width }x{ $height }
 
 settings-language = Language
 settings-language-language = Language
+settings-language-keyboard-layout = Keyboard Layout
+settings-language-t9-search = T9 Search
 
 settings-power = Power
+settings-power-power-profile = Power Profile
 settings-power-power-button-action = Power Button Action
 settings-power-power-button-action-suspend = Suspend
+settings-power-power-button-action-screenshot = Screenshot
 settings-power-power-button-action-shutdown = Shutdown
 settings-power-power-button-action-nothing = Nothing
+settings-power-power-button-long-press-action = Power Button Long Press
 settings-power-lid-close-action = Lid Close Action
+settings-power-lid-close-action-pause = Pause
+settings-power-lid-close-action-save-and-quit = Save and Quit
 settings-power-auto-sleep-when-charging = Auto Sleep When Charging
 settings-power-auto-sleep-duration-minutes = Auto Sleep Duration (Minutes)
 settings-power-auto-sleep-duration-disabled = Disabled
+settings-power-idle-dim-seconds = Auto Dim Screen (Seconds)
+settings-power-auto-power-off-minutes = Auto Power Off When Paused (Minutes)
+settings-power-battery-shutdown-threshold = Critical Battery Shutdown
+settings-power-low-battery-warning = Low Battery Warning
+settings-power-led-charging = LED While Charging
+settings-power-led-low-battery = LED on Low Battery
+settings-power-led-job-finished = LED When Tasks Finish
+settings-battery = Battery
+settings-battery-level = Battery Level
+settings-battery-time-remaining = Time Remaining
+settings-battery-duration = { $hours }h { $minutes }m
+settings-battery-charging = Charging
+settings-battery-unknown = Unknown
+settings-battery-calibration = Calibration
+settings-battery-calibration-none = Not Calibrated
+settings-battery-calibration-in-progress = In Progress
+settings-battery-calibration-done = Calibrated
+settings-battery-calibrate = Calibrate
+settings-battery-reset-calibration = Reset
+settings-battery-calibration-instructions = Charge fully, then unplug and play until the device shuts down.
 
 settings-files = Files
 
+settings-saves = Saves
+settings-saves-save-backups = Save File Backups
+settings-saves-state-backups = Save State Backups
+settings-saves-backups-disabled = Off
+
+settings-consoles = Consoles
+settings-consoles-rename = Rename
+settings-consoles-move-up = Move Up
+settings-consoles-move-down = Move Down
+settings-consoles-show-hide = Show/Hide
+settings-consoles-hidden = { $name } (Hidden)
+settings-consoles-hide-empty = Hide Empty Consoles
+
+settings-artwork = Missing Box Art
+settings-artwork-none = All games have box art
+settings-artwork-download = Download Missing ({ $count })
+settings-artwork-console = { $console }: { $count }
+settings-artwork-other = Other
+settings-artwork-unsupported = Box art can't be downloaded for these consoles
+settings-artwork-downloading = Downloading... ({ $done }/{ $total })
+settings-artwork-downloaded = Downloaded { $count } of { $total }
+
+settings-storage = Storage
+settings-storage-used = Used
+settings-storage-free = Free
+settings-storage-calculating = Calculating... ({ $done }/{ $total })
+settings-storage-test-speed = Test Read Speed
+settings-storage-testing = Testing...
+settings-storage-test-failed = Failed
+settings-storage-sequential-read = Sequential Read
+settings-storage-random-read = Random Read
+settings-storage-slow = This SD card is slow, so games may take longer to load
+
+settings-backup = Settings Backup
+settings-backup-export = Export Settings
+settings-backup-restore = Restore Settings
+settings-backup-exporting = Exporting...
+settings-backup-restoring = Restoring...
+settings-backup-failed = Failed
+settings-backup-none = No backup found
+
+settings-retroarch-import = Import RetroArch Config
+settings-retroarch-import-apply = Import
+settings-retroarch-import-scanning = Scanning...
+settings-retroarch-import-importing = Importing...
+settings-retroarch-import-failed = Failed
+settings-retroarch-import-nothing = Nothing to import
+settings-retroarch-import-files = { $count } files
+settings-retroarch-import-imported = Imported { $count } files
+settings-retroarch-import-new = New
+settings-retroarch-import-changes = { $count } changes
+
+settings-controls = Controls
+settings-controls-confirm = Confirm / Back
+settings-controls-confirm-nintendo = A / B
+settings-controls-confirm-xbox = B / A
+settings-controls-search = Search Button
+settings-controls-menu = Menu Button
+settings-controls-long-press = Long Press Duration
+settings-controls-rumble-navigation = Vibrate on Navigation
+settings-controls-rumble-confirm = Vibrate on Confirm
+settings-controls-rumble-low-battery = Vibrate on Low Battery
+
+settings-hotkeys = RetroArch Hotkeys
+settings-hotkeys-enable = Hotkey Enable
+settings-hotkeys-menu-toggle = Menu Toggle
+settings-hotkeys-save-state = Save State
+settings-hotkeys-load-state = Load State
+settings-hotkeys-fast-forward = Fast Forward
+settings-hotkeys-none = None
+settings-hotkeys-conflict-allium = Conflicts with Allium's Menu hotkeys
+settings-hotkeys-conflict-hotkey = Also bound to { $hotkey }
+
+settings-combos = Button Combos
+settings-combos-screenshot = Screenshot
+settings-combos-quick-settings = Quick Settings
+settings-combos-force-quit = Force Quit Game
+settings-combos-none = None
+
+settings-notifications = Notifications
+settings-notifications-empty = No notifications
+settings-notifications-clear = Clear
+
 settings-about = About
 settings-about-allium-version = Allium Version
 settings-about-model-name = Model Name
@@ -100,6 +318,7 @@ settings-about-firmware-version = Firmware Version
 settings-about-operating-system-version = OS Version
 settings-about-kernel-version = Kernel Version
 settings-about-memory-used = Memory Used
+settings-about-log-level = Log Level
 settings-about-unknown-value = Unknown
 
 settings-needs-restart-for-effect =
@@ -110,6 +329,11 @@ settings-needs-restart-for-effect =
 ingame-menu-continue = Continue
 ingame-menu-save = Save
 ingame-menu-load = Load
+ingame-menu-screenshot = Take Screenshot
+ingame-menu-screenshot-saved = Screenshot saved
+ingame-menu-fast-forward = Fast Forward
+ingame-menu-slow-motion = Slow Motion
+ingame-menu-rewind = Rewind
 ingame-menu-reset = Reset
 ingame-menu-settings = Settings
 ingame-menu-guide = Guide
@@ -117,10 +341,64 @@ ingame-menu-quit = Quit
 ingame-menu-slot = Slot { $slot }
 ingame-menu-slot-auto = Auto
 ingame-menu-disk = Disk { $disk }
+ingame-menu-overrides = Game Overrides
+ingame-menu-display-filter = Display Filter
+ingame-menu-cpu-profile = CPU Profile
+ingame-menu-power-profile = Power Profile
+ingame-menu-performance-overlay = Performance Overlay
+
+cpu-profile-default = Global
+cpu-profile-powersave = Power Saver
+cpu-profile-balanced = Balanced
+cpu-profile-performance = Performance
+
+power-profile-battery-saver = Battery Saver
+power-profile-balanced = Balanced
+power-profile-performance = Performance
+status-bar-power-profile-battery-saver = ECO
+status-bar-power-profile-performance = PERF
+status-bar-notifications = { $count } NEW
+
+log-level-off = Off
+log-level-error = Errors
+log-level-warn = Warnings
+log-level-info = Info
+
+overrides-scope = Apply To
+overrides-scope-game = This Game
+overrides-scope-content-dir = This Folder
+overrides-integer-scaling = Integer Scaling
+overrides-aspect-ratio = Aspect Ratio
+overrides-aspect-ratio-core = Core Provided
+overrides-aspect-ratio-4-3 = 4:3
+overrides-aspect-ratio-16-9 = 16:9
+overrides-aspect-ratio-square = Square Pixels
+overrides-aspect-ratio-full = Full Screen
+overrides-run-ahead = Run-Ahead Frames
+overrides-run-ahead-disabled = Off
+
+display-filter-none = None
+
+rewind-hold = Hold A to rewind
+rewind-elapsed = Rewound { $seconds }s
 
 guide-button-search = Search
 guide-button-next = Next
 guide-button-prev = Prev
+guide-search-matches = { $current } of { $total }
+guide-search-no-matches = No matches
+guide-button-bookmark = Bookmark
+guide-button-bookmarks = Bookmarks
+guide-button-contents = Contents
+guide-button-next-page = Next Page
+guide-button-zoom-in = Zoom In
+guide-button-zoom-out = Zoom Out
+guide-page = { $page } / { $pages }
+guide-pdf-error = Failed to open PDF
+
+cbz-reader-fit-page = Fit Page
+cbz-reader-fit-width = Fit Width
+cbz-reader-error = Failed to open comic
 
 # Hotkeys
 hotkeys-global = Global Hotkeys:
@@ -129,19 +407,43 @@ hotkeys-volume-down = Volume -
 hotkeys-volume-up = Volume +
 hotkeys-brightness-down = Brightness -
 hotkeys-brightness-up = Brightness +
+hotkeys-power-profile = Switch Power Profile
 
 hotkeys-ingame = Ingame Hotkeys:
 hotkeys-toggle-aspect-ratio = Toggle Aspect Ratio
 hotkeys-toggle-fps = Toggle FPS
+hotkeys-gif-capture = Save Last Seconds as GIF
+
+# Onboarding
+onboarding-title = Setup ({ $step }/{ $total }): { $name }
+onboarding-language = Language
+onboarding-timezone = Timezone
+onboarding-theme = Theme
+onboarding-wifi = Wi-Fi
+onboarding-scan = Games
+onboarding-scan-games = Scan for Games
 
 # Common
 button-back = Back
 button-confirm = Confirm
 button-edit = Edit
 button-select = Select
+button-install = Install
+button-pair = Pair
+button-forget = Forget
+button-scan = Scan
+button-next = Next
+button-finish = Finish
 
 keyboard-button-backspace = Backspace
+keyboard-button-select-all = Select All
 keyboard-button-shift = Shift
 
 powering-off = Powering off...
 charging = Charging...
+launcher-recovery = Allium keeps crashing.
+    A: Reset launcher state and retry
+    B: Retry
+    Power: Power off
+screenshot-saved = Screenshot saved
+gif-saving = Saving GIF...
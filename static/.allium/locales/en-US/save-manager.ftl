@@ -0,0 +1,27 @@
+save-manager-title = Save Manager
+save-manager-state-count = { $count ->
+    [one] 1 state
+    *[other] { $count } states
+}
+save-manager-copy = Copy
+save-manager-copy-to = Copy to { $slot }
+save-manager-delete = Delete
+save-manager-confirm-delete = Press A to delete
+save-manager-export = Export
+save-manager-exported = Exported to { $path }
+save-manager-export-failed = Export failed
+save-manager-states = States
+save-manager-save-files = Save Files
+save-manager-backup-count = { $count ->
+    [one] 1 backup
+    *[other] { $count } backups
+}
+save-manager-restore = Restore
+save-manager-restored = Save restored
+save-manager-restore-failed = Restore failed
+save-manager-restore-previous = Previous
+save-manager-previous-count = { $count ->
+    [one] 1 previous
+    *[other] { $count } previous
+}
+save-manager-restored-previous = Previous state restored
@@ -1,5 +1,5 @@
 #!/bin/sh
 
-for pid in $(pgrep -f 'tcpsvd -E 0.0.0.0 21 ftpd'); do
+for pid in $(pgrep -f 'tcpsvd -E .* 21 ftpd'); do
     kill "$pid"
 done
\ No newline at end of file
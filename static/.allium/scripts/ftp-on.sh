@@ -2,8 +2,13 @@
 
 dir=$(dirname $0)
 if "$dir"/wait-for-wifi.sh; then
+    # Only listen on the Wi-Fi interface
+    ip=$(ip -4 addr show wlan0 | awk '/inet / { split($2, a, "/"); print a[1]; exit }')
+    if [ -z "$ip" ]; then
+        exit 1
+    fi
     cd /mnt/SDCARD/ || exit
-    tcpsvd -E 0.0.0.0 21 ftpd -w /mnt/SDCARD > /dev/null &
+    tcpsvd -E "$ip" 21 ftpd -w /mnt/SDCARD > /dev/null &
     exit 0
 fi
 
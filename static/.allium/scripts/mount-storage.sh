@@ -0,0 +1,14 @@
+#!/bin/sh
+# Mounts secondary storage that can hold games: a USB drive, and a second partition on the SD card.
+
+mount_storage() {
+	device="$1"
+	target="$2"
+	if [ -b "$device" ] && ! grep -q " $target " /proc/mounts; then
+		mkdir -p "$target"
+		mount "$device" "$target" || rmdir "$target"
+	fi
+}
+
+mount_storage /dev/sda1 /mnt/usb
+mount_storage /dev/mmcblk0p2 /mnt/SDCARD2
@@ -0,0 +1,3 @@
+#!/bin/sh
+
+killall dropbearmulti
\ No newline at end of file
@@ -0,0 +1,26 @@
+#!/bin/sh
+
+dir=$(dirname "$0")
+if "$dir"/wait-for-wifi.sh; then
+    # Host keys and authorized keys are kept on the SD card, as the root filesystem is read-only
+    keys="$ROOT/.allium/state/ssh"
+    mkdir -p "$keys"
+    # Logins are by public key only, so refuse to start until a key has been added
+    if [ ! -s "$keys/authorized_keys" ]; then
+        echo "no keys in $keys/authorized_keys, not starting SSH" >&2
+        exit 1
+    fi
+    if [ ! -f "$keys/dropbear_ecdsa_host_key" ]; then
+        "$ROOT/.allium/bin/dropbearmulti" dropbearkey -t ecdsa -f "$keys/dropbear_ecdsa_host_key"
+    fi
+    # Only listen on the Wi-Fi interface
+    ip=$(ip -4 addr show wlan0 | awk '/inet / { split($2, a, "/"); print a[1]; exit }')
+    if [ -z "$ip" ]; then
+        exit 1
+    fi
+    cd /mnt/SDCARD/ || exit
+    "$ROOT/.allium/bin/dropbearmulti" dropbear -r "$keys/dropbear_ecdsa_host_key" -D "$keys" -s -p "$ip:22"
+    exit 0
+fi
+
+exit 1
@@ -0,0 +1,3 @@
+#!/bin/sh
+
+killall allium-web
\ No newline at end of file
@@ -0,0 +1,14 @@
+#!/bin/sh
+
+dir=$(dirname "$0")
+if "$dir"/wait-for-wifi.sh; then
+    # Only listen on the Wi-Fi interface
+    ip=$(ip -4 addr show wlan0 | awk '/inet / { split($2, a, "/"); print a[1]; exit }')
+    if [ -z "$ip" ]; then
+        exit 1
+    fi
+    "$ROOT/.allium/bin/allium-web" 8080 "$ip" &> /dev/null
+    exit 0
+fi
+
+exit 1
\ No newline at end of file